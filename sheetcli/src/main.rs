@@ -24,6 +24,10 @@ struct Cli {
     #[arg(long)]
     list_ranges: bool,
 
+    /// Print the source code of every VBA or Basic macro module
+    #[arg(long)]
+    dump_macros: bool,
+
     /// Output file (Required for destructive operations)
     #[arg(short, long)]
     output: Option<PathBuf>,
@@ -36,7 +40,10 @@ struct Cli {
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    let has_ops = !cli.remove_sheets.is_empty() || !cli.remove_ranges.is_empty() || cli.list_ranges;
+    let has_ops = !cli.remove_sheets.is_empty()
+        || !cli.remove_ranges.is_empty()
+        || cli.list_ranges
+        || cli.dump_macros;
 
     if !has_ops {
         println!("No operations specified. Use --help for usage.");
@@ -55,18 +62,54 @@ fn main() -> Result<()> {
         } else {
             // Sort for consistent output
             let mut names: Vec<_> = workbook.defined_names.iter().collect();
-            names.sort_by_key(|(k, _)| k.as_str());
+            names.sort_by_key(|defined| (defined.name.as_str(), defined.scope.as_deref()));
 
-            for (name, value) in names {
+            for defined in names {
                 // ERR002 check: Broken reference contains #REF!
-                let is_broken = value.contains("#REF!");
+                let is_broken = defined.reference.contains("#REF!");
                 let status = if is_broken { "❌" } else { "✅" };
-                println!("  {} {} -> {}", status, name, value);
+                // Sheet-local names show their sheet
+                let scope = defined
+                    .scope
+                    .as_ref()
+                    .map(|sheet| format!(" [{}]", sheet))
+                    .unwrap_or_default();
+                let hidden = if defined.hidden { " (hidden)" } else { "" };
+                println!(
+                    "  {} {}{}{} -> {}",
+                    status, defined.name, scope, hidden, defined.reference
+                );
+                if let Some(comment) = &defined.comment {
+                    println!("      {}", comment);
+                }
             }
         }
         return Ok(());
     }
 
+    if cli.dump_macros {
+        let workbook = sheetrs::reader::read_workbook(&cli.file)
+            .with_context(|| format!("Failed to read file: {}", cli.file.display()))?;
+
+        if workbook.macros.is_empty() {
+            if workbook.has_macros {
+                println!("Macros found but their source could not be read.");
+            } else {
+                println!("No macro modules found.");
+            }
+        }
+        for module in &workbook.macros {
+            println!(
+                "==== {} ({}) ====",
+                module.qualified_name(),
+                module.kind.label()
+            );
+            println!("{}", module.source.trim_end());
+            println!();
+        }
+        return Ok(());
+    }
+
     // Enforce output file for destructive operations
     if cli.output.is_none() {
         anyhow::bail!("Output file is required for destructive operations. Use --output <FILE>.");
@@ -25,6 +25,11 @@ struct Cli {
     /// Show only errors (hide warnings and info)
     #[arg(short, long)]
     errors_only: bool,
+
+    /// Load one sheet at a time to bound memory on very large files.
+    /// Rules that need the whole workbook are skipped.
+    #[arg(long)]
+    streaming: bool,
 }
 
 #[derive(Clone, ValueEnum)]
@@ -66,9 +71,19 @@ fn main() -> Result<()> {
     // Create linter and run
     let linter = Linter::with_config(config);
 
-    let violations = linter
-        .lint_file(&cli.file)
-        .with_context(|| format!("Failed to lint file: {}", cli.file.display()))?;
+    let violations = if cli.streaming {
+        let skipped = linter.streaming_skipped_rules();
+        if !skipped.is_empty() {
+            eprintln!(
+                "Streaming mode: skipping rules that need the whole workbook: {}",
+                skipped.join(", ")
+            );
+        }
+        linter.lint_file_streaming(&cli.file)
+    } else {
+        linter.lint_file(&cli.file)
+    }
+    .with_context(|| format!("Failed to lint file: {}", cli.file.display()))?;
 
     // Filter violations if needed
     let violations: Vec<_> = if cli.errors_only {
@@ -0,0 +1,72 @@
+//! Sheet storage benchmarks on large synthetic sheets
+//!
+//! Run with `cargo bench -p sheetrs --bench cell_store`.
+
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use sheetrs::reader::{Cell, CellValue, Sheet};
+
+/// Dense block of `rows` x `cols` numbers with a formula column at the end
+fn synthetic_sheet(rows: u32, cols: u32) -> Sheet {
+    let mut sheet = Sheet::new("Data".to_string());
+    for row in 0..rows {
+        for col in 0..cols {
+            let value = if col + 1 == cols {
+                CellValue::formula(format!("SUM(A{}:C{})", row + 1, row + 1))
+            } else {
+                CellValue::Number((row * cols + col) as f64)
+            };
+            sheet.cells.insert(
+                (row, col),
+                Cell {
+                    row,
+                    col,
+                    value,
+                    num_fmt: None,
+                    style: None,
+                    comment: None,
+                },
+            );
+        }
+    }
+    sheet.used_range = Some((rows, cols));
+    sheet
+}
+
+fn bench_access(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sheet_access");
+    for rows in [10_000u32, 100_000] {
+        let sheet = synthetic_sheet(rows, 10);
+
+        group.bench_with_input(BenchmarkId::new("cells_in_row", rows), &sheet, |b, s| {
+            b.iter(|| s.cells_in_row(black_box(rows / 2)).count())
+        });
+        group.bench_with_input(BenchmarkId::new("cells_in_column", rows), &sheet, |b, s| {
+            b.iter(|| s.cells_in_column(black_box(3)).count())
+        });
+        group.bench_with_input(BenchmarkId::new("cells_in_range", rows), &sheet, |b, s| {
+            b.iter(|| {
+                s.cells_in_range(black_box((rows / 2, 2)), black_box((rows / 2 + 100, 5)))
+                    .count()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("last_data_cell", rows), &sheet, |b, s| {
+            b.iter(|| s.last_data_cell())
+        });
+        group.bench_with_input(BenchmarkId::new("get_cell", rows), &sheet, |b, s| {
+            b.iter(|| s.get_cell(black_box(rows / 3), black_box(7)))
+        });
+        group.bench_with_input(BenchmarkId::new("neighbor_below", rows), &sheet, |b, s| {
+            b.iter(|| s.cells.below(black_box(rows / 3), black_box(7)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_build(c: &mut Criterion) {
+    c.bench_function("build_100k_x_10", |b| {
+        b.iter(|| synthetic_sheet(black_box(100_000), 10))
+    });
+}
+
+criterion_group!(benches, bench_access, bench_build);
+criterion_main!(benches);
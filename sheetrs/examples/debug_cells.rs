@@ -29,7 +29,7 @@ fn main() -> anyhow::Result<()> {
                 println!("  Num Format: {:?}", cell.num_fmt);
 
                 match &cell.value {
-                    CellValue::Text(text) => {
+                    CellValue::Text(text, _) => {
                         println!("  -> Text content: '{}'", text);
                         println!("  -> Trimmed: '{}'", text.trim());
                         println!(
@@ -46,6 +46,7 @@ fn main() -> anyhow::Result<()> {
                     CellValue::Formula {
                         formula,
                         cached_error,
+                        ..
                     } => {
                         println!("  -> Formula: {}", formula);
                         println!("  -> Cached error: {:?}", cached_error);
@@ -21,6 +21,7 @@ fn main() {
                 CellValue::Formula {
                     formula,
                     cached_error,
+                    ..
                 } => {
                     if let Some(err) = cached_error {
                         println!(
@@ -31,7 +32,7 @@ fn main() {
                         println!("  ({}, {}) [FORMULA]: {}", row, col, formula);
                     }
                 }
-                CellValue::Text(t) => println!("  ({}, {}) [TEXT]: {}", row, col, t),
+                CellValue::Text(t, _) => println!("  ({}, {}) [TEXT]: {}", row, col, t),
                 CellValue::Number(n) => println!("  ({}, {}) [NUMBER]: {}", row, col, n),
                 CellValue::Boolean(b) => println!("  ({}, {}) [BOOL]: {}", row, col, b),
                 CellValue::Empty => println!("  ({}, {}) [EMPTY]", row, col),
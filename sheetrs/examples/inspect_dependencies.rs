@@ -99,7 +99,11 @@ fn main() {
     let formula = cell.value.as_formula().expect("Not a formula");
 
     println!("Formula: {}", formula);
-    let refs = extract_cell_references(formula, &sheet_names, s_name, false);
+    let resolved = wb.resolved_formula(s_name, cell).expect("Not a formula");
+    if resolved != formula {
+        println!("Resolved: {}", resolved);
+    }
+    let refs = extract_cell_references(&resolved, &sheet_names, s_name, false);
     for (sn, row, col) in refs {
         // Convert back to ref
         let mut c = col + 1;
@@ -31,9 +31,10 @@ fn main() -> Result<()> {
                 CellValue::Formula {
                     formula,
                     cached_error,
+                    ..
                 } => {
                     if let Some(error) = cached_error {
-                        error_cells.push((cell.row, cell.col, formula.clone(), error.clone()));
+                        error_cells.push((cell.row, cell.col, formula.clone(), error.to_string()));
                     } else {
                         // Check if formula contains error literals
                         let error_literals = [
@@ -13,7 +13,7 @@ use anyhow::Result;
 use std::path::Path;
 
 pub use config::LinterConfig;
-pub use rules::LinterRule;
+pub use rules::{LinterRule, RuleAccess};
 pub use violation::{Severity, Violation, ViolationScope};
 
 /// Main linter interface
@@ -40,25 +40,7 @@ impl Linter {
         let mut violations = Vec::new();
 
         for rule in &self.rules {
-            let rule_violations = rule.check(&workbook)?;
-
-            // Filter violations based on sheet configuration
-            for violation in rule_violations {
-                let enabled = if let Some(sheet_name) = violation.scope.sheet_name() {
-                    self.config
-                        .is_rule_enabled_for_sheet(&violation.rule_id, sheet_name)
-                } else {
-                    // Book-level violations are enabled if the rule itself is enabled (config logic handles this)
-                    // But wait, the rules vector already contains only globally enabled rules.
-                    // However, we should double check if there's any reason a book-level rule would be disabled?
-                    // Usually book-level rules aren't sheet-specific, so default to true here.
-                    true
-                };
-
-                if enabled {
-                    violations.push(violation);
-                }
-            }
+            self.collect(rule.check(&workbook)?, &mut violations);
         }
 
         // Sort violations by scope for hierarchical reporting
@@ -66,6 +48,90 @@ impl Linter {
 
         Ok(violations)
     }
+
+    /// Lint a spreadsheet file keeping at most one sheet's cells in memory.
+    ///
+    /// Sheets are loaded one at a time for [`RuleAccess::Sheet`] rules and dropped
+    /// afterwards; [`RuleAccess::Metadata`] rules then run once on cell-less sheets.
+    /// Memory is therefore bounded by the largest sheet, not by a row: a sheet
+    /// rule still sees all of its sheet's cells at once.
+    /// Rules needing every sheet at once cannot run this way and are skipped; see
+    /// [`Linter::streaming_skipped_rules`].
+    pub fn lint_file_streaming<P: AsRef<Path>>(&self, path: P) -> Result<Vec<Violation>> {
+        let mut workbook = reader::open_workbook(path)?;
+        let mut violations = Vec::new();
+
+        let sheet_rules: Vec<_> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.access() == RuleAccess::Sheet)
+            .collect();
+
+        // Sheets come in workbook order from one pass over the file where the
+        // format allows it; each is checked alone in a view sharing the
+        // workbook's metadata
+        let mut view = reader::Workbook {
+            sheets: Vec::new(),
+            ..std::mem::take(&mut workbook.metadata)
+        };
+        let mut sheets = Vec::new();
+        workbook.for_each_sheet(!sheet_rules.is_empty(), |sheet| {
+            if sheet_rules.is_empty() {
+                sheets.push(sheet);
+                return Ok(());
+            }
+            view.sheets.push(sheet);
+            for rule in &sheet_rules {
+                self.collect(rule.check(&view)?, &mut violations);
+            }
+            let mut sheet = view.sheets.pop().expect("sheet pushed above");
+            sheet.cells = Default::default();
+            sheets.push(sheet);
+            Ok(())
+        })?;
+
+        let metadata = reader::Workbook { sheets, ..view };
+        for rule in &self.rules {
+            if rule.access() == RuleAccess::Metadata {
+                self.collect(rule.check(&metadata)?, &mut violations);
+            }
+        }
+
+        violations.sort_by(|a, b| a.scope.cmp(&b.scope));
+
+        Ok(violations)
+    }
+
+    /// Enabled rules that need the whole workbook in memory and are therefore
+    /// skipped by [`Linter::lint_file_streaming`]
+    pub fn streaming_skipped_rules(&self) -> Vec<&str> {
+        self.rules
+            .iter()
+            .filter(|rule| rule.access() == RuleAccess::Workbook)
+            .map(|rule| rule.id())
+            .collect()
+    }
+
+    /// Keep the violations that are enabled for their sheet
+    fn collect(&self, rule_violations: Vec<Violation>, violations: &mut Vec<Violation>) {
+        // Filter violations based on sheet configuration
+        for violation in rule_violations {
+            let enabled = if let Some(sheet_name) = violation.scope.sheet_name() {
+                self.config
+                    .is_rule_enabled_for_sheet(&violation.rule_id, sheet_name)
+            } else {
+                // Book-level violations are enabled if the rule itself is enabled (config logic handles this)
+                // But wait, the rules vector already contains only globally enabled rules.
+                // However, we should double check if there's any reason a book-level rule would be disabled?
+                // Usually book-level rules aren't sheet-specific, so default to true here.
+                true
+            };
+
+            if enabled {
+                violations.push(violation);
+            }
+        }
+    }
 }
 
 impl Default for Linter {
@@ -0,0 +1,121 @@
+//! Active content besides macros: embedded objects, controls, data
+//! connections and event scripts
+//!
+//! XLSX worksheets list OLE objects (`xl/embeddings`) and ActiveX controls
+//! (`xl/activeX`) in `<oleObjects>` and `<controls>`; data connections and web
+//! queries live in `xl/connections.xml`, and Power Query keeps its queries in a
+//! `DataMashup` part under `customXml`. ODS has `draw:object-ole` frames,
+//! database ranges, linked cell ranges and `script:event-listener` elements in
+//! `content.xml`.
+
+/// What kind of active content an entry is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ActiveContentKind {
+    /// Embedded or linked OLE object
+    #[default]
+    OleObject,
+    /// ActiveX control on a sheet
+    ActiveXControl,
+    /// Database, OLAP or text file connection
+    DataConnection,
+    /// Connection or linked range reading a web page
+    WebQuery,
+    /// Power Query query
+    PowerQuery,
+    /// Macro bound to a document, sheet or control event
+    EventScript,
+}
+
+impl ActiveContentKind {
+    /// Name used in messages
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::OleObject => "OLE object",
+            Self::ActiveXControl => "ActiveX control",
+            Self::DataConnection => "data connection",
+            Self::WebQuery => "web query",
+            Self::PowerQuery => "Power Query query",
+            Self::EventScript => "event script",
+        }
+    }
+}
+
+/// An object, control, connection or script that loads or runs something
+/// outside the cells
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ActiveContent {
+    pub kind: ActiveContentKind,
+    /// Sheet the object or control is placed on; `None` for workbook-wide content
+    pub sheet: Option<String>,
+    /// OLE program id, control, connection or query name, or event name
+    pub name: String,
+    /// What it reads or runs: the embedded part or linked file, the control's
+    /// class id, the connection string, file or URL, or the script URL
+    pub target: Option<String>,
+    /// Whether opening the file refreshes or runs it
+    pub runs_on_open: bool,
+}
+
+impl ActiveContent {
+    /// Whether the target is a connection string carrying a password
+    pub fn has_password(&self) -> bool {
+        self.target
+            .as_deref()
+            .is_some_and(|target| password_ranges(target).next().is_some())
+    }
+
+    /// The target with connection string passwords replaced by `***`
+    pub fn redacted_target(&self) -> Option<String> {
+        let target = self.target.as_deref()?;
+        let mut redacted = String::new();
+        let mut last = 0;
+        for (start, end) in password_ranges(target) {
+            redacted.push_str(&target[last..start]);
+            redacted.push_str("***");
+            last = end;
+        }
+        redacted.push_str(&target[last..]);
+        Some(redacted)
+    }
+}
+
+/// Byte ranges of the non-empty values of `Password=` and `PWD=` keys in a
+/// `key=value;...` connection string
+fn password_ranges(target: &str) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let mut offset = 0;
+    target.split(';').filter_map(move |pair| {
+        let start = offset;
+        offset += pair.len() + 1;
+        let equals = pair.find('=')?;
+        let key = pair[..equals].trim();
+        let is_password = key.eq_ignore_ascii_case("password") || key.eq_ignore_ascii_case("pwd");
+        (is_password && !pair[equals + 1..].trim().is_empty())
+            .then_some((start + equals + 1, start + pair.len()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_passwords() {
+        let connection = ActiveContent {
+            kind: ActiveContentKind::DataConnection,
+            name: "Orders".to_string(),
+            target: Some("DSN=Orders;UID=sa;PWD=secret;Password=;".to_string()),
+            ..Default::default()
+        };
+        assert!(connection.has_password());
+        assert_eq!(
+            connection.redacted_target().unwrap(),
+            "DSN=Orders;UID=sa;PWD=***;Password=;"
+        );
+
+        let trusted = ActiveContent {
+            target: Some("Provider=SQLOLEDB;Integrated Security=SSPI".to_string()),
+            ..Default::default()
+        };
+        assert!(!trusted.has_password());
+    }
+}
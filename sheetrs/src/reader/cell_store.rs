@@ -0,0 +1,365 @@
+//! Row-major sparse cell storage
+//!
+//! Cells are kept in a B-tree of rows, each row holding its cells sorted by
+//! column. Row access, range scans and neighbor lookups only touch the rows
+//! involved instead of every cell in the sheet, and iteration is always in
+//! row-major order.
+
+use super::workbook::Cell;
+use std::collections::HashMap;
+use std::collections::btree_map::{self, BTreeMap};
+use std::ops::{Bound, Index, RangeBounds};
+
+/// Sparse cell map keyed by `(row, col)`, 0-indexed
+#[derive(Debug, Clone, Default)]
+pub struct CellStore {
+    rows: BTreeMap<u32, Vec<Cell>>,
+    len: usize,
+}
+
+impl CellStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of stored cells
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.rows.clear();
+        self.len = 0;
+    }
+
+    /// Insert a cell, returning the one previously stored at that position.
+    /// The key is authoritative: the cell's `row`/`col` are set from it.
+    pub fn insert(&mut self, (row, col): (u32, u32), mut cell: Cell) -> Option<Cell> {
+        cell.row = row;
+        cell.col = col;
+        let cells = self.rows.entry(row).or_default();
+        // Parsers emit cells in column order, so appending is the common case
+        if cells.last().is_none_or(|last| last.col < col) {
+            cells.push(cell);
+            self.len += 1;
+            return None;
+        }
+        match cells.binary_search_by_key(&col, |c| c.col) {
+            Ok(idx) => Some(std::mem::replace(&mut cells[idx], cell)),
+            Err(idx) => {
+                cells.insert(idx, cell);
+                self.len += 1;
+                None
+            }
+        }
+    }
+
+    pub fn get(&self, &(row, col): &(u32, u32)) -> Option<&Cell> {
+        let cells = self.rows.get(&row)?;
+        let idx = cells.binary_search_by_key(&col, |c| c.col).ok()?;
+        Some(&cells[idx])
+    }
+
+    pub fn get_mut(&mut self, &(row, col): &(u32, u32)) -> Option<&mut Cell> {
+        let cells = self.rows.get_mut(&row)?;
+        let idx = cells.binary_search_by_key(&col, |c| c.col).ok()?;
+        Some(&mut cells[idx])
+    }
+
+    pub fn contains_key(&self, key: &(u32, u32)) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn remove(&mut self, &(row, col): &(u32, u32)) -> Option<Cell> {
+        let cells = self.rows.get_mut(&row)?;
+        let idx = cells.binary_search_by_key(&col, |c| c.col).ok()?;
+        let cell = cells.remove(idx);
+        if cells.is_empty() {
+            self.rows.remove(&row);
+        }
+        self.len -= 1;
+        Some(cell)
+    }
+
+    /// All cells in row-major order
+    pub fn values(&self) -> impl Iterator<Item = &Cell> {
+        self.rows.values().flatten()
+    }
+
+    /// All positions in row-major order
+    pub fn keys(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.values().map(|c| (c.row, c.col))
+    }
+
+    /// `(position, cell)` pairs in row-major order
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            rows: self.rows.values(),
+            current: [].iter(),
+        }
+    }
+
+    /// Cells of one row, sorted by column
+    pub fn row(&self, row: u32) -> &[Cell] {
+        self.rows.get(&row).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Non-empty rows in ascending order
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = (u32, &[Cell])> {
+        self.rows
+            .iter()
+            .map(|(row, cells)| (*row, cells.as_slice()))
+    }
+
+    /// Cells of one column, in row order
+    pub fn column(&self, col: u32) -> impl Iterator<Item = &Cell> {
+        self.rows.values().filter_map(move |cells| {
+            cells
+                .binary_search_by_key(&col, |c| c.col)
+                .ok()
+                .map(|idx| &cells[idx])
+        })
+    }
+
+    /// Cells inside a rectangular range, in row-major order
+    pub fn range<R, C>(&self, rows: R, cols: C) -> impl Iterator<Item = &Cell>
+    where
+        R: RangeBounds<u32>,
+        C: RangeBounds<u32> + Clone,
+    {
+        // BTreeMap::range panics on a range that ends before it starts
+        let rows = (!is_empty(&rows)).then(|| self.rows.range(rows));
+        rows.into_iter().flatten().flat_map(move |(_, cells)| {
+            let cols = cols.clone();
+            let start = cells.partition_point(|c| before(&cols, c.col));
+            cells[start..]
+                .iter()
+                .take_while(move |c| cols.contains(&c.col))
+        })
+    }
+
+    /// First and last row holding a cell
+    pub fn row_bounds(&self) -> Option<(u32, u32)> {
+        Some((*self.rows.keys().next()?, *self.rows.keys().next_back()?))
+    }
+
+    /// Smallest and largest column holding a cell
+    pub fn column_bounds(&self) -> Option<(u32, u32)> {
+        self.rows.values().fold(None, |acc, cells| {
+            let (first, last) = (cells.first()?.col, cells.last()?.col);
+            Some(match acc {
+                Some((min, max)) => (first.min(min), last.max(max)),
+                None => (first, last),
+            })
+        })
+    }
+
+    /// Nearest stored cell left of `(row, col)` in the same row
+    pub fn left_of(&self, row: u32, col: u32) -> Option<&Cell> {
+        let cells = self.row(row);
+        let idx = cells.partition_point(|c| c.col < col);
+        idx.checked_sub(1).map(|i| &cells[i])
+    }
+
+    /// Nearest stored cell right of `(row, col)` in the same row
+    pub fn right_of(&self, row: u32, col: u32) -> Option<&Cell> {
+        let cells = self.row(row);
+        cells.get(cells.partition_point(|c| c.col <= col))
+    }
+
+    /// Nearest stored cell above `(row, col)` in the same column
+    pub fn above(&self, row: u32, col: u32) -> Option<&Cell> {
+        self.rows
+            .range(..row)
+            .rev()
+            .find_map(|(r, _)| self.get(&(*r, col)))
+    }
+
+    /// Nearest stored cell below `(row, col)` in the same column
+    pub fn below(&self, row: u32, col: u32) -> Option<&Cell> {
+        self.rows
+            .range((Bound::Excluded(row), Bound::Unbounded))
+            .find_map(|(r, _)| self.get(&(*r, col)))
+    }
+}
+
+/// Whether `col` lies before the start of `cols`
+/// Whether a range holds no values, including one whose end is before its start
+fn is_empty(range: &impl RangeBounds<u32>) -> bool {
+    match (range.start_bound(), range.end_bound()) {
+        (Bound::Included(start), Bound::Included(end)) => start > end,
+        (
+            Bound::Included(start) | Bound::Excluded(start),
+            Bound::Included(end) | Bound::Excluded(end),
+        ) => start >= end,
+        _ => false,
+    }
+}
+
+fn before(cols: &impl RangeBounds<u32>, col: u32) -> bool {
+    match cols.start_bound() {
+        Bound::Included(start) => col < *start,
+        Bound::Excluded(start) => col <= *start,
+        Bound::Unbounded => false,
+    }
+}
+
+/// Row-major iterator over `(position, cell)` pairs
+pub struct Iter<'a> {
+    rows: btree_map::Values<'a, u32, Vec<Cell>>,
+    current: std::slice::Iter<'a, Cell>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = ((u32, u32), &'a Cell);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(cell) = self.current.next() {
+                return Some(((cell.row, cell.col), cell));
+            }
+            self.current = self.rows.next()?.iter();
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a CellStore {
+    type Item = ((u32, u32), &'a Cell);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for CellStore {
+    type Item = ((u32, u32), Cell);
+    type IntoIter = Box<dyn Iterator<Item = ((u32, u32), Cell)>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(
+            self.rows
+                .into_values()
+                .flatten()
+                .map(|cell| ((cell.row, cell.col), cell)),
+        )
+    }
+}
+
+impl Index<&(u32, u32)> for CellStore {
+    type Output = Cell;
+
+    fn index(&self, key: &(u32, u32)) -> &Cell {
+        self.get(key).expect("no cell at position")
+    }
+}
+
+impl Extend<((u32, u32), Cell)> for CellStore {
+    fn extend<I: IntoIterator<Item = ((u32, u32), Cell)>>(&mut self, iter: I) {
+        for (key, cell) in iter {
+            self.insert(key, cell);
+        }
+    }
+}
+
+impl FromIterator<((u32, u32), Cell)> for CellStore {
+    fn from_iter<I: IntoIterator<Item = ((u32, u32), Cell)>>(iter: I) -> Self {
+        let mut store = Self::new();
+        store.extend(iter);
+        store
+    }
+}
+
+impl From<HashMap<(u32, u32), Cell>> for CellStore {
+    fn from(cells: HashMap<(u32, u32), Cell>) -> Self {
+        cells.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::CellValue;
+
+    fn cell(value: f64) -> Cell {
+        Cell {
+            value: CellValue::Number(value),
+            ..Default::default()
+        }
+    }
+
+    fn sample() -> CellStore {
+        let mut store = CellStore::new();
+        for (row, col) in [(5, 2), (0, 0), (5, 0), (2, 7), (0, 3), (5, 9)] {
+            store.insert((row, col), cell((row * 10 + col) as f64));
+        }
+        store
+    }
+
+    #[test]
+    fn test_insert_get_remove() {
+        let mut store = sample();
+        assert_eq!(store.len(), 6);
+        assert_eq!(store.get(&(5, 2)).unwrap().value, CellValue::Number(52.0));
+        assert_eq!(store[&(2, 7)].row, 2);
+        assert!(!store.contains_key(&(1, 1)));
+
+        let old = store.insert((5, 2), cell(-1.0)).unwrap();
+        assert_eq!(old.value, CellValue::Number(52.0));
+        assert_eq!(store.len(), 6);
+
+        assert!(store.remove(&(2, 7)).is_some());
+        assert!(store.remove(&(2, 7)).is_none());
+        assert_eq!(store.len(), 5);
+        assert!(store.row(2).is_empty());
+    }
+
+    #[test]
+    fn test_row_major_order() {
+        let keys: Vec<_> = sample().keys().collect();
+        assert_eq!(keys, vec![(0, 0), (0, 3), (2, 7), (5, 0), (5, 2), (5, 9)]);
+        let pairs: Vec<_> = sample().iter().map(|(key, _)| key).collect();
+        assert_eq!(pairs, keys);
+    }
+
+    #[test]
+    fn test_row_column_and_range() {
+        let store = sample();
+        let row: Vec<_> = store.row(5).iter().map(|c| c.col).collect();
+        assert_eq!(row, vec![0, 2, 9]);
+
+        let column: Vec<_> = store.column(0).map(|c| c.row).collect();
+        assert_eq!(column, vec![0, 5]);
+
+        let range: Vec<_> = store.range(0..=5, 1..=7).map(|c| (c.row, c.col)).collect();
+        assert_eq!(range, vec![(0, 3), (2, 7), (5, 2)]);
+
+        let open: Vec<_> = store.range(3.., ..).map(|c| (c.row, c.col)).collect();
+        assert_eq!(open, vec![(5, 0), (5, 2), (5, 9)]);
+
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = store.range(5..=0, 7..=1).count();
+        assert_eq!(reversed, 0);
+
+        assert_eq!(store.row_bounds(), Some((0, 5)));
+        assert_eq!(store.column_bounds(), Some((0, 9)));
+        assert_eq!(CellStore::new().row_bounds(), None);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let store = sample();
+        assert_eq!(store.left_of(5, 9).map(|c| c.col), Some(2));
+        assert_eq!(store.left_of(5, 0).map(|c| c.col), None);
+        assert_eq!(store.right_of(5, 0).map(|c| c.col), Some(2));
+        assert_eq!(store.right_of(5, 1).map(|c| c.col), Some(2));
+        assert_eq!(store.above(5, 0).map(|c| c.row), Some(0));
+        assert_eq!(store.above(4, 7).map(|c| c.row), Some(2));
+        assert_eq!(store.below(0, 0).map(|c| c.row), Some(5));
+        assert_eq!(store.below(5, 0).map(|c| c.row), None);
+    }
+}
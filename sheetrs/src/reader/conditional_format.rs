@@ -0,0 +1,158 @@
+//! Conditional formatting rules
+//!
+//! A conditional format applies a style to a set of ranges when its condition
+//! holds: a comparison with the cell value, a formula, a ranking, a text match,
+//! or a visual scale (color scale, data bar, icon set). XLSX groups `<cfRule>`
+//! elements under `<conditionalFormatting sqref="...">` on each worksheet, with
+//! newer rules in the `x14` extension; ODS (LibreOffice) writes
+//! `calcext:conditional-format` elements at the end of each table. Both are read
+//! into the same [`ConditionalFormat`] model on [`super::Sheet`], one per rule.
+
+use super::tables::format_range;
+use super::validation::ValidationOperator;
+
+/// What a conditional formatting rule tests or displays
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ConditionalFormatKind {
+    /// Compare the cell value with one or two formulas
+    CellIs,
+    /// A formula that must evaluate to TRUE
+    Expression,
+    ColorScale,
+    DataBar,
+    IconSet,
+    /// Top or bottom N items or percent
+    Top10,
+    /// Above or below the average of the range
+    AboveAverage,
+    DuplicateValues,
+    UniqueValues,
+    ContainsText,
+    NotContainsText,
+    BeginsWith,
+    EndsWith,
+    ContainsBlanks,
+    NotContainsBlanks,
+    ContainsErrors,
+    NotContainsErrors,
+    /// A date within a period relative to today (yesterday, last week, ...)
+    TimePeriod,
+    #[default]
+    Other,
+}
+
+impl ConditionalFormatKind {
+    /// Parse the XLSX `type` attribute
+    pub fn from_xlsx(value: &str) -> Self {
+        match value {
+            "cellIs" => Self::CellIs,
+            "expression" => Self::Expression,
+            "colorScale" => Self::ColorScale,
+            "dataBar" => Self::DataBar,
+            "iconSet" => Self::IconSet,
+            "top10" => Self::Top10,
+            "aboveAverage" => Self::AboveAverage,
+            "duplicateValues" => Self::DuplicateValues,
+            "uniqueValues" => Self::UniqueValues,
+            "containsText" => Self::ContainsText,
+            "notContainsText" => Self::NotContainsText,
+            "beginsWith" => Self::BeginsWith,
+            "endsWith" => Self::EndsWith,
+            "containsBlanks" => Self::ContainsBlanks,
+            "notContainsBlanks" => Self::NotContainsBlanks,
+            "containsErrors" => Self::ContainsErrors,
+            "notContainsErrors" => Self::NotContainsErrors,
+            "timePeriod" => Self::TimePeriod,
+            _ => Self::Other,
+        }
+    }
+
+    /// Name of the kind as written in XLSX (`cellIs`, `colorScale`, ...)
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::CellIs => "cellIs",
+            Self::Expression => "expression",
+            Self::ColorScale => "colorScale",
+            Self::DataBar => "dataBar",
+            Self::IconSet => "iconSet",
+            Self::Top10 => "top10",
+            Self::AboveAverage => "aboveAverage",
+            Self::DuplicateValues => "duplicateValues",
+            Self::UniqueValues => "uniqueValues",
+            Self::ContainsText => "containsText",
+            Self::NotContainsText => "notContainsText",
+            Self::BeginsWith => "beginsWith",
+            Self::EndsWith => "endsWith",
+            Self::ContainsBlanks => "containsBlanks",
+            Self::NotContainsBlanks => "notContainsBlanks",
+            Self::ContainsErrors => "containsErrors",
+            Self::NotContainsErrors => "notContainsErrors",
+            Self::TimePeriod => "timePeriod",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// A conditional formatting rule and the ranges it applies to
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConditionalFormat {
+    /// Ranges the rule applies to: (start_row, start_col, end_row, end_col)
+    pub ranges: Vec<(u32, u32, u32, u32)>,
+    pub kind: ConditionalFormatKind,
+    /// Comparison of a `CellIs` rule (the same operators as data validation)
+    pub operator: Option<ValidationOperator>,
+    /// Evaluation order, lowest first. ODS has no priorities, so its rules are
+    /// numbered in document order.
+    pub priority: u32,
+    /// Whether lower priority rules are skipped when this one matches
+    pub stop_if_true: bool,
+    /// Formulas of the rule in Excel syntax without the leading `=`: the bounds
+    /// of `CellIs`, the condition of `Expression`, the generated test of text
+    /// rules, and the thresholds of color scales, data bars and icon sets
+    pub formulas: Vec<String>,
+}
+
+impl ConditionalFormat {
+    /// Whether the rule covers the cell
+    pub fn applies_to(&self, row: u32, col: u32) -> bool {
+        self.ranges
+            .iter()
+            .any(|&(r1, c1, r2, c2)| (r1..=r2).contains(&row) && (c1..=c2).contains(&col))
+    }
+
+    /// Applied ranges as an XLSX `sqref`, e.g. `A1:A10 C3`
+    pub fn sqref(&self) -> String {
+        self.ranges
+            .iter()
+            .map(|&range| format_range("", range))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conditional_format_ranges() {
+        let rule = ConditionalFormat {
+            ranges: vec![(0, 0, 9, 0), (2, 2, 2, 2)],
+            kind: ConditionalFormatKind::from_xlsx("cellIs"),
+            operator: Some(ValidationOperator::GreaterThan),
+            priority: 1,
+            formulas: vec!["5".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(rule.kind, ConditionalFormatKind::CellIs);
+        assert_eq!(rule.sqref(), "A1:A10 C3");
+        assert!(rule.applies_to(9, 0));
+        assert!(rule.applies_to(2, 2));
+        assert!(!rule.applies_to(10, 0));
+        assert!(!rule.applies_to(2, 1));
+        assert_eq!(
+            ConditionalFormatKind::from_xlsx("dataBar").as_str(),
+            "dataBar"
+        );
+    }
+}
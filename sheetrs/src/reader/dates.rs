@@ -0,0 +1,284 @@
+//! Date serial numbers and workbook date systems
+//!
+//! Spreadsheets store dates as day counts from an epoch that depends on the
+//! workbook: the 1900 system (Excel's default, including its phantom
+//! 1900-02-29) or the 1904 system (old Mac Excel, `date1904` in XLSX, a
+//! `1904-01-01` null date in ODS). Cell values keep the serial exactly as the
+//! file stores it; use the workbook's [`DateSystem`] to turn it into a date.
+
+use std::fmt;
+
+const MILLIS_PER_DAY: f64 = 86_400_000.0;
+/// First serial past 9999-12-31 in the 1900 system, and past it in the 1904 one too
+const SERIAL_LIMIT: f64 = 2_958_466.0;
+
+/// Epoch used to interpret date serial numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DateSystem {
+    /// Serial 1 is 1900-01-01, with the Lotus 1-2-3 leap-year bug (serial 60 is 1900-02-29)
+    #[default]
+    Excel1900,
+    /// Serial 0 is 1904-01-01
+    Excel1904,
+}
+
+impl DateSystem {
+    /// Convert a serial to a calendar date and time.
+    /// Returns `None` for serials the system cannot represent (negative or past year 9999).
+    pub fn to_datetime(self, serial: f64) -> Option<DateTime> {
+        // Huge serials would overflow the day arithmetic below
+        if !serial.is_finite() || !(0.0..SERIAL_LIMIT).contains(&serial) {
+            return None;
+        }
+
+        let mut days = serial.floor() as i64;
+        let mut millis = ((serial - serial.floor()) * MILLIS_PER_DAY).round() as i64;
+        if millis >= MILLIS_PER_DAY as i64 {
+            days += 1;
+            millis = 0;
+        }
+
+        let (year, month, day) = match self {
+            DateSystem::Excel1900 if days == 60 => (1900, 2, 29),
+            // Serials before the phantom leap day count from 1899-12-31
+            DateSystem::Excel1900 if days < 60 => {
+                civil_from_days(days_from_civil(1899, 12, 31) + days)
+            }
+            DateSystem::Excel1900 => civil_from_days(days_from_civil(1899, 12, 30) + days),
+            DateSystem::Excel1904 => civil_from_days(days_from_civil(1904, 1, 1) + days),
+        };
+        if year > 9999 {
+            return None;
+        }
+
+        let millis = millis as u32;
+        Some(DateTime {
+            year,
+            month,
+            day,
+            hour: millis / 3_600_000,
+            minute: millis / 60_000 % 60,
+            second: millis / 1000 % 60,
+            millisecond: millis % 1000,
+        })
+    }
+
+    /// Convert a calendar date and time to a serial.
+    /// Returns `None` for dates before the system's epoch.
+    pub fn to_serial(self, dt: &DateTime) -> Option<f64> {
+        let days = days_from_civil(dt.year, dt.month, dt.day);
+        let whole = match self {
+            DateSystem::Excel1900 => {
+                let offset = days - days_from_civil(1899, 12, 30);
+                if offset < 2 {
+                    return None;
+                }
+                // Dates before the phantom 1900-02-29 are one lower
+                if offset < 61 { offset - 1 } else { offset }
+            }
+            DateSystem::Excel1904 => {
+                let offset = days - days_from_civil(1904, 1, 1);
+                if offset < 0 {
+                    return None;
+                }
+                offset
+            }
+        };
+        Some(whole as f64 + dt.time_fraction())
+    }
+}
+
+/// A calendar date with time of day, as read from a serial or an ISO 8601 string
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct DateTime {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+    pub millisecond: u32,
+}
+
+impl DateTime {
+    /// Midnight on the given day
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Self {
+        Self {
+            year,
+            month,
+            day,
+            ..Default::default()
+        }
+    }
+
+    /// Same date at the given time
+    pub fn with_time(self, hour: u32, minute: u32, second: u32) -> Self {
+        Self {
+            hour,
+            minute,
+            second,
+            millisecond: 0,
+            ..self
+        }
+    }
+
+    /// Parse `YYYY-MM-DD` or `YYYY-MM-DDThh:mm[:ss[.fff]]`, as used by ODS `office:date-value`
+    pub fn parse_iso(s: &str) -> Option<Self> {
+        let (date_part, time_part) = match s.split_once('T') {
+            Some((date, time)) => (date, Some(time)),
+            None => (s, None),
+        };
+
+        let mut date = date_part.split('-');
+        let year = date.next()?.parse::<i32>().ok()?;
+        let month = date.next()?.parse::<u32>().ok()?;
+        let day = date.next()?.parse::<u32>().ok()?;
+        if date.next().is_some() || !(1..=12).contains(&month) || day == 0 || day > 31 {
+            return None;
+        }
+
+        let mut dt = Self::from_ymd(year, month, day);
+        if let Some(time) = time_part {
+            let mut parts = time.split(':');
+            dt.hour = parts.next()?.parse().ok()?;
+            dt.minute = parts.next()?.parse().ok()?;
+            if let Some(seconds) = parts.next() {
+                let seconds = seconds.parse::<f64>().ok()?;
+                dt.second = seconds.trunc() as u32;
+                dt.millisecond = (seconds.fract() * 1000.0).round() as u32;
+            }
+        }
+        Some(dt)
+    }
+
+    /// Day of the week, 0 for Sunday through 6 for Saturday
+    pub fn weekday(&self) -> u32 {
+        // 1970-01-01 was a Thursday
+        (days_from_civil(self.year, self.month, self.day) + 4).rem_euclid(7) as u32
+    }
+
+    /// Time of day as a fraction of a day
+    pub fn time_fraction(&self) -> f64 {
+        let millis = ((self.hour * 60 + self.minute) * 60 + self.second) as f64 * 1000.0
+            + self.millisecond as f64;
+        millis / MILLIS_PER_DAY
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )?;
+        if self.millisecond > 0 {
+            write!(f, ".{:03}", self.millisecond)?;
+        }
+        Ok(())
+    }
+}
+
+/// Days since 1970-01-01 in the proleptic Gregorian calendar
+fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year } as i64;
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Inverse of [`days_from_civil`]
+fn civil_from_days(days: i64) -> (i32, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year as i32, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(serial: f64, system: DateSystem) -> (i32, u32, u32) {
+        let dt = system.to_datetime(serial).unwrap();
+        (dt.year, dt.month, dt.day)
+    }
+
+    #[test]
+    fn test_1900_system_leap_year_bug() {
+        let sys = DateSystem::Excel1900;
+        assert_eq!(date(1.0, sys), (1900, 1, 1));
+        assert_eq!(date(59.0, sys), (1900, 2, 28));
+        assert_eq!(date(60.0, sys), (1900, 2, 29));
+        assert_eq!(date(61.0, sys), (1900, 3, 1));
+        assert_eq!(date(36433.0, sys), (1999, 9, 30));
+        assert_eq!(date(45658.0, sys), (2025, 1, 1));
+        assert_eq!(date(2_958_465.0, sys), (9999, 12, 31));
+        assert!(sys.to_datetime(2_958_466.0).is_none());
+        assert!(sys.to_datetime(-1.0).is_none());
+        assert!(sys.to_datetime(1e20).is_none());
+        assert!(sys.to_datetime(f64::INFINITY).is_none());
+    }
+
+    #[test]
+    fn test_1904_system() {
+        let sys = DateSystem::Excel1904;
+        assert_eq!(date(0.0, sys), (1904, 1, 1));
+        // Same day is 1462 lower than in the 1900 system
+        assert_eq!(date(45658.0 - 1462.0, sys), (2025, 1, 1));
+        assert_eq!(
+            sys.to_serial(&DateTime::from_ymd(2025, 1, 1)),
+            Some(45658.0 - 1462.0)
+        );
+        assert!(sys.to_serial(&DateTime::from_ymd(1903, 12, 31)).is_none());
+        assert!(sys.to_datetime(1e20).is_none());
+    }
+
+    #[test]
+    fn test_time_of_day_round_trip() {
+        let dt = DateTime::from_ymd(2024, 2, 29).with_time(18, 30, 15);
+        for sys in [DateSystem::Excel1900, DateSystem::Excel1904] {
+            let serial = sys.to_serial(&dt).unwrap();
+            assert_eq!(sys.to_datetime(serial), Some(dt));
+        }
+        assert_eq!(dt.to_string(), "2024-02-29T18:30:15");
+        assert_eq!(dt.weekday(), 4);
+
+        // Rounding up to midnight carries into the next day
+        let sys = DateSystem::Excel1900;
+        assert_eq!(date(45_658.999_999_999, sys), (2025, 1, 2));
+    }
+
+    #[test]
+    fn test_to_serial_1900_boundaries() {
+        let sys = DateSystem::Excel1900;
+        assert_eq!(sys.to_serial(&DateTime::from_ymd(1900, 1, 1)), Some(1.0));
+        assert_eq!(sys.to_serial(&DateTime::from_ymd(1900, 2, 28)), Some(59.0));
+        assert_eq!(sys.to_serial(&DateTime::from_ymd(1900, 3, 1)), Some(61.0));
+        assert!(sys.to_serial(&DateTime::from_ymd(1899, 12, 31)).is_none());
+    }
+
+    #[test]
+    fn test_parse_iso() {
+        assert_eq!(
+            DateTime::parse_iso("1999-09-30"),
+            Some(DateTime::from_ymd(1999, 9, 30))
+        );
+        let dt = DateTime::parse_iso("2023-06-01T08:05:30.250").unwrap();
+        assert_eq!(
+            (dt.hour, dt.minute, dt.second, dt.millisecond),
+            (8, 5, 30, 250)
+        );
+        assert!(DateTime::parse_iso("2023-13-01").is_none());
+        assert!(DateTime::parse_iso("not a date").is_none());
+    }
+}
@@ -0,0 +1,74 @@
+//! Charts and images placed on sheets
+//!
+//! XLSX keeps a sheet's drawing objects in `xl/drawings/drawingN.xml`, linked
+//! from the worksheet (or chartsheet) relationships; each anchor holds a chart
+//! frame pointing at `xl/charts/chartN.xml` or a picture pointing at a file in
+//! `xl/media`. ODS puts `draw:frame` elements in the cells they are anchored
+//! to (or in `table:shapes` for the sheet), holding a chart object
+//! (`Object N/content.xml`) or an image (`Pictures/...`).
+
+/// One data series of a chart. References are in Excel syntax without the
+/// leading `=`, e.g. `Data!$B$2:$B$10`, and `#REF!` once the cells were deleted.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChartSeries {
+    /// Cell holding the series name
+    pub name: Option<String>,
+    /// Category (or X) values
+    pub categories: Option<String>,
+    /// Plotted (or Y) values
+    pub values: Option<String>,
+}
+
+impl ChartSeries {
+    /// Every reference of the series
+    pub fn references(&self) -> impl Iterator<Item = &str> {
+        [&self.name, &self.categories, &self.values]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+}
+
+/// A chart on a sheet, or the chart of a chart sheet
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Chart {
+    /// Plot type such as `bar`, `line`, `pie` or `scatter`; the first one for
+    /// combination charts
+    pub kind: String,
+    /// Title text, when the chart has a literal title
+    pub title: Option<String>,
+    /// Top-left cell the chart is anchored at: (row, col)
+    pub anchor: (u32, u32),
+    pub series: Vec<ChartSeries>,
+}
+
+/// A picture on a sheet
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Image {
+    pub name: String,
+    /// Top-left cell the picture is anchored at: (row, col)
+    pub anchor: (u32, u32),
+    /// Path of the image file in the package, e.g. `xl/media/image1.png`
+    pub path: String,
+    /// Compressed size in bytes of the image file; pictures sharing a file
+    /// report the same size
+    pub size: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chart_series_references() {
+        let series = ChartSeries {
+            name: Some("Data!$B$1".to_string()),
+            categories: None,
+            values: Some("Data!$B$2:$B$10".to_string()),
+        };
+        assert_eq!(
+            series.references().collect::<Vec<_>>(),
+            vec!["Data!$B$1", "Data!$B$2:$B$10"]
+        );
+    }
+}
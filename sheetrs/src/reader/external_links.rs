@@ -0,0 +1,177 @@
+//! External workbook links
+//!
+//! XLSX keeps each linked workbook in its own part (`xl/externalLinks/
+//! externalLinkN.xml`): the sheet names, the defined names and a cached copy
+//! of every cell the formulas read, with the file's location in the part's
+//! relationships. Formulas address the link as `[N]`. ODS copies each linked
+//! sheet into a hidden table whose `table:table-source` names the file and
+//! the sheet, with the cached cells as the table's contents.
+
+use super::CellValue;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A linked workbook
+#[derive(Debug, Clone, Default)]
+pub struct ExternalWorkbook {
+    /// 0-based index in the collection (maps to XLSX 1-based index - 1)
+    /// For XLSX: index N corresponds to [N+1] in formulas
+    /// For ODS: index N corresponds to order of appearance in metadata
+    pub index: usize,
+    /// Basename of the external workbook (e.g., "Book1.xlsx")
+    pub path: String,
+    /// Location as written in the file: a relative or absolute path, a
+    /// `file:` URL or a web address
+    pub target: String,
+    /// Sheets the formulas read, with their cached values
+    pub sheets: Vec<ExternalSheet>,
+}
+
+/// A sheet of a linked workbook
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExternalSheet {
+    pub name: String,
+    /// Values cached when the link was last updated, by (row, col); empty
+    /// cells are left out
+    pub cells: BTreeMap<(u32, u32), CellValue>,
+}
+
+impl ExternalWorkbook {
+    /// Find the linked file on disk: at its target (relative paths starting
+    /// from the linking workbook's folder), then by name in each search path
+    /// and in the linking workbook's folder
+    pub fn locate(&self, workbook_path: &Path, search_paths: &[PathBuf]) -> Option<PathBuf> {
+        let folder = workbook_path.parent().unwrap_or(Path::new(""));
+        let mut candidates = Vec::new();
+        if let Some(path) = self.local_target() {
+            if path.is_absolute() {
+                candidates.push(path);
+            } else {
+                candidates.push(folder.join(&path));
+                // ODS resolves relative links as if the document were a
+                // folder, so a sibling file is `../Book1.ods`
+                if let Ok(sibling) = path.strip_prefix("..") {
+                    candidates.push(folder.join(sibling));
+                }
+            }
+        }
+        if !self.path.is_empty() {
+            for dir in search_paths.iter().map(PathBuf::as_path).chain([folder]) {
+                candidates.push(dir.join(&self.path));
+            }
+        }
+        candidates.into_iter().find(|candidate| candidate.is_file())
+    }
+
+    /// The target as a file path, or `None` for web addresses
+    fn local_target(&self) -> Option<PathBuf> {
+        let target = self.target.trim();
+        if target.is_empty() || target.starts_with("http://") || target.starts_with("https://") {
+            return None;
+        }
+        let path = match target.strip_prefix("file://") {
+            // `file:///C:/Book1.xlsx` names a drive, `file:///home/...` a root
+            Some(url) => {
+                let url = url.strip_prefix("localhost").unwrap_or(url);
+                let url = match url.strip_prefix('/') {
+                    Some(path) if has_drive_letter(path) => path,
+                    _ => url,
+                };
+                percent_decode(url)
+            }
+            None => target.to_string(),
+        };
+        Some(PathBuf::from(path))
+    }
+}
+
+/// Whether a path starts with a Windows drive: `C:`
+fn has_drive_letter(path: &str) -> bool {
+    let mut chars = path.chars();
+    matches!(
+        (chars.next(), chars.next()),
+        (Some(letter), Some(':')) if letter.is_ascii_alphabetic()
+    )
+}
+
+/// Decode `%XX` escapes, leaving malformed ones as they are
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = text
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_target() {
+        let link = |target: &str| ExternalWorkbook {
+            target: target.to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            link("file:///C:/Finance/Rates.xlsx").local_target(),
+            Some(PathBuf::from("C:/Finance/Rates.xlsx"))
+        );
+        assert_eq!(
+            link("file:///home/ana/Rates.xlsx").local_target(),
+            Some(PathBuf::from("/home/ana/Rates.xlsx"))
+        );
+        assert_eq!(
+            link("file:///é:/x").local_target(),
+            Some(PathBuf::from("/é:/x"))
+        );
+        assert_eq!(link("https://example.com/Rates.xlsx").local_target(), None);
+    }
+
+    #[test]
+    fn test_locate_external_workbook() {
+        let dir = tempfile::tempdir().unwrap();
+        let shared = dir.path().join("shared");
+        std::fs::create_dir(&shared).unwrap();
+        std::fs::write(dir.path().join("Sibling.ods"), b"").unwrap();
+        std::fs::write(shared.join("Rates 2024.xlsx"), b"").unwrap();
+        let workbook_path = dir.path().join("Report.xlsx");
+
+        let link = |target: &str, path: &str| ExternalWorkbook {
+            path: path.to_string(),
+            target: target.to_string(),
+            ..Default::default()
+        };
+        let sibling = link("../Sibling.ods", "Sibling.ods");
+        assert_eq!(
+            sibling.locate(&workbook_path, &[]),
+            Some(dir.path().join("Sibling.ods"))
+        );
+        let url = format!("file://{}/Rates%202024.xlsx", shared.display());
+        assert_eq!(
+            link(&url, "Rates 2024.xlsx").locate(&workbook_path, &[]),
+            Some(shared.join("Rates 2024.xlsx"))
+        );
+        // Moved since the link was made: found by name on the search path
+        let moved = link(r"C:\Finance\Rates 2024.xlsx", "Rates 2024.xlsx");
+        assert_eq!(moved.locate(&workbook_path, &[]), None);
+        assert_eq!(
+            moved.locate(&workbook_path, std::slice::from_ref(&shared)),
+            Some(shared.join("Rates 2024.xlsx"))
+        );
+        let web = link("https://example.com/Missing.xlsx", "Missing.xlsx");
+        assert_eq!(web.locate(&workbook_path, &[shared]), None);
+    }
+}
@@ -0,0 +1,155 @@
+//! Autofilters and sort state
+//!
+//! XLSX writes a sheet's autofilter as `<autoFilter ref>` after the cell data,
+//! with a `<filterColumn colId>` per filtered column and the last sort as
+//! `<sortState>`, inside the autofilter or right after it. The rows a filter
+//! hides only carry `hidden="1"`, like rows hidden by hand. ODS keeps the
+//! filter and sort on the sheet's database range (`__Anonymous_Sheet_DB__N`
+//! for a plain autofilter) as `table:filter` and `table:sort`, and marks the
+//! rows the filter hides with `table:visibility="filter"`.
+
+/// A comparison in a custom filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterOperator {
+    Equal,
+    NotEqual,
+    LessThan,
+    LessThanOrEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+}
+
+impl FilterOperator {
+    /// Operator from its XLSX `customFilter` name
+    pub fn from_xlsx(name: &str) -> Option<Self> {
+        Some(match name {
+            "equal" => FilterOperator::Equal,
+            "notEqual" => FilterOperator::NotEqual,
+            "lessThan" => FilterOperator::LessThan,
+            "lessThanOrEqual" => FilterOperator::LessThanOrEqual,
+            "greaterThan" => FilterOperator::GreaterThan,
+            "greaterThanOrEqual" => FilterOperator::GreaterThanOrEqual,
+            _ => return None,
+        })
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FilterOperator::Equal => "=",
+            FilterOperator::NotEqual => "<>",
+            FilterOperator::LessThan => "<",
+            FilterOperator::LessThanOrEqual => "<=",
+            FilterOperator::GreaterThan => ">",
+            FilterOperator::GreaterThanOrEqual => ">=",
+        }
+    }
+}
+
+/// One comparison of a custom filter, e.g. `>= 100`. Text matches use `*` and
+/// `?` wildcards, so "contains" is `= *text*`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterCondition {
+    pub operator: FilterOperator,
+    pub value: String,
+}
+
+/// What a filtered column keeps
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterCriteria {
+    /// The listed values, plus empty cells when `blank` is set
+    Values { values: Vec<String>, blank: bool },
+    /// One or more comparisons, all of which (`and`) or any of which must hold
+    Custom {
+        conditions: Vec<FilterCondition>,
+        and: bool,
+    },
+    /// The largest (or with `bottom`, smallest) `count` items or percent
+    Top {
+        count: f64,
+        percent: bool,
+        bottom: bool,
+    },
+    /// A condition relative to the data or today, such as `aboveAverage` or
+    /// `thisMonth`
+    Dynamic(String),
+    /// Cells of a fill or font color
+    Color,
+    /// Cells showing a conditional formatting icon
+    Icon,
+}
+
+/// A filtered column
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterColumn {
+    /// Sheet column (0-based), not the position within the range
+    pub column: u32,
+    pub criteria: FilterCriteria,
+}
+
+/// A column the range was last sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortKey {
+    /// Sheet column (0-based)
+    pub column: u32,
+    pub descending: bool,
+}
+
+/// A sheet's autofilter
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AutoFilter {
+    /// Filtered range including the header row:
+    /// (start_row, start_col, end_row, end_col)
+    pub range: (u32, u32, u32, u32),
+    /// Columns with criteria; empty when the filter buttons show but nothing
+    /// is filtered
+    pub columns: Vec<FilterColumn>,
+    /// Sort keys, most significant first
+    pub sort: Vec<SortKey>,
+}
+
+impl AutoFilter {
+    /// Whether any column is filtered, i.e. the filter may hide rows
+    pub fn is_filtering(&self) -> bool {
+        !self.columns.is_empty()
+    }
+
+    /// Whether a row is one of the rows the filter applies to, below the
+    /// header row
+    pub fn covers_row(&self, row: u32) -> bool {
+        let (r1, _, r2, _) = self.range;
+        row > r1 && row <= r2
+    }
+
+    /// Criteria of a sheet column, if it is filtered
+    pub fn criteria(&self, column: u32) -> Option<&FilterCriteria> {
+        self.columns
+            .iter()
+            .find(|filtered| filtered.column == column)
+            .map(|filtered| &filtered.criteria)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_filter_rows() {
+        let filter = AutoFilter {
+            range: (2, 0, 10, 3),
+            columns: vec![FilterColumn {
+                column: 1,
+                criteria: FilterCriteria::Dynamic("aboveAverage".to_string()),
+            }],
+            sort: Vec::new(),
+        };
+        assert!(filter.is_filtering());
+        assert!(!filter.covers_row(2));
+        assert!(filter.covers_row(3));
+        assert!(filter.covers_row(10));
+        assert!(!filter.covers_row(11));
+        assert!(filter.criteria(1).is_some());
+        assert!(filter.criteria(0).is_none());
+        assert_eq!(FilterOperator::from_xlsx("lessThan").unwrap().as_str(), "<");
+    }
+}
@@ -0,0 +1,114 @@
+//! Row heights, column widths, outline levels and sheet views
+//!
+//! XLSX keeps sizes and outline levels on `<col>` and `<row>`, defaults in
+//! `<sheetFormatPr>`, and the view (frozen panes, zoom, gridlines, tab
+//! selection) in `<sheetView>`/`<pane>`, with the tab color in `<sheetPr>` and
+//! the active tab in workbook.xml. ODS sizes come from the `table-column` and
+//! `table-row` styles, outline levels from nested `table:table-row-group` and
+//! `table:table-column-group` elements, and the view from settings.xml.
+
+/// Points per unit of column width. Excel measures widths in digits of the
+/// default font (Calibri 11: 7 pixels, 5.25 points), padding included.
+pub const POINTS_PER_CHARACTER: f64 = 5.25;
+
+/// Size and outline level shared by a run of rows or columns
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    /// First row or column (0-based)
+    pub first: u32,
+    /// Last row or column (0-based, inclusive)
+    pub last: u32,
+    /// Column width in characters or row height in points; `None` when the
+    /// run only carries an outline level
+    pub size: Option<f64>,
+    /// Outline (grouping) level, 0 when not grouped
+    pub outline_level: u8,
+}
+
+impl Span {
+    pub fn contains(&self, index: u32) -> bool {
+        (self.first..=self.last).contains(&index)
+    }
+}
+
+/// Append a run, merging it into the previous one when adjacent and equal.
+/// Runs without a size or outline level are dropped.
+pub fn push_span(spans: &mut Vec<Span>, span: Span) {
+    if span.size.is_none() && span.outline_level == 0 {
+        return;
+    }
+    if let Some(last) = spans.last_mut()
+        && last.last + 1 == span.first
+        && last.size == span.size
+        && last.outline_level == span.outline_level
+    {
+        last.last = span.last;
+        return;
+    }
+    spans.push(span);
+}
+
+/// The run covering `index`, if any
+pub fn span_at(spans: &[Span], index: u32) -> Option<&Span> {
+    // Runs are sorted and don't overlap
+    let i = spans.partition_point(|span| span.last < index);
+    spans.get(i).filter(|span| span.contains(index))
+}
+
+/// How a sheet is displayed
+#[derive(Debug, Clone, PartialEq)]
+pub struct SheetView {
+    /// Rows kept in view above the frozen pane
+    pub frozen_rows: u32,
+    /// Columns kept in view left of the frozen pane
+    pub frozen_columns: u32,
+    /// Zoom in percent
+    pub zoom: u16,
+    pub show_gridlines: bool,
+    /// Tab color as `RRGGBB`
+    pub tab_color: Option<String>,
+    /// Whether the tab is selected (grouped sheets select several)
+    pub selected: bool,
+    /// Whether the sheet is shown when the file is opened
+    pub active: bool,
+}
+
+impl Default for SheetView {
+    fn default() -> Self {
+        Self {
+            frozen_rows: 0,
+            frozen_columns: 0,
+            zoom: 100,
+            show_gridlines: true,
+            tab_color: None,
+            selected: false,
+            active: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spans() {
+        let mut spans = Vec::new();
+        let span = |first, last, size, outline_level| Span {
+            first,
+            last,
+            size,
+            outline_level,
+        };
+        push_span(&mut spans, span(0, 1, Some(20.0), 0));
+        push_span(&mut spans, span(2, 4, Some(20.0), 0));
+        push_span(&mut spans, span(5, 5, None, 0));
+        push_span(&mut spans, span(6, 9, None, 2));
+        assert_eq!(spans, vec![span(0, 4, Some(20.0), 0), span(6, 9, None, 2)]);
+
+        assert_eq!(span_at(&spans, 3).and_then(|s| s.size), Some(20.0));
+        assert_eq!(span_at(&spans, 5), None);
+        assert_eq!(span_at(&spans, 9).map(|s| s.outline_level), Some(2));
+        assert_eq!(span_at(&spans, 10), None);
+    }
+}
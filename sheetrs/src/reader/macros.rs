@@ -0,0 +1,87 @@
+//! Macro source code
+//!
+//! XLSM keeps its VBA project in `xl/vbaProject.bin`, a compound file whose
+//! `VBA` storage holds one compressed stream per module (see [`super::vba`]).
+//! ODS keeps StarBasic libraries as plain XML in `Basic/<Library>/<Module>.xml`.
+
+/// What a module is attached to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ModuleKind {
+    /// Standard module (VBA) or Basic module
+    #[default]
+    Standard,
+    /// Class module
+    Class,
+    /// Code behind the workbook or a sheet (`ThisWorkbook`, `Sheet1`)
+    Document,
+    /// Code behind a user form
+    Form,
+}
+
+impl ModuleKind {
+    /// Name used in messages and listings
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Standard => "standard module",
+            Self::Class => "class module",
+            Self::Document => "document module",
+            Self::Form => "form module",
+        }
+    }
+}
+
+/// One macro module with its source code
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MacroModule {
+    /// VBA project or Basic library the module belongs to (`VBAProject`, `Standard`)
+    pub library: String,
+    pub name: String,
+    pub kind: ModuleKind,
+    /// Source code, including the `Attribute VB_...` lines VBA stores at the top
+    pub source: String,
+}
+
+impl MacroModule {
+    /// `Library.Module`, or just the module name when there is no library
+    pub fn qualified_name(&self) -> String {
+        if self.library.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}.{}", self.library, self.name)
+        }
+    }
+
+    /// Source lines with their 1-based line numbers, without `Attribute` lines
+    /// or comments
+    pub fn code_lines(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.source
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| {
+                !line.is_empty()
+                    && !line.starts_with('\'')
+                    && !line.starts_with("Attribute ")
+                    && !line.to_ascii_lowercase().starts_with("rem ")
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_lines() {
+        let module = MacroModule {
+            name: "Module1".to_string(),
+            source: "Attribute VB_Name = \"Module1\"\n' Greets\nSub Hello()\n    MsgBox \"Hi\"\nEnd Sub\n"
+                .to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            module.code_lines().collect::<Vec<_>>(),
+            vec![(3, "Sub Hello()"), (4, "MsgBox \"Hi\""), (5, "End Sub")]
+        );
+    }
+}
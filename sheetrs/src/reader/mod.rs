@@ -2,37 +2,290 @@
 
 use anyhow::{Context, Result};
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::fs::File;
+use std::io::Seek;
 use std::path::Path;
 use zip::ZipArchive;
 
+pub mod active_content;
+pub mod cell_store;
+pub mod conditional_format;
+pub mod dates;
+pub mod drawings;
+pub mod external_links;
+pub mod filter;
+pub mod layout;
+pub mod macros;
+pub mod number_format;
 pub mod ods_parser;
+pub mod openformula;
+pub mod page_setup;
 pub mod parser_utils;
+pub mod pivot;
+pub mod properties;
+pub mod protection;
+pub mod r1c1;
+pub mod range_index;
+pub mod rich_text;
+pub mod styles;
+pub mod tables;
+pub mod validation;
+pub mod vba;
 pub mod workbook;
 pub mod xlsx_parser;
 
 use self::ods_parser::OdsReader;
-use self::xlsx_parser::XlsxReader;
-pub use workbook::{Cell, CellValue, ExternalWorkbook, Sheet, Workbook};
+use self::xlsx_parser::{XlsxReader, XlsxTables};
+pub use active_content::ActiveContent;
+pub use cell_store::CellStore;
+pub use conditional_format::ConditionalFormat;
+pub use dates::{DateSystem, DateTime};
+pub use drawings::{Chart, Image};
+pub use external_links::{ExternalSheet, ExternalWorkbook};
+pub use filter::AutoFilter;
+pub use layout::{SheetView, Span};
+pub use macros::MacroModule;
+pub use number_format::NumberFormat;
+pub use page_setup::{HeaderFooter, PageSetup};
+pub use pivot::PivotTable;
+pub use properties::DocumentProperties;
+pub use protection::{EncryptedFileError, SheetProtection, WorkbookProtection};
+pub use range_index::RangeIndex;
+pub use rich_text::RichText;
+pub use styles::CellStyle;
+pub use tables::Table;
+pub use validation::DataValidation;
+pub use workbook::{
+    ArrayFormula, Cell, CellError, CellValue, Comment, DefinedName, Hyperlink, Sheet, Workbook,
+};
+
+/// Callback receiving one row index and that row's cells in column order
+pub type RowVisitor<'a> = &'a mut dyn FnMut(u32, &[Cell]) -> Result<()>;
+
+/// Attach comments to their cells, adding empty cells for comments on blank positions
+fn attach_comments(cells: &mut CellStore, comments: BTreeMap<(u32, u32), Comment>) {
+    for ((row, col), comment) in comments {
+        match cells.get_mut(&(row, col)) {
+            Some(cell) => cell.comment = Some(comment),
+            None => {
+                cells.insert(
+                    (row, col),
+                    Cell {
+                        comment: Some(comment),
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Row visitor adapter that attaches comments kept outside the cell data (XLSX
+/// comment parts) to rows as they stream past, emitting rows that hold only comments
+struct CommentedRows<'a> {
+    pending: BTreeMap<(u32, u32), Comment>,
+    visit: RowVisitor<'a>,
+}
+
+impl<'a> CommentedRows<'a> {
+    fn new(pending: BTreeMap<(u32, u32), Comment>, visit: RowVisitor<'a>) -> Self {
+        Self { pending, visit }
+    }
+
+    fn row(&mut self, row: u32, cells: &[Cell]) -> Result<()> {
+        self.flush_before(row)?;
+        if self
+            .pending
+            .range((row, 0)..=(row, u32::MAX))
+            .next()
+            .is_none()
+        {
+            return (self.visit)(row, cells);
+        }
+        let mut store: CellStore = cells.iter().map(|c| ((c.row, c.col), c.clone())).collect();
+        let comments = self.pending.split_off(&(row + 1, 0));
+        attach_comments(&mut store, std::mem::replace(&mut self.pending, comments));
+        (self.visit)(row, store.row(row))
+    }
+
+    /// Emit the remaining comment-only rows
+    fn finish(mut self) -> Result<()> {
+        self.flush_before(u32::MAX)
+    }
+
+    fn flush_before(&mut self, row: u32) -> Result<()> {
+        let later = self.pending.split_off(&(row, 0));
+        let earlier = std::mem::replace(&mut self.pending, later);
+        let mut store = CellStore::new();
+        attach_comments(&mut store, earlier);
+        for (r, cells) in store.rows() {
+            (self.visit)(r, cells)?;
+        }
+        Ok(())
+    }
+}
 
 /// Trait for spreadsheet format readers
 pub trait WorkbookReader {
     fn read_sheets(&mut self) -> Result<Vec<Sheet>>;
-    fn read_defined_names(&mut self) -> Result<HashMap<String, String>>;
+    /// Names of the workbook's own sheets, in workbook order, without parsing cells
+    fn sheet_names(&mut self) -> Result<Vec<String>>;
+    /// Parse a single sheet on demand
+    fn read_sheet(&mut self, name: &str) -> Result<Sheet>;
+    /// Stream a sheet's cells row by row, in ascending row order, without keeping them.
+    /// Returns the sheet with all of its metadata but no cells.
+    fn for_each_row(&mut self, name: &str, visit: RowVisitor<'_>) -> Result<Sheet>;
+    /// Hand each sheet to `on_sheet` in workbook order, without cells unless
+    /// `with_cells` is set. Readers that can parse every sheet in one pass override
+    /// this; the default reads them one at a time.
+    fn for_each_sheet(
+        &mut self,
+        with_cells: bool,
+        on_sheet: &mut dyn FnMut(Sheet) -> Result<()>,
+    ) -> Result<()> {
+        for name in self.sheet_names()? {
+            let sheet = if with_cells {
+                self.read_sheet(&name)?
+            } else {
+                self.for_each_row(&name, &mut |_, _| Ok(()))?
+            };
+            on_sheet(sheet)?;
+        }
+        Ok(())
+    }
+    /// Defined names of every scope, plus table names as workbook-wide names
+    fn read_defined_names(&mut self) -> Result<Vec<DefinedName>>;
     fn read_hidden_sheets(&mut self) -> Result<Vec<String>>;
     fn has_macros(&mut self) -> Result<bool>;
     fn read_external_links(&mut self) -> Result<Vec<String>>;
     /// Read external workbook references with indices
     fn read_external_workbooks(&mut self) -> Result<Vec<ExternalWorkbook>>;
+    /// Epoch the file's date serials count from
+    fn read_date_system(&mut self) -> Result<DateSystem>;
+    /// Shared cell style table referenced by `Cell::style`
+    fn read_styles(&mut self) -> Result<Vec<CellStyle>>;
+    /// Structure and window protection of the workbook
+    fn read_workbook_protection(&mut self) -> Result<Option<WorkbookProtection>>;
+    /// Document properties (author, dates, custom properties, ...)
+    fn read_properties(&mut self) -> Result<DocumentProperties>;
+    /// Excel tables / ODS database ranges
+    fn read_tables(&mut self) -> Result<Vec<Table>>;
+    /// Pivot tables / ODS data pilot tables
+    fn read_pivot_tables(&mut self) -> Result<Vec<PivotTable>>;
+    /// Embedded objects, controls, data connections and event scripts
+    fn read_active_content(&mut self) -> Result<Vec<ActiveContent>>;
+    /// VBA modules / Basic library modules with their source code
+    fn read_macros(&mut self) -> Result<Vec<MacroModule>>;
+    /// Sheet names, and the workbook-level data above in a workbook without
+    /// sheets. Readers that can gather it in fewer passes over the file
+    /// override this; the default calls each reader in turn.
+    fn read_metadata(&mut self) -> Result<(Vec<String>, Workbook)> {
+        let sheet_names = self.sheet_names()?;
+        let metadata = Workbook {
+            defined_names: self.read_defined_names()?,
+            hidden_sheets: self.read_hidden_sheets()?,
+            has_macros: self.has_macros()?,
+            external_workbooks: self.read_external_workbooks()?,
+            date_system: self.read_date_system()?,
+            styles: self.read_styles()?,
+            protection: self.read_workbook_protection()?,
+            properties: self.read_properties()?,
+            tables: self.read_tables()?,
+            pivot_tables: self.read_pivot_tables()?,
+            active_content: self.read_active_content()?,
+            macros: self.read_macros()?,
+            ..Default::default()
+        };
+        Ok((sheet_names, metadata))
+    }
 }
 
-/// Read a workbook from a file path
-pub fn read_workbook<P: AsRef<Path>>(path: P) -> Result<Workbook> {
+/// Per-format state kept between on-demand sheet reads
+enum LazySource {
+    Xlsx(XlsxTables),
+    Ods,
+}
+
+/// A workbook whose sheets are parsed only when asked for.
+///
+/// Workbook-level data (defined names, hidden sheets, macros, external links) is
+/// read up front into `metadata`, whose `sheets` stay empty. Sheets are then loaded
+/// one at a time with [`LazyWorkbook::read_sheet`] or [`LazyWorkbook::for_each_sheet`],
+/// or streamed row by row with [`LazyWorkbook::for_each_row`], so memory is bounded by what the caller keeps.
+pub struct LazyWorkbook {
+    pub metadata: Workbook,
+    sheet_names: Vec<String>,
+    archive: ZipArchive<File>,
+    source: LazySource,
+}
+
+impl LazyWorkbook {
+    /// Sheet names in workbook order
+    pub fn sheet_names(&self) -> &[String] {
+        &self.sheet_names
+    }
+
+    /// Parse one sheet fully
+    pub fn read_sheet(&mut self, name: &str) -> Result<Sheet> {
+        self.with_reader(|reader| reader.read_sheet(name))
+    }
+
+    /// Stream one sheet row by row; see [`WorkbookReader::for_each_row`]
+    pub fn for_each_row<F>(&mut self, name: &str, mut visit: F) -> Result<Sheet>
+    where
+        F: FnMut(u32, &[Cell]) -> Result<()>,
+    {
+        self.with_reader(|reader| reader.for_each_row(name, &mut visit))
+    }
+
+    /// Hand each sheet to `on_sheet` in workbook order, dropping it afterwards; see
+    /// [`WorkbookReader::for_each_sheet`]
+    pub fn for_each_sheet<F>(&mut self, with_cells: bool, mut on_sheet: F) -> Result<()>
+    where
+        F: FnMut(Sheet) -> Result<()>,
+    {
+        self.with_reader(|reader| reader.for_each_sheet(with_cells, &mut on_sheet))
+    }
+
+    /// Load every sheet and return the complete workbook
+    pub fn into_workbook(mut self) -> Result<Workbook> {
+        let sheets = self.with_reader(|reader| reader.read_sheets())?;
+        Ok(Workbook {
+            sheets,
+            ..self.metadata
+        })
+    }
+
+    fn with_reader<T>(
+        &mut self,
+        f: impl FnOnce(&mut dyn WorkbookReader) -> Result<T>,
+    ) -> Result<T> {
+        match &mut self.source {
+            LazySource::Xlsx(tables) => {
+                let mut reader = XlsxReader::with_tables(&mut self.archive, std::mem::take(tables));
+                let result = f(&mut reader);
+                *tables = reader.into_tables();
+                result
+            }
+            LazySource::Ods => f(&mut OdsReader::new(&mut self.archive)?),
+        }
+    }
+}
+
+/// Open a workbook for on-demand sheet access
+pub fn open_workbook<P: AsRef<Path>>(path: P) -> Result<LazyWorkbook> {
     let path_ref = path.as_ref();
 
-    let file = File::open(path_ref)
+    let mut file = File::open(path_ref)
         .with_context(|| format!("Failed to open file: {}", path_ref.display()))?;
+    if protection::is_encrypted_ooxml(&mut file)? {
+        return Err(EncryptedFileError {
+            path: path_ref.to_path_buf(),
+        }
+        .into());
+    }
+    file.rewind()?;
     let mut archive = ZipArchive::new(file).context("Failed to open zip archive")?;
 
     let is_xlsx = path_ref
@@ -47,38 +300,40 @@ pub fn read_workbook<P: AsRef<Path>>(path: P) -> Result<Workbook> {
         .map(|s| s.eq_ignore_ascii_case("ods"))
         .unwrap_or(false);
 
-    let (sheets, defined_names, hidden_sheets, has_macros, external_workbooks) = if is_xlsx {
-        let mut reader = XlsxReader::new(&mut archive)?;
-        (
-            reader.read_sheets()?,
-            reader.read_defined_names()?,
-            reader.read_hidden_sheets()?,
-            reader.has_macros()?,
-            reader.read_external_workbooks()?,
-        )
+    let source = if is_xlsx {
+        LazySource::Xlsx(XlsxTables::read(&mut archive))
     } else if is_ods {
-        let mut reader = OdsReader::new(&mut archive)?;
-        let sheets = reader.read_sheets()?;
-        let defined_names = reader.read_defined_names()?;
-        (
-            sheets,
-            defined_names,
-            reader.read_hidden_sheets()?,
-            reader.has_macros()?,
-            reader.read_external_workbooks()?,
-        )
+        if ods_parser::is_encrypted_ods(&mut archive)? {
+            return Err(EncryptedFileError {
+                path: path_ref.to_path_buf(),
+            }
+            .into());
+        }
+        LazySource::Ods
     } else {
         return Err(anyhow::anyhow!("Unsupported file format"));
     };
 
-    Ok(Workbook {
+    let mut lazy = LazyWorkbook {
+        metadata: Workbook::default(),
+        sheet_names: Vec::new(),
+        archive,
+        source,
+    };
+
+    let (sheet_names, metadata) = lazy.with_reader(|reader| reader.read_metadata())?;
+    lazy.sheet_names = sheet_names;
+    lazy.metadata = Workbook {
         path: path_ref.to_path_buf(),
-        sheets,
-        defined_names,
-        hidden_sheets,
-        has_macros,
-        external_workbooks,
-    })
+        ..metadata
+    };
+
+    Ok(lazy)
+}
+
+/// Read a workbook from a file path
+pub fn read_workbook<P: AsRef<Path>>(path: P) -> Result<Workbook> {
+    open_workbook(path)?.into_workbook()
 }
 
 #[cfg(test)]
@@ -306,5 +561,318 @@ mod external_workbook_parity_tests {
                 wb_xlsx.path
             );
         }
+
+        // Both keep the target as written and the cached cells of Sheet1
+        assert_eq!(workbooks_xlsx[0].target, "test.xlsx");
+        let linked_ods = workbooks_ods
+            .iter()
+            .find(|wb| wb.path == "test.xlsx")
+            .unwrap();
+        assert_eq!(linked_ods.target, "../test.xlsx");
+        for linked in [&workbooks_xlsx[0], linked_ods] {
+            let sheet = linked
+                .sheets
+                .iter()
+                .find(|sheet| sheet.name == "Sheet1")
+                .unwrap();
+            assert_eq!(sheet.cells.get(&(2, 1)), Some(&CellValue::Number(8.0)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod formula_parity_tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_formula_parity_ods_xlsx() {
+        const TEST_ODS: &[u8] = include_bytes!("../../../tests/minimal_test.ods");
+        const TEST_XLSX: &[u8] = include_bytes!("../../../tests/minimal_test.xlsx");
+
+        let mut archive_ods = ZipArchive::new(Cursor::new(TEST_ODS)).unwrap();
+        let sheets_ods = OdsReader::new(&mut archive_ods)
+            .unwrap()
+            .read_sheets()
+            .unwrap();
+        let mut archive_xlsx = ZipArchive::new(Cursor::new(TEST_XLSX)).unwrap();
+        let sheets_xlsx = XlsxReader::new(&mut archive_xlsx)
+            .unwrap()
+            .read_sheets()
+            .unwrap();
+
+        let mut compared = 0;
+        for sheet_xlsx in &sheets_xlsx {
+            let sheet_ods = sheets_ods
+                .iter()
+                .find(|sheet| sheet.name == sheet_xlsx.name)
+                .unwrap();
+            for cell in sheet_xlsx.all_cells() {
+                let Some(formula) = cell.value.as_formula() else {
+                    continue;
+                };
+                let cell_ods = sheet_ods.get_cell(cell.row, cell.col).unwrap();
+                assert_eq!(
+                    cell_ods.value.as_formula(),
+                    Some(formula),
+                    "{}!{:?}",
+                    sheet_xlsx.name,
+                    (cell.row, cell.col)
+                );
+                assert!(cell_ods.value.original_formula().is_some());
+                assert_eq!(cell.value.original_formula(), None);
+                compared += 1;
+            }
+        }
+        assert!(compared > 0);
+
+        // The original keeps OpenFormula syntax
+        let sheet7 = sheets_ods.iter().find(|sheet| sheet.name == "Sheet7");
+        let link = sheet7.unwrap().get_cell(5, 9).unwrap();
+        assert_eq!(link.value.as_formula(), Some("[1]Sheet1!B3"));
+        assert!(
+            link.value
+                .original_formula()
+                .is_some_and(|f| f.starts_with("of:=['") && f.ends_with("#$Sheet1.B3]"))
+        );
+    }
+}
+
+#[cfg(test)]
+mod validation_parity_tests {
+    use super::validation::{ValidationKind, ValidationOperator};
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_data_validation_parity_ods_xlsx() {
+        const TEST_ODS: &[u8] = include_bytes!("../../../tests/minimal_test.ods");
+        const TEST_XLSX: &[u8] = include_bytes!("../../../tests/minimal_test.xlsx");
+
+        let mut archive_ods = ZipArchive::new(Cursor::new(TEST_ODS)).unwrap();
+        let sheet_ods = OdsReader::new(&mut archive_ods)
+            .unwrap()
+            .read_sheet("sheet 7")
+            .unwrap();
+        let mut archive_xlsx = ZipArchive::new(Cursor::new(TEST_XLSX)).unwrap();
+        let sheet_xlsx = XlsxReader::new(&mut archive_xlsx)
+            .unwrap()
+            .read_sheet("sheet 7")
+            .unwrap();
+
+        assert_eq!(sheet_ods.data_validations, sheet_xlsx.data_validations);
+
+        let list = sheet_xlsx.validation_at(2, 0).expect("A3 has a list");
+        assert_eq!(list.kind, ValidationKind::List);
+        assert_eq!(
+            list.list_items(),
+            Some(vec!["Excel".into(), "Calc".into(), "GSheet".into()])
+        );
+        assert!(list.allow_blank && list.show_error);
+
+        let whole = sheet_xlsx
+            .validation_at(2, 1)
+            .expect("B3 has a whole number rule");
+        assert_eq!(whole.kind, ValidationKind::WholeNumber);
+        assert_eq!(whole.operator, ValidationOperator::Equal);
+        assert_eq!(whole.formula1.as_deref(), Some("0"));
+        assert_eq!(whole.formula2, None);
+    }
+}
+
+#[cfg(test)]
+mod conditional_format_parity_tests {
+    use super::conditional_format::ConditionalFormatKind;
+    use super::validation::ValidationOperator;
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_conditional_format_parity_ods_xlsx() {
+        const TEST_ODS: &[u8] = include_bytes!("../../../tests/minimal_test.ods");
+        const TEST_XLSX: &[u8] = include_bytes!("../../../tests/minimal_test.xlsx");
+
+        let mut archive_ods = ZipArchive::new(Cursor::new(TEST_ODS)).unwrap();
+        let sheet_ods = OdsReader::new(&mut archive_ods)
+            .unwrap()
+            .read_sheet("Sheet7")
+            .unwrap();
+        let mut archive_xlsx = ZipArchive::new(Cursor::new(TEST_XLSX)).unwrap();
+        let sheet_xlsx = XlsxReader::new(&mut archive_xlsx)
+            .unwrap()
+            .read_sheet("Sheet7")
+            .unwrap();
+
+        // ODS has no priorities, only the order of the rules
+        let summary = |sheet: &Sheet| {
+            sheet
+                .conditional_formats
+                .iter()
+                .map(|cf| (cf.kind, cf.operator, cf.formulas.clone(), cf.sqref()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(summary(&sheet_ods), summary(&sheet_xlsx));
+        assert_eq!(
+            summary(&sheet_xlsx),
+            vec![
+                (
+                    ConditionalFormatKind::CellIs,
+                    Some(ValidationOperator::Equal),
+                    vec!["8".to_string()],
+                    "H13:I13".to_string()
+                ),
+                (
+                    ConditionalFormatKind::CellIs,
+                    Some(ValidationOperator::LessThan),
+                    vec!["0".to_string()],
+                    "J13:L13".to_string()
+                ),
+            ]
+        );
+        assert_eq!(sheet_xlsx.conditional_formats[0].priority, 2);
+    }
+}
+
+#[cfg(test)]
+mod table_parity_tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_table_parity_ods_xlsx() {
+        const TEST_ODS: &[u8] = include_bytes!("../../../tests/minimal_test.ods");
+        const TEST_XLSX: &[u8] = include_bytes!("../../../tests/minimal_test.xlsx");
+
+        let mut archive_ods = ZipArchive::new(Cursor::new(TEST_ODS)).unwrap();
+        let mut tables_ods = OdsReader::new(&mut archive_ods)
+            .unwrap()
+            .read_tables()
+            .unwrap();
+        let mut archive_xlsx = ZipArchive::new(Cursor::new(TEST_XLSX)).unwrap();
+        let mut tables_xlsx = XlsxReader::new(&mut archive_xlsx)
+            .unwrap()
+            .read_tables()
+            .unwrap();
+        tables_ods.sort_by(|a, b| a.name.cmp(&b.name));
+        tables_xlsx.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let summary = |tables: &[Table]| {
+            tables
+                .iter()
+                .map(|t| (t.name.clone(), t.sheet.clone(), t.range, t.header_rows))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(summary(&tables_ods), summary(&tables_xlsx));
+        assert_eq!(
+            summary(&tables_xlsx),
+            vec![
+                (
+                    "numbers_used".to_string(),
+                    "Sheet7".to_string(),
+                    (2, 2, 9, 2),
+                    1
+                ),
+                (
+                    "text_not_used_range".to_string(),
+                    "Sheet7".to_string(),
+                    (2, 1, 9, 1),
+                    1
+                ),
+            ]
+        );
+        assert_eq!(tables_xlsx[1].columns, vec!["Number as text".to_string()]);
+        assert_eq!(tables_ods[1].columns, tables_xlsx[1].columns);
+        assert_eq!(tables_xlsx[0].resolve("", 0), Some((3, 2, 9, 2)));
+    }
+}
+
+#[cfg(test)]
+mod lazy_loading_tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Sheet-by-sheet reads, and on-demand and row-streamed reads of `check` (all
+    /// sheets when empty), must match a full read
+    fn assert_lazy_matches_full(reader: &mut dyn WorkbookReader, check: &[&str]) {
+        let full = reader.read_sheets().unwrap();
+        let names = reader.sheet_names().unwrap();
+        assert_eq!(
+            names,
+            full.iter().map(|s| s.name.clone()).collect::<Vec<_>>()
+        );
+
+        for with_cells in [true, false] {
+            let mut index = 0;
+            reader
+                .for_each_sheet(with_cells, &mut |sheet| {
+                    let expected = &full[index];
+                    assert_eq!(sheet.name, expected.name);
+                    assert_eq!(sheet.used_range, expected.used_range, "{}", sheet.name);
+                    let cells = if with_cells { expected.cells.len() } else { 0 };
+                    assert_eq!(sheet.cells.len(), cells, "{}", sheet.name);
+                    index += 1;
+                    Ok(())
+                })
+                .unwrap();
+            assert_eq!(index, full.len());
+        }
+
+        for expected in full
+            .iter()
+            .filter(|s| check.is_empty() || check.contains(&s.name.as_str()))
+        {
+            let sheet = reader.read_sheet(&expected.name).unwrap();
+            assert_eq!(sheet.cells.len(), expected.cells.len(), "{}", sheet.name);
+            assert_eq!(sheet.used_range, expected.used_range, "{}", sheet.name);
+            for ((row, col), cell) in &expected.cells {
+                assert_eq!(sheet.cells[&(row, col)].value, cell.value);
+            }
+
+            let mut streamed = 0;
+            let mut last_row = None;
+            let metadata = reader
+                .for_each_row(&expected.name, &mut |row, cells| {
+                    assert!(last_row.is_none_or(|last| last < row), "rows out of order");
+                    last_row = Some(row);
+                    for cell in cells {
+                        assert_eq!(cell.row, row);
+                        assert_eq!(expected.cells[&(cell.row, cell.col)].value, cell.value);
+                    }
+                    streamed += cells.len();
+                    Ok(())
+                })
+                .unwrap();
+            assert_eq!(streamed, expected.cells.len(), "{}", expected.name);
+            assert!(metadata.cells.is_empty());
+            assert_eq!(metadata.hidden_rows, expected.hidden_rows);
+            assert_eq!(metadata.merged_cells, expected.merged_cells);
+            assert_eq!(metadata.visible, expected.visible);
+        }
+    }
+
+    #[test]
+    fn test_lazy_sheets_match_full_read_xlsx() {
+        const TEST_XLSX: &[u8] = include_bytes!("../../../tests/minimal_test.xlsx");
+        let mut archive = ZipArchive::new(Cursor::new(TEST_XLSX)).unwrap();
+        let mut reader = XlsxReader::new(&mut archive).unwrap();
+        assert_lazy_matches_full(&mut reader, &[]);
+    }
+
+    #[test]
+    fn test_lazy_sheets_match_full_read_ods() {
+        const TEST_ODS: &[u8] = include_bytes!("../../../tests/minimal_test.ods");
+        let mut archive = ZipArchive::new(Cursor::new(TEST_ODS)).unwrap();
+        let mut reader = OdsReader::new(&mut archive).unwrap();
+        // Reading a single ODS sheet still parses all of content.xml, which pads
+        // sheets to 1M styled rows, so only one sheet is read on its own
+        assert_lazy_matches_full(&mut reader, &["Indexing tests"]);
+    }
+
+    #[test]
+    fn test_read_missing_sheet_ods() {
+        const TEST_ODS: &[u8] = include_bytes!("../../../tests/minimal_test.ods");
+        let mut archive = ZipArchive::new(Cursor::new(TEST_ODS)).unwrap();
+        let mut reader = OdsReader::new(&mut archive).unwrap();
+        assert!(reader.read_sheet("No such sheet").is_err());
     }
 }
@@ -0,0 +1,1447 @@
+//! Number format codes
+//!
+//! Parses Excel-style format codes (ODS number styles are converted to the same
+//! syntax by the reader) and renders cell values to the text a user would see.
+//! A code has up to four `;`-separated sections (positive, negative, zero, text),
+//! each optionally carrying a `[Color]` and a `[condition]`.
+
+use super::dates::{DateSystem, DateTime};
+use super::workbook::CellValue;
+use anyhow::{Result, bail};
+use std::str::FromStr;
+
+/// Shown when a value cannot be rendered, as Excel does for out-of-range dates
+const OVERFLOW: &str = "########";
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+/// A parsed number format code
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberFormat {
+    sections: Vec<Section>,
+    /// Section applied to text values, if the code has one
+    text_section: Option<usize>,
+}
+
+/// One `;`-separated part of a format code
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub condition: Option<Condition>,
+    pub color: Option<Color>,
+    tokens: Vec<Token>,
+    kind: SectionKind,
+}
+
+/// What a section renders
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectionKind {
+    /// `General`, possibly surrounded by literals
+    General,
+    /// Digit placeholders, or literals only
+    Number,
+    /// Date and time tokens
+    DateTime,
+    /// `@` text placeholder
+    Text,
+}
+
+/// A `[>100]`-style section condition
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Condition {
+    pub op: Comparison,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl Condition {
+    pub fn matches(&self, value: f64) -> bool {
+        match self.op {
+            Comparison::Lt => value < self.value,
+            Comparison::Le => value <= self.value,
+            Comparison::Gt => value > self.value,
+            Comparison::Ge => value >= self.value,
+            Comparison::Eq => value == self.value,
+            Comparison::Ne => value != self.value,
+        }
+    }
+}
+
+/// Font color selected by a section
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Blue,
+    Cyan,
+    Green,
+    Magenta,
+    Red,
+    White,
+    Yellow,
+    /// `[ColorN]`, an index into the 56-color legacy palette
+    Indexed(u8),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Literal(String),
+    General,
+    /// `@`
+    Text,
+    /// `0`, `#` or `?`
+    Digit(char),
+    DecimalPoint,
+    /// `,`: digit grouping between placeholders, scaling by 1000 after them
+    Comma,
+    Percent,
+    Exponent {
+        lower: bool,
+        plus: bool,
+    },
+    /// Fraction bar between digit placeholders
+    Slash,
+    Date(DatePart),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DatePart {
+    Year(usize),
+    Month(usize),
+    Day(usize),
+    Hour(usize),
+    Minute(usize),
+    Second(usize),
+    /// Digits of fractional seconds after `ss.`
+    SubSecond(usize),
+    /// `[h]`, `[mm]`, `[ss]`: totals that do not wrap
+    ElapsedHours(usize),
+    ElapsedMinutes(usize),
+    ElapsedSeconds(usize),
+    AmPm {
+        short: bool,
+        lower: bool,
+    },
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self::general()
+    }
+}
+
+impl FromStr for NumberFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(code: &str) -> Result<Self> {
+        Self::parse(code)
+    }
+}
+
+impl NumberFormat {
+    /// The `General` format
+    pub fn general() -> Self {
+        Self {
+            sections: vec![Section {
+                condition: None,
+                color: None,
+                tokens: vec![Token::General],
+                kind: SectionKind::General,
+            }],
+            text_section: None,
+        }
+    }
+
+    /// Parse a format code such as `#,##0.00;[Red]-#,##0.00` or `dd/mm/yyyy hh:mm`
+    pub fn parse(code: &str) -> Result<Self> {
+        let mut sections = split_sections(code)?
+            .into_iter()
+            .map(parse_section)
+            .collect::<Result<Vec<_>>>()?;
+        if sections.is_empty() {
+            sections.push(Self::general().sections.remove(0));
+        }
+
+        // The fourth section is for text; with fewer, a section holding `@` is
+        let text_section = if sections.len() >= 4 {
+            Some(3)
+        } else {
+            sections.iter().rposition(|s| s.kind == SectionKind::Text)
+        };
+
+        Ok(Self {
+            sections,
+            text_section,
+        })
+    }
+
+    pub fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+
+    /// Whether numbers are shown as dates or times
+    pub fn is_date_time(&self) -> bool {
+        self.sections[0].kind == SectionKind::DateTime
+    }
+
+    /// Whether numbers are shown with a calendar date (year, month or day)
+    pub fn is_date(&self) -> bool {
+        self.sections[0].tokens.iter().any(|t| {
+            matches!(
+                t,
+                Token::Date(DatePart::Year(_) | DatePart::Month(_) | DatePart::Day(_))
+            )
+        })
+    }
+
+    /// Whether this is a text-only format such as `@`
+    pub fn is_text(&self) -> bool {
+        self.sections.len() == 1 && self.sections[0].kind == SectionKind::Text
+    }
+
+    /// Section used to render `value`
+    pub fn section_for(&self, value: f64) -> Option<&Section> {
+        self.select(value).map(|(section, _)| section)
+    }
+
+    /// Color the section rendering `value` applies, if any
+    pub fn color_for(&self, value: f64) -> Option<Color> {
+        self.section_for(value).and_then(|s| s.color)
+    }
+
+    /// Render a number the way the cell displays it
+    pub fn format_number(&self, value: f64, date_system: DateSystem) -> String {
+        if !value.is_finite() {
+            return "#NUM!".to_string();
+        }
+        let Some((section, keep_sign)) = self.select(value) else {
+            return format_general(value);
+        };
+
+        let negative = keep_sign && value < 0.0 && section.kind != SectionKind::DateTime;
+        let magnitude = if keep_sign && section.kind == SectionKind::DateTime {
+            value
+        } else {
+            value.abs()
+        };
+        let text = match section.kind {
+            SectionKind::DateTime => format_date_time(&section.tokens, magnitude, date_system),
+            _ => format_digits(&section.tokens, magnitude),
+        };
+
+        // A value that rounds to zero is not shown as negative
+        if negative && text.chars().any(|c| c.is_ascii_digit() && c != '0') {
+            format!("-{}", text)
+        } else {
+            text
+        }
+    }
+
+    /// Render a text value through the text section, if any
+    pub fn format_text(&self, text: &str) -> String {
+        let Some(section) = self.text_section.map(|i| &self.sections[i]) else {
+            return text.to_string();
+        };
+        let mut out = String::new();
+        for token in &section.tokens {
+            match token {
+                Token::Text => out.push_str(text),
+                Token::Literal(s) => out.push_str(s),
+                _ => {}
+            }
+        }
+        out
+    }
+
+    /// Render any cell value. Formulas show their cached error, or nothing when
+    /// the cached result is not known.
+    pub fn format_value(&self, value: &CellValue, date_system: DateSystem) -> String {
+        match value {
+            CellValue::Empty => String::new(),
+            CellValue::Number(n) => self.format_number(*n, date_system),
+            CellValue::Text(s, _) => self.format_text(s),
+            CellValue::Boolean(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+            CellValue::Formula { cached_error, .. } => cached_error
+                .as_ref()
+                .map(|e| e.to_string())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Pick the section for a number, and whether its minus sign is shown.
+    /// Sections after the first spell out their own sign, so it is dropped there.
+    fn select(&self, value: f64) -> Option<(&Section, bool)> {
+        let numeric: Vec<&Section> = self
+            .sections
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| Some(*i) != self.text_section)
+            .map(|(_, s)| s)
+            .take(3)
+            .collect();
+        if numeric.is_empty() {
+            return None;
+        }
+
+        if numeric.iter().any(|s| s.condition.is_some()) {
+            for (i, section) in numeric.iter().enumerate() {
+                match section.condition {
+                    Some(c) if !c.matches(value) => continue,
+                    _ => return Some((section, i == 0)),
+                }
+            }
+            let last = numeric.len() - 1;
+            return Some((numeric[last], last == 0));
+        }
+
+        Some(match numeric.len() {
+            1 => (numeric[0], true),
+            2 if value >= 0.0 => (numeric[0], true),
+            2 => (numeric[1], false),
+            _ if value > 0.0 => (numeric[0], true),
+            _ if value < 0.0 => (numeric[1], false),
+            _ => (numeric[2], false),
+        })
+    }
+}
+
+impl Section {
+    pub fn kind(&self) -> SectionKind {
+        self.kind
+    }
+
+    /// Digits shown after the decimal point (or after the seconds, for times)
+    pub fn decimal_places(&self) -> usize {
+        match self.kind {
+            SectionKind::DateTime => self
+                .tokens
+                .iter()
+                .filter_map(|t| match t {
+                    Token::Date(DatePart::SubSecond(n)) => Some(*n),
+                    _ => None,
+                })
+                .max()
+                .unwrap_or(0),
+            SectionKind::Number => {
+                let Some(point) = self.tokens.iter().position(|t| *t == Token::DecimalPoint) else {
+                    return 0;
+                };
+                self.tokens[point + 1..]
+                    .iter()
+                    .take_while(|t| !matches!(t, Token::Exponent { .. }))
+                    .filter(|t| matches!(t, Token::Digit(_)))
+                    .count()
+            }
+            SectionKind::General | SectionKind::Text => 0,
+        }
+    }
+}
+
+/// Split a code on `;`, ignoring separators inside quotes, brackets and escapes
+fn split_sections(code: &str) -> Result<Vec<&str>> {
+    let mut sections = Vec::new();
+    let mut start = 0;
+    let mut in_quotes = false;
+    let mut in_brackets = false;
+    let mut escaped = false;
+
+    for (i, c) in code.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' if !in_quotes => escaped = true,
+            '"' if !in_brackets => in_quotes = !in_quotes,
+            '[' if !in_quotes => in_brackets = true,
+            ']' if !in_quotes => in_brackets = false,
+            ';' if !in_quotes && !in_brackets => {
+                sections.push(&code[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if in_quotes {
+        bail!("Unterminated string in number format: {}", code);
+    }
+    if in_brackets {
+        bail!("Unterminated bracket in number format: {}", code);
+    }
+    if !code.is_empty() {
+        sections.push(&code[start..]);
+    }
+    Ok(sections)
+}
+
+fn push_literal(tokens: &mut Vec<Token>, s: &str) {
+    if let Some(Token::Literal(last)) = tokens.last_mut() {
+        last.push_str(s);
+    } else {
+        tokens.push(Token::Literal(s.to_string()));
+    }
+}
+
+fn starts_with_ignore_case(chars: &[char], pattern: &str) -> bool {
+    chars.len() >= pattern.len()
+        && chars
+            .iter()
+            .zip(pattern.chars())
+            .all(|(a, b)| a.eq_ignore_ascii_case(&b))
+}
+
+fn parse_section(code: &str) -> Result<Section> {
+    let chars: Vec<char> = code.chars().collect();
+    let mut section = Section {
+        condition: None,
+        color: None,
+        tokens: Vec::new(),
+        kind: SectionKind::Number,
+    };
+    let tokens = &mut section.tokens;
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let rest = &chars[i..];
+        match c {
+            '"' => {
+                let end = rest[1..]
+                    .iter()
+                    .position(|&ch| ch == '"')
+                    .map(|p| i + 1 + p)
+                    .unwrap_or(chars.len());
+                let literal: String = chars[i + 1..end].iter().collect();
+                push_literal(tokens, &literal);
+                i = end + 1;
+                continue;
+            }
+            '\\' => {
+                if let Some(next) = rest.get(1) {
+                    push_literal(tokens, &next.to_string());
+                }
+                i += 2;
+                continue;
+            }
+            // `_x` leaves a space as wide as `x`
+            '_' => {
+                push_literal(tokens, " ");
+                i += 2;
+                continue;
+            }
+            // `*x` fills the column with `x`; there is no column width to fill here
+            '*' => {
+                i += 2;
+                continue;
+            }
+            '[' => {
+                let end = rest
+                    .iter()
+                    .position(|&ch| ch == ']')
+                    .map(|p| i + p)
+                    .unwrap_or(chars.len());
+                let content: String = chars[i + 1..end].iter().collect();
+                parse_bracket(&content, &mut section.condition, &mut section.color, tokens)?;
+                i = end + 1;
+                continue;
+            }
+            '0' | '#' | '?' => tokens.push(Token::Digit(c)),
+            '.' => tokens.push(Token::DecimalPoint),
+            ',' => tokens.push(Token::Comma),
+            '%' => tokens.push(Token::Percent),
+            '@' => tokens.push(Token::Text),
+            '/' => tokens.push(Token::Slash),
+            'E' | 'e' if matches!(rest.get(1), Some('+') | Some('-')) => {
+                tokens.push(Token::Exponent {
+                    lower: c == 'e',
+                    plus: rest[1] == '+',
+                });
+                i += 2;
+                continue;
+            }
+            'G' | 'g' if starts_with_ignore_case(rest, "general") => {
+                tokens.push(Token::General);
+                i += "general".len();
+                continue;
+            }
+            'A' | 'a' if starts_with_ignore_case(rest, "am/pm") => {
+                tokens.push(Token::Date(DatePart::AmPm {
+                    short: false,
+                    lower: c == 'a',
+                }));
+                i += "am/pm".len();
+                continue;
+            }
+            'A' | 'a' if starts_with_ignore_case(rest, "a/p") => {
+                tokens.push(Token::Date(DatePart::AmPm {
+                    short: true,
+                    lower: c == 'a',
+                }));
+                i += "a/p".len();
+                continue;
+            }
+            'y' | 'Y' | 'm' | 'M' | 'd' | 'D' | 'h' | 'H' | 's' | 'S' => {
+                let lower = c.to_ascii_lowercase();
+                let len = rest
+                    .iter()
+                    .take_while(|ch| ch.to_ascii_lowercase() == lower)
+                    .count();
+                tokens.push(Token::Date(match lower {
+                    'y' => DatePart::Year(len),
+                    'm' => DatePart::Month(len),
+                    'd' => DatePart::Day(len),
+                    'h' => DatePart::Hour(len),
+                    _ => DatePart::Second(len),
+                }));
+                i += len;
+                continue;
+            }
+            _ => push_literal(tokens, &c.to_string()),
+        }
+        i += 1;
+    }
+
+    section.kind = if tokens.iter().any(|t| matches!(t, Token::Date(_))) {
+        resolve_date_tokens(tokens);
+        SectionKind::DateTime
+    } else {
+        resolve_number_tokens(tokens);
+        if tokens.contains(&Token::General) {
+            SectionKind::General
+        } else if tokens.contains(&Token::Text)
+            && !tokens.iter().any(|t| matches!(t, Token::Digit(_)))
+        {
+            SectionKind::Text
+        } else {
+            SectionKind::Number
+        }
+    };
+    Ok(section)
+}
+
+fn parse_bracket(
+    content: &str,
+    condition: &mut Option<Condition>,
+    color: &mut Option<Color>,
+    tokens: &mut Vec<Token>,
+) -> Result<()> {
+    // [$€-407]: currency symbol and locale
+    if let Some(currency) = content.strip_prefix('$') {
+        let symbol = currency.split('-').next().unwrap_or_default();
+        if !symbol.is_empty() {
+            push_literal(tokens, symbol);
+        }
+        return Ok(());
+    }
+
+    let ops = [
+        ("<=", Comparison::Le),
+        (">=", Comparison::Ge),
+        ("<>", Comparison::Ne),
+        ("<", Comparison::Lt),
+        (">", Comparison::Gt),
+        ("=", Comparison::Eq),
+    ];
+    for (prefix, op) in ops {
+        if let Some(number) = content.strip_prefix(prefix) {
+            let value = number.trim().parse::<f64>().map_err(|_| {
+                anyhow::anyhow!("Invalid condition in number format: [{}]", content)
+            })?;
+            *condition = Some(Condition { op, value });
+            return Ok(());
+        }
+    }
+
+    let lower = content.to_ascii_lowercase();
+    if !lower.is_empty() && lower.chars().all(|c| c == lower.as_bytes()[0] as char) {
+        let len = lower.len();
+        let part = match lower.as_bytes()[0] {
+            b'h' => Some(DatePart::ElapsedHours(len)),
+            b'm' => Some(DatePart::ElapsedMinutes(len)),
+            b's' => Some(DatePart::ElapsedSeconds(len)),
+            _ => None,
+        };
+        if let Some(part) = part {
+            tokens.push(Token::Date(part));
+            return Ok(());
+        }
+    }
+
+    *color = match lower.as_str() {
+        "black" => Some(Color::Black),
+        "blue" => Some(Color::Blue),
+        "cyan" => Some(Color::Cyan),
+        "green" => Some(Color::Green),
+        "magenta" => Some(Color::Magenta),
+        "red" => Some(Color::Red),
+        "white" => Some(Color::White),
+        "yellow" => Some(Color::Yellow),
+        // Locale and calendar modifiers such as [DBNum1] do not change the text
+        _ => match lower.strip_prefix("color").map(str::parse::<u8>) {
+            Some(Ok(index)) => Some(Color::Indexed(index)),
+            _ => *color,
+        },
+    };
+    Ok(())
+}
+
+/// Turn `m` next to hours or seconds into minutes, `.0` after seconds into
+/// fractional seconds, and number-only tokens into literals
+fn resolve_date_tokens(tokens: &mut Vec<Token>) {
+    let is_hour = |t: &Token| {
+        matches!(
+            t,
+            Token::Date(DatePart::Hour(_) | DatePart::ElapsedHours(_))
+        )
+    };
+    let is_second = |t: &Token| {
+        matches!(
+            t,
+            Token::Date(DatePart::Second(_) | DatePart::ElapsedSeconds(_))
+        )
+    };
+
+    for i in 0..tokens.len() {
+        if let Token::Date(DatePart::Month(len)) = tokens[i]
+            && len <= 2
+        {
+            let prev = tokens[..i]
+                .iter()
+                .rev()
+                .find(|t| matches!(t, Token::Date(_)));
+            let next = tokens[i + 1..].iter().find(|t| matches!(t, Token::Date(_)));
+            if prev.is_some_and(is_hour) || next.is_some_and(is_second) {
+                tokens[i] = Token::Date(DatePart::Minute(len));
+            }
+        }
+    }
+
+    let mut resolved = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i].clone();
+        match token {
+            Token::DecimalPoint
+                if resolved.last().is_some_and(is_second)
+                    && tokens.get(i + 1) == Some(&Token::Digit('0')) =>
+            {
+                let digits = tokens[i + 1..]
+                    .iter()
+                    .take_while(|t| **t == Token::Digit('0'))
+                    .count();
+                resolved.push(Token::Date(DatePart::SubSecond(digits.min(3))));
+                i += 1 + digits;
+                continue;
+            }
+            Token::Digit(c) => push_literal(&mut resolved, &c.to_string()),
+            Token::DecimalPoint => push_literal(&mut resolved, "."),
+            Token::Comma => push_literal(&mut resolved, ","),
+            Token::Percent => push_literal(&mut resolved, "%"),
+            Token::Slash => push_literal(&mut resolved, "/"),
+            Token::Exponent { lower, plus } => {
+                push_literal(&mut resolved, if lower { "e" } else { "E" });
+                push_literal(&mut resolved, if plus { "+" } else { "-" });
+            }
+            Token::Literal(s) => push_literal(&mut resolved, &s),
+            other => resolved.push(other),
+        }
+        i += 1;
+    }
+    *tokens = resolved;
+}
+
+/// Keep `/` as a fraction bar only between digit placeholders
+fn resolve_number_tokens(tokens: &mut [Token]) {
+    for i in 0..tokens.len() {
+        if tokens[i] != Token::Slash {
+            continue;
+        }
+        let after_digit = i > 0 && matches!(tokens[i - 1], Token::Digit(_));
+        let before_denominator = match tokens.get(i + 1) {
+            Some(Token::Digit(_)) => true,
+            Some(Token::Literal(s)) => s.starts_with(|c: char| c.is_ascii_digit()),
+            _ => false,
+        };
+        if !(after_digit && before_denominator) {
+            tokens[i] = Token::Literal("/".to_string());
+        }
+    }
+}
+
+/// Split a non-negative number into integer and fraction digits, rounded half
+/// away from zero at `places` decimals after reducing to 15 significant digits
+/// like spreadsheet applications do. An integer part of zero is returned empty.
+fn round_decimal(value: f64, places: usize) -> (String, String) {
+    let sci = format!("{:.14e}", value);
+    let (mantissa, exponent) = sci.split_once('e').unwrap_or((&sci, "0"));
+    let digits: String = mantissa.chars().filter(|c| c.is_ascii_digit()).collect();
+    let point = exponent.parse::<i64>().unwrap_or(0) + 1;
+
+    let (int_part, mut frac_part) = if point <= 0 {
+        (String::new(), "0".repeat((-point) as usize) + &digits)
+    } else if point as usize >= digits.len() {
+        (
+            digits.clone() + &"0".repeat(point as usize - digits.len()),
+            String::new(),
+        )
+    } else {
+        let (int, frac) = digits.split_at(point as usize);
+        (int.to_string(), frac.to_string())
+    };
+
+    let round_up = frac_part.len() > places && frac_part.as_bytes()[places] >= b'5';
+    frac_part.truncate(places);
+    while frac_part.len() < places {
+        frac_part.push('0');
+    }
+
+    let mut combined: Vec<u8> = format!("{}{}", int_part, frac_part).into_bytes();
+    if round_up {
+        let mut idx = combined.len();
+        loop {
+            if idx == 0 {
+                combined.insert(0, b'1');
+                break;
+            }
+            idx -= 1;
+            if combined[idx] == b'9' {
+                combined[idx] = b'0';
+            } else {
+                combined[idx] += 1;
+                break;
+            }
+        }
+    }
+
+    let split = combined.len() - places;
+    let int_digits = String::from_utf8_lossy(&combined[..split])
+        .trim_start_matches('0')
+        .to_string();
+    let frac_digits = String::from_utf8_lossy(&combined[split..]).to_string();
+    (int_digits, frac_digits)
+}
+
+/// Render a number the way the `General` format does
+fn format_general(value: f64) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+    let sign = if value < 0.0 { "-" } else { "" };
+    let abs = value.abs();
+
+    let scientific = || {
+        let exponent = abs.log10().floor() as i32;
+        let (int, frac) = round_decimal(abs / 10f64.powi(exponent), 5);
+        // Rounding the mantissa may carry into another digit (9.999995 -> 10.00000)
+        let (int, frac, exponent) = if int.len() > 1 {
+            let (int, frac) = round_decimal(abs / 10f64.powi(exponent + 1), 5);
+            (int, frac, exponent + 1)
+        } else {
+            (int, frac, exponent)
+        };
+        let frac = frac.trim_end_matches('0');
+        let mantissa = if frac.is_empty() {
+            int
+        } else {
+            format!("{}.{}", int, frac)
+        };
+        format!(
+            "{}{}E{}{:02}",
+            sign,
+            mantissa,
+            if exponent < 0 { '-' } else { '+' },
+            exponent.abs()
+        )
+    };
+
+    if !(1e-9..1e11).contains(&abs) {
+        return scientific();
+    }
+
+    // Up to 11 characters: integer digits plus as many decimals as fit
+    let int_len = if abs < 1.0 {
+        1
+    } else {
+        abs.log10().floor() as usize + 1
+    };
+    let (int, frac) = round_decimal(abs, 10usize.saturating_sub(int_len));
+    let frac = frac.trim_end_matches('0');
+    if abs < 1e-4 {
+        // Prefer scientific notation when the decimal form drops significant digits
+        let sci = scientific();
+        let sci_digits = sci
+            .split('E')
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .filter(char::is_ascii_digit)
+            .count();
+        if frac.trim_start_matches('0').len() < sci_digits {
+            return sci;
+        }
+    }
+
+    let int = if int.is_empty() { "0" } else { &int };
+    if frac.is_empty() {
+        format!("{}{}", sign, int)
+    } else {
+        format!("{}{}.{}", sign, int, frac)
+    }
+}
+
+/// Fill integer digit placeholders right to left; the leftmost takes any overflow
+fn fill_integer(placeholders: &[char], digits: &str) -> Vec<String> {
+    let digits: Vec<char> = digits.chars().collect();
+    let mut remaining = digits.len();
+    let mut out = vec![String::new(); placeholders.len()];
+    for (i, &p) in placeholders.iter().enumerate().rev() {
+        if i == 0 && remaining > 0 {
+            out[i] = digits[..remaining].iter().collect();
+            remaining = 0;
+        } else if remaining > 0 {
+            remaining -= 1;
+            out[i] = digits[remaining].to_string();
+        } else {
+            out[i] = match p {
+                '0' => "0".to_string(),
+                '?' => " ".to_string(),
+                _ => String::new(),
+            };
+        }
+    }
+    out
+}
+
+/// Fill fraction digit placeholders left to right, dropping insignificant
+/// trailing zeros for `#` and blanking them for `?`
+fn fill_fraction(placeholders: &[char], digits: &str) -> Vec<String> {
+    let mut out: Vec<String> = digits.chars().map(|c| c.to_string()).collect();
+    for i in (0..placeholders.len()).rev() {
+        if out[i] != "0" || placeholders[i] == '0' {
+            break;
+        }
+        out[i] = if placeholders[i] == '?' {
+            " ".to_string()
+        } else {
+            String::new()
+        };
+    }
+    out
+}
+
+/// Insert thousands separators between the digits of an integer part
+fn group_thousands(parts: &[(String, bool)]) -> String {
+    let chars: Vec<(char, bool)> = parts
+        .iter()
+        .flat_map(|(s, is_digit)| s.chars().map(move |c| (c, *is_digit && c.is_ascii_digit())))
+        .collect();
+    let mut out = Vec::with_capacity(chars.len() + chars.len() / 3);
+    let mut seen = 0;
+    for &(c, is_digit) in chars.iter().rev() {
+        if is_digit {
+            if seen > 0 && seen % 3 == 0 {
+                out.push(',');
+            }
+            seen += 1;
+        }
+        out.push(c);
+    }
+    out.iter().rev().collect()
+}
+
+/// Render a non-negative number through a `Number` or `General` section
+fn format_digits(tokens: &[Token], value: f64) -> String {
+    let exponent_at = tokens
+        .iter()
+        .position(|t| matches!(t, Token::Exponent { .. }));
+    let number_end = exponent_at.unwrap_or(tokens.len());
+    let point_at = tokens[..number_end]
+        .iter()
+        .position(|t| *t == Token::DecimalPoint);
+    let int_end = point_at.unwrap_or(number_end);
+
+    // A comma between integer placeholders groups; one after the last
+    // placeholder of the number scales by 1000
+    let mut grouping = false;
+    let mut scale = 0;
+    let mut seen_digit = false;
+    for (i, token) in tokens[..number_end].iter().enumerate() {
+        match token {
+            Token::Digit(_) => seen_digit = true,
+            Token::Comma if seen_digit => {
+                let next = tokens[i + 1..number_end]
+                    .iter()
+                    .find(|t| **t != Token::Comma);
+                match next {
+                    Some(Token::Digit(_)) => grouping |= i < int_end,
+                    Some(Token::DecimalPoint) => {}
+                    _ => scale += 1,
+                }
+            }
+            _ => {}
+        }
+    }
+    let percents = tokens.iter().filter(|t| **t == Token::Percent).count() as i32;
+    let value = value * 100f64.powi(percents) / 1000f64.powi(scale);
+
+    if let Some(slash) = tokens.iter().position(|t| *t == Token::Slash) {
+        return format_fraction(tokens, slash, value, grouping);
+    }
+
+    let int_placeholders: Vec<char> = digit_placeholders(&tokens[..int_end]);
+    let frac_placeholders: Vec<char> = point_at
+        .map(|p| digit_placeholders(&tokens[p + 1..number_end]))
+        .unwrap_or_default();
+
+    let (mantissa, exponent) = match exponent_at {
+        Some(_) => scientific_parts(&int_placeholders, value),
+        None => (value, 0),
+    };
+    let (int_digits, frac_digits) = round_decimal(mantissa, frac_placeholders.len());
+    let (int_digits, frac_digits, exponent) =
+        if exponent_at.is_some() && int_digits.len() > int_placeholders.len().max(1) {
+            // Rounding carried into another digit: shift one more power of ten
+            let step = engineering_step(&int_placeholders);
+            let (i, f) = round_decimal(mantissa / 10f64.powi(step), frac_placeholders.len());
+            (i, f, exponent + step)
+        } else {
+            (int_digits, frac_digits, exponent)
+        };
+
+    let int_fill = fill_integer(&int_placeholders, &int_digits);
+    let frac_fill = fill_fraction(&frac_placeholders, &frac_digits);
+
+    let mut out = String::new();
+    let mut int_parts: Vec<(String, bool)> = Vec::new();
+    let (mut int_idx, mut frac_idx) = (0, 0);
+    for (i, token) in tokens.iter().enumerate() {
+        let in_integer = i < int_end;
+        let piece = match token {
+            Token::Digit(_) if in_integer => {
+                int_idx += 1;
+                int_parts.push((int_fill[int_idx - 1].clone(), true));
+                continue;
+            }
+            Token::Digit(_) if i < number_end => {
+                frac_idx += 1;
+                frac_fill[frac_idx - 1].clone()
+            }
+            Token::Digit(_) => continue,
+            Token::Literal(s) if in_integer => {
+                int_parts.push((s.clone(), false));
+                continue;
+            }
+            Token::Literal(s) => s.clone(),
+            Token::DecimalPoint => ".".to_string(),
+            Token::Percent => "%".to_string(),
+            Token::General if in_integer => {
+                int_parts.push((format_general(value), false));
+                continue;
+            }
+            Token::General => format_general(value),
+            Token::Exponent { lower, plus } => {
+                let exponent_placeholders = digit_placeholders(&tokens[i + 1..]);
+                let width = exponent_placeholders.iter().filter(|&&p| p == '0').count();
+                let sign = if exponent < 0 {
+                    "-"
+                } else if *plus {
+                    "+"
+                } else {
+                    ""
+                };
+                format!(
+                    "{}{}{:0width$}",
+                    if *lower { 'e' } else { 'E' },
+                    sign,
+                    exponent.abs(),
+                    width = width
+                )
+            }
+            Token::Comma | Token::Text | Token::Slash | Token::Date(_) => continue,
+        };
+        if !int_parts.is_empty() {
+            out.push_str(&render_integer(&int_parts, grouping));
+            int_parts.clear();
+        }
+        out.push_str(&piece);
+    }
+    if !int_parts.is_empty() {
+        out.push_str(&render_integer(&int_parts, grouping));
+    }
+    out
+}
+
+fn render_integer(parts: &[(String, bool)], grouping: bool) -> String {
+    if grouping {
+        group_thousands(parts)
+    } else {
+        parts.iter().map(|(s, _)| s.as_str()).collect()
+    }
+}
+
+fn digit_placeholders(tokens: &[Token]) -> Vec<char> {
+    tokens
+        .iter()
+        .filter_map(|t| match t {
+            Token::Digit(c) => Some(*c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// `##0.0E+0` is engineering notation: exponents move in steps of the
+/// integer placeholder count
+fn engineering_step(int_placeholders: &[char]) -> i32 {
+    if int_placeholders.len() > 1 && int_placeholders[0] == '#' {
+        int_placeholders.len() as i32
+    } else {
+        1
+    }
+}
+
+/// Mantissa and exponent of `value` for a scientific format
+fn scientific_parts(int_placeholders: &[char], value: f64) -> (f64, i32) {
+    if value == 0.0 {
+        return (0.0, 0);
+    }
+    let magnitude = value.log10().floor() as i32;
+    let step = engineering_step(int_placeholders);
+    let exponent = if step > 1 {
+        magnitude.div_euclid(step) * step
+    } else {
+        magnitude - (int_placeholders.len().max(1) as i32 - 1)
+    };
+    (value / 10f64.powi(exponent), exponent)
+}
+
+/// Render `# ?/?`-style fractions, with an optional whole part and a fixed or
+/// maximum-width denominator
+fn format_fraction(tokens: &[Token], slash: usize, value: f64, grouping: bool) -> String {
+    let numerator_start = tokens[..slash]
+        .iter()
+        .rposition(|t| !matches!(t, Token::Digit(_)))
+        .map(|p| p + 1)
+        .unwrap_or(0);
+    let whole_placeholders = digit_placeholders(&tokens[..numerator_start]);
+    let numerator_placeholders = digit_placeholders(&tokens[numerator_start..slash]);
+
+    let (denominator_placeholders, fixed_denominator) = match tokens.get(slash + 1) {
+        Some(Token::Literal(s)) => {
+            let digits: String = s.chars().take_while(char::is_ascii_digit).collect();
+            (Vec::new(), digits.parse::<u64>().ok().filter(|d| *d > 0))
+        }
+        _ => {
+            let count = tokens[slash + 1..]
+                .iter()
+                .take_while(|t| matches!(t, Token::Digit(_)))
+                .count();
+            (
+                digit_placeholders(&tokens[slash + 1..slash + 1 + count]),
+                None,
+            )
+        }
+    };
+
+    let has_whole = !whole_placeholders.is_empty();
+    let mut whole = if has_whole { value.trunc() } else { 0.0 };
+    let frac = value - whole;
+
+    let (mut numerator, denominator) = match fixed_denominator {
+        Some(d) => ((frac * d as f64).round() as u64, d),
+        None => {
+            let max = 10u64.pow(denominator_placeholders.len().clamp(1, 4) as u32) - 1;
+            (1..=max)
+                .map(|d| ((frac * d as f64).round() as u64, d))
+                .min_by(|a, b| {
+                    let err = |(n, d): &(u64, u64)| (frac - *n as f64 / *d as f64).abs();
+                    err(a).total_cmp(&err(b))
+                })
+                .unwrap_or((0, 1))
+        }
+    };
+    if has_whole && numerator == denominator {
+        whole += 1.0;
+        numerator = 0;
+    }
+
+    let whole_digits = if whole == 0.0 {
+        String::new()
+    } else {
+        format!("{:.0}", whole)
+    };
+
+    // No fractional part left: show only the whole number (or a lone zero)
+    if numerator == 0 && (has_whole || value == 0.0) {
+        let mut out = format_digits(&tokens[..numerator_start], whole);
+        let trimmed = out.trim_end().len();
+        out.truncate(trimmed);
+        if out.is_empty() {
+            out.push('0');
+        }
+        return out;
+    }
+
+    let whole_fill = fill_integer(&whole_placeholders, &whole_digits);
+    let numerator_fill = fill_integer(&numerator_placeholders, &numerator.to_string());
+    let denominator_text = denominator.to_string();
+    let denominator_pad = denominator_placeholders
+        .iter()
+        .filter(|p| **p == '?')
+        .count()
+        .saturating_sub(denominator_text.len());
+
+    let mut out = String::new();
+    let mut whole_parts: Vec<(String, bool)> = Vec::new();
+    let (mut whole_idx, mut numerator_idx) = (0, 0);
+    let mut denominator_done = false;
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Digit(_) if i < numerator_start => {
+                whole_idx += 1;
+                whole_parts.push((whole_fill[whole_idx - 1].clone(), true));
+            }
+            Token::Digit(_) if i < slash => {
+                numerator_idx += 1;
+                out.push_str(&numerator_fill[numerator_idx - 1]);
+            }
+            Token::Digit(_) if !denominator_done => {
+                out.push_str(&denominator_text);
+                out.push_str(&" ".repeat(denominator_pad));
+                denominator_done = true;
+            }
+            Token::Slash => {
+                out.push('/');
+                if fixed_denominator.is_some() {
+                    out.push_str(&denominator_text);
+                }
+            }
+            Token::Literal(s) if i == slash + 1 && fixed_denominator.is_some() => {
+                out.push_str(s.trim_start_matches(|c: char| c.is_ascii_digit()));
+            }
+            Token::Literal(s) if i < numerator_start => whole_parts.push((s.clone(), false)),
+            Token::Literal(s) => out.push_str(s),
+            Token::Percent => out.push('%'),
+            _ => {}
+        }
+        if i + 1 == numerator_start {
+            out.push_str(&render_integer(&whole_parts, grouping));
+            whole_parts.clear();
+        }
+    }
+    out
+}
+
+/// Render a serial through a date/time section
+fn format_date_time(tokens: &[Token], serial: f64, date_system: DateSystem) -> String {
+    let sub_second_digits = tokens
+        .iter()
+        .filter_map(|t| match t {
+            Token::Date(DatePart::SubSecond(n)) => Some(*n as i32),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0);
+
+    // Round to the finest unit shown so carries reach minutes, hours and days
+    let units_per_day = 86_400.0 * 10f64.powi(sub_second_digits);
+    let serial = (serial * units_per_day).round() / units_per_day;
+    let Some(dt) = date_system.to_datetime(serial) else {
+        return OVERFLOW.to_string();
+    };
+
+    let twelve_hour = tokens
+        .iter()
+        .any(|t| matches!(t, Token::Date(DatePart::AmPm { .. })));
+
+    let mut out = String::new();
+    for token in tokens {
+        match token {
+            Token::Literal(s) => out.push_str(s),
+            Token::Date(part) => out.push_str(&format_date_part(*part, &dt, serial, twelve_hour)),
+            _ => {}
+        }
+    }
+    out
+}
+
+fn format_date_part(part: DatePart, dt: &DateTime, serial: f64, twelve_hour: bool) -> String {
+    let pad = |value: u32, len: usize| {
+        if len >= 2 {
+            format!("{:02}", value)
+        } else {
+            value.to_string()
+        }
+    };
+    // Totals are floored with a small tolerance for binary rounding
+    let total = |per_day: f64| (serial * per_day + 1e-9).floor() as u64;
+
+    match part {
+        DatePart::Year(len) if len <= 2 => format!("{:02}", dt.year.rem_euclid(100)),
+        DatePart::Year(_) => format!("{:04}", dt.year),
+        DatePart::Month(len) => {
+            let name = MONTHS[(dt.month as usize).saturating_sub(1) % 12];
+            match len {
+                1 | 2 => pad(dt.month, len),
+                3 => name[..3].to_string(),
+                5 => name[..1].to_string(),
+                _ => name.to_string(),
+            }
+        }
+        DatePart::Day(len) => {
+            let name = WEEKDAYS[dt.weekday() as usize];
+            match len {
+                1 | 2 => pad(dt.day, len),
+                3 => name[..3].to_string(),
+                _ => name.to_string(),
+            }
+        }
+        DatePart::Hour(len) => {
+            let hour = if twelve_hour {
+                (dt.hour + 11) % 12 + 1
+            } else {
+                dt.hour
+            };
+            pad(hour, len)
+        }
+        DatePart::Minute(len) => pad(dt.minute, len),
+        DatePart::Second(len) => pad(dt.second, len),
+        DatePart::SubSecond(digits) => {
+            let fraction = dt.millisecond / 10u32.pow(3 - digits as u32);
+            format!(".{:0width$}", fraction, width = digits)
+        }
+        DatePart::ElapsedHours(len) => format!("{:0len$}", total(24.0), len = len),
+        DatePart::ElapsedMinutes(len) => format!("{:0len$}", total(1440.0), len = len),
+        DatePart::ElapsedSeconds(len) => format!("{:0len$}", total(86_400.0), len = len),
+        DatePart::AmPm { short, lower } => {
+            let text = match (dt.hour < 12, short) {
+                (true, false) => "AM",
+                (false, false) => "PM",
+                (true, true) => "A",
+                (false, true) => "P",
+            };
+            if lower {
+                text.to_ascii_lowercase()
+            } else {
+                text.to_string()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(code: &str, value: f64) -> String {
+        NumberFormat::parse(code)
+            .unwrap()
+            .format_number(value, DateSystem::Excel1900)
+    }
+
+    #[test]
+    fn test_general() {
+        assert_eq!(render("General", 0.0), "0");
+        assert_eq!(render("General", 42.0), "42");
+        assert_eq!(render("General", -1.5), "-1.5");
+        assert_eq!(render("General", 0.1 + 0.2), "0.3");
+        assert_eq!(render("General", 1234.567890123), "1234.56789");
+        assert_eq!(render("General", 123456789012.0), "1.23457E+11");
+        assert_eq!(render("General", 0.00001234), "0.00001234");
+        assert_eq!(render("General", 0.0000123456789), "1.23457E-05");
+        assert_eq!(render("", 7.0), "7");
+    }
+
+    #[test]
+    fn test_digits_thousands_and_rounding() {
+        assert_eq!(render("0", 2.5), "3");
+        assert_eq!(render("0.00", 2.675), "2.68");
+        assert_eq!(render("0.00", 0.125), "0.13");
+        assert_eq!(render("#,##0", 1234567.0), "1,234,567");
+        assert_eq!(render("#,##0.00", 1234.5), "1,234.50");
+        assert_eq!(render("#.##", 0.5), ".5");
+        assert_eq!(render("0.0#", 3.1), "3.1");
+        assert_eq!(render("0.??", 3.1), "3.1 ");
+        assert_eq!(render("000", 7.0), "007");
+        assert_eq!(render("#,##0,", 1234567.0), "1,235");
+        assert_eq!(render("0.0,,\"M\"", 2500000.0), "2.5M");
+        assert_eq!(render("000-0000", 5551234.0), "555-1234");
+    }
+
+    #[test]
+    fn test_percent_and_scientific() {
+        assert_eq!(render("0%", 0.256), "26%");
+        assert_eq!(render("0.00%", 0.25), "25.00%");
+        assert_eq!(render("0.00E+00", 12345.0), "1.23E+04");
+        assert_eq!(render("0.00E+00", 0.00012), "1.20E-04");
+        assert_eq!(render("0.00E-00", 12345.0), "1.23E04");
+        assert_eq!(render("##0.0E+0", 12345.0), "12.3E+3");
+        assert_eq!(render("0.0E+00", 9.99), "1.0E+01");
+    }
+
+    #[test]
+    fn test_sections_colors_and_conditions() {
+        let code = "#,##0.00;[Red](#,##0.00);\"zero\";\"text: \"@";
+        assert_eq!(render(code, 1234.5), "1,234.50");
+        assert_eq!(render(code, -1234.5), "(1,234.50)");
+        assert_eq!(render(code, 0.0), "zero");
+
+        let format = NumberFormat::parse(code).unwrap();
+        assert_eq!(format.color_for(-1.0), Some(Color::Red));
+        assert_eq!(format.color_for(1.0), None);
+        assert_eq!(format.format_text("abc"), "text: abc");
+
+        assert_eq!(render("0", -5.0), "-5");
+        assert_eq!(render("0", -0.2), "0");
+        assert_eq!(render("0;0", -5.0), "5");
+
+        let code = "[Blue][<=100]\"low \"0;[Color10][>100]\"high \"0";
+        assert_eq!(render(code, 50.0), "low 50");
+        assert_eq!(render(code, 500.0), "high 500");
+        let format = NumberFormat::parse(code).unwrap();
+        assert_eq!(format.color_for(500.0), Some(Color::Indexed(10)));
+    }
+
+    #[test]
+    fn test_literals_and_currency() {
+        assert_eq!(render("\\$#,##0.00", 5.0), "$5.00");
+        assert_eq!(render("[$€-407] #,##0.00", 1234.0), "€ 1,234.00");
+        assert_eq!(render("[$-409]0", 5.0), "5");
+        assert_eq!(render("0_);(0)", 5.0), "5 ");
+        assert_eq!(render("* #,##0", 12.0), "12");
+        assert_eq!(render("0;-0;\"-\"", 0.0), "-");
+        assert_eq!(render("\"Total: \"General", 3.5), "Total: 3.5");
+    }
+
+    #[test]
+    fn test_fractions() {
+        assert_eq!(render("# ?/?", 1.5), "1 1/2");
+        assert_eq!(render("# ??/??", 1.2345), "1 19/81");
+        assert_eq!(render("?/?", 0.75), "3/4");
+        assert_eq!(render("# ?/8", 2.3), "2 2/8");
+        assert_eq!(render("# ?/?", 2.0), "2");
+    }
+
+    #[test]
+    fn test_dates_and_times() {
+        // 2024-03-05 14:07:09
+        let serial = 45356.0 + (14.0 * 3600.0 + 7.0 * 60.0 + 9.0) / 86400.0;
+        assert_eq!(render("yyyy-mm-dd", serial), "2024-03-05");
+        assert_eq!(render("mm/dd/yy", serial), "03/05/24");
+        assert_eq!(render("d-mmm-yy", serial), "5-Mar-24");
+        assert_eq!(
+            render("dddd, mmmm d, yyyy", serial),
+            "Tuesday, March 5, 2024"
+        );
+        assert_eq!(render("ddd mmmmm", serial), "Tue M");
+        assert_eq!(render("h:mm:ss", serial), "14:07:09");
+        assert_eq!(render("h:mm AM/PM", serial), "2:07 PM");
+        assert_eq!(render("hh:mm a/p", 0.25), "06:00 a");
+        assert_eq!(render("mm:ss", serial), "07:09");
+        assert_eq!(render("[$-409]m/d/yy h:mm", serial), "3/5/24 14:07");
+        assert_eq!(render("yyyy\\-mm\\-dd", serial), "2024-03-05");
+    }
+
+    #[test]
+    fn test_elapsed_and_sub_second_times() {
+        assert_eq!(render("[h]:mm:ss", 1.5), "36:00:00");
+        assert_eq!(render("[mm]:ss", 0.5 / 24.0), "30:00");
+        assert_eq!(render("[ss]", 1.0 / 1440.0), "60");
+        assert_eq!(render("ss.00", 1.25 / 86400.0), "01.25");
+        assert_eq!(render("h:mm:ss", 0.999_999_9), "0:00:00");
+        assert_eq!(render("yyyy-mm-dd", -1.0), OVERFLOW);
+        assert_eq!(render("yyyy-mm-dd", 1e20), OVERFLOW);
+        assert_eq!(render("[h]:mm:ss", 1e20), OVERFLOW);
+        assert_eq!(render("[$-409]mmmm d, yyyy", 1e20), OVERFLOW);
+    }
+
+    #[test]
+    fn test_date_systems() {
+        let format = NumberFormat::parse("yyyy-mm-dd").unwrap();
+        assert_eq!(
+            format.format_number(0.0, DateSystem::Excel1904),
+            "1904-01-01"
+        );
+        assert_eq!(
+            format.format_number(60.0, DateSystem::Excel1900),
+            "1900-02-29"
+        );
+    }
+
+    #[test]
+    fn test_classification() {
+        let date = NumberFormat::parse("[$-409]mmmm d, yyyy;@").unwrap();
+        assert!(date.is_date() && date.is_date_time());
+        assert_eq!(date.format_text("n/a"), "n/a");
+
+        let time = NumberFormat::parse("h:mm").unwrap();
+        assert!(time.is_date_time() && !time.is_date());
+
+        for code in ["General", "0.00", "#,##0 \"m\"", "[Red]0", "0.00E+00"] {
+            let format = NumberFormat::parse(code).unwrap();
+            assert!(!format.is_date_time(), "{}", code);
+        }
+        assert!(NumberFormat::parse("@").unwrap().is_text());
+        assert_eq!(
+            NumberFormat::parse("0.000").unwrap().sections()[0].decimal_places(),
+            3
+        );
+        assert_eq!(
+            NumberFormat::parse("ss.00").unwrap().sections()[0].decimal_places(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_format_value() {
+        let format = NumberFormat::parse("0.0").unwrap();
+        let sys = DateSystem::Excel1900;
+        assert_eq!(format.format_value(&CellValue::Number(1.25), sys), "1.3");
+        assert_eq!(format.format_value(&CellValue::Boolean(true), sys), "TRUE");
+        assert_eq!(
+            format.format_value(&CellValue::Text("x".into(), None), sys),
+            "x"
+        );
+        assert_eq!(format.format_value(&CellValue::Empty, sys), "");
+        assert_eq!(
+            format.format_value(&CellValue::formula_with_error("1/0", "#DIV/0!"), sys),
+            "#DIV/0!"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(NumberFormat::parse("\"unterminated").is_err());
+        assert!(NumberFormat::parse("[Red0").is_err());
+        assert!(NumberFormat::parse("[>abc]0").is_err());
+    }
+}
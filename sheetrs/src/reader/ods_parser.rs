@@ -3,135 +3,183 @@
 use anyhow::Result;
 use quick_xml::Reader;
 use quick_xml::events::Event;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::BufReader;
 use zip::ZipArchive;
 
-use super::{Cell, CellValue, Sheet, WorkbookReader};
+use super::active_content::{ActiveContent, ActiveContentKind};
+use super::conditional_format::{ConditionalFormat, ConditionalFormatKind};
+use super::drawings::{Chart, ChartSeries, Image};
+use super::external_links::{ExternalSheet, ExternalWorkbook};
+use super::filter::{
+    AutoFilter, FilterColumn, FilterCondition, FilterCriteria, FilterOperator, SortKey,
+};
+use super::layout::{POINTS_PER_CHARACTER, SheetView, Span, push_span};
+use super::macros::MacroModule;
+use super::openformula;
+use super::page_setup::{
+    HeaderFooter, HeaderFooterKind, HeaderFooterPages, Margins, Orientation, PageSetup,
+};
+use super::parser_utils::parse_cell_ref;
+use super::pivot::{PivotSource, PivotTable};
+use super::properties::{DocumentProperties, parse_timestamp, set_text};
+use super::protection::{PasswordHash, SheetProtection, WorkbookProtection};
+use super::rich_text::RichText;
+use super::styles::{Border, BorderLine, CellStyle, Font, parse_hex_color};
+use super::tables::{Table, format_range};
+use super::validation::{ValidationErrorStyle, ValidationKind, ValidationOperator};
+use super::{
+    ArrayFormula, Cell, CellStore, CellValue, Comment, DataValidation, DateSystem, DateTime,
+    DefinedName, Hyperlink, RowVisitor, Sheet, Workbook, WorkbookReader,
+};
+
+/// Handler fed every event of one pass over content.xml, so that several
+/// extractors can share the pass
+trait OdsContentScan {
+    fn event(&mut self, event: &Event<'_>) -> Result<()>;
+}
 
-pub fn extract_hidden_sheets_from_ods(
+/// Run `scans` over content.xml in a single pass, ending with `Event::Eof`.
+/// Without content.xml they see no events.
+fn scan_ods_content(
     archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
-) -> Result<Vec<String>> {
-    let mut hidden_sheets = Vec::new();
-    let mut sheet_styles = Vec::new(); // (sheet_name, style_name)
-    let mut hidden_styles = std::collections::HashSet::new();
+    scans: &mut [&mut dyn OdsContentScan],
+) -> Result<()> {
+    let content_xml = match archive.by_name("content.xml") {
+        Ok(file) => file,
+        Err(_) => return Ok(()),
+    };
 
-    {
-        let content_xml = match archive.by_name("content.xml") {
-            Ok(file) => file,
-            Err(_) => return Ok(hidden_sheets),
-        };
+    let mut reader = Reader::from_reader(BufReader::new(content_xml));
+    reader.config_mut().trim_text(true);
 
-        let buf_reader = BufReader::new(content_xml);
-        let mut reader = Reader::from_reader(buf_reader);
-        reader.config_mut().trim_text(true);
+    let mut buf = Vec::new();
+    loop {
+        let event = reader.read_event_into(&mut buf)?;
+        for scan in scans.iter_mut() {
+            scan.event(&event)?;
+        }
+        if matches!(event, Event::Eof) {
+            break;
+        }
+        buf.clear();
+    }
 
-        let mut buf = Vec::new();
-        loop {
-            match reader.read_event_into(&mut buf)? {
-                Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"style:style" => {
-                    let mut style_name = String::new();
-                    for attr in e.attributes().flatten() {
-                        if attr.key.as_ref() == b"style:name" {
-                            style_name = attr.unescape_value()?.to_string();
-                        }
-                    }
+    Ok(())
+}
 
-                    if !style_name.is_empty() {
-                        // If it was a Start event, now look for style:table-properties
-                        let mut inner_buf = Vec::new();
-                        loop {
-                            match reader.read_event_into(&mut inner_buf)? {
-                                Event::Start(ee) | Event::Empty(ee)
-                                    if ee.name().as_ref() == b"style:table-properties" =>
-                                {
-                                    for attr in ee.attributes().flatten() {
-                                        if attr.key.as_ref() == b"table:display"
-                                            && attr.value.as_ref() == b"false"
-                                        {
-                                            hidden_styles.insert(style_name.clone());
-                                        }
-                                    }
-                                }
-                                Event::End(ee) if ee.name().as_ref() == b"style:style" => break,
-                                Event::Eof => break,
-                                _ => {}
-                            }
-                            inner_buf.clear();
-                        }
-                    }
+pub fn extract_hidden_sheets_from_ods(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<Vec<String>> {
+    let mut names = OdsSheetNames::default();
+    let mut hidden = OdsHiddenSheets::default();
+    scan_ods_content(archive, &mut [&mut names, &mut hidden])?;
+    hidden.finish(archive, &names.all)
+}
+
+/// Tables whose style sets `table:display="false"`
+#[derive(Default)]
+struct OdsHiddenSheets {
+    /// Sheet name and style name of each table
+    sheet_styles: Vec<(String, String)>,
+    hidden_styles: HashSet<String>,
+    /// Name of the `style:style` being read
+    style: Option<String>,
+}
+
+impl OdsContentScan for OdsHiddenSheets {
+    fn event(&mut self, event: &Event<'_>) -> Result<()> {
+        match event {
+            Event::Start(e) if e.name().as_ref() == b"style:style" => {
+                self.style = ods_attr(e, b"style:name")?.filter(|name| !name.is_empty());
+            }
+            Event::End(e) if e.name().as_ref() == b"style:style" => self.style = None,
+            Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"style:table-properties" => {
+                let hidden = e.attributes().flatten().any(|attr| {
+                    attr.key.as_ref() == b"table:display" && attr.value.as_ref() == b"false"
+                });
+                if let Some(style) = &self.style
+                    && hidden
+                {
+                    self.hidden_styles.insert(style.clone());
                 }
-                Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"table:table" => {
-                    let mut sheet_name = String::new();
-                    let mut style_name = String::new();
-                    for attr in e.attributes().flatten() {
-                        if attr.key.as_ref() == b"table:name" {
-                            sheet_name = attr.unescape_value()?.to_string();
-                        } else if attr.key.as_ref() == b"table:style-name" {
-                            style_name = attr.unescape_value()?.to_string();
-                        }
-                    }
-                    if !sheet_name.is_empty() && !style_name.is_empty() {
-                        sheet_styles.push((sheet_name, style_name));
-                    }
+            }
+            Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"table:table" => {
+                let name = ods_attr(e, b"table:name")?.unwrap_or_default();
+                let style = ods_attr(e, b"table:style-name")?.unwrap_or_default();
+                if !name.is_empty() && !style.is_empty() {
+                    self.sheet_styles.push((name, style));
                 }
-                Event::Eof => break,
-                _ => {}
             }
-            buf.clear();
-        }
-    }
-
-    for (name, style) in sheet_styles {
-        if hidden_styles.contains(&style) {
-            hidden_sheets.push(name);
+            _ => {}
         }
+        Ok(())
     }
+}
 
-    // Fallback: use settings.xml if no hidden sheets found or to supplement
-    if hidden_sheets.is_empty() {
-        let all_sheets = extract_all_sheet_names_from_ods(archive)?;
-        let visible_sheets = extract_visible_sheets_from_settings(archive)?;
-        for sheet in all_sheets {
-            if !visible_sheets.contains(&sheet) && !hidden_sheets.contains(&sheet) {
-                hidden_sheets.push(sheet);
+impl OdsHiddenSheets {
+    /// Names of the hidden sheets, falling back to those of `all_sheets` that
+    /// settings.xml doesn't list when no table style hides a sheet
+    fn finish(
+        self,
+        archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+        all_sheets: &[String],
+    ) -> Result<Vec<String>> {
+        let Self {
+            sheet_styles,
+            hidden_styles,
+            ..
+        } = self;
+        let mut hidden_sheets: Vec<String> = sheet_styles
+            .into_iter()
+            .filter(|(_, style)| hidden_styles.contains(style))
+            .map(|(name, _)| name)
+            .collect();
+
+        if hidden_sheets.is_empty() {
+            let visible_sheets = extract_visible_sheets_from_settings(archive)?;
+            for sheet in all_sheets {
+                if !visible_sheets.contains(sheet) && !hidden_sheets.contains(sheet) {
+                    hidden_sheets.push(sheet.clone());
+                }
             }
         }
-    }
 
-    Ok(hidden_sheets)
+        Ok(hidden_sheets)
+    }
 }
 
-fn extract_all_sheet_names_from_ods(
+/// Read the date system from the `table:null-date` calculation setting in content.xml.
+/// The ODS default null date, 1899-12-30, gives serials matching the 1900 system.
+pub fn extract_date_system_from_ods(
     archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
-) -> Result<Vec<String>> {
-    let mut sheet_names = Vec::new();
-
+) -> Result<DateSystem> {
     let content_xml = match archive.by_name("content.xml") {
         Ok(file) => file,
-        Err(_) => return Ok(sheet_names),
+        Err(_) => return Ok(DateSystem::default()),
     };
 
-    let buf_reader = BufReader::new(content_xml);
-    let mut reader = Reader::from_reader(buf_reader);
+    let mut reader = Reader::from_reader(BufReader::new(content_xml));
     reader.config_mut().trim_text(true);
 
     let mut buf = Vec::new();
-
     loop {
         match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
-                if e.name().as_ref() == b"table:table" {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => match e.name().as_ref() {
+                b"table:null-date" => {
                     for attr in e.attributes().flatten() {
-                        if attr.key.as_ref() == b"table:name" {
-                            let name = attr.unescape_value()?.to_string();
-                            if !name.is_empty() {
-                                sheet_names.push(name);
-                            }
+                        if attr.key.as_ref() == b"table:date-value"
+                            && attr.unescape_value()?.starts_with("1904-01-01")
+                        {
+                            return Ok(DateSystem::Excel1904);
                         }
                     }
+                    break;
                 }
-            }
+                // Calculation settings precede the first table
+                b"table:table" => break,
+                _ => {}
+            },
             Ok(Event::Eof) => break,
             Err(e) => return Err(anyhow::anyhow!("XML parsing error: {}", e)),
             _ => {}
@@ -139,7 +187,49 @@ fn extract_all_sheet_names_from_ods(
         buf.clear();
     }
 
-    Ok(sheet_names)
+    Ok(DateSystem::Excel1900)
+}
+
+/// Sheet names in document order
+#[derive(Default)]
+struct OdsSheetNames {
+    /// Names excluding sheets linked from external files (tables carrying a
+    /// `table:table-source`), matching what `read_sheets` returns
+    local: Vec<String>,
+    all: Vec<String>,
+    /// Name of the table being read, until a source shows it is linked
+    current: Option<String>,
+    /// Depth of the rows being read, which never contain sheet names
+    rows: u32,
+}
+
+impl OdsContentScan for OdsSheetNames {
+    fn event(&mut self, event: &Event<'_>) -> Result<()> {
+        match event {
+            Event::Start(e) if e.name().as_ref() == b"table:table-row" => self.rows += 1,
+            Event::End(e) if e.name().as_ref() == b"table:table-row" => {
+                self.rows = self.rows.saturating_sub(1);
+            }
+            _ if self.rows > 0 => {}
+            Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"table:table" => {
+                self.local.extend(self.current.take());
+                self.current = ods_attr(e, b"table:name")?;
+                self.all
+                    .extend(self.current.clone().filter(|name| !name.is_empty()));
+            }
+            Event::Start(e) | Event::Empty(e)
+                if e.name().as_ref() == b"table:table-source"
+                    && e.attributes()
+                        .flatten()
+                        .any(|attr| attr.key.as_ref() == b"xlink:href") =>
+            {
+                self.current = None;
+            }
+            Event::Eof => self.local.extend(self.current.take()),
+            _ => {}
+        }
+        Ok(())
+    }
 }
 
 /// Extract visible sheet names from settings.xml Tables section
@@ -203,564 +293,502 @@ fn extract_visible_sheets_from_settings(
     Ok(visible_sheets)
 }
 
-/// Extract hidden columns and rows from an ODS worksheet
-/// ODS format:
-/// - Hidden columns: <table:table-column table:visibility="collapse" or "filter">
-/// - Hidden rows: <table:table-row table:visibility="collapse" or "filter">
-pub fn extract_hidden_columns_rows_from_ods(
+/// Column widths (in characters) and custom row heights (in points) of the
+/// `table-column` and `table-row` automatic styles in content.xml. Rows whose
+/// height LibreOffice fits to the content (`use-optimal-row-height`) are left out.
+fn extract_row_column_sizes_from_ods(
     archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
-    sheet_name: &str,
-) -> Result<(Vec<u32>, Vec<u32>)> {
-    let mut hidden_columns = Vec::new();
-    let mut hidden_rows = Vec::new();
-
-    // ODS stores all sheets in content.xml
+) -> Result<(HashMap<String, f64>, HashMap<String, f64>)> {
+    let mut column_widths = HashMap::new();
+    let mut row_heights = HashMap::new();
     let content_xml = match archive.by_name("content.xml") {
         Ok(file) => file,
-        Err(_) => return Ok((hidden_columns, hidden_rows)),
+        Err(_) => return Ok((column_widths, row_heights)),
     };
-
-    let buf_reader = BufReader::new(content_xml);
-    let mut reader = Reader::from_reader(buf_reader);
+    let mut reader = Reader::from_reader(BufReader::new(content_xml));
     reader.config_mut().trim_text(true);
 
     let mut buf = Vec::new();
-    let mut in_target_sheet = false;
-    let mut current_col = 0u32;
-    let mut current_row = 0u32;
-
+    let mut style_name = String::new();
     loop {
-        match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
-                match e.name().as_ref() {
-                    b"table:table" => {
-                        let mut name = String::new();
-                        for attr in e.attributes().flatten() {
-                            if attr.key.as_ref() == b"table:name" {
-                                name = attr.unescape_value()?.to_string();
-                            }
-                        }
-
-                        if name == sheet_name {
-                            in_target_sheet = true;
-                            current_col = 0;
-                            current_row = 0;
-                        } else if in_target_sheet {
-                            // We were in the target sheet, but now we encountered another table tag.
-                            // In ODS, tables are top-level and don't nest.
-                            break;
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) | Event::Empty(e) => match e.name().as_ref() {
+                b"style:style" => {
+                    style_name.clear();
+                    for attr in e.attributes().flatten() {
+                        if attr.key.as_ref() == b"style:name" {
+                            style_name = attr.unescape_value()?.to_string();
                         }
                     }
-                    b"table:table-column" if in_target_sheet => {
-                        let mut visibility = String::from("visible");
-                        let mut repeated = 1u32;
-
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"table:visibility" => {
-                                    visibility = attr.unescape_value()?.to_string();
-                                }
-                                b"table:number-columns-repeated" => {
-                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
-                                        repeated = val;
-                                    }
-                                }
-                                _ => {}
-                            }
-                        }
-
-                        // If visibility is "collapse" or "filter", the column is hidden
-                        if visibility == "collapse" || visibility == "filter" {
-                            for _ in 0..repeated {
-                                hidden_columns.push(current_col);
-                                current_col += 1;
-                            }
-                        } else {
-                            current_col += repeated;
+                }
+                b"style:table-column-properties" => {
+                    for attr in e.attributes().flatten() {
+                        if attr.key.as_ref() == b"style:column-width"
+                            && let Some(points) = ods_length_in_points(&attr.unescape_value()?)
+                        {
+                            column_widths.insert(style_name.clone(), points / POINTS_PER_CHARACTER);
                         }
                     }
-                    b"table:table-row" if in_target_sheet => {
-                        let mut visibility = String::from("visible");
-                        let mut repeated = 1u32;
-
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"table:visibility" => {
-                                    visibility = attr.unescape_value()?.to_string();
-                                }
-                                b"table:number-rows-repeated" => {
-                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
-                                        repeated = val;
-                                    }
-                                }
-                                _ => {}
+                }
+                b"style:table-row-properties" => {
+                    let mut height = None;
+                    let mut optimal = false;
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"style:row-height" => {
+                                height = ods_length_in_points(&attr.unescape_value()?);
                             }
-                        }
-
-                        // If visibility is "collapse" or "filter", the row is hidden
-                        if visibility == "collapse" || visibility == "filter" {
-                            for _ in 0..repeated {
-                                hidden_rows.push(current_row);
-                                current_row += 1;
+                            b"style:use-optimal-row-height" => {
+                                optimal = attr.value.as_ref() == b"true";
                             }
-                        } else {
-                            current_row += repeated;
+                            _ => {}
                         }
                     }
-                    _ => {}
-                }
-            }
-            Ok(Event::End(e)) => {
-                if e.name().as_ref() == b"table:table" && in_target_sheet {
-                    break; // Found our sheet, done
+                    if let Some(height) = height.filter(|_| !optimal) {
+                        row_heights.insert(style_name.clone(), height);
+                    }
                 }
-            }
-            Ok(Event::Eof) => break,
-            Err(e) => return Err(anyhow::anyhow!("XML parsing error: {}", e)),
+                // Automatic styles come before the body
+                b"office:body" => break,
+                _ => {}
+            },
+            Event::Eof => break,
             _ => {}
         }
         buf.clear();
     }
-
-    Ok((hidden_columns, hidden_rows))
+    Ok((column_widths, row_heights))
 }
 
-/// Extract merged cell ranges from an ODS worksheet
-/// ODS format: <table:table-cell table:number-columns-spanned="X" table:number-rows-spanned="Y">
-pub fn extract_merged_cells_from_ods(
+/// Per-sheet view settings of the first view in settings.xml: frozen panes
+/// (split mode 2, with the split position counting rows or columns), zoom,
+/// gridlines and tab color, plus the `ActiveTable` shown on opening
+fn extract_sheet_views_from_ods(
     archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
-    sheet_name: &str,
-) -> Result<Vec<(u32, u32, u32, u32)>> {
-    let mut merged_cells = Vec::new();
+) -> Result<HashMap<String, SheetView>> {
+    #[derive(Default)]
+    struct TableSettings {
+        zoom: Option<u16>,
+        show_grid: Option<bool>,
+        tab_color: Option<String>,
+        /// Horizontal split mode and position, vertical split mode and position
+        split: [u32; 4],
+    }
 
-    // ODS stores all sheets in content.xml
-    let content_xml = match archive.by_name("content.xml") {
+    let mut views = HashMap::new();
+    let settings_xml = match archive.by_name("settings.xml") {
         Ok(file) => file,
-        Err(_) => return Ok(merged_cells),
+        Err(_) => return Ok(views),
     };
-
-    let buf_reader = BufReader::new(content_xml);
-    let mut reader = Reader::from_reader(buf_reader);
+    let mut reader = Reader::from_reader(BufReader::new(settings_xml));
     reader.config_mut().trim_text(true);
 
     let mut buf = Vec::new();
-    let mut in_target_sheet = false;
-    let mut current_row = 0u32;
-    let mut current_col = 0u32;
-    let mut current_row_repeated = 1u32;
-
+    let mut tables: BTreeMap<String, TableSettings> = BTreeMap::new();
+    let mut active_table = None;
+    let mut view_show_grid = None;
+    // Depth inside the `Views` map; the first view is its first entry
+    let mut views_depth = 0;
+    let mut first_view_done = false;
+    let mut in_tables = false;
+    let mut table: Option<String> = None;
+    let mut item: Option<String> = None;
     loop {
-        match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) => {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => {
+                let mut name = None;
+                for attr in e.attributes().flatten() {
+                    if attr.key.as_ref() == b"config:name" {
+                        name = Some(attr.unescape_value()?.to_string());
+                    }
+                }
                 match e.name().as_ref() {
-                    b"table:table" => {
-                        let mut name = String::new();
-                        for attr in e.attributes().flatten() {
-                            if attr.key.as_ref() == b"table:name" {
-                                name = attr.unescape_value()?.to_string();
-                            }
-                        }
-
-                        if name == sheet_name {
-                            in_target_sheet = true;
-                            current_row = 0;
-                            current_col = 0;
-                        } else if in_target_sheet {
-                            break;
+                    b"config:config-item-map-indexed" if name.as_deref() == Some("Views") => {
+                        views_depth = 1;
+                    }
+                    b"config:config-item-map-named" if views_depth > 0 => {
+                        views_depth += 1;
+                        in_tables = name.as_deref() == Some("Tables");
+                    }
+                    b"config:config-item-map-entry" if views_depth > 0 => {
+                        views_depth += 1;
+                        if in_tables {
+                            table = name;
                         }
                     }
-                    b"table:table-row" if in_target_sheet => {
-                        current_col = 0; // Reset column for new row
-                        // Tracking row repetition for spanning logic
-                        current_row_repeated = 1;
-                        for attr in e.attributes().flatten() {
-                            if attr.key.as_ref() == b"table:number-rows-repeated"
-                                && let Ok(val) = attr.unescape_value()?.parse::<u32>()
-                            {
-                                current_row_repeated = val;
-                            }
-                        }
-                    }
-                    b"table:table-cell" if in_target_sheet => {
-                        let mut cols_spanned = 1u32;
-                        let mut rows_spanned = 1u32;
-                        let mut repeated = 1u32;
-
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"table:number-columns-spanned" => {
-                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
-                                        cols_spanned = val;
-                                    }
-                                }
-                                b"table:number-rows-spanned" => {
-                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
-                                        rows_spanned = val;
-                                    }
-                                }
-                                b"table:number-columns-repeated" => {
-                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
-                                        repeated = val;
-                                    }
-                                }
-                                _ => {}
-                            }
-                        }
-
-                        // If either span is > 1, this is a merged cell
-                        if cols_spanned > 1 || rows_spanned > 1 {
-                            merged_cells.push((
-                                current_row,
-                                current_col,
-                                current_row + rows_spanned - 1,
-                                current_col + cols_spanned - 1,
-                            ));
-                        }
-
-                        current_col += repeated;
-                    }
-                    b"table:covered-table-cell" if in_target_sheet => {
-                        // Covered cells are part of a merged range and should increment the column counter
-                        let mut repeated = 1u32;
-                        for attr in e.attributes() {
-                            if let Ok(attr) = attr
-                                && attr.key.as_ref() == b"table:number-columns-repeated"
-                                && let Ok(val) = attr.unescape_value()?.parse::<u32>()
-                            {
-                                repeated = val;
-                            }
-                        }
-                        current_col += repeated;
-                    }
+                    b"config:config-item" if views_depth > 0 && !first_view_done => item = name,
                     _ => {}
                 }
             }
-            Ok(Event::Empty(e)) => {
-                match e.name().as_ref() {
-                    b"table:table-row" if in_target_sheet => {
-                        // Empty row - increment row counter
-                        let mut repeated = 1u32;
-                        for attr in e.attributes().flatten() {
-                            if attr.key.as_ref() == b"table:number-rows-repeated"
-                                && let Ok(val) = attr.unescape_value()?.parse::<u32>()
-                            {
-                                repeated = val;
+            Event::Text(e) => {
+                let Some(item) = item.as_deref() else {
+                    buf.clear();
+                    continue;
+                };
+                let value = e.unescape()?;
+                let number = value.parse::<i64>().ok();
+                match &table {
+                    Some(table) => {
+                        let settings = tables.entry(table.clone()).or_default();
+                        match item {
+                            "HorizontalSplitMode" => settings.split[0] = number.unwrap_or(0) as u32,
+                            "HorizontalSplitPosition" => {
+                                settings.split[1] = number.unwrap_or(0) as u32
                             }
-                        }
-                        current_row += repeated;
-                    }
-                    b"table:table-cell" if in_target_sheet => {
-                        let mut cols_spanned = 1u32;
-                        let mut rows_spanned = 1u32;
-                        let mut repeated = 1u32;
-
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"table:number-columns-spanned" => {
-                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
-                                        cols_spanned = val;
-                                    }
-                                }
-                                b"table:number-rows-spanned" => {
-                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
-                                        rows_spanned = val;
-                                    }
-                                }
-                                b"table:number-columns-repeated" => {
-                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
-                                        repeated = val;
-                                    }
-                                }
-                                _ => {}
+                            "VerticalSplitMode" => settings.split[2] = number.unwrap_or(0) as u32,
+                            "VerticalSplitPosition" => {
+                                settings.split[3] = number.unwrap_or(0) as u32
                             }
-                        }
-
-                        // If either span is > 1, this is a merged cell
-                        if cols_spanned > 1 || rows_spanned > 1 {
-                            merged_cells.push((
-                                current_row,
-                                current_col,
-                                current_row + rows_spanned - 1,
-                                current_col + cols_spanned - 1,
-                            ));
-                        }
-
-                        current_col += repeated;
-                    }
-                    b"table:covered-table-cell" if in_target_sheet => {
-                        // Covered cells are part of a merged range and should increment the column counter
-                        let mut repeated = 1u32;
-                        for attr in e.attributes() {
-                            if let Ok(attr) = attr
-                                && attr.key.as_ref() == b"table:number-columns-repeated"
-                                && let Ok(val) = attr.unescape_value()?.parse::<u32>()
-                            {
-                                repeated = val;
+                            "ZoomValue" => settings.zoom = number.map(|zoom| zoom as u16),
+                            "ShowGrid" => settings.show_grid = Some(value == "true"),
+                            "TabColor" => {
+                                settings.tab_color = number
+                                    .filter(|color| *color >= 0)
+                                    .map(|color| format!("{:06X}", color & 0xFF_FFFF))
                             }
+                            _ => {}
                         }
-                        current_col += repeated;
                     }
-                    _ => {}
+                    None => match item {
+                        "ActiveTable" => active_table = Some(value.to_string()),
+                        "ShowGrid" => view_show_grid = Some(value == "true"),
+                        _ => {}
+                    },
                 }
             }
-            Ok(Event::End(e)) if in_target_sheet => match e.name().as_ref() {
-                b"table:table-row" => {
-                    current_row += current_row_repeated;
+            Event::End(e) => match e.name().as_ref() {
+                b"config:config-item" => item = None,
+                b"config:config-item-map-named" if views_depth > 0 => {
+                    views_depth -= 1;
+                    in_tables = false;
                 }
-                b"table:table" => {
-                    break;
+                b"config:config-item-map-entry" if views_depth > 0 => {
+                    views_depth -= 1;
+                    if table.take().is_none() && views_depth == 1 {
+                        first_view_done = true;
+                    }
                 }
+                b"config:config-item-map-indexed" if views_depth == 1 => views_depth = 0,
                 _ => {}
             },
-            Ok(Event::Eof) => break,
-            Err(e) => return Err(anyhow::anyhow!("XML parsing error: {}", e)),
+            Event::Eof => break,
             _ => {}
         }
         buf.clear();
     }
 
-    Ok(merged_cells)
-}
-
-/// Check if ODS file contains macros
-/// ODS macros are stored in Basic/ or Scripts/ directories,
-/// or declared in META-INF/manifest.xml
-pub fn has_macros(archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>) -> Result<bool> {
-    // 1. Check for directory presence
-    for i in 0..archive.len() {
-        if let Ok(file) = archive.by_index(i) {
-            let name = file.name();
-            if name.starts_with("Basic/") || name.starts_with("Scripts/") {
-                return Ok(true);
-            }
+    for (name, settings) in tables {
+        let [horizontal_mode, columns, vertical_mode, rows] = settings.split;
+        let mut view = SheetView {
+            zoom: settings.zoom.unwrap_or(100),
+            show_gridlines: settings.show_grid.or(view_show_grid).unwrap_or(true),
+            tab_color: settings.tab_color,
+            ..Default::default()
+        };
+        if horizontal_mode == 2 {
+            view.frozen_columns = columns;
         }
+        if vertical_mode == 2 {
+            view.frozen_rows = rows;
+        }
+        views.insert(name, view);
+    }
+    if let Some(active) = active_table {
+        let view = views.entry(active).or_default();
+        // Calc selects only the sheet it shows
+        view.active = true;
+        view.selected = true;
     }
+    Ok(views)
+}
 
-    // 2. Check manifest for macro-related media types
-    if let Ok(manifest_file) = archive.by_name("META-INF/manifest.xml") {
-        let buf_reader = BufReader::new(manifest_file);
-        let mut reader = Reader::from_reader(buf_reader);
-        let mut buf = Vec::new();
+/// Page setup of each table style in content.xml: the page layout and header
+/// and footer contents of the master page the style names, from styles.xml.
+/// Tables without a style use the `Default` master page, kept under "".
+fn extract_page_setups_from_ods(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<HashMap<String, PageSetup>> {
+    let mut setups = HashMap::new();
+    let mut layouts: HashMap<String, PageSetup> = HashMap::new();
+    let mut master_pages: HashMap<String, PageSetup> = HashMap::new();
+
+    if let Ok(styles_xml) = archive.by_name("styles.xml") {
+        // Untrimmed: spaces next to header fields matter
+        let mut reader = Reader::from_reader(BufReader::new(styles_xml));
 
+        let mut buf = Vec::new();
+        let mut layout_name = String::new();
         loop {
-            match reader.read_event_into(&mut buf)? {
-                Event::Start(e) | Event::Empty(e)
-                    if e.name().as_ref() == b"manifest:file-entry" =>
-                {
-                    for attr in e.attributes().flatten() {
-                        if attr.key.as_ref() == b"manifest:media-type" {
-                            let media_type = attr.unescape_value()?;
-                            if media_type.contains("application/vnd.sun.xml.ui.configuration")
-                                || media_type.contains("script")
-                            {
-                                // This is a bit broad, but Basic/ scripts often have specific media types
-                            }
-                        }
+            let (e, has_children) = match reader.read_event_into(&mut buf)? {
+                Event::Start(e) => (e.into_owned(), true),
+                Event::Empty(e) => (e.into_owned(), false),
+                Event::Eof => break,
+                _ => {
+                    buf.clear();
+                    continue;
+                }
+            };
+            match e.name().as_ref() {
+                b"style:page-layout" => {
+                    layout_name = ods_attr(&e, b"style:name")?.unwrap_or_default();
+                }
+                b"style:page-layout-properties" => {
+                    layouts.insert(layout_name.clone(), read_ods_page_layout(&e)?);
+                }
+                b"style:master-page" => {
+                    let name = ods_attr(&e, b"style:name")?.unwrap_or_default();
+                    let mut setup = ods_attr(&e, b"style:page-layout-name")?
+                        .and_then(|layout| layouts.get(&layout).cloned())
+                        .unwrap_or_default();
+                    if has_children {
+                        setup.headers_footers = read_ods_master_page(&mut reader)?;
                     }
+                    master_pages.insert(name, setup);
                 }
-                Event::Eof => break,
                 _ => {}
             }
             buf.clear();
         }
     }
-
-    Ok(false)
-}
-
-/// Extract external links from ODS metadata
-pub fn extract_external_links_ods(
-    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
-) -> Result<Vec<String>> {
-    let mut links = std::collections::HashSet::new();
+    if master_pages.is_empty() {
+        return Ok(setups);
+    }
+    if let Some(default) = master_pages.get("Default") {
+        setups.insert(String::new(), default.clone());
+    }
 
     let content_xml = match archive.by_name("content.xml") {
         Ok(file) => file,
-        Err(_) => return Ok(Vec::new()),
+        Err(_) => return Ok(setups),
     };
-
-    let buf_reader = BufReader::new(content_xml);
-    let mut reader = Reader::from_reader(buf_reader);
+    let mut reader = Reader::from_reader(BufReader::new(content_xml));
     reader.config_mut().trim_text(true);
 
     let mut buf = Vec::new();
     loop {
         match reader.read_event_into(&mut buf)? {
-            Event::Start(ref e) if e.name().as_ref() == b"text:a" => {
-                for attr in e.attributes().flatten() {
-                    if attr.key.as_ref() == b"xlink:href" {
-                        let link = attr.unescape_value()?.to_string();
-                        if !link.starts_with("#") {
-                            links.insert(link);
-                        }
-                    }
-                }
-            }
-            Event::Start(ref e) | Event::Empty(ref e)
-                if e.name().as_ref() == b"table:table-source" =>
-            {
-                for attr in e.attributes().flatten() {
-                    if attr.key.as_ref() == b"xlink:href" {
-                        links.insert(attr.unescape_value()?.to_string());
+            Event::Start(e) | Event::Empty(e) => match e.name().as_ref() {
+                b"style:style" => {
+                    if let Some(name) = ods_attr(&e, b"style:name")?
+                        && let Some(setup) = ods_attr(&e, b"style:master-page-name")?
+                            .and_then(|master| master_pages.get(&master))
+                    {
+                        setups.insert(name, setup.clone());
                     }
                 }
+                // Automatic styles come before the body
+                b"office:body" => break,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(setups)
+}
+
+/// Value of an attribute, unescaped
+fn ods_attr(e: &quick_xml::events::BytesStart, key: &[u8]) -> Result<Option<String>> {
+    for attr in e.attributes().flatten() {
+        if attr.key.as_ref() == key {
+            return Ok(Some(attr.unescape_value()?.to_string()));
+        }
+    }
+    Ok(None)
+}
+
+/// Read `style:page-layout-properties`: margins, orientation and scaling.
+/// ODS prints the header inside the top margin, so the header and footer
+/// margins are the page's top and bottom margins.
+fn read_ods_page_layout(e: &quick_xml::events::BytesStart) -> Result<PageSetup> {
+    let mut setup = PageSetup::default();
+    let mut margins = [None; 4];
+    let mut fit = (None, None);
+    for attr in e.attributes().flatten() {
+        let value = attr.unescape_value()?;
+        let inches = || ods_length_in_points(&value).map(|points| points / 72.0);
+        match attr.key.as_ref() {
+            b"fo:margin" => margins = [inches(); 4],
+            b"fo:margin-left" => margins[0] = inches(),
+            b"fo:margin-right" => margins[1] = inches(),
+            b"fo:margin-top" => margins[2] = inches(),
+            b"fo:margin-bottom" => margins[3] = inches(),
+            b"style:print-orientation" => {
+                setup.orientation = match value.as_ref() {
+                    "portrait" => Some(Orientation::Portrait),
+                    "landscape" => Some(Orientation::Landscape),
+                    _ => None,
+                };
             }
-            Event::Start(ref e) | Event::Empty(ref e)
-                if e.name().as_ref() == b"table:table-cell" =>
-            {
-                for attr in e.attributes().flatten() {
-                    if attr.key.as_ref() == b"table:formula" {
-                        let formula = attr.unescape_value()?;
-                        if formula.contains("'file:///")
-                            && let Some(start) = formula.find("'file:///")
-                        {
-                            let remainder = &formula[start + 1..];
-                            if let Some(end) = remainder.find("'#") {
-                                let path = &remainder[..end];
-                                links.insert(path.to_string());
-                            }
-                        }
+            b"style:scale-to" => setup.scale = value.trim_end_matches('%').parse().ok(),
+            // Written as style: (ODF 1.3) or loext: (older LibreOffice)
+            _ if attr.key.local_name().as_ref() == b"scale-to-X" => fit.0 = value.parse().ok(),
+            _ if attr.key.local_name().as_ref() == b"scale-to-Y" => fit.1 = value.parse().ok(),
+            _ => {}
+        }
+    }
+    if margins.iter().any(Option::is_some) {
+        let [left, right, top, bottom] = margins.map(|margin| margin.unwrap_or(0.0));
+        setup.margins = Some(Margins {
+            left,
+            right,
+            top,
+            bottom,
+            header: top,
+            footer: bottom,
+        });
+    }
+    if fit.0.is_some() || fit.1.is_some() {
+        setup.fit_to_pages = Some((fit.0.unwrap_or(0), fit.1.unwrap_or(0)));
+        setup.scale = None;
+    }
+    Ok(setup)
+}
+
+/// Read the headers and footers of a `style:master-page` up to its end,
+/// skipping those turned off with `style:display="false"`
+fn read_ods_master_page<R: std::io::BufRead>(reader: &mut Reader<R>) -> Result<Vec<HeaderFooter>> {
+    let mut headers_footers = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => {
+                let (kind, pages) = match e.name().as_ref() {
+                    b"style:header" => (HeaderFooterKind::Header, HeaderFooterPages::All),
+                    b"style:footer" => (HeaderFooterKind::Footer, HeaderFooterPages::All),
+                    b"style:header-left" => (HeaderFooterKind::Header, HeaderFooterPages::Even),
+                    b"style:footer-left" => (HeaderFooterKind::Footer, HeaderFooterPages::Even),
+                    b"style:header-first" => (HeaderFooterKind::Header, HeaderFooterPages::First),
+                    b"style:footer-first" => (HeaderFooterKind::Footer, HeaderFooterPages::First),
+                    _ => {
+                        buf.clear();
+                        continue;
                     }
+                };
+                let shown =
+                    ods_attr(&e, b"style:display")?.is_none_or(|display| display != "false");
+                let text = read_ods_header_footer_text(reader)?;
+                if shown && !text.is_empty() {
+                    headers_footers.push(HeaderFooter { kind, pages, text });
                 }
             }
+            Event::End(e) if e.name().as_ref() == b"style:master-page" => break,
             Event::Eof => break,
             _ => {}
         }
         buf.clear();
     }
+    Ok(headers_footers)
+}
 
-    // Strip file:// prefix from paths for cleaner display
-    let cleaned_links: Vec<String> = links
-        .into_iter()
-        .map(|link| {
-            if link.starts_with("file://") {
-                link.trim_start_matches("file://").to_string()
-            } else {
-                link
-            }
+/// Read a header or footer up to its end, writing it in Excel's code syntax:
+/// the left, center and right regions become `&L`, `&C` and `&R` sections,
+/// and fields their codes (`&A` sheet name, `&P` page, `&N` page count, `&D`
+/// date, `&T` time, `&F` file name, `&Z` folder) in place of their cached text
+fn read_ods_header_footer_text<R: std::io::BufRead>(reader: &mut Reader<R>) -> Result<String> {
+    fn field_code(e: &quick_xml::events::BytesStart) -> Result<Option<&'static str>> {
+        Ok(match e.name().as_ref() {
+            b"text:sheet-name" => Some("&A"),
+            b"text:page-number" => Some("&P"),
+            b"text:page-count" => Some("&N"),
+            b"text:date" => Some("&D"),
+            b"text:time" => Some("&T"),
+            // Shows the full path unless told otherwise
+            b"text:file-name" => Some(match ods_attr(e, b"text:display")?.as_deref() {
+                Some("name") | Some("name-and-extension") => "&F",
+                Some("path") => "&Z",
+                _ => "&Z&F",
+            }),
+            _ => None,
         })
-        .collect();
-
-    Ok(cleaned_links)
-}
-
-/// Extract external workbooks with indices from ODS file
-/// Returns a vector of ExternalWorkbook where index is 0-based (order of appearance)
-/// Only extracts from metadata (table:table-source), not from formulas
-pub fn extract_external_workbooks_ods(
-    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
-) -> Result<Vec<super::ExternalWorkbook>> {
-    use super::ExternalWorkbook;
-    use std::collections::HashMap;
-
-    let mut workbooks = Vec::new();
-    let mut path_to_index: HashMap<String, usize> = HashMap::new();
-    let mut next_index = 0;
-
-    let content_xml = match archive.by_name("content.xml") {
-        Ok(file) => file,
-        Err(_) => return Ok(workbooks),
-    };
-
-    let buf_reader = BufReader::new(content_xml);
-    let mut reader = Reader::from_reader(buf_reader);
-    reader.config_mut().trim_text(true);
+    }
 
+    let mut text = String::new();
+    let mut depth = 1;
+    // Depth of the paragraph being read; text outside paragraphs is layout
+    let mut paragraph_depth = None;
+    // Depth of the field being read, whose cached text is left out
+    let mut field_depth = None;
+    let mut paragraphs = 0;
     let mut buf = Vec::new();
     loop {
         match reader.read_event_into(&mut buf)? {
-            // Check table:table-source elements for external workbook references
-            Event::Start(ref e) | Event::Empty(ref e)
-                if e.name().as_ref() == b"table:table-source" =>
-            {
-                for attr in e.attributes().flatten() {
-                    if attr.key.as_ref() == b"xlink:href" {
-                        let path = attr.unescape_value()?.to_string();
-                        let basename = super::parser_utils::extract_basename(&path);
-
-                        // Only add if we haven't seen this basename before
-                        if !path_to_index.contains_key(&basename) {
-                            path_to_index.insert(basename.clone(), next_index);
-                            workbooks.push(ExternalWorkbook {
-                                index: next_index,
-                                path: basename,
-                            });
-                            next_index += 1;
+            Event::Start(e) => {
+                depth += 1;
+                if field_depth.is_some() {
+                    buf.clear();
+                    continue;
+                }
+                match e.name().as_ref() {
+                    b"style:region-left" => text.push_str("&L"),
+                    b"style:region-center" => text.push_str("&C"),
+                    b"style:region-right" => text.push_str("&R"),
+                    b"text:p" => {
+                        if paragraphs > 0 {
+                            text.push('\n');
+                        }
+                        paragraphs += 1;
+                        paragraph_depth = Some(depth);
+                    }
+                    _ => {
+                        if let Some(code) = field_code(&e)? {
+                            text.push_str(code);
+                            field_depth = Some(depth);
                         }
                     }
                 }
+                if e.name().as_ref().starts_with(b"style:region-") {
+                    paragraphs = 0;
+                }
+            }
+            Event::Empty(e) if field_depth.is_none() => match e.name().as_ref() {
+                b"text:s" => {
+                    let count = ods_attr(&e, b"text:c")?.and_then(|c| c.parse().ok());
+                    text.push_str(&" ".repeat(count.unwrap_or(1)));
+                }
+                b"text:tab" => text.push('\t'),
+                b"text:line-break" => text.push('\n'),
+                _ => text.extend(field_code(&e)?),
+            },
+            Event::Text(t) if paragraph_depth.is_some() && field_depth.is_none() => {
+                text.push_str(&t.unescape()?.replace('&', "&&"));
+            }
+            Event::End(_) => {
+                if field_depth == Some(depth) {
+                    field_depth = None;
+                }
+                if paragraph_depth == Some(depth) {
+                    paragraph_depth = None;
+                }
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
             }
             Event::Eof => break,
             _ => {}
         }
         buf.clear();
     }
-
-    Ok(workbooks)
-}
-
-/// Normalize ODS external workbook references to XLSX index format
-/// Converts ['file:///path/to/file.xlsx'#Sheet1.A1] -> [1]Sheet1!A1
-///
-/// # Arguments
-/// * `formula` - The ODS formula string
-/// * `external_workbooks` - List of external workbooks with their indices
-///
-/// # Returns
-/// Normalized formula with XLSX-style external references
-pub fn normalize_ods_external_refs(
-    formula: &str,
-    external_workbooks: &[super::ExternalWorkbook],
-) -> String {
-    let mut result = formula.to_string();
-
-    // Build mapping from path to index
-    for wb in external_workbooks {
-        // ODS patterns to match (wb.path is basename):
-        // ['file:///absolute/path/basename.xlsx'#Sheet.Cell]
-        // ['../relative/path/basename.xlsx'#Sheet.Cell]
-        // We need to match any path ending with the basename
-
-        // XLSX format: [index] where index is 1-based
-        let xlsx_ref = format!("[{}]", wb.index + 1);
-
-        // Use regex to match paths ending with the basename
-        use regex::Regex;
-        let pattern_str = format!(r"\['[^']*{}'#", regex::escape(&wb.path));
-        if let Ok(re) = Regex::new(&pattern_str) {
-            result = re
-                .replace_all(&result, &format!("{}#", xlsx_ref))
-                .to_string();
-        }
-    }
-
-    // Convert ODS cell reference separator (.) to XLSX (!)
-    // Pattern: after ] and before cell reference
-    // Use regex to be more precise
-    use regex::Regex;
-    use std::sync::OnceLock;
-
-    static EXTERNAL_REF_PATTERN: OnceLock<Regex> = OnceLock::new();
-    let re = EXTERNAL_REF_PATTERN.get_or_init(|| Regex::new(r"\[(\d+)\]([^!]+)\.").unwrap());
-
-    result = re.replace_all(&result, "[$1]$2!").to_string();
-
-    result
+    Ok(text)
 }
 
-/// Extract cached error values from an ODS worksheet
-/// ODS error values are often stored in calcext:value-type="error" and calcext:value="#ERROR!"
-pub fn extract_cached_errors_from_ods(
+/// Extract hidden columns and rows from an ODS worksheet
+/// ODS format:
+/// - Hidden columns: <table:table-column table:visibility="collapse" or "filter">
+/// - Hidden rows: <table:table-row table:visibility="collapse" or "filter">
+pub fn extract_hidden_columns_rows_from_ods(
     archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
     sheet_name: &str,
-) -> Result<std::collections::HashMap<(u32, u32), String>> {
-    use std::collections::HashMap;
-    let mut errors = HashMap::new();
+) -> Result<(Vec<u32>, Vec<u32>)> {
+    let mut hidden_columns = Vec::new();
+    let mut hidden_rows = Vec::new();
 
     // ODS stores all sheets in content.xml
     let content_xml = match archive.by_name("content.xml") {
         Ok(file) => file,
-        Err(_) => return Ok(errors),
+        Err(_) => return Ok((hidden_columns, hidden_rows)),
     };
 
     let buf_reader = BufReader::new(content_xml);
@@ -769,147 +797,93 @@ pub fn extract_cached_errors_from_ods(
 
     let mut buf = Vec::new();
     let mut in_target_sheet = false;
-    let mut current_row = 0u32;
     let mut current_col = 0u32;
-    let mut current_row_repeated = 1u32;
+    let mut current_row = 0u32;
 
     loop {
         match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) => match e.name().as_ref() {
-                b"table:table" => {
-                    let mut name = String::new();
-                    for attr in e.attributes().flatten() {
-                        if attr.key.as_ref() == b"table:name" {
-                            name = attr.unescape_value()?.to_string();
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                match e.name().as_ref() {
+                    b"table:table" => {
+                        let mut name = String::new();
+                        for attr in e.attributes().flatten() {
+                            if attr.key.as_ref() == b"table:name" {
+                                name = attr.unescape_value()?.to_string();
+                            }
                         }
-                    }
 
-                    if name == sheet_name {
-                        in_target_sheet = true;
-                        current_row = 0;
-                        current_col = 0;
-                    } else if in_target_sheet {
-                        break;
-                    }
-                }
-                b"table:table-row" if in_target_sheet => {
-                    current_col = 0;
-                    current_row_repeated = 1;
-                    for attr in e.attributes() {
-                        if let Ok(attr) = attr
-                            && attr.key.as_ref() == b"table:number-rows-repeated"
-                            && let Ok(val) = attr.unescape_value()?.parse::<u32>()
-                        {
-                            current_row_repeated = val;
+                        if name == sheet_name {
+                            in_target_sheet = true;
+                            current_col = 0;
+                            current_row = 0;
+                        } else if in_target_sheet {
+                            // We were in the target sheet, but now we encountered another table tag.
+                            // In ODS, tables are top-level and don't nest.
+                            break;
                         }
                     }
-                }
-                b"table:table-cell" if in_target_sheet => {
-                    let mut repeated = 1u32;
-                    let mut is_error = false;
-                    let mut error_msg = String::new();
+                    b"table:table-column" if in_target_sheet => {
+                        let mut visibility = String::from("visible");
+                        let mut repeated = 1u32;
 
-                    for attr in e.attributes().flatten() {
-                        match attr.key.as_ref() {
-                            b"calcext:value-type" => {
-                                if attr.value.as_ref() == b"error" {
-                                    is_error = true;
-                                }
-                            }
-                            b"calcext:value" | b"office:string-value" => {
-                                if error_msg.is_empty() {
-                                    error_msg = attr.unescape_value()?.to_string();
+                        for attr in e.attributes().flatten() {
+                            match attr.key.as_ref() {
+                                b"table:visibility" => {
+                                    visibility = attr.unescape_value()?.to_string();
                                 }
-                            }
-                            b"table:number-columns-repeated" => {
-                                if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
-                                    repeated = val;
+                                b"table:number-columns-repeated" => {
+                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
+                                        repeated = val;
+                                    }
                                 }
+                                _ => {}
                             }
-                            _ => {}
                         }
-                    }
-
-                    if is_error && error_msg.is_empty() {
-                        error_msg = "#ERROR!".to_string();
-                    }
 
-                    if is_error && !error_msg.is_empty() {
-                        for i in 0..repeated {
-                            for r in 0..current_row_repeated {
-                                errors
-                                    .insert((current_row + r, current_col + i), error_msg.clone());
+                        // If visibility is "collapse" or "filter", the column is hidden
+                        if visibility == "collapse" || visibility == "filter" {
+                            for _ in 0..repeated {
+                                hidden_columns.push(current_col);
+                                current_col += 1;
                             }
+                        } else {
+                            current_col += repeated;
                         }
                     }
-                    current_col += repeated;
-                }
-                _ => {}
-            },
-            Ok(Event::Empty(e)) => match e.name().as_ref() {
-                b"table:table-row" if in_target_sheet => {
-                    let mut repeated = 1u32;
-                    for attr in e.attributes() {
-                        if let Ok(attr) = attr
-                            && attr.key.as_ref() == b"table:number-rows-repeated"
-                            && let Ok(val) = attr.unescape_value()?.parse::<u32>()
-                        {
-                            repeated = val;
-                        }
-                    }
-                    current_row += repeated;
-                }
-                b"table:table-cell" if in_target_sheet => {
-                    let mut repeated = 1u32;
-                    let mut is_error = false;
-                    let mut error_msg = String::new();
+                    b"table:table-row" if in_target_sheet => {
+                        let mut visibility = String::from("visible");
+                        let mut repeated = 1u32;
 
-                    for attr in e.attributes().flatten() {
-                        match attr.key.as_ref() {
-                            b"calcext:value-type" => {
-                                if attr.value.as_ref() == b"error" {
-                                    is_error = true;
-                                }
-                            }
-                            b"calcext:value" | b"office:string-value" => {
-                                if error_msg.is_empty() {
-                                    error_msg = attr.unescape_value()?.to_string();
+                        for attr in e.attributes().flatten() {
+                            match attr.key.as_ref() {
+                                b"table:visibility" => {
+                                    visibility = attr.unescape_value()?.to_string();
                                 }
-                            }
-                            b"table:number-columns-repeated" => {
-                                if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
-                                    repeated = val;
+                                b"table:number-rows-repeated" => {
+                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
+                                        repeated = val;
+                                    }
                                 }
+                                _ => {}
                             }
-                            _ => {}
                         }
-                    }
-
-                    if is_error && error_msg.is_empty() {
-                        error_msg = "#ERROR!".to_string();
-                    }
 
-                    if is_error && !error_msg.is_empty() {
-                        for i in 0..repeated {
-                            for r in 0..current_row_repeated {
-                                errors
-                                    .insert((current_row + r, current_col + i), error_msg.clone());
+                        // If visibility is "collapse" or "filter", the row is hidden
+                        if visibility == "collapse" || visibility == "filter" {
+                            for _ in 0..repeated {
+                                hidden_rows.push(current_row);
+                                current_row += 1;
                             }
+                        } else {
+                            current_row += repeated;
                         }
                     }
-                    current_col += repeated;
+                    _ => {}
                 }
-                _ => {}
-            },
+            }
             Ok(Event::End(e)) => {
-                match e.name().as_ref() {
-                    b"table:table-row" if in_target_sheet => {
-                        current_row += current_row_repeated;
-                    }
-                    b"table:table" if in_target_sheet => {
-                        break; // Found our sheet, done
-                    }
-                    _ => {}
+                if e.name().as_ref() == b"table:table" && in_target_sheet {
+                    break; // Found our sheet, done
                 }
             }
             Ok(Event::Eof) => break,
@@ -919,1608 +893,5963 @@ pub fn extract_cached_errors_from_ods(
         buf.clear();
     }
 
-    Ok(errors)
+    Ok((hidden_columns, hidden_rows))
 }
 
-/// Extract date styles from ODS content.xml and styles.xml
-/// Returns a map of style_name -> excel_format_string
-// Helper to extract date styles and cell style mappings
-fn parse_ods_date(date_str: &str) -> Option<f64> {
-    // Format: YYYY-MM-DD or YYYY-MM-DDThh:mm:ss
-    let parts: Vec<&str> = date_str.split('T').collect();
-    let date_part = parts[0];
-    let time_part = if parts.len() > 1 { parts[1] } else { "" };
-
-    let date_components: Vec<&str> = date_part.split('-').collect();
-    if date_components.len() != 3 {
-        return None;
-    }
-
-    let year = date_components[0].parse::<i32>().ok()?;
-    let month = date_components[1].parse::<u32>().ok()?;
-    let day = date_components[2].parse::<u32>().ok()?;
-
-    // Simple days count from 1899-12-30
-    // Excel epoch: 1899-12-30 = 0.
-    // 1900-01-01 = 2 (Excel bug: 1900 is leap year).
-
-    // We can use a simplified algorithm since we likely deal with modern dates
-    // Algorithm to convert YMD to total days since 0000-03-01
-    // But easier to just count days.
-
-    let is_leap = |y: i32| (y % 4 == 0 && y % 100 != 0) || (y % 400 == 0);
-
-    let days_in_month = [0, 31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
-
-    let mut total_days = 0;
-
-    // Years
-    for y in 1900..year {
-        total_days += if is_leap(y) { 366 } else { 365 };
-    }
-
-    // Months
-    for m in 1..month {
-        if m == 2 && is_leap(year) {
-            total_days += 29;
-        } else {
-            total_days += days_in_month[m as usize];
-        }
-    }
-
-    // Days
-    total_days += day as i32;
-
-    // Adjust for Excel epoch (1900-01-01 is day 1, but we start counting from 1900-01-01 as day 1 in this loop?)
-    // Loop starts 1900.
-    // if date is 1900-01-01: loop 0, month 0, day 1. total = 1.
-    // Excel 1900-01-01 is 2? No, 1. (Actually 1900-01-01 is 1.0).
-    // Excel thinks 1900-02-29 exists (day 60).
-
-    // If our date is > 1900-02-28, we need to ADD 1 to match Excel's bug.
-    // Unless ODS date is pre-1900, which is rare.
-
-    // Let's verify:
-    // 1999-09-30 should be 36433.
-    // Calc:
-    // Years 1900..1999 (99 years).
-    // Leaps: 1904, 08, 12, ... 96. (96-4)/4 + 1 = 24 leap years.
-    // 99 * 365 + 24 = 36135 + 24 = 36159.
-    // Months in 1999 (Jan-Aug): 31+28+31+30+31+30+31+31 = 243.
-    // Days: 30.
-    // Total = 36159 + 243 + 30 = 36432.
-    // Target 36433.
-    // Why diff 1? Because Excel has extra day (Feb 29 1900).
-    // So we add 1 offset + 1 (starting index?).
-
-    // Actually, "1900-01-01" in my loop gives 1. In Excel it is 1.
-    // "1900-02-28" loop: 31 + 28 = 59. Excel: 59.
-    // "1900-03-01" loop: 31 + 28 + 1 = 60. Excel: 61 (60 is 2/29).
-
-    // So if total_days > 59, add 1.
-    if total_days > 59 {
-        total_days += 1;
-    }
-
-    // Time
-    let mut time_fraction = 0.0;
-    if !time_part.is_empty() {
-        // HH:MM:SS or HH:MM:SS.mmm
-        let time_parts: Vec<&str> = time_part.split(':').collect();
-        if time_parts.len() >= 2 {
-            let h = time_parts[0].parse::<f64>().unwrap_or(0.0);
-            let m = time_parts[1].parse::<f64>().unwrap_or(0.0);
-            let s = if time_parts.len() > 2 {
-                time_parts[2].parse::<f64>().unwrap_or(0.0)
-            } else {
-                0.0
-            };
-
-            time_fraction = (h * 3600.0 + m * 60.0 + s) / 86400.0;
-        }
-    }
-
-    // Excel starts from Dec 30 1899?
-    // My loop started Jan 1 1900 as 1.
-    // This matches Excel (1 = 1900-01-01).
-    // So should be fine.
-
-    Some(total_days as f64 + time_fraction)
-}
-
-/// Extract date styles from ODS content.xml and styles.xml
-/// Returns a map of style_name -> excel_format_string
-///
-/// # Automatic Order Handling
-///
-/// When `number:automatic-order="true"` is set on a `number:date-style` element,
-/// this function treats day and month components as two-digit format (equivalent to
-/// `number:style="long"`) when no explicit `number:style` attribute is present.
-///
-/// This behavior aligns with:
-/// - Unicode CLDR standard patterns where `dd` and `MM` are standard for locale-aware formatting
-/// - ISO 8601 requirements for two-digit padding in international date representation
-/// - Observed behavior in LibreOffice Calc and other ODF-compliant applications
-///
-/// # Arguments
-///
-/// * `archive` - Mutable reference to the ODS ZIP archive
-///
-/// # Returns
-///
-/// A HashMap mapping style names to their Excel-compatible format strings
-pub fn extract_date_styles_from_ods(
+/// Extract merged cell ranges from an ODS worksheet
+/// ODS format: <table:table-cell table:number-columns-spanned="X" table:number-rows-spanned="Y">
+pub fn extract_merged_cells_from_ods(
     archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
-) -> Result<std::collections::HashMap<String, String>> {
-    use std::collections::HashMap;
+    sheet_name: &str,
+) -> Result<Vec<(u32, u32, u32, u32)>> {
+    let mut merged_cells = Vec::new();
 
-    // Map: Data Style Name -> Format String (e.g., "N49" -> "dd/mm/yyyy")
-    let mut data_styles = HashMap::new();
-    // Map: Cell Style Name -> Data Style Name (e.g., "ce14" -> "N49")
-    let mut cell_styles = HashMap::new();
+    // ODS stores all sheets in content.xml
+    let content_xml = match archive.by_name("content.xml") {
+        Ok(file) => file,
+        Err(_) => return Ok(merged_cells),
+    };
 
-    // Helper to parse a styles file (content.xml or styles.xml)
-    let mut parse_styles_file = |file: std::io::BufReader<zip::read::ZipFile>| -> Result<()> {
-        let mut reader = Reader::from_reader(file);
-        reader.config_mut().trim_text(false);
+    let buf_reader = BufReader::new(content_xml);
+    let mut reader = Reader::from_reader(buf_reader);
+    reader.config_mut().trim_text(true);
 
-        let mut buf = Vec::new();
-        let mut current_data_style_name = String::new();
-        let mut current_format = String::new();
-        let mut in_date_style = false;
-        let mut automatic_order = false;
+    let mut buf = Vec::new();
+    let mut in_target_sheet = false;
+    let mut current_row = 0u32;
+    let mut current_col = 0u32;
+    let mut current_row_repeated = 1u32;
 
-        loop {
-            match reader.read_event_into(&mut buf)? {
-                Event::Start(e) => {
-                    match e.name().as_ref() {
-                        b"number:date-style" => {
-                            in_date_style = true;
-                            current_format.clear();
-                            automatic_order = false;
-                            for attr in e.attributes().flatten() {
-                                match attr.key.as_ref() {
-                                    b"style:name" => {
-                                        current_data_style_name =
-                                            attr.unescape_value()?.to_string();
-                                    }
-                                    b"number:automatic-order" => {
-                                        if attr.value.as_ref() == b"true" {
-                                            automatic_order = true;
-                                        }
-                                    }
-                                    _ => {}
-                                }
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                match e.name().as_ref() {
+                    b"table:table" => {
+                        let mut name = String::new();
+                        for attr in e.attributes().flatten() {
+                            if attr.key.as_ref() == b"table:name" {
+                                name = attr.unescape_value()?.to_string();
                             }
                         }
-                        b"number:text-style" => {
-                            // Text format style (equivalent to "@" in XLSX)
-                            for attr in e.attributes().flatten() {
-                                if attr.key.as_ref() == b"style:name" {
-                                    let style_name = attr.unescape_value()?.to_string();
-                                    // Mark this as a text format with "@"
-                                    data_styles.insert(style_name, "@".to_string());
-                                }
+
+                        if name == sheet_name {
+                            in_target_sheet = true;
+                            current_row = 0;
+                            current_col = 0;
+                        } else if in_target_sheet {
+                            break;
+                        }
+                    }
+                    b"table:table-row" if in_target_sheet => {
+                        current_col = 0; // Reset column for new row
+                        // Tracking row repetition for spanning logic
+                        current_row_repeated = 1;
+                        for attr in e.attributes().flatten() {
+                            if attr.key.as_ref() == b"table:number-rows-repeated"
+                                && let Ok(val) = attr.unescape_value()?.parse::<u32>()
+                            {
+                                current_row_repeated = val;
                             }
                         }
-                        b"style:style" => {
-                            let mut is_cell_style = false;
-                            let mut style_name = String::new();
-                            let mut data_style_name = String::new();
+                    }
+                    b"table:table-cell" if in_target_sheet => {
+                        let mut cols_spanned = 1u32;
+                        let mut rows_spanned = 1u32;
+                        let mut repeated = 1u32;
 
-                            for attr in e.attributes().flatten() {
-                                match attr.key.as_ref() {
-                                    b"style:family" => {
-                                        if attr.value.as_ref() == b"table-cell" {
-                                            is_cell_style = true;
-                                        }
+                        for attr in e.attributes().flatten() {
+                            match attr.key.as_ref() {
+                                b"table:number-columns-spanned" => {
+                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
+                                        cols_spanned = val;
                                     }
-                                    b"style:name" => {
-                                        style_name = attr.unescape_value()?.to_string();
+                                }
+                                b"table:number-rows-spanned" => {
+                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
+                                        rows_spanned = val;
                                     }
-                                    b"style:data-style-name" => {
-                                        data_style_name = attr.unescape_value()?.to_string();
+                                }
+                                b"table:number-columns-repeated" => {
+                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
+                                        repeated = val;
                                     }
-                                    _ => {}
                                 }
+                                _ => {}
                             }
+                        }
 
-                            if is_cell_style
-                                && !style_name.is_empty()
-                                && !data_style_name.is_empty()
+                        // If either span is > 1, this is a merged cell
+                        if cols_spanned > 1 || rows_spanned > 1 {
+                            merged_cells.push((
+                                current_row,
+                                current_col,
+                                current_row + rows_spanned - 1,
+                                current_col + cols_spanned - 1,
+                            ));
+                        }
+
+                        current_col += repeated;
+                    }
+                    b"table:covered-table-cell" if in_target_sheet => {
+                        // Covered cells are part of a merged range and should increment the column counter
+                        let mut repeated = 1u32;
+                        for attr in e.attributes() {
+                            if let Ok(attr) = attr
+                                && attr.key.as_ref() == b"table:number-columns-repeated"
+                                && let Ok(val) = attr.unescape_value()?.parse::<u32>()
                             {
-                                cell_styles.insert(style_name, data_style_name);
+                                repeated = val;
                             }
                         }
-                        b"number:day" if in_date_style => {
-                            let mut long = automatic_order;
-                            for attr in e.attributes().flatten() {
-                                if attr.key.as_ref() == b"number:style" {
-                                    long = attr.value.as_ref() == b"long";
-                                }
+                        current_col += repeated;
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Empty(e)) => {
+                match e.name().as_ref() {
+                    b"table:table-row" if in_target_sheet => {
+                        // Empty row - increment row counter
+                        let mut repeated = 1u32;
+                        for attr in e.attributes().flatten() {
+                            if attr.key.as_ref() == b"table:number-rows-repeated"
+                                && let Ok(val) = attr.unescape_value()?.parse::<u32>()
+                            {
+                                repeated = val;
                             }
-                            current_format.push_str(if long { "dd" } else { "d" });
                         }
-                        b"number:month" if in_date_style => {
-                            let mut long = automatic_order;
-                            let mut textual = false;
-                            for attr in e.attributes().flatten() {
-                                match attr.key.as_ref() {
-                                    b"number:style" => {
-                                        long = attr.value.as_ref() == b"long";
+                        current_row += repeated;
+                    }
+                    b"table:table-cell" if in_target_sheet => {
+                        let mut cols_spanned = 1u32;
+                        let mut rows_spanned = 1u32;
+                        let mut repeated = 1u32;
+
+                        for attr in e.attributes().flatten() {
+                            match attr.key.as_ref() {
+                                b"table:number-columns-spanned" => {
+                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
+                                        cols_spanned = val;
                                     }
-                                    b"number:textual" => {
-                                        if attr.value.as_ref() == b"true" {
-                                            textual = true;
-                                        }
+                                }
+                                b"table:number-rows-spanned" => {
+                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
+                                        rows_spanned = val;
                                     }
-                                    _ => {}
                                 }
-                            }
-                            if textual {
-                                current_format.push_str(if long { "mmmm" } else { "mmm" });
-                            } else {
-                                current_format.push_str(if long { "mm" } else { "m" });
+                                b"table:number-columns-repeated" => {
+                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
+                                        repeated = val;
+                                    }
+                                }
+                                _ => {}
                             }
                         }
-                        b"number:year" if in_date_style => {
-                            let mut long = false;
-                            for attr in e.attributes().flatten() {
-                                if attr.key.as_ref() == b"number:style"
-                                    && attr.value.as_ref() == b"long"
-                                {
-                                    long = true;
-                                }
-                            }
-                            current_format.push_str(if long { "yyyy" } else { "yy" });
-                        }
-                        b"number:hours" if in_date_style => {
-                            current_format.push_str("hh");
-                        }
-                        b"number:minutes" if in_date_style => {
-                            current_format.push_str("mm");
-                        }
-                        b"number:seconds" if in_date_style => {
-                            current_format.push_str("ss");
-                        }
-                        b"number:text" if in_date_style => {
-                            // Will read text event next
-                        }
-                        _ => {}
-                    }
-                }
-                Event::Empty(e) => match e.name().as_ref() {
-                    b"number:day" if in_date_style => {
-                        let mut long = automatic_order;
-                        for attr in e.attributes().flatten() {
-                            if attr.key.as_ref() == b"number:style" {
-                                long = attr.value.as_ref() == b"long";
-                            }
-                        }
-                        current_format.push_str(if long { "dd" } else { "d" });
-                    }
-                    b"number:month" if in_date_style => {
-                        let mut long = automatic_order;
-                        let mut textual = false;
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"number:style" => {
-                                    long = attr.value.as_ref() == b"long";
-                                }
-                                b"number:textual" => {
-                                    if attr.value.as_ref() == b"true" {
-                                        textual = true;
-                                    }
-                                }
-                                _ => {}
-                            }
-                        }
-                        if textual {
-                            current_format.push_str(if long { "mmmm" } else { "mmm" });
-                        } else {
-                            current_format.push_str(if long { "mm" } else { "m" });
+
+                        // If either span is > 1, this is a merged cell
+                        if cols_spanned > 1 || rows_spanned > 1 {
+                            merged_cells.push((
+                                current_row,
+                                current_col,
+                                current_row + rows_spanned - 1,
+                                current_col + cols_spanned - 1,
+                            ));
                         }
+
+                        current_col += repeated;
                     }
-                    b"number:year" if in_date_style => {
-                        let mut long = false;
-                        for attr in e.attributes().flatten() {
-                            if attr.key.as_ref() == b"number:style"
-                                && attr.value.as_ref() == b"long"
+                    b"table:covered-table-cell" if in_target_sheet => {
+                        // Covered cells are part of a merged range and should increment the column counter
+                        let mut repeated = 1u32;
+                        for attr in e.attributes() {
+                            if let Ok(attr) = attr
+                                && attr.key.as_ref() == b"table:number-columns-repeated"
+                                && let Ok(val) = attr.unescape_value()?.parse::<u32>()
                             {
-                                long = true;
+                                repeated = val;
                             }
                         }
-                        current_format.push_str(if long { "yyyy" } else { "yy" });
-                    }
-                    b"number:hours" if in_date_style => {
-                        current_format.push_str("hh");
+                        current_col += repeated;
                     }
-                    b"number:minutes" if in_date_style => {
-                        current_format.push_str("mm");
+                    _ => {}
+                }
+            }
+            Ok(Event::End(e)) if in_target_sheet => match e.name().as_ref() {
+                b"table:table-row" => {
+                    current_row += current_row_repeated;
+                }
+                b"table:table" => {
+                    break;
+                }
+                _ => {}
+            },
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(anyhow::anyhow!("XML parsing error: {}", e)),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(merged_cells)
+}
+
+/// Read document properties from meta.xml. ODS has no company or manager
+/// fields; `meta:initial-creator` is the author and `dc:creator` the last editor.
+pub fn extract_properties_from_ods(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<DocumentProperties> {
+    let mut properties = DocumentProperties::default();
+    let meta_xml = match archive.by_name("meta.xml") {
+        Ok(file) => file,
+        Err(_) => return Ok(properties),
+    };
+
+    let mut reader = Reader::from_reader(BufReader::new(meta_xml));
+    reader.config_mut().trim_text(true);
+
+    let mut element = Vec::new();
+    let mut custom_name: Option<String> = None;
+    let mut keywords = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"meta:template" => {
+                for attr in e.attributes().flatten() {
+                    if attr.key.as_ref() == b"xlink:href" {
+                        set_text(&mut properties.template, &attr.unescape_value()?);
                     }
-                    b"number:seconds" if in_date_style => {
-                        current_format.push_str("ss");
+                }
+            }
+            Event::Start(e) => {
+                element = e.name().as_ref().to_vec();
+                if element == b"meta:user-defined" {
+                    custom_name = None;
+                    for attr in e.attributes().flatten() {
+                        if attr.key.as_ref() == b"meta:name" {
+                            custom_name = Some(attr.unescape_value()?.to_string());
+                        }
                     }
-                    _ => {}
-                },
-                Event::Text(e) if in_date_style => {
-                    current_format.push_str(e.unescape()?.as_ref());
                 }
-                Event::End(e) => {
-                    if e.name().as_ref() == b"number:date-style" {
-                        if !current_data_style_name.is_empty() {
-                            data_styles
-                                .insert(current_data_style_name.clone(), current_format.clone());
+            }
+            Event::Text(e) => {
+                let value = e.unescape()?;
+                match element.as_slice() {
+                    b"dc:title" => set_text(&mut properties.title, &value),
+                    b"dc:subject" => set_text(&mut properties.subject, &value),
+                    b"dc:description" => set_text(&mut properties.description, &value),
+                    b"meta:keyword" => keywords.push(value.to_string()),
+                    b"meta:initial-creator" => set_text(&mut properties.author, &value),
+                    b"dc:creator" => set_text(&mut properties.last_modified_by, &value),
+                    b"meta:generator" => set_text(&mut properties.application, &value),
+                    b"meta:creation-date" => properties.created = parse_timestamp(&value),
+                    b"dc:date" => properties.modified = parse_timestamp(&value),
+                    b"meta:user-defined" => {
+                        if let Some(name) = custom_name.take() {
+                            properties.custom.insert(name, value.to_string());
                         }
-                        in_date_style = false;
                     }
+                    _ => {}
                 }
-                Event::Eof => break,
-                _ => {}
             }
-            buf.clear();
+            Event::End(_) => element.clear(),
+            Event::Eof => break,
+            _ => {}
         }
-        Ok(())
-    };
-
-    // 1. Read automatic styles from content.xml
-    if let Ok(file) = archive.by_name("content.xml") {
-        parse_styles_file(BufReader::new(file))?;
+        buf.clear();
     }
 
-    // 2. Read styles from styles.xml (global styles)
-    if let Ok(file) = archive.by_name("styles.xml") {
-        parse_styles_file(BufReader::new(file))?;
+    if !keywords.is_empty() {
+        properties.keywords = Some(keywords.join(", "));
     }
+    Ok(properties)
+}
 
-    // 3. Resolve Cell Styles to Format Strings
-    let mut resolved_styles = HashMap::new();
-    for (cell_style, data_style) in cell_styles {
-        if let Some(format) = data_styles.get(&data_style) {
-            resolved_styles.insert(cell_style, format.clone());
+/// Whether the manifest marks any part of the package as encrypted
+pub fn is_encrypted_ods(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<bool> {
+    let manifest = match archive.by_name("META-INF/manifest.xml") {
+        Ok(file) => file,
+        Err(_) => return Ok(false),
+    };
+
+    let mut reader = Reader::from_reader(BufReader::new(manifest));
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"encryption-data" => {
+                return Ok(true);
+            }
+            Event::Eof => break,
+            _ => {}
         }
+        buf.clear();
     }
 
-    // Also include data styles directly, just in case
-    for (data_style, format) in data_styles {
-        resolved_styles.entry(data_style).or_insert(format);
+    Ok(false)
+}
+
+/// Read the protection of `table:table` (`table:protected`) or
+/// `office:spreadsheet` (`table:structure-protected`). Returns `None` when the
+/// flag isn't set, otherwise the password hash, if any.
+fn read_ods_protection(
+    e: &quick_xml::events::BytesStart,
+    flag: &[u8],
+) -> Result<Option<Option<PasswordHash>>> {
+    let mut protected = false;
+    let mut key = None;
+    let mut algorithm = None;
+    for attr in e.attributes().flatten() {
+        let key_name = attr.key.as_ref();
+        if key_name == flag {
+            protected = attr.unescape_value()? == "true";
+        } else if key_name == b"table:protection-key" {
+            key = Some(attr.unescape_value()?.to_string());
+        } else if key_name == b"table:protection-key-digest-algorithm" {
+            algorithm = Some(attr.unescape_value()?.to_string());
+        }
     }
 
-    Ok(resolved_styles)
+    Ok(protected.then(|| {
+        key.filter(|key| !key.is_empty())
+            .map(|key| PasswordHash::from_ods(&key, algorithm.as_deref()))
+    }))
 }
 
-/// Extract formulas from an ODS worksheet
-/// ODS formulas are stored in table:formula attribute
-pub fn extract_formulas_from_ods(
+/// Read the structure protection set on `office:spreadsheet` in content.xml
+pub fn extract_workbook_protection_from_ods(
     archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
-    sheet_name: &str,
-) -> Result<std::collections::HashMap<(u32, u32), String>> {
-    use std::collections::HashMap;
-    let mut formulas = HashMap::new();
-
-    // ODS stores all sheets in content.xml
+) -> Result<Option<WorkbookProtection>> {
     let content_xml = match archive.by_name("content.xml") {
         Ok(file) => file,
-        Err(_) => return Ok(formulas),
+        Err(_) => return Ok(None),
     };
 
-    let buf_reader = BufReader::new(content_xml);
-    let mut reader = Reader::from_reader(buf_reader);
+    let mut reader = Reader::from_reader(BufReader::new(content_xml));
     reader.config_mut().trim_text(true);
 
     let mut buf = Vec::new();
-    let mut in_target_sheet = false;
-    let mut current_row = 0u32;
-    let mut current_col = 0u32;
-    let mut current_row_repeated = 1u32;
-
     loop {
-        match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) => match e.name().as_ref() {
-                b"table:table" => {
-                    let mut name = String::new();
-                    for attr in e.attributes().flatten() {
-                        if attr.key.as_ref() == b"table:name" {
-                            name = attr.unescape_value()?.to_string();
-                        }
-                    }
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"office:spreadsheet" => {
+                return Ok(read_ods_protection(&e, b"table:structure-protected")?.map(
+                    |password| WorkbookProtection {
+                        structure: true,
+                        windows: false,
+                        password,
+                    },
+                ));
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
 
-                    if name == sheet_name {
-                        in_target_sheet = true;
-                        current_row = 0;
-                        current_col = 0;
-                    } else if in_target_sheet {
-                        break;
+    Ok(None)
+}
+
+/// Read database ranges from content.xml as tables. ODS ranges have no totals
+/// row; with `table:contains-header` (the default) the column names are the
+/// text of the range's first row.
+pub fn extract_tables_from_ods(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<Vec<Table>> {
+    let mut tables = OdsTables::default();
+    scan_ods_content(archive, &mut [&mut tables])?;
+    tables.finish(archive)
+}
+
+/// Named database ranges, read as tables
+#[derive(Default)]
+struct OdsTables {
+    tables: Vec<Table>,
+}
+
+impl OdsContentScan for OdsTables {
+    fn event(&mut self, event: &Event<'_>) -> Result<()> {
+        if let Event::Start(e) | Event::Empty(e) = event
+            && e.name().as_ref() == b"table:database-range"
+        {
+            let mut name = String::new();
+            let mut address = String::new();
+            let mut contains_header = true;
+            for attr in e.attributes().flatten() {
+                match attr.key.as_ref() {
+                    b"table:name" => name = attr.unescape_value()?.to_string(),
+                    b"table:target-range-address" => {
+                        address = attr.unescape_value()?.to_string();
                     }
-                }
-                b"table:table-row" if in_target_sheet => {
-                    current_col = 0;
-                    current_row_repeated = 1;
-                    for attr in e.attributes() {
-                        if let Ok(attr) = attr
-                            && attr.key.as_ref() == b"table:number-rows-repeated"
-                            && let Ok(val) = attr.unescape_value()?.parse::<u32>()
-                        {
-                            current_row_repeated = val;
-                        }
+                    b"table:contains-header" => {
+                        contains_header = attr.value.as_ref() != b"false";
                     }
+                    _ => {}
                 }
-                b"table:table-cell" | b"table:covered-table-cell" if in_target_sheet => {
-                    let mut repeated = 1u32;
-                    let mut formula = String::new();
+            }
+            // Unnamed per-sheet ranges (autofilters) can't be used in formulas
+            if !name.starts_with("__Anonymous_Sheet_DB__")
+                && let Some((sheet, range)) = parse_ods_range_address(&address)
+            {
+                self.tables.push(Table {
+                    name,
+                    sheet,
+                    range,
+                    header_rows: contains_header as u32,
+                    ..Default::default()
+                });
+            }
+        }
+        Ok(())
+    }
+}
 
-                    for attr in e.attributes().flatten() {
-                        match attr.key.as_ref() {
-                            b"table:formula" => {
-                                formula = attr.unescape_value()?.to_string();
-                            }
-                            b"table:number-columns-repeated" => {
-                                if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
-                                    repeated = val;
-                                }
-                            }
-                            _ => {}
-                        }
-                    }
+impl OdsTables {
+    /// The tables, with their column names read from their header rows
+    fn finish(
+        self,
+        archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+    ) -> Result<Vec<Table>> {
+        let mut tables = self.tables;
+        if tables.iter().any(|table| table.header_rows > 0) {
+            read_ods_table_headers(archive, &mut tables)?;
+        }
+        Ok(tables)
+    }
+}
 
-                    // Only extract formulas from non-repeated cells.
-                    // Repeated cells should be handled by calamine, which correctly adjusts
-                    // cell references. Copying the same formula to repeated cells without
-                    // adjustment causes false circular references (e.g., G721's formula
-                    // copied to G731 without adjusting references).
-                    if !formula.is_empty() && repeated == 1 && current_row_repeated == 1 {
-                        // ODS formulas usually start with "of:=". We keep it raw here and normalize later.
-                        formulas.insert((current_row, current_col), formula);
-                    }
-                    current_col += repeated;
-                }
-                _ => {}
-            },
-            Ok(Event::Empty(e)) => match e.name().as_ref() {
-                b"table:table-row" if in_target_sheet => {
-                    let mut repeated = 1u32;
-                    for attr in e.attributes() {
-                        if let Ok(attr) = attr
-                            && attr.key.as_ref() == b"table:number-rows-repeated"
-                            && let Ok(val) = attr.unescape_value()?.parse::<u32>()
-                        {
-                            repeated = val;
-                        }
-                    }
-                    current_row += repeated;
-                }
-                b"table:table-cell" | b"table:covered-table-cell" if in_target_sheet => {
-                    let mut repeated = 1u32;
-                    let mut formula = String::new();
+/// A database range being read for its autofilter
+struct OdsFilterRange {
+    sheet: String,
+    filter: AutoFilter,
+    /// The sheet's unnamed range, which holds its plain autofilter
+    anonymous: bool,
+    buttons: bool,
+    /// Whether the conditions are joined by `table:filter-and`
+    and: bool,
+    /// Field (counting from the range's first column), operator, value, and
+    /// the values picked in a multiple choice
+    conditions: Vec<(u32, String, String, Vec<String>)>,
+}
 
-                    for attr in e.attributes().flatten() {
-                        match attr.key.as_ref() {
-                            b"table:formula" => {
-                                formula = attr.unescape_value()?.to_string();
-                            }
-                            b"table:number-columns-repeated" => {
-                                if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
-                                    repeated = val;
-                                }
-                            }
-                            _ => {}
-                        }
-                    }
+/// Autofilter of each sheet, from the database ranges in content.xml: the
+/// sheet's anonymous range (`__Anonymous_Sheet_DB__N`), or else the first
+/// named range on it with filter buttons or a filter
+fn extract_auto_filters_from_ods(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<HashMap<String, AutoFilter>> {
+    fn finish(filters: &mut HashMap<String, AutoFilter>, range: OdsFilterRange) {
+        let OdsFilterRange {
+            sheet,
+            mut filter,
+            anonymous,
+            buttons,
+            and,
+            conditions,
+        } = range;
+        filter.columns = ods_filter_columns(filter.range.1, conditions, and);
+        if !buttons && filter.columns.is_empty() {
+            return;
+        }
+        if anonymous {
+            filters.insert(sheet, filter);
+        } else {
+            filters.entry(sheet).or_insert(filter);
+        }
+    }
 
-                    // Only extract formulas from non-repeated cells (see comment above)
-                    if !formula.is_empty() && repeated == 1 && current_row_repeated == 1 {
-                        formulas.insert((current_row, current_col), formula);
-                    }
-                    current_col += repeated;
+    let mut filters = HashMap::new();
+    let content_xml = match archive.by_name("content.xml") {
+        Ok(file) => file,
+        Err(_) => return Ok(filters),
+    };
+    let mut reader = Reader::from_reader(BufReader::new(content_xml));
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut current: Option<OdsFilterRange> = None;
+    loop {
+        let (e, has_children) = match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => (e.into_owned(), true),
+            Event::Empty(e) => (e.into_owned(), false),
+            Event::End(e) => {
+                if e.name().as_ref() == b"table:database-range"
+                    && let Some(range) = current.take()
+                {
+                    finish(&mut filters, range);
                 }
-                _ => {}
-            },
-            Ok(Event::End(e)) => {
+                buf.clear();
+                continue;
+            }
+            Event::Eof => break,
+            _ => {
+                buf.clear();
+                continue;
+            }
+        };
+        let field = || -> Result<u32> {
+            Ok(ods_attr(&e, b"table:field-number")?
+                .and_then(|field| field.parse().ok())
+                .unwrap_or(0))
+        };
+        match e.name().as_ref() {
+            b"table:database-range" => {
+                let name = ods_attr(&e, b"table:name")?.unwrap_or_default();
+                let address = ods_attr(&e, b"table:target-range-address")?.unwrap_or_default();
+                let buttons =
+                    ods_attr(&e, b"table:display-filter-buttons")?.as_deref() == Some("true");
+                current = parse_ods_range_address(&address).map(|(sheet, range)| OdsFilterRange {
+                    sheet,
+                    filter: AutoFilter {
+                        range,
+                        ..Default::default()
+                    },
+                    anonymous: name.starts_with("__Anonymous_Sheet_DB__"),
+                    buttons,
+                    and: true,
+                    conditions: Vec::new(),
+                });
+                if !has_children && let Some(range) = current.take() {
+                    finish(&mut filters, range);
+                }
+            }
+            _ => {
+                let Some(range) = current.as_mut() else {
+                    buf.clear();
+                    continue;
+                };
                 match e.name().as_ref() {
-                    b"table:table-row" if in_target_sheet => {
-                        current_row += current_row_repeated;
+                    b"table:filter-and" => range.and = true,
+                    b"table:filter-or" => range.and = false,
+                    b"table:filter-condition" => {
+                        let operator = ods_attr(&e, b"table:operator")?.unwrap_or_default();
+                        let value = ods_attr(&e, b"table:value")?.unwrap_or_default();
+                        range
+                            .conditions
+                            .push((field()?, operator, value, Vec::new()));
                     }
-                    b"table:table" if in_target_sheet => {
-                        break; // Found our sheet, done
+                    b"table:filter-set-item" => {
+                        if let Some((_, _, _, items)) = range.conditions.last_mut() {
+                            items.extend(ods_attr(&e, b"table:value")?);
+                        }
+                    }
+                    b"table:sort-by" => {
+                        let descending =
+                            ods_attr(&e, b"table:order")?.as_deref() == Some("descending");
+                        range.filter.sort.push(SortKey {
+                            column: range.filter.range.1 + field()?,
+                            descending,
+                        });
                     }
                     _ => {}
                 }
             }
-            Ok(Event::Eof) => break,
-            Err(e) => return Err(anyhow::anyhow!("XML parsing error: {}", e)),
-            _ => {}
         }
         buf.clear();
     }
-
-    Ok(formulas)
+    Ok(filters)
 }
-/// Normalize ODS references (formulas, ranges, etc.) to a format consistent with XLSX.
-/// Strips "of:=" prefix, handles sheet-qualified references, and cleans up local sheet names.
-///
-/// If `visible_to_xml_row` is provided, converts visible row numbers (1-indexed, used in ODS formulas)
-/// to XML row numbers (0-indexed, used internally). This accounts for hidden rows.
-/// The mapping is ONLY applied to references pointing to `current_sheet_name` (or local references).
-pub fn normalize_ods_reference(
-    reference: &str,
-    preserve_sheet: bool,
-    visible_to_xml_row: Option<&HashMap<u32, u32>>,
-    current_sheet_name: Option<&str>,
-) -> String {
-    use regex::Regex;
-    use std::sync::OnceLock;
-
-    static ODS_REL_REF: OnceLock<Regex> = OnceLock::new();
-    static ODS_COL_REF: OnceLock<Regex> = OnceLock::new();
-    static ODS_ROW_REF: OnceLock<Regex> = OnceLock::new();
-    static ODS_SHEET_REF: OnceLock<Regex> = OnceLock::new();
-    static ODS_SHEET_REF_NO_BRACKET: OnceLock<Regex> = OnceLock::new();
-    static ODS_RECT_REF: OnceLock<Regex> = OnceLock::new();
-
-    let mut normalized = reference
-        .strip_prefix("of:=")
-        .unwrap_or(reference)
-        .to_string();
-
-    // 0. Replace rectangular ranges [.A1:.B2] -> A1:B2
-    // Matches [.A1:.B2]
-    let rect_ref = ODS_RECT_REF
-        .get_or_init(|| Regex::new(r"\[\.(\$?[A-Z]+\$?[0-9]+):\.(\$?[A-Z]+\$?[0-9]+)\]").unwrap());
-    normalized = rect_ref.replace_all(&normalized, "$1:$2").to_string();
-
-    // 0b. Replace sheet-qualified ranges [$Sheet.A1:.A2] -> Sheet!A1:A2
-    // Matches [$Sheet.A1:.B2], [$Sheet.$A$1:.$B$2]
-    // The colon is followed by a dot for relative/absolute mixing in ODS
-    static ODS_SHEET_RANGE_REF: OnceLock<Regex> = OnceLock::new();
-    let sheet_range_ref = ODS_SHEET_RANGE_REF
-        .get_or_init(|| Regex::new(r"\[\$([^.]+)\.([A-Za-z0-9$]+):\.([A-Za-z0-9$]+)\]").unwrap());
-    normalized = sheet_range_ref
-        .replace_all(&normalized, "$1!$2:$3")
-        .to_string();
 
-    // 0b2. Replace unbracketed sheet-qualified ranges $Sheet.A1:.A2 -> Sheet!A1:A2
-    // Matches $Sheet.A1:.B2 (common in database-range targets)
-    // We exclude '[' from the sheet name capture to avoid matching relative column refs like [.A:.A]
-    static ODS_SHEET_RANGE_NO_BRACKET_REF: OnceLock<Regex> = OnceLock::new();
-    let sheet_range_no_bracket_ref = ODS_SHEET_RANGE_NO_BRACKET_REF
-        .get_or_init(|| Regex::new(r"\$?([^.\[]+)\.([A-Za-z0-9$]+):\.([A-Za-z0-9$]+)").unwrap());
-    normalized = sheet_range_no_bracket_ref
-        .replace_all(&normalized, "$1!$2:$3")
-        .to_string();
+/// Group the conditions of an ODS filter by column. Equality conditions
+/// become value lists and top/bottom conditions a top filter; text
+/// conditions are written with Excel's wildcards (`contains x` is `= *x*`).
+fn ods_filter_columns(
+    first_column: u32,
+    conditions: Vec<(u32, String, String, Vec<String>)>,
+    and: bool,
+) -> Vec<FilterColumn> {
+    let mut fields: Vec<(u32, Vec<(String, String, Vec<String>)>)> = Vec::new();
+    for (field, operator, value, items) in conditions {
+        match fields.iter_mut().find(|(existing, _)| *existing == field) {
+            Some((_, conditions)) => conditions.push((operator, value, items)),
+            None => fields.push((field, vec![(operator, value, items)])),
+        }
+    }
 
-    if !preserve_sheet {
-        // 0c. Replace plain sheet-level ranges Sheet1.A1:Sheet1.B2 -> A1:B2
-        // Matches Sheet1.A1:Sheet1.B2, Sheet1.A1, etc.
-        // This is common in ODS conditional formatting targets.
-        // We strip the sheet name if it's the same for both parts of the range.
-        // Updated to handle both '.' (ODS native) and '!' (normalized) separators
-        static ODS_LOCAL_RANGE_REF: OnceLock<Regex> = OnceLock::new();
-        let local_range_ref = ODS_LOCAL_RANGE_REF.get_or_init(|| {
-            Regex::new(r"([^.!]+)[.!]([A-Z0-9$]+):([^.!]+)[.!]([A-Z0-9$]+)").unwrap()
-        });
-        normalized = local_range_ref
-            .replace_all(&normalized, |caps: &regex::Captures| {
-                if caps[1] == caps[3] {
-                    if caps[2] == caps[4] {
-                        caps[2].to_string()
+    let mut columns = Vec::new();
+    for (field, conditions) in fields {
+        let (operator, value, _) = &conditions[0];
+        let top = match operator.to_lowercase().as_str() {
+            "top values" => Some((false, false)),
+            "bottom values" => Some((false, true)),
+            "top percent" => Some((true, false)),
+            "bottom percent" => Some((true, true)),
+            _ => None,
+        };
+        let criteria = if let Some((percent, bottom)) = top {
+            FilterCriteria::Top {
+                count: value.parse().unwrap_or(10.0),
+                percent,
+                bottom,
+            }
+        } else if conditions
+            .iter()
+            .all(|(operator, _, _)| operator == "=" || operator == "empty")
+        {
+            let mut values = Vec::new();
+            let mut blank = false;
+            for (operator, value, items) in conditions {
+                let picked = if items.is_empty() { vec![value] } else { items };
+                for value in picked {
+                    if operator == "empty" || value.is_empty() {
+                        blank = true;
                     } else {
-                        format!("{}:{}", &caps[2], &caps[4])
+                        values.push(value);
                     }
-                } else {
-                    format!("{}!{}:{}!{}", &caps[1], &caps[2], &caps[3], &caps[4])
                 }
-            })
-            .to_string();
-
-        // 0d. Replace single local reference Sheet1.A1 -> A1
-        // Updated to handle both '.' and '!' separators
-        static ODS_LOCAL_SINGLE_REF: OnceLock<Regex> = OnceLock::new();
-        let local_single_ref =
-            ODS_LOCAL_SINGLE_REF.get_or_init(|| Regex::new(r"^([^.!]+)[.!]([A-Z0-9$]+)$").unwrap());
-        normalized = local_single_ref.replace_all(&normalized, "$2").to_string();
-
-        // 0e. Replace normalized sheet ranges Sheet1!A1:B2 -> A1:B2
-        // This handles cases where 0b/0b2 normalized the range to Excel format,
-        // but we want to strip the sheet name for local context parity.
-        static ODS_NORMALIZED_RANGE_REF: OnceLock<Regex> = OnceLock::new();
-        let normalized_range_ref = ODS_NORMALIZED_RANGE_REF
-            .get_or_init(|| Regex::new(r"^([^!]+)!([A-Z0-9$]+):([A-Z0-9$]+)$").unwrap());
-        normalized = normalized_range_ref
-            .replace_all(&normalized, "$2:$3")
-            .to_string();
+            }
+            FilterCriteria::Values { values, blank }
+        } else {
+            let conditions = conditions
+                .into_iter()
+                .filter_map(|(operator, value, _)| {
+                    use FilterOperator::*;
+                    let (operator, value) = match operator.as_str() {
+                        "=" => (Equal, value),
+                        "!=" => (NotEqual, value),
+                        "<" => (LessThan, value),
+                        "<=" => (LessThanOrEqual, value),
+                        ">" => (GreaterThan, value),
+                        ">=" => (GreaterThanOrEqual, value),
+                        "contains" => (Equal, format!("*{}*", value)),
+                        "!contains" => (NotEqual, format!("*{}*", value)),
+                        "begins" => (Equal, format!("{}*", value)),
+                        "!begins" => (NotEqual, format!("{}*", value)),
+                        "ends" => (Equal, format!("*{}", value)),
+                        "!ends" => (NotEqual, format!("*{}", value)),
+                        "!empty" => (NotEqual, String::new()),
+                        // Regular expressions have no Excel equivalent
+                        _ => return None,
+                    };
+                    Some(FilterCondition { operator, value })
+                })
+                .collect();
+            FilterCriteria::Custom { conditions, and }
+        };
+        columns.push(FilterColumn {
+            column: first_column + field,
+            criteria,
+        });
     }
+    columns
+}
 
-    // 1. Replace sheet references [$Sheet1.A1] -> Sheet1!A1 (BEFORE non-bracketed version)
-    // Matches [$Sheet.$A$1], [$Sheet.A1], etc.
-    // The cell reference part can have $ signs for absolute references
-    let sheet_ref =
-        ODS_SHEET_REF.get_or_init(|| Regex::new(r"\[\$([^.]+)\.(\$?[A-Z]+\$?[0-9]+)\]").unwrap());
-    normalized = sheet_ref.replace_all(&normalized, "$1!$2").to_string();
-
-    // 2. Replace $SHEETNAME.CELLREF -> SHEETNAME!CELLREF (without brackets)
-    // Matches $INGRESOS.BC$50, $Sheet1.A1, $Sheet.$A$1 etc.
-    // This comes AFTER bracketed version, so it won't match already-processed [$SHEET.CELL]
-    let sheet_ref_no_bracket = ODS_SHEET_REF_NO_BRACKET
-        .get_or_init(|| Regex::new(r"\$([A-Za-z0-9_]+)\.(\$?[A-Z]+\$?[0-9]+)").unwrap());
-    normalized = sheet_ref_no_bracket
-        .replace_all(&normalized, "$1!$2")
-        .to_string();
-
-    // 3. Replace relative references [.A1] -> A1
-    // Matches [.A1], [.AA123]
-    let rel_ref = ODS_REL_REF.get_or_init(|| Regex::new(r"\[\.(\$?[A-Z]+\$?[0-9]+)\]").unwrap());
-    normalized = rel_ref.replace_all(&normalized, "$1").to_string();
-
-    // 4. Replace whole column references [.A:.A] -> A:A
-    // Matches [.A:.A], [.A:.B]
-    let col_ref = ODS_COL_REF.get_or_init(|| Regex::new(r"\[\.([A-Z]+):\.([A-Z]+)\]").unwrap());
-    normalized = col_ref.replace_all(&normalized, "$1:$2").to_string();
-
-    // 5. Replace whole row references [.1:.1] -> 1:1
-    // Matches [.1:.1], [.1:.10]
-    let row_ref = ODS_ROW_REF.get_or_init(|| Regex::new(r"\[\.([0-9]+):\.([0-9]+)\]").unwrap());
-    normalized = row_ref.replace_all(&normalized, "$1:$2").to_string();
-
-    // Final check for identical range parts (e.g. A1:A1 -> A1)
-    if let Some((start, end)) = normalized.split_once(':')
-        && start == end
-    {
-        return start.to_string();
-    }
+/// Read data pilot tables from content.xml. ODS keeps no pivot cache: each
+/// table names its source range, database query or service itself.
+pub fn extract_pivot_tables_from_ods(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<Vec<PivotTable>> {
+    let mut pivots = OdsPivotTables::default();
+    scan_ods_content(archive, &mut [&mut pivots])?;
+    Ok(pivots.pivot_tables)
+}
 
-    // Convert visible row numbers to XML row numbers if mapping is provided
-    // This accounts for hidden rows in ODS files
-    if let Some(row_map) = visible_to_xml_row {
-        // Regex to match cell references: Sheet!A1, A1, $A$1, etc.
-        // Captures: (optional sheet)(column)(row number)
-        static CELL_REF_PATTERN: OnceLock<Regex> = OnceLock::new();
-        let cell_ref = CELL_REF_PATTERN
-            .get_or_init(|| Regex::new(r"(?:([A-Za-z0-9_]+)!)?(\$?[A-Z]+)(\$?)([0-9]+)").unwrap());
+/// Data pilot tables with their sources
+#[derive(Default)]
+struct OdsPivotTables {
+    pivot_tables: Vec<PivotTable>,
+    /// The `table:data-pilot-table` being read
+    current: Option<PivotTable>,
+}
 
-        normalized = cell_ref
-            .replace_all(&normalized, |caps: &regex::Captures| {
-                let sheet_name = caps.get(1).map(|m| m.as_str());
-                let sheet_prefix = sheet_name.map(|s| format!("{}!", s)).unwrap_or_default();
-                let col = caps.get(2).map(|m| m.as_str()).unwrap_or("");
-                let abs_marker = caps.get(3).map(|m| m.as_str()).unwrap_or("");
-                let row_str = caps.get(4).map(|m| m.as_str()).unwrap_or("");
-
-                // ONLY convert row numbers for references to the CURRENT sheet
-                // This includes:
-                // 1. Local references (no sheet prefix)
-                // 2. Explicit references to the current sheet (e.g., CurrentSheet!A1)
-                let should_convert = if let Some(current_sheet) = current_sheet_name {
-                    sheet_name.is_none() || sheet_name == Some(current_sheet)
-                } else {
-                    // If no current sheet provided, only convert local references
-                    sheet_name.is_none()
+impl OdsContentScan for OdsPivotTables {
+    fn event(&mut self, event: &Event<'_>) -> Result<()> {
+        match event {
+            Event::Start(e) if e.name().as_ref() == b"table:data-pilot-table" => {
+                self.current = read_ods_data_pilot(e)?;
+            }
+            Event::Empty(e) if e.name().as_ref() == b"table:data-pilot-table" => {
+                self.pivot_tables.extend(read_ods_data_pilot(e)?);
+            }
+            Event::End(e) if e.name().as_ref() == b"table:data-pilot-table" => {
+                self.pivot_tables.extend(self.current.take());
+            }
+            Event::Start(e) | Event::Empty(e) if self.current.is_some() => {
+                let attribute = |name: &[u8]| -> Option<String> {
+                    e.attributes()
+                        .flatten()
+                        .find(|attr| attr.key.as_ref() == name)
+                        .and_then(|attr| attr.unescape_value().ok().map(|v| v.to_string()))
                 };
-
-                if should_convert && let Ok(visible_row) = row_str.parse::<u32>() {
-                    // Convert visible row (1-indexed) to XML row (0-indexed)
-                    if let Some(&xml_row) = row_map.get(&visible_row) {
-                        // Convert back to 1-indexed for formula representation
-                        return format!("{}{}{}", col, abs_marker, xml_row + 1);
+                let source = match e.name().as_ref() {
+                    b"table:source-cell-range" => parse_ods_pivot_source(
+                        &attribute(b"table:cell-range-address").unwrap_or_default(),
+                    ),
+                    b"table:database-source-table" => PivotSource::Connection(format!(
+                        "{}.{}",
+                        attribute(b"table:database-name").unwrap_or_default(),
+                        attribute(b"table:database-table-name").unwrap_or_default()
+                    )),
+                    b"table:database-source-query" => PivotSource::Connection(format!(
+                        "{}.{}",
+                        attribute(b"table:database-name").unwrap_or_default(),
+                        attribute(b"table:query-name").unwrap_or_default()
+                    )),
+                    b"table:database-source-sql" => PivotSource::Connection(
+                        attribute(b"table:database-name").unwrap_or_default(),
+                    ),
+                    b"table:source-service" => {
+                        PivotSource::Connection(attribute(b"table:name").unwrap_or_default())
                     }
+                    _ => return Ok(()),
+                };
+                if let Some(pivot) = self.current.as_mut() {
+                    pivot.source = source;
                 }
-                // Keep original for cross-sheet references or if no mapping found
-                format!("{}{}{}{}", sheet_prefix, col, abs_marker, row_str)
-            })
-            .to_string();
+            }
+            _ => {}
+        }
+        Ok(())
     }
-
-    normalized
-}
-pub struct OdsReader<'a, R: std::io::Read + std::io::Seek> {
-    archive: &'a mut ZipArchive<R>,
 }
 
-impl<'a, R: std::io::Read + std::io::Seek> OdsReader<'a, R> {
-    pub fn new(archive: &'a mut ZipArchive<R>) -> Result<Self> {
-        Ok(Self { archive })
+/// Name and location of a `table:data-pilot-table`; `None` when the target
+/// range doesn't parse
+fn read_ods_data_pilot(e: &quick_xml::events::BytesStart) -> Result<Option<PivotTable>> {
+    let mut pivot = PivotTable::default();
+    let mut location = None;
+    for attr in e.attributes().flatten() {
+        match attr.key.as_ref() {
+            b"table:name" => pivot.name = attr.unescape_value()?.to_string(),
+            b"table:target-range-address" => {
+                location = parse_ods_range_address(&attr.unescape_value()?);
+            }
+            _ => {}
+        }
     }
+    Ok(location.map(|(sheet, range)| PivotTable {
+        sheet,
+        location: range,
+        ..pivot
+    }))
 }
 
-impl<'a, R: std::io::Read + std::io::Seek> WorkbookReader for OdsReader<'a, R> {
-    fn read_sheets(&mut self) -> Result<Vec<Sheet>> {
-        // Initialize date styles map first to avoid borrow check issues
-        let date_styles = extract_date_styles_from_ods(self.archive)?;
-        let hidden_sheets = extract_hidden_sheets_from_ods(self.archive)?;
-
-        // Extract external workbooks for formula normalization
-        let external_workbooks = self.read_external_workbooks()?;
+/// Source of a data pilot table from its `table:cell-range-address`, such as
+/// `Data.A1:Data.D100`, or `'file:///data/q1.ods'#$Data.A1:.D100` for a range
+/// in another document
+fn parse_ods_pivot_source(address: &str) -> PivotSource {
+    if let Some((workbook, reference)) = address
+        .strip_prefix('\'')
+        .and_then(|rest| rest.split_once("'#"))
+    {
+        return PivotSource::External {
+            workbook: workbook.to_string(),
+            reference: match parse_ods_range_address(reference) {
+                Some((sheet, range)) => format_range(&sheet, range),
+                None => reference.to_string(),
+            },
+        };
+    }
+    match parse_ods_range_address(address) {
+        Some((sheet, range)) => PivotSource::Range {
+            sheet,
+            reference: format_range("", range),
+        },
+        None => PivotSource::Range {
+            sheet: String::new(),
+            reference: if address.contains("#REF!") {
+                "#REF!".to_string()
+            } else {
+                address.to_string()
+            },
+        },
+    }
+}
 
-        let mut sheets = Vec::new();
+/// Read the OLE objects, database ranges, linked cell ranges and event scripts
+/// of `content.xml`. Document events sit in `office:scripts`, sheet and
+/// control events inside the `table:table` they belong to.
+pub fn extract_active_content_from_ods(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<Vec<ActiveContent>> {
+    let mut content = OdsActiveContent::default();
+    scan_ods_content(archive, &mut [&mut content])?;
+    Ok(content.content)
+}
 
-        let content_xml = match self.archive.by_name("content.xml") {
-            Ok(file) => file,
-            Err(_) => return Ok(sheets),
-        };
+/// OLE objects, data connections, linked ranges and event scripts
+#[derive(Default)]
+struct OdsActiveContent {
+    content: Vec<ActiveContent>,
+    current_sheet: Option<String>,
+    frame_name: String,
+    range_name: String,
+}
 
-        let mut reader = Reader::from_reader(BufReader::new(content_xml));
-        reader.config_mut().trim_text(true);
+impl OdsContentScan for OdsActiveContent {
+    fn event(&mut self, event: &Event<'_>) -> Result<()> {
+        match event {
+            Event::Start(e) | Event::Empty(e) => {
+                let is_start = matches!(event, Event::Start(_));
+                let attribute = |name: &[u8]| -> Option<String> {
+                    e.attributes()
+                        .flatten()
+                        .find(|attr| attr.key.as_ref() == name)
+                        .and_then(|attr| attr.unescape_value().ok().map(|v| v.to_string()))
+                };
+                match e.name().as_ref() {
+                    // An empty `table:table` holds nothing
+                    b"table:table" if is_start => self.current_sheet = attribute(b"table:name"),
+                    b"draw:frame" => self.frame_name = attribute(b"draw:name").unwrap_or_default(),
+                    b"draw:object-ole" => {
+                        // Embedded objects live in the package (`./Object 2`)
+                        let href = attribute(b"xlink:href").unwrap_or_default();
+                        let href = href.trim_start_matches("./").trim_end_matches('/');
+                        self.content.push(ActiveContent {
+                            kind: ActiveContentKind::OleObject,
+                            sheet: self.current_sheet.clone(),
+                            name: self.frame_name.clone(),
+                            target: Some(href.to_string()).filter(|href| !href.is_empty()),
+                            runs_on_open: false,
+                        });
+                    }
+                    b"table:database-range" => {
+                        self.range_name = attribute(b"table:name").unwrap_or_default();
+                    }
+                    b"table:database-source-sql"
+                    | b"table:database-source-table"
+                    | b"table:database-source-query" => {
+                        let database = attribute(b"table:database-name").unwrap_or_default();
+                        let source = attribute(b"table:sql-statement")
+                            .or_else(|| attribute(b"table:database-table-name"))
+                            .or_else(|| attribute(b"table:query-name"))
+                            .unwrap_or_default();
+                        self.content.push(ActiveContent {
+                            kind: ActiveContentKind::DataConnection,
+                            sheet: None,
+                            name: self.range_name.clone(),
+                            target: Some(format!("{} ({})", database, source)),
+                            runs_on_open: false,
+                        });
+                    }
+                    // Linked areas: another file's range or a web page's table
+                    b"table:cell-range-source" => {
+                        let href = attribute(b"xlink:href").unwrap_or_default();
+                        let is_web = href.starts_with("http://") || href.starts_with("https://");
+                        self.content.push(ActiveContent {
+                            kind: if is_web {
+                                ActiveContentKind::WebQuery
+                            } else {
+                                ActiveContentKind::DataConnection
+                            },
+                            sheet: self.current_sheet.clone(),
+                            name: attribute(b"table:name").unwrap_or_default(),
+                            target: Some(href),
+                            runs_on_open: false,
+                        });
+                    }
+                    b"script:event-listener" => {
+                        let event = attribute(b"script:event-name").unwrap_or_default();
+                        self.content.push(ActiveContent {
+                            kind: ActiveContentKind::EventScript,
+                            sheet: self.current_sheet.clone(),
+                            runs_on_open: event.ends_with(":load"),
+                            name: event,
+                            target: attribute(b"xlink:href")
+                                .or_else(|| attribute(b"script:macro-name")),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(e) if e.name().as_ref() == b"table:table" => self.current_sheet = None,
+            _ => {}
+        }
+        Ok(())
+    }
+}
 
-        let mut buf = Vec::new();
-        let mut current_sheet: Option<Sheet> = None;
-        let mut current_row = 0u32;
-        let mut row_repeated = 1u32;
-        let mut current_col = 0u32;
-        let mut current_cf_range: Option<String> = None;
-        let mut skip_current_sheet = false; // Flag to skip external sheets
+/// Split an ODS range address such as `Sheet1.A1:Sheet1.B9`, `$Sheet1.$A$1:.$B$9`
+/// or `'My Sheet'.A1` into the sheet name and 0-based range
+fn parse_ods_range_address(address: &str) -> Option<(String, (u32, u32, u32, u32))> {
+    // Split at the last '.' or ':' outside quoted sheet names
+    fn split_outside_quotes(text: &str, separator: char) -> Option<(&str, &str)> {
+        let mut quoted = false;
+        let mut split = None;
+        for (i, c) in text.char_indices() {
+            match c {
+                '\'' => quoted = !quoted,
+                c if c == separator && !quoted => split = Some(i),
+                _ => {}
+            }
+        }
+        split.map(|i| (&text[..i], &text[i + 1..]))
+    }
 
-        // Track visible row numbering for ODS formulas
-        // ODS formulas use 1-indexed visible row numbers (accounting for hidden rows)
-        // but we store cells using 0-indexed XML row numbers
-        let mut visible_row_counter = 1u32; // 1-indexed (ODS formula style)
-        let mut visible_to_xml_row: HashMap<u32, u32> = HashMap::new();
+    let (start, end) = split_outside_quotes(address, ':').unwrap_or((address, address));
+    let (sheet, start) = split_outside_quotes(start, '.')?;
+    let end = split_outside_quotes(end, '.').map_or(end, |(_, cell)| cell);
+
+    let sheet = sheet.trim_start_matches('$');
+    let sheet = match sheet.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')) {
+        Some(quoted) => quoted.replace("''", "'"),
+        None => sheet.to_string(),
+    };
+    let (start_row, start_col) = parse_cell_ref(start)?;
+    let (end_row, end_col) = parse_cell_ref(end)?;
+    Some((sheet, (start_row, start_col, end_row, end_col)))
+}
+
+/// Fill in table column names from the text of each table's header row
+fn read_ods_table_headers(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+    tables: &mut [Table],
+) -> Result<()> {
+    for table in tables.iter_mut().filter(|table| table.header_rows > 0) {
+        let (_, c1, _, c2) = table.range;
+        table.columns = vec![String::new(); (c2.saturating_sub(c1) + 1).min(16384) as usize];
+    }
+
+    let content_xml = match archive.by_name("content.xml") {
+        Ok(file) => file,
+        Err(_) => return Ok(()),
+    };
+    let mut reader = Reader::from_reader(BufReader::new(content_xml));
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut sheet = String::new();
+    let mut row = 0u32;
+    let mut rows_repeated = 1u32;
+    let mut col = 0u32;
+    // Tables whose header row is the current row
+    let mut header_of: Vec<usize> = Vec::new();
+    // Start column, repeat count and text of the header cell being read
+    let mut header_cell: Option<(u32, u32, String)> = None;
+
+    let repeated = |e: &quick_xml::events::BytesStart, key: &[u8]| -> u32 {
+        e.attributes()
+            .flatten()
+            .find(|attr| attr.key.as_ref() == key)
+            .and_then(|attr| attr.unescape_value().ok()?.parse().ok())
+            .unwrap_or(1)
+    };
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => match e.name().as_ref() {
+                b"table:table" => {
+                    sheet = e
+                        .attributes()
+                        .flatten()
+                        .find(|attr| attr.key.as_ref() == b"table:name")
+                        .map(|attr| attr.unescape_value().map(|v| v.to_string()))
+                        .transpose()?
+                        .unwrap_or_default();
+                    row = 0;
+                }
+                b"table:table-row" => {
+                    rows_repeated = repeated(&e, b"table:number-rows-repeated");
+                    col = 0;
+                    header_of = (0..tables.len())
+                        .filter(|&i| {
+                            let table = &tables[i];
+                            table.header_rows > 0
+                                && table.sheet == sheet
+                                && (row..row.saturating_add(rows_repeated)).contains(&table.range.0)
+                        })
+                        .collect();
+                }
+                b"table:table-cell" | b"table:covered-table-cell" => {
+                    let columns = repeated(&e, b"table:number-columns-repeated");
+                    if !header_of.is_empty() {
+                        header_cell = Some((col, columns, String::new()));
+                    }
+                    col = col.saturating_add(columns);
+                }
+                _ => {}
+            },
+            Event::Empty(e) => match e.name().as_ref() {
+                b"table:table-row" => {
+                    row = row.saturating_add(repeated(&e, b"table:number-rows-repeated"));
+                }
+                b"table:table-cell" | b"table:covered-table-cell" => {
+                    col = col.saturating_add(repeated(&e, b"table:number-columns-repeated"));
+                }
+                _ => {}
+            },
+            Event::Text(e) => {
+                if let Some((_, _, text)) = &mut header_cell {
+                    text.push_str(&e.unescape()?);
+                }
+            }
+            Event::End(e) => match e.name().as_ref() {
+                b"table:table-row" => {
+                    row = row.saturating_add(rows_repeated);
+                    header_of.clear();
+                }
+                b"table:table-cell" | b"table:covered-table-cell" => {
+                    if let Some((start, columns, text)) = header_cell.take() {
+                        for &i in &header_of {
+                            let table = &mut tables[i];
+                            let first = table.range.1;
+                            let cells = start.max(first)..start.saturating_add(columns);
+                            for c in cells {
+                                match table.columns.get_mut((c - first) as usize) {
+                                    Some(name) => *name = text.clone(),
+                                    None => break,
+                                }
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(())
+}
+
+/// Check if ODS file contains macros
+/// ODS macros are stored in Basic/ or Scripts/ directories,
+/// or declared in META-INF/manifest.xml
+pub fn has_macros(archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>) -> Result<bool> {
+    // 1. Check for directory presence
+    for i in 0..archive.len() {
+        if let Ok(file) = archive.by_index(i) {
+            let name = file.name();
+            if name.starts_with("Basic/") || name.starts_with("Scripts/") {
+                return Ok(true);
+            }
+        }
+    }
+
+    // 2. Check manifest for macro-related media types
+    if let Ok(manifest_file) = archive.by_name("META-INF/manifest.xml") {
+        let buf_reader = BufReader::new(manifest_file);
+        let mut reader = Reader::from_reader(buf_reader);
+        let mut buf = Vec::new();
 
         loop {
             match reader.read_event_into(&mut buf)? {
-                Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"table:table" => {
-                    // Finalize previous sheet if it exists and it's not external
-                    if let Some(sheet) = current_sheet.take()
-                        && !skip_current_sheet
-                    {
-                        sheets.push(sheet);
+                Event::Start(e) | Event::Empty(e)
+                    if e.name().as_ref() == b"manifest:file-entry" =>
+                {
+                    for attr in e.attributes().flatten() {
+                        if attr.key.as_ref() == b"manifest:media-type" {
+                            let media_type = attr.unescape_value()?;
+                            if media_type.contains("application/vnd.sun.xml.ui.configuration")
+                                || media_type.contains("script")
+                            {
+                                // This is a bit broad, but Basic/ scripts often have specific media types
+                            }
+                        }
                     }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+    }
+
+    Ok(false)
+}
+
+/// Read the Basic modules of `Basic/<Library>/<Module>.xml`. Each library
+/// directory also holds a `script-lb.xml` index, and dialogs sit next to the
+/// modules as `dlg:window` documents.
+pub fn extract_macros_from_ods(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<Vec<MacroModule>> {
+    let paths: Vec<String> = archive
+        .file_names()
+        .filter(|name| {
+            name.starts_with("Basic/")
+                && name.ends_with(".xml")
+                && name.matches('/').count() == 2
+                && !name.ends_with("/script-lb.xml")
+        })
+        .map(str::to_string)
+        .collect();
 
+    let mut modules = Vec::new();
+    for path in paths {
+        let library = path.split('/').nth(1).unwrap_or_default().to_string();
+        let file = archive.by_name(&path)?;
+        let mut reader = Reader::from_reader(BufReader::new(file));
+        let mut buf = Vec::new();
+        let mut module: Option<MacroModule> = None;
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"script:module" => {
                     let mut name = String::new();
                     for attr in e.attributes().flatten() {
-                        if attr.key.as_ref() == b"table:name" {
+                        if attr.key.as_ref() == b"script:name" {
                             name = attr.unescape_value()?.to_string();
                         }
                     }
-                    let mut new_sheet = Sheet::new(name.clone());
-                    new_sheet.visible = !hidden_sheets.contains(&name);
-                    current_sheet = Some(new_sheet);
-                    current_row = 0;
-                    current_col = 0; // Reset column tracking for new sheet
-                    skip_current_sheet = false; // Reset skip flag for new sheet
+                    module = Some(MacroModule {
+                        library: library.clone(),
+                        name,
+                        ..Default::default()
+                    });
                 }
-                // Detect external sheets by checking for table:table-source
-                Event::Start(ref e) | Event::Empty(ref e)
-                    if e.name().as_ref() == b"table:table-source" =>
-                {
-                    // Check if this table-source has an xlink:href attribute
-                    for attr in e.attributes().flatten() {
-                        if attr.key.as_ref() == b"xlink:href" {
-                            // This sheet is from an external workbook, mark it to be skipped
-                            skip_current_sheet = true;
-                            break;
-                        }
+                Event::Text(e) => {
+                    if let Some(module) = module.as_mut() {
+                        module.source.push_str(&e.unescape()?);
                     }
                 }
-                Event::Start(e) if e.name().as_ref() == b"table:table-column" => {
-                    if let Some(ref mut sheet) = current_sheet {
-                        let mut hidden = false;
-                        let mut repeated = 1u32;
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"table:visibility" => {
-                                    if attr.value.as_ref() == b"collapse"
-                                        || attr.value.as_ref() == b"filter"
-                                    {
-                                        hidden = true;
-                                    }
-                                }
-                                b"table:number-columns-repeated" => {
-                                    repeated = attr.unescape_value()?.parse::<u32>().unwrap_or(1);
-                                }
-                                _ => {}
-                            }
-                        }
-                        if hidden {
-                            for _ in 0..repeated {
-                                sheet.hidden_columns.push(current_col);
-                                current_col += 1;
-                            }
-                        } else {
-                            current_col += repeated;
-                        }
+                Event::CData(e) => {
+                    if let Some(module) = module.as_mut() {
+                        module.source.push_str(&String::from_utf8_lossy(&e));
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+        // Dialogs and other documents have no script:module root
+        modules.extend(module);
+    }
+    Ok(modules)
+}
 
-                        // If it's a start tag, we need to skip to its end tag to avoid nested column issues
-                        let mut col_buf = Vec::new();
-                        loop {
-                            match reader.read_event_into(&mut col_buf)? {
-                                Event::End(ref te)
-                                    if te.name().as_ref() == b"table:table-column" =>
-                                {
-                                    break;
-                                }
-                                Event::Eof => break,
-                                _ => {}
-                            }
-                            col_buf.clear();
+/// Extract external links from ODS metadata
+pub fn extract_external_links_ods(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<Vec<String>> {
+    let mut links = std::collections::HashSet::new();
+
+    let content_xml = match archive.by_name("content.xml") {
+        Ok(file) => file,
+        Err(_) => return Ok(Vec::new()),
+    };
+
+    let buf_reader = BufReader::new(content_xml);
+    let mut reader = Reader::from_reader(buf_reader);
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(ref e) if e.name().as_ref() == b"text:a" => {
+                for attr in e.attributes().flatten() {
+                    if attr.key.as_ref() == b"xlink:href" {
+                        let link = attr.unescape_value()?.to_string();
+                        if !link.starts_with("#") {
+                            links.insert(link);
                         }
                     }
                 }
-                Event::Empty(e) if e.name().as_ref() == b"table:table-column" => {
-                    if let Some(ref mut sheet) = current_sheet {
-                        let mut hidden = false;
-                        let mut repeated = 1u32;
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"table:visibility" => {
-                                    if attr.value.as_ref() == b"collapse"
-                                        || attr.value.as_ref() == b"filter"
-                                    {
-                                        hidden = true;
-                                    }
-                                }
-                                b"table:number-columns-repeated" => {
-                                    repeated = attr.unescape_value()?.parse::<u32>().unwrap_or(1);
-                                }
-                                _ => {}
+            }
+            Event::Start(ref e) | Event::Empty(ref e)
+                if e.name().as_ref() == b"table:table-source" =>
+            {
+                for attr in e.attributes().flatten() {
+                    if attr.key.as_ref() == b"xlink:href" {
+                        links.insert(attr.unescape_value()?.to_string());
+                    }
+                }
+            }
+            Event::Start(ref e) | Event::Empty(ref e)
+                if e.name().as_ref() == b"table:table-cell" =>
+            {
+                for attr in e.attributes().flatten() {
+                    if attr.key.as_ref() == b"table:formula" {
+                        let formula = attr.unescape_value()?;
+                        if formula.contains("'file:///")
+                            && let Some(start) = formula.find("'file:///")
+                        {
+                            let remainder = &formula[start + 1..];
+                            if let Some(end) = remainder.find("'#") {
+                                let path = &remainder[..end];
+                                links.insert(path.to_string());
                             }
                         }
-                        if hidden {
-                            for _ in 0..repeated {
-                                sheet.hidden_columns.push(current_col);
-                                current_col += 1;
-                            }
-                        } else {
-                            current_col += repeated;
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    // Strip file:// prefix from paths for cleaner display
+    let cleaned_links: Vec<String> = links
+        .into_iter()
+        .map(|link| {
+            if link.starts_with("file://") {
+                link.trim_start_matches("file://").to_string()
+            } else {
+                link
+            }
+        })
+        .collect();
+
+    Ok(cleaned_links)
+}
+
+/// Extract external workbooks with indices from ODS file
+/// Returns a vector of ExternalWorkbook where index is 0-based (order of appearance)
+/// Only extracts from metadata (table:table-source), not from formulas. Each linked
+/// sheet is a hidden table whose cells are the cached values.
+pub fn extract_external_workbooks_ods(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<Vec<ExternalWorkbook>> {
+    let mut workbooks = OdsExternalWorkbooks::new(extract_date_system_from_ods(archive)?);
+    scan_ods_content(archive, &mut [&mut workbooks])?;
+    Ok(workbooks.workbooks)
+}
+
+/// Linked workbooks and the cached cells of their sheets
+struct OdsExternalWorkbooks {
+    date_system: DateSystem,
+    workbooks: Vec<ExternalWorkbook>,
+    path_to_index: HashMap<String, usize>,
+    /// Workbook and sheet index of the linked sheet being read
+    linked: Option<(usize, usize)>,
+    row: u32,
+    col: u32,
+    row_repeated: u32,
+    /// Column repeat count and value of the cell being read, and whether its
+    /// text comes from its paragraphs
+    cell: Option<(u32, Option<CellValue>, bool)>,
+}
+
+impl OdsExternalWorkbooks {
+    fn new(date_system: DateSystem) -> Self {
+        Self {
+            date_system,
+            workbooks: Vec::new(),
+            path_to_index: HashMap::new(),
+            linked: None,
+            row: 0,
+            col: 0,
+            row_repeated: 1,
+            cell: None,
+        }
+    }
+}
+
+impl OdsContentScan for OdsExternalWorkbooks {
+    fn event(&mut self, event: &Event<'_>) -> Result<()> {
+        let (e, has_children) = match event {
+            Event::Start(e) => (e, true),
+            Event::Empty(e) => (e, false),
+            Event::Text(t) => {
+                if let Some((_, Some(CellValue::Text(text, _)), true)) = self.cell.as_mut() {
+                    if !text.is_empty() {
+                        text.push('\n');
+                    }
+                    text.push_str(&t.unescape()?);
+                }
+                return Ok(());
+            }
+            Event::End(e) => {
+                match e.name().as_ref() {
+                    b"table:table" => self.linked = None,
+                    b"table:table-row" if self.linked.is_some() => self.row += self.row_repeated,
+                    b"table:table-cell" | b"table:covered-table-cell" => {
+                        if let Some((repeated, value, _)) = self.cell.take() {
+                            store_linked_cell(
+                                &mut self.workbooks,
+                                self.linked,
+                                (self.row, self.col),
+                                self.row_repeated,
+                                repeated,
+                                value,
+                            );
+                            self.col += repeated;
                         }
                     }
+                    _ => {}
                 }
-                Event::Start(e) if e.name().as_ref() == b"table:table-row" => {
-                    row_repeated = 1;
-                    current_col = 0;
-                    if let Some(ref mut sheet) = current_sheet {
-                        let mut hidden = false;
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"table:number-rows-repeated" => {
-                                    row_repeated =
-                                        attr.unescape_value()?.parse::<u32>().unwrap_or(1);
-                                }
-                                b"table:visibility" => {
-                                    if attr.value.as_ref() == b"collapse"
-                                        || attr.value.as_ref() == b"filter"
-                                    {
-                                        hidden = true;
-                                    }
-                                }
-                                _ => {}
-                            }
+                return Ok(());
+            }
+            _ => return Ok(()),
+        };
+        match e.name().as_ref() {
+            // Check table:table-source elements for external workbook references
+            b"table:table-source" => {
+                let Some(target) = ods_attr(e, b"xlink:href")? else {
+                    return Ok(());
+                };
+                let basename = super::parser_utils::extract_basename(&target);
+
+                // Only add if we haven't seen this basename before
+                let index = *self
+                    .path_to_index
+                    .entry(basename.clone())
+                    .or_insert_with(|| {
+                        self.workbooks.push(ExternalWorkbook {
+                            index: self.workbooks.len(),
+                            path: basename,
+                            target,
+                            sheets: Vec::new(),
+                        });
+                        self.workbooks.len() - 1
+                    });
+                let sheets = &mut self.workbooks[index].sheets;
+                sheets.push(ExternalSheet {
+                    name: ods_attr(e, b"table:table-name")?.unwrap_or_default(),
+                    ..Default::default()
+                });
+                self.linked = Some((index, sheets.len() - 1));
+                (self.row, self.col) = (0, 0);
+            }
+            b"table:table-row" if self.linked.is_some() => {
+                self.row_repeated = ods_attr(e, b"table:number-rows-repeated")?
+                    .and_then(|repeated| repeated.parse().ok())
+                    .unwrap_or(1);
+                self.col = 0;
+                if !has_children {
+                    self.row += self.row_repeated;
+                }
+            }
+            b"table:table-cell" | b"table:covered-table-cell" if self.linked.is_some() => {
+                let repeated = ods_attr(e, b"table:number-columns-repeated")?
+                    .and_then(|repeated| repeated.parse().ok())
+                    .unwrap_or(1);
+                let value_type = ods_attr(e, b"office:value-type")?;
+                let string_value = ods_attr(e, b"office:string-value")?;
+                let from_paragraphs =
+                    value_type.as_deref() == Some("string") && string_value.is_none();
+                let value = match value_type.as_deref() {
+                    Some("float" | "percentage" | "currency") => ods_attr(e, b"office:value")?
+                        .and_then(|value| value.parse().ok())
+                        .map(CellValue::Number),
+                    Some("boolean") => Some(CellValue::Boolean(
+                        ods_attr(e, b"office:boolean-value")?.as_deref() == Some("true"),
+                    )),
+                    Some("date") => ods_attr(e, b"office:date-value")?.map(|date| {
+                        parse_ods_date(&date, self.date_system)
+                            .map_or(CellValue::Text(date, None), CellValue::Number)
+                    }),
+                    Some("time") => {
+                        ods_attr(e, b"office:time-value")?.map(|time| CellValue::Text(time, None))
+                    }
+                    Some(_) => Some(CellValue::Text(string_value.unwrap_or_default(), None)),
+                    None => None,
+                };
+                if has_children {
+                    self.cell = Some((repeated, value, from_paragraphs));
+                } else {
+                    store_linked_cell(
+                        &mut self.workbooks,
+                        self.linked,
+                        (self.row, self.col),
+                        self.row_repeated,
+                        repeated,
+                        value,
+                    );
+                    self.col += repeated;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Store a cached cell of a linked sheet over the rows and columns it repeats
+/// across; empty cells are left out
+fn store_linked_cell(
+    workbooks: &mut [ExternalWorkbook],
+    linked: Option<(usize, usize)>,
+    (row, col): (u32, u32),
+    rows: u32,
+    columns: u32,
+    value: Option<CellValue>,
+) {
+    let (Some((workbook, sheet)), Some(value)) = (linked, value) else {
+        return;
+    };
+    if matches!(&value, CellValue::Text(text, _) if text.is_empty()) {
+        return;
+    }
+    let cells = &mut workbooks[workbook].sheets[sheet].cells;
+    for r in row..row.saturating_add(rows) {
+        for c in col..col.saturating_add(columns) {
+            cells.insert((r, c), value.clone());
+        }
+    }
+}
+
+/// Extract cached error values from an ODS worksheet
+/// ODS error values are often stored in calcext:value-type="error" and calcext:value="#ERROR!"
+pub fn extract_cached_errors_from_ods(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+    sheet_name: &str,
+) -> Result<std::collections::HashMap<(u32, u32), String>> {
+    use std::collections::HashMap;
+    let mut errors = HashMap::new();
+
+    // ODS stores all sheets in content.xml
+    let content_xml = match archive.by_name("content.xml") {
+        Ok(file) => file,
+        Err(_) => return Ok(errors),
+    };
+
+    let buf_reader = BufReader::new(content_xml);
+    let mut reader = Reader::from_reader(buf_reader);
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut in_target_sheet = false;
+    let mut current_row = 0u32;
+    let mut current_col = 0u32;
+    let mut current_row_repeated = 1u32;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => match e.name().as_ref() {
+                b"table:table" => {
+                    let mut name = String::new();
+                    for attr in e.attributes().flatten() {
+                        if attr.key.as_ref() == b"table:name" {
+                            name = attr.unescape_value()?.to_string();
                         }
+                    }
 
-                        if hidden {
-                            for i in 0..row_repeated {
-                                sheet.hidden_rows.push(current_row + i);
-                            }
-                        } else {
-                            // Map visible row numbers to XML row indices
-                            for i in 0..row_repeated {
-                                visible_to_xml_row.insert(visible_row_counter, current_row + i);
-                                visible_row_counter += 1;
-                            }
+                    if name == sheet_name {
+                        in_target_sheet = true;
+                        current_row = 0;
+                        current_col = 0;
+                    } else if in_target_sheet {
+                        break;
+                    }
+                }
+                b"table:table-row" if in_target_sheet => {
+                    current_col = 0;
+                    current_row_repeated = 1;
+                    for attr in e.attributes() {
+                        if let Ok(attr) = attr
+                            && attr.key.as_ref() == b"table:number-rows-repeated"
+                            && let Ok(val) = attr.unescape_value()?.parse::<u32>()
+                        {
+                            current_row_repeated = val;
                         }
                     }
                 }
-                Event::Empty(e) if e.name().as_ref() == b"table:table-row" => {
-                    // Empty row (self-closing tag) - no cells, just increment row counter
-                    row_repeated = 1;
-                    if let Some(ref mut sheet) = current_sheet {
-                        let mut hidden = false;
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"table:number-rows-repeated" => {
-                                    row_repeated =
-                                        attr.unescape_value()?.parse::<u32>().unwrap_or(1);
+                b"table:table-cell" if in_target_sheet => {
+                    let mut repeated = 1u32;
+                    let mut is_error = false;
+                    let mut error_msg = String::new();
+
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"calcext:value-type" => {
+                                if attr.value.as_ref() == b"error" {
+                                    is_error = true;
                                 }
-                                b"table:visibility" => {
-                                    if attr.value.as_ref() == b"collapse"
-                                        || attr.value.as_ref() == b"filter"
-                                    {
-                                        hidden = true;
-                                    }
+                            }
+                            b"calcext:value" | b"office:string-value" => {
+                                if error_msg.is_empty() {
+                                    error_msg = attr.unescape_value()?.to_string();
                                 }
-                                _ => {}
                             }
-                        }
-                        if hidden {
-                            for r in 0..row_repeated {
-                                sheet.hidden_rows.push(current_row + r);
+                            b"table:number-columns-repeated" => {
+                                if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
+                                    repeated = val;
+                                }
                             }
-                        } else {
-                            // Map visible row numbers to XML row indices
-                            for r in 0..row_repeated {
-                                visible_to_xml_row.insert(visible_row_counter, current_row + r);
-                                visible_row_counter += 1;
+                            _ => {}
+                        }
+                    }
+
+                    if is_error && error_msg.is_empty() {
+                        error_msg = "#ERROR!".to_string();
+                    }
+
+                    if is_error && !error_msg.is_empty() {
+                        for i in 0..repeated {
+                            for r in 0..current_row_repeated {
+                                errors
+                                    .insert((current_row + r, current_col + i), error_msg.clone());
                             }
                         }
                     }
-                    current_row += row_repeated;
-                    current_col = 0;
+                    current_col += repeated;
                 }
-                Event::Start(e)
-                    if e.name().as_ref() == b"table:table-cell"
-                        || e.name().as_ref() == b"table:covered-table-cell" =>
-                {
-                    if let Some(ref mut sheet) = current_sheet {
-                        let mut col_repeated = 1u32;
-                        let mut cols_spanned = 1u32;
-                        let mut rows_spanned = 1u32;
-                        let mut formula = None;
-                        let mut value = CellValue::Empty;
-                        let mut has_value = false;
-                        let mut is_error_cell = false;
-                        let mut style_name = String::new();
-
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"table:number-columns-repeated" => {
-                                    col_repeated =
-                                        attr.unescape_value()?.parse::<u32>().unwrap_or(1);
-                                }
-                                b"table:number-columns-spanned" => {
-                                    cols_spanned =
-                                        attr.unescape_value()?.parse::<u32>().unwrap_or(1);
-                                }
-                                b"table:number-rows-spanned" => {
-                                    rows_spanned =
-                                        attr.unescape_value()?.parse::<u32>().unwrap_or(1);
+                _ => {}
+            },
+            Ok(Event::Empty(e)) => match e.name().as_ref() {
+                b"table:table-row" if in_target_sheet => {
+                    let mut repeated = 1u32;
+                    for attr in e.attributes() {
+                        if let Ok(attr) = attr
+                            && attr.key.as_ref() == b"table:number-rows-repeated"
+                            && let Ok(val) = attr.unescape_value()?.parse::<u32>()
+                        {
+                            repeated = val;
+                        }
+                    }
+                    current_row += repeated;
+                }
+                b"table:table-cell" if in_target_sheet => {
+                    let mut repeated = 1u32;
+                    let mut is_error = false;
+                    let mut error_msg = String::new();
+
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"calcext:value-type" => {
+                                if attr.value.as_ref() == b"error" {
+                                    is_error = true;
                                 }
-                                b"table:formula" => {
-                                    let raw_formula = attr.unescape_value()?;
-                                    let normalized = normalize_ods_reference(
-                                        &raw_formula,
-                                        false,
-                                        Some(&visible_to_xml_row),
-                                        Some(&sheet.name),
-                                    );
-                                    // Apply external workbook normalization
-                                    formula = Some(normalize_ods_external_refs(
-                                        &normalized,
-                                        &external_workbooks,
-                                    ));
+                            }
+                            b"calcext:value" | b"office:string-value" => {
+                                if error_msg.is_empty() {
+                                    error_msg = attr.unescape_value()?.to_string();
                                 }
-                                b"table:style-name" => {
-                                    style_name = attr.unescape_value()?.to_string();
+                            }
+                            b"table:number-columns-repeated" => {
+                                if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
+                                    repeated = val;
                                 }
-                                b"calcext:value-type" => {
-                                    if attr.value.as_ref() == b"error" {
-                                        is_error_cell = true;
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    if is_error && error_msg.is_empty() {
+                        error_msg = "#ERROR!".to_string();
+                    }
+
+                    if is_error && !error_msg.is_empty() {
+                        for i in 0..repeated {
+                            for r in 0..current_row_repeated {
+                                errors
+                                    .insert((current_row + r, current_col + i), error_msg.clone());
+                            }
+                        }
+                    }
+                    current_col += repeated;
+                }
+                _ => {}
+            },
+            Ok(Event::End(e)) => {
+                match e.name().as_ref() {
+                    b"table:table-row" if in_target_sheet => {
+                        current_row += current_row_repeated;
+                    }
+                    b"table:table" if in_target_sheet => {
+                        break; // Found our sheet, done
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(anyhow::anyhow!("XML parsing error: {}", e)),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(errors)
+}
+
+/// Convert an ISO `office:date-value` to a serial in the document's date system
+fn parse_ods_date(date_str: &str, date_system: DateSystem) -> Option<f64> {
+    date_system.to_serial(&DateTime::parse_iso(date_str)?)
+}
+
+/// Extract date styles from ODS content.xml and styles.xml
+/// Returns a map of style_name -> excel_format_string
+///
+/// # Automatic Order Handling
+///
+/// When `number:automatic-order="true"` is set on a `number:date-style` element,
+/// this function treats day and month components as two-digit format (equivalent to
+/// `number:style="long"`) when no explicit `number:style` attribute is present.
+///
+/// This behavior aligns with:
+/// - Unicode CLDR standard patterns where `dd` and `MM` are standard for locale-aware formatting
+/// - ISO 8601 requirements for two-digit padding in international date representation
+/// - Observed behavior in LibreOffice Calc and other ODF-compliant applications
+///
+/// # Arguments
+///
+/// * `archive` - Mutable reference to the ODS ZIP archive
+///
+/// # Returns
+///
+/// A HashMap mapping style names to their Excel-compatible format strings
+pub fn extract_date_styles_from_ods(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<std::collections::HashMap<String, String>> {
+    use std::collections::HashMap;
+
+    // Map: Data Style Name -> Format String (e.g., "N49" -> "dd/mm/yyyy")
+    let mut data_styles = HashMap::new();
+    // Map: Cell Style Name -> Data Style Name (e.g., "ce14" -> "N49")
+    let mut cell_styles = HashMap::new();
+
+    // Helper to parse a styles file (content.xml or styles.xml)
+    let mut parse_styles_file = |file: std::io::BufReader<zip::read::ZipFile>| -> Result<()> {
+        let mut reader = Reader::from_reader(file);
+        reader.config_mut().trim_text(false);
+
+        let mut buf = Vec::new();
+        let mut current_data_style_name = String::new();
+        let mut current_format = String::new();
+        let mut in_date_style = false;
+        let mut automatic_order = false;
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(e) => {
+                    match e.name().as_ref() {
+                        b"number:date-style" => {
+                            in_date_style = true;
+                            current_format.clear();
+                            automatic_order = false;
+                            for attr in e.attributes().flatten() {
+                                match attr.key.as_ref() {
+                                    b"style:name" => {
+                                        current_data_style_name =
+                                            attr.unescape_value()?.to_string();
                                     }
-                                }
-                                b"office:value"
-                                | b"office:string-value"
-                                | b"office:boolean-value"
-                                | b"office:date-value" => {
-                                    let val_str = attr.unescape_value()?.to_string();
-                                    if !has_value {
-                                        value = match attr.key.as_ref() {
-                                            b"office:value" => {
-                                                if let Ok(n) = val_str.parse::<f64>() {
-                                                    CellValue::Number(n)
-                                                } else {
-                                                    CellValue::Text(val_str)
-                                                }
-                                            }
-                                            b"office:date-value" => {
-                                                // Convert ISO date to Serial Number
-                                                if let Some(n) = parse_ods_date(&val_str) {
-                                                    CellValue::Number(n)
-                                                } else {
-                                                    CellValue::Text(val_str)
-                                                }
-                                            }
-                                            b"office:boolean-value" => {
-                                                CellValue::Boolean(val_str == "true")
-                                            }
-                                            _ => CellValue::Text(val_str),
-                                        };
-                                        has_value = true;
+                                    b"number:automatic-order" => {
+                                        if attr.value.as_ref() == b"true" {
+                                            automatic_order = true;
+                                        }
                                     }
+                                    _ => {}
                                 }
-                                _ => {}
                             }
                         }
-
-                        if cols_spanned > 1 || rows_spanned > 1 {
-                            sheet.merged_cells.push((
-                                current_row,
-                                current_col,
-                                current_row + rows_spanned - 1,
-                                current_col + cols_spanned - 1,
-                            ));
+                        b"number:text-style" => {
+                            // Text format style (equivalent to "@" in XLSX)
+                            for attr in e.attributes().flatten() {
+                                if attr.key.as_ref() == b"style:name" {
+                                    let style_name = attr.unescape_value()?.to_string();
+                                    // Mark this as a text format with "@"
+                                    data_styles.insert(style_name, "@".to_string());
+                                }
+                            }
                         }
+                        b"style:style" => {
+                            let mut is_cell_style = false;
+                            let mut style_name = String::new();
+                            let mut data_style_name = String::new();
 
-                        // Read text content from <text:p> elements
-                        // This handles both error cells and regular text cells
-                        let mut text_content = String::new();
-                        let mut text_buf = Vec::new();
-                        loop {
-                            match reader.read_event_into(&mut text_buf)? {
-                                Event::Start(ref te) if te.name().as_ref() == b"text:p" => {
-                                    let mut p_buf = Vec::new();
-                                    loop {
-                                        match reader.read_event_into(&mut p_buf)? {
-                                            Event::Text(ref t) => {
-                                                text_content.push_str(t.unescape()?.as_ref());
-                                            }
-                                            Event::End(ref pe)
-                                                if pe.name().as_ref() == b"text:p" =>
-                                            {
-                                                break;
-                                            }
-                                            Event::Eof => break,
-                                            _ => {}
+                            for attr in e.attributes().flatten() {
+                                match attr.key.as_ref() {
+                                    b"style:family" => {
+                                        if attr.value.as_ref() == b"table-cell" {
+                                            is_cell_style = true;
                                         }
-                                        p_buf.clear();
                                     }
+                                    b"style:name" => {
+                                        style_name = attr.unescape_value()?.to_string();
+                                    }
+                                    b"style:data-style-name" => {
+                                        data_style_name = attr.unescape_value()?.to_string();
+                                    }
+                                    _ => {}
                                 }
-                                Event::End(ref te)
-                                    if te.name().as_ref() == b"table:table-cell"
-                                        || te.name().as_ref() == b"table:covered-table-cell" =>
-                                {
-                                    break;
+                            }
+
+                            if is_cell_style
+                                && !style_name.is_empty()
+                                && !data_style_name.is_empty()
+                            {
+                                cell_styles.insert(style_name, data_style_name);
+                            }
+                        }
+                        b"number:day" if in_date_style => {
+                            let mut long = automatic_order;
+                            for attr in e.attributes().flatten() {
+                                if attr.key.as_ref() == b"number:style" {
+                                    long = attr.value.as_ref() == b"long";
                                 }
-                                Event::Eof => break,
-                                _ => {}
                             }
-                            text_buf.clear();
+                            current_format.push_str(if long { "dd" } else { "d" });
                         }
+                        b"number:month" if in_date_style => {
+                            let mut long = automatic_order;
+                            let mut textual = false;
+                            for attr in e.attributes().flatten() {
+                                match attr.key.as_ref() {
+                                    b"number:style" => {
+                                        long = attr.value.as_ref() == b"long";
+                                    }
+                                    b"number:textual" => {
+                                        if attr.value.as_ref() == b"true" {
+                                            textual = true;
+                                        }
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            if textual {
+                                current_format.push_str(if long { "mmmm" } else { "mmm" });
+                            } else {
+                                current_format.push_str(if long { "mm" } else { "m" });
+                            }
+                        }
+                        b"number:year" if in_date_style => {
+                            let mut long = false;
+                            for attr in e.attributes().flatten() {
+                                if attr.key.as_ref() == b"number:style"
+                                    && attr.value.as_ref() == b"long"
+                                {
+                                    long = true;
+                                }
+                            }
+                            current_format.push_str(if long { "yyyy" } else { "yy" });
+                        }
+                        b"number:hours" if in_date_style => {
+                            current_format.push_str("hh");
+                        }
+                        b"number:minutes" if in_date_style => {
+                            current_format.push_str("mm");
+                        }
+                        b"number:seconds" if in_date_style => {
+                            current_format.push_str("ss");
+                        }
+                        b"number:text" if in_date_style => {
+                            // Will read text event next
+                        }
+                        _ => {}
+                    }
+                }
+                Event::Empty(e) => match e.name().as_ref() {
+                    b"number:day" if in_date_style => {
+                        let mut long = automatic_order;
+                        for attr in e.attributes().flatten() {
+                            if attr.key.as_ref() == b"number:style" {
+                                long = attr.value.as_ref() == b"long";
+                            }
+                        }
+                        current_format.push_str(if long { "dd" } else { "d" });
+                    }
+                    b"number:month" if in_date_style => {
+                        let mut long = automatic_order;
+                        let mut textual = false;
+                        for attr in e.attributes().flatten() {
+                            match attr.key.as_ref() {
+                                b"number:style" => {
+                                    long = attr.value.as_ref() == b"long";
+                                }
+                                b"number:textual" => {
+                                    if attr.value.as_ref() == b"true" {
+                                        textual = true;
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
+                        if textual {
+                            current_format.push_str(if long { "mmmm" } else { "mmm" });
+                        } else {
+                            current_format.push_str(if long { "mm" } else { "m" });
+                        }
+                    }
+                    b"number:year" if in_date_style => {
+                        let mut long = false;
+                        for attr in e.attributes().flatten() {
+                            if attr.key.as_ref() == b"number:style"
+                                && attr.value.as_ref() == b"long"
+                            {
+                                long = true;
+                            }
+                        }
+                        current_format.push_str(if long { "yyyy" } else { "yy" });
+                    }
+                    b"number:hours" if in_date_style => {
+                        current_format.push_str("hh");
+                    }
+                    b"number:minutes" if in_date_style => {
+                        current_format.push_str("mm");
+                    }
+                    b"number:seconds" if in_date_style => {
+                        current_format.push_str("ss");
+                    }
+                    _ => {}
+                },
+                Event::Text(e) if in_date_style => {
+                    current_format.push_str(e.unescape()?.as_ref());
+                }
+                Event::End(e) => {
+                    if e.name().as_ref() == b"number:date-style" {
+                        if !current_data_style_name.is_empty() {
+                            data_styles
+                                .insert(current_data_style_name.clone(), current_format.clone());
+                        }
+                        in_date_style = false;
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+        Ok(())
+    };
+
+    // 1. Read automatic styles from content.xml
+    if let Ok(file) = archive.by_name("content.xml") {
+        parse_styles_file(BufReader::new(file))?;
+    }
+
+    // 2. Read styles from styles.xml (global styles)
+    if let Ok(file) = archive.by_name("styles.xml") {
+        parse_styles_file(BufReader::new(file))?;
+    }
+
+    // 3. Resolve Cell Styles to Format Strings
+    let mut resolved_styles = HashMap::new();
+    for (cell_style, data_style) in cell_styles {
+        if let Some(format) = data_styles.get(&data_style) {
+            resolved_styles.insert(cell_style, format.clone());
+        }
+    }
+
+    // Also include data styles directly, just in case
+    for (data_style, format) in data_styles {
+        resolved_styles.entry(data_style).or_insert(format);
+    }
+
+    Ok(resolved_styles)
+}
+
+/// Read the cell style table from the default, common (styles.xml) and automatic
+/// (content.xml) `table-cell` styles, applying parent styles.
+///
+/// Returns the table, whose entry 0 is the default style, and the index of each
+/// style name. Indices follow style names in sorted order, so they are stable
+/// across reads of the same file.
+pub fn extract_cell_styles_from_ods(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<(Vec<CellStyle>, HashMap<String, usize>)> {
+    // Style name -> (parent name, flattened property attributes)
+    let mut raw: BTreeMap<String, (Option<String>, HashMap<String, String>)> = BTreeMap::new();
+    let mut defaults: HashMap<String, String> = HashMap::new();
+
+    for part in ["styles.xml", "content.xml"] {
+        let file = match archive.by_name(part) {
+            Ok(file) => file,
+            Err(_) => continue,
+        };
+        let mut reader = Reader::from_reader(BufReader::new(file));
+        reader.config_mut().trim_text(true);
+
+        // Name of the style being read, or `None` for the default style
+        let mut current: Option<Option<String>> = None;
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(e) | Event::Empty(e)
+                    if matches!(e.name().as_ref(), b"style:style" | b"style:default-style") =>
+                {
+                    let mut name = None;
+                    let mut parent = None;
+                    let mut family = String::new();
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"style:name" => name = Some(attr.unescape_value()?.to_string()),
+                            b"style:parent-style-name" => {
+                                parent = Some(attr.unescape_value()?.to_string())
+                            }
+                            b"style:family" => family = attr.unescape_value()?.to_string(),
+                            _ => {}
+                        }
+                    }
+                    current = None;
+                    if family == "table-cell" {
+                        match name {
+                            Some(name) => {
+                                raw.insert(name.clone(), (parent, HashMap::new()));
+                                current = Some(Some(name));
+                            }
+                            None if e.name().as_ref() == b"style:default-style" => {
+                                current = Some(None)
+                            }
+                            None => {}
+                        }
+                    }
+                }
+                Event::Start(e) | Event::Empty(e)
+                    if matches!(
+                        e.name().as_ref(),
+                        b"style:table-cell-properties"
+                            | b"style:paragraph-properties"
+                            | b"style:text-properties"
+                    ) =>
+                {
+                    let properties = match &current {
+                        Some(Some(name)) => raw.get_mut(name).map(|(_, props)| props),
+                        Some(None) => Some(&mut defaults),
+                        None => None,
+                    };
+                    if let Some(properties) = properties {
+                        for attr in e.attributes().flatten() {
+                            properties.insert(
+                                String::from_utf8_lossy(attr.key.as_ref()).to_string(),
+                                attr.unescape_value()?.to_string(),
+                            );
+                        }
+                    }
+                }
+                Event::End(e)
+                    if matches!(e.name().as_ref(), b"style:style" | b"style:default-style") =>
+                {
+                    current = None;
+                }
+                // Styles precede the document body
+                Event::Start(e) if e.name().as_ref() == b"office:body" => break,
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+    }
+
+    // Properties of a style after applying its ancestors over the default style
+    let resolve = |name: &str| {
+        let mut chain = Vec::new();
+        let mut next = Some(name);
+        while let Some(name) = next {
+            match raw.get(name) {
+                // Guard against parent cycles
+                Some(entry) if chain.len() < 32 => {
+                    chain.push(&entry.1);
+                    next = entry.0.as_deref();
+                }
+                _ => break,
+            }
+        }
+        let mut properties = defaults.clone();
+        for props in chain.into_iter().rev() {
+            properties.extend(props.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        ods_cell_style(&properties)
+    };
+
+    let mut styles = vec![resolve("Default")];
+    let mut indices = HashMap::new();
+    for name in raw.keys() {
+        if name == "Default" {
+            indices.insert(name.clone(), 0);
+        } else {
+            indices.insert(name.clone(), styles.len());
+            styles.push(resolve(name));
+        }
+    }
+    Ok((styles, indices))
+}
+
+/// Fonts of the `text` styles that `text:span`s name, by style name. Only the
+/// properties a style sets are filled in; parent styles are not applied, as
+/// span styles are automatic styles without parents.
+pub fn extract_text_styles_from_ods(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<HashMap<String, Font>> {
+    let mut fonts = HashMap::new();
+    for part in ["styles.xml", "content.xml"] {
+        let file = match archive.by_name(part) {
+            Ok(file) => file,
+            Err(_) => continue,
+        };
+        let mut reader = Reader::from_reader(BufReader::new(file));
+        reader.config_mut().trim_text(true);
+
+        let mut current = None;
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"style:style" => {
+                    current = match ods_attr(&e, b"style:family")?.as_deref() {
+                        Some("text") => ods_attr(&e, b"style:name")?,
+                        _ => None,
+                    };
+                }
+                Event::Start(e) | Event::Empty(e)
+                    if e.name().as_ref() == b"style:text-properties" =>
+                {
+                    if let Some(name) = current.take() {
+                        let mut properties = HashMap::new();
+                        for attr in e.attributes().flatten() {
+                            properties.insert(
+                                String::from_utf8_lossy(attr.key.as_ref()).to_string(),
+                                attr.unescape_value()?.to_string(),
+                            );
+                        }
+                        fonts.insert(name, ods_cell_style(&properties).font);
+                    }
+                }
+                Event::End(e) if e.name().as_ref() == b"style:style" => current = None,
+                // Styles precede the document body
+                Event::Start(e) if e.name().as_ref() == b"office:body" => break,
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+    }
+    Ok(fonts)
+}
+
+/// Build a style from flattened ODS cell, paragraph and text properties
+fn ods_cell_style(properties: &HashMap<String, String>) -> CellStyle {
+    let get = |key: &str| properties.get(key).map(String::as_str);
+    let mut style = CellStyle::default();
+
+    let font = &mut style.font;
+    font.name = get("style:font-name")
+        .or_else(|| get("fo:font-family"))
+        .map(|name| name.trim_matches('\'').to_string());
+    font.size = get("fo:font-size").and_then(ods_length_in_points);
+    font.bold = get("fo:font-weight")
+        .is_some_and(|w| w == "bold" || w.parse::<u32>().is_ok_and(|w| w >= 600));
+    font.italic = get("fo:font-style").is_some_and(|s| s == "italic" || s == "oblique");
+    font.underline = get("style:text-underline-style").is_some_and(|s| s != "none");
+    font.strikethrough = get("style:text-line-through-style").is_some_and(|s| s != "none");
+    if get("style:use-window-font-color") != Some("true") {
+        font.color = get("fo:color").and_then(parse_hex_color);
+    }
+
+    style.fill.color = get("fo:background-color").and_then(parse_hex_color);
+
+    let edge = |side: &str| {
+        get(&format!("fo:border-{}", side))
+            .or_else(|| get("fo:border"))
+            .and_then(ods_border_line)
+    };
+    style.border = Border {
+        left: edge("left"),
+        right: edge("right"),
+        top: edge("top"),
+        bottom: edge("bottom"),
+    };
+
+    let alignment = &mut style.alignment;
+    if get("style:text-align-source") != Some("value-type") {
+        alignment.horizontal = get("fo:text-align").map(|align| {
+            match align {
+                "start" => "left",
+                "end" => "right",
+                other => other,
+            }
+            .to_string()
+        });
+    }
+    alignment.vertical = match get("style:vertical-align") {
+        Some("top") => Some("top".to_string()),
+        Some("middle") => Some("center".to_string()),
+        _ => None,
+    };
+    alignment.wrap_text = get("fo:wrap-option") == Some("wrap");
+    alignment.shrink_to_fit = get("style:shrink-to-fit") == Some("true");
+    alignment.rotation = get("style:rotation-angle")
+        .and_then(|angle| angle.trim_end_matches("deg").parse::<f64>().ok())
+        .map(|angle| {
+            let angle = angle.rem_euclid(360.0).round() as i32;
+            if angle > 180 { angle - 360 } else { angle }
+        })
+        .unwrap_or(0);
+
+    if let Some(protect) = get("style:cell-protect") {
+        style.protection.locked = protect.contains("protected");
+        style.protection.hidden = protect.contains("hidden");
+    }
+
+    style
+}
+
+/// Parse a border such as `0.74pt solid #000000` into an XLSX-style line
+fn ods_border_line(spec: &str) -> Option<BorderLine> {
+    let mut width = None;
+    let mut line = None;
+    let mut color = None;
+    for token in spec.split_whitespace() {
+        if token.starts_with('#') {
+            color = parse_hex_color(token);
+        } else if token.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+            width = ods_length_in_points(token);
+        } else {
+            line = Some(token);
+        }
+    }
+
+    let style = match line? {
+        "none" | "hidden" => return None,
+        "double" | "double-thin" => "double",
+        "dotted" => "dotted",
+        "dashed" | "fine-dashed" => "dashed",
+        "dash-dot" => "dashDot",
+        "dash-dot-dot" => "dashDotDot",
+        _ => match width.unwrap_or(0.75) {
+            w if w < 0.5 => "hair",
+            w if w < 1.25 => "thin",
+            w if w < 2.25 => "medium",
+            _ => "thick",
+        },
+    };
+    Some(BorderLine {
+        style: style.to_string(),
+        color,
+    })
+}
+
+/// Convert an ODS length (`10pt`, `0.26mm`, `0.1in`, ...) to points
+fn ods_length_in_points(length: &str) -> Option<f64> {
+    let split = length
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+        .unwrap_or(length.len());
+    let (value, unit) = length.split_at(split);
+    let value = value.parse::<f64>().ok()?;
+    let factor = match unit {
+        "pt" | "" => 1.0,
+        "mm" => 72.0 / 25.4,
+        "cm" => 72.0 / 2.54,
+        "in" => 72.0,
+        "pc" => 12.0,
+        "px" => 0.75,
+        _ => return None,
+    };
+    Some(value * factor)
+}
+
+/// Extract formulas from an ODS worksheet
+/// ODS formulas are stored in table:formula attribute
+pub fn extract_formulas_from_ods(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+    sheet_name: &str,
+) -> Result<std::collections::HashMap<(u32, u32), String>> {
+    use std::collections::HashMap;
+    let mut formulas = HashMap::new();
+
+    // ODS stores all sheets in content.xml
+    let content_xml = match archive.by_name("content.xml") {
+        Ok(file) => file,
+        Err(_) => return Ok(formulas),
+    };
+
+    let buf_reader = BufReader::new(content_xml);
+    let mut reader = Reader::from_reader(buf_reader);
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut in_target_sheet = false;
+    let mut current_row = 0u32;
+    let mut current_col = 0u32;
+    let mut current_row_repeated = 1u32;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => match e.name().as_ref() {
+                b"table:table" => {
+                    let mut name = String::new();
+                    for attr in e.attributes().flatten() {
+                        if attr.key.as_ref() == b"table:name" {
+                            name = attr.unescape_value()?.to_string();
+                        }
+                    }
+
+                    if name == sheet_name {
+                        in_target_sheet = true;
+                        current_row = 0;
+                        current_col = 0;
+                    } else if in_target_sheet {
+                        break;
+                    }
+                }
+                b"table:table-row" if in_target_sheet => {
+                    current_col = 0;
+                    current_row_repeated = 1;
+                    for attr in e.attributes() {
+                        if let Ok(attr) = attr
+                            && attr.key.as_ref() == b"table:number-rows-repeated"
+                            && let Ok(val) = attr.unescape_value()?.parse::<u32>()
+                        {
+                            current_row_repeated = val;
+                        }
+                    }
+                }
+                b"table:table-cell" | b"table:covered-table-cell" if in_target_sheet => {
+                    let mut repeated = 1u32;
+                    let mut formula = String::new();
+
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"table:formula" => {
+                                formula = attr.unescape_value()?.to_string();
+                            }
+                            b"table:number-columns-repeated" => {
+                                if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
+                                    repeated = val;
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    // Only extract formulas from non-repeated cells.
+                    // Repeated cells should be handled by calamine, which correctly adjusts
+                    // cell references. Copying the same formula to repeated cells without
+                    // adjustment causes false circular references (e.g., G721's formula
+                    // copied to G731 without adjusting references).
+                    if !formula.is_empty() && repeated == 1 && current_row_repeated == 1 {
+                        // ODS formulas usually start with "of:=". We keep it raw here and normalize later.
+                        formulas.insert((current_row, current_col), formula);
+                    }
+                    current_col += repeated;
+                }
+                _ => {}
+            },
+            Ok(Event::Empty(e)) => match e.name().as_ref() {
+                b"table:table-row" if in_target_sheet => {
+                    let mut repeated = 1u32;
+                    for attr in e.attributes() {
+                        if let Ok(attr) = attr
+                            && attr.key.as_ref() == b"table:number-rows-repeated"
+                            && let Ok(val) = attr.unescape_value()?.parse::<u32>()
+                        {
+                            repeated = val;
+                        }
+                    }
+                    current_row += repeated;
+                }
+                b"table:table-cell" | b"table:covered-table-cell" if in_target_sheet => {
+                    let mut repeated = 1u32;
+                    let mut formula = String::new();
+
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"table:formula" => {
+                                formula = attr.unescape_value()?.to_string();
+                            }
+                            b"table:number-columns-repeated" => {
+                                if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
+                                    repeated = val;
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    // Only extract formulas from non-repeated cells (see comment above)
+                    if !formula.is_empty() && repeated == 1 && current_row_repeated == 1 {
+                        formulas.insert((current_row, current_col), formula);
+                    }
+                    current_col += repeated;
+                }
+                _ => {}
+            },
+            Ok(Event::End(e)) => {
+                match e.name().as_ref() {
+                    b"table:table-row" if in_target_sheet => {
+                        current_row += current_row_repeated;
+                    }
+                    b"table:table" if in_target_sheet => {
+                        break; // Found our sheet, done
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(anyhow::anyhow!("XML parsing error: {}", e)),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(formulas)
+}
+/// Normalize ODS references (formulas, ranges, etc.) to a format consistent with XLSX.
+/// Strips "of:=" prefix, handles sheet-qualified references, and cleans up local sheet names.
+///
+/// If `visible_to_xml_row` is provided, converts visible row numbers (1-indexed, used in ODS formulas)
+/// to XML row numbers (0-indexed, used internally). This accounts for hidden rows.
+/// The mapping is ONLY applied to references pointing to `current_sheet_name` (or local references).
+pub fn normalize_ods_reference(
+    reference: &str,
+    preserve_sheet: bool,
+    visible_to_xml_row: Option<&HashMap<u32, u32>>,
+    current_sheet_name: Option<&str>,
+) -> String {
+    use regex::Regex;
+    use std::sync::OnceLock;
+
+    static ODS_REL_REF: OnceLock<Regex> = OnceLock::new();
+    static ODS_COL_REF: OnceLock<Regex> = OnceLock::new();
+    static ODS_ROW_REF: OnceLock<Regex> = OnceLock::new();
+    static ODS_SHEET_REF: OnceLock<Regex> = OnceLock::new();
+    static ODS_SHEET_REF_NO_BRACKET: OnceLock<Regex> = OnceLock::new();
+    static ODS_RECT_REF: OnceLock<Regex> = OnceLock::new();
+
+    let mut normalized = reference
+        .strip_prefix("of:=")
+        .unwrap_or(reference)
+        .to_string();
+
+    // 0. Replace rectangular ranges [.A1:.B2] -> A1:B2
+    // Matches [.A1:.B2]
+    let rect_ref = ODS_RECT_REF
+        .get_or_init(|| Regex::new(r"\[\.(\$?[A-Z]+\$?[0-9]+):\.(\$?[A-Z]+\$?[0-9]+)\]").unwrap());
+    normalized = rect_ref.replace_all(&normalized, "$1:$2").to_string();
+
+    // 0b. Replace sheet-qualified ranges [$Sheet.A1:.A2] -> Sheet!A1:A2
+    // Matches [$Sheet.A1:.B2], [$Sheet.$A$1:.$B$2]
+    // The colon is followed by a dot for relative/absolute mixing in ODS
+    static ODS_SHEET_RANGE_REF: OnceLock<Regex> = OnceLock::new();
+    let sheet_range_ref = ODS_SHEET_RANGE_REF
+        .get_or_init(|| Regex::new(r"\[\$([^.]+)\.([A-Za-z0-9$]+):\.([A-Za-z0-9$]+)\]").unwrap());
+    normalized = sheet_range_ref
+        .replace_all(&normalized, "$1!$2:$3")
+        .to_string();
+
+    // 0b2. Replace unbracketed sheet-qualified ranges $Sheet.A1:.A2 -> Sheet!A1:A2
+    // Matches $Sheet.A1:.B2 (common in database-range targets)
+    // We exclude '[' from the sheet name capture to avoid matching relative column refs like [.A:.A]
+    static ODS_SHEET_RANGE_NO_BRACKET_REF: OnceLock<Regex> = OnceLock::new();
+    let sheet_range_no_bracket_ref = ODS_SHEET_RANGE_NO_BRACKET_REF
+        .get_or_init(|| Regex::new(r"\$?([^.\[]+)\.([A-Za-z0-9$]+):\.([A-Za-z0-9$]+)").unwrap());
+    normalized = sheet_range_no_bracket_ref
+        .replace_all(&normalized, "$1!$2:$3")
+        .to_string();
+
+    if !preserve_sheet {
+        // 0c. Replace plain sheet-level ranges Sheet1.A1:Sheet1.B2 -> A1:B2
+        // Matches Sheet1.A1:Sheet1.B2, Sheet1.A1, etc.
+        // This is common in ODS conditional formatting targets.
+        // We strip the sheet name if it's the same for both parts of the range.
+        // Updated to handle both '.' (ODS native) and '!' (normalized) separators
+        static ODS_LOCAL_RANGE_REF: OnceLock<Regex> = OnceLock::new();
+        let local_range_ref = ODS_LOCAL_RANGE_REF.get_or_init(|| {
+            Regex::new(r"([^.!]+)[.!]([A-Z0-9$]+):([^.!]+)[.!]([A-Z0-9$]+)").unwrap()
+        });
+        normalized = local_range_ref
+            .replace_all(&normalized, |caps: &regex::Captures| {
+                if caps[1] == caps[3] {
+                    if caps[2] == caps[4] {
+                        caps[2].to_string()
+                    } else {
+                        format!("{}:{}", &caps[2], &caps[4])
+                    }
+                } else {
+                    format!("{}!{}:{}!{}", &caps[1], &caps[2], &caps[3], &caps[4])
+                }
+            })
+            .to_string();
+
+        // 0d. Replace single local reference Sheet1.A1 -> A1
+        // Updated to handle both '.' and '!' separators
+        static ODS_LOCAL_SINGLE_REF: OnceLock<Regex> = OnceLock::new();
+        let local_single_ref =
+            ODS_LOCAL_SINGLE_REF.get_or_init(|| Regex::new(r"^([^.!]+)[.!]([A-Z0-9$]+)$").unwrap());
+        normalized = local_single_ref.replace_all(&normalized, "$2").to_string();
+
+        // 0e. Replace normalized sheet ranges Sheet1!A1:B2 -> A1:B2
+        // This handles cases where 0b/0b2 normalized the range to Excel format,
+        // but we want to strip the sheet name for local context parity.
+        static ODS_NORMALIZED_RANGE_REF: OnceLock<Regex> = OnceLock::new();
+        let normalized_range_ref = ODS_NORMALIZED_RANGE_REF
+            .get_or_init(|| Regex::new(r"^([^!]+)!([A-Z0-9$]+):([A-Z0-9$]+)$").unwrap());
+        normalized = normalized_range_ref
+            .replace_all(&normalized, "$2:$3")
+            .to_string();
+    }
+
+    // 1. Replace sheet references [$Sheet1.A1] -> Sheet1!A1 (BEFORE non-bracketed version)
+    // Matches [$Sheet.$A$1], [$Sheet.A1], etc.
+    // The cell reference part can have $ signs for absolute references
+    let sheet_ref =
+        ODS_SHEET_REF.get_or_init(|| Regex::new(r"\[\$([^.]+)\.(\$?[A-Z]+\$?[0-9]+)\]").unwrap());
+    normalized = sheet_ref.replace_all(&normalized, "$1!$2").to_string();
+
+    // 2. Replace $SHEETNAME.CELLREF -> SHEETNAME!CELLREF (without brackets)
+    // Matches $INGRESOS.BC$50, $Sheet1.A1, $Sheet.$A$1 etc.
+    // This comes AFTER bracketed version, so it won't match already-processed [$SHEET.CELL]
+    let sheet_ref_no_bracket = ODS_SHEET_REF_NO_BRACKET
+        .get_or_init(|| Regex::new(r"\$([A-Za-z0-9_]+)\.(\$?[A-Z]+\$?[0-9]+)").unwrap());
+    normalized = sheet_ref_no_bracket
+        .replace_all(&normalized, "$1!$2")
+        .to_string();
+
+    // 3. Replace relative references [.A1] -> A1
+    // Matches [.A1], [.AA123]
+    let rel_ref = ODS_REL_REF.get_or_init(|| Regex::new(r"\[\.(\$?[A-Z]+\$?[0-9]+)\]").unwrap());
+    normalized = rel_ref.replace_all(&normalized, "$1").to_string();
+
+    // 4. Replace whole column references [.A:.A] -> A:A
+    // Matches [.A:.A], [.A:.B]
+    let col_ref = ODS_COL_REF.get_or_init(|| Regex::new(r"\[\.([A-Z]+):\.([A-Z]+)\]").unwrap());
+    normalized = col_ref.replace_all(&normalized, "$1:$2").to_string();
+
+    // 5. Replace whole row references [.1:.1] -> 1:1
+    // Matches [.1:.1], [.1:.10]
+    let row_ref = ODS_ROW_REF.get_or_init(|| Regex::new(r"\[\.([0-9]+):\.([0-9]+)\]").unwrap());
+    normalized = row_ref.replace_all(&normalized, "$1:$2").to_string();
+
+    // Final check for identical range parts (e.g. A1:A1 -> A1)
+    if let Some((start, end)) = normalized.split_once(':')
+        && start == end
+    {
+        return start.to_string();
+    }
+
+    // Convert visible row numbers to XML row numbers if mapping is provided
+    // This accounts for hidden rows in ODS files
+    if let Some(row_map) = visible_to_xml_row {
+        normalized = map_visible_rows(&normalized, row_map, current_sheet_name);
+    }
+
+    normalized
+}
+
+/// Translate a cell's OpenFormula formula to Excel syntax, with row numbers
+/// mapped through `visible_to_xml_row` like [`normalize_ods_reference`] does
+fn translate_ods_formula(
+    formula: &str,
+    external_workbooks: &[ExternalWorkbook],
+    visible_to_xml_row: &HashMap<u32, u32>,
+    current_sheet_name: &str,
+) -> String {
+    let translated = openformula::to_excel(formula, external_workbooks);
+    map_visible_rows(&translated, visible_to_xml_row, Some(current_sheet_name))
+}
+
+/// Convert visible row numbers (1-indexed, used in ODS formulas) to XML row
+/// numbers in the references of an Excel-syntax formula.
+/// The mapping is ONLY applied to references pointing to `current_sheet_name` (or local references).
+fn map_visible_rows(
+    formula: &str,
+    row_map: &HashMap<u32, u32>,
+    current_sheet_name: Option<&str>,
+) -> String {
+    use regex::Regex;
+    use std::sync::OnceLock;
+
+    // Regex to match cell references: Sheet!A1, A1, $A$1, etc.
+    // Captures: (optional sheet)(column)(row number)
+    static CELL_REF_PATTERN: OnceLock<Regex> = OnceLock::new();
+    let cell_ref = CELL_REF_PATTERN
+        .get_or_init(|| Regex::new(r"(?:([A-Za-z0-9_]+)!)?(\$?[A-Z]+)(\$?)([0-9]+)").unwrap());
+
+    cell_ref
+        .replace_all(formula, |caps: &regex::Captures| {
+            let sheet_name = caps.get(1).map(|m| m.as_str());
+            let sheet_prefix = sheet_name.map(|s| format!("{}!", s)).unwrap_or_default();
+            let col = caps.get(2).map(|m| m.as_str()).unwrap_or("");
+            let abs_marker = caps.get(3).map(|m| m.as_str()).unwrap_or("");
+            let row_str = caps.get(4).map(|m| m.as_str()).unwrap_or("");
+
+            // ONLY convert row numbers for references to the CURRENT sheet
+            // This includes:
+            // 1. Local references (no sheet prefix)
+            // 2. Explicit references to the current sheet (e.g., CurrentSheet!A1)
+            let should_convert = if let Some(current_sheet) = current_sheet_name {
+                sheet_name.is_none() || sheet_name == Some(current_sheet)
+            } else {
+                // If no current sheet provided, only convert local references
+                sheet_name.is_none()
+            };
+
+            if should_convert && let Ok(visible_row) = row_str.parse::<u32>() {
+                // Convert visible row (1-indexed) to XML row (0-indexed)
+                if let Some(&xml_row) = row_map.get(&visible_row) {
+                    // Convert back to 1-indexed for formula representation
+                    return format!("{}{}{}", col, abs_marker, xml_row + 1);
+                }
+            }
+            // Keep original for cross-sheet references or if no mapping found
+            format!("{}{}{}{}", sheet_prefix, col, abs_marker, row_str)
+        })
+        .to_string()
+}
+
+/// Named expressions, of the document and of each sheet, and named database ranges
+#[derive(Default)]
+struct OdsDefinedNames {
+    names: Vec<DefinedName>,
+    in_named_expressions: bool,
+    in_database_ranges: bool,
+    /// Named expressions inside a table:table are local to that sheet
+    current_sheet: Option<String>,
+}
+
+impl OdsContentScan for OdsDefinedNames {
+    fn event(&mut self, event: &Event<'_>) -> Result<()> {
+        match event {
+            Event::Start(e) if e.name().as_ref() == b"table:table" => {
+                for attr in e.attributes().flatten() {
+                    if attr.key.as_ref() == b"table:name" {
+                        self.current_sheet = Some(attr.unescape_value()?.to_string());
+                    }
+                }
+            }
+            Event::Start(e) if e.name().as_ref() == b"table:named-expressions" => {
+                self.in_named_expressions = true;
+            }
+            Event::Start(e) if e.name().as_ref() == b"table:database-ranges" => {
+                self.in_database_ranges = true;
+            }
+            // Combined match for Start/Empty of item tags
+            Event::Empty(e) | Event::Start(e) => {
+                if self.in_named_expressions && e.name().as_ref() == b"table:named-range" {
+                    let mut name = String::new();
+                    let mut cell_range_address = String::new();
+
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"table:name" => {
+                                name = attr.unescape_value()?.to_string();
+                            }
+                            b"table:cell-range-address" => {
+                                cell_range_address = attr.unescape_value()?.to_string();
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    // Print areas and filters imported from Excel files keep
+                    // their built-in names (`Excel_BuiltIn_Print_Area`)
+                    if !name.is_empty()
+                        && !cell_range_address.is_empty()
+                        && !name.starts_with("Excel_BuiltIn_")
+                    {
+                        let normalized =
+                            normalize_ods_reference(&cell_range_address, true, None, None);
+                        self.names.push(DefinedName {
+                            scope: self.current_sheet.clone(),
+                            ..DefinedName::new(name, normalized)
+                        });
+                    }
+                } else if self.in_database_ranges && e.name().as_ref() == b"table:database-range" {
+                    let mut name = String::new();
+                    let mut target_range_address = String::new();
+
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"table:name" => {
+                                name = attr.unescape_value()?.to_string();
+                            }
+                            b"table:target-range-address" => {
+                                target_range_address = attr.unescape_value()?.to_string();
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    if !name.is_empty() && !target_range_address.is_empty() {
+                        // Filter out internal ODS names that start with __Anonymous_Sheet_DB__
+                        if !name.starts_with("__Anonymous_Sheet_DB__") {
+                            let normalized =
+                                normalize_ods_reference(&target_range_address, true, None, None);
+                            self.names.push(DefinedName::new(name, normalized));
+                        }
+                    }
+                }
+            }
+            Event::End(e) => {
+                if e.name().as_ref() == b"table:named-expressions" {
+                    self.in_named_expressions = false;
+                } else if e.name().as_ref() == b"table:database-ranges" {
+                    self.in_database_ranges = false;
+                } else if e.name().as_ref() == b"table:table" {
+                    self.current_sheet = None;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+pub struct OdsReader<'a, R: std::io::Read + std::io::Seek> {
+    archive: &'a mut ZipArchive<R>,
+}
+
+impl<'a, R: std::io::Read + std::io::Seek> OdsReader<'a, R> {
+    pub fn new(archive: &'a mut ZipArchive<R>) -> Result<Self> {
+        Ok(Self { archive })
+    }
+}
+
+impl<'a, R: std::io::Read + std::io::Seek> WorkbookReader for OdsReader<'a, R> {
+    fn read_sheets(&mut self) -> Result<Vec<Sheet>> {
+        let mut sheets = Vec::new();
+        self.parse_content(None, None, &mut |sheet| {
+            sheets.push(sheet);
+            Ok(())
+        })?;
+        Ok(sheets)
+    }
+
+    fn sheet_names(&mut self) -> Result<Vec<String>> {
+        let mut names = OdsSheetNames::default();
+        scan_ods_content(self.archive, &mut [&mut names])?;
+        Ok(names.local)
+    }
+
+    fn read_sheet(&mut self, name: &str) -> Result<Sheet> {
+        let mut found = None;
+        self.parse_content(Some(name), None, &mut |sheet| {
+            found = Some(sheet);
+            Ok(())
+        })?;
+        found.ok_or_else(|| anyhow::anyhow!("Sheet '{}' not found", name))
+    }
+
+    fn for_each_row(&mut self, name: &str, visit: RowVisitor<'_>) -> Result<Sheet> {
+        let mut found = None;
+        self.parse_content(Some(name), Some(visit), &mut |sheet| {
+            found = Some(sheet);
+            Ok(())
+        })?;
+        found.ok_or_else(|| anyhow::anyhow!("Sheet '{}' not found", name))
+    }
+
+    fn for_each_sheet(
+        &mut self,
+        with_cells: bool,
+        on_sheet: &mut dyn FnMut(Sheet) -> Result<()>,
+    ) -> Result<()> {
+        if with_cells {
+            self.parse_content(None, None, on_sheet)
+        } else {
+            self.parse_content(None, Some(&mut |_, _| Ok(())), on_sheet)
+        }
+    }
+
+    fn read_defined_names(&mut self) -> Result<Vec<DefinedName>> {
+        let mut names = OdsDefinedNames::default();
+        scan_ods_content(self.archive, &mut [&mut names])?;
+        Ok(names.names)
+    }
+
+    fn read_hidden_sheets(&mut self) -> Result<Vec<String>> {
+        extract_hidden_sheets_from_ods(self.archive)
+    }
+
+    fn has_macros(&mut self) -> Result<bool> {
+        has_macros(self.archive)
+    }
+
+    fn read_external_links(&mut self) -> Result<Vec<String>> {
+        extract_external_links_ods(self.archive)
+    }
+
+    fn read_external_workbooks(&mut self) -> Result<Vec<ExternalWorkbook>> {
+        extract_external_workbooks_ods(self.archive)
+    }
+
+    fn read_date_system(&mut self) -> Result<DateSystem> {
+        extract_date_system_from_ods(self.archive)
+    }
+
+    fn read_styles(&mut self) -> Result<Vec<CellStyle>> {
+        Ok(extract_cell_styles_from_ods(self.archive)?.0)
+    }
+
+    fn read_workbook_protection(&mut self) -> Result<Option<WorkbookProtection>> {
+        extract_workbook_protection_from_ods(self.archive)
+    }
+
+    fn read_properties(&mut self) -> Result<DocumentProperties> {
+        extract_properties_from_ods(self.archive)
+    }
+
+    fn read_tables(&mut self) -> Result<Vec<Table>> {
+        extract_tables_from_ods(self.archive)
+    }
+
+    fn read_pivot_tables(&mut self) -> Result<Vec<PivotTable>> {
+        extract_pivot_tables_from_ods(self.archive)
+    }
+
+    fn read_active_content(&mut self) -> Result<Vec<ActiveContent>> {
+        extract_active_content_from_ods(self.archive)
+    }
+
+    fn read_macros(&mut self) -> Result<Vec<MacroModule>> {
+        extract_macros_from_ods(self.archive)
+    }
+
+    fn read_metadata(&mut self) -> Result<(Vec<String>, Workbook)> {
+        // Date system and styles sit before the first table and are read
+        // without going through the document body
+        let date_system = extract_date_system_from_ods(self.archive)?;
+        let mut names = OdsSheetNames::default();
+        let mut hidden = OdsHiddenSheets::default();
+        let mut defined_names = OdsDefinedNames::default();
+        let mut external_workbooks = OdsExternalWorkbooks::new(date_system);
+        let mut tables = OdsTables::default();
+        let mut pivot_tables = OdsPivotTables::default();
+        let mut active_content = OdsActiveContent::default();
+        scan_ods_content(
+            self.archive,
+            &mut [
+                &mut names,
+                &mut hidden,
+                &mut defined_names,
+                &mut external_workbooks,
+                &mut tables,
+                &mut pivot_tables,
+                &mut active_content,
+            ],
+        )?;
+
+        let metadata = Workbook {
+            defined_names: defined_names.names,
+            hidden_sheets: hidden.finish(self.archive, &names.all)?,
+            has_macros: self.has_macros()?,
+            external_workbooks: external_workbooks.workbooks,
+            date_system,
+            styles: self.read_styles()?,
+            protection: self.read_workbook_protection()?,
+            properties: self.read_properties()?,
+            tables: tables.finish(self.archive)?,
+            pivot_tables: pivot_tables.pivot_tables,
+            active_content: active_content.content,
+            macros: self.read_macros()?,
+            ..Default::default()
+        };
+        Ok((names.local, metadata))
+    }
+}
+
+impl<'a, R: std::io::Read + std::io::Seek> OdsReader<'a, R> {
+    /// Single pass over content.xml.
+    ///
+    /// Finished sheets are handed to `on_sheet`. With `only_sheet` set, every other
+    /// sheet is parsed (formula row mapping depends on document order) but its cells
+    /// are dropped. With `on_row` set, the selected sheet's cells are streamed row by
+    /// row instead of being stored.
+    fn parse_content(
+        &mut self,
+        only_sheet: Option<&str>,
+        mut on_row: Option<RowVisitor<'_>>,
+        on_sheet: &mut dyn FnMut(Sheet) -> Result<()>,
+    ) -> Result<()> {
+        // Initialize date styles map first to avoid borrow check issues
+        let date_styles = extract_date_styles_from_ods(self.archive)?;
+        let (_, style_indices) = extract_cell_styles_from_ods(self.archive)?;
+        let text_styles = extract_text_styles_from_ods(self.archive)?;
+        let date_system = extract_date_system_from_ods(self.archive)?;
+
+        // Hidden sheets, and external workbooks for formula normalization
+        let mut names = OdsSheetNames::default();
+        let mut hidden = OdsHiddenSheets::default();
+        let mut external = OdsExternalWorkbooks::new(date_system);
+        scan_ods_content(self.archive, &mut [&mut names, &mut hidden, &mut external])?;
+        let hidden_sheets = hidden.finish(self.archive, &names.all)?;
+        let external_workbooks = external.workbooks;
+        let (chart_objects, part_sizes) = read_ods_embedded_parts(self.archive)?;
+        let (column_widths, row_heights) = extract_row_column_sizes_from_ods(self.archive)?;
+        let mut views = extract_sheet_views_from_ods(self.archive)?;
+        let page_setups = extract_page_setups_from_ods(self.archive)?;
+        let mut auto_filters = extract_auto_filters_from_ods(self.archive)?;
+
+        let content_xml = match self.archive.by_name("content.xml") {
+            Ok(file) => file,
+            Err(_) => return Ok(()),
+        };
+
+        let mut reader = Reader::from_reader(BufReader::new(content_xml));
+        reader.config_mut().trim_text(true);
+
+        let mut buf = Vec::new();
+        let mut current_sheet: Option<Sheet> = None;
+        let mut current_row = 0u32;
+        let mut row_repeated = 1u32;
+        let mut current_col = 0u32;
+        // Depth of the row and column groups being read, i.e. their outline level
+        let mut row_groups = 0u8;
+        let mut column_groups = 0u8;
+        // First row or column of the print titles being read
+        let mut header_rows_start = None;
+        let mut header_columns_start = None;
+        let mut current_cf_ranges: Vec<(u32, u32, u32, u32)> = Vec::new();
+        let mut skip_current_sheet = false; // Flag to skip external sheets
+        let mut keep_current_sheet = true; // False when filtering for another sheet
+        let mut row_cells: Vec<Cell> = Vec::new();
+        // Validations are defined once for the document and referenced by name from cells
+        let mut validations: HashMap<String, DataValidation> = HashMap::new();
+        let mut validated_cells: BTreeMap<String, Vec<(u32, u32, u32, u32)>> = BTreeMap::new();
+
+        // Track visible row numbering for ODS formulas
+        // ODS formulas use 1-indexed visible row numbers (accounting for hidden rows)
+        // but we store cells using 0-indexed XML row numbers
+        let mut visible_row_counter = 1u32; // 1-indexed (ODS formula style)
+        let mut visible_to_xml_row: HashMap<u32, u32> = HashMap::new();
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"table:table" => {
+                    // Finalize previous sheet if it exists and it's not external
+                    if let Some(sheet) = current_sheet.take()
+                        && !skip_current_sheet
+                        && keep_current_sheet
+                    {
+                        on_sheet(sheet)?;
+                    }
+
+                    let mut name = String::new();
+                    let mut style = String::new();
+                    let mut print_ranges = None;
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"table:name" => name = attr.unescape_value()?.to_string(),
+                            b"table:style-name" => style = attr.unescape_value()?.to_string(),
+                            b"table:print-ranges" => {
+                                print_ranges = Some(attr.unescape_value()?.to_string());
+                            }
+                            _ => {}
+                        }
+                    }
+                    keep_current_sheet = only_sheet.is_none_or(|only| only == name);
+                    let mut new_sheet = Sheet::new(name.clone());
+                    new_sheet.visible = !hidden_sheets.contains(&name);
+                    new_sheet.view = views.remove(&name).unwrap_or_default();
+                    new_sheet.auto_filter = auto_filters.remove(&name);
+                    new_sheet.page_setup = page_setups
+                        .get(&style)
+                        .or_else(|| page_setups.get(""))
+                        .cloned()
+                        .unwrap_or_default();
+                    if let Some(print_ranges) = print_ranges {
+                        let ranges = parse_ods_range_list(&print_ranges);
+                        if ranges.is_empty() || print_ranges.contains("#REF!") {
+                            new_sheet.page_setup.broken_print_area = Some(print_ranges);
+                        } else {
+                            new_sheet.page_setup.print_area = ranges;
+                        }
+                    }
+                    new_sheet.protection = read_ods_protection(&e, b"table:protected")?
+                        .map(|password| SheetProtection { password });
+                    current_sheet = Some(new_sheet);
+                    current_row = 0;
+                    current_col = 0; // Reset column tracking for new sheet
+                    skip_current_sheet = false; // Reset skip flag for new sheet
+                }
+                Event::Start(e) if e.name().as_ref() == b"table:content-validation" => {
+                    let (name, validation) = read_ods_validation(&mut reader, &e, true)?;
+                    validations.insert(name, validation);
+                }
+                Event::Empty(e) if e.name().as_ref() == b"table:content-validation" => {
+                    let (name, validation) = read_ods_validation(&mut reader, &e, false)?;
+                    validations.insert(name, validation);
+                }
+                // Detect external sheets by checking for table:table-source
+                Event::Start(ref e) | Event::Empty(ref e)
+                    if e.name().as_ref() == b"table:table-source" =>
+                {
+                    // Check if this table-source has an xlink:href attribute
+                    for attr in e.attributes().flatten() {
+                        if attr.key.as_ref() == b"xlink:href" {
+                            // This sheet is from an external workbook, mark it to be skipped
+                            skip_current_sheet = true;
+                            break;
+                        }
+                    }
+                }
+                Event::Start(e) if e.name().as_ref() == b"table:table-column-group" => {
+                    column_groups = column_groups.saturating_add(1);
+                }
+                Event::End(e) if e.name().as_ref() == b"table:table-column-group" => {
+                    column_groups = column_groups.saturating_sub(1);
+                }
+                Event::Start(e) if e.name().as_ref() == b"table:table-header-columns" => {
+                    header_columns_start = Some(current_col);
+                }
+                Event::End(e) if e.name().as_ref() == b"table:table-header-columns" => {
+                    if let (Some(first), Some(sheet)) =
+                        (header_columns_start.take(), current_sheet.as_mut())
+                        && current_col > first
+                    {
+                        sheet.page_setup.title_columns = Some((first, current_col - 1));
+                    }
+                }
+                Event::Start(e) if e.name().as_ref() == b"table:table-header-rows" => {
+                    header_rows_start = Some(current_row);
+                }
+                Event::End(e) if e.name().as_ref() == b"table:table-header-rows" => {
+                    if let (Some(first), Some(sheet)) =
+                        (header_rows_start.take(), current_sheet.as_mut())
+                        && current_row > first
+                    {
+                        sheet.page_setup.title_rows = Some((first, current_row - 1));
+                    }
+                }
+                Event::Start(e) if e.name().as_ref() == b"table:table-row-group" => {
+                    row_groups = row_groups.saturating_add(1);
+                }
+                Event::End(e) if e.name().as_ref() == b"table:table-row-group" => {
+                    row_groups = row_groups.saturating_sub(1);
+                }
+                Event::Start(e) if e.name().as_ref() == b"table:table-column" => {
+                    if let Some(ref mut sheet) = current_sheet {
+                        let mut hidden = false;
+                        let mut repeated = 1u32;
+                        let mut style = None;
+                        for attr in e.attributes().flatten() {
+                            match attr.key.as_ref() {
+                                b"table:style-name" => {
+                                    style = Some(attr.unescape_value()?.to_string());
+                                }
+                                b"table:visibility" => {
+                                    if attr.value.as_ref() == b"collapse"
+                                        || attr.value.as_ref() == b"filter"
+                                    {
+                                        hidden = true;
+                                    }
+                                }
+                                b"table:number-columns-repeated" => {
+                                    repeated = attr.unescape_value()?.parse::<u32>().unwrap_or(1);
+                                }
+                                _ => {}
+                            }
+                        }
+                        push_span(
+                            &mut sheet.columns,
+                            Span {
+                                first: current_col,
+                                last: current_col + repeated.max(1) - 1,
+                                size: style.and_then(|style| column_widths.get(&style).copied()),
+                                outline_level: column_groups,
+                            },
+                        );
+                        if hidden {
+                            for _ in 0..repeated {
+                                sheet.hidden_columns.push(current_col);
+                                current_col += 1;
+                            }
+                        } else {
+                            current_col += repeated;
+                        }
+
+                        // If it's a start tag, we need to skip to its end tag to avoid nested column issues
+                        let mut col_buf = Vec::new();
+                        loop {
+                            match reader.read_event_into(&mut col_buf)? {
+                                Event::End(ref te)
+                                    if te.name().as_ref() == b"table:table-column" =>
+                                {
+                                    break;
+                                }
+                                Event::Eof => break,
+                                _ => {}
+                            }
+                            col_buf.clear();
+                        }
+                    }
+                }
+                Event::Empty(e) if e.name().as_ref() == b"table:table-column" => {
+                    if let Some(ref mut sheet) = current_sheet {
+                        let mut hidden = false;
+                        let mut repeated = 1u32;
+                        let mut style = None;
+                        for attr in e.attributes().flatten() {
+                            match attr.key.as_ref() {
+                                b"table:style-name" => {
+                                    style = Some(attr.unescape_value()?.to_string());
+                                }
+                                b"table:visibility" => {
+                                    if attr.value.as_ref() == b"collapse"
+                                        || attr.value.as_ref() == b"filter"
+                                    {
+                                        hidden = true;
+                                    }
+                                }
+                                b"table:number-columns-repeated" => {
+                                    repeated = attr.unescape_value()?.parse::<u32>().unwrap_or(1);
+                                }
+                                _ => {}
+                            }
+                        }
+                        push_span(
+                            &mut sheet.columns,
+                            Span {
+                                first: current_col,
+                                last: current_col + repeated.max(1) - 1,
+                                size: style.and_then(|style| column_widths.get(&style).copied()),
+                                outline_level: column_groups,
+                            },
+                        );
+                        if hidden {
+                            for _ in 0..repeated {
+                                sheet.hidden_columns.push(current_col);
+                                current_col += 1;
+                            }
+                        } else {
+                            current_col += repeated;
+                        }
+                    }
+                }
+                Event::Start(e) if e.name().as_ref() == b"table:table-row" => {
+                    row_repeated = 1;
+                    current_col = 0;
+                    if let Some(ref mut sheet) = current_sheet {
+                        let mut hidden = false;
+                        let mut filtered = false;
+                        let mut style = None;
+                        for attr in e.attributes().flatten() {
+                            match attr.key.as_ref() {
+                                b"table:style-name" => {
+                                    style = Some(attr.unescape_value()?.to_string());
+                                }
+                                b"table:number-rows-repeated" => {
+                                    row_repeated =
+                                        attr.unescape_value()?.parse::<u32>().unwrap_or(1);
+                                }
+                                b"table:visibility" => {
+                                    hidden = attr.value.as_ref() == b"collapse";
+                                    filtered = attr.value.as_ref() == b"filter";
+                                }
+                                _ => {}
+                            }
+                        }
+
+                        push_span(
+                            &mut sheet.rows,
+                            Span {
+                                first: current_row,
+                                last: current_row + row_repeated.max(1) - 1,
+                                size: style.and_then(|style| row_heights.get(&style).copied()),
+                                outline_level: row_groups,
+                            },
+                        );
+                        if hidden {
+                            for i in 0..row_repeated {
+                                sheet.hidden_rows.push(current_row + i);
+                            }
+                        } else if filtered {
+                            for i in 0..row_repeated {
+                                sheet.filtered_rows.push(current_row + i);
+                            }
+                        } else {
+                            // Map visible row numbers to XML row indices
+                            for i in 0..row_repeated {
+                                visible_to_xml_row.insert(visible_row_counter, current_row + i);
+                                visible_row_counter += 1;
+                            }
+                        }
+                    }
+                }
+                Event::Empty(e) if e.name().as_ref() == b"table:table-row" => {
+                    // Empty row (self-closing tag) - no cells, just increment row counter
+                    row_repeated = 1;
+                    if let Some(ref mut sheet) = current_sheet {
+                        let mut hidden = false;
+                        let mut filtered = false;
+                        let mut style = None;
+                        for attr in e.attributes().flatten() {
+                            match attr.key.as_ref() {
+                                b"table:style-name" => {
+                                    style = Some(attr.unescape_value()?.to_string());
+                                }
+                                b"table:number-rows-repeated" => {
+                                    row_repeated =
+                                        attr.unescape_value()?.parse::<u32>().unwrap_or(1);
+                                }
+                                b"table:visibility" => {
+                                    hidden = attr.value.as_ref() == b"collapse";
+                                    filtered = attr.value.as_ref() == b"filter";
+                                }
+                                _ => {}
+                            }
+                        }
+                        push_span(
+                            &mut sheet.rows,
+                            Span {
+                                first: current_row,
+                                last: current_row + row_repeated.max(1) - 1,
+                                size: style.and_then(|style| row_heights.get(&style).copied()),
+                                outline_level: row_groups,
+                            },
+                        );
+                        if hidden {
+                            for r in 0..row_repeated {
+                                sheet.hidden_rows.push(current_row + r);
+                            }
+                        } else if filtered {
+                            for r in 0..row_repeated {
+                                sheet.filtered_rows.push(current_row + r);
+                            }
+                        } else {
+                            // Map visible row numbers to XML row indices
+                            for r in 0..row_repeated {
+                                visible_to_xml_row.insert(visible_row_counter, current_row + r);
+                                visible_row_counter += 1;
+                            }
+                        }
+                    }
+                    current_row += row_repeated;
+                    current_col = 0;
+                }
+                Event::Start(e)
+                    if e.name().as_ref() == b"table:table-cell"
+                        || e.name().as_ref() == b"table:covered-table-cell" =>
+                {
+                    if let Some(ref mut sheet) = current_sheet {
+                        let mut col_repeated = 1u32;
+                        let mut cols_spanned = 1u32;
+                        let mut rows_spanned = 1u32;
+                        // Size of the result of a matrix (array) formula
+                        let mut matrix_spanned = (0u32, 0u32);
+                        let mut formula = None;
+                        let mut value = CellValue::Empty;
+                        let mut has_value = false;
+                        let mut is_error_cell = false;
+                        let mut style_name = String::new();
+                        let mut validation_name = None;
+
+                        for attr in e.attributes().flatten() {
+                            match attr.key.as_ref() {
+                                b"table:number-columns-repeated" => {
+                                    col_repeated =
+                                        attr.unescape_value()?.parse::<u32>().unwrap_or(1);
+                                }
+                                b"table:number-columns-spanned" => {
+                                    cols_spanned =
+                                        attr.unescape_value()?.parse::<u32>().unwrap_or(1);
+                                }
+                                b"table:number-rows-spanned" => {
+                                    rows_spanned =
+                                        attr.unescape_value()?.parse::<u32>().unwrap_or(1);
+                                }
+                                b"table:number-matrix-rows-spanned" => {
+                                    matrix_spanned.0 =
+                                        attr.unescape_value()?.parse::<u32>().unwrap_or(0);
+                                }
+                                b"table:number-matrix-columns-spanned" => {
+                                    matrix_spanned.1 =
+                                        attr.unescape_value()?.parse::<u32>().unwrap_or(0);
+                                }
+                                b"table:formula" => {
+                                    let raw_formula = attr.unescape_value()?.to_string();
+                                    let translated = translate_ods_formula(
+                                        &raw_formula,
+                                        &external_workbooks,
+                                        &visible_to_xml_row,
+                                        &sheet.name,
+                                    );
+                                    formula = Some((translated, raw_formula));
+                                }
+                                b"table:style-name" => {
+                                    style_name = attr.unescape_value()?.to_string();
+                                }
+                                b"table:content-validation-name" => {
+                                    validation_name = Some(attr.unescape_value()?.to_string());
+                                }
+                                b"calcext:value-type" => {
+                                    if attr.value.as_ref() == b"error" {
+                                        is_error_cell = true;
+                                    }
+                                }
+                                b"office:value"
+                                | b"office:string-value"
+                                | b"office:boolean-value"
+                                | b"office:date-value" => {
+                                    let val_str = attr.unescape_value()?.to_string();
+                                    if !has_value {
+                                        value = match attr.key.as_ref() {
+                                            b"office:value" => {
+                                                if let Ok(n) = val_str.parse::<f64>() {
+                                                    CellValue::Number(n)
+                                                } else {
+                                                    CellValue::Text(val_str, None)
+                                                }
+                                            }
+                                            b"office:date-value" => {
+                                                // Convert ISO date to Serial Number
+                                                if let Some(n) =
+                                                    parse_ods_date(&val_str, date_system)
+                                                {
+                                                    CellValue::Number(n)
+                                                } else {
+                                                    CellValue::Text(val_str, None)
+                                                }
+                                            }
+                                            b"office:boolean-value" => {
+                                                CellValue::Boolean(val_str == "true")
+                                            }
+                                            _ => CellValue::Text(val_str, None),
+                                        };
+                                        has_value = true;
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
+
+                        if cols_spanned > 1 || rows_spanned > 1 {
+                            sheet.merged_cells.push((
+                                current_row,
+                                current_col,
+                                current_row + rows_spanned - 1,
+                                current_col + cols_spanned - 1,
+                            ));
+                        }
+
+                        if formula.is_some() && matrix_spanned.0 > 0 && matrix_spanned.1 > 0 {
+                            sheet.array_formulas.push(ArrayFormula {
+                                row: current_row,
+                                col: current_col,
+                                range: (
+                                    current_row,
+                                    current_col,
+                                    current_row + matrix_spanned.0 - 1,
+                                    current_col + matrix_spanned.1 - 1,
+                                ),
+                                dynamic: false,
+                            });
+                        }
+
+                        // Read text content from <text:p> elements
+                        // This handles both error cells and regular text cells
+                        let mut text_content = String::new();
+                        let mut rich_text = RichText::default();
+                        let mut paragraphs = 0;
+                        let mut comment = None;
+                        // Target and displayed text of the first `text:a` link
+                        let mut link: Option<(String, Option<String>)> = None;
+                        let mut text_buf = Vec::new();
+                        loop {
+                            match reader.read_event_into(&mut text_buf)? {
+                                // Annotation paragraphs are the comment, not the cell text
+                                Event::Start(ref te)
+                                    if te.name().as_ref() == b"office:annotation" =>
+                                {
+                                    comment = Some(read_ods_annotation(&mut reader)?);
+                                }
+                                Event::Start(ref te) if te.name().as_ref() == b"draw:frame" => {
+                                    read_ods_frame(
+                                        &mut reader,
+                                        te,
+                                        (current_row, current_col),
+                                        &chart_objects,
+                                        &part_sizes,
+                                        sheet,
+                                    )?;
+                                }
+                                Event::Start(ref te) if te.name().as_ref() == b"text:p" => {
+                                    // Each paragraph is a line of the cell
+                                    if paragraphs > 0 {
+                                        text_content.push('\n');
+                                        rich_text.push("\n", None);
+                                    }
+                                    paragraphs += 1;
+                                    let mut p_buf = Vec::new();
+                                    let mut link_text_start = 0;
+                                    // Fonts of the enclosing `text:span`s, innermost last
+                                    let mut spans: Vec<Option<&Font>> = Vec::new();
+                                    // Spaces at the edges of a span are part of the text
+                                    reader.config_mut().trim_text(false);
+                                    loop {
+                                        let font = spans.iter().rev().find_map(|font| *font);
+                                        match reader.read_event_into(&mut p_buf)? {
+                                            Event::Text(ref t) => {
+                                                let text = t.unescape()?;
+                                                text_content.push_str(&text);
+                                                rich_text.push(&text, font);
+                                            }
+                                            Event::Start(ref se)
+                                                if se.name().as_ref() == b"text:span" =>
+                                            {
+                                                let style = ods_attr(se, b"text:style-name")?;
+                                                spans.push(
+                                                    style.and_then(|name| text_styles.get(&name)),
+                                                );
+                                            }
+                                            Event::End(ref se)
+                                                if se.name().as_ref() == b"text:span" =>
+                                            {
+                                                spans.pop();
+                                            }
+                                            Event::Empty(ref se) => {
+                                                let text = match se.name().as_ref() {
+                                                    b"text:s" => {
+                                                        let count = ods_attr(se, b"text:c")?
+                                                            .and_then(|c| c.parse().ok());
+                                                        " ".repeat(count.unwrap_or(1))
+                                                    }
+                                                    b"text:tab" => "\t".to_string(),
+                                                    b"text:line-break" => "\n".to_string(),
+                                                    _ => String::new(),
+                                                };
+                                                text_content.push_str(&text);
+                                                rich_text.push(&text, font);
+                                            }
+                                            Event::Start(ref ae)
+                                                if ae.name().as_ref() == b"text:a"
+                                                    && link.is_none() =>
+                                            {
+                                                for attr in ae.attributes().flatten() {
+                                                    if attr.key.as_ref() == b"xlink:href" {
+                                                        link = Some((
+                                                            attr.unescape_value()?.to_string(),
+                                                            None,
+                                                        ));
+                                                    }
+                                                }
+                                                link_text_start = text_content.len();
+                                            }
+                                            Event::End(ref ae)
+                                                if ae.name().as_ref() == b"text:a" =>
+                                            {
+                                                if let Some((_, display @ None)) = link.as_mut() {
+                                                    *display = Some(
+                                                        text_content[link_text_start..].to_string(),
+                                                    );
+                                                }
+                                            }
+                                            Event::End(ref pe)
+                                                if pe.name().as_ref() == b"text:p" =>
+                                            {
+                                                break;
+                                            }
+                                            Event::Eof => break,
+                                            _ => {}
+                                        }
+                                        p_buf.clear();
+                                    }
+                                    reader.config_mut().trim_text(true);
+                                }
+                                Event::End(ref te)
+                                    if te.name().as_ref() == b"table:table-cell"
+                                        || te.name().as_ref() == b"table:covered-table-cell" =>
+                                {
+                                    break;
+                                }
+                                Event::Eof => break,
+                                _ => {}
+                            }
+                            text_buf.clear();
+                        }
+
+                        // A `text:a` link wins over a HYPERLINK() formula
+                        let link = match link {
+                            Some((target, display)) => {
+                                Some(Hyperlink::new(current_row, current_col, &target, display))
+                            }
+                            None => formula.as_ref().and_then(|(f, _)| {
+                                Hyperlink::from_formula(current_row, current_col, f)
+                            }),
+                        };
+                        if let Some(link) = link {
+                            for r in 0..row_repeated {
+                                for c in 0..col_repeated {
+                                    sheet.hyperlinks.push(Hyperlink {
+                                        row: current_row + r,
+                                        col: current_col + c,
+                                        ..link.clone()
+                                    });
+                                }
+                            }
+                        }
+
+                        // Use text content if we have it and no other value
+                        if !text_content.is_empty() {
+                            if is_error_cell {
+                                value = CellValue::formula_with_error("", text_content);
+                                has_value = true;
+                            } else if !has_value {
+                                // Only use text:p content if we don't have a value from attributes
+                                value = CellValue::Text(text_content, rich_text.into_formatting());
+                                has_value = true;
+                            }
+                        }
+
+                        if has_value
+                            || formula.is_some()
+                            || !style_name.is_empty()
+                            || comment.is_some()
+                        {
+                            let mut cell_value = value;
+                            if let Some((f, raw)) = formula {
+                                cell_value = match cell_value {
+                                    CellValue::Formula {
+                                        cached_error: Some(msg),
+                                        ..
+                                    } => CellValue::formula_with_error(f, msg),
+                                    _ => CellValue::formula(f),
+                                }
+                                .with_original(raw);
+                            }
+
+                            // Look up format string from style
+                            let num_fmt = if !style_name.is_empty() {
+                                date_styles.get(&style_name).cloned()
+                            } else {
+                                None
+                            };
+
+                            // Check if this is a text-formatted number
+                            // In ODS, text format is indicated by num_fmt == "@"
+                            if num_fmt.as_deref() == Some("@")
+                                && let CellValue::Number(n) = cell_value
+                            {
+                                // Convert number to text
+                                cell_value = CellValue::Text(n.to_string(), None);
+                            }
+
+                            for r in 0..row_repeated {
+                                for c in 0..col_repeated {
+                                    let cell = Cell {
+                                        row: current_row + r,
+                                        col: current_col + c,
+                                        value: cell_value.clone(),
+                                        num_fmt: num_fmt.clone(),
+                                        style: style_indices.get(&style_name).copied(),
+                                        comment: comment.clone(),
+                                    };
+                                    if on_row.is_some() {
+                                        row_cells.push(cell);
+                                    } else if keep_current_sheet {
+                                        sheet
+                                            .cells
+                                            .insert((current_row + r, current_col + c), cell);
+                                    }
+
+                                    // Update used_range for any inserted cell (value, formula, or style)
+                                    let row_pos = current_row + r;
+                                    let col_pos = current_col + c;
+                                    if let Some((max_row, max_col)) = sheet.used_range {
+                                        sheet.used_range = Some((
+                                            max_row.max(row_pos + 1),
+                                            max_col.max(col_pos + 1),
+                                        ));
+                                    } else {
+                                        sheet.used_range = Some((row_pos + 1, col_pos + 1));
+                                    }
+                                }
+                            }
+                        }
+
+                        if let Some(name) = validation_name {
+                            validated_cells.entry(name).or_default().push((
+                                current_row,
+                                current_col,
+                                current_row + row_repeated - 1,
+                                current_col + col_repeated - 1,
+                            ));
+                        }
+
+                        // Multiply repeated by spanned to get true column consumption
+                        current_col += col_repeated;
+                    }
+                }
+                Event::Empty(e)
+                    if e.name().as_ref() == b"table:table-cell"
+                        || e.name().as_ref() == b"table:covered-table-cell" =>
+                {
+                    if let Some(ref mut sheet) = current_sheet {
+                        let mut col_repeated = 1u32;
+                        let mut cols_spanned = 1u32;
+                        let mut rows_spanned = 1u32;
+                        // Size of the result of a matrix (array) formula
+                        let mut matrix_spanned = (0u32, 0u32);
+                        let mut formula = None;
+                        let mut style_name = String::new();
+                        let mut validation_name = None;
+
+                        for attr in e.attributes().flatten() {
+                            match attr.key.as_ref() {
+                                b"table:number-columns-repeated" => {
+                                    col_repeated =
+                                        attr.unescape_value()?.parse::<u32>().unwrap_or(1);
+                                }
+                                b"table:number-columns-spanned" => {
+                                    cols_spanned =
+                                        attr.unescape_value()?.parse::<u32>().unwrap_or(1);
+                                }
+                                b"table:number-rows-spanned" => {
+                                    rows_spanned =
+                                        attr.unescape_value()?.parse::<u32>().unwrap_or(1);
+                                }
+                                b"table:number-matrix-rows-spanned" => {
+                                    matrix_spanned.0 =
+                                        attr.unescape_value()?.parse::<u32>().unwrap_or(0);
+                                }
+                                b"table:number-matrix-columns-spanned" => {
+                                    matrix_spanned.1 =
+                                        attr.unescape_value()?.parse::<u32>().unwrap_or(0);
+                                }
+                                b"table:formula" => {
+                                    let raw_formula = attr.unescape_value()?.to_string();
+                                    let translated = translate_ods_formula(
+                                        &raw_formula,
+                                        &external_workbooks,
+                                        &visible_to_xml_row,
+                                        &sheet.name,
+                                    );
+                                    formula = Some((translated, raw_formula));
+                                }
+                                b"table:style-name" => {
+                                    style_name = attr.unescape_value()?.to_string();
+                                }
+                                b"table:content-validation-name" => {
+                                    validation_name = Some(attr.unescape_value()?.to_string());
+                                }
+                                _ => {}
+                            }
+                        }
+
+                        // Check if this empty cell is actually a merged cell
+                        if cols_spanned > 1 || rows_spanned > 1 {
+                            sheet.merged_cells.push((
+                                current_row,
+                                current_col,
+                                current_row + rows_spanned - 1,
+                                current_col + cols_spanned - 1,
+                            ));
+                        }
+
+                        if formula.is_some() && matrix_spanned.0 > 0 && matrix_spanned.1 > 0 {
+                            sheet.array_formulas.push(ArrayFormula {
+                                row: current_row,
+                                col: current_col,
+                                range: (
+                                    current_row,
+                                    current_col,
+                                    current_row + matrix_spanned.0 - 1,
+                                    current_col + matrix_spanned.1 - 1,
+                                ),
+                                dynamic: false,
+                            });
+                        }
+
+                        // If it's an empty cell but has a formula or style, we should store it.
+                        if formula.is_some() || !style_name.is_empty() {
+                            let cell_value = formula
+                                .map(|(f, raw)| CellValue::formula(f).with_original(raw))
+                                .unwrap_or(CellValue::Empty);
+
+                            // Look up format string from style
+                            let num_fmt = if !style_name.is_empty() {
+                                date_styles.get(&style_name).cloned()
+                            } else {
+                                None
+                            };
+
+                            for r in 0..row_repeated {
+                                for c in 0..col_repeated {
+                                    let cell = Cell {
+                                        row: current_row + r,
+                                        col: current_col + c,
+                                        value: cell_value.clone(),
+                                        num_fmt: num_fmt.clone(),
+                                        style: style_indices.get(&style_name).copied(),
+                                        comment: None,
+                                    };
+                                    if on_row.is_some() {
+                                        row_cells.push(cell);
+                                    } else if keep_current_sheet {
+                                        sheet
+                                            .cells
+                                            .insert((current_row + r, current_col + c), cell);
+                                    }
+
+                                    // Update used_range for any inserted cell (formula or style)
+                                    let row_pos = current_row + r;
+                                    let col_pos = current_col + c;
+                                    if let Some((max_row, max_col)) = sheet.used_range {
+                                        sheet.used_range = Some((
+                                            max_row.max(row_pos + 1),
+                                            max_col.max(col_pos + 1),
+                                        ));
+                                    } else {
+                                        sheet.used_range = Some((row_pos + 1, col_pos + 1));
+                                    }
+                                }
+                            }
+                        }
+
+                        if let Some(name) = validation_name {
+                            validated_cells.entry(name).or_default().push((
+                                current_row,
+                                current_col,
+                                current_row + row_repeated - 1,
+                                current_col + col_repeated - 1,
+                            ));
+                        }
+
+                        // Multiply repeated by spanned to get true column consumption
+                        current_col += col_repeated;
+                    }
+                }
+                Event::Start(e)
+                    if matches!(
+                        e.name().as_ref(),
+                        b"calcext:conditional-format" | b"table:conditional-formatting"
+                    ) =>
+                {
+                    for attr in e.attributes().flatten() {
+                        if matches!(
+                            attr.key.as_ref(),
+                            b"calcext:target-range-address" | b"table:target-range-address"
+                        ) {
+                            current_cf_ranges = parse_ods_range_list(&attr.unescape_value()?);
+                        }
+                    }
+                }
+                Event::End(e)
+                    if matches!(
+                        e.name().as_ref(),
+                        b"calcext:conditional-format" | b"table:conditional-formatting"
+                    ) =>
+                {
+                    current_cf_ranges.clear();
+                }
+                Event::Start(e) | Event::Empty(e)
+                    if matches!(
+                        e.name().as_ref(),
+                        b"calcext:condition"
+                            | b"calcext:color-scale"
+                            | b"calcext:data-bar"
+                            | b"calcext:icon-set"
+                            | b"calcext:date-is"
+                            | b"calcext:color-scale-entry"
+                            | b"calcext:formatting-entry"
+                            | b"table:conditional-formatting-rule"
+                    ) =>
+                {
+                    if let Some(ref mut sheet) = current_sheet {
+                        read_ods_conditional_format(&e, &current_cf_ranges, sheet)?;
+                    }
+                }
+                // Frames anchored to the sheet rather than a cell (`table:shapes`)
+                Event::Start(e) if e.name().as_ref() == b"draw:frame" => {
+                    if let Some(ref mut sheet) = current_sheet {
+                        read_ods_frame(
+                            &mut reader,
+                            &e,
+                            (0, 0),
+                            &chart_objects,
+                            &part_sizes,
+                            sheet,
+                        )?;
+                    }
+                }
+                Event::End(e) if e.name().as_ref() == b"table:table-row" => {
+                    if let Some(visit) = on_row.as_mut()
+                        && !row_cells.is_empty()
+                    {
+                        if keep_current_sheet && !skip_current_sheet {
+                            // Repeated rows put several rows' cells in one element
+                            row_cells.sort_by_key(|cell| (cell.row, cell.col));
+                            for row in row_cells.chunk_by(|a, b| a.row == b.row) {
+                                visit(row[0].row, row)?;
+                            }
+                        }
+                        row_cells.clear();
+                    }
+                    current_row += row_repeated;
+                    current_col = 0;
+                }
+                Event::End(e) if e.name().as_ref() == b"table:table" => {
+                    // Calculate used range for the sheet before finalizing
+                    // This must happen here (not at sheet finalization) because it needs to run
+                    // for ALL sheets, whether they have conditional formatting or not
+                    if let Some(ref mut sheet) = current_sheet {
+                        for (name, ranges) in std::mem::take(&mut validated_cells) {
+                            if let Some(validation) = validations.get(&name) {
+                                sheet.data_validations.push(DataValidation {
+                                    ranges: merge_ranges(ranges),
+                                    ..validation.clone()
+                                });
+                            }
+                        }
+
+                        let cells_range = calculate_used_range(&sheet.cells);
+
+                        // Merge styled cell tracking with value cells
+                        // Both are already in count format (0-indexed position + 1)
+                        sheet.used_range = match (sheet.used_range, cells_range) {
+                            (Some((s_row, s_col)), Some((c_row, c_col))) => {
+                                Some((s_row.max(c_row), s_col.max(c_col)))
+                            }
+                            (Some(s), None) => Some(s),
+                            (None, Some(c)) => Some(c),
+                            (None, None) => None,
+                        };
+
+                        // Include hidden rows/columns in used_range for format parity
+                        // Both ODS and XLSX should report ALL empty rows/columns (visible or hidden)
+                        if let Some((mut rows, mut cols)) = sheet.used_range {
+                            let hidden_rows = sheet.hidden_rows.iter().chain(&sheet.filtered_rows);
+                            if let Some(&max_hidden_row) = hidden_rows.max() {
+                                rows = rows.max(max_hidden_row + 1);
+                            }
+                            if let Some(&max_hidden_col) = sheet.hidden_columns.iter().max() {
+                                cols = cols.max(max_hidden_col + 1);
+                            }
+                            sheet.used_range = Some((rows, cols));
+                        }
+                    }
+                }
+
+                // Handle conditional formatting that appears after table closing tag
+                Event::Start(e) if e.name().as_ref() == b"calcext:conditional-formats" => {
+                    // This wrapper appears after </table:table>, continue processing
+                }
+                Event::End(e) if e.name().as_ref() == b"calcext:conditional-formats" => {
+                    // End of conditional formatting section
+                    // Don't finalize the sheet here - the table end handler will do it
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        // Finalize the last sheet if it exists and it's not external
+        if let Some(sheet) = current_sheet
+            && !skip_current_sheet
+            && keep_current_sheet
+        {
+            on_sheet(sheet)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Read an `<office:annotation>` after its start tag, up to the matching end tag
+fn read_ods_annotation<R: std::io::BufRead>(reader: &mut Reader<R>) -> Result<Comment> {
+    let mut comment = Comment::default();
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut field: Option<&[u8]> = None;
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => match e.name().as_ref() {
+                b"dc:creator" => field = Some(b"dc:creator"),
+                b"dc:date" => field = Some(b"dc:date"),
+                b"text:p" => {
+                    paragraphs.push(String::new());
+                    field = Some(b"text:p");
+                }
+                _ => {}
+            },
+            Event::Empty(e) if e.name().as_ref() == b"text:p" => paragraphs.push(String::new()),
+            Event::Text(t) => {
+                let text = t.unescape()?;
+                match field {
+                    Some(b"dc:creator") => comment.author = Some(text.to_string()),
+                    Some(b"dc:date") => comment.date = Some(text.to_string()),
+                    Some(b"text:p") => {
+                        if let Some(p) = paragraphs.last_mut() {
+                            p.push_str(&text);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(e) => match e.name().as_ref() {
+                b"office:annotation" => break,
+                b"dc:creator" | b"dc:date" | b"text:p" => field = None,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    comment.text = paragraphs.join("\n");
+    Ok(comment)
+}
+
+/// Read a `<table:content-validation>` definition, returning its name and rule.
+/// `has_body` when it was a start tag, possibly holding a `table:error-message`.
+fn read_ods_validation<R: std::io::BufRead>(
+    reader: &mut Reader<R>,
+    start: &quick_xml::events::BytesStart,
+    has_body: bool,
+) -> Result<(String, DataValidation)> {
+    let mut name = String::new();
+    let mut validation = DataValidation {
+        allow_blank: true,
+        ..Default::default()
+    };
+    for attr in start.attributes().flatten() {
+        match attr.key.as_ref() {
+            b"table:name" => name = attr.unescape_value()?.to_string(),
+            b"table:condition" => {
+                parse_ods_validation_condition(&attr.unescape_value()?, &mut validation);
+            }
+            b"table:allow-empty-cell" => validation.allow_blank = attr.value.as_ref() == b"true",
+            _ => {}
+        }
+    }
+
+    if has_body {
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(e) | Event::Empty(e)
+                    if e.name().as_ref() == b"table:error-message" =>
+                {
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"table:message-type" => {
+                                validation.error_style =
+                                    ValidationErrorStyle::parse(&attr.unescape_value()?);
+                            }
+                            b"table:display" => {
+                                validation.show_error = attr.value.as_ref() == b"true";
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Event::End(e) if e.name().as_ref() == b"table:content-validation" => break,
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+    }
+    Ok((name, validation))
+}
+
+/// Add the rule described by a child of `calcext:conditional-format` to the
+/// sheet, or a threshold to the color scale, data bar or icon set before it
+fn read_ods_conditional_format(
+    e: &quick_xml::events::BytesStart,
+    ranges: &[(u32, u32, u32, u32)],
+    sheet: &mut Sheet,
+) -> Result<()> {
+    let kind = match e.name().as_ref() {
+        b"calcext:condition" | b"table:conditional-formatting-rule" => ConditionalFormatKind::Other,
+        b"calcext:color-scale" => ConditionalFormatKind::ColorScale,
+        b"calcext:data-bar" => ConditionalFormatKind::DataBar,
+        b"calcext:icon-set" => ConditionalFormatKind::IconSet,
+        b"calcext:date-is" => ConditionalFormatKind::TimePeriod,
+        b"calcext:color-scale-entry" | b"calcext:formatting-entry" => {
+            let mut value = None;
+            let mut fixed = false;
+            for attr in e.attributes().flatten() {
+                match attr.key.as_ref() {
+                    b"calcext:value" => value = Some(attr.unescape_value()?.into_owned()),
+                    // Minimum and maximum entries carry no threshold of their own
+                    b"calcext:type" => fixed = attr.value.as_ref().ends_with(b"imum"),
+                    _ => {}
+                }
+            }
+            if let Some(value) = value.filter(|_| !fixed)
+                && let Some(rule) = sheet.conditional_formats.last_mut()
+            {
+                rule.formulas.push(normalize_ods_validation_formula(&value));
+            }
+            return Ok(());
+        }
+        _ => return Ok(()),
+    };
+
+    let mut rule = ConditionalFormat {
+        ranges: ranges.to_vec(),
+        kind,
+        priority: sheet.conditional_formats.len() as u32 + 1,
+        ..Default::default()
+    };
+    if kind == ConditionalFormatKind::Other {
+        for attr in e.attributes().flatten() {
+            if matches!(attr.key.as_ref(), b"calcext:value" | b"table:condition") {
+                parse_ods_conditional_condition(&attr.unescape_value()?, &mut rule);
+            }
+        }
+    }
+    sheet.conditional_formats.push(rule);
+    Ok(())
+}
+
+/// Fill kind, operator and formulas from a LibreOffice condition such as `>=5`,
+/// `between(1,10)`, `formula-is([.A1]>0)`, `duplicate` or `begins-with("x")`
+fn parse_ods_conditional_condition(condition: &str, rule: &mut ConditionalFormat) {
+    let condition = condition.trim();
+    let op_len = condition
+        .find(|c: char| !matches!(c, '<' | '>' | '=' | '!'))
+        .unwrap_or(condition.len());
+    if let Some(operator) = ValidationOperator::from_symbol(&condition[..op_len]) {
+        rule.kind = ConditionalFormatKind::CellIs;
+        rule.operator = Some(operator);
+        rule.formulas = vec![normalize_ods_validation_formula(&condition[op_len..])];
+        return;
+    }
+
+    let (name, args) = match condition
+        .split_once('(')
+        .and_then(|(name, rest)| Some((name, rest.strip_suffix(')')?)))
+    {
+        Some((name, args)) => (name, Some(args)),
+        None => (condition, None),
+    };
+    let (kind, operator) = match name {
+        "between" => (
+            ConditionalFormatKind::CellIs,
+            Some(ValidationOperator::Between),
+        ),
+        "not-between" => (
+            ConditionalFormatKind::CellIs,
+            Some(ValidationOperator::NotBetween),
+        ),
+        "formula-is" => (ConditionalFormatKind::Expression, None),
+        "duplicate" => (ConditionalFormatKind::DuplicateValues, None),
+        "unique" => (ConditionalFormatKind::UniqueValues, None),
+        "top-elements" | "bottom-elements" | "top-percent" | "bottom-percent" => {
+            (ConditionalFormatKind::Top10, None)
+        }
+        "above-average" | "below-average" | "above-equal-average" | "below-equal-average" => {
+            (ConditionalFormatKind::AboveAverage, None)
+        }
+        "is-error" => (ConditionalFormatKind::ContainsErrors, None),
+        "is-no-error" => (ConditionalFormatKind::NotContainsErrors, None),
+        "begins-with" => (ConditionalFormatKind::BeginsWith, None),
+        "ends-with" => (ConditionalFormatKind::EndsWith, None),
+        "contains-text" => (ConditionalFormatKind::ContainsText, None),
+        "not-contains-text" => (ConditionalFormatKind::NotContainsText, None),
+        _ => return,
+    };
+    rule.kind = kind;
+    rule.operator = operator;
+    rule.formulas = match args {
+        // The formula of `formula-is` is a single argument whatever it contains
+        Some(args) if kind == ConditionalFormatKind::Expression => {
+            vec![normalize_ods_validation_formula(args)]
+        }
+        Some(args) => split_top_level(args, ",")
+            .into_iter()
+            .map(normalize_ods_validation_formula)
+            .collect(),
+        None => Vec::new(),
+    };
+}
+
+/// Charts of the document's embedded chart objects, keyed by object directory
+/// (`Object 1`), and the compressed size of every file in the package
+fn read_ods_embedded_parts(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<(HashMap<String, Chart>, HashMap<String, u64>)> {
+    let mut sizes = HashMap::new();
+    let mut objects = Vec::new();
+    for i in 0..archive.len() {
+        let file = archive.by_index(i)?;
+        if let Some(object) = file.name().strip_suffix("/content.xml") {
+            objects.push(object.to_string());
+        }
+        sizes.insert(file.name().to_string(), file.compressed_size());
+    }
+
+    let mut charts = HashMap::new();
+    for object in objects {
+        if let Some(chart) = parse_ods_chart(archive, &format!("{}/content.xml", object))? {
+            charts.insert(object, chart);
+        }
+    }
+    Ok((charts, sizes))
+}
+
+/// Read the plot type, title and series of an embedded object; `None` when
+/// the object isn't a chart
+fn parse_ods_chart(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+    path: &str,
+) -> Result<Option<Chart>> {
+    let object_xml = match archive.by_name(path) {
+        Ok(file) => file,
+        Err(_) => return Ok(None),
+    };
+    let mut reader = Reader::from_reader(BufReader::new(object_xml));
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut chart: Option<Chart> = None;
+    let mut in_plot_area = false;
+    let mut categories = None;
+    let mut series: Option<ChartSeries> = None;
+    loop {
+        let event = reader.read_event_into(&mut buf)?;
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let empty = matches!(event, Event::Empty(_));
+                let attribute = |name: &[u8]| -> Option<String> {
+                    e.attributes()
+                        .flatten()
+                        .find(|attr| attr.key.as_ref() == name)
+                        .and_then(|attr| attr.unescape_value().ok().map(|v| v.to_string()))
+                };
+                match e.name().as_ref() {
+                    b"chart:chart" => {
+                        chart = Some(Chart {
+                            kind: ods_chart_kind(&attribute(b"chart:class").unwrap_or_default()),
+                            ..Default::default()
+                        });
+                    }
+                    // Axis titles are inside the plot area
+                    b"chart:title" if !in_plot_area && !empty => {
+                        if let Some(chart) = chart.as_mut() {
+                            chart.title = Some(read_ods_paragraphs(&mut reader)?)
+                                .filter(|title| !title.is_empty());
+                        }
+                    }
+                    b"chart:plot-area" => in_plot_area = true,
+                    b"chart:categories" => {
+                        categories = attribute(b"table:cell-range-address")
+                            .map(|address| ods_chart_reference(&address));
+                    }
+                    b"chart:series" => {
+                        let new_series = ChartSeries {
+                            name: attribute(b"chart:label-cell-address")
+                                .map(|address| ods_chart_reference(&address)),
+                            categories: None,
+                            values: attribute(b"chart:values-cell-range-address")
+                                .map(|address| ods_chart_reference(&address)),
+                        };
+                        match chart.as_mut() {
+                            Some(chart) if empty => chart.series.push(new_series),
+                            _ => series = Some(new_series),
+                        }
+                    }
+                    // X values of scatter and bubble charts
+                    b"chart:domain" => {
+                        if let Some(series) = series.as_mut() {
+                            series.categories = attribute(b"table:cell-range-address")
+                                .map(|address| ods_chart_reference(&address));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(e) if e.name().as_ref() == b"chart:series" => {
+                if let Some(chart) = chart.as_mut() {
+                    chart.series.extend(series.take());
+                }
+            }
+            Event::End(e) if e.name().as_ref() == b"chart:plot-area" => in_plot_area = false,
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    // Categories are set on the axis for every series
+    if let Some(chart) = chart.as_mut() {
+        for series in &mut chart.series {
+            if series.categories.is_none() {
+                series.categories = categories.clone();
+            }
+        }
+    }
+    Ok(chart)
+}
+
+/// Plot type of a `chart:class` (`chart:bar`, `chart:circle`), named like the
+/// XLSX plot elements
+fn ods_chart_kind(class: &str) -> String {
+    match class.trim_start_matches("chart:") {
+        "circle" => "pie".to_string(),
+        "ring" => "doughnut".to_string(),
+        "filled-radar" => "radar".to_string(),
+        kind => kind.to_string(),
+    }
+}
+
+/// Excel reference for a chart's ODS range address: `Sheet1.B2:Sheet1.B5`
+/// becomes `Sheet1!B2:B5`, and a list of ranges becomes `(Sheet1!B2:B5,Sheet1!D2:D5)`
+fn ods_chart_reference(address: &str) -> String {
+    if address.contains("#REF!") {
+        return "#REF!".to_string();
+    }
+    let mut references = Vec::new();
+    let mut quoted = false;
+    let mut start = 0;
+    for (i, c) in address.char_indices().chain([(address.len(), ' ')]) {
+        match c {
+            '\'' => quoted = !quoted,
+            ' ' if !quoted => {
+                let part = &address[start..i];
+                if !part.is_empty() {
+                    references.push(match parse_ods_range_address(part) {
+                        Some((sheet, range)) => format_range(&sheet, range),
+                        None => part.to_string(),
+                    });
+                }
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    match references.len() {
+        1 => references.remove(0),
+        _ => format!("({})", references.join(",")),
+    }
+}
+
+/// Text of the `text:p` paragraphs up to the end of the current element,
+/// joined with newlines
+fn read_ods_paragraphs<R: std::io::BufRead>(reader: &mut Reader<R>) -> Result<String> {
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut depth = 1;
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => {
+                depth += 1;
+                if e.name().as_ref() == b"text:p" {
+                    paragraphs.push(String::new());
+                }
+            }
+            Event::Text(t) => {
+                if let Some(paragraph) = paragraphs.last_mut() {
+                    paragraph.push_str(&t.unescape()?);
+                }
+            }
+            Event::End(_) => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(paragraphs.join("\n"))
+}
+
+/// Read a `draw:frame` up to its end, adding the chart or image it holds to
+/// the sheet. Other embedded objects (formulas, OLE objects) are skipped.
+fn read_ods_frame<R: std::io::BufRead>(
+    reader: &mut Reader<R>,
+    start: &quick_xml::events::BytesStart,
+    anchor: (u32, u32),
+    chart_objects: &HashMap<String, Chart>,
+    part_sizes: &HashMap<String, u64>,
+    sheet: &mut Sheet,
+) -> Result<()> {
+    let mut name = String::new();
+    for attr in start.attributes().flatten() {
+        if attr.key.as_ref() == b"draw:name" {
+            name = attr.unescape_value()?.to_string();
+        }
+    }
+
+    // The first object and image in the frame; an object's image is its
+    // preview, not a picture of its own
+    let mut object = None;
+    let mut image = None;
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) | Event::Empty(e) => {
+                let target = match e.name().as_ref() {
+                    b"draw:object" | b"draw:object-ole" => &mut object,
+                    b"draw:image" => &mut image,
+                    _ => {
+                        buf.clear();
+                        continue;
+                    }
+                };
+                if target.is_none() {
+                    for attr in e.attributes().flatten() {
+                        if attr.key.as_ref() == b"xlink:href" {
+                            let href = attr.unescape_value()?;
+                            *target = Some(
+                                href.trim_start_matches("./")
+                                    .trim_end_matches('/')
+                                    .to_string(),
+                            );
+                        }
+                    }
+                }
+            }
+            Event::End(e) if e.name().as_ref() == b"draw:frame" => break,
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    match (object, image) {
+        (Some(object), _) => {
+            if let Some(chart) = chart_objects.get(&object) {
+                sheet.charts.push(Chart {
+                    anchor,
+                    ..chart.clone()
+                });
+            }
+        }
+        // Linked images point outside the package
+        (None, Some(path)) if !path.contains("://") => {
+            let size = part_sizes.get(&path).copied().unwrap_or(0);
+            sheet.images.push(Image {
+                name,
+                anchor,
+                path,
+                size,
+            });
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Parse a space-separated list of ODS range addresses, such as a conditional
+/// format's `Sheet1.A1:Sheet1.A9 'My Sheet'.C3`, ignoring the sheet names
+fn parse_ods_range_list(addresses: &str) -> Vec<(u32, u32, u32, u32)> {
+    let mut ranges = Vec::new();
+    let mut quoted = false;
+    let mut start = 0;
+    for (i, c) in addresses.char_indices().chain([(addresses.len(), ' ')]) {
+        match c {
+            '\'' => quoted = !quoted,
+            ' ' if !quoted => {
+                ranges.extend(parse_ods_range_address(&addresses[start..i]).map(|(_, r)| r));
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    ranges
+}
+
+/// Fill kind, operator and formulas from an ODF validation condition such as
+/// `of:cell-content-is-whole-number() and cell-content-is-between(1;10)`
+fn parse_ods_validation_condition(condition: &str, validation: &mut DataValidation) {
+    let condition = match condition.split_once(':') {
+        Some(("of" | "oooc" | "ooow", rest)) => rest,
+        _ => condition,
+    };
+
+    let call = |part: &'_ str, name: &str| -> Option<String> {
+        part.strip_prefix(name)?
+            .strip_prefix('(')?
+            .strip_suffix(')')
+            .map(str::to_string)
+    };
+    let bounds = |args: &str, validation: &mut DataValidation| {
+        let mut args = split_top_level(args, ";").into_iter();
+        validation.formula1 = args.next().map(normalize_ods_validation_formula);
+        validation.formula2 = args.next().map(normalize_ods_validation_formula);
+    };
+
+    for part in split_top_level(condition, " and ") {
+        let part = part.trim();
+        if let Some(args) = call(part, "cell-content-is-in-list") {
+            validation.kind = ValidationKind::List;
+            validation.formula1 = Some(ods_list_formula(&args));
+        } else if part == "cell-content-is-whole-number()" {
+            validation.kind = ValidationKind::WholeNumber;
+        } else if part == "cell-content-is-decimal-number()" {
+            validation.kind = ValidationKind::Decimal;
+        } else if part == "cell-content-is-date()" {
+            validation.kind = ValidationKind::Date;
+        } else if part == "cell-content-is-time()" {
+            validation.kind = ValidationKind::Time;
+        } else if let Some(args) = call(part, "is-true-formula") {
+            validation.kind = ValidationKind::Custom;
+            validation.formula1 = Some(normalize_ods_validation_formula(&args));
+        } else if let Some(args) = call(part, "cell-content-text-length-is-between") {
+            validation.kind = ValidationKind::TextLength;
+            validation.operator = ValidationOperator::Between;
+            bounds(&args, validation);
+        } else if let Some(args) = call(part, "cell-content-text-length-is-not-between") {
+            validation.kind = ValidationKind::TextLength;
+            validation.operator = ValidationOperator::NotBetween;
+            bounds(&args, validation);
+        } else if let Some(args) = call(part, "cell-content-is-between") {
+            validation.operator = ValidationOperator::Between;
+            bounds(&args, validation);
+        } else if let Some(args) = call(part, "cell-content-is-not-between") {
+            validation.operator = ValidationOperator::NotBetween;
+            bounds(&args, validation);
+        } else if let Some(rest) = part.strip_prefix("cell-content-text-length()") {
+            validation.kind = ValidationKind::TextLength;
+            parse_ods_comparison(rest, validation);
+        } else if let Some(rest) = part.strip_prefix("cell-content()") {
+            parse_ods_comparison(rest, validation);
+        }
+    }
+
+    // A bare comparison without a type function compares numbers
+    if validation.kind == ValidationKind::Any && validation.formula1.is_some() {
+        validation.kind = ValidationKind::Decimal;
+    }
+}
+
+/// Parse the `>=5` tail of `cell-content()>=5`
+fn parse_ods_comparison(rest: &str, validation: &mut DataValidation) {
+    let rest = rest.trim();
+    let op_len = rest
+        .find(|c: char| !matches!(c, '<' | '>' | '=' | '!'))
+        .unwrap_or(rest.len());
+    if let Some(operator) = ValidationOperator::from_symbol(&rest[..op_len]) {
+        validation.operator = operator;
+        validation.formula1 = Some(normalize_ods_validation_formula(&rest[op_len..]));
+        validation.formula2 = None;
+    }
+}
+
+/// Turn the arguments of `cell-content-is-in-list` into the XLSX list form:
+/// `"a";"b"` becomes `"a,b"`, a range reference becomes `Sheet!$A$1:$A$5`
+fn ods_list_formula(args: &str) -> String {
+    let items = split_top_level(args, ";");
+    if items.iter().all(|item| item.trim().starts_with('"')) {
+        let items: Vec<String> = items
+            .iter()
+            .map(|item| {
+                let item = item.trim();
+                let inner = item.strip_prefix('"').unwrap_or(item);
+                inner
+                    .strip_suffix('"')
+                    .unwrap_or(inner)
+                    .replace("\"\"", "\"")
+            })
+            .collect();
+        format!("\"{}\"", items.join(",").replace('"', "\"\""))
+    } else {
+        normalize_ods_validation_formula(args)
+    }
+}
+
+/// Convert the references in a validation formula to Excel syntax
+fn normalize_ods_validation_formula(formula: &str) -> String {
+    let translated = openformula::to_excel(formula.trim(), &[]);
+    normalize_ods_reference(&translated, true, None, None)
+}
+
+/// Split at `separator` outside quotes and parentheses
+fn split_top_level<'a>(s: &'a str, separator: &str) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut in_quotes = false;
+    let mut start = 0;
+    let mut i = 0;
+    while i < s.len() {
+        let rest = &s[i..];
+        let c = rest.chars().next().unwrap_or_default();
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '(' if !in_quotes => depth += 1,
+            ')' if !in_quotes => depth -= 1,
+            _ if !in_quotes && depth == 0 && rest.starts_with(separator) => {
+                parts.push(&s[start..i]);
+                i += separator.len();
+                start = i;
+                continue;
+            }
+            _ => {}
+        }
+        i += c.len_utf8();
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// Merge the rectangles of adjacent validated cells into larger ranges
+fn merge_ranges(mut ranges: Vec<(u32, u32, u32, u32)>) -> Vec<(u32, u32, u32, u32)> {
+    // Side by side on the same rows
+    ranges.sort_unstable();
+    let mut rows: Vec<(u32, u32, u32, u32)> = Vec::new();
+    for range in ranges {
+        match rows.last_mut() {
+            Some(last) if (last.0, last.2) == (range.0, range.2) && last.3 + 1 == range.1 => {
+                last.3 = range.3;
+            }
+            _ => rows.push(range),
+        }
+    }
+
+    // Stacked over the same columns
+    rows.sort_unstable_by_key(|&(r1, c1, r2, c2)| (c1, c2, r1, r2));
+    let mut merged: Vec<(u32, u32, u32, u32)> = Vec::new();
+    for range in rows {
+        match merged.last_mut() {
+            Some(last) if (last.1, last.3) == (range.1, range.3) && last.2 + 1 == range.0 => {
+                last.2 = range.2;
+            }
+            _ => merged.push(range),
+        }
+    }
+    merged.sort_unstable();
+    merged
+}
+
+// Helper to calculate used range from cells
+fn calculate_used_range(cells: &CellStore) -> Option<(u32, u32)> {
+    let (_, max_row) = cells.row_bounds()?;
+    let (_, max_col) = cells.column_bounds()?;
+
+    // Return (max_row, max_col) as inclusive indices
+    Some((max_row, max_col))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::CellStore;
+
+    #[test]
+    fn test_normalize_ods_reference_basic() {
+        assert_eq!(
+            normalize_ods_reference("of:=SUM([.A1:.B2])", false, None, None),
+            "SUM(A1:B2)"
+        );
+        assert_eq!(
+            normalize_ods_reference("of:=[.A1]+[.B1]", false, None, None),
+            "A1+B1"
+        );
+        assert_eq!(
+            normalize_ods_reference("of:=SUM([.A:.A])", false, None, None),
+            "SUM(A:A)"
+        );
+        assert_eq!(
+            normalize_ods_reference("of:=SUM([.1:.1])", false, None, None),
+            "SUM(1:1)"
+        );
+    }
+
+    #[test]
+    fn test_normalize_ods_reference_sheet() {
+        assert_eq!(
+            normalize_ods_reference("of:=[$Sheet1.A1]*2", false, None, None),
+            "Sheet1!A1*2"
+        );
+        assert_eq!(
+            normalize_ods_reference("of:=SUM([$Sheet1.A1:.B2])", false, None, None),
+            "SUM(Sheet1!A1:B2)"
+        );
+        assert_eq!(
+            normalize_ods_reference("of:=$Sheet1.$A$1+$Sheet1.B1", false, None, None),
+            "Sheet1!$A$1+Sheet1!B1"
+        );
+    }
+
+    #[test]
+    fn test_normalize_ods_reference_mixed() {
+        assert_eq!(
+            normalize_ods_reference("of:=[.A1:.$B$2]", false, None, None),
+            "A1:$B$2"
+        );
+        assert_eq!(
+            normalize_ods_reference("of:=[.A$1]+$Sheet1.B$2", false, None, None),
+            "A$1+Sheet1!B$2"
+        );
+    }
+
+    #[test]
+    fn test_normalize_ods_range() {
+        // Local range with redundant sheet names
+        assert_eq!(
+            normalize_ods_reference("Sheet1.B2:Sheet1.B4", false, None, None),
+            "B2:B4"
+        );
+        // Single cell ref
+        assert_eq!(
+            normalize_ods_reference("Sheet1.A1", false, None, None),
+            "A1"
+        );
+        // Multi-sheet range
+        assert_eq!(
+            normalize_ods_reference("Sheet1.A1:Sheet2.B2", false, None, None),
+            "Sheet1!A1:Sheet2!B2"
+        );
+        // Absolute local ref
+        assert_eq!(
+            normalize_ods_reference("Sheet1.$A$1", false, None, None),
+            "$A$1"
+        );
+    }
+    #[test]
+    fn test_normalize_ods_unbracketed_range() {
+        // User reported "Listas!$D$19:.$M$19" appearing in output (dot issue).
+        // This suggests input was "$Listas.$D$19:.$M$19" (no brackets, like database ranges)
+        // and sheet_range_ref failed because it expects brackets.
+        let raw = "$Listas.$D$19:.$M$19";
+
+        // With preserve=true (defined names), we want the full sheet qualification
+        let expected_true = "Listas!$D$19:$M$19";
+        assert_eq!(
+            normalize_ods_reference(raw, true, None, None),
+            expected_true,
+            "Failed with preserve=true"
+        );
+
+        // With preserve=false (local formulas), we want to strip the sheet name if possible
+        // to avoid false circular references (ERR003 treat explicit self-sheet as non-trivial)
+        let expected_false = "$D$19:$M$19";
+        assert_eq!(
+            normalize_ods_reference(raw, false, None, None),
+            expected_false,
+            "Failed with preserve=false"
+        );
+    }
+
+    #[test]
+    fn test_normalize_ods_strip_after_regex_match() {
+        // If 0b2 matches "$Sheet1.A1:.$B2" -> "Sheet1!A1:B2"
+        // 0c should SHOULD strip "Sheet1!" if preserve_sheet=false
+        let raw = "$Sheet1.A1:.$B2";
+        // Currently (before fix) this prints "Sheet1!A1:B2"
+        // We want "A1:B2" if preserve_sheet=false (simulating local formula)
+        // With preserve=false, it should strip matched sheet names
+        assert_eq!(normalize_ods_reference(raw, false, None, None), "A1:$B2");
+
+        // Also check bracketed case
+        let raw_bracket = "[$Sheet1.A1:.$B2]";
+        assert_eq!(
+            normalize_ods_reference(raw_bracket, false, None, None),
+            "A1:$B2"
+        );
+    }
+
+    #[test]
+    fn test_normalize_ods_preserve_sheet() {
+        // Should preserve sheet name even if it looks local
+        assert_eq!(
+            normalize_ods_reference("Sheet1.A1", true, None, None),
+            "Sheet1.A1"
+        );
+        // Should preserve absolute local ref
+        assert_eq!(
+            normalize_ods_reference("Sheet1.$G$2", true, None, None),
+            "Sheet1.$G$2"
+        );
+        // Normal ranges should still be processed if they don't match the strip pattern
+        // But our strip pattern in 0c matches: ([^.]+)\.([A-Z0-9$]+):([^.]+)\.([A-Z0-9$]+)
+        // If preserve=true, this pattern is skipped.
+        assert_eq!(
+            normalize_ods_reference("Sheet1.A1:Sheet1.B2", true, None, None),
+            "Sheet1.A1:Sheet1.B2"
+        );
+    }
+
+    #[test]
+    fn test_normalize_ods_reference_single_cell_range() {
+        // PERF004 regression: "Sheet1.A1:Sheet1.A1" should normalize to "A1"
+        assert_eq!(
+            normalize_ods_reference("Sheet1.A1:Sheet1.A1", false, None, None),
+            "A1"
+        );
+        assert_eq!(normalize_ods_reference("A1:A1", false, None, None), "A1");
+        assert_eq!(
+            normalize_ods_reference("[.A1:.A1]", false, None, None),
+            "A1"
+        );
+    }
+
+    #[test]
+    fn test_read_database_ranges_ods() {
+        use std::io::Cursor;
+        use std::io::Write;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+
+            zip.start_file("content.xml", options).unwrap();
+            zip.write_all(br#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0">
+    <office:body>
+        <office:spreadsheet>
+            <table:table table:name="Sheet1">
+                <table:table-row><table:table-cell/></table:table-row>
+                <table:named-expressions>
+                    <table:named-range table:name="Local" table:base-cell-address="$Sheet1.$A$1" table:cell-range-address="$Sheet1.$A$1"/>
+                </table:named-expressions>
+            </table:table>
+            <table:named-expressions>
+                <table:named-range table:name="Global" table:base-cell-address="$Sheet1.$A$1" table:cell-range-address="$Sheet1.$B$1"/>
+            </table:named-expressions>
+            <table:database-ranges>
+                <table:database-range table:name="MyRange" table:target-range-address="Sheet1.A1:Sheet1.B2"/>
+                <table:database-range table:name="OtherRange" table:target-range-address="Sheet1.C3"/>
+            </table:database-ranges>
+        </office:spreadsheet>
+    </office:body>
+</office:document-content>"#).unwrap();
+
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let mut reader = OdsReader::new(&mut archive).unwrap();
+        let defined_names = reader.read_defined_names().unwrap();
+
+        // Note: read_defined_names returns normalized Excel-style references
+        assert_eq!(defined_names.len(), 4);
+        // Named ranges inside a table:table are local to that sheet
+        assert_eq!(
+            defined_names[0],
+            DefinedName {
+                scope: Some("Sheet1".to_string()),
+                ..DefinedName::new("Local", "Sheet1!$A$1")
+            }
+        );
+        assert_eq!(defined_names[1], DefinedName::new("Global", "Sheet1!$B$1"));
+        // "Sheet1.A1:Sheet1.B2" -> normalized with preserve_sheet=true keeps it as is
+        assert_eq!(
+            defined_names[2],
+            DefinedName::new("MyRange", "Sheet1.A1:Sheet1.B2")
+        );
+        // "Sheet1.C3" -> normalizes to "Sheet1.C3"
+        assert_eq!(
+            defined_names[3],
+            DefinedName::new("OtherRange", "Sheet1.C3")
+        );
+    }
+
+    #[test]
+    fn test_extract_tables_from_ods() {
+        use std::io::Cursor;
+        use std::io::Write;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+
+            zip.start_file("content.xml", options).unwrap();
+            zip.write_all(br#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+    <office:body>
+        <office:spreadsheet>
+            <table:table table:name="Q1 Sales">
+                <table:table-row table:number-rows-repeated="2"><table:table-cell table:number-columns-repeated="3"/></table:table-row>
+                <table:table-row>
+                    <table:table-cell/>
+                    <table:table-cell><text:p>Region</text:p></table:table-cell>
+                    <table:table-cell><text:p>Amount</text:p></table:table-cell>
+                </table:table-row>
+                <table:table-row><table:table-cell/><table:table-cell><text:p>North</text:p></table:table-cell><table:table-cell><text:p>10</text:p></table:table-cell></table:table-row>
+            </table:table>
+            <table:database-ranges>
+                <table:database-range table:name="Sales" table:target-range-address="'Q1 Sales'.B3:'Q1 Sales'.C4"/>
+                <table:database-range table:name="Raw" table:target-range-address="$'Q1 Sales'.$A$1:.$B$2" table:contains-header="false"/>
+                <table:database-range table:name="__Anonymous_Sheet_DB__0" table:target-range-address="'Q1 Sales'.A1:'Q1 Sales'.C4"/>
+            </table:database-ranges>
+        </office:spreadsheet>
+    </office:body>
+</office:document-content>"#).unwrap();
+
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let tables = extract_tables_from_ods(&mut archive).unwrap();
+
+        assert_eq!(
+            tables,
+            vec![
+                Table {
+                    name: "Sales".to_string(),
+                    sheet: "Q1 Sales".to_string(),
+                    range: (2, 1, 3, 2),
+                    columns: vec!["Region".to_string(), "Amount".to_string()],
+                    header_rows: 1,
+                    ..Default::default()
+                },
+                Table {
+                    name: "Raw".to_string(),
+                    sheet: "Q1 Sales".to_string(),
+                    range: (0, 0, 1, 1),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_styled_empty_cell_ods() {
+        use std::io::Cursor;
+        use std::io::Write;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+
+            zip.start_file("content.xml", options).unwrap();
+            zip.write_all(br#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0">
+    <office:body>
+        <office:spreadsheet>
+            <table:table table:name="Sheet1">
+                <table:table-row>
+                    <table:table-cell table:style-name="ce1"/>
+                </table:table-row>
+            </table:table>
+        </office:spreadsheet>
+    </office:body>
+</office:document-content>"#).unwrap();
+
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let mut reader = OdsReader::new(&mut archive).unwrap();
+        let sheets = reader.read_sheets().unwrap();
+
+        assert_eq!(sheets.len(), 1);
+        let sheet = &sheets[0];
+        // The cell (0, 0) should be in the map because it has a style "ce1"
+        assert!(sheet.cells.contains_key(&(0, 0)));
+        assert_eq!(sheet.cells.get(&(0, 0)).unwrap().value, CellValue::Empty);
+    }
+
+    #[test]
+    fn test_annotations_ods() {
+        use std::io::Cursor;
+        use std::io::Write;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+
+            zip.start_file("content.xml", options).unwrap();
+            zip.write_all(br#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <office:body>
+        <office:spreadsheet>
+            <table:table table:name="Sheet1">
+                <table:table-row>
+                    <table:table-cell office:value-type="string"><office:annotation><dc:creator>Ann</dc:creator><dc:date>2024-05-01T10:00:00</dc:date><text:p>First line</text:p><text:p>Second line</text:p></office:annotation><text:p>Value</text:p></table:table-cell>
+                    <table:table-cell><office:annotation><text:p>On a blank cell</text:p></office:annotation></table:table-cell>
+                </table:table-row>
+            </table:table>
+        </office:spreadsheet>
+    </office:body>
+</office:document-content>"#).unwrap();
+
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let mut reader = OdsReader::new(&mut archive).unwrap();
+        let sheet = reader.read_sheet("Sheet1").unwrap();
+
+        let cell = &sheet.cells[&(0, 0)];
+        assert_eq!(cell.value, CellValue::Text("Value".to_string(), None));
+        let comment = cell.comment.as_ref().unwrap();
+        assert_eq!(comment.author.as_deref(), Some("Ann"));
+        assert_eq!(comment.date.as_deref(), Some("2024-05-01T10:00:00"));
+        assert_eq!(comment.text, "First line\nSecond line");
+
+        let blank = &sheet.cells[&(0, 1)];
+        assert_eq!(blank.value, CellValue::Empty);
+        assert_eq!(
+            blank.comment.as_ref().map(|c| c.text.as_str()),
+            Some("On a blank cell")
+        );
+    }
+
+    #[test]
+    fn test_hyperlinks_ods() {
+        use std::io::Cursor;
+        use std::io::Write;
+        use zip::write::FileOptions;
 
-                        // Use text content if we have it and no other value
-                        if !text_content.is_empty() {
-                            if is_error_cell {
-                                value = CellValue::formula_with_error("", text_content);
-                                has_value = true;
-                            } else if !has_value {
-                                // Only use text:p content if we don't have a value from attributes
-                                value = CellValue::Text(text_content);
-                                has_value = true;
-                            }
-                        }
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
 
-                        if has_value || formula.is_some() || !style_name.is_empty() {
-                            let mut cell_value = value;
-                            if let Some(f) = formula {
-                                cell_value = match cell_value {
-                                    CellValue::Formula {
-                                        cached_error: Some(msg),
-                                        ..
-                                    } => CellValue::formula_with_error(f, msg),
-                                    _ => CellValue::formula(f),
-                                };
-                            }
+            zip.start_file("content.xml", options).unwrap();
+            zip.write_all(br##"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" xmlns:xlink="http://www.w3.org/1999/xlink">
+    <office:body>
+        <office:spreadsheet>
+            <table:table table:name="Sheet1">
+                <table:table-row>
+                    <table:table-cell office:value-type="string"><text:p><text:a xlink:href="https://example.com/docs" xlink:type="simple">Docs</text:a></text:p></table:table-cell>
+                    <table:table-cell office:value-type="string"><text:p><text:a xlink:href="#Sheet2.A1">Jump</text:a></text:p></table:table-cell>
+                    <table:table-cell table:formula="of:=HYPERLINK(&quot;file:///C:/data/report.ods&quot;;&quot;Report&quot;)" office:value-type="string"><text:p>Report</text:p></table:table-cell>
+                    <table:table-cell office:value-type="string"><text:p>Plain</text:p></table:table-cell>
+                </table:table-row>
+            </table:table>
+        </office:spreadsheet>
+    </office:body>
+</office:document-content>"##).unwrap();
 
-                            // Look up format string from style
-                            let num_fmt = if !style_name.is_empty() {
-                                date_styles.get(&style_name).cloned()
-                            } else {
-                                None
-                            };
+            zip.finish().unwrap();
+        }
 
-                            // Check if this is a text-formatted number
-                            // In ODS, text format is indicated by num_fmt == "@"
-                            if num_fmt.as_deref() == Some("@")
-                                && let CellValue::Number(n) = cell_value
-                            {
-                                // Convert number to text
-                                cell_value = CellValue::Text(n.to_string());
-                            }
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let mut reader = OdsReader::new(&mut archive).unwrap();
+        let sheet = reader.read_sheet("Sheet1").unwrap();
 
-                            for r in 0..row_repeated {
-                                for c in 0..col_repeated {
-                                    let cell = Cell {
-                                        row: current_row + r,
-                                        col: current_col + c,
-                                        value: cell_value.clone(),
-                                        num_fmt: num_fmt.clone(),
-                                    };
-                                    sheet.cells.insert((current_row + r, current_col + c), cell);
+        assert_eq!(sheet.hyperlinks.len(), 3);
+        let external = sheet.hyperlink_at(0, 0).unwrap();
+        assert_eq!(external.target, "https://example.com/docs");
+        assert_eq!(external.display.as_deref(), Some("Docs"));
+        assert!(!external.internal);
+        assert_eq!(
+            sheet.cells[&(0, 0)].value,
+            CellValue::Text("Docs".to_string(), None)
+        );
 
-                                    // Update used_range for any inserted cell (value, formula, or style)
-                                    let row_pos = current_row + r;
-                                    let col_pos = current_col + c;
-                                    if let Some((max_row, max_col)) = sheet.used_range {
-                                        sheet.used_range = Some((
-                                            max_row.max(row_pos + 1),
-                                            max_col.max(col_pos + 1),
-                                        ));
-                                    } else {
-                                        sheet.used_range = Some((row_pos + 1, col_pos + 1));
-                                    }
-                                }
-                            }
-                        }
+        let internal = sheet.hyperlink_at(0, 1).unwrap();
+        assert_eq!(internal.target, "Sheet2.A1");
+        assert!(internal.internal);
 
-                        // Multiply repeated by spanned to get true column consumption
-                        current_col += col_repeated;
-                    }
-                }
-                Event::Empty(e)
-                    if e.name().as_ref() == b"table:table-cell"
-                        || e.name().as_ref() == b"table:covered-table-cell" =>
-                {
-                    if let Some(ref mut sheet) = current_sheet {
-                        let mut col_repeated = 1u32;
-                        let mut cols_spanned = 1u32;
-                        let mut rows_spanned = 1u32;
-                        let mut formula = None;
-                        let mut style_name = String::new();
+        let formula = sheet.hyperlink_at(0, 2).unwrap();
+        assert_eq!(formula.target, "file:///C:/data/report.ods");
+        assert_eq!(formula.display.as_deref(), Some("Report"));
+        assert!(formula.is_file());
+        assert!(sheet.hyperlink_at(0, 3).is_none());
+    }
 
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"table:number-columns-repeated" => {
-                                    col_repeated =
-                                        attr.unescape_value()?.parse::<u32>().unwrap_or(1);
-                                }
-                                b"table:number-columns-spanned" => {
-                                    cols_spanned =
-                                        attr.unescape_value()?.parse::<u32>().unwrap_or(1);
-                                }
-                                b"table:number-rows-spanned" => {
-                                    rows_spanned =
-                                        attr.unescape_value()?.parse::<u32>().unwrap_or(1);
-                                }
-                                b"table:formula" => {
-                                    let raw_formula = attr.unescape_value()?;
-                                    formula = Some(normalize_ods_reference(
-                                        &raw_formula,
-                                        false,
-                                        Some(&visible_to_xml_row),
-                                        Some(&sheet.name),
-                                    ));
-                                }
-                                b"table:style-name" => {
-                                    style_name = attr.unescape_value()?.to_string();
-                                }
-                                _ => {}
-                            }
-                        }
+    #[test]
+    fn test_conditional_formats_ods() {
+        use std::io::Cursor;
+        use std::io::Write;
+        use zip::write::FileOptions;
 
-                        // Check if this empty cell is actually a merged cell
-                        if cols_spanned > 1 || rows_spanned > 1 {
-                            sheet.merged_cells.push((
-                                current_row,
-                                current_col,
-                                current_row + rows_spanned - 1,
-                                current_col + cols_spanned - 1,
-                            ));
-                        }
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
 
-                        // If it's an empty cell but has a formula or style, we should store it.
-                        if formula.is_some() || !style_name.is_empty() {
-                            let cell_value =
-                                formula.map(CellValue::formula).unwrap_or(CellValue::Empty);
+            zip.start_file("content.xml", options).unwrap();
+            zip.write_all(br##"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" xmlns:calcext="urn:org:documentfoundation:names:experimental:calc:xmlns:calcext:1.0">
+    <office:body>
+        <office:spreadsheet>
+            <table:table table:name="Sheet1">
+                <table:table-row>
+                    <table:table-cell office:value-type="float" office:value="1"><text:p>1</text:p></table:table-cell>
+                </table:table-row>
+                <calcext:conditional-formats>
+                    <calcext:conditional-format calcext:target-range-address="Sheet1.A1:Sheet1.A10 Sheet1.C3">
+                        <calcext:condition calcext:apply-style-name="Good" calcext:value="&gt;=5" calcext:base-cell-address="Sheet1.A1"/>
+                        <calcext:condition calcext:apply-style-name="Bad" calcext:value="between(1,[.B1])" calcext:base-cell-address="Sheet1.A1"/>
+                    </calcext:conditional-format>
+                    <calcext:conditional-format calcext:target-range-address="'My Sheet'.B1:'My Sheet'.B5">
+                        <calcext:condition calcext:apply-style-name="Good" calcext:value="formula-is(ISERROR([.B1]))" calcext:base-cell-address="'My Sheet'.B1"/>
+                        <calcext:condition calcext:apply-style-name="Good" calcext:value="duplicate" calcext:base-cell-address="'My Sheet'.B1"/>
+                    </calcext:conditional-format>
+                    <calcext:conditional-format calcext:target-range-address="Sheet1.D1:Sheet1.D20">
+                        <calcext:color-scale>
+                            <calcext:color-scale-entry calcext:value="0" calcext:type="minimum" calcext:color="#f8696b"/>
+                            <calcext:color-scale-entry calcext:value="50" calcext:type="percentile" calcext:color="#ffeb84"/>
+                            <calcext:color-scale-entry calcext:value="0" calcext:type="maximum" calcext:color="#63be7b"/>
+                        </calcext:color-scale>
+                    </calcext:conditional-format>
+                </calcext:conditional-formats>
+            </table:table>
+        </office:spreadsheet>
+    </office:body>
+</office:document-content>"##).unwrap();
 
-                            // Look up format string from style
-                            let num_fmt = if !style_name.is_empty() {
-                                date_styles.get(&style_name).cloned()
-                            } else {
-                                None
-                            };
+            zip.finish().unwrap();
+        }
 
-                            for r in 0..row_repeated {
-                                for c in 0..col_repeated {
-                                    let cell = Cell {
-                                        row: current_row + r,
-                                        col: current_col + c,
-                                        value: cell_value.clone(),
-                                        num_fmt: num_fmt.clone(),
-                                    };
-                                    sheet.cells.insert((current_row + r, current_col + c), cell);
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let mut reader = OdsReader::new(&mut archive).unwrap();
+        let sheet = reader.read_sheet("Sheet1").unwrap();
+        let rules = &sheet.conditional_formats;
+        assert_eq!(rules.len(), 5);
 
-                                    // Update used_range for any inserted cell (formula or style)
-                                    let row_pos = current_row + r;
-                                    let col_pos = current_col + c;
-                                    if let Some((max_row, max_col)) = sheet.used_range {
-                                        sheet.used_range = Some((
-                                            max_row.max(row_pos + 1),
-                                            max_col.max(col_pos + 1),
-                                        ));
-                                    } else {
-                                        sheet.used_range = Some((row_pos + 1, col_pos + 1));
-                                    }
-                                }
-                            }
-                        }
+        assert_eq!(rules[0].kind, ConditionalFormatKind::CellIs);
+        assert_eq!(
+            rules[0].operator,
+            Some(ValidationOperator::GreaterThanOrEqual)
+        );
+        assert_eq!(rules[0].formulas, vec!["5"]);
+        assert_eq!(rules[0].ranges, vec![(0, 0, 9, 0), (2, 2, 2, 2)]);
+        assert_eq!(rules[0].priority, 1);
 
-                        // Multiply repeated by spanned to get true column consumption
-                        current_col += col_repeated;
-                    }
-                }
-                Event::Start(e) if e.name().as_ref() == b"calcext:conditional-format" => {
-                    for attr in e.attributes().flatten() {
-                        if attr.key.as_ref() == b"calcext:target-range-address" {
-                            current_cf_range = Some(attr.unescape_value()?.to_string());
-                        }
-                    }
-                }
-                Event::End(e) if e.name().as_ref() == b"calcext:conditional-format" => {
-                    current_cf_range = None;
-                }
-                Event::Start(e) if e.name().as_ref() == b"calcext:condition" => {
-                    if let Some(ref mut sheet) = current_sheet {
-                        sheet.conditional_formatting_count += 1;
-                        if let Some(ref range) = current_cf_range {
-                            sheet
-                                .conditional_formatting_ranges
-                                .push(normalize_ods_reference(range, false, None, None));
-                        }
-                    }
-                }
-                // standard ODS conditional formatting
-                Event::Start(e) if e.name().as_ref() == b"table:conditional-formatting" => {
-                    for attr in e.attributes().flatten() {
-                        if attr.key.as_ref() == b"table:target-range-address" {
-                            current_cf_range = Some(attr.unescape_value()?.to_string());
-                        }
-                    }
-                }
-                Event::End(e) if e.name().as_ref() == b"table:conditional-formatting" => {
-                    current_cf_range = None;
-                }
-                Event::Start(e) if e.name().as_ref() == b"table:conditional-formatting-rule" => {
-                    if let Some(ref mut sheet) = current_sheet {
-                        sheet.conditional_formatting_count += 1;
-                        if let Some(ref range) = current_cf_range {
-                            sheet
-                                .conditional_formatting_ranges
-                                .push(normalize_ods_reference(range, false, None, None));
-                        }
-                    }
-                }
-                Event::Empty(e) if e.name().as_ref() == b"calcext:condition" => {
-                    if let Some(ref mut sheet) = current_sheet {
-                        sheet.conditional_formatting_count += 1;
-                        if let Some(ref range) = current_cf_range {
-                            sheet
-                                .conditional_formatting_ranges
-                                .push(normalize_ods_reference(range, false, None, None));
-                        }
-                    }
-                }
-                Event::End(e) if e.name().as_ref() == b"table:table-row" => {
-                    current_row += row_repeated;
-                    current_col = 0;
-                }
-                Event::End(e) if e.name().as_ref() == b"table:table" => {
-                    // Calculate used range for the sheet before finalizing
-                    // This must happen here (not at sheet finalization) because it needs to run
-                    // for ALL sheets, whether they have conditional formatting or not
-                    if let Some(ref mut sheet) = current_sheet {
-                        let cells_range = calculate_used_range(&sheet.cells);
+        assert_eq!(rules[1].operator, Some(ValidationOperator::Between));
+        assert_eq!(rules[1].formulas, vec!["1", "B1"]);
+        assert_eq!(rules[1].priority, 2);
 
-                        // Merge styled cell tracking with value cells
-                        // Both are already in count format (0-indexed position + 1)
-                        sheet.used_range = match (sheet.used_range, cells_range) {
-                            (Some((s_row, s_col)), Some((c_row, c_col))) => {
-                                Some((s_row.max(c_row), s_col.max(c_col)))
-                            }
-                            (Some(s), None) => Some(s),
-                            (None, Some(c)) => Some(c),
-                            (None, None) => None,
-                        };
+        assert_eq!(rules[2].kind, ConditionalFormatKind::Expression);
+        assert_eq!(rules[2].operator, None);
+        assert_eq!(rules[2].formulas, vec!["ISERROR(B1)"]);
+        assert_eq!(rules[2].sqref(), "B1:B5");
 
-                        // Include hidden rows/columns in used_range for format parity
-                        // Both ODS and XLSX should report ALL empty rows/columns (visible or hidden)
-                        if let Some((mut rows, mut cols)) = sheet.used_range {
-                            if let Some(&max_hidden_row) = sheet.hidden_rows.iter().max() {
-                                rows = rows.max(max_hidden_row + 1);
-                            }
-                            if let Some(&max_hidden_col) = sheet.hidden_columns.iter().max() {
-                                cols = cols.max(max_hidden_col + 1);
-                            }
-                            sheet.used_range = Some((rows, cols));
-                        }
-                    }
-                }
+        assert_eq!(rules[3].kind, ConditionalFormatKind::DuplicateValues);
+        assert!(rules[3].formulas.is_empty());
 
-                // Handle conditional formatting that appears after table closing tag
-                Event::Start(e) if e.name().as_ref() == b"calcext:conditional-formats" => {
-                    // This wrapper appears after </table:table>, continue processing
-                }
-                Event::End(e) if e.name().as_ref() == b"calcext:conditional-formats" => {
-                    // End of conditional formatting section
-                    // Don't finalize the sheet here - the table end handler will do it
-                }
-                Event::Eof => break,
-                _ => {}
-            }
-            buf.clear();
-        }
+        assert_eq!(rules[4].kind, ConditionalFormatKind::ColorScale);
+        assert_eq!(rules[4].formulas, vec!["50"]);
+        assert_eq!(rules[4].priority, 5);
+    }
 
-        // Finalize the last sheet if it exists and it's not external
-        if let Some(sheet) = current_sheet
-            && !skip_current_sheet
+    #[test]
+    fn test_pivot_tables_ods() {
+        use std::io::Cursor;
+        use std::io::Write;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
         {
-            sheets.push(sheet);
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+
+            zip.start_file("content.xml", options).unwrap();
+            zip.write_all(br##"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0">
+    <office:body>
+        <office:spreadsheet>
+            <table:table table:name="Data"/>
+            <table:data-pilot-tables>
+                <table:data-pilot-table table:name="DataPilot1" table:target-range-address="Report.A3:Report.C8" table:buttons="Report.A1">
+                    <table:source-cell-range table:cell-range-address="Data.A1:Data.C201"/>
+                    <table:data-pilot-field table:source-field-name="Region" table:orientation="row"/>
+                </table:data-pilot-table>
+                <table:data-pilot-table table:name="DataPilot2" table:target-range-address="'My Report'.F3:'My Report'.G10">
+                    <table:source-cell-range table:cell-range-address="'file:///home/finance/budget.ods'#$'Plan 2024'.A1:.B50"/>
+                </table:data-pilot-table>
+                <table:data-pilot-table table:name="DataPilot3" table:target-range-address="Report.J1:Report.K4">
+                    <table:database-source-table table:database-name="Sales" table:database-table-name="Orders"/>
+                </table:data-pilot-table>
+            </table:data-pilot-tables>
+        </office:spreadsheet>
+    </office:body>
+</office:document-content>"##).unwrap();
+
+            zip.finish().unwrap();
         }
 
-        Ok(sheets)
-    }
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let mut reader = OdsReader::new(&mut archive).unwrap();
+        let pivots = reader.read_pivot_tables().unwrap();
+        assert_eq!(pivots.len(), 3);
 
-    fn read_defined_names(&mut self) -> Result<HashMap<String, String>> {
-        let mut defined_names = HashMap::new();
+        assert_eq!(pivots[0].name, "DataPilot1");
+        assert_eq!(pivots[0].sheet, "Report");
+        assert_eq!(pivots[0].location, (2, 0, 7, 2));
+        assert_eq!(
+            pivots[0].source,
+            PivotSource::Range {
+                sheet: "Data".to_string(),
+                reference: "A1:C201".to_string(),
+            }
+        );
+        assert_eq!(pivots[0].cache, None);
 
-        let content_xml = match self.archive.by_name("content.xml") {
-            Ok(file) => file,
-            Err(_) => return Ok(defined_names),
-        };
+        assert_eq!(pivots[1].sheet, "My Report");
+        assert_eq!(
+            pivots[1].source,
+            PivotSource::External {
+                workbook: "file:///home/finance/budget.ods".to_string(),
+                reference: "'Plan 2024'!A1:B50".to_string(),
+            }
+        );
 
-        let mut reader = Reader::from_reader(BufReader::new(content_xml));
-        reader.config_mut().trim_text(true);
+        assert_eq!(
+            pivots[2].source,
+            PivotSource::Connection("Sales.Orders".to_string())
+        );
+    }
 
-        let mut buf = Vec::new();
-        let mut in_named_expressions = false;
-        let mut in_database_ranges = false;
+    #[test]
+    fn test_drawings_ods() {
+        use std::io::Cursor;
+        use std::io::Write;
+        use zip::write::FileOptions;
 
-        loop {
-            match reader.read_event_into(&mut buf)? {
-                Event::Start(ref e) if e.name().as_ref() == b"table:named-expressions" => {
-                    in_named_expressions = true;
-                }
-                Event::Start(ref e) if e.name().as_ref() == b"table:database-ranges" => {
-                    in_database_ranges = true;
-                }
-                // Combined match for Start/Empty of item tags
-                Event::Empty(ref e) | Event::Start(ref e) => {
-                    if in_named_expressions && e.name().as_ref() == b"table:named-range" {
-                        let mut name = String::new();
-                        let mut cell_range_address = String::new();
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
 
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"table:name" => {
-                                    name = attr.unescape_value()?.to_string();
-                                }
-                                b"table:cell-range-address" => {
-                                    cell_range_address = attr.unescape_value()?.to_string();
-                                }
-                                _ => {}
-                            }
-                        }
+            zip.start_file("content.xml", options).unwrap();
+            zip.write_all(br#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" xmlns:draw="urn:oasis:names:tc:opendocument:xmlns:drawing:1.0" xmlns:xlink="http://www.w3.org/1999/xlink">
+    <office:body>
+        <office:spreadsheet>
+            <table:table table:name="Sheet1">
+                <table:shapes>
+                    <draw:frame draw:name="Logo"><draw:image xlink:href="Pictures/logo.png"/></draw:frame>
+                </table:shapes>
+                <table:table-row>
+                    <table:table-cell office:value-type="float" office:value="1"><text:p>1</text:p></table:table-cell>
+                    <table:table-cell>
+                        <draw:frame draw:name="Chart 1">
+                            <draw:object xlink:href="./Object 1"/>
+                            <draw:image xlink:href="./ObjectReplacements/Object 1"/>
+                        </draw:frame>
+                    </table:table-cell>
+                    <table:table-cell office:value-type="float" office:value="2"><text:p>2</text:p></table:table-cell>
+                </table:table-row>
+            </table:table>
+        </office:spreadsheet>
+    </office:body>
+</office:document-content>"#).unwrap();
 
-                        if !name.is_empty() && !cell_range_address.is_empty() {
-                            let normalized =
-                                normalize_ods_reference(&cell_range_address, true, None, None);
-                            defined_names.insert(name, normalized);
-                        }
-                    } else if in_database_ranges && e.name().as_ref() == b"table:database-range" {
-                        let mut name = String::new();
-                        let mut target_range_address = String::new();
+            zip.start_file("Object 1/content.xml", options).unwrap();
+            zip.write_all(br##"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:chart="urn:oasis:names:tc:opendocument:xmlns:chart:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+    <office:body>
+        <office:chart>
+            <chart:chart chart:class="chart:circle">
+                <chart:title><text:p>Share</text:p></chart:title>
+                <chart:plot-area table:cell-range-address="Sheet1.A1:Sheet1.B5">
+                    <chart:axis chart:dimension="x"><chart:categories table:cell-range-address="Sheet1.A2:Sheet1.A5"/></chart:axis>
+                    <chart:axis chart:dimension="y"><chart:title><text:p>Units</text:p></chart:title></chart:axis>
+                    <chart:series chart:values-cell-range-address="Sheet1.B2:Sheet1.B5" chart:label-cell-address="Sheet1.B1"/>
+                    <chart:series chart:values-cell-range-address="#REF!"><chart:data-point/></chart:series>
+                </chart:plot-area>
+            </chart:chart>
+        </office:chart>
+    </office:body>
+</office:document-content>"##).unwrap();
 
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"table:name" => {
-                                    name = attr.unescape_value()?.to_string();
-                                }
-                                b"table:target-range-address" => {
-                                    target_range_address = attr.unescape_value()?.to_string();
-                                }
-                                _ => {}
-                            }
-                        }
+            zip.start_file("Pictures/logo.png", options).unwrap();
+            zip.write_all(&[0u8; 300]).unwrap();
+            zip.start_file("ObjectReplacements/Object 1", options)
+                .unwrap();
+            zip.write_all(&[0u8; 50]).unwrap();
 
-                        if !name.is_empty() && !target_range_address.is_empty() {
-                            // Filter out internal ODS names that start with __Anonymous_Sheet_DB__
-                            if !name.starts_with("__Anonymous_Sheet_DB__") {
-                                let normalized = normalize_ods_reference(
-                                    &target_range_address,
-                                    true,
-                                    None,
-                                    None,
-                                );
-                                defined_names.insert(name, normalized);
-                            }
-                        }
-                    }
-                }
-                Event::End(e) => {
-                    if e.name().as_ref() == b"table:named-expressions" {
-                        in_named_expressions = false;
-                    } else if e.name().as_ref() == b"table:database-ranges" {
-                        in_database_ranges = false;
-                    }
-                }
-                Event::Eof => break,
-                _ => {}
-            }
-            buf.clear();
+            zip.finish().unwrap();
         }
 
-        Ok(defined_names)
-    }
-
-    fn read_hidden_sheets(&mut self) -> Result<Vec<String>> {
-        extract_hidden_sheets_from_ods(self.archive)
-    }
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let mut reader = OdsReader::new(&mut archive).unwrap();
+        let sheet = reader.read_sheet("Sheet1").unwrap();
 
-    fn has_macros(&mut self) -> Result<bool> {
-        has_macros(self.archive)
-    }
+        // The frame doesn't stop the row's cells from being read
+        assert!(sheet.get_cell(0, 2).is_some());
 
-    fn read_external_links(&mut self) -> Result<Vec<String>> {
-        extract_external_links_ods(self.archive)
-    }
+        assert_eq!(sheet.charts.len(), 1);
+        let chart = &sheet.charts[0];
+        assert_eq!(chart.kind, "pie");
+        assert_eq!(chart.title.as_deref(), Some("Share"));
+        assert_eq!(chart.anchor, (0, 1));
+        assert_eq!(
+            chart.series,
+            vec![
+                ChartSeries {
+                    name: Some("Sheet1!B1".to_string()),
+                    categories: Some("Sheet1!A2:A5".to_string()),
+                    values: Some("Sheet1!B2:B5".to_string()),
+                },
+                ChartSeries {
+                    name: None,
+                    categories: Some("Sheet1!A2:A5".to_string()),
+                    values: Some("#REF!".to_string()),
+                },
+            ]
+        );
 
-    fn read_external_workbooks(&mut self) -> Result<Vec<super::ExternalWorkbook>> {
-        extract_external_workbooks_ods(self.archive)
+        assert_eq!(
+            sheet.images,
+            vec![Image {
+                name: "Logo".to_string(),
+                anchor: (0, 0),
+                path: "Pictures/logo.png".to_string(),
+                size: 300,
+            }]
+        );
     }
-}
+    #[test]
+    fn test_active_content_ods() {
+        use std::io::Cursor;
+        use std::io::Write;
+        use zip::write::FileOptions;
 
-// Helper to calculate used range from cells
-fn calculate_used_range(cells: &HashMap<(u32, u32), Cell>) -> Option<(u32, u32)> {
-    if cells.is_empty() {
-        return None;
-    }
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
 
-    let mut max_row = 0;
-    let mut max_col = 0;
+            zip.start_file("content.xml", options).unwrap();
+            zip.write_all(br#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:draw="urn:oasis:names:tc:opendocument:xmlns:drawing:1.0" xmlns:script="urn:oasis:names:tc:opendocument:xmlns:script:1.0" xmlns:form="urn:oasis:names:tc:opendocument:xmlns:form:1.0" xmlns:xlink="http://www.w3.org/1999/xlink">
+    <office:scripts>
+        <office:event-listeners>
+            <script:event-listener script:language="ooo:script" script:event-name="dom:load" xlink:href="vnd.sun.star.script:Standard.Module1.Main?language=Basic&amp;location=document"/>
+        </office:event-listeners>
+    </office:scripts>
+    <office:body>
+        <office:spreadsheet>
+            <table:table table:name="Empty"/>
+            <table:table table:name="Sheet1">
+                <office:forms>
+                    <form:form form:name="Form">
+                        <form:button form:name="PushButton">
+                            <office:event-listeners>
+                                <script:event-listener script:language="ooo:script" script:event-name="form:performaction" xlink:href="vnd.sun.star.script:Standard.Module1.Click?language=Basic&amp;location=document"/>
+                            </office:event-listeners>
+                        </form:button>
+                    </form:form>
+                </office:forms>
+                <table:table-row>
+                    <table:table-cell>
+                        <draw:frame draw:name="Object 2"><draw:object-ole xlink:href="./Object 2"/></draw:frame>
+                    </table:table-cell>
+                    <table:table-cell>
+                        <table:cell-range-source table:name="HTML_tables" table:filter-name="calc_HTML_WebQuery_Import" xlink:href="https://example.com/rates.html" table:last-column-spanned="3" table:last-row-spanned="10"/>
+                    </table:table-cell>
+                </table:table-row>
+            </table:table>
+            <table:database-ranges>
+                <table:database-range table:name="Orders" table:target-range-address="Sheet1.A5:Sheet1.C20">
+                    <table:database-source-sql table:database-name="Sales" table:sql-statement="SELECT * FROM orders"/>
+                </table:database-range>
+            </table:database-ranges>
+        </office:spreadsheet>
+    </office:body>
+</office:document-content>"#).unwrap();
 
-    for (row, col) in cells.keys() {
-        if *row > max_row {
-            max_row = *row;
-        }
-        if *col > max_col {
-            max_col = *col;
+            zip.finish().unwrap();
         }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let mut reader = OdsReader::new(&mut archive).unwrap();
+        let content = reader.read_active_content().unwrap();
+        let sheet = Some("Sheet1".to_string());
+        assert_eq!(
+            content,
+            vec![
+                ActiveContent {
+                    kind: ActiveContentKind::EventScript,
+                    sheet: None,
+                    name: "dom:load".to_string(),
+                    target: Some(
+                        "vnd.sun.star.script:Standard.Module1.Main?language=Basic&location=document"
+                            .to_string()
+                    ),
+                    runs_on_open: true,
+                },
+                ActiveContent {
+                    kind: ActiveContentKind::EventScript,
+                    sheet: sheet.clone(),
+                    name: "form:performaction".to_string(),
+                    target: Some(
+                        "vnd.sun.star.script:Standard.Module1.Click?language=Basic&location=document"
+                            .to_string()
+                    ),
+                    runs_on_open: false,
+                },
+                ActiveContent {
+                    kind: ActiveContentKind::OleObject,
+                    sheet: sheet.clone(),
+                    name: "Object 2".to_string(),
+                    target: Some("Object 2".to_string()),
+                    runs_on_open: false,
+                },
+                ActiveContent {
+                    kind: ActiveContentKind::WebQuery,
+                    sheet: sheet.clone(),
+                    name: "HTML_tables".to_string(),
+                    target: Some("https://example.com/rates.html".to_string()),
+                    runs_on_open: false,
+                },
+                ActiveContent {
+                    kind: ActiveContentKind::DataConnection,
+                    sheet: None,
+                    name: "Orders".to_string(),
+                    target: Some("Sales (SELECT * FROM orders)".to_string()),
+                    runs_on_open: false,
+                },
+            ]
+        );
     }
 
-    // Return (max_row, max_col) as inclusive indices
-    Some((max_row, max_col))
-}
+    #[test]
+    fn test_layout_ods() {
+        use crate::reader::Span;
+        use std::io::Cursor;
+        use std::io::Write;
+        use zip::write::FileOptions;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            let mut add = |name: &str, xml: &str| {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(xml.as_bytes()).unwrap();
+            };
+            add(
+                "content.xml",
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+    <office:automatic-styles>
+        <style:style style:name="co1" style:family="table-column"><style:table-column-properties style:column-width="10.5pt"/></style:style>
+        <style:style style:name="co2" style:family="table-column"><style:table-column-properties style:column-width="1in"/></style:style>
+        <style:style style:name="ro1" style:family="table-row"><style:table-row-properties style:row-height="0.452cm" style:use-optimal-row-height="true"/></style:style>
+        <style:style style:name="ro2" style:family="table-row"><style:table-row-properties style:row-height="30pt" style:use-optimal-row-height="false"/></style:style>
+    </office:automatic-styles>
+    <office:body>
+        <office:spreadsheet>
+            <table:table table:name="Sheet1">
+                <table:table-column table:style-name="co2"/>
+                <table:table-column-group>
+                    <table:table-column table:style-name="co1" table:number-columns-repeated="2"/>
+                </table:table-column-group>
+                <table:table-row table:style-name="ro2"><table:table-cell office:value-type="string"><text:p>Name</text:p></table:table-cell></table:table-row>
+                <table:table-row-group>
+                    <table:table-row table:style-name="ro1"><table:table-cell office:value-type="float" office:value="1"/></table:table-row>
+                    <table:table-row-group>
+                        <table:table-row table:style-name="ro1" table:number-rows-repeated="2"/>
+                    </table:table-row-group>
+                </table:table-row-group>
+                <table:table-row table:style-name="ro1"><table:table-cell/></table:table-row>
+            </table:table>
+            <table:table table:name="Sheet2"><table:table-row><table:table-cell/></table:table-row></table:table>
+        </office:spreadsheet>
+    </office:body>
+</office:document-content>"#,
+            );
+            add(
+                "settings.xml",
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-settings xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:config="urn:oasis:names:tc:opendocument:xmlns:config:1.0">
+    <office:settings>
+        <config:config-item-set config:name="ooo:view-settings">
+            <config:config-item-map-indexed config:name="Views">
+                <config:config-item-map-entry>
+                    <config:config-item config:name="ViewId" config:type="string">view1</config:config-item>
+                    <config:config-item-map-named config:name="Tables">
+                        <config:config-item-map-entry config:name="Sheet1">
+                            <config:config-item config:name="HorizontalSplitMode" config:type="short">2</config:config-item>
+                            <config:config-item config:name="VerticalSplitMode" config:type="short">2</config:config-item>
+                            <config:config-item config:name="HorizontalSplitPosition" config:type="int">1</config:config-item>
+                            <config:config-item config:name="VerticalSplitPosition" config:type="int">1</config:config-item>
+                            <config:config-item config:name="ZoomValue" config:type="int">120</config:config-item>
+                            <config:config-item config:name="TabColor" config:type="int">16711680</config:config-item>
+                        </config:config-item-map-entry>
+                        <config:config-item-map-entry config:name="Sheet2">
+                            <config:config-item config:name="HorizontalSplitMode" config:type="short">0</config:config-item>
+                            <config:config-item config:name="TabColor" config:type="int">-1</config:config-item>
+                        </config:config-item-map-entry>
+                    </config:config-item-map-named>
+                    <config:config-item config:name="ActiveTable" config:type="string">Sheet2</config:config-item>
+                    <config:config-item config:name="ShowGrid" config:type="boolean">false</config:config-item>
+                </config:config-item-map-entry>
+            </config:config-item-map-indexed>
+        </config:config-item-set>
+        <config:config-item-set config:name="ooo:configuration-settings">
+            <config:config-item config:name="ShowGrid" config:type="boolean">true</config:config-item>
+        </config:config-item-set>
+    </office:settings>
+</office:document-settings>"#,
+            );
+            zip.finish().unwrap();
+        }
 
-    #[test]
-    fn test_normalize_ods_reference_basic() {
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let mut reader = OdsReader::new(&mut archive).unwrap();
+        let sheet = reader.read_sheet("Sheet1").unwrap();
         assert_eq!(
-            normalize_ods_reference("of:=SUM([.A1:.B2])", false, None, None),
-            "SUM(A1:B2)"
+            sheet.columns,
+            vec![
+                Span {
+                    first: 0,
+                    last: 0,
+                    size: Some(72.0 / POINTS_PER_CHARACTER),
+                    outline_level: 0,
+                },
+                Span {
+                    first: 1,
+                    last: 2,
+                    size: Some(2.0),
+                    outline_level: 1,
+                },
+            ]
         );
+        // Only the first row has a height set by hand
         assert_eq!(
-            normalize_ods_reference("of:=[.A1]+[.B1]", false, None, None),
-            "A1+B1"
+            sheet.rows,
+            vec![
+                Span {
+                    first: 0,
+                    last: 0,
+                    size: Some(30.0),
+                    outline_level: 0,
+                },
+                Span {
+                    first: 1,
+                    last: 1,
+                    size: None,
+                    outline_level: 1,
+                },
+                Span {
+                    first: 2,
+                    last: 3,
+                    size: None,
+                    outline_level: 2,
+                },
+            ]
+        );
+        assert_eq!((sheet.view.frozen_rows, sheet.view.frozen_columns), (1, 1));
+        assert_eq!(sheet.view.zoom, 120);
+        assert!(!sheet.view.show_gridlines);
+        assert_eq!(sheet.view.tab_color.as_deref(), Some("FF0000"));
+        assert!(!sheet.view.active);
+
+        let other = reader.read_sheet("Sheet2").unwrap();
+        assert_eq!(other.view.tab_color, None);
+        assert!(other.view.active && other.view.selected);
+    }
+
+    #[test]
+    fn test_page_setup_ods() {
+        use crate::reader::page_setup::{HeaderFooterKind, HeaderFooterPages, Orientation};
+        use std::io::Cursor;
+        use std::io::Write;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            let mut add = |name: &str, xml: &str| {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(xml.as_bytes()).unwrap();
+            };
+            add(
+                "styles.xml",
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-styles xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" xmlns:loext="urn:org:documentfoundation:names:experimental:office:xmlns:loext:1.0">
+    <office:automatic-styles>
+        <style:page-layout style:name="Mpm1"><style:page-layout-properties fo:margin-top="2cm" fo:margin-bottom="2cm" fo:margin-left="1in" fo:margin-right="1in" style:print-orientation="landscape" style:scale-to="100%"/></style:page-layout>
+        <style:page-layout style:name="Mpm2"><style:page-layout-properties style:print-orientation="portrait" loext:scale-to-X="1" loext:scale-to-Y="0"/></style:page-layout>
+    </office:automatic-styles>
+    <office:master-styles>
+        <style:master-page style:name="Default" style:page-layout-name="Mpm1">
+            <style:header><style:region-left><text:p><text:sheet-name>???</text:sheet-name></text:p></style:region-left><style:region-right><text:p>Page <text:page-number>1</text:page-number> of <text:page-count>9</text:page-count></text:p></style:region-right></style:header>
+            <style:header-left style:display="false"/>
+            <style:footer><text:p>R&amp;D <text:file-name text:display="full">???</text:file-name></text:p><text:p>Second line</text:p></style:footer>
+        </style:master-page>
+        <style:master-page style:name="Report" style:page-layout-name="Mpm2"/>
+    </office:master-styles>
+</office:document-styles>"#,
+            );
+            add(
+                "content.xml",
+                r##"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+    <office:automatic-styles>
+        <style:style style:name="ta1" style:family="table" style:master-page-name="Default"/>
+        <style:style style:name="ta2" style:family="table" style:master-page-name="Report"/>
+    </office:automatic-styles>
+    <office:body>
+        <office:spreadsheet>
+            <table:table table:name="Sheet1" table:style-name="ta1" table:print-ranges="Sheet1.A1:Sheet1.C10 Sheet1.E1:Sheet1.E2">
+                <table:table-header-columns><table:table-column/></table:table-header-columns>
+                <table:table-column table:number-columns-repeated="4"/>
+                <table:table-row><table:table-cell/></table:table-row>
+                <table:table-header-rows><table:table-row table:number-rows-repeated="2"><table:table-cell/></table:table-row></table:table-header-rows>
+                <table:table-row><table:table-cell office:value-type="float" office:value="1"/></table:table-row>
+            </table:table>
+            <table:table table:name="Sheet2" table:style-name="ta2" table:print-ranges="#REF!"><table:table-row><table:table-cell/></table:table-row></table:table>
+        </office:spreadsheet>
+        <table:named-expressions>
+            <table:named-range table:name="Excel_BuiltIn_Print_Area" table:base-cell-address="$Sheet1.$A$1" table:cell-range-address="$Sheet1.$A$1:.$C$10"/>
+        </table:named-expressions>
+    </office:body>
+</office:document-content>"##,
+            );
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let mut reader = OdsReader::new(&mut archive).unwrap();
+        assert!(reader.read_defined_names().unwrap().is_empty());
+
+        let sheet = reader.read_sheet("Sheet1").unwrap();
+        let setup = &sheet.page_setup;
+        assert_eq!(setup.print_area, vec![(0, 0, 9, 2), (0, 4, 1, 4)]);
+        assert_eq!(setup.title_rows, Some((1, 2)));
+        assert_eq!(setup.title_columns, Some((0, 0)));
+        assert_eq!(setup.orientation, Some(Orientation::Landscape));
+        assert_eq!(setup.scale, Some(100));
+        let margins = setup.margins.unwrap();
+        assert_eq!(margins.left, 1.0);
+        assert!((margins.top - 2.0 / 2.54).abs() < 1e-9);
+        let headers: Vec<_> = setup
+            .headers_footers
+            .iter()
+            .map(|h| (h.kind, h.pages, h.text.as_str()))
+            .collect();
+        assert_eq!(
+            headers,
+            vec![
+                (
+                    HeaderFooterKind::Header,
+                    HeaderFooterPages::All,
+                    "&L&A&RPage &P of &N"
+                ),
+                (
+                    HeaderFooterKind::Footer,
+                    HeaderFooterPages::All,
+                    "R&&D &Z&F\nSecond line"
+                ),
+            ]
         );
+
+        let other = reader.read_sheet("Sheet2").unwrap();
+        assert!(other.page_setup.print_area.is_empty());
+        assert_eq!(other.page_setup.broken_print_area.as_deref(), Some("#REF!"));
+        assert_eq!(other.page_setup.orientation, Some(Orientation::Portrait));
+        assert_eq!(other.page_setup.fit_to_pages, Some((1, 0)));
+        assert!(other.page_setup.headers_footers.is_empty());
+    }
+
+    #[test]
+    fn test_auto_filter_ods() {
+        use crate::reader::filter::{
+            FilterColumn, FilterCondition, FilterCriteria, FilterOperator, SortKey,
+        };
+        use std::io::Cursor;
+        use std::io::Write;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            zip.start_file("content.xml", options).unwrap();
+            zip.write_all(
+                br#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+    <office:body>
+        <office:spreadsheet>
+            <table:table table:name="Sales">
+                <table:table-row table:visibility="collapse"><table:table-cell/></table:table-row>
+                <table:table-row><table:table-cell office:value-type="string"><text:p>Region</text:p></table:table-cell></table:table-row>
+                <table:table-row table:visibility="filter" table:number-rows-repeated="2"><table:table-cell office:value-type="string"><text:p>East</text:p></table:table-cell></table:table-row>
+                <table:table-row><table:table-cell office:value-type="string"><text:p>North</text:p></table:table-cell></table:table-row>
+            </table:table>
+            <table:table table:name="Stock"><table:table-row><table:table-cell/></table:table-row></table:table>
+        </office:spreadsheet>
+        <table:database-ranges>
+            <table:database-range table:name="__Anonymous_Sheet_DB__0" table:target-range-address="Sales.B2:Sales.E10" table:display-filter-buttons="true">
+                <table:filter>
+                    <table:filter-and>
+                        <table:filter-condition table:field-number="0" table:value="North" table:operator="="><table:filter-set-item table:value="North"/><table:filter-set-item table:value="South"/></table:filter-condition>
+                        <table:filter-condition table:field-number="2" table:value="100" table:operator="&gt;=" table:data-type="number"/>
+                        <table:filter-condition table:field-number="2" table:value="kg" table:operator="ends"/>
+                        <table:filter-condition table:field-number="3" table:value="5" table:operator="bottom percent" table:data-type="number"/>
+                    </table:filter-and>
+                </table:filter>
+                <table:sort><table:sort-by table:field-number="2" table:order="descending"/></table:sort>
+            </table:database-range>
+            <table:database-range table:name="Products" table:target-range-address="Stock.A1:Stock.C5" table:display-filter-buttons="true"/>
+            <table:database-range table:name="Archive" table:target-range-address="Stock.E1:Stock.F5"/>
+        </table:database-ranges>
+    </office:body>
+</office:document-content>"#,
+            )
+            .unwrap();
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let mut reader = OdsReader::new(&mut archive).unwrap();
+        let sales = reader.read_sheet("Sales").unwrap();
+        let filter = sales.auto_filter.as_ref().unwrap();
+        assert_eq!(filter.range, (1, 1, 9, 4));
         assert_eq!(
-            normalize_ods_reference("of:=SUM([.A:.A])", false, None, None),
-            "SUM(A:A)"
+            filter.columns,
+            vec![
+                FilterColumn {
+                    column: 1,
+                    criteria: FilterCriteria::Values {
+                        values: vec!["North".into(), "South".into()],
+                        blank: false,
+                    },
+                },
+                FilterColumn {
+                    column: 3,
+                    criteria: FilterCriteria::Custom {
+                        conditions: vec![
+                            FilterCondition {
+                                operator: FilterOperator::GreaterThanOrEqual,
+                                value: "100".into(),
+                            },
+                            FilterCondition {
+                                operator: FilterOperator::Equal,
+                                value: "*kg".into(),
+                            },
+                        ],
+                        and: true,
+                    },
+                },
+                FilterColumn {
+                    column: 4,
+                    criteria: FilterCriteria::Top {
+                        count: 5.0,
+                        percent: true,
+                        bottom: true,
+                    },
+                },
+            ]
         );
         assert_eq!(
-            normalize_ods_reference("of:=SUM([.1:.1])", false, None, None),
-            "SUM(1:1)"
+            filter.sort,
+            vec![SortKey {
+                column: 3,
+                descending: true,
+            }]
         );
+        assert_eq!(sales.hidden_rows, vec![0]);
+        assert_eq!(sales.filtered_rows, vec![2, 3]);
+
+        // A named range with filter buttons stands in for the sheet's own
+        let stock = reader.read_sheet("Stock").unwrap();
+        let filter = stock.auto_filter.as_ref().unwrap();
+        assert_eq!(filter.range, (0, 0, 4, 2));
+        assert!(!filter.is_filtering());
+    }
+
+    #[test]
+    fn test_rich_text_ods() {
+        use std::io::Cursor;
+        use std::io::Write;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            zip.start_file("content.xml", options).unwrap();
+            zip.write_all(
+                br##"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+    <office:automatic-styles>
+        <style:style style:name="T1" style:family="text"><style:text-properties fo:font-weight="bold" fo:color="#ff0000"/></style:style>
+    </office:automatic-styles>
+    <office:body>
+        <office:spreadsheet>
+            <table:table table:name="Notes">
+                <table:table-row>
+                    <table:table-cell office:value-type="string"><text:p>Plain</text:p></table:table-cell>
+                    <table:table-cell office:value-type="string"><text:p>Total <text:span text:style-name="T1">due</text:span></text:p><text:p>now<text:s text:c="2"/>paid<text:line-break/>late</text:p></table:table-cell>
+                    <table:table-cell office:value-type="string"><text:p>First</text:p><text:p>Second</text:p></table:table-cell>
+                </table:table-row>
+            </table:table>
+        </office:spreadsheet>
+    </office:body>
+</office:document-content>"##,
+            )
+            .unwrap();
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let mut reader = OdsReader::new(&mut archive).unwrap();
+        let sheet = reader.read_sheet("Notes").unwrap();
+        let value = |col| sheet.get_cell(0, col).unwrap().value.clone();
+
+        assert_eq!(value(0), CellValue::Text("Plain".into(), None));
+
+        let CellValue::Text(text, Some(rich)) = value(1) else {
+            panic!("B1 should keep its runs");
+        };
+        assert_eq!(text, "Total due\nnow  paid\nlate");
+        let texts: Vec<_> = rich.runs.iter().map(|run| run.text.as_str()).collect();
+        assert_eq!(texts, vec!["Total ", "due", "\nnow  paid\nlate"]);
+        let bold = rich.runs[1].font.as_ref().unwrap();
+        assert!(bold.bold);
+        assert_eq!(bold.color.as_deref(), Some("FF0000"));
+        assert_eq!(rich.runs[2].font, None);
+
+        // Paragraphs are lines, whether or not the text is formatted
+        assert_eq!(value(2), CellValue::Text("First\nSecond".into(), None));
     }
 
     #[test]
-    fn test_normalize_ods_reference_sheet() {
-        assert_eq!(
-            normalize_ods_reference("of:=[$Sheet1.A1]*2", false, None, None),
-            "Sheet1!A1*2"
-        );
-        assert_eq!(
-            normalize_ods_reference("of:=SUM([$Sheet1.A1:.B2])", false, None, None),
-            "SUM(Sheet1!A1:B2)"
-        );
+    fn test_macros_ods() {
+        use crate::reader::macros::ModuleKind;
+        use std::io::Cursor;
+        use std::io::Write;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            let mut add = |name: &str, xml: &str| {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(xml.as_bytes()).unwrap();
+            };
+            add(
+                "Basic/script-lc.xml",
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<library:libraries xmlns:library="http://openoffice.org/2000/library"><library:library library:name="Standard" library:link="false"/></library:libraries>"#,
+            );
+            add(
+                "Basic/Standard/script-lb.xml",
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<library:library xmlns:library="http://openoffice.org/2000/library" library:name="Standard"><library:element library:name="Module1"/></library:library>"#,
+            );
+            add(
+                "Basic/Standard/Module1.xml",
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<script:module xmlns:script="http://openoffice.org/2000/script" script:name="Module1" script:language="StarBasic">Sub Main
+    If 1 &lt; 2 Then Shell(&quot;calc.exe&quot;)
+End Sub
+</script:module>"#,
+            );
+            add(
+                "Basic/Standard/Dialog1.xml",
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<dlg:window xmlns:dlg="http://openoffice.org/2000/dialog" dlg:id="Dialog1"/>"#,
+            );
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let modules = extract_macros_from_ods(&mut archive).unwrap();
+        assert_eq!(modules.len(), 1);
+        assert_eq!(modules[0].library, "Standard");
+        assert_eq!(modules[0].name, "Module1");
+        assert_eq!(modules[0].kind, ModuleKind::Standard);
         assert_eq!(
-            normalize_ods_reference("of:=$Sheet1.$A$1+$Sheet1.B1", false, None, None),
-            "Sheet1!$A$1+Sheet1!B1"
+            modules[0].source,
+            "Sub Main\n    If 1 < 2 Then Shell(\"calc.exe\")\nEnd Sub\n"
         );
     }
 
     #[test]
-    fn test_normalize_ods_reference_mixed() {
-        assert_eq!(
-            normalize_ods_reference("of:=[.A1:.$B$2]", false, None, None),
-            "A1:$B$2"
-        );
+    fn test_array_formulas_ods() {
+        use std::io::Cursor;
+        use std::io::Write;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+
+            zip.start_file("content.xml", options).unwrap();
+            zip.write_all(br##"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+    <office:body>
+        <office:spreadsheet>
+            <table:table table:name="Sheet1">
+                <table:table-row>
+                    <table:table-cell office:value-type="float" office:value="1"><text:p>1</text:p></table:table-cell>
+                    <table:table-cell table:formula="of:=[.A1:.A2]*2" table:number-matrix-columns-spanned="1" table:number-matrix-rows-spanned="2" office:value-type="float" office:value="2"><text:p>2</text:p></table:table-cell>
+                    <table:table-cell table:formula="of:=[.A1]+1" office:value-type="float" office:value="2"><text:p>2</text:p></table:table-cell>
+                </table:table-row>
+                <table:table-row>
+                    <table:table-cell office:value-type="float" office:value="3"><text:p>3</text:p></table:table-cell>
+                    <table:table-cell office:value-type="float" office:value="6"><text:p>6</text:p></table:table-cell>
+                </table:table-row>
+            </table:table>
+        </office:spreadsheet>
+    </office:body>
+</office:document-content>"##).unwrap();
+
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let mut reader = OdsReader::new(&mut archive).unwrap();
+        let sheet = reader.read_sheet("Sheet1").unwrap();
         assert_eq!(
-            normalize_ods_reference("of:=[.A$1]+$Sheet1.B$2", false, None, None),
-            "A$1+Sheet1!B$2"
+            sheet.array_formulas,
+            vec![ArrayFormula {
+                row: 0,
+                col: 1,
+                range: (0, 1, 1, 1),
+                dynamic: false,
+            }]
         );
+        assert!(sheet.is_spilled(1, 1));
+        assert!(!sheet.is_spilled(0, 1));
+        assert!(!sheet.is_spilled(0, 2));
     }
 
     #[test]
-    fn test_normalize_ods_range() {
-        // Local range with redundant sheet names
-        assert_eq!(
-            normalize_ods_reference("Sheet1.B2:Sheet1.B4", false, None, None),
-            "B2:B4"
-        );
-        // Single cell ref
-        assert_eq!(
-            normalize_ods_reference("Sheet1.A1", false, None, None),
-            "A1"
-        );
-        // Multi-sheet range
-        assert_eq!(
-            normalize_ods_reference("Sheet1.A1:Sheet2.B2", false, None, None),
-            "Sheet1!A1:Sheet2!B2"
-        );
-        // Absolute local ref
+    fn test_cell_styles_ods() {
+        use std::io::Cursor;
+        use std::io::Write;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+
+            zip.start_file("styles.xml", options).unwrap();
+            zip.write_all(br##"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-styles xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0">
+    <office:styles>
+        <style:default-style style:family="table-cell">
+            <style:text-properties style:font-name="Liberation Sans" fo:font-size="10pt"/>
+        </style:default-style>
+        <style:style style:name="Default" style:family="table-cell"/>
+        <style:style style:name="Heading" style:family="table-cell" style:parent-style-name="Default">
+            <style:text-properties fo:font-weight="bold" fo:font-size="14pt" fo:color="#1f4e79"/>
+        </style:style>
+        <style:style style:name="Heading" style:family="paragraph">
+            <style:text-properties fo:font-style="italic"/>
+        </style:style>
+    </office:styles>
+</office:document-styles>"##).unwrap();
+
+            zip.start_file("content.xml", options).unwrap();
+            zip.write_all(br##"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+    <office:automatic-styles>
+        <style:style style:name="ce1" style:family="table-cell" style:parent-style-name="Heading">
+            <style:table-cell-properties fo:background-color="#ffcc00" fo:border="0.06pt solid #000000" fo:border-bottom="2.5pt double #ff0000" style:vertical-align="middle" fo:wrap-option="wrap" style:rotation-angle="270" style:cell-protect="none"/>
+            <style:paragraph-properties fo:text-align="end"/>
+        </style:style>
+        <style:style style:name="ce2" style:family="table-cell" style:parent-style-name="Default">
+            <style:table-cell-properties fo:background-color="transparent" style:cell-protect="hidden-and-protected"/>
+            <style:paragraph-properties fo:text-align="center"/>
+        </style:style>
+    </office:automatic-styles>
+    <office:body>
+        <office:spreadsheet>
+            <table:table table:name="Sheet1">
+                <table:table-row>
+                    <table:table-cell office:value-type="float" office:value="1"><text:p>1</text:p></table:table-cell>
+                    <table:table-cell table:style-name="ce1" table:number-columns-repeated="2" office:value-type="string"><text:p>Title</text:p></table:table-cell>
+                    <table:table-cell table:style-name="ce2" office:value-type="float" office:value="2"><text:p>2</text:p></table:table-cell>
+                </table:table-row>
+            </table:table>
+        </office:spreadsheet>
+    </office:body>
+</office:document-content>"##).unwrap();
+
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let mut reader = OdsReader::new(&mut archive).unwrap();
+        let styles = reader.read_styles().unwrap();
+        // Default, Heading, ce1, ce2
+        assert_eq!(styles.len(), 4);
+        assert_eq!(styles[0].font.name.as_deref(), Some("Liberation Sans"));
+        assert_eq!(styles[0].font.size, Some(10.0));
+        assert!(!styles[0].font.bold);
+
+        let sheet = reader.read_sheet("Sheet1").unwrap();
+        let style_of = |col: u32| &styles[sheet.cells[&(0, col)].style.unwrap_or(0)];
+        assert_eq!(sheet.cells[&(0, 0)].style, None);
+        assert_eq!(sheet.cells[&(0, 1)].style, sheet.cells[&(0, 2)].style);
+
+        // ce1 inherits the heading font, which inherits the default font name
+        let title = style_of(1);
+        assert_eq!(title.font.name.as_deref(), Some("Liberation Sans"));
+        assert_eq!(title.font.size, Some(14.0));
+        assert!(title.font.bold);
+        // Paragraph styles of the same name don't leak into cell styles
+        assert!(!title.font.italic);
+        assert_eq!(title.font.color.as_deref(), Some("1F4E79"));
+        assert_eq!(title.fill.color.as_deref(), Some("FFCC00"));
+        assert_eq!(title.border.left.as_ref().unwrap().style, "hair");
         assert_eq!(
-            normalize_ods_reference("Sheet1.$A$1", false, None, None),
-            "$A$1"
+            title.border.bottom,
+            Some(BorderLine {
+                style: "double".to_string(),
+                color: Some("FF0000".to_string()),
+            })
         );
+        assert_eq!(title.alignment.horizontal.as_deref(), Some("right"));
+        assert_eq!(title.alignment.vertical.as_deref(), Some("center"));
+        assert!(title.alignment.wrap_text);
+        assert_eq!(title.alignment.rotation, -90);
+        assert!(!title.protection.locked);
+
+        let centered = style_of(3);
+        assert_eq!(centered.fill.color, None);
+        assert_eq!(centered.alignment.horizontal.as_deref(), Some("center"));
+        assert!(centered.protection.locked && centered.protection.hidden);
     }
+
     #[test]
-    fn test_normalize_ods_unbracketed_range() {
-        // User reported "Listas!$D$19:.$M$19" appearing in output (dot issue).
-        // This suggests input was "$Listas.$D$19:.$M$19" (no brackets, like database ranges)
-        // and sheet_range_ref failed because it expects brackets.
-        let raw = "$Listas.$D$19:.$M$19";
+    fn test_protection_ods() {
+        use std::io::Cursor;
+        use std::io::Write;
+        use zip::write::FileOptions;
 
-        // With preserve=true (defined names), we want the full sheet qualification
-        let expected_true = "Listas!$D$19:$M$19";
-        assert_eq!(
-            normalize_ods_reference(raw, true, None, None),
-            expected_true,
-            "Failed with preserve=true"
-        );
+        let package = |manifest: &str| {
+            let mut buf = Vec::new();
+            {
+                let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+                let options =
+                    FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+
+                zip.start_file("META-INF/manifest.xml", options).unwrap();
+                zip.write_all(manifest.as_bytes()).unwrap();
+
+                zip.start_file("content.xml", options).unwrap();
+                zip.write_all(br##"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+    <office:automatic-styles>
+        <style:style style:name="ce1" style:family="table-cell">
+            <style:table-cell-properties style:cell-protect="none"/>
+        </style:style>
+    </office:automatic-styles>
+    <office:body>
+        <office:spreadsheet table:structure-protected="true" table:protection-key="a2V5" table:protection-key-digest-algorithm="http://www.w3.org/2000/09/xmldsig#sha256">
+            <table:table table:name="Locked" table:protected="true" table:protection-key="a2V5">
+                <table:table-row>
+                    <table:table-cell office:value-type="float" office:value="1"><text:p>1</text:p></table:table-cell>
+                    <table:table-cell table:style-name="ce1" office:value-type="float" office:value="2"><text:p>2</text:p></table:table-cell>
+                </table:table-row>
+            </table:table>
+            <table:table table:name="Open">
+                <table:table-row>
+                    <table:table-cell office:value-type="float" office:value="3"><text:p>3</text:p></table:table-cell>
+                </table:table-row>
+            </table:table>
+        </office:spreadsheet>
+    </office:body>
+</office:document-content>"##).unwrap();
 
-        // With preserve=false (local formulas), we want to strip the sheet name if possible
-        // to avoid false circular references (ERR003 treat explicit self-sheet as non-trivial)
-        let expected_false = "$D$19:$M$19";
-        assert_eq!(
-            normalize_ods_reference(raw, false, None, None),
-            expected_false,
-            "Failed with preserve=false"
+                zip.finish().unwrap();
+            }
+            ZipArchive::new(Cursor::new(buf)).unwrap()
+        };
+
+        let mut archive = package(
+            r#"<manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0"><manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/></manifest:manifest>"#,
         );
-    }
+        assert!(!is_encrypted_ods(&mut archive).unwrap());
 
-    #[test]
-    fn test_normalize_ods_strip_after_regex_match() {
-        // If 0b2 matches "$Sheet1.A1:.$B2" -> "Sheet1!A1:B2"
-        // 0c should SHOULD strip "Sheet1!" if preserve_sheet=false
-        let raw = "$Sheet1.A1:.$B2";
-        // Currently (before fix) this prints "Sheet1!A1:B2"
-        // We want "A1:B2" if preserve_sheet=false (simulating local formula)
-        // With preserve=false, it should strip matched sheet names
-        assert_eq!(normalize_ods_reference(raw, false, None, None), "A1:$B2");
+        let mut reader = OdsReader::new(&mut archive).unwrap();
+        let structure = reader.read_workbook_protection().unwrap().unwrap();
+        assert!(structure.structure);
+        assert_eq!(structure.password.unwrap().algorithm, "SHA-256");
 
-        // Also check bracketed case
-        let raw_bracket = "[$Sheet1.A1:.$B2]";
-        assert_eq!(
-            normalize_ods_reference(raw_bracket, false, None, None),
-            "A1:$B2"
+        let styles = reader.read_styles().unwrap();
+        let sheets = reader.read_sheets().unwrap();
+        let protection = sheets[0].protection.as_ref().unwrap();
+        // No digest algorithm means SHA-1
+        assert_eq!(protection.password.as_ref().unwrap().algorithm, "SHA-1");
+        assert_eq!(sheets[1].protection, None);
+
+        let workbook = super::super::Workbook {
+            styles,
+            ..Default::default()
+        };
+        assert!(workbook.is_cell_locked(&sheets[0].cells[&(0, 0)]));
+        assert!(!workbook.is_cell_locked(&sheets[0].cells[&(0, 1)]));
+
+        let mut encrypted = package(
+            r#"<manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0"><manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"><manifest:encryption-data manifest:checksum-type="SHA1/1K" manifest:checksum="abc="><manifest:algorithm manifest:algorithm-name="Blowfish CFB" manifest:initialisation-vector="abc="/></manifest:encryption-data></manifest:file-entry></manifest:manifest>"#,
         );
+        assert!(is_encrypted_ods(&mut encrypted).unwrap());
     }
 
     #[test]
-    fn test_normalize_ods_preserve_sheet() {
-        // Should preserve sheet name even if it looks local
-        assert_eq!(
-            normalize_ods_reference("Sheet1.A1", true, None, None),
-            "Sheet1.A1"
-        );
-        // Should preserve absolute local ref
+    fn test_document_properties_ods() {
+        use std::io::Cursor;
+        use std::io::Write;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+
+            zip.start_file("meta.xml", options).unwrap();
+            zip.write_all(br##"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-meta xmlns:meta="urn:oasis:names:tc:opendocument:xmlns:meta:1.0" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:xlink="http://www.w3.org/1999/xlink" xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0">
+    <office:meta>
+        <meta:initial-creator>Jane Doe</meta:initial-creator>
+        <meta:creation-date>2024-01-15T09:30:00</meta:creation-date>
+        <dc:creator>John Roe</dc:creator>
+        <dc:date>2024-02-01T17:05:12.250000000</dc:date>
+        <dc:title>Q3 Budget</dc:title>
+        <meta:keyword>budget</meta:keyword>
+        <meta:keyword>q3</meta:keyword>
+        <meta:template xlink:type="simple" xlink:actuate="onRequest" xlink:title="Budget" xlink:href="file:///home/jdoe/Templates/Budget.ots"/>
+        <meta:generator>LibreOffice/25.8</meta:generator>
+        <meta:user-defined meta:name="Department">Finance</meta:user-defined>
+        <meta:user-defined meta:name="Empty"/>
+    </office:meta>
+</office:document-meta>"##).unwrap();
+
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let properties = extract_properties_from_ods(&mut archive).unwrap();
+        assert_eq!(properties.author.as_deref(), Some("Jane Doe"));
+        assert_eq!(properties.last_modified_by.as_deref(), Some("John Roe"));
+        assert_eq!(properties.title.as_deref(), Some("Q3 Budget"));
+        assert_eq!(properties.keywords.as_deref(), Some("budget, q3"));
         assert_eq!(
-            normalize_ods_reference("Sheet1.$G$2", true, None, None),
-            "Sheet1.$G$2"
+            properties.template.as_deref(),
+            Some("file:///home/jdoe/Templates/Budget.ots")
         );
-        // Normal ranges should still be processed if they don't match the strip pattern
-        // But our strip pattern in 0c matches: ([^.]+)\.([A-Z0-9$]+):([^.]+)\.([A-Z0-9$]+)
-        // If preserve=true, this pattern is skipped.
+        assert_eq!(properties.application.as_deref(), Some("LibreOffice/25.8"));
         assert_eq!(
-            normalize_ods_reference("Sheet1.A1:Sheet1.B2", true, None, None),
-            "Sheet1.A1:Sheet1.B2"
+            properties.created,
+            Some(DateTime::from_ymd(2024, 1, 15).with_time(9, 30, 0))
         );
+        assert_eq!(properties.modified.unwrap().millisecond, 250);
+        assert_eq!(properties.custom.len(), 1);
+        assert_eq!(properties.custom["Department"], "Finance");
+        assert_eq!(properties.company, None);
+        let personal: Vec<_> = properties.personal().into_iter().map(|(k, _)| k).collect();
+        assert_eq!(personal, vec!["Author", "Last modified by", "Template"]);
+
+        const TEST_ODS: &[u8] = include_bytes!("../../../tests/minimal_test.ods");
+        let mut archive = ZipArchive::new(Cursor::new(TEST_ODS)).unwrap();
+        let properties = extract_properties_from_ods(&mut archive).unwrap();
+        assert!(properties.personal().is_empty());
+        assert_eq!(properties.custom["AppVersion"], "15.0000");
     }
 
     #[test]
-    fn test_normalize_ods_reference_single_cell_range() {
-        // PERF004 regression: "Sheet1.A1:Sheet1.A1" should normalize to "A1"
-        assert_eq!(
-            normalize_ods_reference("Sheet1.A1:Sheet1.A1", false, None, None),
-            "A1"
-        );
-        assert_eq!(normalize_ods_reference("A1:A1", false, None, None), "A1");
+    fn test_parse_ods_validation_condition() {
+        let parse = |condition: &str| {
+            let mut validation = DataValidation::default();
+            parse_ods_validation_condition(condition, &mut validation);
+            validation
+        };
+
+        let list = parse("of:cell-content-is-in-list(\"Yes\";\"No, never\")");
+        assert_eq!(list.kind, ValidationKind::List);
+        assert_eq!(list.formula1.as_deref(), Some("\"Yes,No, never\""));
+
+        let range = parse("of:cell-content-is-in-list([$Lists.$A$1:.$A$5])");
+        assert_eq!(range.list_source(), Some("Lists!$A$1:$A$5"));
+
+        let whole = parse("of:cell-content-is-whole-number() and cell-content-is-between(1;10)");
+        assert_eq!(whole.kind, ValidationKind::WholeNumber);
+        assert_eq!(whole.operator, ValidationOperator::Between);
         assert_eq!(
-            normalize_ods_reference("[.A1:.A1]", false, None, None),
-            "A1"
+            (whole.formula1.as_deref(), whole.formula2.as_deref()),
+            (Some("1"), Some("10"))
         );
+
+        let decimal = parse("of:cell-content-is-decimal-number() and cell-content()>=[.B1]");
+        assert_eq!(decimal.kind, ValidationKind::Decimal);
+        assert_eq!(decimal.operator, ValidationOperator::GreaterThanOrEqual);
+        assert_eq!(decimal.formula1.as_deref(), Some("B1"));
+
+        let length = parse("of:cell-content-text-length()<=20");
+        assert_eq!(length.kind, ValidationKind::TextLength);
+        assert_eq!(length.operator, ValidationOperator::LessThanOrEqual);
+
+        let custom = parse("of:is-true-formula(AND([.A1]>0;[.A1]<5))");
+        assert_eq!(custom.kind, ValidationKind::Custom);
+        assert_eq!(custom.formula1.as_deref(), Some("AND(A1>0,A1<5)"));
     }
 
     #[test]
-    fn test_read_database_ranges_ods() {
+    fn test_content_validations_ods() {
         use std::io::Cursor;
         use std::io::Write;
         use zip::write::FileOptions;
@@ -2533,13 +6862,25 @@ mod tests {
 
             zip.start_file("content.xml", options).unwrap();
             zip.write_all(br#"<?xml version="1.0" encoding="UTF-8"?>
-<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0">
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
     <office:body>
         <office:spreadsheet>
-            <table:database-ranges>
-                <table:database-range table:name="MyRange" table:target-range-address="Sheet1.A1:Sheet1.B2"/>
-                <table:database-range table:name="OtherRange" table:target-range-address="Sheet1.C3"/>
-            </table:database-ranges>
+            <table:content-validations>
+                <table:content-validation table:name="val1" table:condition="of:cell-content-is-whole-number() and cell-content-is-between(1;10)" table:allow-empty-cell="false">
+                    <table:error-message table:message-type="warning" table:display="true"/>
+                </table:content-validation>
+            </table:content-validations>
+            <table:table table:name="Sheet1">
+                <table:table-row table:number-rows-repeated="2">
+                    <table:table-cell table:content-validation-name="val1" table:number-columns-repeated="2"/>
+                    <table:table-cell/>
+                </table:table-row>
+                <table:table-row>
+                    <table:table-cell table:content-validation-name="val1" office:value-type="float" office:value="12"><text:p>12</text:p></table:table-cell>
+                    <table:table-cell table:content-validation-name="val1"/>
+                    <table:table-cell table:content-validation-name="val1"/>
+                </table:table-row>
+            </table:table>
         </office:spreadsheet>
     </office:body>
 </office:document-content>"#).unwrap();
@@ -2548,25 +6889,22 @@ mod tests {
         }
 
         let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
-        let mut reader = OdsReader::new(&mut archive).unwrap();
-        let defined_names = reader.read_defined_names().unwrap();
-
-        // Note: read_defined_names returns normalized Excel-style references
-        assert_eq!(defined_names.len(), 2);
-        // "Sheet1.A1:Sheet1.B2" -> normalized with preserve_sheet=true keeps it as is
-        assert_eq!(
-            defined_names.get("MyRange"),
-            Some(&"Sheet1.A1:Sheet1.B2".to_string())
-        );
-        // "Sheet1.C3" -> normalizes to "Sheet1.C3"
-        assert_eq!(
-            defined_names.get("OtherRange"),
-            Some(&"Sheet1.C3".to_string())
-        );
+        let sheet = OdsReader::new(&mut archive)
+            .unwrap()
+            .read_sheet("Sheet1")
+            .unwrap();
+
+        assert_eq!(sheet.data_validations.len(), 1);
+        let validation = &sheet.data_validations[0];
+        assert_eq!(validation.ranges, vec![(0, 0, 1, 1), (2, 0, 2, 2)]);
+        assert_eq!(validation.kind, ValidationKind::WholeNumber);
+        assert_eq!(validation.error_style, ValidationErrorStyle::Warning);
+        assert!(validation.show_error && !validation.allow_blank);
+        assert_eq!(validation.check(&sheet.cells[&(2, 0)].value), Some(false));
     }
 
     #[test]
-    fn test_styled_empty_cell_ods() {
+    fn test_null_date_and_error_codes_ods() {
         use std::io::Cursor;
         use std::io::Write;
         use zip::write::FileOptions;
@@ -2579,12 +6917,16 @@ mod tests {
 
             zip.start_file("content.xml", options).unwrap();
             zip.write_all(br#"<?xml version="1.0" encoding="UTF-8"?>
-<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0">
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" xmlns:calcext="urn:org:documentfoundation:names:experimental:calc:xmlns:calcext:1.0" xmlns:of="urn:oasis:names:tc:opendocument:xmlns:of:1.2">
     <office:body>
         <office:spreadsheet>
+            <table:calculation-settings>
+                <table:null-date table:date-value="1904-01-01"/>
+            </table:calculation-settings>
             <table:table table:name="Sheet1">
                 <table:table-row>
-                    <table:table-cell table:style-name="ce1"/>
+                    <table:table-cell office:value-type="date" office:date-value="2025-01-01T12:00:00"><text:p>2025-01-01 12:00</text:p></table:table-cell>
+                    <table:table-cell table:formula="of:=1/0" office:value-type="float" office:value="0" calcext:value-type="error"><text:p>Err:532</text:p></table:table-cell>
                 </table:table-row>
             </table:table>
         </office:spreadsheet>
@@ -2596,13 +6938,22 @@ mod tests {
 
         let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
         let mut reader = OdsReader::new(&mut archive).unwrap();
-        let sheets = reader.read_sheets().unwrap();
+        assert_eq!(reader.read_date_system().unwrap(), DateSystem::Excel1904);
 
-        assert_eq!(sheets.len(), 1);
-        let sheet = &sheets[0];
-        // The cell (0, 0) should be in the map because it has a style "ce1"
-        assert!(sheet.cells.contains_key(&(0, 0)));
-        assert_eq!(sheet.cells.get(&(0, 0)).unwrap().value, CellValue::Empty);
+        let sheet = reader.read_sheet("Sheet1").unwrap();
+        let serial = match sheet.cells[&(0, 0)].value {
+            CellValue::Number(n) => n,
+            ref other => panic!("expected a date serial, got {:?}", other),
+        };
+        assert_eq!(serial, 45658.5 - 1462.0);
+        assert_eq!(
+            DateSystem::Excel1904.to_datetime(serial),
+            Some(DateTime::from_ymd(2025, 1, 1).with_time(12, 0, 0))
+        );
+        assert_eq!(
+            sheet.cells[&(0, 1)].value.as_error(),
+            Some(&super::super::CellError::DivZero)
+        );
     }
 
     #[test]
@@ -2664,43 +7015,42 @@ mod tests {
         // (0, 0) -> "Spanned"
         assert_eq!(
             sheet.cells.get(&(0, 0)).unwrap().value,
-            CellValue::Text("Spanned".to_string())
+            CellValue::Text("Spanned".to_string(), None)
         );
         // (0, 1) -> Covered (usually not in map if empty)
         // (0, 2) -> "Target" (This would be (0, 3) in the buggy version)
         assert_eq!(
             sheet.cells.get(&(0, 2)).unwrap().value,
-            CellValue::Text("Target".to_string())
+            CellValue::Text("Target".to_string(), None)
         );
 
         // Row 2
         // (1, 0) -> "Repeated"
         assert_eq!(
             sheet.cells.get(&(1, 0)).unwrap().value,
-            CellValue::Text("Repeated".to_string())
+            CellValue::Text("Repeated".to_string(), None)
         );
         // (1, 1) -> "Repeated"
         assert_eq!(
             sheet.cells.get(&(1, 1)).unwrap().value,
-            CellValue::Text("Repeated".to_string())
+            CellValue::Text("Repeated".to_string(), None)
         );
         // (1, 2) -> "AfterRepeated"
         assert_eq!(
             sheet.cells.get(&(1, 2)).unwrap().value,
-            CellValue::Text("AfterRepeated".to_string())
+            CellValue::Text("AfterRepeated".to_string(), None)
         );
     }
 
     #[test]
     fn test_calculate_used_range_individual_cells() {
-        use std::collections::HashMap;
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (5, 3),
             Cell {
                 row: 5,
                 col: 3,
-                value: CellValue::Text("test".to_string()),
+                value: CellValue::Text("test".to_string(), None),
                 ..Default::default()
             },
         );
@@ -2724,8 +7074,7 @@ mod tests {
 
     #[test]
     fn test_calculate_used_range_merged_cells() {
-        use std::collections::HashMap;
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
 
         // Merged cell B2:C3
         cells.insert(
@@ -2733,7 +7082,7 @@ mod tests {
             Cell {
                 row: 1,
                 col: 1,
-                value: CellValue::Text("M".to_string()),
+                value: CellValue::Text("M".to_string(), None),
                 ..Default::default()
             },
         );
@@ -2768,8 +7117,7 @@ mod tests {
 
     #[test]
     fn test_calculate_used_range_empty() {
-        use std::collections::HashMap;
-        let cells: HashMap<(u32, u32), Cell> = HashMap::new();
+        let cells = CellStore::new();
         assert_eq!(calculate_used_range(&cells), None);
     }
 }
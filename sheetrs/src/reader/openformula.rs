@@ -0,0 +1,850 @@
+//! OpenFormula and Excel formula syntax
+//!
+//! ODS writes formulas in OpenFormula (`of:=SUM([.A1:.B2];[$Data.C1])`):
+//! references in brackets, with a `.` before each address and a `$` before
+//! absolute sheet names, `;` between arguments, `~` for the union and `!` (or
+//! LibreOffice's `!!`) for the intersection of references, `;` between the
+//! columns and `|` between the rows of inline arrays, `TRUE()` and `FALSE()` as
+//! functions, and Excel's newer functions under a `COM.MICROSOFT.` prefix. A
+//! linked workbook is named in the reference itself
+//! (`['file:///Rates.xlsx'#$Rates.B3]`), where Excel writes its `[N]` index.
+//! The workbook model keeps formulas in Excel syntax, without the leading `=`.
+//! LibreOffice's own functions (`ORG.OPENOFFICE.*`, `ORG.LIBREOFFICE.*`) have
+//! no Excel equivalent and keep their names.
+
+use super::external_links::ExternalWorkbook;
+use super::parser_utils::{quote_sheet_name, unquote_sheet_name};
+use super::workbook::CellError;
+use std::borrow::Cow;
+
+/// Functions OpenFormula names differently from Excel, as (OpenFormula, Excel)
+const RENAMED_FUNCTIONS: &[(&str, &str)] = &[
+    ("CEILING.XCL", "CEILING"),
+    ("FLOOR.XCL", "FLOOR"),
+    ("FORMULA", "FORMULATEXT"),
+    ("LEGACY.CHIDIST", "CHIDIST"),
+    ("LEGACY.CHIINV", "CHIINV"),
+    ("LEGACY.CHITEST", "CHITEST"),
+    ("LEGACY.FDIST", "FDIST"),
+    ("LEGACY.FINV", "FINV"),
+    ("LEGACY.NORMSDIST", "NORMSDIST"),
+    ("LEGACY.NORMSINV", "NORMSINV"),
+    ("LEGACY.TDIST", "TDIST"),
+];
+
+/// Excel functions OpenFormula writes with a `COM.MICROSOFT.` prefix
+const MICROSOFT_FUNCTIONS: &[&str] = &[
+    "AGGREGATE",
+    "BETA.DIST",
+    "BETA.INV",
+    "BINOM.DIST",
+    "BINOM.INV",
+    "CEILING.MATH",
+    "CEILING.PRECISE",
+    "CHISQ.DIST",
+    "CHISQ.DIST.RT",
+    "CHISQ.INV",
+    "CHISQ.INV.RT",
+    "CHISQ.TEST",
+    "CONCAT",
+    "CONFIDENCE.NORM",
+    "CONFIDENCE.T",
+    "COVARIANCE.P",
+    "COVARIANCE.S",
+    "ENCODEURL",
+    "ERF.PRECISE",
+    "ERFC.PRECISE",
+    "EXPON.DIST",
+    "F.DIST",
+    "F.DIST.RT",
+    "F.INV",
+    "F.INV.RT",
+    "F.TEST",
+    "FILTER",
+    "FILTERXML",
+    "FLOOR.MATH",
+    "FLOOR.PRECISE",
+    "FORECAST.LINEAR",
+    "GAMMA.DIST",
+    "GAMMA.INV",
+    "GAMMALN.PRECISE",
+    "HYPGEOM.DIST",
+    "IFS",
+    "LET",
+    "LOGNORM.DIST",
+    "LOGNORM.INV",
+    "MAXIFS",
+    "MINIFS",
+    "MODE.MULT",
+    "MODE.SNGL",
+    "NEGBINOM.DIST",
+    "NETWORKDAYS.INTL",
+    "NORM.DIST",
+    "NORM.INV",
+    "NORM.S.DIST",
+    "NORM.S.INV",
+    "PERCENTILE.EXC",
+    "PERCENTILE.INC",
+    "PERCENTRANK.EXC",
+    "PERCENTRANK.INC",
+    "POISSON.DIST",
+    "QUARTILE.EXC",
+    "QUARTILE.INC",
+    "RANDARRAY",
+    "RANK.AVG",
+    "RANK.EQ",
+    "SEQUENCE",
+    "SORT",
+    "SORTBY",
+    "STDEV.P",
+    "STDEV.S",
+    "SWITCH",
+    "T.DIST",
+    "T.DIST.2T",
+    "T.DIST.RT",
+    "T.INV",
+    "T.INV.2T",
+    "T.TEST",
+    "TEXTJOIN",
+    "UNIQUE",
+    "VAR.P",
+    "VAR.S",
+    "WEBSERVICE",
+    "WEIBULL.DIST",
+    "WORKDAY.INTL",
+    "XLOOKUP",
+    "XMATCH",
+    "Z.TEST",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Token<'a> {
+    /// `"..."`, quotes included
+    Text(&'a str),
+    /// `'...'`, quotes included: an Excel sheet name
+    Quoted(&'a str),
+    /// `[...]`, brackets included: an OpenFormula reference, or an Excel
+    /// workbook index or structured reference
+    Bracket(&'a str),
+    /// Name, number, address or error literal
+    Word(&'a str),
+    Space(&'a str),
+    Punct(char),
+}
+
+/// What an open parenthesis or brace holds
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FrameKind {
+    /// The whole formula
+    Formula,
+    Call,
+    Group,
+    Array,
+}
+
+struct Frame {
+    kind: FrameKind,
+    /// Where the current argument starts in the output
+    start: usize,
+    /// Whether the current argument is a union of references
+    union: bool,
+}
+
+impl Frame {
+    fn new(kind: FrameKind, start: usize) -> Self {
+        Self {
+            kind,
+            start,
+            union: false,
+        }
+    }
+
+    /// End the current argument. Excel has no union operator, only `,`
+    /// between references, so a union outside parentheses of its own gets
+    /// some.
+    fn close_argument(&mut self, out: &mut String) {
+        if self.union && self.kind != FrameKind::Group {
+            let argument = &out[self.start..];
+            let at = self.start + argument.len() - argument.trim_start().len();
+            out.insert(at, '(');
+            out.push(')');
+        }
+        self.union = false;
+    }
+}
+
+/// Translate an OpenFormula formula (`of:=SUM([.A1];[.B2])`) to Excel syntax
+/// (`SUM(A1,B2)`), without the leading `=`. Linked workbooks get the `[N]`
+/// index of the matching entry in `external_workbooks`. Formulas already in
+/// Excel syntax (`msoxl:=`) only lose their prefix.
+pub fn to_excel(formula: &str, external_workbooks: &[ExternalWorkbook]) -> String {
+    let (namespace, body) = split_namespace(formula);
+    if namespace == Some("msoxl") {
+        return body.to_string();
+    }
+
+    let tokens = tokenize(body);
+    let mut out = String::with_capacity(body.len());
+    let mut frames = vec![Frame::new(FrameKind::Formula, 0)];
+    let mut i = 0;
+    while i < tokens.len() {
+        let frame = frames.last_mut().expect("formula frame");
+        match tokens[i] {
+            Token::Word(name) if tokens.get(i + 1) == Some(&Token::Punct('(')) => {
+                // `TRUE()` and `FALSE()` are constants in Excel
+                if is_boolean(name) && tokens.get(i + 2) == Some(&Token::Punct(')')) {
+                    out.push_str(name);
+                    i += 3;
+                    continue;
+                }
+                out.push_str(&excel_function_name(name));
+                out.push('(');
+                frames.push(Frame::new(FrameKind::Call, out.len()));
+                i += 2;
+                continue;
+            }
+            Token::Bracket(reference) => {
+                match reference
+                    .strip_prefix('[')
+                    .and_then(|r| r.strip_suffix(']'))
+                {
+                    Some(inner) => out.push_str(&reference_to_excel(inner, external_workbooks)),
+                    // Unterminated, passed through as written
+                    None => out.push_str(reference),
+                }
+            }
+            Token::Punct('(') => {
+                out.push('(');
+                frames.push(Frame::new(FrameKind::Group, out.len()));
+            }
+            Token::Punct('{') => {
+                out.push('{');
+                frames.push(Frame::new(FrameKind::Array, out.len()));
+            }
+            Token::Punct(close @ (')' | '}')) => {
+                frame.close_argument(&mut out);
+                if frames.len() > 1 {
+                    frames.pop();
+                }
+                out.push(close);
+            }
+            Token::Punct(';') => {
+                frame.close_argument(&mut out);
+                out.push(',');
+                frame.start = out.len();
+            }
+            Token::Punct('|') if frame.kind == FrameKind::Array => out.push(';'),
+            Token::Punct('~') => {
+                frame.union = true;
+                out.push(',');
+            }
+            // Intersection, written `!` or `!!`: one space in Excel
+            Token::Punct('!') => {
+                out.truncate(out.trim_end().len());
+                out.push(' ');
+                while matches!(tokens.get(i + 1), Some(Token::Punct('!') | Token::Space(_))) {
+                    i += 1;
+                }
+            }
+            Token::Punct(ch) => out.push(ch),
+            Token::Text(s) | Token::Quoted(s) | Token::Word(s) | Token::Space(s) => out.push_str(s),
+        }
+        i += 1;
+    }
+    if let [frame] = frames.as_mut_slice() {
+        frame.close_argument(&mut out);
+    }
+    out
+}
+
+/// Translate an Excel formula (with or without its `=`) to OpenFormula, with
+/// the `of:=` prefix. `[N]` workbook indices become the target of the matching
+/// entry in `external_workbooks`.
+pub fn to_openformula(formula: &str, external_workbooks: &[ExternalWorkbook]) -> String {
+    let body = formula.strip_prefix('=').unwrap_or(formula);
+    let tokens = tokenize(body);
+    let mut out = String::from("of:=");
+    let mut frames = vec![FrameKind::Formula];
+    let mut i = 0;
+    while i < tokens.len() {
+        let frame = *frames.last().expect("formula frame");
+        if let Some((reference, len)) = reference_to_openformula(&tokens[i..], external_workbooks) {
+            out.push_str(&reference);
+            i += len;
+            continue;
+        }
+        match tokens[i] {
+            Token::Word(name) if tokens.get(i + 1) == Some(&Token::Punct('(')) => {
+                out.push_str(&openformula_function_name(name));
+                out.push('(');
+                frames.push(FrameKind::Call);
+                i += 2;
+                continue;
+            }
+            Token::Word(name) if is_boolean(name) => {
+                out.push_str(name);
+                out.push_str("()");
+            }
+            Token::Punct('(') => {
+                out.push('(');
+                frames.push(FrameKind::Group);
+            }
+            Token::Punct('{') => {
+                out.push('{');
+                frames.push(FrameKind::Array);
+            }
+            Token::Punct(close @ (')' | '}')) => {
+                if frames.len() > 1 {
+                    frames.pop();
+                }
+                out.push(close);
+            }
+            Token::Punct(',') => out.push(match frame {
+                FrameKind::Call | FrameKind::Array => ';',
+                FrameKind::Formula | FrameKind::Group => '~',
+            }),
+            Token::Punct(';') if frame == FrameKind::Array => out.push('|'),
+            // A space between two references is their intersection
+            Token::Space(space) => {
+                let before = i.checked_sub(1).map(|j| tokens[j]);
+                let after = tokens.get(i + 1).copied();
+                if before.is_some_and(ends_operand) && after.is_some_and(starts_operand) {
+                    out.push('!');
+                } else {
+                    out.push_str(space);
+                }
+            }
+            Token::Punct(ch) => out.push(ch),
+            Token::Text(s) | Token::Quoted(s) | Token::Word(s) | Token::Bracket(s) => {
+                out.push_str(s)
+            }
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Split the namespace prefix (`of`, `msoxl`, `ooow`) off a formula and drop
+/// its `=`
+fn split_namespace(formula: &str) -> (Option<&str>, &str) {
+    if let Some((namespace, body)) = formula.split_once(":=")
+        && !namespace.is_empty()
+        && namespace.chars().all(|c| c.is_ascii_alphabetic())
+    {
+        return (Some(namespace), body);
+    }
+    (None, formula.strip_prefix('=').unwrap_or(formula))
+}
+
+fn is_boolean(name: &str) -> bool {
+    name.eq_ignore_ascii_case("TRUE") || name.eq_ignore_ascii_case("FALSE")
+}
+
+fn excel_function_name(name: &str) -> Cow<'_, str> {
+    const MICROSOFT: &str = "COM.MICROSOFT.";
+    if name.len() > MICROSOFT.len()
+        && name
+            .get(..MICROSOFT.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(MICROSOFT))
+    {
+        return Cow::Borrowed(&name[MICROSOFT.len()..]);
+    }
+    match RENAMED_FUNCTIONS
+        .iter()
+        .find(|(openformula, _)| openformula.eq_ignore_ascii_case(name))
+    {
+        Some((_, excel)) => Cow::Borrowed(excel),
+        None => Cow::Borrowed(name),
+    }
+}
+
+fn openformula_function_name(name: &str) -> Cow<'_, str> {
+    // Prefixes of functions newer than the file format
+    let name = ["_xlfn.", "_xlws."]
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix))
+        .unwrap_or(name);
+    if let Some((openformula, _)) = RENAMED_FUNCTIONS
+        .iter()
+        .find(|(_, excel)| excel.eq_ignore_ascii_case(name))
+    {
+        return Cow::Borrowed(openformula);
+    }
+    if MICROSOFT_FUNCTIONS
+        .iter()
+        .any(|function| function.eq_ignore_ascii_case(name))
+    {
+        return Cow::Owned(format!("COM.MICROSOFT.{}", name));
+    }
+    Cow::Borrowed(name)
+}
+
+/// Excel form of the inside of an OpenFormula `[...]` reference
+fn reference_to_excel(reference: &str, external_workbooks: &[ExternalWorkbook]) -> String {
+    // `'file:///Rates.xlsx'#$Rates.B3` reads a linked workbook
+    let (source, reference) = match reference.strip_prefix('\'') {
+        Some(_) => {
+            let len = quoted_len(reference, '\'');
+            match reference[len..].strip_prefix('#') {
+                Some(rest) => (Some(unquote_sheet_name(&reference[..len])), rest),
+                None => (None, reference),
+            }
+        }
+        None => (None, reference),
+    };
+
+    let parts: Vec<(Option<Cow<'_, str>>, &str)> = split_outside_quotes(reference, ':')
+        .into_iter()
+        .map(|part| {
+            let part = part.strip_prefix('$').unwrap_or(part);
+            let (sheet, address) = if part.starts_with('\'') {
+                let len = quoted_len(part, '\'');
+                (Some(&part[..len]), &part[len..])
+            } else {
+                match part.rfind('.') {
+                    Some(dot) => (Some(&part[..dot]).filter(|s| !s.is_empty()), &part[dot..]),
+                    None => (None, part),
+                }
+            };
+            (
+                sheet.map(unquote_sheet_name),
+                address.strip_prefix('.').unwrap_or(address),
+            )
+        })
+        .collect();
+
+    // Deleted cells and sheets
+    let ref_error = CellError::Ref.as_str();
+    if parts.iter().any(|(sheet, address)| {
+        address.contains(ref_error) || sheet.as_deref().is_some_and(|s| s.contains(ref_error))
+    }) {
+        return ref_error.to_string();
+    }
+
+    // A reference spanning sheets names both: `Jan:Mar!A1`
+    let first = parts[0].0.as_deref();
+    let last = parts.get(1).and_then(|(sheet, _)| sheet.as_deref());
+    let spans_sheets = matches!((first, last), (Some(first), Some(last)) if first != last);
+    let sheet = match (first, last) {
+        (Some(first), Some(last)) if spans_sheets => Some(format!("{}:{}", first, last)),
+        (first, _) => first.map(str::to_string),
+    };
+    let mut excel = String::new();
+    let workbook = source.map(|source| {
+        external_workbooks
+            .iter()
+            .find(|wb| wb.target == *source || (!wb.path.is_empty() && source.ends_with(&wb.path)))
+            .map(|wb| (wb.index + 1).to_string())
+            .unwrap_or_else(|| {
+                source
+                    .rsplit(['/', '\\'])
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            })
+    });
+    match (workbook, sheet) {
+        (Some(workbook), Some(sheet)) => {
+            let quoted = matches!(quote_sheet_name(&sheet), Cow::Owned(_));
+            let name = format!("[{}]{}", workbook, sheet);
+            if quoted {
+                excel.push_str(&format!("'{}'!", name.replace('\'', "''")));
+            } else {
+                excel.push_str(&name);
+                excel.push('!');
+            }
+        }
+        (Some(workbook), None) => excel.push_str(&format!("[{}]!", workbook)),
+        (None, Some(sheet)) => {
+            // Each side of a sheet span only needs quotes of its own when the
+            // span as a whole does
+            let quoted = sheet
+                .split(':')
+                .any(|s| matches!(quote_sheet_name(s), Cow::Owned(_)));
+            if quoted {
+                excel.push_str(&format!("'{}'!", sheet.replace('\'', "''")));
+            } else {
+                excel.push_str(&sheet);
+                excel.push('!');
+            }
+        }
+        (None, None) => {}
+    }
+    let mut addresses: Vec<&str> = parts.iter().map(|(_, address)| *address).collect();
+    if spans_sheets {
+        addresses.dedup();
+    }
+    excel.push_str(&addresses.join(":"));
+    excel
+}
+
+/// OpenFormula form of the Excel reference the tokens start with, and how many
+/// tokens it takes
+fn reference_to_openformula(
+    tokens: &[Token<'_>],
+    external_workbooks: &[ExternalWorkbook],
+) -> Option<(String, usize)> {
+    let mut i = 0;
+    let mut workbook: Option<usize> = None;
+    if let Some(Token::Bracket(bracket)) = tokens.first() {
+        let index = bracket.strip_prefix('[')?.strip_suffix(']')?;
+        workbook = Some(index.parse().ok()?);
+        i += 1;
+    }
+
+    let mut sheets: Option<(String, Option<String>)> = None;
+    match tokens[i..] {
+        [Token::Quoted(quoted), Token::Punct('!'), ..] => {
+            let mut name = unquote_sheet_name(quoted).into_owned();
+            // `'[1]My Sheet'!A1`
+            if workbook.is_none()
+                && let Some((index, rest)) =
+                    name.strip_prefix('[').and_then(|rest| rest.split_once(']'))
+            {
+                workbook = Some(index.parse().ok()?);
+                name = rest.to_string();
+            }
+            sheets = Some(match name.split_once(':') {
+                Some((first, last)) => (first.to_string(), Some(last.to_string())),
+                None => (name, None),
+            });
+            i += 2;
+        }
+        [Token::Word(sheet), Token::Punct('!'), ..] => {
+            sheets = Some((sheet.to_string(), None));
+            i += 2;
+        }
+        [
+            Token::Word(first),
+            Token::Punct(':'),
+            Token::Word(last),
+            Token::Punct('!'),
+            ..,
+        ] => {
+            sheets = Some((first.to_string(), Some(last.to_string())));
+            i += 4;
+        }
+        // A defined name of the linked workbook: `[1]!Rate`
+        [Token::Punct('!'), ..] if workbook.is_some() => i += 1,
+        _ if workbook.is_some() => return None,
+        _ => {}
+    }
+
+    let Some(Token::Word(start)) = tokens.get(i) else {
+        return None;
+    };
+    let kind = address_kind(start);
+    let mut end = None;
+    if let [Token::Punct(':'), Token::Word(last), ..] = tokens[i + 1..]
+        && kind.is_some()
+        && address_kind(last) == kind
+    {
+        end = Some(last);
+        i += 3;
+    } else if kind == Some(AddressKind::Cell) || workbook.is_some() || sheets.is_some() {
+        i += 1;
+    } else {
+        return None;
+    }
+    // A function whose name looks like a cell: `LOG10(`
+    if tokens.get(i) == Some(&Token::Punct('(')) {
+        return None;
+    }
+
+    let mut reference = String::from("[");
+    if let Some(index) = workbook {
+        let target = external_workbooks
+            .iter()
+            .find(|wb| wb.index + 1 == index)
+            .map(|wb| {
+                if wb.target.is_empty() {
+                    wb.path.clone()
+                } else {
+                    wb.target.clone()
+                }
+            })
+            .unwrap_or_else(|| index.to_string());
+        reference.push_str(&format!("'{}'#", target.replace('\'', "''")));
+    }
+    let (first_sheet, last_sheet) = match &sheets {
+        Some((first, last)) => (Some(first.as_str()), last.as_deref()),
+        None => (None, None),
+    };
+    match first_sheet {
+        Some(sheet) => reference.push_str(&format!("${}.", quote_sheet_name(sheet))),
+        None if workbook.is_none() => reference.push('.'),
+        None => {}
+    }
+    reference.push_str(start);
+    if last_sheet.is_some() || end.is_some() {
+        reference.push(':');
+        match last_sheet {
+            Some(sheet) => reference.push_str(&format!("${}.", quote_sheet_name(sheet))),
+            None => reference.push('.'),
+        }
+        reference.push_str(end.unwrap_or(start));
+    }
+    reference.push(']');
+    Some((reference, i))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum AddressKind {
+    Cell,
+    Column,
+    Row,
+}
+
+/// Whether a word is a cell address (`$A$1`), a column (`A`) or a row (`1`)
+pub(super) fn address_kind(word: &str) -> Option<AddressKind> {
+    let word = word.strip_prefix('$').unwrap_or(word);
+    let letters = word.len()
+        - word
+            .trim_start_matches(|c: char| c.is_ascii_alphabetic())
+            .len();
+    let rest = &word[letters..];
+    let digits = rest.strip_prefix('$').unwrap_or(rest);
+    let is_row = !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit());
+    match letters {
+        0 if is_row && rest == digits => Some(AddressKind::Row),
+        1..=3 if rest.is_empty() => Some(AddressKind::Column),
+        1..=3 if is_row => Some(AddressKind::Cell),
+        _ => None,
+    }
+}
+
+fn ends_operand(token: Token<'_>) -> bool {
+    matches!(
+        token,
+        Token::Word(_) | Token::Bracket(_) | Token::Punct(')')
+    )
+}
+
+fn starts_operand(token: Token<'_>) -> bool {
+    matches!(
+        token,
+        Token::Word(_) | Token::Bracket(_) | Token::Quoted(_) | Token::Punct('(')
+    )
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '.' | '$' | '\\' | '?')
+}
+
+pub(super) fn tokenize(formula: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = formula;
+    while let Some(ch) = rest.chars().next() {
+        let len = match ch {
+            '"' | '\'' => quoted_len(rest, ch),
+            '[' => bracket_len(rest),
+            '#' => CellError::STANDARD
+                .iter()
+                .map(CellError::as_str)
+                .find(|error| {
+                    rest.get(..error.len())
+                        .is_some_and(|s| s.eq_ignore_ascii_case(error))
+                })
+                .map_or(1, str::len),
+            c if c.is_whitespace() => rest
+                .find(|c: char| !c.is_whitespace())
+                .unwrap_or(rest.len()),
+            c if is_word_char(c) => rest.find(|c| !is_word_char(c)).unwrap_or(rest.len()),
+            c => c.len_utf8(),
+        };
+        let (token, tail) = rest.split_at(len);
+        tokens.push(match ch {
+            '"' => Token::Text(token),
+            '\'' => Token::Quoted(token),
+            '[' => Token::Bracket(token),
+            '#' if len > 1 => Token::Word(token),
+            c if c.is_whitespace() => Token::Space(token),
+            c if is_word_char(c) => Token::Word(token),
+            c => Token::Punct(c),
+        });
+        rest = tail;
+    }
+    tokens
+}
+
+/// Length of the quoted run `s` starts with, where a doubled quote stands
+/// for itself; the rest of `s` when the quote isn't closed
+fn quoted_len(s: &str, quote: char) -> usize {
+    let mut chars = s.char_indices().skip(1).peekable();
+    while let Some((i, ch)) = chars.next() {
+        if ch == quote {
+            if chars.peek().is_some_and(|&(_, next)| next == quote) {
+                chars.next();
+            } else {
+                return i + ch.len_utf8();
+            }
+        }
+    }
+    s.len()
+}
+
+/// Length of the bracketed run `s` starts with. OpenFormula quotes file and
+/// sheet names, after `[`, `$` or `:`; elsewhere (Excel structured
+/// references) `'` escapes the next character.
+fn bracket_len(s: &str) -> usize {
+    let mut depth = 0;
+    let mut prev = ' ';
+    let mut i = 0;
+    while let Some(ch) = s[i..].chars().next() {
+        match ch {
+            '\'' if matches!(prev, '[' | '$' | ':') => {
+                i += quoted_len(&s[i..], '\'');
+                prev = '\'';
+                continue;
+            }
+            '\'' => {
+                i += 1;
+                if let Some(escaped) = s[i..].chars().next() {
+                    i += escaped.len_utf8();
+                }
+                prev = ' ';
+                continue;
+            }
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+        prev = ch;
+        i += ch.len_utf8();
+    }
+    s.len()
+}
+
+/// Split at each `separator` outside `'...'`
+fn split_outside_quotes(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while let Some(ch) = s[i..].chars().next() {
+        if ch == '\'' {
+            i += quoted_len(&s[i..], '\'');
+            continue;
+        }
+        if ch == separator {
+            parts.push(&s[start..i]);
+            start = i + 1;
+        }
+        i += ch.len_utf8();
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rates() -> Vec<ExternalWorkbook> {
+        vec![ExternalWorkbook {
+            index: 0,
+            path: "Rates.xlsx".to_string(),
+            target: "file:///data/Rates.xlsx".to_string(),
+            ..Default::default()
+        }]
+    }
+
+    #[test]
+    fn test_openformula_to_excel() {
+        let cases = [
+            (r#"of:=IF([.I7]=""; "Y";"N")"#, r#"IF(I7="", "Y","N")"#),
+            ("of:=SUM({1;2|3;4})", "SUM({1,2;3,4})"),
+            (
+                r#"of:=COM.MICROSOFT.CONCAT([.A1];"a;b")"#,
+                r#"CONCAT(A1,"a;b")"#,
+            ),
+            ("of:=SUM([.A1:.B2]~[$Data.C1])", "SUM((A1:B2,Data!C1))"),
+            ("of:=SUM(([.A1]~[.B1]);[.C1])", "SUM((A1,B1),C1)"),
+            ("of:=SUM([.A1:.C3]![.B2:.B4])", "SUM(A1:C3 B2:B4)"),
+            ("of:=[.A1]!![.B1]", "A1 B1"),
+            ("of:=[.A1:.C3] !! [.B2:.B4]", "A1:C3 B2:B4"),
+            (
+                "of:=ORG.OPENOFFICE.EASTERSUNDAY([.A1])",
+                "ORG.OPENOFFICE.EASTERSUNDAY(A1)",
+            ),
+            (
+                "of:=IF(TRUE();CEILING.XCL([.A1];2);[$'My Sheet'.$A$1])",
+                "IF(TRUE,CEILING(A1,2),'My Sheet'!$A$1)",
+            ),
+            ("of:=SUM([$Jan.A1:$Mar.A1])", "SUM(Jan:Mar!A1)"),
+            ("of:=SUM([.A:.A];[.1:.3])", "SUM(A:A,1:3)"),
+            (
+                "of:=['file:///data/Rates.xlsx'#$Rates.B3]*2",
+                "[1]Rates!B3*2",
+            ),
+            (
+                "of:=['file:///data/Rates.xlsx'#$'Q1 Rates'.B3]",
+                "'[1]Q1 Rates'!B3",
+            ),
+            ("of:=[.#REF!]+1", "#REF!+1"),
+            ("msoxl:=SUM(A1,B1)", "SUM(A1,B1)"),
+            ("=1+2", "1+2"),
+        ];
+        for (openformula, excel) in cases {
+            assert_eq!(to_excel(openformula, &rates()), excel, "{}", openformula);
+        }
+    }
+
+    #[test]
+    fn test_excel_to_openformula() {
+        let cases = [
+            (r#"IF(I7="", "Y","N")"#, r#"of:=IF([.I7]=""; "Y";"N")"#),
+            ("=SUM({1,2;3,4})", "of:=SUM({1;2|3;4})"),
+            (
+                "_xlfn.CONCAT(A1,TRUE)",
+                "of:=COM.MICROSOFT.CONCAT([.A1];TRUE())",
+            ),
+            ("SUM((A1:B2,Data!C1))", "of:=SUM(([.A1:.B2]~[$Data.C1]))"),
+            ("SUM(A1:C3 B2:B4)", "of:=SUM([.A1:.C3]![.B2:.B4])"),
+            (
+                "CEILING('My Sheet'!$A$1,2)",
+                "of:=CEILING.XCL([$'My Sheet'.$A$1];2)",
+            ),
+            ("SUM(Jan:Mar!A1)", "of:=SUM([$Jan.A1:$Mar.A1])"),
+            ("SUM(A:A,1:3)+LOG10(2)", "of:=SUM([.A:.A];[.1:.3])+LOG10(2)"),
+            (
+                "[1]Rates!B3*2",
+                "of:=['file:///data/Rates.xlsx'#$Rates.B3]*2",
+            ),
+            (
+                "'[1]Q1 Rates'!B3",
+                "of:=['file:///data/Rates.xlsx'#$'Q1 Rates'.B3]",
+            ),
+            ("Sales[Amount]*Rate", "of:=Sales[Amount]*Rate"),
+        ];
+        for (excel, openformula) in cases {
+            assert_eq!(to_openformula(excel, &rates()), openformula, "{}", excel);
+        }
+    }
+
+    #[test]
+    fn test_malformed_formulas() {
+        // Multibyte function names and unterminated brackets pass through
+        assert_eq!(to_excel("of:=aÄÄÄÄÄÄÄ([.A1])", &rates()), "aÄÄÄÄÄÄÄ(A1)");
+        assert_eq!(to_excel("of:=[", &rates()), "[");
+        assert_eq!(to_excel("of:=[.A1", &rates()), "[.A1");
+        assert_eq!(to_openformula("=[", &rates()), "of:=[");
+        assert_eq!(to_openformula("=Sales[", &rates()), "of:=Sales[");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for excel in [
+            r#"IF(AND(A1>0,'Q1 Rates'!B$2<>""),VLOOKUP(A1,Data!A:C,3,FALSE),NA())"#,
+            "SUMPRODUCT((A1:A10>0)*B1:B10)+[1]Rates!B3",
+            "XLOOKUP(A1,B:B,C:C)&TEXTJOIN(\",\",TRUE,D1:D3)",
+        ] {
+            let openformula = to_openformula(excel, &rates());
+            assert_eq!(to_excel(&openformula, &rates()), excel, "{}", openformula);
+        }
+    }
+}
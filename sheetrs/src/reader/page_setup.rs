@@ -0,0 +1,259 @@
+//! Print setup and page layout
+//!
+//! XLSX keeps the print area and print titles as the sheet-local
+//! `_xlnm.Print_Area` and `_xlnm.Print_Titles` defined names, and the rest in
+//! the worksheet: `<pageMargins>` (inches), `<pageSetup>` (orientation, scale,
+//! fit-to-page counts, which only apply when `<pageSetUpPr fitToPage>` is set)
+//! and `<headerFooter>`. ODS puts the print ranges on `table:table`, the title
+//! rows and columns in `table:table-header-rows`/`-columns`, and the rest in
+//! the master page of the table's style: the page layout in styles.xml and the
+//! header and footer contents.
+
+/// Page orientation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Portrait,
+    Landscape,
+}
+
+/// Page margins in inches
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Margins {
+    pub left: f64,
+    pub right: f64,
+    pub top: f64,
+    pub bottom: f64,
+    /// Distance from the top edge to the header
+    pub header: f64,
+    /// Distance from the bottom edge to the footer
+    pub footer: f64,
+}
+
+/// Whether a header/footer text is printed at the top or the bottom
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderFooterKind {
+    Header,
+    Footer,
+}
+
+impl HeaderFooterKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HeaderFooterKind::Header => "header",
+            HeaderFooterKind::Footer => "footer",
+        }
+    }
+}
+
+/// Pages a header or footer is printed on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderFooterPages {
+    /// Every page, or the odd pages when even pages have their own
+    All,
+    Even,
+    First,
+}
+
+/// A header or footer
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeaderFooter {
+    pub kind: HeaderFooterKind,
+    pub pages: HeaderFooterPages,
+    /// Content in Excel's code syntax: `&L`, `&C` and `&R` start the left,
+    /// center and right sections, `&P` is the page number, `&N` the page
+    /// count, `&D`/`&T` the date and time, `&A` the sheet name, `&F` the file
+    /// name and `&Z` its folder. ODS fields are written the same way.
+    pub text: String,
+}
+
+impl HeaderFooter {
+    /// The literal text, without section, field and font codes, with runs of
+    /// whitespace collapsed
+    pub fn literal_text(&self) -> String {
+        let mut literal = String::new();
+        let mut chars = self.text.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '&' {
+                literal.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('&') => literal.push('&'),
+                // Font name and style: &"Arial,Bold"
+                Some('"') => {
+                    for c in chars.by_ref() {
+                        if c == '"' {
+                            break;
+                        }
+                    }
+                }
+                // Font size: &12
+                Some(c) if c.is_ascii_digit() => {
+                    while chars.next_if(|c| c.is_ascii_digit()).is_some() {}
+                }
+                // Font color: &KFF0000, or a theme color like &K01+000
+                Some('K') => {
+                    for _ in 0..6 {
+                        chars.next();
+                    }
+                }
+                // Section start or field: keep the text around it apart
+                Some(_) => literal.push(' '),
+                None => {}
+            }
+        }
+        literal.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// Whether the header or footer prints the file's folder (`&Z`)
+    pub fn shows_file_path(&self) -> bool {
+        let mut chars = self.text.chars();
+        while let Some(c) = chars.next() {
+            // Skipping the character after each `&` also skips `&&`, a literal ampersand
+            if c == '&' && chars.next() == Some('Z') {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// How a sheet is printed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PageSetup {
+    /// Ranges printed: (start_row, start_col, end_row, end_col); empty when
+    /// the whole used range is printed
+    pub print_area: Vec<(u32, u32, u32, u32)>,
+    /// The print area as written, when it doesn't resolve to ranges on the
+    /// sheet (e.g. `#REF!` after its rows were deleted)
+    pub broken_print_area: Option<String>,
+    /// Rows repeated at the top of every page (0-based, inclusive)
+    pub title_rows: Option<(u32, u32)>,
+    /// Columns repeated at the left of every page (0-based, inclusive)
+    pub title_columns: Option<(u32, u32)>,
+    pub orientation: Option<Orientation>,
+    /// Scale in percent, when not fitting to pages
+    pub scale: Option<u16>,
+    /// Pages wide and tall the sheet is shrunk to fit, 0 meaning as many as
+    /// needed
+    pub fit_to_pages: Option<(u32, u32)>,
+    pub margins: Option<Margins>,
+    pub headers_footers: Vec<HeaderFooter>,
+}
+
+impl PageSetup {
+    /// Set the print area from an A1 reference list such as
+    /// `Sheet1!$A$1:$C$9,Sheet1!$E$1:$F$2`
+    pub fn set_print_area(&mut self, reference: &str) {
+        let ranges: Option<Vec<_>> = split_references(reference)
+            .map(|range| parse_print_range(range).map(|(range, _)| range))
+            .collect();
+        match ranges {
+            Some(ranges) if !ranges.is_empty() => self.print_area = ranges,
+            _ => self.broken_print_area = Some(reference.to_string()),
+        }
+    }
+
+    /// Set the print titles from an A1 reference list of whole rows and/or
+    /// whole columns, such as `Sheet1!$1:$2,Sheet1!$A:$A`
+    pub fn set_print_titles(&mut self, reference: &str) {
+        for range in split_references(reference) {
+            match parse_print_range(range) {
+                Some(((r1, _, r2, _), RangeKind::Rows)) => self.title_rows = Some((r1, r2)),
+                Some(((_, c1, _, c2), RangeKind::Columns)) => self.title_columns = Some((c1, c2)),
+                _ => {}
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RangeKind {
+    Cells,
+    Rows,
+    Columns,
+}
+
+/// Split a reference list at the commas outside quoted sheet names
+fn split_references(reference: &str) -> impl Iterator<Item = &str> {
+    let mut quoted = false;
+    reference
+        .split(move |c| {
+            if c == '\'' {
+                quoted = !quoted;
+            }
+            c == ',' && !quoted
+        })
+        .map(str::trim)
+        .filter(|range| !range.is_empty())
+}
+
+/// Parse a range like `Sheet1!$A$1:$C$9`, `$1:$2` (whole rows) or `$A:$B`
+/// (whole columns), ignoring the sheet name
+fn parse_print_range(range: &str) -> Option<((u32, u32, u32, u32), RangeKind)> {
+    let range = range.rsplit_once('!').map_or(range, |(_, range)| range);
+    let (start, end) = range.split_once(':').unwrap_or((range, range));
+    let (start, end) = (start.replace('$', ""), end.replace('$', ""));
+
+    let row = |text: &str| text.parse::<u32>().ok()?.checked_sub(1);
+    let column = |text: &str| -> Option<u32> {
+        if text.is_empty() || !text.chars().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        let col = text.chars().fold(0u32, |col, c| {
+            col * 26 + (c.to_ascii_uppercase() as u32 - 'A' as u32 + 1)
+        });
+        col.checked_sub(1)
+    };
+    let cell = |text: &str| -> Option<(u32, u32)> {
+        let split = text.find(|c: char| c.is_ascii_digit())?;
+        Some((row(&text[split..])?, column(&text[..split])?))
+    };
+
+    if let (Some(r1), Some(r2)) = (row(&start), row(&end)) {
+        return Some(((r1, 0, r2, u32::MAX), RangeKind::Rows));
+    }
+    if let (Some(c1), Some(c2)) = (column(&start), column(&end)) {
+        return Some(((0, c1, u32::MAX, c2), RangeKind::Columns));
+    }
+    let ((r1, c1), (r2, c2)) = (cell(&start)?, cell(&end)?);
+    Some(((r1, c1, r2, c2), RangeKind::Cells))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_print_area_and_titles() {
+        let mut setup = PageSetup::default();
+        setup.set_print_area("'My Sheet'!$A$1:$C$9,'My Sheet'!$E$2");
+        setup.set_print_titles("'My Sheet'!$1:$2,'My Sheet'!$A:$B");
+        assert_eq!(setup.print_area, vec![(0, 0, 8, 2), (1, 4, 1, 4)]);
+        assert_eq!(setup.title_rows, Some((0, 1)));
+        assert_eq!(setup.title_columns, Some((0, 1)));
+        assert_eq!(setup.broken_print_area, None);
+
+        let mut broken = PageSetup::default();
+        broken.set_print_area("Sheet1!#REF!");
+        assert!(broken.print_area.is_empty());
+        assert_eq!(broken.broken_print_area.as_deref(), Some("Sheet1!#REF!"));
+    }
+
+    #[test]
+    fn test_header_footer_text() {
+        let footer = HeaderFooter {
+            kind: HeaderFooterKind::Footer,
+            pages: HeaderFooterPages::All,
+            text: "&L&\"Arial,Bold\"&12Prepared by R&&D&C&Z&F&RPage &P of &N".to_string(),
+        };
+        assert_eq!(footer.literal_text(), "Prepared by R&D Page of");
+        assert!(footer.shows_file_path());
+
+        let header = HeaderFooter {
+            text: "&CSales && &&Z".to_string(),
+            ..footer
+        };
+        assert!(!header.shows_file_path());
+    }
+}
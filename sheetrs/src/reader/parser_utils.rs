@@ -3,6 +3,7 @@
 use anyhow::Result;
 use quick_xml::Reader;
 use quick_xml::events::Event;
+use std::borrow::Cow;
 
 /// Parse a cell reference like "A1" into (row, col) as 0-based indices
 pub fn parse_cell_ref(cell_ref: &str) -> Option<(u32, u32)> {
@@ -40,6 +41,27 @@ pub fn parse_cell_range(range: &str) -> Option<(u32, u32, u32, u32)> {
     Some((start_row, start_col, end_row, end_col))
 }
 
+/// Sheet name as formulas write it, quoted when it contains anything besides
+/// letters, digits, `_` and `.`
+pub fn quote_sheet_name(sheet: &str) -> Cow<'_, str> {
+    if sheet
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+    {
+        Cow::Borrowed(sheet)
+    } else {
+        Cow::Owned(format!("'{}'", sheet.replace('\'', "''")))
+    }
+}
+
+/// Sheet name from a formula's `Sheet1` or `'My Sheet'` qualifier
+pub fn unquote_sheet_name(sheet: &str) -> Cow<'_, str> {
+    match sheet.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')) {
+        Some(quoted) => Cow::Owned(quoted.replace("''", "'")),
+        None => Cow::Borrowed(sheet),
+    }
+}
+
 /// Read text content from an XML node
 pub fn read_text_node<R: std::io::BufRead>(reader: &mut Reader<R>) -> Result<String> {
     let mut buf = Vec::new();
@@ -76,6 +98,15 @@ mod tests {
         assert_eq!(parse_cell_range("C3:D4"), Some((2, 2, 3, 3)));
         assert_eq!(parse_cell_range("A1:Z26"), Some((0, 0, 25, 25)));
     }
+
+    #[test]
+    fn test_quote_sheet_name() {
+        assert_eq!(quote_sheet_name("Sheet1"), "Sheet1");
+        assert_eq!(quote_sheet_name("Q1 Sales"), "'Q1 Sales'");
+        assert_eq!(quote_sheet_name("Bob's"), "'Bob''s'");
+        assert_eq!(unquote_sheet_name("'Bob''s'"), "Bob's");
+        assert_eq!(unquote_sheet_name("Sheet1"), "Sheet1");
+    }
 }
 
 /// Extract basename from a file path
@@ -84,10 +115,10 @@ pub fn extract_basename(path: &str) -> String {
     // Strip file:// prefix if present
     let clean_path = path.trim_start_matches("file://");
 
-    // Extract basename using Path
-    std::path::Path::new(clean_path)
-        .file_name()
-        .and_then(|s| s.to_str())
+    // Split at either separator: Windows paths are read on any platform
+    clean_path
+        .rsplit(['/', '\\'])
+        .next()
         .unwrap_or(clean_path)
         .to_string()
 }
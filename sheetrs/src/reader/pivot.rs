@@ -0,0 +1,117 @@
+//! Pivot tables (XLSX) and data pilot tables (ODS)
+//!
+//! An XLSX pivot table (`xl/pivotTables/pivotTableN.xml`, linked from the
+//! worksheet it is placed on) reads its data through a pivot cache
+//! (`xl/pivotCache/pivotCacheDefinitionN.xml` and, when the data is saved with
+//! the file, `pivotCacheRecordsN.xml`); the cache definition names the source
+//! range, table or connection. ODS lists `table:data-pilot-table` elements at
+//! the end of `content.xml`, each with its own source and no saved cache.
+
+use super::parser_utils::{parse_cell_range, quote_sheet_name};
+
+/// (start_row, start_col, end_row, end_col)
+type Range = (u32, u32, u32, u32);
+
+/// Where a pivot table takes its data from
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum PivotSource {
+    /// Cells of a sheet of this workbook, with the range as the file writes
+    /// it: `A1:D100`, or `#REF!` once the cells were deleted
+    Range { sheet: String, reference: String },
+    /// A defined name or table of this workbook
+    Name(String),
+    /// Cells or a name in another workbook
+    External { workbook: String, reference: String },
+    /// A data connection or database query: the XLSX `connectionId`, or the
+    /// ODS database and table or query name
+    Connection(String),
+    /// Consolidation ranges, scenarios or a source the reader doesn't model
+    #[default]
+    Other,
+}
+
+impl PivotSource {
+    /// The source as a formula would reference it (`Data!A1:D100`, `Sales`);
+    /// `None` for sources outside the workbook
+    pub fn formula(&self) -> Option<String> {
+        match self {
+            Self::Range { sheet, reference } if sheet.is_empty() => Some(reference.clone()),
+            Self::Range { sheet, reference } => {
+                Some(format!("{}!{}", quote_sheet_name(sheet), reference))
+            }
+            Self::Name(name) => Some(name.clone()),
+            _ => None,
+        }
+    }
+
+    /// Sheet and cells of a `Range` source whose reference is still valid
+    pub fn range(&self) -> Option<(&str, Range)> {
+        match self {
+            Self::Range { sheet, reference } => Some((
+                sheet.as_str(),
+                parse_cell_range(&reference.replace('$', ""))?,
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Data an XLSX pivot table keeps in the file to recalculate without its source
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PivotCache {
+    /// `cacheId`; pivot tables sharing a cache have the same id
+    pub id: u32,
+    /// Number of source rows saved in the cache records; `None` when the file
+    /// doesn't say
+    pub records: Option<u64>,
+    /// Compressed size in bytes of the cache definition and records parts
+    pub size: u64,
+    /// Whether Excel rebuilds the cache from the source when opening the file
+    pub refresh_on_load: bool,
+}
+
+/// A pivot table (XLSX) or data pilot table (ODS)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PivotTable {
+    pub name: String,
+    /// Sheet the pivot table is placed on; empty when the file doesn't say
+    pub sheet: String,
+    /// Cells the pivot table fills: (start_row, start_col, end_row, end_col)
+    pub location: (u32, u32, u32, u32),
+    pub source: PivotSource,
+    /// `None` for ODS, which keeps no cache
+    pub cache: Option<PivotCache>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pivot_source_references() {
+        let source = PivotSource::Range {
+            sheet: "Raw Data".to_string(),
+            reference: "$A$1:$D$100".to_string(),
+        };
+        assert_eq!(source.formula().unwrap(), "'Raw Data'!$A$1:$D$100");
+        assert_eq!(source.range(), Some(("Raw Data", (0, 0, 99, 3))));
+
+        let broken = PivotSource::Range {
+            sheet: "Data".to_string(),
+            reference: "#REF!".to_string(),
+        };
+        assert_eq!(broken.formula().unwrap(), "Data!#REF!");
+        assert_eq!(broken.range(), None);
+
+        assert_eq!(
+            PivotSource::Name("Sales".to_string()).formula().unwrap(),
+            "Sales"
+        );
+        let external = PivotSource::External {
+            workbook: "file:///C:/data/q1.xlsx".to_string(),
+            reference: "Data!A1:C20".to_string(),
+        };
+        assert_eq!(external.formula(), None);
+        assert_eq!(external.range(), None);
+    }
+}
@@ -0,0 +1,122 @@
+//! Document properties
+//!
+//! Descriptive metadata saved with a workbook: who created and last edited it,
+//! their organisation, the template it came from, timestamps and user-defined
+//! custom properties. XLSX spreads these over `docProps/core.xml` (Dublin
+//! Core), `docProps/app.xml` (application properties such as company and
+//! manager) and `docProps/custom.xml`; ODS keeps them in `meta.xml`.
+
+use super::dates::DateTime;
+use std::collections::BTreeMap;
+
+/// Metadata saved with a workbook; fields the file leaves empty are `None`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocumentProperties {
+    pub title: Option<String>,
+    pub subject: Option<String>,
+    pub description: Option<String>,
+    pub keywords: Option<String>,
+    /// Who created the document
+    pub author: Option<String>,
+    pub last_modified_by: Option<String>,
+    pub company: Option<String>,
+    pub manager: Option<String>,
+    /// Template the document was created from (a name or a path)
+    pub template: Option<String>,
+    /// Application that last saved the document
+    pub application: Option<String>,
+    pub created: Option<DateTime>,
+    pub modified: Option<DateTime>,
+    /// User-defined properties, by name
+    pub custom: BTreeMap<String, String>,
+}
+
+impl DocumentProperties {
+    /// Properties that identify people or organisations, as (label, value)
+    pub fn personal(&self) -> Vec<(&'static str, &str)> {
+        let mut personal = Vec::new();
+        let fields = [
+            ("Author", &self.author),
+            ("Last modified by", &self.last_modified_by),
+            ("Manager", &self.manager),
+            ("Company", &self.company),
+        ];
+        for (label, value) in fields {
+            if let Some(value) = value {
+                personal.push((label, value.as_str()));
+            }
+        }
+        // A template path often includes the user's profile directory
+        if let Some(template) = &self.template
+            && (template.contains('/') || template.contains('\\'))
+        {
+            personal.push(("Template", template.as_str()));
+        }
+        personal
+    }
+}
+
+/// Store a text property read from the file, ignoring blank values
+pub fn set_text(field: &mut Option<String>, value: &str) {
+    let value = value.trim();
+    if !value.is_empty() {
+        *field = Some(value.to_string());
+    }
+}
+
+/// Parse a property timestamp such as `2025-12-24T12:23:35Z` or an ODS
+/// `2025-12-23T16:23:58.135638672`; time zone offsets are dropped
+pub fn parse_timestamp(value: &str) -> Option<DateTime> {
+    let value = value.trim().trim_end_matches('Z');
+    let value = match value.split_once('T') {
+        Some((date, time)) => match time.find(['+', '-']) {
+            Some(offset) => &value[..date.len() + 1 + offset],
+            None => value,
+        },
+        None => value,
+    };
+    DateTime::parse_iso(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(
+            parse_timestamp("2025-12-24T12:23:35Z"),
+            Some(DateTime::from_ymd(2025, 12, 24).with_time(12, 23, 35))
+        );
+        assert_eq!(
+            parse_timestamp("2025-12-24T12:23:35+01:00"),
+            Some(DateTime::from_ymd(2025, 12, 24).with_time(12, 23, 35))
+        );
+        assert_eq!(
+            parse_timestamp("2025-12-23T16:23:58.135").map(|dt| dt.millisecond),
+            Some(135)
+        );
+        assert_eq!(parse_timestamp(""), None);
+    }
+
+    #[test]
+    fn test_personal_properties() {
+        let properties = DocumentProperties {
+            title: Some("Budget".to_string()),
+            author: Some("Jane Doe".to_string()),
+            company: Some("Acme".to_string()),
+            template: Some("Normal".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            properties.personal(),
+            vec![("Author", "Jane Doe"), ("Company", "Acme")]
+        );
+
+        let properties = DocumentProperties {
+            template: Some("C:\\Users\\jdoe\\Templates\\Budget.xltx".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(properties.personal().len(), 1);
+    }
+}
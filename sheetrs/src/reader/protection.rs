@@ -0,0 +1,188 @@
+//! Sheet and workbook protection, and encrypted package detection
+//!
+//! Protection only locks editing: the content stays readable and the password
+//! is stored as a hash, so how the hash was made decides how easily it can be
+//! reversed. XLSX uses `<sheetProtection>` and `<workbookProtection>`, either
+//! with the legacy 16-bit `password` hash or with an `algorithmName`, salt and
+//! spin count; ODS uses `table:protected` / `table:structure-protected` with a
+//! `table:protection-key` digest. Which cells a protected sheet locks comes
+//! from the cell styles (see [`super::styles::Protection`]).
+//!
+//! Encryption is different: an encrypted OOXML file is a Compound File Binary
+//! (CFB) container wrapping the real package, and an encrypted ODS is a zip whose
+//! parts are encrypted. Both are reported with [`EncryptedFileError`].
+
+use anyhow::Result;
+use std::fmt;
+use std::io::Read;
+use std::path::PathBuf;
+
+/// Signature of a Compound File Binary container
+const CFB_SIGNATURE: [u8; 8] = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+/// Hash of a protection password
+#[derive(Debug, Clone, PartialEq)]
+pub struct PasswordHash {
+    /// `legacy` for the 16-bit XLSX hash, otherwise the algorithm name
+    /// (`SHA-512`, `SHA-256`, `SHA-1`, ...)
+    pub algorithm: String,
+    /// Hash as stored: hex for the legacy hash, base64 otherwise
+    pub value: String,
+    pub salted: bool,
+    /// Number of hash iterations
+    pub spin_count: u32,
+}
+
+impl PasswordHash {
+    /// The 16-bit hash of the XLSX `password` attributes
+    pub fn legacy(value: &str) -> Self {
+        Self {
+            algorithm: "legacy".to_string(),
+            value: value.to_string(),
+            salted: false,
+            spin_count: 0,
+        }
+    }
+
+    /// An ODS `table:protection-key` with its digest algorithm URI. ODF 1.2
+    /// defaults to SHA-1 when no algorithm is given; keys are never salted.
+    pub fn from_ods(value: &str, algorithm_uri: Option<&str>) -> Self {
+        let algorithm = match algorithm_uri.and_then(|uri| uri.rsplit('#').next()) {
+            None | Some("sha1") => "SHA-1",
+            Some("sha256") => "SHA-256",
+            Some("sha512") => "SHA-512",
+            Some("legacy-hash-excel") => "legacy",
+            Some(other) => other,
+        };
+        Self {
+            algorithm: algorithm.to_string(),
+            value: value.to_string(),
+            salted: false,
+            spin_count: 1,
+        }
+    }
+
+    /// Why the hash is easy to reverse, or `None` for a salted modern hash
+    pub fn weakness(&self) -> Option<String> {
+        let algorithm = self.algorithm.to_ascii_uppercase();
+        if algorithm == "LEGACY" {
+            return Some("the legacy 16-bit hash, which is trivially reversible".to_string());
+        }
+        if matches!(
+            algorithm.as_str(),
+            "MD2" | "MD4" | "MD5" | "SHA-1" | "SHA1" | "RIPEMD-128" | "RIPEMD-160"
+        ) {
+            return Some(format!("the broken {} algorithm", self.algorithm));
+        }
+        if !self.salted {
+            return Some(format!("an unsalted {} hash", self.algorithm));
+        }
+        None
+    }
+}
+
+/// Protection of a sheet's cells; present only when the sheet is protected
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SheetProtection {
+    /// `None` when the protection has no password
+    pub password: Option<PasswordHash>,
+}
+
+/// Protection of the workbook's structure (adding, moving, hiding sheets) and
+/// windows
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkbookProtection {
+    pub structure: bool,
+    pub windows: bool,
+    pub password: Option<PasswordHash>,
+}
+
+impl WorkbookProtection {
+    /// Whether anything is actually locked
+    pub fn is_active(&self) -> bool {
+        self.structure || self.windows
+    }
+}
+
+/// A spreadsheet that can't be read without its password
+#[derive(Debug)]
+pub struct EncryptedFileError {
+    pub path: PathBuf,
+}
+
+impl fmt::Display for EncryptedFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is encrypted with a password; save an unencrypted copy to read it",
+            self.path.display()
+        )
+    }
+}
+
+impl std::error::Error for EncryptedFileError {}
+
+/// Whether the input is a CFB container holding an encrypted OOXML package.
+///
+/// Returns `Ok(false)` for anything that isn't CFB, without reading past the
+/// signature.
+pub fn is_encrypted_ooxml(mut input: impl Read) -> Result<bool> {
+    let mut signature = [0u8; 8];
+    if input.read_exact(&mut signature).is_err() || signature != CFB_SIGNATURE {
+        return Ok(false);
+    }
+
+    // Directory entries hold UTF-16LE stream names; legacy .xls files are CFB
+    // too but have no EncryptedPackage stream
+    let needle: Vec<u8> = "EncryptedPackage"
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    let mut data = Vec::new();
+    input.read_to_end(&mut data)?;
+    Ok(data.windows(needle.len()).any(|window| window == needle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_password_hash_weakness() {
+        assert!(
+            PasswordHash::legacy("CC1A")
+                .weakness()
+                .unwrap()
+                .contains("16-bit")
+        );
+        assert_eq!(
+            PasswordHash::from_ods("abc=", None).weakness().as_deref(),
+            Some("the broken SHA-1 algorithm")
+        );
+        let sha256 =
+            PasswordHash::from_ods("abc=", Some("http://www.w3.org/2000/09/xmldsig#sha256"));
+        assert_eq!(sha256.algorithm, "SHA-256");
+        assert_eq!(
+            sha256.weakness().as_deref(),
+            Some("an unsalted SHA-256 hash")
+        );
+        let modern = PasswordHash {
+            algorithm: "SHA-512".to_string(),
+            value: "abc=".to_string(),
+            salted: true,
+            spin_count: 100000,
+        };
+        assert_eq!(modern.weakness(), None);
+    }
+
+    #[test]
+    fn test_is_encrypted_ooxml() {
+        let mut cfb = CFB_SIGNATURE.to_vec();
+        cfb.extend_from_slice(&[0; 64]);
+        assert!(!is_encrypted_ooxml(cfb.as_slice()).unwrap());
+        cfb.extend("EncryptedPackage".encode_utf16().flat_map(u16::to_le_bytes));
+        assert!(is_encrypted_ooxml(cfb.as_slice()).unwrap());
+        assert!(!is_encrypted_ooxml(&b"PK\x03\x04 zip"[..]).unwrap());
+        assert!(!is_encrypted_ooxml(&b""[..]).unwrap());
+    }
+}
@@ -0,0 +1,132 @@
+//! R1C1 form of formulas
+//!
+//! Copying a formula changes its A1 text (`A1+B1` in C1 becomes `A2+B2` in
+//! C2) but not its R1C1 text, where relative references are offsets from the
+//! formula's own cell (`RC[-2]+RC[-1]`) and absolute ones keep their row and
+//! column numbers (`R1C1`). Formulas with the same R1C1 text are copies of one
+//! formula, which is how Excel's inconsistent formula check sees them.
+
+use super::openformula::{AddressKind, Token, address_kind, tokenize};
+
+/// R1C1 form of an A1-style formula held by the cell at (row, col), 0-based,
+/// without the leading `=`. Sheet names, defined names, structured references
+/// and text are kept as written.
+pub fn to_r1c1(formula: &str, row: u32, col: u32) -> String {
+    let formula = formula.trim();
+    let tokens = tokenize(formula.strip_prefix('=').unwrap_or(formula));
+    let mut out = String::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        // Words before `!` name sheets and words before `(` name functions
+        let is_operand = |j: usize| {
+            !matches!(
+                tokens.get(j + 1),
+                Some(Token::Punct('!')) | Some(Token::Punct('('))
+            )
+        };
+        if let Token::Word(word) = tokens[i]
+            && is_operand(i)
+        {
+            let kind = address_kind(word);
+            // Whole columns and rows only stand as references in a range
+            if let [Token::Punct(':'), Token::Word(end), ..] = tokens[i + 1..]
+                && kind.is_some()
+                && address_kind(end) == kind
+                && is_operand(i + 2)
+            {
+                out.push_str(&address_to_r1c1(word, row, col));
+                out.push(':');
+                out.push_str(&address_to_r1c1(end, row, col));
+                i += 3;
+                continue;
+            }
+            if kind == Some(AddressKind::Cell) {
+                out.push_str(&address_to_r1c1(word, row, col));
+                i += 1;
+                continue;
+            }
+        }
+        match tokens[i] {
+            Token::Punct(ch) => out.push(ch),
+            Token::Text(s)
+            | Token::Quoted(s)
+            | Token::Bracket(s)
+            | Token::Word(s)
+            | Token::Space(s) => out.push_str(s),
+        }
+        i += 1;
+    }
+    out
+}
+
+/// R1C1 form of a cell (`$B3`), column (`B`) or row (`3`) address
+fn address_to_r1c1(address: &str, row: u32, col: u32) -> String {
+    let (col_absolute, rest) = match address.strip_prefix('$') {
+        Some(rest) => (true, rest),
+        None => (false, address),
+    };
+    let letters = rest.len()
+        - rest
+            .trim_start_matches(|c: char| c.is_ascii_alphabetic())
+            .len();
+    let (letters, rest) = rest.split_at(letters);
+    let (row_absolute, digits) = match rest.strip_prefix('$') {
+        Some(digits) => (true, digits),
+        None => (false, rest),
+    };
+    // A lone `$` belongs to the row when there are no letters: `$3`
+    let row_absolute = row_absolute || (letters.is_empty() && col_absolute);
+
+    let mut r1c1 = String::new();
+    if let Ok(number) = digits.parse::<i64>() {
+        r1c1.push_str(&offset('R', number - 1, row, row_absolute));
+    }
+    if !letters.is_empty() {
+        let index = letters.chars().fold(0i64, |n, c| {
+            n * 26 + (c.to_ascii_uppercase() as i64 - 'A' as i64 + 1)
+        });
+        r1c1.push_str(&offset('C', index - 1, col, col_absolute));
+    }
+    r1c1
+}
+
+/// `R5` for an absolute row, `R[-2]` or `R` for a relative one
+fn offset(axis: char, index: i64, origin: u32, absolute: bool) -> String {
+    let delta = index - origin as i64;
+    if absolute {
+        format!("{}{}", axis, index + 1)
+    } else if delta == 0 {
+        axis.to_string()
+    } else {
+        format!("{}[{}]", axis, delta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_r1c1() {
+        // Copies down a column share their R1C1 form
+        assert_eq!(to_r1c1("=A1+B1", 0, 2), "RC[-2]+RC[-1]");
+        assert_eq!(to_r1c1("A2+B2", 1, 2), "RC[-2]+RC[-1]");
+        assert_eq!(
+            to_r1c1("SUM($A$1:A3)*$B2+C$1", 2, 3),
+            "SUM(R1C1:RC[-3])*R[-1]C2+R1C[-1]"
+        );
+        assert_eq!(
+            to_r1c1("VLOOKUP(A5,Data!$A:$C,3,FALSE)+SUM(2:$4)", 4, 1),
+            "VLOOKUP(RC[-1],Data!C1:C3,3,FALSE)+SUM(R[-3]:R4)"
+        );
+        // Sheet spans, functions named like cells, names and text stay
+        assert_eq!(
+            to_r1c1(r#"SUM(Jan:Mar!B2)+LOG10(A1)&"A1"&Rate"#, 0, 0),
+            r#"SUM(Jan:Mar!R[1]C[1])+LOG10(RC)&"A1"&Rate"#
+        );
+        assert_eq!(
+            to_r1c1("'My Sheet'!A1+[1]Rates!B3+Sales[Amount]", 1, 0),
+            "'My Sheet'!R[-1]C+[1]Rates!R[1]C[1]+Sales[Amount]"
+        );
+    }
+}
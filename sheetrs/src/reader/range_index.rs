@@ -0,0 +1,129 @@
+//! Cell lookup over rectangular ranges
+//!
+//! Array formulas and data validations each cover one or more ranges of a
+//! sheet. Finding the one covering a cell by scanning them all is fine once,
+//! but rules asking for every cell of a sheet need an index: the sheet's rows
+//! are cut into bands where the same ranges apply, and each band keeps its
+//! ranges sorted by first column.
+
+/// Items of a sheet (array formulas, validations) looked up by covered cell
+#[derive(Debug, Clone)]
+pub struct RangeIndex<'a, T> {
+    items: &'a [T],
+    /// First row of each band, with the ranges covering the band's rows
+    bands: Vec<(u32, Vec<Entry>)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    first_col: u32,
+    last_col: u32,
+    /// Largest `last_col` of this entry and those before it in the band
+    reach: u32,
+    item: usize,
+}
+
+impl<'a, T> RangeIndex<'a, T> {
+    /// Index `items` by the `(start_row, start_col, end_row, end_col)` ranges
+    /// each covers; corners may be given in either order
+    pub fn new(items: &'a [T], ranges: impl Fn(&T) -> &[(u32, u32, u32, u32)]) -> Self {
+        let mut rects = Vec::new();
+        for (item, value) in items.iter().enumerate() {
+            for &(r1, c1, r2, c2) in ranges(value) {
+                rects.push((r1.min(r2), r2.max(r1), c1.min(c2), c2.max(c1), item));
+            }
+        }
+
+        // Bands start where a range starts or just after one ends
+        let mut starts: Vec<u32> = rects
+            .iter()
+            .flat_map(|&(r1, r2, ..)| [Some(r1), r2.checked_add(1)])
+            .flatten()
+            .collect();
+        starts.sort_unstable();
+        starts.dedup();
+
+        rects.sort_unstable_by_key(|&(r1, ..)| r1);
+        let mut by_start = rects.into_iter().peekable();
+        let mut active: Vec<(u32, u32, u32, usize)> = Vec::new();
+
+        let mut bands = Vec::with_capacity(starts.len());
+        for start in starts {
+            active.retain(|&(last_row, ..)| last_row >= start);
+            while let Some((_, r2, c1, c2, item)) = by_start.next_if(|&(r1, ..)| r1 <= start) {
+                active.push((r2, c1, c2, item));
+            }
+
+            let mut entries: Vec<Entry> = active
+                .iter()
+                .map(|&(_, first_col, last_col, item)| Entry {
+                    first_col,
+                    last_col,
+                    reach: last_col,
+                    item,
+                })
+                .collect();
+            entries.sort_unstable_by_key(|entry| (entry.first_col, entry.item));
+            let mut reach = 0;
+            for entry in &mut entries {
+                reach = reach.max(entry.last_col);
+                entry.reach = reach;
+            }
+            bands.push((start, entries));
+        }
+
+        Self { items, bands }
+    }
+
+    /// First item, in the original order, covering the cell
+    pub fn at(&self, row: u32, col: u32) -> Option<&'a T> {
+        let band = self.bands.partition_point(|(start, _)| *start <= row);
+        let (_, entries) = &self.bands[band.checked_sub(1)?];
+        let end = entries.partition_point(|entry| entry.first_col <= col);
+        entries[..end]
+            .iter()
+            .rev()
+            .take_while(|entry| entry.reach >= col)
+            .filter(|entry| entry.last_col >= col)
+            .map(|entry| entry.item)
+            .min()
+            .map(|item| &self.items[item])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_index() {
+        let ranges: Vec<Vec<(u32, u32, u32, u32)>> = vec![
+            // Whole column A
+            vec![(0, 0, 1_048_575, 0)],
+            // B2:C3 and E5, then a range written reversed (D10:D8)
+            vec![(1, 1, 2, 2), (4, 4, 4, 4)],
+            vec![(9, 3, 7, 3)],
+            // Overlaps the first one at A1
+            vec![(0, 0, 0, 1)],
+        ];
+        let index = RangeIndex::new(&ranges, |ranges| ranges.as_slice());
+        let at = |row, col| {
+            index
+                .at(row, col)
+                .map(|found| ranges.iter().position(|r| r == found).unwrap())
+        };
+
+        assert_eq!(at(500_000, 0), Some(0));
+        assert_eq!(at(0, 0), Some(0));
+        assert_eq!(at(0, 1), Some(3));
+        assert_eq!(at(2, 2), Some(1));
+        assert_eq!(at(4, 4), Some(1));
+        assert_eq!(at(3, 1), None);
+        assert_eq!(at(8, 3), Some(2));
+        assert_eq!(at(10, 3), None);
+        assert_eq!(at(u32::MAX, 0), None);
+
+        let empty: [Vec<(u32, u32, u32, u32)>; 0] = [];
+        assert!(RangeIndex::new(&empty, |r| r.as_slice()).at(0, 0).is_none());
+    }
+}
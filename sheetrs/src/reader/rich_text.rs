@@ -0,0 +1,97 @@
+//! Rich text
+//!
+//! XLSX writes formatted text as runs: a shared (`<si>`) or inline (`<is>`)
+//! string holds `<r>` elements, each with its font in `<rPr>` and its text in
+//! `<t>`. `<rPh>` elements hold phonetic guides (furigana) for a span of the
+//! base text and are not part of it. ODS writes one `text:p` per line, with
+//! `text:span`s naming automatic text styles, and `text:s`, `text:tab` and
+//! `text:line-break` for runs of spaces, tabs and line breaks.
+
+use super::styles::Font;
+
+/// A stretch of text in one font
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextRun {
+    pub text: String,
+    /// Font the run sets, `None` for the cell's own font. ODS spans only set
+    /// the properties their style changes; the rest follow the cell's font.
+    pub font: Option<Font>,
+}
+
+/// A phonetic guide shown above part of the text
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PhoneticRun {
+    pub text: String,
+    /// Characters of the base text it reads: `start..end`
+    pub start: u32,
+    pub end: u32,
+}
+
+/// Text of a cell with its formatting
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RichText {
+    /// Runs in order; their text joined is the cell's text, with `\n` between
+    /// lines
+    pub runs: Vec<TextRun>,
+    pub phonetic: Vec<PhoneticRun>,
+}
+
+impl RichText {
+    /// Append text in a font, extending the last run when the font is the same
+    pub fn push(&mut self, text: &str, font: Option<&Font>) {
+        if text.is_empty() {
+            return;
+        }
+        match self.runs.last_mut() {
+            Some(run) if run.font.as_ref() == font => run.text.push_str(text),
+            _ => self.runs.push(TextRun {
+                text: text.to_string(),
+                font: font.cloned(),
+            }),
+        }
+    }
+
+    /// The text without formatting
+    pub fn text(&self) -> String {
+        self.runs.iter().map(|run| run.text.as_str()).collect()
+    }
+
+    /// Whether there is nothing besides the text: no run sets a font and there
+    /// are no phonetic guides
+    pub fn is_plain(&self) -> bool {
+        self.phonetic.is_empty() && self.runs.iter().all(|run| run.font.is_none())
+    }
+
+    /// The formatting to keep on a [`super::CellValue::Text`]: `None` when the
+    /// text is plain
+    pub fn into_formatting(self) -> Option<Box<RichText>> {
+        (!self.is_plain()).then(|| Box::new(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rich_text_runs() {
+        let bold = Font {
+            bold: true,
+            ..Default::default()
+        };
+        let mut rich = RichText::default();
+        rich.push("Total ", None);
+        rich.push("", Some(&bold));
+        rich.push("due", Some(&bold));
+        rich.push(" now", Some(&bold));
+        rich.push("\nPaid", None);
+        assert_eq!(rich.runs.len(), 3);
+        assert_eq!(rich.runs[1].text, "due now");
+        assert_eq!(rich.text(), "Total due now\nPaid");
+        assert!(rich.into_formatting().is_some());
+
+        let mut plain = RichText::default();
+        plain.push("Total", None);
+        assert!(plain.into_formatting().is_none());
+    }
+}
@@ -0,0 +1,116 @@
+//! Cell style table
+//!
+//! Formatting is stored once per distinct style and shared by index: each
+//! [`super::Cell`] carries a `style` index into [`super::Workbook::styles`]. XLSX
+//! builds the table from the `cellXfs` records of `styles.xml`, resolving their
+//! font, fill and border references; ODS from its default, common and automatic
+//! `table-cell` styles with parent styles applied. Index 0 is the default style.
+//!
+//! Colors are upper-case `RRGGBB` strings; automatic colors are `None`.
+
+/// Font of a cell's text
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Font {
+    pub name: Option<String>,
+    /// Size in points
+    pub size: Option<f64>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strikethrough: bool,
+    pub color: Option<String>,
+}
+
+/// Background of a cell
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Fill {
+    /// Background color; `None` when the cell has no fill
+    pub color: Option<String>,
+}
+
+/// One edge of a cell border
+#[derive(Debug, Clone, PartialEq)]
+pub struct BorderLine {
+    /// Line style in XLSX terms: `hair`, `thin`, `medium`, `thick`, `dashed`,
+    /// `dotted`, `double`, ...
+    pub style: String,
+    pub color: Option<String>,
+}
+
+/// Cell borders; absent edges are `None`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Border {
+    pub left: Option<BorderLine>,
+    pub right: Option<BorderLine>,
+    pub top: Option<BorderLine>,
+    pub bottom: Option<BorderLine>,
+}
+
+/// Placement of a cell's content
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Alignment {
+    /// `left`, `center`, `right`, `justify`, ...; `None` for the general alignment
+    pub horizontal: Option<String>,
+    /// `top`, `center` or `bottom`; `None` for the default (bottom)
+    pub vertical: Option<String>,
+    pub wrap_text: bool,
+    pub shrink_to_fit: bool,
+    /// Indentation level
+    pub indent: u32,
+    /// Text rotation in degrees counter-clockwise
+    pub rotation: i32,
+}
+
+/// Sheet protection flags of a cell; they only take effect on protected sheets
+#[derive(Debug, Clone, PartialEq)]
+pub struct Protection {
+    /// Whether the cell can't be edited (the default)
+    pub locked: bool,
+    /// Whether the cell's formula is hidden
+    pub hidden: bool,
+}
+
+impl Default for Protection {
+    fn default() -> Self {
+        Self {
+            locked: true,
+            hidden: false,
+        }
+    }
+}
+
+/// Formatting shared by the cells that use one style
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CellStyle {
+    pub font: Font,
+    pub fill: Fill,
+    pub border: Border,
+    pub alignment: Alignment,
+    pub protection: Protection,
+}
+
+/// Normalise `#rrggbb`, `rrggbb` or ARGB `aarrggbb` to `RRGGBB`
+pub fn parse_hex_color(value: &str) -> Option<String> {
+    let hex = value.trim().trim_start_matches('#');
+    let hex = match hex.len() {
+        6 => hex,
+        8 => &hex[2..],
+        _ => return None,
+    };
+    hex.chars()
+        .all(|c| c.is_ascii_hexdigit())
+        .then(|| hex.to_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#ff0000").as_deref(), Some("FF0000"));
+        assert_eq!(parse_hex_color("FF00B050").as_deref(), Some("00B050"));
+        assert_eq!(parse_hex_color("transparent"), None);
+        assert_eq!(parse_hex_color("#fff"), None);
+    }
+}
@@ -0,0 +1,432 @@
+//! Excel tables and ODS database ranges
+//!
+//! A table is a named block of cells on one sheet: an optional header row,
+//! the data rows and an optional totals row. XLSX keeps each table in its own
+//! part (`xl/tables/tableN.xml`) linked from the owning worksheet's
+//! relationships; ODS lists them as `table:database-range` elements at the end
+//! of `content.xml`, with the column names in the header cells.
+//!
+//! Formulas address tables with structured references such as `Sales[Amount]`,
+//! `Sales[@Qty]` or `Sales[[#Headers],[Region]:[Amount]]`;
+//! [`resolve_structured_references`] rewrites them into A1 references so that
+//! reference-based analysis can follow them.
+
+use super::parser_utils::quote_sheet_name;
+use crate::violation::CellReference;
+use std::borrow::Cow;
+
+/// A table (XLSX) or database range (ODS)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    /// Name used in formulas (the XLSX `displayName`)
+    pub name: String,
+    /// Owning sheet; empty when the file doesn't say
+    pub sheet: String,
+    /// Whole table including header and totals rows:
+    /// (start_row, start_col, end_row, end_col)
+    pub range: (u32, u32, u32, u32),
+    /// Column names, left to right; empty when the table has no header row to
+    /// take them from
+    pub columns: Vec<String>,
+    pub header_rows: u32,
+    pub totals_rows: u32,
+    /// Table style name, e.g. `TableStyleMedium2`
+    pub style: Option<String>,
+}
+
+/// Row sections a structured reference can select
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    All,
+    Data,
+    Headers,
+    Totals,
+    ThisRow,
+}
+
+impl Table {
+    /// Whether a cell lies within the table
+    pub fn contains(&self, sheet: &str, row: u32, col: u32) -> bool {
+        let (r1, c1, r2, c2) = self.range;
+        self.sheet == sheet && (r1..=r2).contains(&row) && (c1..=c2).contains(&col)
+    }
+
+    /// First and last data row, or `None` when the table has no data rows
+    pub fn data_rows(&self) -> Option<(u32, u32)> {
+        let (r1, _, r2, _) = self.range;
+        let first = r1 + self.header_rows;
+        let last = (r2 + 1).checked_sub(self.totals_rows + 1)?;
+        (first <= last).then_some((first, last))
+    }
+
+    /// Sheet column of a table column, matched case-insensitively like Excel
+    pub fn column_index(&self, name: &str) -> Option<u32> {
+        let name = name.trim();
+        self.columns
+            .iter()
+            .position(|column| column.to_lowercase() == name.to_lowercase())
+            .map(|index| self.range.1 + index as u32)
+    }
+
+    /// Cells addressed by the part of a structured reference between the
+    /// table name's brackets (`Amount`, `@Qty`, `[#Totals],[Amount]`, or empty
+    /// for the data rows), for a formula on `row`. `None` when the reference
+    /// doesn't resolve: an unknown column, a missing header or totals row, or
+    /// `@` outside the data rows.
+    pub fn resolve(&self, specifier: &str, row: u32) -> Option<(u32, u32, u32, u32)> {
+        let mut sections = Vec::new();
+        let mut columns = Vec::new();
+        let specifier = specifier.trim();
+        match specifier.strip_prefix('@') {
+            Some(rest) => {
+                sections.push(Section::ThisRow);
+                parse_items(rest.trim(), &mut sections, &mut columns)?;
+            }
+            None => parse_items(specifier, &mut sections, &mut columns)?,
+        }
+        if sections.is_empty() {
+            sections.push(Section::Data);
+        }
+
+        let (r1, c1, r2, c2) = self.range;
+        let mut rows: Option<(u32, u32)> = None;
+        for section in sections {
+            let (first, last) = match section {
+                Section::All => (r1, r2),
+                Section::Data => self.data_rows()?,
+                Section::Headers if self.header_rows > 0 => (r1, r1 + self.header_rows - 1),
+                Section::Totals if self.totals_rows > 0 => (r2 + 1 - self.totals_rows, r2),
+                Section::ThisRow => {
+                    let (first, last) = self.data_rows()?;
+                    if !(first..=last).contains(&row) {
+                        return None;
+                    }
+                    (row, row)
+                }
+                Section::Headers | Section::Totals => return None,
+            };
+            rows = Some(match rows {
+                Some((lo, hi)) => (lo.min(first), hi.max(last)),
+                None => (first, last),
+            });
+        }
+        let (first_row, last_row) = rows?;
+
+        let (mut first_col, mut last_col) = (c1, c2);
+        if !columns.is_empty() {
+            let mut indices = Vec::new();
+            for (start, end) in &columns {
+                indices.push(self.column_index(start)?);
+                indices.push(self.column_index(end)?);
+            }
+            first_col = *indices.iter().min()?;
+            last_col = *indices.iter().max()?;
+        }
+
+        Some((first_row, first_col, last_row, last_col))
+    }
+
+    /// A1 reference to a range on the table's sheet, e.g. `'Q1 Sales'!B2:B10`
+    pub fn a1_reference(&self, range: (u32, u32, u32, u32)) -> String {
+        format_range(&self.sheet, range)
+    }
+}
+
+/// Format a range as an A1 reference, prefixed with the sheet name (quoted
+/// when needed) unless `sheet` is empty
+pub fn format_range(sheet: &str, (r1, c1, r2, c2): (u32, u32, u32, u32)) -> String {
+    let mut reference = String::new();
+    if !sheet.is_empty() {
+        reference.push_str(&quote_sheet_name(sheet));
+        reference.push('!');
+    }
+    reference.push_str(&CellReference::new(r1, c1).to_string());
+    if (r1, c1) != (r2, c2) {
+        reference.push(':');
+        reference.push_str(&CellReference::new(r2, c2).to_string());
+    }
+    reference
+}
+
+/// Parse the items of a structured reference: `Amount`, `#Data`, or a list of
+/// bracketed items separated by `,` (`;` in ODS) with `:` joining column
+/// ranges. Returns `None` on malformed input.
+fn parse_items(
+    specifier: &str,
+    sections: &mut Vec<Section>,
+    columns: &mut Vec<(String, String)>,
+) -> Option<()> {
+    if specifier.is_empty() {
+        return Some(());
+    }
+    if !specifier.starts_with('[') {
+        return add_item(specifier, sections, columns, false);
+    }
+
+    let mut rest = specifier;
+    let mut range_end = false;
+    loop {
+        let close = matching_bracket(rest, 0)?;
+        add_item(&rest[1..close], sections, columns, range_end)?;
+        rest = rest[close + 1..].trim_start();
+        range_end = match rest.chars().next() {
+            None => return Some(()),
+            Some(',' | ';') => false,
+            Some(':') => true,
+            Some(_) => return None,
+        };
+        rest = rest[1..].trim_start();
+    }
+}
+
+fn add_item(
+    item: &str,
+    sections: &mut Vec<Section>,
+    columns: &mut Vec<(String, String)>,
+    range_end: bool,
+) -> Option<()> {
+    let item = item.trim();
+    if item.starts_with('#') {
+        let section = match item.to_ascii_lowercase().as_str() {
+            "#all" => Section::All,
+            "#data" => Section::Data,
+            "#headers" => Section::Headers,
+            "#totals" => Section::Totals,
+            "#this row" => Section::ThisRow,
+            _ => return None,
+        };
+        sections.push(section);
+    } else if range_end {
+        columns.last_mut()?.1 = unescape(item);
+    } else {
+        columns.push((unescape(item), unescape(item)));
+    }
+    Some(())
+}
+
+/// Drop the `'` escapes structured references put before `[`, `]`, `#` and `'`
+fn unescape(item: &str) -> String {
+    let mut unescaped = String::with_capacity(item.len());
+    let mut chars = item.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => unescaped.extend(chars.next()),
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// Byte index of the `]` closing the `[` at `open`, skipping `'` escapes
+fn matching_bracket(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut escaped = false;
+    for (i, c) in text[open..].char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\'' => escaped = true,
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '.' || c == '\\'
+}
+
+/// Rewrite the structured references in a formula on `sheet` at (`row`, `col`)
+/// into A1 references: `Sales[Amount]` becomes `Data!C2:C9`, `Sales[@Qty]`
+/// the cell in the formula's row, a bare `Sales` its data rows, and a
+/// tableless `[@Qty]` inside a table refers to that table. References that
+/// don't resolve become `#REF!`, as they would in Excel.
+pub fn resolve_structured_references<'f>(
+    formula: &'f str,
+    tables: &[Table],
+    sheet: &str,
+    row: u32,
+    col: u32,
+) -> Cow<'f, str> {
+    if tables.is_empty() {
+        return Cow::Borrowed(formula);
+    }
+
+    let mut resolved = String::new();
+    let mut copied = 0;
+    let mut previous: Option<char> = None;
+    let mut chars = formula.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let after_name = previous.is_some_and(|p| is_name_char(p) || matches!(p, '!' | ']'));
+        let mut replacement = None;
+
+        if c == '"' || c == '\'' {
+            // String literals and quoted sheet names are copied unchanged
+            // (doubled quotes just close and reopen the span)
+            while chars.next_if(|&(_, next)| next != c).is_some() {}
+            chars.next();
+        } else if c == '[' && !after_name {
+            if let Some(table) = tables.iter().find(|t| t.contains(sheet, row, col))
+                && let Some(close) = matching_bracket(formula, i)
+            {
+                let specifier = &formula[i + 1..close];
+                // `[1]Sheet!A1` is an external workbook and `[.A1]` an ODS address
+                let is_structured = !specifier.is_empty()
+                    && !specifier.chars().all(|c| c.is_ascii_digit())
+                    && !specifier.starts_with(['.', '$', '\'']);
+                if is_structured {
+                    replacement = Some((close + 1, table, specifier));
+                }
+            }
+        } else if is_name_char(c) && !c.is_ascii_digit() && !after_name {
+            let end = formula[i..]
+                .find(|c: char| !is_name_char(c))
+                .map_or(formula.len(), |n| i + n);
+            let name = &formula[i..end];
+            if let Some(table) = tables.iter().find(|t| t.name.eq_ignore_ascii_case(name)) {
+                match formula[end..].chars().next() {
+                    Some('[') => {
+                        if let Some(close) = matching_bracket(formula, end) {
+                            replacement = Some((close + 1, table, &formula[end + 1..close]));
+                        }
+                    }
+                    Some('(' | '!') => {}
+                    _ => replacement = Some((end, table, "")),
+                }
+            }
+            if replacement.is_none() {
+                while chars.next_if(|&(n, _)| n < end).is_some() {}
+            }
+        }
+
+        if let Some((end, table, specifier)) = replacement {
+            resolved.push_str(&formula[copied..i]);
+            match table.resolve(specifier, row) {
+                Some(range) => resolved.push_str(&table.a1_reference(range)),
+                None => resolved.push_str("#REF!"),
+            }
+            copied = end;
+            while chars.next_if(|&(n, _)| n < end).is_some() {}
+        }
+        previous = formula[..chars.peek().map_or(formula.len(), |&(n, _)| n)]
+            .chars()
+            .next_back();
+    }
+
+    if copied == 0 {
+        return Cow::Borrowed(formula);
+    }
+    resolved.push_str(&formula[copied..]);
+    Cow::Owned(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sales() -> Table {
+        // Header on row 2, data on rows 3-6, totals on row 7, columns B:D
+        Table {
+            name: "Sales".to_string(),
+            sheet: "Data".to_string(),
+            range: (1, 1, 6, 3),
+            columns: vec![
+                "Region".to_string(),
+                "Qty".to_string(),
+                "Amount".to_string(),
+            ],
+            header_rows: 1,
+            totals_rows: 1,
+            style: Some("TableStyleMedium2".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_table_resolve() {
+        let table = sales();
+        assert_eq!(table.data_rows(), Some((2, 5)));
+        assert_eq!(table.resolve("", 0), Some((2, 1, 5, 3)));
+        assert_eq!(table.resolve("Amount", 0), Some((2, 3, 5, 3)));
+        assert_eq!(table.resolve("amount", 0), Some((2, 3, 5, 3)));
+        assert_eq!(table.resolve("@Qty", 3), Some((3, 2, 3, 2)));
+        assert_eq!(table.resolve("@[Qty]", 3), Some((3, 2, 3, 2)));
+        assert_eq!(table.resolve("[#This Row],[Qty]", 4), Some((4, 2, 4, 2)));
+        assert_eq!(table.resolve("@", 4), Some((4, 1, 4, 3)));
+        assert_eq!(table.resolve("#All", 0), Some((1, 1, 6, 3)));
+        assert_eq!(
+            table.resolve("[#Headers],[Qty]:[Amount]", 0),
+            Some((1, 2, 1, 3))
+        );
+        assert_eq!(
+            table.resolve("[#Headers],[#Data],[Region]", 0),
+            Some((1, 1, 5, 1))
+        );
+        assert_eq!(table.resolve("[#Totals];[Amount]", 0), Some((6, 3, 6, 3)));
+
+        assert_eq!(table.resolve("Price", 0), None);
+        assert_eq!(table.resolve("@Qty", 6), None);
+        let no_totals = Table {
+            totals_rows: 0,
+            ..sales()
+        };
+        assert_eq!(no_totals.resolve("#Totals", 0), None);
+    }
+
+    #[test]
+    fn test_table_escaped_column_names() {
+        let table = Table {
+            columns: vec!["Unit [EUR]".to_string(), "#Items".to_string()],
+            range: (0, 0, 3, 1),
+            header_rows: 1,
+            ..Default::default()
+        };
+        assert_eq!(table.resolve("[Unit '[EUR']]", 0), Some((1, 0, 3, 0)));
+        assert_eq!(table.resolve("'#Items", 0), Some((1, 1, 3, 1)));
+    }
+
+    #[test]
+    fn test_resolve_structured_references() {
+        let tables = vec![
+            sales(),
+            Table {
+                name: "Rates".to_string(),
+                sheet: "Q1 Rates".to_string(),
+                range: (0, 0, 4, 1),
+                columns: vec!["Region".to_string(), "Rate".to_string()],
+                header_rows: 1,
+                ..Default::default()
+            },
+        ];
+        let resolve = |formula| resolve_structured_references(formula, &tables, "Data", 3, 4);
+
+        assert_eq!(resolve("=SUM(Sales[Amount])"), "=SUM(Data!D3:D6)");
+        assert_eq!(resolve("=Sales[@Qty]*2"), "=Data!C4*2");
+        assert_eq!(resolve("=Sales[[#This Row],[Qty]]"), "=Data!C4");
+        assert_eq!(resolve("=ROWS(sales)"), "=ROWS(Data!B3:D6)");
+        assert_eq!(resolve("=MAX(Rates[Rate])"), "=MAX('Q1 Rates'!B2:B5)");
+        assert_eq!(resolve("=Sales[Price]"), "=#REF!");
+        // Names that merely contain a table name, strings and sheet names stay
+        assert_eq!(resolve("=SalesTotal+1"), "=SalesTotal+1");
+        assert_eq!(resolve("=\"Sales[Amount]\""), "=\"Sales[Amount]\"");
+        assert_eq!(resolve("=Sales!A1"), "=Sales!A1");
+        assert!(matches!(resolve("=A1+B2"), Cow::Borrowed(_)));
+
+        // Tableless references only resolve in a cell inside the table
+        let inside = resolve_structured_references("=[@Qty]*[@Amount]", &tables, "Data", 3, 3);
+        assert_eq!(inside, "=Data!C4*Data!D4");
+        assert_eq!(resolve("=[@Qty]"), "=[@Qty]");
+        assert_eq!(
+            resolve_structured_references("=[1]Sheet1!A1", &tables, "Data", 3, 3),
+            "=[1]Sheet1!A1"
+        );
+    }
+}
@@ -0,0 +1,430 @@
+//! Data validation rules
+//!
+//! A validation restricts what may be typed into a set of ranges: a whole or
+//! decimal number, a date, a time, a text length, an entry from a list, or a
+//! custom formula. XLSX keeps them in `<dataValidations>` on each worksheet; ODS
+//! defines them once in `table:content-validations` and points cells at them by
+//! name. Both are read into the same [`DataValidation`] model on [`super::Sheet`].
+
+use super::CellValue;
+
+/// Kind of value a validation accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationKind {
+    /// No restriction (input message only)
+    #[default]
+    Any,
+    WholeNumber,
+    Decimal,
+    /// One of the entries of a literal list or a range
+    List,
+    Date,
+    Time,
+    TextLength,
+    /// A formula that must evaluate to TRUE
+    Custom,
+}
+
+impl ValidationKind {
+    /// Parse the XLSX `type` attribute
+    pub fn from_xlsx(value: &str) -> Self {
+        match value {
+            "whole" => Self::WholeNumber,
+            "decimal" => Self::Decimal,
+            "list" => Self::List,
+            "date" => Self::Date,
+            "time" => Self::Time,
+            "textLength" => Self::TextLength,
+            "custom" => Self::Custom,
+            _ => Self::Any,
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Self::Any => "any value",
+            Self::WholeNumber => "whole number",
+            Self::Decimal => "decimal",
+            Self::List => "list entry",
+            Self::Date => "date",
+            Self::Time => "time",
+            Self::TextLength => "text length",
+            Self::Custom => "custom formula",
+        }
+    }
+}
+
+/// Comparison applied to the value (or its text length) and the validation formulas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ValidationOperator {
+    #[default]
+    Between,
+    NotBetween,
+    Equal,
+    NotEqual,
+    GreaterThan,
+    LessThan,
+    GreaterThanOrEqual,
+    LessThanOrEqual,
+}
+
+impl ValidationOperator {
+    /// Parse the XLSX `operator` attribute
+    pub fn from_xlsx(value: &str) -> Self {
+        match value {
+            "notBetween" => Self::NotBetween,
+            "equal" => Self::Equal,
+            "notEqual" => Self::NotEqual,
+            "greaterThan" => Self::GreaterThan,
+            "lessThan" => Self::LessThan,
+            "greaterThanOrEqual" => Self::GreaterThanOrEqual,
+            "lessThanOrEqual" => Self::LessThanOrEqual,
+            _ => Self::Between,
+        }
+    }
+
+    /// Parse a comparison symbol as used in ODS conditions (`<=`, `!=`, ...)
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        Some(match symbol {
+            "=" => Self::Equal,
+            "!=" | "<>" => Self::NotEqual,
+            ">" => Self::GreaterThan,
+            "<" => Self::LessThan,
+            ">=" => Self::GreaterThanOrEqual,
+            "<=" => Self::LessThanOrEqual,
+            _ => return None,
+        })
+    }
+
+    /// Whether `value` passes against the bounds; `second` is only used by the range operators
+    pub fn holds(self, value: f64, first: f64, second: f64) -> bool {
+        match self {
+            Self::Between => value >= first && value <= second,
+            Self::NotBetween => value < first || value > second,
+            Self::Equal => value == first,
+            Self::NotEqual => value != first,
+            Self::GreaterThan => value > first,
+            Self::LessThan => value < first,
+            Self::GreaterThanOrEqual => value >= first,
+            Self::LessThanOrEqual => value <= first,
+        }
+    }
+
+    fn is_range(self) -> bool {
+        matches!(self, Self::Between | Self::NotBetween)
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Self::Between => "between",
+            Self::NotBetween => "not between",
+            Self::Equal => "=",
+            Self::NotEqual => "<>",
+            Self::GreaterThan => ">",
+            Self::LessThan => "<",
+            Self::GreaterThanOrEqual => ">=",
+            Self::LessThanOrEqual => "<=",
+        }
+    }
+}
+
+/// What the application does when an invalid value is entered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationErrorStyle {
+    /// Reject the value
+    #[default]
+    Stop,
+    /// Ask for confirmation
+    Warning,
+    /// Accept the value with a notice
+    Information,
+}
+
+impl ValidationErrorStyle {
+    /// Parse the XLSX `errorStyle` or ODS `table:message-type` attribute
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "warning" => Self::Warning,
+            "information" => Self::Information,
+            _ => Self::Stop,
+        }
+    }
+}
+
+/// A data validation rule and the ranges it applies to
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DataValidation {
+    /// Ranges the rule applies to: (start_row, start_col, end_row, end_col)
+    pub ranges: Vec<(u32, u32, u32, u32)>,
+    pub kind: ValidationKind,
+    pub operator: ValidationOperator,
+    /// List source for `List` (a quoted comma-separated literal or a reference),
+    /// the formula for `Custom`, otherwise the first bound.
+    /// References use Excel syntax (`Sheet2!$A$1:$A$5`) for both formats.
+    pub formula1: Option<String>,
+    /// Second bound of `Between`/`NotBetween`
+    pub formula2: Option<String>,
+    pub allow_blank: bool,
+    /// Whether invalid input raises the error alert at all
+    pub show_error: bool,
+    pub error_style: ValidationErrorStyle,
+}
+
+impl DataValidation {
+    /// Whether the rule covers the cell
+    pub fn applies_to(&self, row: u32, col: u32) -> bool {
+        self.ranges
+            .iter()
+            .any(|&(r1, c1, r2, c2)| (r1..=r2).contains(&row) && (c1..=c2).contains(&col))
+    }
+
+    /// Entries of a literal list (`"Yes,No"`); `None` when the list comes from a reference
+    pub fn list_items(&self) -> Option<Vec<String>> {
+        if self.kind != ValidationKind::List {
+            return None;
+        }
+        let formula = self.formula1.as_deref()?.trim();
+        let inner = formula.strip_prefix('"')?.strip_suffix('"')?;
+        Some(
+            inner
+                .replace("\"\"", "\"")
+                .split(',')
+                .map(|item| item.trim().to_string())
+                .collect(),
+        )
+    }
+
+    /// Reference or name a `List` rule takes its entries from
+    pub fn list_source(&self) -> Option<&str> {
+        if self.kind != ValidationKind::List || self.list_items().is_some() {
+            return None;
+        }
+        self.formula1
+            .as_deref()
+            .map(|f| f.trim().trim_start_matches('='))
+    }
+
+    /// Whether `value` satisfies the rule, or `None` when that depends on
+    /// other cells or a formula (references as bounds, range lists, custom rules,
+    /// formula cells whose result is not stored).
+    /// Blank cells always pass: validation applies to what is entered.
+    pub fn check(&self, value: &CellValue) -> Option<bool> {
+        match value {
+            CellValue::Empty => return Some(true),
+            CellValue::Formula { .. } => return None,
+            _ => {}
+        }
+        match self.kind {
+            ValidationKind::Any => Some(true),
+            ValidationKind::Custom => None,
+            ValidationKind::List => Some(self.check_in_list(value, &self.list_items()?)),
+            ValidationKind::TextLength => {
+                let length = match value {
+                    CellValue::Text(text, _) => text.chars().count(),
+                    CellValue::Boolean(b) => {
+                        if *b {
+                            4
+                        } else {
+                            5
+                        }
+                    }
+                    // The length of a number depends on how it is displayed
+                    _ => return None,
+                };
+                self.compare(length as f64)
+            }
+            ValidationKind::WholeNumber
+            | ValidationKind::Decimal
+            | ValidationKind::Date
+            | ValidationKind::Time => match value {
+                CellValue::Number(n) => {
+                    if self.kind == ValidationKind::WholeNumber && n.fract() != 0.0 {
+                        return Some(false);
+                    }
+                    self.compare(*n)
+                }
+                _ => Some(false),
+            },
+        }
+    }
+
+    /// Whether `value` is one of `items` (compared case-insensitively, numbers by value)
+    pub fn check_in_list(&self, value: &CellValue, items: &[String]) -> bool {
+        match value {
+            CellValue::Empty => true,
+            CellValue::Number(n) => items
+                .iter()
+                .any(|item| item.parse::<f64>().is_ok_and(|v| v == *n)),
+            CellValue::Text(text, _) => items.iter().any(|item| item.eq_ignore_ascii_case(text)),
+            CellValue::Boolean(b) => {
+                let text = if *b { "TRUE" } else { "FALSE" };
+                items.iter().any(|item| item.eq_ignore_ascii_case(text))
+            }
+            CellValue::Formula { .. } => true,
+        }
+    }
+
+    /// Short description of the rule, e.g. `whole number between 1 and 10`
+    pub fn describe(&self) -> String {
+        let f1 = self.formula1.as_deref().unwrap_or("");
+        match self.kind {
+            ValidationKind::Any => self.kind.describe().to_string(),
+            ValidationKind::List => format!("list {}", f1),
+            ValidationKind::Custom => format!("custom formula {}", f1),
+            _ if self.operator.is_range() => format!(
+                "{} {} {} and {}",
+                self.kind.describe(),
+                self.operator.describe(),
+                f1,
+                self.formula2.as_deref().unwrap_or("")
+            ),
+            _ => format!(
+                "{} {} {}",
+                self.kind.describe(),
+                self.operator.describe(),
+                f1
+            ),
+        }
+    }
+
+    fn compare(&self, value: f64) -> Option<bool> {
+        let first = parse_bound(self.formula1.as_deref()?)?;
+        let second = if self.operator.is_range() {
+            parse_bound(self.formula2.as_deref()?)?
+        } else {
+            first
+        };
+        Some(self.operator.holds(value, first, second))
+    }
+}
+
+/// A constant bound; references and expressions cannot be evaluated here
+fn parse_bound(formula: &str) -> Option<f64> {
+    formula.trim().trim_start_matches('=').parse::<f64>().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(
+        kind: ValidationKind,
+        operator: ValidationOperator,
+        f1: &str,
+        f2: &str,
+    ) -> DataValidation {
+        DataValidation {
+            ranges: vec![(0, 0, 9, 0)],
+            kind,
+            operator,
+            formula1: Some(f1.to_string()),
+            formula2: (!f2.is_empty()).then(|| f2.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_numeric_checks() {
+        let whole = rule(
+            ValidationKind::WholeNumber,
+            ValidationOperator::Between,
+            "1",
+            "10",
+        );
+        assert_eq!(whole.check(&CellValue::Number(5.0)), Some(true));
+        assert_eq!(whole.check(&CellValue::Number(5.5)), Some(false));
+        assert_eq!(whole.check(&CellValue::Number(11.0)), Some(false));
+        assert_eq!(whole.check(&CellValue::Text("5".into(), None)), Some(false));
+        assert_eq!(whole.check(&CellValue::Empty), Some(true));
+        assert_eq!(whole.check(&CellValue::formula("A2+1")), None);
+        assert_eq!(whole.describe(), "whole number between 1 and 10");
+
+        let decimal = rule(
+            ValidationKind::Decimal,
+            ValidationOperator::GreaterThanOrEqual,
+            "0.5",
+            "",
+        );
+        assert_eq!(decimal.check(&CellValue::Number(0.5)), Some(true));
+        assert_eq!(decimal.check(&CellValue::Number(0.25)), Some(false));
+
+        // Bounds that reference cells cannot be checked without the workbook
+        let by_ref = rule(
+            ValidationKind::Decimal,
+            ValidationOperator::LessThan,
+            "$B$1",
+            "",
+        );
+        assert_eq!(by_ref.check(&CellValue::Number(1.0)), None);
+    }
+
+    #[test]
+    fn test_text_length_check() {
+        let length = rule(
+            ValidationKind::TextLength,
+            ValidationOperator::LessThanOrEqual,
+            "3",
+            "",
+        );
+        assert_eq!(
+            length.check(&CellValue::Text("abc".into(), None)),
+            Some(true)
+        );
+        assert_eq!(
+            length.check(&CellValue::Text("abcd".into(), None)),
+            Some(false)
+        );
+        assert_eq!(length.check(&CellValue::Number(12345.0)), None);
+    }
+
+    #[test]
+    fn test_list_checks() {
+        let list = rule(
+            ValidationKind::List,
+            ValidationOperator::Between,
+            "\"Excel,Calc, GSheet\"",
+            "",
+        );
+        assert_eq!(
+            list.list_items(),
+            Some(vec!["Excel".into(), "Calc".into(), "GSheet".into()])
+        );
+        assert_eq!(list.list_source(), None);
+        assert_eq!(
+            list.check(&CellValue::Text("calc".into(), None)),
+            Some(true)
+        );
+        assert_eq!(
+            list.check(&CellValue::Text("Numbers".into(), None)),
+            Some(false)
+        );
+
+        let numbers = rule(
+            ValidationKind::List,
+            ValidationOperator::Between,
+            "\"1,2,3\"",
+            "",
+        );
+        assert_eq!(numbers.check(&CellValue::Number(2.0)), Some(true));
+        assert_eq!(numbers.check(&CellValue::Number(4.0)), Some(false));
+
+        let ranged = rule(
+            ValidationKind::List,
+            ValidationOperator::Between,
+            "Lists!$A$1:$A$5",
+            "",
+        );
+        assert_eq!(ranged.list_items(), None);
+        assert_eq!(ranged.list_source(), Some("Lists!$A$1:$A$5"));
+        assert_eq!(ranged.check(&CellValue::Text("x".into(), None)), None);
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut validation = rule(ValidationKind::Any, ValidationOperator::Between, "", "");
+        validation.ranges = vec![(0, 0, 9, 0), (2, 2, 2, 2)];
+        assert!(validation.applies_to(5, 0));
+        assert!(validation.applies_to(2, 2));
+        assert!(!validation.applies_to(10, 0));
+    }
+}
@@ -0,0 +1,687 @@
+//! VBA project reader
+//!
+//! `vbaProject.bin` is a compound file (MS-CFB). Its `PROJECT` stream lists the
+//! modules by kind, the `VBA/dir` stream (MS-OVBA compressed) gives each
+//! module's stream name and where its compressed source starts, and each
+//! module stream holds the compiled p-code followed by that source.
+
+use super::macros::{MacroModule, ModuleKind};
+use anyhow::{Result, bail};
+
+const CFB_SIGNATURE: [u8; 8] = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+/// Sector ids at and above this mark the end of a chain or special sectors
+const MAX_REGULAR_SECTOR: u32 = 0xFFFF_FFFA;
+const NO_STREAM: u32 = 0xFFFF_FFFF;
+
+/// Read the modules of a VBA project
+pub fn read_vba_project(data: &[u8]) -> Result<Vec<MacroModule>> {
+    let cfb = CompoundFile::open(data)?;
+    let Some(vba) = cfb.find(&["VBA"]) else {
+        bail!("VBA project has no VBA storage");
+    };
+    let Some(dir) = cfb.child(vba, "dir") else {
+        bail!("VBA project has no dir stream");
+    };
+    let dir = decompress(&cfb.stream(dir)?)?;
+    let project = parse_dir_stream(&dir)?;
+
+    // The PROJECT stream tells document, class and form modules apart
+    let kinds = match cfb.find(&["PROJECT"]) {
+        Some(entry) => parse_project_stream(&decode(&cfb.stream(entry)?, project.code_page)),
+        None => Vec::new(),
+    };
+
+    let mut modules = Vec::new();
+    for module in project.modules {
+        let Some(entry) = cfb.child(vba, &module.stream) else {
+            continue;
+        };
+        let data = cfb.stream(entry)?;
+        let source = match data.get(module.offset as usize..) {
+            Some(compressed) if !compressed.is_empty() => {
+                decode(&decompress(compressed)?, project.code_page)
+            }
+            _ => String::new(),
+        };
+        let kind = kinds
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&module.name))
+            .map(|(_, kind)| *kind)
+            .unwrap_or(if module.procedural {
+                ModuleKind::Standard
+            } else {
+                ModuleKind::Class
+            });
+        modules.push(MacroModule {
+            library: project.name.clone(),
+            name: module.name,
+            kind,
+            source,
+        });
+    }
+    Ok(modules)
+}
+
+/// A directory entry of a compound file
+struct DirEntry {
+    name: String,
+    left: u32,
+    right: u32,
+    child: u32,
+    start: u32,
+    size: u64,
+}
+
+/// Read-only view of a compound file held in memory
+struct CompoundFile<'a> {
+    data: &'a [u8],
+    sector_size: usize,
+    mini_sector_size: usize,
+    mini_cutoff: u64,
+    fat: Vec<u32>,
+    mini_fat: Vec<u32>,
+    entries: Vec<DirEntry>,
+    mini_stream: Vec<u8>,
+}
+
+impl<'a> CompoundFile<'a> {
+    fn open(data: &'a [u8]) -> Result<Self> {
+        if data.len() < 512 || data[..8] != CFB_SIGNATURE {
+            bail!("Not a compound file");
+        }
+        let sector_shift = u16_at(data, 0x1E) as u32;
+        let mini_sector_shift = u16_at(data, 0x20) as u32;
+        if !(7..=16).contains(&sector_shift) || mini_sector_shift >= sector_shift {
+            bail!("Invalid compound file sector size");
+        }
+        let mut cfb = CompoundFile {
+            data,
+            sector_size: 1 << sector_shift,
+            mini_sector_size: 1 << mini_sector_shift,
+            mini_cutoff: u32_at(data, 0x38) as u64,
+            fat: Vec::new(),
+            mini_fat: Vec::new(),
+            entries: Vec::new(),
+            mini_stream: Vec::new(),
+        };
+
+        // The first 109 FAT sectors are listed in the header, the rest in a
+        // chain of DIFAT sectors whose last entry points at the next one
+        let mut fat_sectors: Vec<u32> = (0..109).map(|i| u32_at(data, 0x4C + i * 4)).collect();
+        let mut difat = u32_at(data, 0x44);
+        let per_sector = cfb.sector_size / 4;
+        let mut seen = 0;
+        while difat < MAX_REGULAR_SECTOR && seen < data.len() / cfb.sector_size {
+            let sector = cfb.sector(difat)?;
+            fat_sectors.extend((0..per_sector - 1).map(|i| u32_at(sector, i * 4)));
+            difat = u32_at(sector, (per_sector - 1) * 4);
+            seen += 1;
+        }
+        for id in fat_sectors
+            .into_iter()
+            .filter(|&id| id < MAX_REGULAR_SECTOR)
+        {
+            let sector = cfb.sector(id)?;
+            cfb.fat
+                .extend((0..per_sector).map(|i| u32_at(sector, i * 4)));
+        }
+
+        let mini_fat = cfb.read_chain(u32_at(data, 0x3C), None)?;
+        cfb.mini_fat = mini_fat
+            .chunks_exact(4)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect();
+
+        let directory = cfb.read_chain(u32_at(data, 0x30), None)?;
+        for entry in directory.chunks_exact(128) {
+            let name_len = (u16_at(entry, 64) as usize).min(64);
+            let name: Vec<u16> = (0..name_len.saturating_sub(2) / 2)
+                .map(|i| u16_at(entry, i * 2))
+                .collect();
+            cfb.entries.push(DirEntry {
+                name: String::from_utf16_lossy(&name),
+                left: u32_at(entry, 68),
+                right: u32_at(entry, 72),
+                child: u32_at(entry, 76),
+                start: u32_at(entry, 116),
+                size: u32_at(entry, 120) as u64 | (u32_at(entry, 124) as u64) << 32,
+            });
+        }
+        let Some(root) = cfb.entries.first() else {
+            bail!("Compound file has no root entry");
+        };
+        // Streams under the cutoff size live in the root entry's mini stream
+        cfb.mini_stream = cfb.read_chain(root.start, Some(root.size))?;
+        Ok(cfb)
+    }
+
+    fn sector(&self, id: u32) -> Result<&'a [u8]> {
+        let start = (id as usize + 1) * self.sector_size;
+        match self.data.get(start..start + self.sector_size) {
+            Some(sector) => Ok(sector),
+            // The last sector may be cut short
+            None if start < self.data.len() => Ok(&self.data[start..]),
+            None => bail!("Compound file sector {} is out of range", id),
+        }
+    }
+
+    /// Concatenate the sectors of a FAT chain, cut to `size` when given
+    fn read_chain(&self, start: u32, size: Option<u64>) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        let mut id = start;
+        while id < MAX_REGULAR_SECTOR {
+            if data.len() > self.data.len() {
+                bail!("Compound file sector chain loops");
+            }
+            data.extend_from_slice(self.sector(id)?);
+            id = *self.fat.get(id as usize).unwrap_or(&NO_STREAM);
+        }
+        if let Some(size) = size {
+            data.truncate(size as usize);
+        }
+        Ok(data)
+    }
+
+    fn stream(&self, entry: usize) -> Result<Vec<u8>> {
+        let entry = &self.entries[entry];
+        if entry.size >= self.mini_cutoff {
+            return self.read_chain(entry.start, Some(entry.size));
+        }
+        let mut data = Vec::new();
+        let mut id = entry.start;
+        while id < MAX_REGULAR_SECTOR && (data.len() as u64) < entry.size {
+            let start = id as usize * self.mini_sector_size;
+            let Some(sector) = self.mini_stream.get(start..start + self.mini_sector_size) else {
+                bail!("Compound file mini sector {} is out of range", id);
+            };
+            data.extend_from_slice(sector);
+            id = *self.mini_fat.get(id as usize).unwrap_or(&NO_STREAM);
+        }
+        data.truncate(entry.size as usize);
+        Ok(data)
+    }
+
+    /// Entry named `name` (ignoring case) among the children of a storage
+    fn child(&self, storage: usize, name: &str) -> Option<usize> {
+        // Children form a binary tree through the left/right sibling links
+        let mut pending = vec![self.entries[storage].child];
+        let mut visited = 0;
+        while let Some(id) = pending.pop() {
+            let entry = self.entries.get(id as usize)?;
+            if entry.name.eq_ignore_ascii_case(name) {
+                return Some(id as usize);
+            }
+            visited += 1;
+            if visited > self.entries.len() {
+                return None;
+            }
+            pending.extend(
+                [entry.left, entry.right]
+                    .into_iter()
+                    .filter(|&id| id != NO_STREAM),
+            );
+        }
+        None
+    }
+
+    /// Entry at a path of storage and stream names below the root
+    fn find(&self, path: &[&str]) -> Option<usize> {
+        path.iter()
+            .try_fold(0, |storage, name| self.child(storage, name))
+    }
+}
+
+fn u16_at(data: &[u8], offset: usize) -> u16 {
+    data.get(offset..offset + 2)
+        .map_or(0, |bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+    data.get(offset..offset + 4).map_or(0, |bytes| {
+        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    })
+}
+
+/// Decompress an MS-OVBA compressed container
+pub fn decompress(container: &[u8]) -> Result<Vec<u8>> {
+    if container.first() != Some(&0x01) {
+        bail!("Invalid VBA compressed container signature");
+    }
+    let mut output = Vec::new();
+    let mut pos = 1;
+    while pos + 2 <= container.len() {
+        let header = u16_at(container, pos);
+        let chunk_end = (pos + (header & 0x0FFF) as usize + 3).min(container.len());
+        pos += 2;
+        let chunk_start = output.len();
+
+        if header & 0x8000 == 0 {
+            // Uncompressed chunks hold 4096 raw bytes
+            let end = (pos + 4096).min(container.len());
+            output.extend_from_slice(&container[pos..end]);
+            pos = end;
+            continue;
+        }
+
+        while pos < chunk_end {
+            let flags = container[pos];
+            pos += 1;
+            for bit in 0..8 {
+                if pos >= chunk_end {
+                    break;
+                }
+                if flags & (1 << bit) == 0 {
+                    output.push(container[pos]);
+                    pos += 1;
+                    continue;
+                }
+                // Copy token: the split between offset and length bits grows
+                // with the amount of the chunk already decompressed
+                let token = u16_at(container, pos) as usize;
+                pos += 2;
+                let decompressed = output.len() - chunk_start;
+                // A chunk decompresses to at most 4096 bytes, so the offset
+                // takes 4 to 12 bits
+                if decompressed > 4096 {
+                    bail!("Invalid VBA compressed chunk");
+                }
+                let bit_count = (usize::BITS - decompressed.saturating_sub(1).leading_zeros())
+                    .clamp(4, 12) as usize;
+                let length_mask = 0xFFFF >> bit_count;
+                let offset = (token >> (16 - bit_count)) + 1;
+                let length = (token & length_mask) + 3;
+                if offset > decompressed {
+                    bail!("Invalid VBA copy token");
+                }
+                for _ in 0..length {
+                    output.push(output[output.len() - offset]);
+                }
+            }
+        }
+        pos = chunk_end;
+    }
+    Ok(output)
+}
+
+/// A module as described by the `dir` stream
+struct DirModule {
+    name: String,
+    stream: String,
+    offset: u32,
+    procedural: bool,
+}
+
+struct DirProject {
+    name: String,
+    code_page: u16,
+    modules: Vec<DirModule>,
+}
+
+/// Read the project name, code page and modules of a decompressed `dir` stream
+fn parse_dir_stream(dir: &[u8]) -> Result<DirProject> {
+    let mut project = DirProject {
+        name: String::new(),
+        code_page: 1252,
+        modules: Vec::new(),
+    };
+    let mut module: Option<DirModule> = None;
+    let mut pos = 0;
+    while pos + 6 <= dir.len() {
+        let id = u16_at(dir, pos);
+        let mut size = u32_at(dir, pos + 2) as usize;
+        // PROJECTVERSION declares 4 bytes but holds 6
+        if id == 0x0009 {
+            size = 6;
+        }
+        let start = pos + 6;
+        let Some(data) = dir.get(start..start + size) else {
+            bail!("Truncated VBA dir record {:#06x}", id);
+        };
+        pos = start + size;
+
+        match id {
+            0x0003 => project.code_page = u16_at(data, 0),
+            0x0004 => project.name = decode(data, project.code_page),
+            0x0019 => {
+                module = Some(DirModule {
+                    name: decode(data, project.code_page),
+                    stream: String::new(),
+                    offset: 0,
+                    procedural: true,
+                });
+            }
+            0x0047 => {
+                if let Some(module) = module.as_mut() {
+                    module.name = decode_utf16(data);
+                }
+            }
+            0x001A => {
+                if let Some(module) = module.as_mut() {
+                    module.stream = decode(data, project.code_page);
+                }
+            }
+            0x0032 => {
+                if let Some(module) = module.as_mut() {
+                    module.stream = decode_utf16(data);
+                }
+            }
+            0x0031 => {
+                if let Some(module) = module.as_mut() {
+                    module.offset = u32_at(data, 0);
+                }
+            }
+            0x0022 => {
+                if let Some(module) = module.as_mut() {
+                    module.procedural = false;
+                }
+            }
+            // End of a module record
+            0x002B => project.modules.extend(module.take()),
+            _ => {}
+        }
+    }
+    Ok(project)
+}
+
+/// Module kinds from the `Module=`, `Class=`, `Document=` and `BaseClass=`
+/// lines of the `PROJECT` stream
+fn parse_project_stream(text: &str) -> Vec<(String, ModuleKind)> {
+    text.lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            let kind = match key.trim() {
+                "Module" => ModuleKind::Standard,
+                "Class" => ModuleKind::Class,
+                "Document" => ModuleKind::Document,
+                "BaseClass" => ModuleKind::Form,
+                _ => return None,
+            };
+            // Documents carry their type library version: `ThisWorkbook/&H00000000`
+            let name = value.split('/').next().unwrap_or(value).trim();
+            Some((name.to_string(), kind))
+        })
+        .collect()
+}
+
+/// Decode text in the project's code page. UTF-8 and Windows-1252 are decoded
+/// exactly; other code pages keep their ASCII characters and fall back to
+/// Windows-1252 for the rest.
+fn decode(data: &[u8], code_page: u16) -> String {
+    if code_page == 65001 {
+        return String::from_utf8_lossy(data).to_string();
+    }
+    const CP1252_HIGH: [char; 32] = [
+        '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž',
+        '\u{8F}', '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}',
+        'ž', 'Ÿ',
+    ];
+    data.iter()
+        .map(|&byte| match byte {
+            0x80..=0x9F => CP1252_HIGH[(byte - 0x80) as usize],
+            _ => byte as char,
+        })
+        .collect()
+}
+
+fn decode_utf16(data: &[u8]) -> String {
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Compress with literal tokens only, which every reader accepts. Full
+    /// 4096-byte chunks would grow past the chunk size limit, so they are
+    /// stored uncompressed.
+    pub(crate) fn compress(data: &[u8]) -> Vec<u8> {
+        let mut container = vec![0x01];
+        for chunk in data.chunks(4096) {
+            if chunk.len() == 4096 {
+                container.extend_from_slice(&0x3FFFu16.to_le_bytes());
+                container.extend_from_slice(chunk);
+                continue;
+            }
+            let mut body = Vec::new();
+            for group in chunk.chunks(8) {
+                body.push(0x00);
+                body.extend_from_slice(group);
+            }
+            assert!(body.len() <= 4096, "chunk too long for literal tokens");
+            let header = 0xB000 | (body.len() as u16 + 2 - 3);
+            container.extend_from_slice(&header.to_le_bytes());
+            container.extend(body);
+        }
+        container
+    }
+
+    /// Build a version 3 compound file (512-byte sectors) with every stream in
+    /// the mini stream. `entries` are (name, parent index, stream data), where
+    /// `None` data makes a storage and index 0 is the root.
+    pub(crate) fn build_compound_file(entries: &[(&str, usize, Option<&[u8]>)]) -> Vec<u8> {
+        const END: u32 = 0xFFFF_FFFE;
+        const FAT_SECTOR: u32 = 0xFFFF_FFFD;
+
+        // Mini stream and mini FAT
+        let mut mini_stream = Vec::new();
+        let mut mini_fat: Vec<u32> = Vec::new();
+        let mut starts = vec![END; entries.len() + 1];
+        for (i, (_, _, data)) in entries.iter().enumerate() {
+            let Some(data) = data else { continue };
+            if data.is_empty() {
+                continue;
+            }
+            let first = (mini_stream.len() / 64) as u32;
+            starts[i + 1] = first;
+            let sectors = data.len().div_ceil(64) as u32;
+            for s in 0..sectors {
+                mini_fat.push(if s + 1 == sectors { END } else { first + s + 1 });
+            }
+            mini_stream.extend_from_slice(data);
+            mini_stream.resize(mini_stream.len().div_ceil(64) * 64, 0);
+        }
+
+        // Directory: root, then the given entries; siblings chained through
+        // the right link
+        let mut directory = Vec::new();
+        let mut last_child: Vec<Option<usize>> = vec![None; entries.len() + 1];
+        let mut links = vec![(NO_STREAM, NO_STREAM); entries.len() + 1]; // (right, child)
+        for (i, (_, parent, _)) in entries.iter().enumerate() {
+            let id = i + 1;
+            match last_child[*parent] {
+                Some(previous) => links[previous].0 = id as u32,
+                None => links[*parent].1 = id as u32,
+            }
+            last_child[*parent] = Some(id);
+        }
+
+        // Sector layout: FAT, directory, mini FAT, mini stream
+        let sectors = |bytes: usize| bytes.div_ceil(512).max(1);
+        let dir_bytes = (entries.len() + 1) * 128;
+        let (dir_sectors, mini_fat_sectors, mini_stream_sectors) = (
+            sectors(dir_bytes),
+            sectors(mini_fat.len() * 4),
+            sectors(mini_stream.len()),
+        );
+        let dir_start = 1u32;
+        let mini_fat_start = dir_start + dir_sectors as u32;
+        let mini_stream_start = mini_fat_start + mini_fat_sectors as u32;
+        let total = mini_stream_start as usize + mini_stream_sectors;
+        assert!(total <= 128, "test compound file too large");
+
+        let mut fat = vec![NO_STREAM; 128];
+        fat[0] = FAT_SECTOR;
+        for (start, count) in [
+            (dir_start, dir_sectors),
+            (mini_fat_start, mini_fat_sectors),
+            (mini_stream_start, mini_stream_sectors),
+        ] {
+            for s in 0..count as u32 {
+                fat[(start + s) as usize] = if s + 1 == count as u32 {
+                    END
+                } else {
+                    start + s + 1
+                };
+            }
+        }
+
+        for id in 0..=entries.len() {
+            let (name, kind, start, size) = if id == 0 {
+                ("Root Entry", 5u8, mini_stream_start, mini_stream.len())
+            } else {
+                let (name, _, data) = entries[id - 1];
+                match data {
+                    Some(data) => (name, 2, starts[id], data.len()),
+                    None => (name, 1, END, 0),
+                }
+            };
+            let mut entry = vec![0u8; 128];
+            let name: Vec<u8> = name.encode_utf16().flat_map(u16::to_le_bytes).collect();
+            entry[..name.len()].copy_from_slice(&name);
+            entry[64..66].copy_from_slice(&(name.len() as u16 + 2).to_le_bytes());
+            entry[66] = kind;
+            entry[68..72].copy_from_slice(&NO_STREAM.to_le_bytes());
+            entry[72..76].copy_from_slice(&links[id].0.to_le_bytes());
+            entry[76..80].copy_from_slice(&links[id].1.to_le_bytes());
+            entry[116..120].copy_from_slice(&start.to_le_bytes());
+            entry[120..124].copy_from_slice(&(size as u32).to_le_bytes());
+            directory.extend(entry);
+        }
+
+        let mut file = vec![0u8; 512];
+        file[..8].copy_from_slice(&CFB_SIGNATURE);
+        file[0x1A..0x1C].copy_from_slice(&3u16.to_le_bytes());
+        file[0x1C..0x1E].copy_from_slice(&0xFFFEu16.to_le_bytes());
+        file[0x1E..0x20].copy_from_slice(&9u16.to_le_bytes());
+        file[0x20..0x22].copy_from_slice(&6u16.to_le_bytes());
+        file[0x2C..0x30].copy_from_slice(&1u32.to_le_bytes());
+        file[0x30..0x34].copy_from_slice(&dir_start.to_le_bytes());
+        file[0x38..0x3C].copy_from_slice(&4096u32.to_le_bytes());
+        file[0x3C..0x40].copy_from_slice(&mini_fat_start.to_le_bytes());
+        file[0x40..0x44].copy_from_slice(&(mini_fat_sectors as u32).to_le_bytes());
+        file[0x44..0x48].copy_from_slice(&END.to_le_bytes());
+        for i in 0..109 {
+            let id = if i == 0 { 0 } else { NO_STREAM };
+            file[0x4C + i * 4..0x50 + i * 4].copy_from_slice(&id.to_le_bytes());
+        }
+
+        let pad = |file: &mut Vec<u8>, data: &[u8], count: usize| {
+            let mut bytes = data.to_vec();
+            bytes.resize(count * 512, 0);
+            file.extend(bytes);
+        };
+        let fat_bytes: Vec<u8> = fat.iter().flat_map(|id| id.to_le_bytes()).collect();
+        pad(&mut file, &fat_bytes, 1);
+        pad(&mut file, &directory, dir_sectors);
+        let mut mini_fat_bytes: Vec<u8> = mini_fat.iter().flat_map(|id| id.to_le_bytes()).collect();
+        mini_fat_bytes.resize(mini_fat_sectors * 512, 0xFF);
+        pad(&mut file, &mini_fat_bytes, mini_fat_sectors);
+        pad(&mut file, &mini_stream, mini_stream_sectors);
+        file
+    }
+
+    /// A `dir` stream record
+    fn record(id: u16, data: &[u8]) -> Vec<u8> {
+        let mut bytes = id.to_le_bytes().to_vec();
+        bytes.extend((data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    /// A `vbaProject.bin` with the given (name, procedural, source) modules
+    pub(crate) fn build_vba_project(modules: &[(&str, bool, &str)], project: &str) -> Vec<u8> {
+        let mut dir = Vec::new();
+        dir.extend(record(0x0003, &1252u16.to_le_bytes()));
+        dir.extend(record(0x0004, b"VBAProject"));
+        for (name, procedural, _) in modules {
+            dir.extend(record(0x0019, name.as_bytes()));
+            dir.extend(record(0x001A, name.as_bytes()));
+            dir.extend(record(0x0031, &0u32.to_le_bytes()));
+            dir.extend(record(if *procedural { 0x0021 } else { 0x0022 }, &[]));
+            dir.extend(record(0x002B, &[]));
+        }
+
+        let compressed_dir = compress(&dir);
+        let sources: Vec<Vec<u8>> = modules
+            .iter()
+            // Windows-1252, matching the code page record
+            .map(|(_, _, source)| compress(&source.chars().map(|c| c as u8).collect::<Vec<_>>()))
+            .collect();
+        let mut entries: Vec<(&str, usize, Option<&[u8]>)> = vec![
+            ("PROJECT", 0, Some(project.as_bytes())),
+            ("VBA", 0, None),
+            ("dir", 2, Some(&compressed_dir)),
+        ];
+        for ((name, _, _), source) in modules.iter().zip(&sources) {
+            entries.push((name, 2, Some(source)));
+        }
+        build_compound_file(&entries)
+    }
+
+    #[test]
+    fn test_decompress() {
+        // Example from MS-OVBA 3.2.3
+        let compressed = [
+            0x01, 0x2F, 0xB0, 0x00, 0x23, 0x61, 0x61, 0x61, 0x62, 0x63, 0x64, 0x65, 0x82, 0x66,
+            0x00, 0x70, 0x61, 0x67, 0x68, 0x69, 0x6A, 0x01, 0x38, 0x08, 0x61, 0x6B, 0x6C, 0x00,
+            0x30, 0x6D, 0x6E, 0x6F, 0x70, 0x06, 0x71, 0x02, 0x70, 0x04, 0x10, 0x72, 0x73, 0x74,
+            0x75, 0x76, 0x10, 0x77, 0x78, 0x79, 0x7A, 0x00, 0x3C,
+        ];
+        assert_eq!(
+            String::from_utf8(decompress(&compressed).unwrap()).unwrap(),
+            "#aaabcdefaaaaghijaaaaaklaaamnopqaaaaaaaaaaaarstuvwxyzaaa"
+        );
+
+        let text = "Sub Auto_Open()\r\n".repeat(400);
+        assert_eq!(
+            decompress(&compress(text.as_bytes())).unwrap(),
+            text.as_bytes()
+        );
+
+        // A chunk whose copy tokens run past 4096 bytes is malformed
+        let overlong = [0x01, 0x06, 0xB0, 0x06, b'A', 0xFF, 0x0F, 0xFF, 0x0F];
+        assert!(decompress(&overlong).is_err());
+    }
+
+    #[test]
+    fn test_read_vba_project() {
+        let data = build_vba_project(
+            &[
+                (
+                    "ThisWorkbook",
+                    false,
+                    "Attribute VB_Name = \"ThisWorkbook\"\r\nPrivate Sub Workbook_Open()\r\nEnd Sub\r\n",
+                ),
+                (
+                    "Module1",
+                    true,
+                    "Sub Hello()\r\n    MsgBox \"Olá\"\r\nEnd Sub\r\n",
+                ),
+                ("Class1", false, "Public Name As String\r\n"),
+            ],
+            "ID=\"{00000000-0000-0000-0000-000000000000}\"\r\nDocument=ThisWorkbook/&H00000000\r\nModule=Module1\r\nClass=Class1\r\nName=\"VBAProject\"\r\n",
+        );
+
+        let modules = read_vba_project(&data).unwrap();
+        let summary: Vec<_> = modules
+            .iter()
+            .map(|module| (module.library.as_str(), module.name.as_str(), module.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("VBAProject", "ThisWorkbook", ModuleKind::Document),
+                ("VBAProject", "Module1", ModuleKind::Standard),
+                ("VBAProject", "Class1", ModuleKind::Class),
+            ]
+        );
+        assert!(modules[0].source.contains("Private Sub Workbook_Open()"));
+        assert_eq!(modules[1].source.lines().nth(1), Some("    MsgBox \"Olá\""));
+
+        assert!(read_vba_project(b"not a compound file").is_err());
+    }
+}
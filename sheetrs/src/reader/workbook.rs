@@ -1,24 +1,39 @@
 //! Workbook data structures
 
-use std::collections::HashMap;
+use super::active_content::ActiveContent;
+use super::cell_store::CellStore;
+use super::conditional_format::ConditionalFormat;
+use super::dates::{DateSystem, DateTime};
+use super::drawings::{Chart, Image};
+use super::external_links::ExternalWorkbook;
+use super::filter::AutoFilter;
+use super::layout::{SheetView, Span, span_at};
+use super::macros::MacroModule;
+use super::number_format::NumberFormat;
+use super::openformula::{Token, tokenize};
+use super::page_setup::PageSetup;
+use super::parser_utils::{quote_sheet_name, unquote_sheet_name};
+use super::pivot::PivotTable;
+use super::properties::DocumentProperties;
+use super::protection::{SheetProtection, WorkbookProtection};
+use super::r1c1;
+use super::range_index::RangeIndex;
+use super::rich_text::RichText;
+use super::styles::CellStyle;
+use super::tables::{self, Table};
+use super::validation::DataValidation;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt;
 use std::path::PathBuf;
 
-/// Represents an external workbook reference
-#[derive(Debug, Clone)]
-pub struct ExternalWorkbook {
-    /// 0-based index in the collection (maps to XLSX 1-based index - 1)
-    /// For XLSX: index N corresponds to [N+1] in formulas
-    /// For ODS: index N corresponds to order of appearance in metadata
-    pub index: usize,
-    /// Basename of the external workbook (e.g., "Book1.xlsx")
-    pub path: String,
-}
-
 #[derive(Debug, Clone, Default)]
 pub struct Workbook {
     pub path: PathBuf,
     pub sheets: Vec<Sheet>,
-    pub defined_names: HashMap<String, String>,
+    /// Defined names, workbook-wide and sheet-local; the same name can be
+    /// defined once per scope
+    pub defined_names: Vec<DefinedName>,
     /// List of hidden sheet names
     pub hidden_sheets: Vec<String>,
     /// Whether the workbook contains macros or VBA code
@@ -27,6 +42,23 @@ pub struct Workbook {
     /// For XLSX: index N corresponds to [N+1] in formulas
     /// For ODS: index N corresponds to order of appearance in metadata
     pub external_workbooks: Vec<ExternalWorkbook>,
+    /// Epoch used by the workbook's date serials
+    pub date_system: DateSystem,
+    /// Shared cell styles, indexed by `Cell::style`; index 0 is the default style
+    pub styles: Vec<CellStyle>,
+    /// Structure/window protection; `None` when the workbook isn't protected
+    pub protection: Option<WorkbookProtection>,
+    /// Author, dates and other document metadata
+    pub properties: DocumentProperties,
+    /// Excel tables and ODS database ranges, on any sheet
+    pub tables: Vec<Table>,
+    /// Pivot tables (XLSX) and data pilot tables (ODS), on any sheet
+    pub pivot_tables: Vec<PivotTable>,
+    /// OLE objects, ActiveX controls, data connections and event scripts;
+    /// macro projects themselves are flagged by `has_macros`
+    pub active_content: Vec<ActiveContent>,
+    /// VBA and Basic modules with their source code
+    pub macros: Vec<MacroModule>,
 }
 
 impl Workbook {
@@ -39,28 +71,147 @@ impl Workbook {
     pub fn sheet_names(&self) -> Vec<&str> {
         self.sheets.iter().map(|s| s.name.as_str()).collect()
     }
+
+    /// Look up a defined name the way a formula on `sheet` sees it: a name
+    /// local to that sheet hides a workbook-wide name of the same name, and
+    /// `Sheet1!Name` only finds the name local to Sheet1. Names ignore case.
+    pub fn defined_name(&self, name: &str, sheet: Option<&str>) -> Option<&DefinedName> {
+        let (qualifier, name) = match name.rsplit_once('!') {
+            Some((qualifier, name)) => (Some(unquote_sheet_name(qualifier)), name),
+            None => (None, name),
+        };
+        let mut candidates = self
+            .defined_names
+            .iter()
+            .filter(|defined| defined.name.eq_ignore_ascii_case(name));
+        if let Some(qualifier) = qualifier {
+            return candidates.find(|defined| defined.scope.as_deref() == Some(qualifier.as_ref()));
+        }
+        let candidates: Vec<_> = candidates.collect();
+        candidates
+            .iter()
+            .find(|defined| sheet.is_some() && defined.scope.as_deref() == sheet)
+            .or_else(|| candidates.iter().find(|defined| defined.scope.is_none()))
+            .copied()
+    }
+
+    /// Find a table by name, ignoring case like formulas do
+    pub fn table(&self, name: &str) -> Option<&Table> {
+        self.tables
+            .iter()
+            .find(|table| table.name.eq_ignore_ascii_case(name))
+    }
+
+    /// A formula cell's formula with structured references (`Sales[Amount]`,
+    /// `Sales[@Qty]`) rewritten into A1 references; `None` for other cells
+    pub fn resolved_formula<'c>(&self, sheet: &str, cell: &'c Cell) -> Option<Cow<'c, str>> {
+        let formula = cell.value.as_formula()?;
+        Some(tables::resolve_structured_references(
+            formula,
+            &self.tables,
+            sheet,
+            cell.row,
+            cell.col,
+        ))
+    }
+
+    /// Names of the sheets that chart series read
+    pub fn sheets_read_by_charts(&self) -> HashSet<&str> {
+        let mut read = HashSet::new();
+        for reference in self
+            .sheets
+            .iter()
+            .flat_map(|sheet| &sheet.charts)
+            .flat_map(|chart| &chart.series)
+            .flat_map(|series| series.references())
+        {
+            // Sheet qualifiers are the names right before `!`, not those of
+            // another workbook (`[1]Sheet1!`)
+            let tokens = tokenize(reference);
+            for (i, token) in tokens.iter().enumerate() {
+                let (Token::Word(qualifier) | Token::Quoted(qualifier)) = token else {
+                    continue;
+                };
+                if tokens.get(i + 1) != Some(&Token::Punct('!'))
+                    || matches!(
+                        i.checked_sub(1).map(|j| &tokens[j]),
+                        Some(Token::Bracket(_))
+                    )
+                {
+                    continue;
+                }
+                let qualifier = unquote_sheet_name(qualifier);
+                if let Some(sheet) = self.sheets.iter().find(|sheet| sheet.name == qualifier) {
+                    read.insert(sheet.name.as_str());
+                }
+            }
+        }
+        read
+    }
+
+    /// Style of a cell, falling back to the default style for unstyled cells
+    pub fn cell_style(&self, cell: &Cell) -> Option<&CellStyle> {
+        self.styles.get(cell.style.unwrap_or(0))
+    }
+
+    /// Whether a cell is locked on a protected sheet (cells are locked by default)
+    pub fn is_cell_locked(&self, cell: &Cell) -> bool {
+        self.cell_style(cell)
+            .is_none_or(|style| style.protection.locked)
+    }
+
+    /// Interpret a date serial using the workbook's date system
+    pub fn serial_to_datetime(&self, serial: f64) -> Option<DateTime> {
+        self.date_system.to_datetime(serial)
+    }
 }
 
 /// Represents a worksheet
 #[derive(Debug, Clone, Default)]
 pub struct Sheet {
     pub name: String,
-    pub cells: HashMap<(u32, u32), Cell>,
+    pub cells: CellStore,
     pub used_range: Option<(u32, u32)>, // (rows, cols)
     /// List of hidden column indices (0-based)
     pub hidden_columns: Vec<u32>,
-    /// List of hidden row indices (0-based)
+    /// Rows hidden by hand (0-based)
     pub hidden_rows: Vec<u32>,
+    /// Rows hidden by the autofilter (0-based), not repeated in `hidden_rows`
+    pub filtered_rows: Vec<u32>,
+    /// Autofilter range, criteria and sort keys
+    pub auto_filter: Option<AutoFilter>,
+    /// Column widths (in characters) and outline levels, sorted runs
+    pub columns: Vec<Span>,
+    /// Custom row heights (in points) and outline levels, sorted runs
+    pub rows: Vec<Span>,
+    /// Width of columns without their own, in characters
+    pub default_column_width: Option<f64>,
+    /// Height of rows without a custom one, in points
+    pub default_row_height: Option<f64>,
+    /// Frozen panes, zoom, gridlines, tab color and selection
+    pub view: SheetView,
+    /// Print area, print titles, margins, headers and footers
+    pub page_setup: PageSetup,
     /// Merged cell ranges: (start_row, start_col, end_row, end_col)
     pub merged_cells: Vec<(u32, u32, u32, u32)>,
     /// Error message if there was an error parsing formulas for this sheet
     pub formula_parsing_error: Option<String>,
     /// Internal path to the sheet XML file in the ZIP archive
     pub sheet_path: Option<String>,
-    /// Number of conditional formatting rules in this sheet
-    pub conditional_formatting_count: usize,
-    /// Ranges where conditional formatting rules are applied
-    pub conditional_formatting_ranges: Vec<String>,
+    /// Conditional formatting rules, each with the ranges it applies to
+    pub conditional_formats: Vec<ConditionalFormat>,
+    /// Array formulas and the ranges their results fill
+    pub array_formulas: Vec<ArrayFormula>,
+    /// Data validation rules, each with the ranges it covers
+    pub data_validations: Vec<DataValidation>,
+    /// Hyperlinks attached to cells, in row-major order
+    pub hyperlinks: Vec<Hyperlink>,
+    /// Charts placed on the sheet; a chart sheet holds one and no cells
+    pub charts: Vec<Chart>,
+    /// Pictures placed on the sheet
+    pub images: Vec<Image>,
+    /// Cell protection; `None` when the sheet isn't protected
+    pub protection: Option<SheetProtection>,
     pub visible: bool,
 }
 
@@ -68,15 +219,28 @@ impl Sheet {
     pub fn new(name: String) -> Self {
         Self {
             name,
-            cells: HashMap::new(),
+            cells: CellStore::new(),
             used_range: None,
             hidden_columns: Vec::new(),
             hidden_rows: Vec::new(),
+            filtered_rows: Vec::new(),
+            auto_filter: None,
+            columns: Vec::new(),
+            rows: Vec::new(),
+            default_column_width: None,
+            default_row_height: None,
+            view: SheetView::default(),
+            page_setup: PageSetup::default(),
             merged_cells: Vec::new(),
             formula_parsing_error: None,
             sheet_path: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
+            conditional_formats: Vec::new(),
+            array_formulas: Vec::new(),
+            data_validations: Vec::new(),
+            hyperlinks: Vec::new(),
+            charts: Vec::new(),
+            images: Vec::new(),
+            protection: None,
             visible: true,
         }
     }
@@ -86,36 +250,122 @@ impl Sheet {
         self.cells.get(&(row, col))
     }
 
-    /// Get all cells with values
+    /// Width of a column in characters, falling back to the sheet default
+    pub fn column_width(&self, col: u32) -> Option<f64> {
+        span_at(&self.columns, col)
+            .and_then(|span| span.size)
+            .or(self.default_column_width)
+    }
+
+    /// Height of a row in points, falling back to the sheet default
+    pub fn row_height(&self, row: u32) -> Option<f64> {
+        span_at(&self.rows, row)
+            .and_then(|span| span.size)
+            .or(self.default_row_height)
+    }
+
+    /// Outline level of a column, 0 when not grouped
+    pub fn column_outline_level(&self, col: u32) -> u8 {
+        span_at(&self.columns, col).map_or(0, |span| span.outline_level)
+    }
+
+    /// Outline level of a row, 0 when not grouped
+    pub fn row_outline_level(&self, row: u32) -> u8 {
+        span_at(&self.rows, row).map_or(0, |span| span.outline_level)
+    }
+
+    /// Data validation covering the given position; use
+    /// [`Sheet::validation_index`] to look up many cells
+    pub fn validation_at(&self, row: u32, col: u32) -> Option<&DataValidation> {
+        self.data_validations
+            .iter()
+            .find(|validation| validation.applies_to(row, col))
+    }
+
+    /// Data validations by covered cell
+    pub fn validation_index(&self) -> RangeIndex<'_, DataValidation> {
+        RangeIndex::new(&self.data_validations, |validation| &validation.ranges)
+    }
+
+    /// Array formula whose result covers the given position; use
+    /// [`Sheet::array_formula_index`] to look up many cells
+    pub fn array_formula_at(&self, row: u32, col: u32) -> Option<&ArrayFormula> {
+        self.array_formulas
+            .iter()
+            .find(|array| array.contains(row, col))
+    }
+
+    /// Array formulas by the cells their results cover
+    pub fn array_formula_index(&self) -> RangeIndex<'_, ArrayFormula> {
+        RangeIndex::new(&self.array_formulas, |array| {
+            std::slice::from_ref(&array.range)
+        })
+    }
+
+    /// Whether the cell holds part of an array result rather than its own
+    /// content (any cell of an array range except the formula cell)
+    pub fn is_spilled(&self, row: u32, col: u32) -> bool {
+        self.array_formula_at(row, col)
+            .is_some_and(|array| array.spills_into(row, col))
+    }
+
+    /// Hyperlink attached to the given position
+    pub fn hyperlink_at(&self, row: u32, col: u32) -> Option<&Hyperlink> {
+        self.hyperlinks
+            .iter()
+            .find(|link| link.row == row && link.col == col)
+    }
+
+    /// Get all cells with values, in row-major order
     pub fn all_cells(&self) -> impl Iterator<Item = &Cell> {
         self.cells.values()
     }
 
     /// Get cells in a specific column
     pub fn cells_in_column(&self, col: u32) -> impl Iterator<Item = &Cell> {
-        self.cells.values().filter(move |c| c.col == col)
+        self.cells.column(col)
     }
 
     /// Get cells in a specific row
     pub fn cells_in_row(&self, row: u32) -> impl Iterator<Item = &Cell> {
-        self.cells.values().filter(move |c| c.row == row)
+        self.cells.row(row).iter()
+    }
+
+    /// Get cells inside an inclusive rectangular range, in row-major order.
+    /// The corners may be given in either order, as in `A10:A1`.
+    pub fn cells_in_range(
+        &self,
+        (start_row, start_col): (u32, u32),
+        (end_row, end_col): (u32, u32),
+    ) -> impl Iterator<Item = &Cell> {
+        self.cells.range(
+            start_row.min(end_row)..=start_row.max(end_row),
+            start_col.min(end_col)..=start_col.max(end_col),
+        )
+    }
+
+    /// Cells carrying a note or comment thread, in row-major order
+    pub fn comments(&self) -> impl Iterator<Item = (&Cell, &Comment)> {
+        self.cells
+            .values()
+            .filter_map(|cell| cell.comment.as_ref().map(|comment| (cell, comment)))
     }
 
     /// Get the last cell with actual data (bottom-right corner of data range)
     pub fn last_data_cell(&self) -> Option<(u32, u32)> {
-        let non_empty_cells: Vec<_> = self
+        // Rows are ordered, so the last row with data is found scanning backwards
+        let max_row = self
             .cells
-            .values()
-            .filter(|c| !matches!(c.value, CellValue::Empty))
-            .collect();
-
-        if non_empty_cells.is_empty() {
-            return None;
-        }
-
-        // Find the maximum row and maximum column independently
-        let max_row = non_empty_cells.iter().map(|c| c.row).max()?;
-        let max_col = non_empty_cells.iter().map(|c| c.col).max()?;
+            .rows()
+            .rev()
+            .find(|(_, cells)| cells.iter().any(|c| !c.value.is_empty()))?
+            .0;
+        let max_col = self
+            .cells
+            .rows()
+            .filter_map(|(_, cells)| cells.iter().rev().find(|c| !c.value.is_empty()))
+            .map(|c| c.col)
+            .max()?;
 
         Some((max_row, max_col))
     }
@@ -128,6 +378,204 @@ pub struct Cell {
     pub col: u32,
     pub value: CellValue,
     pub num_fmt: Option<String>,
+    /// Index into `Workbook::styles`; `None` when the cell uses the default style
+    pub style: Option<usize>,
+    /// Note or comment thread attached to the cell
+    pub comment: Option<Comment>,
+}
+
+/// A cell note (legacy comment) or threaded comment
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Comment {
+    pub author: Option<String>,
+    pub text: String,
+    /// Creation time as written in the file (ISO 8601), when recorded
+    pub date: Option<String>,
+    /// Whether this is a threaded comment rather than a note
+    pub threaded: bool,
+    /// Replies to a threaded comment, oldest first
+    pub replies: Vec<Comment>,
+}
+
+impl Comment {
+    /// Authors of the comment and its replies, in thread order
+    pub fn authors(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self)
+            .chain(&self.replies)
+            .filter_map(|c| c.author.as_deref())
+    }
+}
+
+/// A defined name: a named range, constant or formula
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DefinedName {
+    pub name: String,
+    /// What the name stands for, e.g. `Sheet1!$A$1:$B$5`
+    pub reference: String,
+    /// Sheet the name is local to; `None` for workbook-wide names
+    pub scope: Option<String>,
+    /// Hidden from the name manager (usually created by Excel or an add-in)
+    pub hidden: bool,
+    pub comment: Option<String>,
+}
+
+impl DefinedName {
+    /// A visible workbook-wide name
+    pub fn new(name: impl Into<String>, reference: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            reference: reference.into(),
+            ..Default::default()
+        }
+    }
+
+    /// The name as a formula on another sheet writes it: `Sheet1!Name` for a
+    /// local name, the bare name otherwise
+    pub fn qualified_name(&self) -> String {
+        match &self.scope {
+            Some(sheet) => format!("{}!{}", quote_sheet_name(sheet), self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// A formula whose result fills a range: a legacy array (CSE) formula, an
+/// Excel dynamic array formula or an ODS matrix formula. Only the top-left cell
+/// holds the formula; the other cells keep the cached results as plain values.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ArrayFormula {
+    /// Cell holding the formula
+    pub row: u32,
+    pub col: u32,
+    /// Cells the result occupies: (start_row, start_col, end_row, end_col)
+    pub range: (u32, u32, u32, u32),
+    /// Whether the result spills to whatever size it evaluates to (an Excel
+    /// dynamic array) instead of filling a fixed range
+    pub dynamic: bool,
+}
+
+impl ArrayFormula {
+    /// Whether the result covers the cell
+    pub fn contains(&self, row: u32, col: u32) -> bool {
+        let (r1, c1, r2, c2) = self.range;
+        (r1..=r2).contains(&row) && (c1..=c2).contains(&col)
+    }
+
+    /// Whether the cell holds part of the result other than the formula cell
+    pub fn spills_into(&self, row: u32, col: u32) -> bool {
+        self.contains(row, col) && (self.row, self.col) != (row, col)
+    }
+}
+
+impl RangeIndex<'_, ArrayFormula> {
+    /// Whether the cell holds part of an array result rather than its own
+    /// content; see [`Sheet::is_spilled`]
+    pub fn is_spilled(&self, row: u32, col: u32) -> bool {
+        self.at(row, col)
+            .is_some_and(|array| array.spills_into(row, col))
+    }
+}
+
+/// A hyperlink on a cell, from XLSX `<hyperlink>` elements, ODS `text:a` or a
+/// `HYPERLINK()` formula with a literal target
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Hyperlink {
+    pub row: u32,
+    pub col: u32,
+    /// URL or file path; for internal links the location, e.g. `Sheet2!A1`
+    pub target: String,
+    /// Text shown for the link when the file records it
+    pub display: Option<String>,
+    /// Whether the link points to a location inside the workbook
+    pub internal: bool,
+}
+
+impl Hyperlink {
+    /// Link for a target as written in the file, where a leading `#` marks a
+    /// location inside the workbook
+    pub fn new(row: u32, col: u32, target: &str, display: Option<String>) -> Self {
+        let (target, internal) = match target.strip_prefix('#') {
+            Some(location) => (location.to_string(), true),
+            None => (target.to_string(), false),
+        };
+        Self {
+            row,
+            col,
+            target,
+            display,
+            internal,
+        }
+    }
+
+    /// Link created by a `HYPERLINK("target"[, "text"])` call with a literal target
+    pub fn from_formula(row: u32, col: u32, formula: &str) -> Option<Self> {
+        let upper = formula.to_ascii_uppercase();
+        // The whole function name, not the end of `MYHYPERLINK(`
+        let start = upper
+            .match_indices("HYPERLINK(")
+            .map(|(i, _)| i)
+            .find(|&i| {
+                !upper[..i]
+                    .chars()
+                    .next_back()
+                    .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.')
+            })?
+            + "HYPERLINK(".len();
+        let (target, rest) = read_string_literal(formula[start..].trim_start())?;
+        let display = rest
+            .trim_start()
+            .strip_prefix([',', ';'])
+            .and_then(|rest| read_string_literal(rest.trim_start()))
+            .map(|(text, _)| text);
+        Some(Self::new(row, col, &target, display))
+    }
+
+    /// Whether the target is a local or network file (`file://`, UNC or drive path)
+    pub fn is_file(&self) -> bool {
+        let target = self.target.to_ascii_lowercase();
+        target.starts_with("file:")
+            || target.starts_with("\\\\")
+            || target.starts_with("//")
+            || matches!(target.as_bytes(), [_, b':', b'\\' | b'/', ..])
+    }
+}
+
+/// Split a leading `"..."` literal (with `""` escapes) off a formula fragment
+fn read_string_literal(s: &str) -> Option<(String, &str)> {
+    let mut chars = s.strip_prefix('"')?.char_indices().peekable();
+    let mut text = String::new();
+    while let Some((i, ch)) = chars.next() {
+        if ch == '"' {
+            if chars.peek().is_some_and(|&(_, next)| next == '"') {
+                chars.next();
+                text.push('"');
+            } else {
+                return Some((text, &s[i + 2..]));
+            }
+        } else {
+            text.push(ch);
+        }
+    }
+    None
+}
+
+impl Cell {
+    /// Text the cell displays: its value rendered through `num_fmt`.
+    /// Missing or unparseable formats fall back to `General`.
+    pub fn display_text(&self, date_system: DateSystem) -> String {
+        self.num_fmt
+            .as_deref()
+            .and_then(|code| NumberFormat::parse(code).ok())
+            .unwrap_or_default()
+            .format_value(&self.value, date_system)
+    }
+
+    /// The formula in R1C1 form, relative to this cell: copies of a formula
+    /// share it
+    pub fn formula_r1c1(&self) -> Option<String> {
+        let formula = self.value.as_formula()?;
+        Some(r1c1::to_r1c1(formula, self.row, self.col))
+    }
 }
 
 /// Cell value types
@@ -136,14 +584,126 @@ pub enum CellValue {
     #[default]
     Empty,
     Number(f64),
-    Text(String),
+    /// Text, with its runs and phonetic guides when it is formatted
+    Text(String, Option<Box<RichText>>),
     Boolean(bool),
     Formula {
+        /// Excel syntax, without the leading `=`
         formula: String,
-        cached_error: Option<String>,
+        cached_error: Option<CellError>,
+        /// The formula as the file writes it, when its syntax differs: the
+        /// OpenFormula text of an ODS formula
+        original: Option<String>,
     },
 }
 
+/// Error value cached for a formula cell
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CellError {
+    /// `#NULL!`: empty intersection of ranges
+    Null,
+    /// `#DIV/0!`
+    DivZero,
+    /// `#VALUE!`: wrong argument or operand type
+    Value,
+    /// `#REF!`: invalid cell reference
+    Ref,
+    /// `#NAME?`: unknown function or name
+    Name,
+    /// `#NUM!`: invalid numeric value
+    Num,
+    /// `#N/A`: value not available
+    NotAvailable,
+    /// `#SPILL!`: dynamic array result blocked
+    Spill,
+    /// `#CALC!`: calculation engine error
+    Calc,
+    /// Any other error text, kept verbatim (e.g. ODS `Err:522`)
+    Other(String),
+}
+
+impl CellError {
+    /// Error literals that can appear in formulas and cached values
+    pub const STANDARD: [CellError; 9] = [
+        CellError::Null,
+        CellError::DivZero,
+        CellError::Value,
+        CellError::Ref,
+        CellError::Name,
+        CellError::Num,
+        CellError::NotAvailable,
+        CellError::Spill,
+        CellError::Calc,
+    ];
+
+    /// Parse an error as written in a file: an Excel literal such as `#DIV/0!`
+    /// or an ODS `Err:5xx` code. Unknown errors are kept as [`CellError::Other`].
+    pub fn parse(s: &str) -> Self {
+        let s = s.trim();
+        if let Some(error) = Self::STANDARD
+            .iter()
+            .find(|e| e.as_str().eq_ignore_ascii_case(s))
+        {
+            return error.clone();
+        }
+        s.strip_prefix("Err:")
+            .and_then(|code| code.parse().ok())
+            .and_then(Self::from_ods_code)
+            .unwrap_or_else(|| CellError::Other(s.to_string()))
+    }
+
+    /// Map a LibreOffice `Err:nnn` code to its Excel equivalent, if it has one
+    pub fn from_ods_code(code: u16) -> Option<Self> {
+        Some(match code {
+            // Invalid argument, invalid floating point operation, no convergence
+            502 | 503 | 523 => CellError::Num,
+            // Error in parameter list, wrong data type
+            504 | 519 => CellError::Value,
+            521 => CellError::Null,
+            524 => CellError::Ref,
+            525 => CellError::Name,
+            532 => CellError::DivZero,
+            // LibreOffice's internal code for #N/A
+            32767 => CellError::NotAvailable,
+            _ => return None,
+        })
+    }
+
+    /// Text shown in the cell
+    pub fn as_str(&self) -> &str {
+        match self {
+            CellError::Null => "#NULL!",
+            CellError::DivZero => "#DIV/0!",
+            CellError::Value => "#VALUE!",
+            CellError::Ref => "#REF!",
+            CellError::Name => "#NAME?",
+            CellError::Num => "#NUM!",
+            CellError::NotAvailable => "#N/A",
+            CellError::Spill => "#SPILL!",
+            CellError::Calc => "#CALC!",
+            CellError::Other(s) => s,
+        }
+    }
+}
+
+impl fmt::Display for CellError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for CellError {
+    fn from(s: &str) -> Self {
+        Self::parse(s)
+    }
+}
+
+impl From<String> for CellError {
+    fn from(s: String) -> Self {
+        Self::parse(&s)
+    }
+}
+
 impl CellValue {
     /// Check if the cell contains an error
     pub fn is_error(&self) -> bool {
@@ -167,7 +727,7 @@ impl CellValue {
     }
 
     /// Get the error value if this is an error cell
-    pub fn as_error(&self) -> Option<&str> {
+    pub fn as_error(&self) -> Option<&CellError> {
         match self {
             CellValue::Formula {
                 cached_error: Some(e),
@@ -190,14 +750,140 @@ impl CellValue {
         CellValue::Formula {
             formula: f.into(),
             cached_error: None,
+            original: None,
         }
     }
 
     /// Create a formula cell with cached error
-    pub fn formula_with_error(f: impl Into<String>, error: impl Into<String>) -> Self {
+    pub fn formula_with_error(f: impl Into<String>, error: impl Into<CellError>) -> Self {
         CellValue::Formula {
             formula: f.into(),
             cached_error: Some(error.into()),
+            original: None,
+        }
+    }
+
+    /// Keep the text a formula was translated from
+    pub fn with_original(mut self, text: impl Into<String>) -> Self {
+        if let CellValue::Formula { original, .. } = &mut self {
+            *original = Some(text.into());
+        }
+        self
+    }
+
+    /// The formula as the file writes it, if it was translated
+    pub fn original_formula(&self) -> Option<&str> {
+        match self {
+            CellValue::Formula { original, .. } => original.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cell_error_parse() {
+        assert_eq!(CellError::parse("#DIV/0!"), CellError::DivZero);
+        assert_eq!(CellError::parse(" #n/a "), CellError::NotAvailable);
+        assert_eq!(CellError::parse("#SPILL!"), CellError::Spill);
+        assert_eq!(CellError::parse("Err:532"), CellError::DivZero);
+        assert_eq!(CellError::parse("Err:524"), CellError::Ref);
+        assert_eq!(CellError::parse("Err:502"), CellError::Num);
+        assert_eq!(
+            CellError::parse("Err:522"),
+            CellError::Other("Err:522".to_string())
+        );
+        assert_eq!(CellError::parse("#BOGUS").to_string(), "#BOGUS");
+        for error in CellError::STANDARD {
+            assert_eq!(CellError::parse(error.as_str()), error);
+        }
+    }
+
+    #[test]
+    fn test_hyperlink_from_formula() {
+        let link =
+            Hyperlink::from_formula(0, 0, r#"IF(A1,HYPERLINK("https://example.com";"Site"),"")"#)
+                .unwrap();
+        assert_eq!(link.target, "https://example.com");
+        assert_eq!(link.display.as_deref(), Some("Site"));
+        assert!(Hyperlink::from_formula(0, 0, r#"MYHYPERLINK("https://example.com")"#).is_none());
+        assert!(Hyperlink::from_formula(0, 0, "HYPERLINK(A1)").is_none());
+    }
+
+    #[test]
+    fn test_cells_in_reversed_range() {
+        let mut sheet = Sheet::new("Sheet1".to_string());
+        for row in 0..10 {
+            sheet.cells.insert(
+                (row, 0),
+                Cell {
+                    row,
+                    value: CellValue::Number(row as f64),
+                    ..Default::default()
+                },
+            );
         }
+        // A10:A1 covers the same cells as A1:A10
+        let rows: Vec<u32> = sheet
+            .cells_in_range((9, 0), (0, 0))
+            .map(|c| c.row)
+            .collect();
+        assert_eq!(rows, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_cell_display_text() {
+        let mut cell = Cell {
+            value: CellValue::Number(45658.75),
+            num_fmt: Some("dd/mm/yyyy hh:mm".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(cell.display_text(DateSystem::Excel1900), "01/01/2025 18:00");
+        assert_eq!(cell.display_text(DateSystem::Excel1904), "02/01/2029 18:00");
+
+        cell.num_fmt = None;
+        assert_eq!(cell.display_text(DateSystem::Excel1900), "45658.75");
+    }
+
+    #[test]
+    fn test_workbook_date_system() {
+        let mut workbook = Workbook::default();
+        let dt = workbook.serial_to_datetime(1.0).unwrap();
+        assert_eq!((dt.year, dt.month, dt.day), (1900, 1, 1));
+
+        workbook.date_system = DateSystem::Excel1904;
+        let dt = workbook.serial_to_datetime(1.0).unwrap();
+        assert_eq!((dt.year, dt.month, dt.day), (1904, 1, 2));
+    }
+
+    #[test]
+    fn test_defined_name_scopes() {
+        let local = DefinedName {
+            scope: Some("Q2 Sales".to_string()),
+            ..DefinedName::new("Rate", "'Q2 Sales'!$B$1")
+        };
+        let workbook = Workbook {
+            defined_names: vec![DefinedName::new("Rate", "Inputs!$B$1"), local.clone()],
+            ..Default::default()
+        };
+
+        let lookup = |name, sheet| {
+            workbook
+                .defined_name(name, sheet)
+                .map(|defined| defined.reference.as_str())
+        };
+        assert_eq!(lookup("Rate", None), Some("Inputs!$B$1"));
+        assert_eq!(lookup("rate", Some("Inputs")), Some("Inputs!$B$1"));
+        assert_eq!(lookup("Rate", Some("Q2 Sales")), Some("'Q2 Sales'!$B$1"));
+        assert_eq!(
+            lookup("'Q2 Sales'!Rate", Some("Inputs")),
+            Some("'Q2 Sales'!$B$1")
+        );
+        assert_eq!(lookup("Inputs!Rate", None), None);
+        assert_eq!(lookup("Tax", Some("Inputs")), None);
+        assert_eq!(local.qualified_name(), "'Q2 Sales'!Rate");
     }
 }
@@ -3,11 +3,35 @@
 use anyhow::{Context, Result};
 use quick_xml::Reader;
 use quick_xml::events::Event;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::BufReader;
 use zip::ZipArchive;
 
-use super::{Cell, CellValue, Sheet, WorkbookReader};
+use super::active_content::{ActiveContent, ActiveContentKind};
+use super::conditional_format::{ConditionalFormat, ConditionalFormatKind};
+use super::drawings::{Chart, ChartSeries, Image};
+use super::external_links::{ExternalSheet, ExternalWorkbook};
+use super::filter::{
+    AutoFilter, FilterColumn, FilterCondition, FilterCriteria, FilterOperator, SortKey,
+};
+use super::layout::{SheetView, Span, push_span};
+use super::macros::MacroModule;
+use super::page_setup::{
+    HeaderFooter, HeaderFooterKind, HeaderFooterPages, Margins, Orientation, PageSetup,
+};
+use super::pivot::{PivotCache, PivotSource, PivotTable};
+use super::properties::{DocumentProperties, parse_timestamp, set_text};
+use super::protection::{PasswordHash, SheetProtection, WorkbookProtection};
+use super::rich_text::{PhoneticRun, RichText};
+use super::styles::{
+    Alignment, Border, BorderLine, CellStyle, Fill, Font, Protection, parse_hex_color,
+};
+use super::tables::{Table, format_range};
+use super::validation::{ValidationErrorStyle, ValidationKind, ValidationOperator};
+use super::{
+    ArrayFormula, Cell, CellStore, CellValue, Comment, CommentedRows, DataValidation, DateSystem,
+    DefinedName, Hyperlink, RowVisitor, Sheet, WorkbookReader, attach_comments,
+};
 
 /// Resolve sheet name to its XML path in the XLSX archive
 pub fn get_xlsx_sheet_path(
@@ -109,11 +133,27 @@ pub fn get_xlsx_sheet_path(
     }
 }
 
-/// Extract defined names (named ranges) from XLSX file
+/// Extract defined names (named ranges) from XLSX file, leaving out the names
+/// Excel keeps for itself (print areas, print titles, filter ranges)
 pub fn extract_defined_names_from_xlsx(
     archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
-) -> Result<HashMap<String, String>> {
-    let mut defined_names = HashMap::new();
+) -> Result<Vec<DefinedName>> {
+    Ok(read_defined_names_from_xlsx(archive)?
+        .into_iter()
+        .filter(|name| {
+            !name.name.is_empty()
+                && !name.name.starts_with("_xlnm.")
+                && !name.name.contains("_FilterDatabase")
+        })
+        .collect())
+}
+
+/// Every defined name in workbook.xml, built-in ones included. `localSheetId`
+/// is the position of the owning sheet in `<sheets>`.
+fn read_defined_names_from_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<Vec<DefinedName>> {
+    let mut defined_names = Vec::new();
 
     // Try to read workbook.xml
     let workbook_xml = match archive.by_name("xl/workbook.xml") {
@@ -126,42 +166,53 @@ pub fn extract_defined_names_from_xlsx(
     reader.config_mut().trim_text(true);
 
     let mut buf = Vec::new();
+    let mut sheet_names = Vec::new();
     let mut in_defined_names = false;
-    let mut current_name = String::new();
-    let mut current_ref = String::new();
+    let mut current: Option<DefinedName> = None;
 
     loop {
         match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
-                match e.name().as_ref() {
-                    b"definedNames" => in_defined_names = true,
-                    b"definedName" if in_defined_names => {
-                        // Get the name attribute
-                        for attr in e.attributes() {
-                            if let Ok(attr) = attr
-                                && attr.key.as_ref() == b"name"
-                            {
-                                current_name = attr.unescape_value()?.to_string();
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => match e.name().as_ref() {
+                b"sheet" => {
+                    for attr in e.attributes().flatten() {
+                        if attr.key.as_ref() == b"name" {
+                            sheet_names.push(attr.unescape_value()?.to_string());
+                        }
+                    }
+                }
+                b"definedNames" => in_defined_names = true,
+                b"definedName" if in_defined_names => {
+                    let mut name = DefinedName::default();
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"name" => name.name = attr.unescape_value()?.to_string(),
+                            b"localSheetId" => {
+                                name.scope = attr
+                                    .unescape_value()?
+                                    .parse::<usize>()
+                                    .ok()
+                                    .and_then(|index| sheet_names.get(index).cloned());
                             }
+                            b"hidden" => {
+                                name.hidden = matches!(attr.value.as_ref(), b"1" | b"true");
+                            }
+                            b"comment" => {
+                                name.comment = Some(attr.unescape_value()?.to_string());
+                            }
+                            _ => {}
                         }
                     }
-                    _ => {}
+                    current = Some(name);
+                }
+                _ => {}
+            },
+            Ok(Event::Text(e)) if in_defined_names => {
+                if let Some(name) = &mut current {
+                    name.reference = e.unescape().unwrap_or_default().to_string();
                 }
-            }
-            Ok(Event::Text(e)) if in_defined_names && !current_name.is_empty() => {
-                current_ref = e.unescape().unwrap_or_default().to_string();
             }
             Ok(Event::End(e)) => match e.name().as_ref() {
-                b"definedName" if !current_name.is_empty() => {
-                    // Filter out internal Excel names
-                    if !current_name.starts_with("_xlnm.")
-                        && !current_name.contains("_FilterDatabase")
-                    {
-                        defined_names.insert(current_name.clone(), current_ref.clone());
-                    }
-                    current_name.clear();
-                    current_ref.clear();
-                }
+                b"definedName" => defined_names.extend(current.take()),
                 b"definedNames" => in_defined_names = false,
                 _ => {}
             },
@@ -175,12 +226,12 @@ pub fn extract_defined_names_from_xlsx(
     Ok(defined_names)
 }
 
-/// Extract Excel Tables from XLSX file as defined names
-/// Scans xl/tables/*.xml
+/// Extract Excel tables from `xl/tables/*.xml`. The owning sheet comes from
+/// the worksheet relationships; tables no sheet links to keep an empty sheet.
 pub fn extract_tables_from_xlsx(
     archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
-) -> Result<HashMap<String, String>> {
-    let mut current_tables = HashMap::new();
+) -> Result<Vec<Table>> {
+    let mut tables = Vec::new();
     let mut table_files = Vec::new();
 
     // Find all table files
@@ -192,6 +243,10 @@ pub fn extract_tables_from_xlsx(
             }
         }
     }
+    if table_files.is_empty() {
+        return Ok(tables);
+    }
+    let owners = extract_part_owners_from_xlsx(archive, "/table")?;
 
     for table_file in table_files {
         let xml = match archive.by_name(&table_file) {
@@ -202,177 +257,116 @@ pub fn extract_tables_from_xlsx(
         let mut reader = Reader::from_reader(BufReader::new(xml));
         reader.config_mut().trim_text(true);
 
+        let mut table = Table {
+            header_rows: 1,
+            ..Default::default()
+        };
+        let mut range = None;
         let mut buf = Vec::new();
         loop {
             match reader.read_event_into(&mut buf) {
-                Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
-                    if e.name().as_ref() == b"table" {
-                        let mut name = String::new();
-                        let mut ref_sqref = String::new();
-
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) => match e.local_name().as_ref() {
+                    b"table" => {
                         for attr in e.attributes().flatten() {
                             match attr.key.as_ref() {
-                                b"name" | b"displayName" => {
-                                    // displayName is usually the safe name, name might be id.
-                                    // Spec says: name is collection name, displayName is unique name for formulas.
-                                    // DisplayName is prioritized.
-                                    if name.is_empty() {
-                                        name = attr.unescape_value()?.to_string();
-                                    } else if attr.key.as_ref() == b"displayName" {
-                                        name = attr.unescape_value()?.to_string();
-                                    }
+                                // displayName is the unique name formulas use; name
+                                // is only a fallback
+                                b"name" if table.name.is_empty() => {
+                                    table.name = attr.unescape_value()?.to_string();
+                                }
+                                b"displayName" => {
+                                    table.name = attr.unescape_value()?.to_string();
                                 }
-                                b"ref" => {
-                                    ref_sqref = attr.unescape_value()?.to_string();
+                                b"ref" => range = parse_cell_range(&attr.unescape_value()?),
+                                b"headerRowCount" => {
+                                    table.header_rows = attr.unescape_value()?.parse().unwrap_or(1);
+                                }
+                                b"totalsRowCount" => {
+                                    table.totals_rows = attr.unescape_value()?.parse().unwrap_or(0);
                                 }
                                 _ => {}
                             }
                         }
-
-                        if !name.is_empty() && !ref_sqref.is_empty() {
-                            // Tables are usually local to the sheet they are in, but the table definition
-                            // DOES NOT contain the sheet name in 'ref' (it's just A1:B2).
-                            // However, defined names MUST include sheet name to be useful globally.
-                            //
-                            // The exact sheet ownership is not easily known from table XML alone without relationships.
-                            // But Excel tables HAVE a unique name across the workbook.
-                            // A formula refers to it by name `Table1`, not `Sheet1!Table1`.
-                            // So storing just the name is sufficient.
-                            //
-                            // PERF001 checks for unused named ranges by looking for the name in formulas.
-                            // If users use `=SUM(Table1)`, `extract_formulas` will return strings containing `Table1`.
-                            // Registering `Table1` as a defined name enables this check.
-                            // The associated value (range) is primarily for information/reporting.
-
-                            current_tables.insert(name, ref_sqref);
+                    }
+                    b"tableColumn" => {
+                        for attr in e.attributes().flatten() {
+                            if attr.key.as_ref() == b"name" {
+                                table.columns.push(attr.unescape_value()?.to_string());
+                            }
                         }
-                        // Only the top level table element is processed
-                        break;
                     }
-                }
+                    b"tableStyleInfo" => {
+                        for attr in e.attributes().flatten() {
+                            if attr.key.as_ref() == b"name" {
+                                table.style = Some(attr.unescape_value()?.to_string());
+                            }
+                        }
+                    }
+                    _ => {}
+                },
                 Ok(Event::Eof) => break,
                 Err(_) => break,
                 _ => {}
             }
             buf.clear();
         }
-    }
-
-    Ok(current_tables)
-}
-
-pub struct XlsxReader<'a, R: std::io::Read + std::io::Seek> {
-    archive: &'a mut ZipArchive<R>,
-    shared_strings: Vec<String>,
-    styles: Vec<String>,
-}
-
-impl<'a, R: std::io::Read + std::io::Seek> XlsxReader<'a, R> {
-    pub fn new(archive: &'a mut ZipArchive<R>) -> Result<Self> {
-        let shared_strings = extract_shared_strings(archive).unwrap_or_default();
-        let styles = parse_styles(archive).unwrap_or_default();
-        Ok(Self {
-            archive,
-            shared_strings,
-            styles,
-        })
-    }
-}
-
-impl<'a, R: std::io::Read + std::io::Seek> WorkbookReader for XlsxReader<'a, R> {
-    fn read_sheets(&mut self) -> Result<Vec<Sheet>> {
-        let mut sheets = Vec::new();
-        let sheet_names = self.get_sheet_names()?;
-        let hidden_sheets = self.read_hidden_sheets()?;
-
-        for name in sheet_names {
-            let path = get_xlsx_sheet_path(self.archive, &name)?;
-            let mut sheet = Sheet::new(name.clone());
-            sheet.sheet_path = Some(path.clone());
-            sheet.visible = !hidden_sheets.contains(&name);
 
-            // Parse sheet data
-            let (cells, hidden_cols, hidden_rows, merged_cells, cf_count, cf_ranges, dim_range) =
-                self.parse_sheet_xml(&path)?;
-
-            sheet.cells = cells;
-            sheet.hidden_columns = hidden_cols;
-            sheet.hidden_rows = hidden_rows;
-            sheet.merged_cells = merged_cells;
-            sheet.conditional_formatting_count = cf_count;
-            sheet.conditional_formatting_ranges = cf_ranges;
-
-            sheet.used_range = dim_range;
-
-            sheets.push(sheet);
+        if let Some(range) = range
+            && !table.name.is_empty()
+        {
+            table.range = range;
+            table.sheet = owners.get(&table_file).cloned().unwrap_or_default();
+            tables.push(table);
         }
-
-        Ok(sheets)
-    }
-
-    fn read_defined_names(&mut self) -> Result<HashMap<String, String>> {
-        let mut names = extract_defined_names_from_xlsx(self.archive)?;
-        let tables = extract_tables_from_xlsx(self.archive)?;
-        names.extend(tables);
-        Ok(names)
-    }
-
-    fn read_hidden_sheets(&mut self) -> Result<Vec<String>> {
-        extract_hidden_sheets_from_xlsx(self.archive)
     }
 
-    fn has_macros(&mut self) -> Result<bool> {
-        // Check for vbaProject.bin, macrosheets, or any .bin files in xl/
-        if self.archive.by_name("xl/vbaProject.bin").is_ok() {
-            return Ok(true);
-        }
+    Ok(tables)
+}
 
-        // Check for macrosheets
-        for i in 0..self.archive.len() {
-            if let Ok(file) = self.archive.by_index(i)
-                && file.name().starts_with("xl/macrosheets/")
-            {
-                return Ok(true);
+/// Map each part of a kind (`/table`, `/pivotTable`, the end of the
+/// relationship type) to the name of the sheet whose relationships list it
+fn extract_part_owners_from_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+    rel_type: &str,
+) -> Result<HashMap<String, String>> {
+    let mut owners = HashMap::new();
+    for (name, sheet_path) in extract_sheet_parts_from_xlsx(archive)? {
+        for rel in read_part_relationships(archive, &sheet_path)? {
+            if rel.rel_type.ends_with(rel_type) {
+                owners.insert(resolve_part_target(&sheet_path, &rel.target), name.clone());
             }
         }
-
-        Ok(false)
-    }
-
-    fn read_external_links(&mut self) -> Result<Vec<String>> {
-        extract_external_links_xlsx(self.archive)
-    }
-
-    fn read_external_workbooks(&mut self) -> Result<Vec<super::ExternalWorkbook>> {
-        extract_external_workbooks_xlsx(self.archive)
     }
+    Ok(owners)
 }
 
-pub fn extract_external_links_xlsx(
+/// Name and part path of every sheet, in workbook order
+fn extract_sheet_parts_from_xlsx(
     archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
-) -> Result<Vec<String>> {
-    let mut links = Vec::new();
-    let mut external_rels = Vec::new();
+) -> Result<Vec<(String, String)>> {
+    let mut sheets = Vec::new();
+    {
+        let workbook_xml = match archive.by_name("xl/workbook.xml") {
+            Ok(file) => file,
+            Err(_) => return Ok(Vec::new()),
+        };
+        let mut reader = Reader::from_reader(BufReader::new(workbook_xml));
+        reader.config_mut().trim_text(true);
 
-    // 1. Find external link relationships in workbook.xml.rels
-    if let Ok(rels_xml) = archive.by_name("xl/_rels/workbook.xml.rels") {
-        let mut reader = Reader::from_reader(BufReader::new(rels_xml));
         let mut buf = Vec::new();
         loop {
             match reader.read_event_into(&mut buf)? {
-                Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"Relationship" => {
-                    let mut target = String::new();
-                    let mut r_type = String::new();
+                Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"sheet" => {
+                    let mut name = String::new();
+                    let mut r_id = String::new();
                     for attr in e.attributes().flatten() {
                         match attr.key.as_ref() {
-                            b"Target" => target = attr.unescape_value()?.to_string(),
-                            b"Type" => r_type = attr.unescape_value()?.to_string(),
+                            b"name" => name = attr.unescape_value()?.to_string(),
+                            b"r:id" => r_id = attr.unescape_value()?.to_string(),
                             _ => {}
                         }
                     }
-                    if r_type.ends_with("/externalLink") {
-                        external_rels.push(target);
-                    }
+                    sheets.push((name, r_id));
                 }
                 Event::Eof => break,
                 _ => {}
@@ -381,78 +375,310 @@ pub fn extract_external_links_xlsx(
         }
     }
 
-    // 2. Resolve each external link to its target workbook
-    for rel_path in external_rels {
-        // rel_path is usually like "externalLinks/externalLink1.xml"
-        // We need the .rels for this file
-        let filename = std::path::Path::new(&rel_path)
-            .file_name()
-            .and_then(|s| s.to_str())
-            .unwrap_or("");
+    let workbook_rels = read_part_relationships(archive, "xl/workbook.xml")?;
+    Ok(sheets
+        .into_iter()
+        .filter_map(|(name, r_id)| {
+            let rel = workbook_rels.iter().find(|rel| rel.id == r_id)?;
+            Some((name, resolve_part_target("xl/workbook.xml", &rel.target)))
+        })
+        .collect())
+}
 
-        let rels_of_ext = format!("xl/externalLinks/_rels/{}.rels", filename);
+/// Extract pivot tables from `xl/pivotTables/*.xml`, with the source and size
+/// of the pivot cache each one reads. The owning sheet comes from the
+/// worksheet relationships.
+pub fn extract_pivot_tables_from_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<Vec<PivotTable>> {
+    let mut pivot_files: Vec<String> = archive
+        .file_names()
+        .filter(|name| name.starts_with("xl/pivotTables/") && name.ends_with(".xml"))
+        .map(str::to_string)
+        .collect();
+    if pivot_files.is_empty() {
+        return Ok(Vec::new());
+    }
+    pivot_files.sort();
+    let owners = extract_part_owners_from_xlsx(archive, "/pivotTable")?;
+
+    let mut pivot_tables = Vec::new();
+    for pivot_file in pivot_files {
+        let mut pivot = PivotTable::default();
+        let mut cache_id = 0;
+        let mut location = None;
+        {
+            let xml = match archive.by_name(&pivot_file) {
+                Ok(file) => file,
+                Err(_) => continue,
+            };
+            let mut reader = Reader::from_reader(BufReader::new(xml));
+            reader.config_mut().trim_text(true);
 
-        if let Ok(ext_rels_xml) = archive.by_name(&rels_of_ext) {
-            let mut reader = Reader::from_reader(BufReader::new(ext_rels_xml));
             let mut buf = Vec::new();
             loop {
-                match reader.read_event_into(&mut buf)? {
-                    Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"Relationship" => {
-                        let mut target = String::new();
-                        let mut r_type = String::new();
+                match reader.read_event_into(&mut buf) {
+                    Ok(Event::Start(e)) | Ok(Event::Empty(e)) => match e.local_name().as_ref() {
+                        b"pivotTableDefinition" => {
+                            for attr in e.attributes().flatten() {
+                                match attr.key.as_ref() {
+                                    b"name" => pivot.name = attr.unescape_value()?.to_string(),
+                                    b"cacheId" => {
+                                        cache_id = attr.unescape_value()?.parse().unwrap_or(0);
+                                    }
+                                    _ => {}
+                                }
+                            }
+                        }
+                        b"location" => {
+                            for attr in e.attributes().flatten() {
+                                if attr.key.as_ref() == b"ref" {
+                                    let reference = attr.unescape_value()?;
+                                    location = parse_cell_range(&reference)
+                                        .or_else(|| parse_sqref(&reference).first().copied());
+                                }
+                            }
+                            // The fields and items that follow can be long
+                            break;
+                        }
+                        _ => {}
+                    },
+                    Ok(Event::Eof) => break,
+                    Err(_) => break,
+                    _ => {}
+                }
+                buf.clear();
+            }
+        }
+        let Some(location) = location else {
+            continue;
+        };
+        pivot.location = location;
+        pivot.sheet = owners.get(&pivot_file).cloned().unwrap_or_default();
+
+        let cache_file = read_part_relationships(archive, &pivot_file)?
+            .into_iter()
+            .find(|rel| rel.rel_type.ends_with("/pivotCacheDefinition"))
+            .map(|rel| resolve_part_target(&pivot_file, &rel.target));
+        if let Some(cache_file) = cache_file {
+            let (source, cache) = read_pivot_cache_xlsx(archive, &cache_file)?;
+            pivot.source = source;
+            pivot.cache = Some(PivotCache {
+                id: cache_id,
+                ..cache
+            });
+        }
+        pivot_tables.push(pivot);
+    }
+
+    Ok(pivot_tables)
+}
+
+/// Read the source of a pivot cache definition and the size of the cache
+fn read_pivot_cache_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+    cache_file: &str,
+) -> Result<(PivotSource, PivotCache)> {
+    let relationships = read_part_relationships(archive, cache_file)?;
+    let mut cache = PivotCache::default();
+    let mut source = PivotSource::Other;
+    // ref, sheet, name and r:id of a worksheetSource
+    let mut worksheet_source: Option<[Option<String>; 4]> = None;
+    {
+        let xml = match archive.by_name(cache_file) {
+            Ok(file) => file,
+            Err(_) => return Ok((source, cache)),
+        };
+        cache.size += xml.compressed_size();
+        let mut reader = Reader::from_reader(BufReader::new(xml));
+        reader.config_mut().trim_text(true);
+
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) => match e.local_name().as_ref() {
+                    b"pivotCacheDefinition" => {
                         for attr in e.attributes().flatten() {
                             match attr.key.as_ref() {
-                                b"Target" => target = attr.unescape_value()?.to_string(),
-                                b"Type" => r_type = attr.unescape_value()?.to_string(),
+                                b"recordCount" => {
+                                    cache.records = attr.unescape_value()?.parse().ok();
+                                }
+                                b"refreshOnLoad" => {
+                                    cache.refresh_on_load =
+                                        matches!(attr.value.as_ref(), b"1" | b"true");
+                                }
                                 _ => {}
                             }
                         }
-                        // Accept both externalLinkPath (for file references) and externalWorkbook
-                        if r_type.ends_with("/externalLinkPath")
-                            || r_type.ends_with("/externalWorkbook")
-                        {
-                            links.push(target);
+                    }
+                    b"cacheSource" => {
+                        for attr in e.attributes().flatten() {
+                            if attr.key.as_ref() == b"connectionId" {
+                                source =
+                                    PivotSource::Connection(attr.unescape_value()?.to_string());
+                            }
                         }
                     }
-                    Event::Eof => break,
+                    b"worksheetSource" => {
+                        let mut fields: [Option<String>; 4] = Default::default();
+                        for attr in e.attributes().flatten() {
+                            let index = match attr.key.as_ref() {
+                                b"ref" => 0,
+                                b"sheet" => 1,
+                                b"name" => 2,
+                                b"r:id" => 3,
+                                _ => continue,
+                            };
+                            fields[index] = Some(attr.unescape_value()?.to_string());
+                        }
+                        worksheet_source = Some(fields);
+                    }
+                    // The shared items of each field can be long
+                    b"cacheFields" => break,
                     _ => {}
-                }
-                buf.clear();
+                },
+                Ok(Event::Eof) => break,
+                Err(_) => break,
+                _ => {}
             }
+            buf.clear();
         }
     }
 
-    Ok(links)
+    if let Some([reference, sheet, name, r_id]) = worksheet_source {
+        let workbook = r_id.and_then(|id| {
+            relationships
+                .iter()
+                .find(|rel| rel.id == id && rel.external)
+                .map(|rel| rel.target.clone())
+        });
+        source = match (workbook, name, reference) {
+            (Some(workbook), Some(name), _) => PivotSource::External {
+                workbook,
+                reference: name,
+            },
+            (Some(workbook), None, reference) => PivotSource::External {
+                workbook,
+                reference: match sheet {
+                    Some(sheet) => format!(
+                        "{}!{}",
+                        super::parser_utils::quote_sheet_name(&sheet),
+                        reference.unwrap_or_default()
+                    ),
+                    None => reference.unwrap_or_default(),
+                },
+            },
+            (None, Some(name), _) => PivotSource::Name(name),
+            (None, None, reference) => PivotSource::Range {
+                sheet: sheet.unwrap_or_default(),
+                reference: reference.unwrap_or_else(|| "#REF!".to_string()),
+            },
+        };
+    }
+
+    // Saved source rows
+    for rel in &relationships {
+        if rel.rel_type.ends_with("/pivotCacheRecords") {
+            let records_file = resolve_part_target(cache_file, &rel.target);
+            if let Ok(file) = archive.by_name(&records_file) {
+                cache.size += file.compressed_size();
+            }
+        }
+    }
+
+    Ok((source, cache))
 }
 
-/// Extract external workbooks with indices from XLSX file
-/// Returns a vector of ExternalWorkbook where index is 0-based (maps to [N+1] in formulas)
-pub fn extract_external_workbooks_xlsx(
+/// Read the OLE objects and ActiveX controls of every worksheet, the data
+/// connections and web queries of `xl/connections.xml`, and the Power Query
+/// mashup kept under `customXml`
+pub fn extract_active_content_from_xlsx(
     archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
-) -> Result<Vec<super::ExternalWorkbook>> {
-    use super::ExternalWorkbook;
-    let mut workbooks = Vec::new();
-    let mut external_rels = Vec::new();
+) -> Result<Vec<ActiveContent>> {
+    let mut content = Vec::new();
+
+    for (sheet, sheet_path) in extract_sheet_parts_from_xlsx(archive)? {
+        let relationships = read_part_relationships(archive, &sheet_path)?;
+        let has_objects = relationships.iter().any(|rel| {
+            rel.rel_type.ends_with("/oleObject")
+                || rel.rel_type.ends_with("/package")
+                || rel.rel_type.ends_with("/control")
+        });
+        if has_objects {
+            content.extend(read_sheet_objects_xlsx(
+                archive,
+                &sheet,
+                &sheet_path,
+                &relationships,
+            )?);
+        }
+    }
+
+    content.extend(read_connections_xlsx(archive)?);
+
+    // Queries only loaded into the data model have no connection of their own
+    if !content
+        .iter()
+        .any(|item| item.kind == ActiveContentKind::PowerQuery)
+        && has_data_mashup_xlsx(archive)?
+    {
+        content.push(ActiveContent {
+            kind: ActiveContentKind::PowerQuery,
+            name: "DataMashup".to_string(),
+            ..Default::default()
+        });
+    }
+
+    Ok(content)
+}
+
+/// Read the `<oleObject>` and `<control>` elements of a worksheet. Excel
+/// writes each one twice, in the `mc:Choice` and `mc:Fallback` branches.
+fn read_sheet_objects_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+    sheet: &str,
+    sheet_path: &str,
+    relationships: &[Relationship],
+) -> Result<Vec<ActiveContent>> {
+    // (kind, name, relationship id or link, runs on open)
+    let mut objects: Vec<(ActiveContentKind, String, String, bool)> = Vec::new();
+    {
+        let sheet_xml = match archive.by_name(sheet_path) {
+            Ok(file) => file,
+            Err(_) => return Ok(Vec::new()),
+        };
+        let mut reader = Reader::from_reader(BufReader::new(sheet_xml));
+        reader.config_mut().trim_text(true);
 
-    // 1. Find external link relationships in workbook.xml.rels
-    // These are ordered and numbered (externalLink1.xml, externalLink2.xml, etc.)
-    if let Ok(rels_xml) = archive.by_name("xl/_rels/workbook.xml.rels") {
-        let mut reader = Reader::from_reader(BufReader::new(rels_xml));
         let mut buf = Vec::new();
         loop {
             match reader.read_event_into(&mut buf)? {
-                Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"Relationship" => {
-                    let mut target = String::new();
-                    let mut r_type = String::new();
+                Event::Start(e) | Event::Empty(e)
+                    if matches!(e.local_name().as_ref(), b"oleObject" | b"control") =>
+                {
+                    let kind = if e.local_name().as_ref() == b"control" {
+                        ActiveContentKind::ActiveXControl
+                    } else {
+                        ActiveContentKind::OleObject
+                    };
+                    let mut name = String::new();
+                    let mut source = String::new();
+                    let mut runs_on_open = false;
                     for attr in e.attributes().flatten() {
                         match attr.key.as_ref() {
-                            b"Target" => target = attr.unescape_value()?.to_string(),
-                            b"Type" => r_type = attr.unescape_value()?.to_string(),
+                            b"progId" | b"name" => name = attr.unescape_value()?.to_string(),
+                            b"r:id" | b"link" => source = attr.unescape_value()?.to_string(),
+                            b"oleUpdate" => {
+                                runs_on_open = attr.value.as_ref() == b"OLEUPDATE_ALWAYS"
+                            }
                             _ => {}
                         }
                     }
-                    if r_type.ends_with("/externalLink") {
-                        external_rels.push(target);
+                    if !objects
+                        .iter()
+                        .any(|(k, _, s, _)| *k == kind && *s == source)
+                    {
+                        objects.push((kind, name, source, runs_on_open));
                     }
                 }
                 Event::Eof => break,
@@ -462,323 +688,885 @@ pub fn extract_external_workbooks_xlsx(
         }
     }
 
-    // 2. Resolve each external link to its target workbook
-    // Extract the numeric index from the filename (e.g., externalLink1.xml -> 1)
-    for rel_path in external_rels {
-        let filename = std::path::Path::new(&rel_path)
-            .file_name()
-            .and_then(|s| s.to_str())
-            .unwrap_or("");
-
-        // Extract index from filename (e.g., "externalLink1.xml" -> 1)
-        // In XLSX, formulas use [1], [2], etc., so we convert to 0-based: index = N - 1
-        let xlsx_index = filename
-            .trim_start_matches("externalLink")
-            .trim_end_matches(".xml")
-            .parse::<usize>()
-            .unwrap_or(1); // Default to 1 if parsing fails
-        let index = xlsx_index.saturating_sub(1); // Convert to 0-based
+    let mut content = Vec::new();
+    for (kind, name, source, runs_on_open) in objects {
+        let part = relationships
+            .iter()
+            .find(|rel| rel.id == source)
+            .map(|rel| match rel.external {
+                true => rel.target.clone(),
+                false => resolve_part_target(sheet_path, &rel.target),
+            });
+        let target = match (kind, part) {
+            (ActiveContentKind::ActiveXControl, Some(part)) => {
+                read_activex_class_id(archive, &part)?.or(Some(part))
+            }
+            // Linked objects name their external link (`[1]!''`) instead of a part
+            (_, part) => part.or(Some(source)).filter(|target| !target.is_empty()),
+        };
+        content.push(ActiveContent {
+            kind,
+            sheet: Some(sheet.to_string()),
+            name,
+            target,
+            runs_on_open,
+        });
+    }
+    Ok(content)
+}
 
-        let rels_of_ext = format!("xl/externalLinks/_rels/{}.rels", filename);
+/// Class id of an ActiveX control part (`xl/activeX/activeX1.xml`)
+fn read_activex_class_id(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+    path: &str,
+) -> Result<Option<String>> {
+    let control_xml = match archive.by_name(path) {
+        Ok(file) => file,
+        Err(_) => return Ok(None),
+    };
+    let mut reader = Reader::from_reader(BufReader::new(control_xml));
+    reader.config_mut().trim_text(true);
 
-        if let Ok(ext_rels_xml) = archive.by_name(&rels_of_ext) {
-            let mut reader = Reader::from_reader(BufReader::new(ext_rels_xml));
-            let mut buf = Vec::new();
-            loop {
-                match reader.read_event_into(&mut buf)? {
-                    Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"Relationship" => {
-                        let mut target = String::new();
-                        let mut r_type = String::new();
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"Target" => target = attr.unescape_value()?.to_string(),
-                                b"Type" => r_type = attr.unescape_value()?.to_string(),
-                                _ => {}
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"ocx" => {
+                for attr in e.attributes().flatten() {
+                    if attr.key.local_name().as_ref() == b"classid" {
+                        return Ok(Some(attr.unescape_value()?.to_string()));
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(None)
+}
+
+/// Read the connections of `xl/connections.xml`: web queries, Power Query
+/// queries (through the `Microsoft.Mashup.OleDb` provider) and other database,
+/// OLAP or text file connections
+fn read_connections_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<Vec<ActiveContent>> {
+    let mut connections = Vec::new();
+    let connections_xml = match archive.by_name("xl/connections.xml") {
+        Ok(file) => file,
+        Err(_) => return Ok(connections),
+    };
+    let mut reader = Reader::from_reader(BufReader::new(connections_xml));
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut current: Option<ActiveContent> = None;
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                b"connection" => {
+                    let mut connection = ActiveContent {
+                        kind: ActiveContentKind::DataConnection,
+                        ..Default::default()
+                    };
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"name" => connection.name = attr.unescape_value()?.to_string(),
+                            b"refreshOnLoad" => {
+                                connection.runs_on_open =
+                                    matches!(attr.value.as_ref(), b"1" | b"true");
+                            }
+                            b"odcFile" => {
+                                connection.target = Some(attr.unescape_value()?.to_string());
                             }
+                            _ => {}
                         }
-                        // Accept both externalLinkPath (for file references) and externalWorkbook
-                        if r_type.ends_with("/externalLinkPath")
-                            || r_type.ends_with("/externalWorkbook")
-                        {
-                            workbooks.push(ExternalWorkbook {
-                                index,
-                                path: super::parser_utils::extract_basename(&target),
-                            });
+                    }
+                    connections.extend(current.replace(connection));
+                }
+                b"dbPr" => {
+                    if let Some(connection) = current.as_mut()
+                        && let Some(string) = attr_value(&e, b"connection")?
+                    {
+                        if string.contains("Microsoft.Mashup.OleDb") {
+                            connection.kind = ActiveContentKind::PowerQuery;
+                            // The query name is the `Location` of the mashup
+                            connection.target = string
+                                .split(';')
+                                .find_map(|pair| pair.trim().strip_prefix("Location="))
+                                .map(str::to_string);
+                        } else {
+                            connection.target = Some(string);
                         }
                     }
-                    Event::Eof => break,
-                    _ => {}
                 }
-                buf.clear();
-            }
+                b"webPr" => {
+                    if let Some(connection) = current.as_mut() {
+                        connection.kind = ActiveContentKind::WebQuery;
+                        if let Some(url) = attr_value(&e, b"url")? {
+                            connection.target = Some(url);
+                        }
+                    }
+                }
+                b"textPr" => {
+                    if let Some(connection) = current.as_mut()
+                        && let Some(file) = attr_value(&e, b"sourceFile")?
+                    {
+                        connection.target = Some(file);
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
         }
+        buf.clear();
     }
+    connections.extend(current);
+    Ok(connections)
+}
 
-    // Sort by index to ensure correct order
-    workbooks.sort_by_key(|w| w.index);
+/// Whether a `customXml` item holds the Power Query `DataMashup` blob. The
+/// items are usually UTF-16, so only the start of each one is decoded.
+fn has_data_mashup_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<bool> {
+    use std::io::Read;
+
+    let items: Vec<String> = archive
+        .file_names()
+        .filter(|name| name.starts_with("customXml/item") && name.ends_with(".xml"))
+        .map(str::to_string)
+        .collect();
+    for item in items {
+        let mut head = Vec::new();
+        archive.by_name(&item)?.take(512).read_to_end(&mut head)?;
+        let text = match head.as_slice() {
+            [0xFF, 0xFE, rest @ ..] => String::from_utf16_lossy(
+                &rest
+                    .chunks_exact(2)
+                    .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                    .collect::<Vec<_>>(),
+            ),
+            _ => String::from_utf8_lossy(&head).to_string(),
+        };
+        if text.contains("<DataMashup") {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
 
-    Ok(workbooks)
+/// Workbook-wide lookup tables referenced by every worksheet
+#[derive(Debug, Clone, Default)]
+pub struct XlsxTables {
+    /// Shared string table: text and, when formatted, its runs
+    pub shared_strings: Vec<(String, Option<Box<RichText>>)>,
+    /// Number format code per cellXfs index
+    pub styles: Vec<String>,
+    /// Resolved formatting per cellXfs index
+    pub cell_styles: Vec<CellStyle>,
+    /// Theme palette, for colors given by theme index
+    pub theme_colors: Vec<String>,
 }
 
-fn translate_shared_formula(formula: &str, row_shift: i32, col_shift: i32) -> String {
-    thread_local! {
-        static RE: regex::Regex = regex::Regex::new(r"(?P<sheet>(?:'[^']+'|[A-Za-z0-9_\.\-]+)!)?(?P<col_abs>\$?)(?P<col>[A-Z]+)(?P<row_abs>\$?)(?P<row>[0-9]+)").unwrap();
+impl XlsxTables {
+    pub fn read(archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>) -> Self {
+        Self {
+            shared_strings: extract_shared_strings(archive).unwrap_or_default(),
+            styles: parse_styles(archive).unwrap_or_default(),
+            cell_styles: parse_cell_styles(archive).unwrap_or_default(),
+            theme_colors: read_theme_colors(archive).unwrap_or_default(),
+        }
     }
-    RE.with(|re| {
-        re.replace_all(formula, |caps: &regex::Captures| {
-            let sheet = caps.name("sheet").map(|m| m.as_str()).unwrap_or("");
-            let col_abs = !caps.name("col_abs").unwrap().as_str().is_empty();
-            let col_str = caps.name("col").unwrap().as_str();
-            let row_abs = !caps.name("row_abs").unwrap().as_str().is_empty();
-            let row_str = caps.name("row").unwrap().as_str();
+}
 
-            let mut col = 0u32;
-            for ch in col_str.chars() {
-                col = col * 26 + (ch.to_ascii_uppercase() as u32 - 'A' as u32 + 1);
-            }
-            col -= 1;
+pub struct XlsxReader<'a, R: std::io::Read + std::io::Seek> {
+    archive: &'a mut ZipArchive<R>,
+    tables: XlsxTables,
+}
 
-            let row = row_str.parse::<u32>().unwrap_or(1) - 1;
+impl<'a, R: std::io::Read + std::io::Seek> XlsxReader<'a, R> {
+    pub fn new(archive: &'a mut ZipArchive<R>) -> Result<Self> {
+        let tables = XlsxTables::read(archive);
+        Ok(Self::with_tables(archive, tables))
+    }
 
-            let new_row = if row_abs {
-                row
-            } else {
-                (row as i32 + row_shift).max(0) as u32
-            };
-            let new_col = if col_abs {
-                col
-            } else {
-                (col as i32 + col_shift).max(0) as u32
-            };
+    /// Reuse tables parsed by an earlier reader on the same archive
+    pub fn with_tables(archive: &'a mut ZipArchive<R>, tables: XlsxTables) -> Self {
+        Self { archive, tables }
+    }
 
-            let mut result = sheet.to_string();
-            if col_abs {
-                result.push('$');
-            }
+    pub fn into_tables(self) -> XlsxTables {
+        self.tables
+    }
+}
 
-            // Col to letter logic
-            let mut c = new_col + 1;
-            let mut col_letter = String::new();
-            while c > 0 {
-                let m = (c - 1) % 26;
-                col_letter.insert(0, (b'A' + m as u8) as char);
-                c = (c - m) / 26;
-            }
-            result.push_str(&col_letter);
+impl<'a, R: std::io::Read + std::io::Seek> WorkbookReader for XlsxReader<'a, R> {
+    fn read_sheets(&mut self) -> Result<Vec<Sheet>> {
+        let mut sheets = Vec::new();
+        for name in self.get_sheet_names()? {
+            sheets.push(self.load_sheet(&name, None)?);
+        }
+        Ok(sheets)
+    }
 
-            if row_abs {
-                result.push('$');
+    fn sheet_names(&mut self) -> Result<Vec<String>> {
+        self.get_sheet_names()
+    }
+
+    fn read_sheet(&mut self, name: &str) -> Result<Sheet> {
+        self.load_sheet(name, None)
+    }
+
+    fn for_each_row(&mut self, name: &str, visit: RowVisitor<'_>) -> Result<Sheet> {
+        self.load_sheet(name, Some(visit))
+    }
+
+    fn read_defined_names(&mut self) -> Result<Vec<DefinedName>> {
+        let mut names = extract_defined_names_from_xlsx(self.archive)?;
+        // Table names work like workbook-wide defined names in formulas
+        for table in extract_tables_from_xlsx(self.archive)? {
+            names.push(DefinedName::new(table.name, format_range("", table.range)));
+        }
+        Ok(names)
+    }
+
+    fn read_hidden_sheets(&mut self) -> Result<Vec<String>> {
+        extract_hidden_sheets_from_xlsx(self.archive)
+    }
+
+    fn has_macros(&mut self) -> Result<bool> {
+        // Check for vbaProject.bin, macrosheets, or any .bin files in xl/
+        if self.archive.by_name("xl/vbaProject.bin").is_ok() {
+            return Ok(true);
+        }
+
+        // Check for macrosheets
+        for i in 0..self.archive.len() {
+            if let Ok(file) = self.archive.by_index(i)
+                && file.name().starts_with("xl/macrosheets/")
+            {
+                return Ok(true);
             }
-            result.push_str(&(new_row + 1).to_string());
+        }
 
-            result
-        })
-        .to_string()
-    })
+        Ok(false)
+    }
+
+    fn read_external_links(&mut self) -> Result<Vec<String>> {
+        extract_external_links_xlsx(self.archive)
+    }
+
+    fn read_external_workbooks(&mut self) -> Result<Vec<ExternalWorkbook>> {
+        extract_external_workbooks_xlsx(self.archive)
+    }
+
+    fn read_date_system(&mut self) -> Result<DateSystem> {
+        extract_date_system_from_xlsx(self.archive)
+    }
+
+    fn read_styles(&mut self) -> Result<Vec<CellStyle>> {
+        Ok(self.tables.cell_styles.clone())
+    }
+
+    fn read_workbook_protection(&mut self) -> Result<Option<WorkbookProtection>> {
+        extract_workbook_protection_from_xlsx(self.archive)
+    }
+
+    fn read_properties(&mut self) -> Result<DocumentProperties> {
+        extract_properties_from_xlsx(self.archive)
+    }
+
+    fn read_tables(&mut self) -> Result<Vec<Table>> {
+        extract_tables_from_xlsx(self.archive)
+    }
+
+    fn read_pivot_tables(&mut self) -> Result<Vec<PivotTable>> {
+        extract_pivot_tables_from_xlsx(self.archive)
+    }
+
+    fn read_active_content(&mut self) -> Result<Vec<ActiveContent>> {
+        extract_active_content_from_xlsx(self.archive)
+    }
+
+    fn read_macros(&mut self) -> Result<Vec<MacroModule>> {
+        extract_macros_from_xlsx(self.archive)
+    }
 }
 
-impl<'a, R: std::io::Read + std::io::Seek> XlsxReader<'a, R> {
-    fn get_sheet_names(&mut self) -> Result<Vec<String>> {
-        let mut names = Vec::new();
-        let workbook_xml = self.archive.by_name("xl/workbook.xml")?;
-        let mut reader = Reader::from_reader(BufReader::new(workbook_xml));
-        reader.config_mut().trim_text(true);
+pub fn extract_external_links_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<Vec<String>> {
+    let mut links = Vec::new();
+    let mut external_rels = Vec::new();
 
+    // 1. Find external link relationships in workbook.xml.rels
+    if let Ok(rels_xml) = archive.by_name("xl/_rels/workbook.xml.rels") {
+        let mut reader = Reader::from_reader(BufReader::new(rels_xml));
         let mut buf = Vec::new();
         loop {
             match reader.read_event_into(&mut buf)? {
-                Event::Start(e) | Event::Empty(e) => {
-                    if e.name().as_ref() == b"sheet" {
-                        for attr in e.attributes().flatten() {
-                            if attr.key.as_ref() == b"name" {
-                                names.push(attr.unescape_value()?.to_string());
-                            }
+                Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"Relationship" => {
+                    let mut target = String::new();
+                    let mut r_type = String::new();
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"Target" => target = attr.unescape_value()?.to_string(),
+                            b"Type" => r_type = attr.unescape_value()?.to_string(),
+                            _ => {}
                         }
                     }
+                    if r_type.ends_with("/externalLink") {
+                        external_rels.push(target);
+                    }
                 }
                 Event::Eof => break,
                 _ => {}
             }
             buf.clear();
         }
-        Ok(names)
     }
 
-    fn parse_sheet_xml(
-        &mut self,
-        path: &str,
-    ) -> Result<(
-        HashMap<(u32, u32), Cell>,
-        Vec<u32>,
-        Vec<u32>,
-        Vec<(u32, u32, u32, u32)>,
-        usize,
-        Vec<String>,
-        Option<(u32, u32)>,
-    )> {
-        let mut cells = HashMap::new();
-        let mut hidden_columns = Vec::new();
-        let mut hidden_rows = Vec::new();
-        let mut merged_cells = Vec::new();
-        let mut cf_count = 0;
-        let mut cf_ranges = Vec::new();
-        let mut dim_range = None;
-        let mut shared_formulas: HashMap<
-            u32,
-            Vec<(String, u32, u32, Option<(u32, u32, u32, u32)>)>,
-        > = HashMap::new();
-
-        let sheet_xml = self.archive.by_name(path)?;
-        let mut reader = Reader::from_reader(BufReader::new(sheet_xml));
-        reader.config_mut().trim_text(true);
+    // 2. Resolve each external link to its target workbook
+    for rel_path in external_rels {
+        // rel_path is usually like "externalLinks/externalLink1.xml"
+        // We need the .rels for this file
+        let filename = std::path::Path::new(&rel_path)
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("");
 
-        let mut buf = Vec::new();
-        let mut current_row = 0u32;
-        let mut current_col = 0u32;
+        let rels_of_ext = format!("xl/externalLinks/_rels/{}.rels", filename);
 
-        loop {
-            match reader.read_event_into(&mut buf)? {
-                Event::Start(e) => match e.name().as_ref() {
-                    b"dimension" => {
-                        for attr in e.attributes().flatten() {
-                            if attr.key.as_ref() == b"ref" {
-                                let ref_str = attr.unescape_value()?;
-                                if let Some((_, _, end_row, end_col)) = parse_cell_range(&ref_str) {
-                                    // defined range is inclusive, so count is index + 1
-                                    dim_range = Some((end_row + 1, end_col + 1));
-                                } else if let Some((end_row, end_col)) = parse_cell_ref(&ref_str) {
-                                    // Single cell ref used as dimension? Rare but possible.
-                                    dim_range = Some((end_row + 1, end_col + 1));
-                                }
-                            }
-                        }
-                    }
-                    b"col" => {
-                        let mut min = 0u32;
-                        let mut max = 0u32;
-                        let mut hidden = false;
+        if let Ok(ext_rels_xml) = archive.by_name(&rels_of_ext) {
+            let mut reader = Reader::from_reader(BufReader::new(ext_rels_xml));
+            let mut buf = Vec::new();
+            loop {
+                match reader.read_event_into(&mut buf)? {
+                    Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"Relationship" => {
+                        let mut target = String::new();
+                        let mut r_type = String::new();
                         for attr in e.attributes().flatten() {
                             match attr.key.as_ref() {
-                                b"min" => {
-                                    min = attr.unescape_value()?.parse::<u32>()?.saturating_sub(1);
-                                }
-                                b"max" => {
-                                    max = attr.unescape_value()?.parse::<u32>()?.saturating_sub(1);
-                                }
-                                b"hidden" => {
-                                    hidden = attr.value.as_ref() == b"1"
-                                        || attr.value.as_ref() == b"true";
-                                }
+                                b"Target" => target = attr.unescape_value()?.to_string(),
+                                b"Type" => r_type = attr.unescape_value()?.to_string(),
                                 _ => {}
                             }
                         }
-                        if hidden {
-                            for col in min..=max {
-                                hidden_columns.push(col);
-                            }
+                        // Accept both externalLinkPath (for file references) and externalWorkbook
+                        if r_type.ends_with("/externalLinkPath")
+                            || r_type.ends_with("/externalWorkbook")
+                        {
+                            links.push(target);
                         }
                     }
-                    b"row" => {
-                        for attr in e.attributes().flatten() {
-                            if attr.key.as_ref() == b"r" {
-                                current_row =
-                                    attr.unescape_value()?.parse::<u32>()?.saturating_sub(1);
-                            }
-                            if attr.key.as_ref() == b"hidden"
-                                && (attr.value.as_ref() == b"1" || attr.value.as_ref() == b"true")
-                            {
-                                hidden_rows.push(current_row);
-                            }
+                    Event::Eof => break,
+                    _ => {}
+                }
+                buf.clear();
+            }
+        }
+    }
+
+    Ok(links)
+}
+
+/// Extract external workbooks with indices from XLSX file
+/// Returns a vector of ExternalWorkbook where index is 0-based (maps to [N+1] in formulas),
+/// with the full target and the sheet names and cached values of each link part
+pub fn extract_external_workbooks_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<Vec<ExternalWorkbook>> {
+    let mut workbooks = Vec::new();
+    let mut external_rels = Vec::new();
+
+    // 1. Find external link relationships in workbook.xml.rels
+    // These are ordered and numbered (externalLink1.xml, externalLink2.xml, etc.)
+    if let Ok(rels_xml) = archive.by_name("xl/_rels/workbook.xml.rels") {
+        let mut reader = Reader::from_reader(BufReader::new(rels_xml));
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"Relationship" => {
+                    let mut target = String::new();
+                    let mut r_type = String::new();
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"Target" => target = attr.unescape_value()?.to_string(),
+                            b"Type" => r_type = attr.unescape_value()?.to_string(),
+                            _ => {}
                         }
-                        current_col = 0;
                     }
-                    b"c" => {
-                        let mut r_attr = String::new();
-                        let mut s_attr = None;
-                        let mut t_attr = String::new();
+                    if r_type.ends_with("/externalLink") {
+                        external_rels.push(target);
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+    }
+
+    // 2. Resolve each external link to its target workbook
+    // Extract the numeric index from the filename (e.g., externalLink1.xml -> 1)
+    for rel_path in external_rels {
+        let filename = std::path::Path::new(&rel_path)
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("");
+
+        // Extract index from filename (e.g., "externalLink1.xml" -> 1)
+        // In XLSX, formulas use [1], [2], etc., so we convert to 0-based: index = N - 1
+        let xlsx_index = filename
+            .trim_start_matches("externalLink")
+            .trim_end_matches(".xml")
+            .parse::<usize>()
+            .unwrap_or(1); // Default to 1 if parsing fails
+        let index = xlsx_index.saturating_sub(1); // Convert to 0-based
+
+        let rels_of_ext = format!("xl/externalLinks/_rels/{}.rels", filename);
+        let sheets = read_external_link_cache(archive, &format!("xl/externalLinks/{}", filename))?;
+
+        if let Ok(ext_rels_xml) = archive.by_name(&rels_of_ext) {
+            let mut reader = Reader::from_reader(BufReader::new(ext_rels_xml));
+            let mut buf = Vec::new();
+            loop {
+                match reader.read_event_into(&mut buf)? {
+                    Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"Relationship" => {
+                        let mut target = String::new();
+                        let mut r_type = String::new();
                         for attr in e.attributes().flatten() {
                             match attr.key.as_ref() {
-                                b"r" => r_attr = attr.unescape_value()?.to_string(),
-                                b"s" => s_attr = Some(attr.unescape_value()?.parse::<usize>()?),
-                                b"t" => t_attr = attr.unescape_value()?.to_string(),
+                                b"Target" => target = attr.unescape_value()?.to_string(),
+                                b"Type" => r_type = attr.unescape_value()?.to_string(),
                                 _ => {}
                             }
                         }
+                        // Accept both externalLinkPath (for file references) and externalWorkbook
+                        if r_type.ends_with("/externalLinkPath")
+                            || r_type.ends_with("/externalWorkbook")
+                        {
+                            workbooks.push(ExternalWorkbook {
+                                index,
+                                path: super::parser_utils::extract_basename(&target),
+                                target,
+                                sheets: sheets.clone(),
+                            });
+                        }
+                    }
+                    Event::Eof => break,
+                    _ => {}
+                }
+                buf.clear();
+            }
+        }
+    }
 
-                        let (row, col) = if !r_attr.is_empty() {
-                            let (r, c) =
-                                parse_cell_ref(&r_attr).unwrap_or((current_row, current_col));
-                            current_col = c + 1;
-                            (r, c)
-                        } else {
-                            let c = current_col;
-                            current_col += 1;
-                            (current_row, c)
-                        };
+    // Sort by index to ensure correct order
+    workbooks.sort_by_key(|w| w.index);
 
-                        let num_fmt = s_attr.and_then(|idx| self.styles.get(idx).cloned());
+    Ok(workbooks)
+}
 
-                        let (value, mut formula, shared_si, shared_ref) = parse_cell_contents(
-                            &mut reader,
-                            &t_attr,
-                            &self.shared_strings,
-                            &self.styles,
-                            num_fmt.as_deref(),
-                        )?;
+/// Read the sheet names and cached cell values of an external link part.
+/// Cells are typed like worksheet cells, except that `t="s"` and `t="str"`
+/// hold the text itself rather than a shared string index.
+fn read_external_link_cache(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+    part: &str,
+) -> Result<Vec<ExternalSheet>> {
+    let mut sheets: Vec<ExternalSheet> = Vec::new();
+    let xml = match archive.by_name(part) {
+        Ok(file) => file,
+        Err(_) => return Ok(sheets),
+    };
+    let mut reader = Reader::from_reader(BufReader::new(xml));
+    reader.config_mut().trim_text(true);
 
-                        if let Some(si) = shared_si {
-                            if let Some(f) = formula.as_ref() {
-                                let range = shared_ref.and_then(|r| parse_cell_range(&r));
-                                shared_formulas.entry(si).or_default().push((
-                                    f.clone(),
-                                    row,
-                                    col,
-                                    range,
-                                ));
-                            } else if let Some(defs) = shared_formulas.get(&si) {
-                                // Find matching definition by range
-                                let mut best_def = None;
-                                for def in defs {
-                                    if let Some((min_r, min_c, max_r, max_c)) = def.3
-                                        && row >= min_r
-                                        && row <= max_r
-                                        && col >= min_c
-                                        && col <= max_c
-                                    {
-                                        best_def = Some(def);
-                                        break;
-                                    }
-                                }
+    let mut buf = Vec::new();
+    let mut sheet_index = None;
+    // Position and type of the cell being read
+    let mut cell: Option<((u32, u32), String)> = None;
+    let mut in_value = false;
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                b"sheetName" => sheets.push(ExternalSheet {
+                    name: attr_value(&e, b"val")?.unwrap_or_default(),
+                    ..Default::default()
+                }),
+                b"sheetData" => {
+                    sheet_index =
+                        attr_value(&e, b"sheetId")?.and_then(|id| id.parse::<usize>().ok());
+                }
+                b"cell" => {
+                    let position = attr_value(&e, b"r")?.and_then(|r| parse_cell_ref(&r));
+                    let kind = attr_value(&e, b"t")?.unwrap_or_default();
+                    cell = position.map(|position| (position, kind));
+                }
+                b"v" => in_value = true,
+                _ => {}
+            },
+            Event::Text(e) if in_value => {
+                let text = e.unescape()?.to_string();
+                if let (Some((position, kind)), Some(sheet)) =
+                    (&cell, sheet_index.and_then(|index| sheets.get_mut(index)))
+                {
+                    let value = match kind.as_str() {
+                        "b" => CellValue::Boolean(text == "1"),
+                        "s" | "str" | "inlineStr" | "e" => CellValue::Text(text, None),
+                        _ => text
+                            .parse()
+                            .map(CellValue::Number)
+                            .unwrap_or(CellValue::Text(text, None)),
+                    };
+                    sheet.cells.insert(*position, value);
+                }
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"v" => in_value = false,
+                b"cell" => cell = None,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(sheets)
+}
 
-                                // Fallback to last definition if no range match (or no range provided)
-                                // This is risky but standard XML parsing often relies on latest def.
-                                // However, strictly speaking, correct one should match range.
-                                if best_def.is_none() {
-                                    best_def = defs.last();
-                                }
+fn translate_shared_formula(formula: &str, row_shift: i32, col_shift: i32) -> String {
+    thread_local! {
+        static RE: regex::Regex = regex::Regex::new(r"(?P<sheet>(?:'[^']+'|[A-Za-z0-9_\.\-]+)!)?(?P<col_abs>\$?)(?P<col>[A-Z]+)(?P<row_abs>\$?)(?P<row>[0-9]+)").unwrap();
+    }
+    RE.with(|re| {
+        re.replace_all(formula, |caps: &regex::Captures| {
+            let sheet = caps.name("sheet").map(|m| m.as_str()).unwrap_or("");
+            let col_abs = !caps.name("col_abs").unwrap().as_str().is_empty();
+            let col_str = caps.name("col").unwrap().as_str();
+            let row_abs = !caps.name("row_abs").unwrap().as_str().is_empty();
+            let row_str = caps.name("row").unwrap().as_str();
 
-                                if let Some((base_formula, base_row, base_col, _)) = best_def {
-                                    let row_shift = row as i32 - *base_row as i32;
-                                    let col_shift = col as i32 - *base_col as i32;
-                                    formula = Some(translate_shared_formula(
-                                        base_formula,
-                                        row_shift,
-                                        col_shift,
-                                    ));
-                                }
-                            }
-                        }
+            let mut col = 0u32;
+            for ch in col_str.chars() {
+                col = col * 26 + (ch.to_ascii_uppercase() as u32 - 'A' as u32 + 1);
+            }
+            col -= 1;
 
-                        let mut cell = Cell {
-                            row,
-                            col,
-                            value: value.clone(),
-                            num_fmt,
-                        };
-                        if let Some(mut f) = formula {
-                            if f.starts_with('=') {
+            let row = row_str.parse::<u32>().unwrap_or(1) - 1;
+
+            let new_row = if row_abs {
+                row
+            } else {
+                (row as i32 + row_shift).max(0) as u32
+            };
+            let new_col = if col_abs {
+                col
+            } else {
+                (col as i32 + col_shift).max(0) as u32
+            };
+
+            let mut result = sheet.to_string();
+            if col_abs {
+                result.push('$');
+            }
+
+            // Col to letter logic
+            let mut c = new_col + 1;
+            let mut col_letter = String::new();
+            while c > 0 {
+                let m = (c - 1) % 26;
+                col_letter.insert(0, (b'A' + m as u8) as char);
+                c = (c - m) / 26;
+            }
+            result.push_str(&col_letter);
+
+            if row_abs {
+                result.push('$');
+            }
+            result.push_str(&(new_row + 1).to_string());
+
+            result
+        })
+        .to_string()
+    })
+}
+
+impl<'a, R: std::io::Read + std::io::Seek> XlsxReader<'a, R> {
+    /// Parse one sheet, either fully or streaming its rows through `on_row`
+    fn load_sheet(&mut self, name: &str, on_row: Option<RowVisitor<'_>>) -> Result<Sheet> {
+        let path = get_xlsx_sheet_path(self.archive, name)?;
+        let hidden_sheets = self.read_hidden_sheets()?;
+        let mut sheet = Sheet::new(name.to_string());
+        sheet.sheet_path = Some(path.clone());
+        sheet.visible = !hidden_sheets.iter().any(|hidden| hidden == name);
+        sheet.view.active = extract_active_sheet_from_xlsx(self.archive)?.as_deref() == Some(name);
+        // Print areas and titles are sheet-local built-in names
+        for defined in read_defined_names_from_xlsx(self.archive)? {
+            if defined.scope.as_deref() != Some(name) {
+                continue;
+            }
+            match defined.name.as_str() {
+                "_xlnm.Print_Area" => sheet.page_setup.set_print_area(&defined.reference),
+                "_xlnm.Print_Titles" => sheet.page_setup.set_print_titles(&defined.reference),
+                _ => {}
+            }
+        }
+
+        // Comments live in separate parts; attach them to the parsed or streamed cells
+        let comments = extract_comments_from_xlsx(self.archive, &path)?;
+        (sheet.charts, sheet.images) = extract_drawings_from_xlsx(self.archive, &path)?;
+
+        // Parse sheet data
+        match on_row {
+            Some(visit) => {
+                let mut rows = CommentedRows::new(comments, visit);
+                self.parse_sheet_xml(
+                    &path,
+                    &mut sheet,
+                    Some(&mut |row, cells| rows.row(row, cells)),
+                )?;
+                rows.finish()?;
+            }
+            None => {
+                self.parse_sheet_xml(&path, &mut sheet, None)?;
+                attach_comments(&mut sheet.cells, comments);
+            }
+        }
+
+        Ok(sheet)
+    }
+
+    fn get_sheet_names(&mut self) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        let workbook_xml = self.archive.by_name("xl/workbook.xml")?;
+        let mut reader = Reader::from_reader(BufReader::new(workbook_xml));
+        reader.config_mut().trim_text(true);
+
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(e) | Event::Empty(e) => {
+                    if e.name().as_ref() == b"sheet" {
+                        for attr in e.attributes().flatten() {
+                            if attr.key.as_ref() == b"name" {
+                                names.push(attr.unescape_value()?.to_string());
+                            }
+                        }
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+        Ok(names)
+    }
+
+    /// Parse a worksheet part into `sheet`. Cells are collected into `sheet.cells`, or
+    /// handed to `on_row` one row at a time (and not kept) when a row visitor is given.
+    fn parse_sheet_xml(
+        &mut self,
+        path: &str,
+        sheet: &mut Sheet,
+        mut on_row: Option<RowVisitor<'_>>,
+    ) -> Result<()> {
+        let mut cells = CellStore::new();
+        let mut hidden_columns = Vec::new();
+        let mut hidden_rows = Vec::new();
+        let mut merged_cells = Vec::new();
+        let mut conditional_formats = Vec::new();
+        let mut array_formulas = Vec::new();
+        // x14 rules, and the ids of main rules that also have an x14 copy
+        let mut extension_formats: Vec<(Option<String>, ConditionalFormat)> = Vec::new();
+        let mut linked_ids = HashSet::new();
+        let mut dim_range = None;
+        let mut data_validations = Vec::new();
+        let mut hyperlinks = Vec::new();
+        let mut formula_hyperlinks = Vec::new();
+        let mut protection = None;
+        let mut columns = Vec::new();
+        let mut rows = Vec::new();
+        let (mut default_column_width, mut default_row_height) = (None, None);
+        let mut view = SheetView::default();
+        let mut views = 0;
+        let mut fit_to_page = false;
+        let mut auto_filter = None;
+        let mut sort = Vec::new();
+        let mut shared_formulas: HashMap<
+            u32,
+            Vec<(String, u32, u32, Option<(u32, u32, u32, u32)>)>,
+        > = HashMap::new();
+
+        // External hyperlink targets live in the sheet's relationships
+        let relationships = read_part_relationships(self.archive, path)?;
+
+        let sheet_xml = self.archive.by_name(path)?;
+        let mut reader = Reader::from_reader(BufReader::new(sheet_xml));
+        reader.config_mut().trim_text(true);
+
+        let mut buf = Vec::new();
+        let mut current_row = 0u32;
+        let mut current_col = 0u32;
+        let mut row_cells: Vec<Cell> = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(e) => match e.name().as_ref() {
+                    b"dimension" => {
+                        for attr in e.attributes().flatten() {
+                            if attr.key.as_ref() == b"ref" {
+                                let ref_str = attr.unescape_value()?;
+                                if let Some((_, _, end_row, end_col)) = parse_cell_range(&ref_str) {
+                                    // defined range is inclusive, so count is index + 1
+                                    dim_range = Some((end_row + 1, end_col + 1));
+                                } else if let Some((end_row, end_col)) = parse_cell_ref(&ref_str) {
+                                    // Single cell ref used as dimension? Rare but possible.
+                                    dim_range = Some((end_row + 1, end_col + 1));
+                                }
+                            }
+                        }
+                    }
+                    b"col" => {
+                        let (span, hidden) = read_xlsx_col(&e)?;
+                        if hidden {
+                            hidden_columns.extend(span.first..=span.last);
+                        }
+                        push_span(&mut columns, span);
+                    }
+                    b"sheetFormatPr" => {
+                        (default_column_width, default_row_height) = read_sheet_format(&e)?;
+                    }
+                    b"sheetView" | b"pane" | b"tabColor" => read_sheet_view_element(
+                        &e,
+                        &mut view,
+                        &mut views,
+                        &self.tables.theme_colors,
+                    )?,
+                    b"row" => {
+                        let (hidden, span) = read_xlsx_row(&e, &mut current_row)?;
+                        if hidden {
+                            hidden_rows.push(current_row);
+                        }
+                        push_span(&mut rows, span);
+                        current_col = 0;
+                    }
+                    b"c" => {
+                        let mut r_attr = String::new();
+                        let mut s_attr = None;
+                        let mut t_attr = String::new();
+                        // Cell metadata; Excel sets it on dynamic array formulas
+                        let mut has_metadata = false;
+                        for attr in e.attributes().flatten() {
+                            match attr.key.as_ref() {
+                                b"r" => r_attr = attr.unescape_value()?.to_string(),
+                                b"s" => s_attr = Some(attr.unescape_value()?.parse::<usize>()?),
+                                b"t" => t_attr = attr.unescape_value()?.to_string(),
+                                b"cm" => has_metadata = true,
+                                _ => {}
+                            }
+                        }
+
+                        let (row, col) = if !r_attr.is_empty() {
+                            let (r, c) =
+                                parse_cell_ref(&r_attr).unwrap_or((current_row, current_col));
+                            current_col = c + 1;
+                            (r, c)
+                        } else {
+                            let c = current_col;
+                            current_col += 1;
+                            (current_row, c)
+                        };
+
+                        let num_fmt = s_attr.and_then(|idx| self.tables.styles.get(idx).cloned());
+
+                        let (value, mut formula, attributes) = parse_cell_contents(
+                            &mut reader,
+                            &t_attr,
+                            &self.tables.shared_strings,
+                            &self.tables.theme_colors,
+                            num_fmt.as_deref(),
+                        )?;
+
+                        if attributes.array && formula.is_some() {
+                            let range = attributes
+                                .range
+                                .as_deref()
+                                .and_then(|r| parse_sqref(r).first().copied())
+                                // Corners may be written in either order
+                                .map(|(r1, c1, r2, c2)| {
+                                    (r1.min(r2), c1.min(c2), r1.max(r2), c1.max(c2))
+                                })
+                                .unwrap_or((row, col, row, col));
+                            array_formulas.push(ArrayFormula {
+                                row,
+                                col,
+                                range,
+                                dynamic: has_metadata,
+                            });
+                        }
+
+                        if let Some(si) = attributes.shared_index {
+                            if let Some(f) = formula.as_ref() {
+                                let range = attributes.range.and_then(|r| parse_cell_range(&r));
+                                shared_formulas.entry(si).or_default().push((
+                                    f.clone(),
+                                    row,
+                                    col,
+                                    range,
+                                ));
+                            } else if let Some(defs) = shared_formulas.get(&si) {
+                                // Find matching definition by range
+                                let mut best_def = None;
+                                for def in defs {
+                                    if let Some((min_r, min_c, max_r, max_c)) = def.3
+                                        && row >= min_r
+                                        && row <= max_r
+                                        && col >= min_c
+                                        && col <= max_c
+                                    {
+                                        best_def = Some(def);
+                                        break;
+                                    }
+                                }
+
+                                // Fallback to last definition if no range match (or no range provided)
+                                // This is risky but standard XML parsing often relies on latest def.
+                                // However, strictly speaking, correct one should match range.
+                                if best_def.is_none() {
+                                    best_def = defs.last();
+                                }
+
+                                if let Some((base_formula, base_row, base_col, _)) = best_def {
+                                    let row_shift = row as i32 - *base_row as i32;
+                                    let col_shift = col as i32 - *base_col as i32;
+                                    formula = Some(translate_shared_formula(
+                                        base_formula,
+                                        row_shift,
+                                        col_shift,
+                                    ));
+                                }
+                            }
+                        }
+
+                        let mut cell = Cell {
+                            row,
+                            col,
+                            value: value.clone(),
+                            num_fmt,
+                            style: s_attr,
+                            comment: None,
+                        };
+                        if let Some(mut f) = formula {
+                            if f.starts_with('=') {
                                 f = f[1..].to_string();
                             }
+                            if f.contains("_xl") {
+                                f = strip_future_function_prefixes(&f);
+                            }
                             cell.value = match cell.value {
                                 CellValue::Formula {
                                     cached_error: Some(err),
@@ -787,7 +1575,16 @@ impl<'a, R: std::io::Read + std::io::Seek> XlsxReader<'a, R> {
                                 _ => CellValue::formula(f),
                             };
                         }
-                        cells.insert((row, col), cell);
+                        if let Some(f) = cell.value.as_formula()
+                            && let Some(link) = Hyperlink::from_formula(row, col, f)
+                        {
+                            formula_hyperlinks.push(link);
+                        }
+                        if on_row.is_some() {
+                            row_cells.push(cell);
+                        } else {
+                            cells.insert((row, col), cell);
+                        }
                     }
                     b"mergeCell" => {
                         for attr in e.attributes().flatten() {
@@ -799,17 +1596,31 @@ impl<'a, R: std::io::Read + std::io::Seek> XlsxReader<'a, R> {
                             }
                         }
                     }
-                    b"cfRule" => {
-                        cf_count += 1;
-                    }
-                    b"conditionalFormatting" => {
-                        for attr in e.attributes().flatten() {
-                            if attr.key.as_ref() == b"sqref" {
-                                let sqref = attr.unescape_value()?;
-                                cf_ranges.push(sqref.to_string());
+                    // Also matches the x14:conditionalFormatting extension
+                    _ if e.local_name().as_ref() == b"conditionalFormatting" => {
+                        let extension = e.name().as_ref() != b"conditionalFormatting";
+                        for (id, rule) in read_conditional_formatting(&mut reader, &e)? {
+                            if extension {
+                                extension_formats.push((id, rule));
+                            } else {
+                                linked_ids.extend(id);
+                                conditional_formats.push(rule);
                             }
                         }
                     }
+                    // Also matches the x14:dataValidation extension used for cross-sheet lists
+                    _ if e.local_name().as_ref() == b"dataValidation" => {
+                        data_validations.push(read_data_validation(&mut reader, &e, true)?);
+                    }
+                    b"hyperlink" => {
+                        hyperlinks.extend(read_hyperlink(&e, &relationships)?);
+                    }
+                    b"sheetProtection" => protection = read_sheet_protection(&e)?,
+                    b"headerFooter" => {
+                        sheet.page_setup.headers_footers = read_header_footer(&mut reader)?;
+                    }
+                    b"autoFilter" => auto_filter = read_auto_filter(&mut reader, &e, true)?,
+                    b"sortState" => sort = read_sort_state(&mut reader)?,
 
                     _ => {}
                 },
@@ -827,30 +1638,21 @@ impl<'a, R: std::io::Read + std::io::Seek> XlsxReader<'a, R> {
                         }
                     }
                     b"col" => {
-                        let mut min = 0u32;
-                        let mut max = 0u32;
-                        let mut hidden = false;
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"min" => {
-                                    min = attr.unescape_value()?.parse::<u32>()?.saturating_sub(1);
-                                }
-                                b"max" => {
-                                    max = attr.unescape_value()?.parse::<u32>()?.saturating_sub(1);
-                                }
-                                b"hidden" => {
-                                    hidden = attr.value.as_ref() == b"1"
-                                        || attr.value.as_ref() == b"true";
-                                }
-                                _ => {}
-                            }
-                        }
+                        let (span, hidden) = read_xlsx_col(&e)?;
                         if hidden {
-                            for col in min..=max {
-                                hidden_columns.push(col);
-                            }
+                            hidden_columns.extend(span.first..=span.last);
                         }
+                        push_span(&mut columns, span);
                     }
+                    b"sheetFormatPr" => {
+                        (default_column_width, default_row_height) = read_sheet_format(&e)?;
+                    }
+                    b"sheetView" | b"pane" | b"tabColor" => read_sheet_view_element(
+                        &e,
+                        &mut view,
+                        &mut views,
+                        &self.tables.theme_colors,
+                    )?,
                     b"c" => {
                         let mut r_attr = String::new();
                         let mut s_attr = None;
@@ -871,25 +1673,28 @@ impl<'a, R: std::io::Read + std::io::Seek> XlsxReader<'a, R> {
                             current_col += 1;
                             (current_row, c)
                         };
-                        let num_fmt = s_attr.and_then(|idx| self.styles.get(idx).cloned());
-                        cells.insert(
-                            (row, col),
-                            Cell {
-                                row,
-                                col,
-                                value: CellValue::Empty,
-                                num_fmt,
-                            },
-                        );
+                        let num_fmt = s_attr.and_then(|idx| self.tables.styles.get(idx).cloned());
+                        let cell = Cell {
+                            row,
+                            col,
+                            value: CellValue::Empty,
+                            num_fmt,
+                            style: s_attr,
+                            comment: None,
+                        };
+                        if on_row.is_some() {
+                            row_cells.push(cell);
+                        } else {
+                            cells.insert((row, col), cell);
+                        }
                     }
                     b"row" => {
                         // Empty row tag
-                        for attr in e.attributes().flatten() {
-                            if attr.key.as_ref() == b"r" {
-                                current_row =
-                                    attr.unescape_value()?.parse::<u32>()?.saturating_sub(1);
-                            }
+                        let (hidden, span) = read_xlsx_row(&e, &mut current_row)?;
+                        if hidden {
+                            hidden_rows.push(current_row);
                         }
+                        push_span(&mut rows, span);
                         current_col = 0;
                     }
                     b"mergeCell" => {
@@ -902,25 +1707,31 @@ impl<'a, R: std::io::Read + std::io::Seek> XlsxReader<'a, R> {
                             }
                         }
                     }
-                    b"cfRule" => {
-                        cf_count += 1;
+                    _ if e.local_name().as_ref() == b"dataValidation" => {
+                        data_validations.push(read_data_validation(&mut reader, &e, false)?);
                     }
-                    b"conditionalFormatting" => {
-                        for attr in e.attributes().flatten() {
-                            if attr.key.as_ref() == b"sqref" {
-                                let sqref = attr.unescape_value()?;
-                                // Strict normalization reverted to preserve original format (e.g. A1 instead of A1:A1):
-                                cf_ranges.push(sqref.into_owned());
-                            }
-                        }
+                    b"hyperlink" => {
+                        hyperlinks.extend(read_hyperlink(&e, &relationships)?);
                     }
+                    b"sheetProtection" => protection = read_sheet_protection(&e)?,
+                    b"pageSetUpPr" | b"pageMargins" | b"pageSetup" => {
+                        read_page_setup_element(&e, &mut sheet.page_setup, &mut fit_to_page)?
+                    }
+                    b"autoFilter" => auto_filter = read_auto_filter(&mut reader, &e, false)?,
                     _ => {}
                 },
-                Event::End(e) => {
-                    if e.name().as_ref() == b"worksheet" {
-                        break;
+                Event::End(e) => match e.name().as_ref() {
+                    b"row" => {
+                        if let Some(visit) = on_row.as_mut()
+                            && !row_cells.is_empty()
+                        {
+                            visit(current_row, &row_cells)?;
+                            row_cells.clear();
+                        }
                     }
-                }
+                    b"worksheet" => break,
+                    _ => {}
+                },
                 Event::Eof => break,
                 _ => {}
             }
@@ -949,963 +1760,4246 @@ impl<'a, R: std::io::Read + std::io::Seek> XlsxReader<'a, R> {
             dim_range = Some((rows, cols));
         }
 
-        Ok((
-            cells,
-            hidden_columns,
-            hidden_rows,
-            merged_cells,
-            cf_count,
-            cf_ranges,
-            dim_range,
-        ))
+        // A sort state after the autofilter belongs to it
+        if let Some(filter) = auto_filter.as_mut()
+            && filter.sort.is_empty()
+        {
+            filter.sort = sort;
+        }
+        // Rows a filter hides are marked like rows hidden by hand; while it
+        // has criteria, take the hidden rows below its header as filtered
+        if let Some(filter) = auto_filter.as_ref().filter(|filter| filter.is_filtering()) {
+            (sheet.filtered_rows, hidden_rows) = hidden_rows
+                .into_iter()
+                .partition(|&row| filter.covers_row(row));
+        }
+
+        sheet.cells = cells;
+        sheet.hidden_columns = hidden_columns;
+        sheet.hidden_rows = hidden_rows;
+        sheet.auto_filter = auto_filter;
+        sheet.columns = columns;
+        sheet.rows = rows;
+        sheet.default_column_width = default_column_width;
+        sheet.default_row_height = default_row_height;
+        // Keep the active flag set by the caller
+        view.active = sheet.view.active;
+        sheet.view = view;
+        sheet.merged_cells = merged_cells;
+        // Excel 2010 writes some rules (data bars) both ways; keep the main copy
+        conditional_formats.extend(
+            extension_formats
+                .into_iter()
+                .filter(|(id, _)| id.as_ref().is_none_or(|id| !linked_ids.contains(id)))
+                .map(|(_, rule)| rule),
+        );
+        conditional_formats.sort_by_key(|rule: &ConditionalFormat| rule.priority);
+        sheet.conditional_formats = conditional_formats;
+        sheet.array_formulas = array_formulas;
+        sheet.data_validations = data_validations;
+        sheet.protection = protection;
+        sheet.used_range = dim_range;
+
+        // `<hyperlinks>` entries win over links computed by HYPERLINK() formulas
+        for link in formula_hyperlinks {
+            if !hyperlinks
+                .iter()
+                .any(|h: &Hyperlink| (h.row, h.col) == (link.row, link.col))
+            {
+                hyperlinks.push(link);
+            }
+        }
+        hyperlinks.sort_by_key(|link| (link.row, link.col));
+        sheet.hyperlinks = hyperlinks;
+
+        Ok(())
     }
 }
 
-fn parse_cell_contents<R: std::io::BufRead>(
-    reader: &mut Reader<R>,
-    t_attr: &str,
-    shared_strings: &[String],
-    _styles: &[String],
-    num_fmt: Option<&str>,
-) -> Result<(CellValue, Option<String>, Option<u32>, Option<String>)> {
-    let mut value = CellValue::Empty;
-    let mut formula = None;
-    let mut shared_si = None;
-    let mut shared_ref = None;
-    let mut potential_error = None; // Store potential error value from t="e"
-    let mut buf = Vec::new();
+/// Read a `<col>` element: the run of columns with its width and outline
+/// level, and whether the columns are hidden
+fn read_xlsx_col(e: &quick_xml::events::BytesStart) -> Result<(Span, bool)> {
+    let mut span = Span {
+        first: 0,
+        last: 0,
+        size: None,
+        outline_level: 0,
+    };
+    let mut hidden = false;
+    for attr in e.attributes().flatten() {
+        match attr.key.as_ref() {
+            b"min" => span.first = attr.unescape_value()?.parse::<u32>()?.saturating_sub(1),
+            b"max" => span.last = attr.unescape_value()?.parse::<u32>()?.saturating_sub(1),
+            b"width" => span.size = attr.unescape_value()?.parse().ok(),
+            b"outlineLevel" => span.outline_level = attr.unescape_value()?.parse().unwrap_or(0),
+            b"hidden" => hidden = attr.value.as_ref() == b"1" || attr.value.as_ref() == b"true",
+            _ => {}
+        }
+    }
+    span.last = span.last.max(span.first);
+    Ok((span, hidden))
+}
 
-    loop {
-        let event = reader.read_event_into(&mut buf)?;
-        match event {
-            Event::Start(ref e) | Event::Empty(ref e) => match e.name().as_ref() {
-                b"v" => {
-                    let v_text = if let Event::Start(_) = event {
-                        read_text_node(reader)?
-                    } else {
-                        String::new()
-                    };
-                    value = match t_attr {
-                        "s" => {
-                            let idx = v_text.parse::<usize>().unwrap_or(0);
-                            CellValue::Text(shared_strings.get(idx).cloned().unwrap_or_default())
-                        }
-                        "b" => CellValue::Boolean(v_text == "1"),
-                        "e" => {
-                            // Store the error value but don't create error cell yet
-                            // We need to check if there's a formula first
-                            potential_error = Some(v_text);
-                            CellValue::Empty // Temporary, will be set later if needed
-                        }
-                        _ => {
-                            // Check if this is a text-formatted number
-                            // In XLSX, text format is indicated by num_fmt == "@"
-                            if num_fmt == Some("@") {
-                                // Store as text even if it looks like a number
-                                CellValue::Text(v_text)
-                            } else if let Ok(n) = v_text.parse::<f64>() {
-                                CellValue::Number(n)
-                            } else {
-                                CellValue::Text(v_text)
-                            }
-                        }
-                    };
-                }
-                b"f" => {
-                    let mut si = None;
-                    let mut is_shared = false;
-                    for attr in e.attributes().flatten() {
-                        match attr.key.as_ref() {
-                            b"si" => {
-                                si = attr.unescape_value()?.parse::<u32>().ok();
-                            }
-                            b"t" => {
-                                if attr.value.as_ref() == b"shared" {
-                                    is_shared = true;
-                                }
-                            }
-                            b"ref" => {
-                                shared_ref = Some(attr.unescape_value()?.to_string());
-                            }
-                            _ => {}
-                        }
-                    }
+/// Read a `<row>` element, moving `row` to its index when given. Returns
+/// whether the row is hidden and its custom height and outline level.
+fn read_xlsx_row(e: &quick_xml::events::BytesStart, row: &mut u32) -> Result<(bool, Span)> {
+    let mut hidden = false;
+    let mut height = None;
+    let mut custom_height = false;
+    let mut outline_level = 0;
+    for attr in e.attributes().flatten() {
+        match attr.key.as_ref() {
+            b"r" => *row = attr.unescape_value()?.parse::<u32>()?.saturating_sub(1),
+            b"hidden" => hidden = attr.value.as_ref() == b"1" || attr.value.as_ref() == b"true",
+            b"ht" => height = attr.unescape_value()?.parse().ok(),
+            b"customHeight" => {
+                custom_height = attr.value.as_ref() == b"1" || attr.value.as_ref() == b"true"
+            }
+            b"outlineLevel" => outline_level = attr.unescape_value()?.parse().unwrap_or(0),
+            _ => {}
+        }
+    }
+    // Heights Excel fitted to the content aren't kept
+    let span = Span {
+        first: *row,
+        last: *row,
+        size: height.filter(|_| custom_height),
+        outline_level,
+    };
+    Ok((hidden, span))
+}
 
-                    if let Event::Start(_) = event {
-                        let f_text = read_text_node(reader)?;
-                        if !f_text.is_empty() {
-                            formula = Some(f_text);
-                        }
-                    }
+/// Read `<sheetFormatPr>`: the default column width and row height
+fn read_sheet_format(e: &quick_xml::events::BytesStart) -> Result<(Option<f64>, Option<f64>)> {
+    let mut column_width = None;
+    let mut row_height = None;
+    for attr in e.attributes().flatten() {
+        match attr.key.as_ref() {
+            b"defaultColWidth" => column_width = attr.unescape_value()?.parse().ok(),
+            b"defaultRowHeight" => row_height = attr.unescape_value()?.parse().ok(),
+            _ => {}
+        }
+    }
+    Ok((column_width, row_height))
+}
 
-                    if is_shared {
-                        shared_si = si;
-                    }
+/// Apply a `<sheetView>`, `<pane>` or `<tabColor>` element to `view`. A sheet
+/// can have a view per workbook window; only the first one (`views` == 1) counts.
+fn read_sheet_view_element(
+    e: &quick_xml::events::BytesStart,
+    view: &mut SheetView,
+    views: &mut u32,
+    theme: &[String],
+) -> Result<()> {
+    match e.name().as_ref() {
+        b"sheetView" => {
+            *views += 1;
+            if *views > 1 {
+                return Ok(());
+            }
+            for attr in e.attributes().flatten() {
+                let value = attr.unescape_value()?;
+                match attr.key.as_ref() {
+                    b"tabSelected" => view.selected = value == "1" || value == "true",
+                    b"zoomScale" => view.zoom = value.parse().unwrap_or(100),
+                    b"showGridLines" => view.show_gridlines = !(value == "0" || value == "false"),
+                    _ => {}
                 }
-                b"is" => {
-                    // Inline string can have multiple <t> tags
-                    if let Event::Start(_) = event {
-                        let mut is_text = String::new();
-                        let mut is_buf = Vec::new();
-                        loop {
-                            match reader.read_event_into(&mut is_buf)? {
-                                Event::Start(ref ee) if ee.name().as_ref() == b"t" => {
-                                    is_text.push_str(&read_text_node(reader)?);
-                                }
-                                Event::End(ref ee) if ee.name().as_ref() == b"is" => break,
-                                Event::Eof => break,
-                                _ => {}
-                            }
-                            is_buf.clear();
-                        }
-                        value = CellValue::Text(is_text);
-                    }
+            }
+        }
+        b"pane" if *views == 1 => {
+            let mut split = (0.0f64, 0.0f64);
+            let mut frozen = false;
+            for attr in e.attributes().flatten() {
+                let value = attr.unescape_value()?;
+                match attr.key.as_ref() {
+                    b"xSplit" => split.0 = value.parse().unwrap_or(0.0),
+                    b"ySplit" => split.1 = value.parse().unwrap_or(0.0),
+                    // Also "frozenSplit"; plain "split" panes are measured in twips
+                    b"state" => frozen = value.starts_with("frozen"),
+                    _ => {}
                 }
-                _ => {}
-            },
-            Event::End(e) if e.name().as_ref() == b"c" => break,
-            Event::Eof => break,
-            _ => {}
+            }
+            if frozen {
+                view.frozen_columns = split.0 as u32;
+                view.frozen_rows = split.1 as u32;
+            }
         }
-        buf.clear();
+        b"tabColor" => view.tab_color = read_xlsx_color(e, theme)?,
+        _ => {}
     }
+    Ok(())
+}
 
-    // Post-processing: handle potential_error
-    // Excel uses t="e" for TWO different purposes:
-    // 1. Real error cells (formula evaluated to error) - e.g., =car() -> #NAME?
-    // 2. Array formulas (returns array, not error) - e.g., =Sheet!A1:B10 or =A1:A10
-    //
-    // The key distinction: array formulas contain range references (with ':')
-    // Real errors are formulas without ranges that failed evaluation
-    //
-    // Note: In valid spreadsheet files, t="e" ALWAYS has a formula. Errors without
-    // formulas don't exist in Excel/ODS files.
-    if let Some(err) = potential_error
-        && let Some(ref f) = formula
-    {
-        // If t="e" is present with a formula, it's a formula that evaluated to an error
-        value = CellValue::formula_with_error(f.clone(), err);
+/// Apply a `<pageSetUpPr>`, `<pageMargins>` or `<pageSetup>` element to
+/// `setup`. `<pageSetUpPr>` comes first, inside `<sheetPr>`, and tells whether
+/// the fit-to-page counts of `<pageSetup>` apply instead of its scale.
+fn read_page_setup_element(
+    e: &quick_xml::events::BytesStart,
+    setup: &mut PageSetup,
+    fit_to_page: &mut bool,
+) -> Result<()> {
+    match e.name().as_ref() {
+        b"pageSetUpPr" => {
+            *fit_to_page = attr_value(e, b"fitToPage")?.is_some_and(|v| v == "1" || v == "true");
+        }
+        b"pageMargins" => {
+            let margin = |key: &[u8], default: f64| -> Result<f64> {
+                Ok(attr_value(e, key)?
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(default))
+            };
+            setup.margins = Some(Margins {
+                left: margin(b"left", 0.7)?,
+                right: margin(b"right", 0.7)?,
+                top: margin(b"top", 0.75)?,
+                bottom: margin(b"bottom", 0.75)?,
+                header: margin(b"header", 0.3)?,
+                footer: margin(b"footer", 0.3)?,
+            });
+        }
+        b"pageSetup" => {
+            setup.orientation = match attr_value(e, b"orientation")?.as_deref() {
+                Some("portrait") => Some(Orientation::Portrait),
+                Some("landscape") => Some(Orientation::Landscape),
+                _ => None,
+            };
+            let count = |key: &[u8]| -> Result<u32> {
+                Ok(attr_value(e, key)?
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(1))
+            };
+            if *fit_to_page {
+                setup.fit_to_pages = Some((count(b"fitToWidth")?, count(b"fitToHeight")?));
+            } else {
+                setup.scale = attr_value(e, b"scale")?.and_then(|v| v.parse().ok());
+            }
+        }
+        _ => {}
     }
-
-    Ok((value, formula, shared_si, shared_ref))
+    Ok(())
 }
 
-pub fn extract_shared_strings(
-    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
-) -> Result<Vec<String>> {
-    let mut strings = Vec::new();
-    let ss_xml = match archive.by_name("xl/sharedStrings.xml") {
-        Ok(file) => file,
-        Err(_) => return Ok(strings),
-    };
-
-    let mut reader = Reader::from_reader(BufReader::new(ss_xml));
-    reader.config_mut().trim_text(true);
+/// Read the headers and footers of a `<headerFooter>` element up to its end
+fn read_header_footer<R: std::io::BufRead>(reader: &mut Reader<R>) -> Result<Vec<HeaderFooter>> {
+    let mut headers_footers = Vec::new();
     let mut buf = Vec::new();
-    let mut current_string = String::new();
-
     loop {
         match reader.read_event_into(&mut buf)? {
-            Event::Start(e) if e.name().as_ref() == b"t" => {
-                current_string.push_str(&read_text_node(&mut reader)?);
-            }
-            Event::End(e) if e.name().as_ref() == b"si" => {
-                strings.push(current_string.clone());
-                current_string.clear();
+            Event::Start(e) => {
+                let (kind, pages) = match e.name().as_ref() {
+                    b"oddHeader" => (HeaderFooterKind::Header, HeaderFooterPages::All),
+                    b"oddFooter" => (HeaderFooterKind::Footer, HeaderFooterPages::All),
+                    b"evenHeader" => (HeaderFooterKind::Header, HeaderFooterPages::Even),
+                    b"evenFooter" => (HeaderFooterKind::Footer, HeaderFooterPages::Even),
+                    b"firstHeader" => (HeaderFooterKind::Header, HeaderFooterPages::First),
+                    b"firstFooter" => (HeaderFooterKind::Footer, HeaderFooterPages::First),
+                    _ => {
+                        buf.clear();
+                        continue;
+                    }
+                };
+                let text = read_text_node(reader)?;
+                if !text.is_empty() {
+                    headers_footers.push(HeaderFooter { kind, pages, text });
+                }
             }
+            Event::End(e) if e.name().as_ref() == b"headerFooter" => break,
             Event::Eof => break,
             _ => {}
         }
         buf.clear();
     }
-    Ok(strings)
+    Ok(headers_footers)
 }
 
-fn read_text_node<R: std::io::BufRead>(reader: &mut Reader<R>) -> Result<String> {
+/// Read an `<autoFilter>`: the range, each filtered column's criteria and a
+/// nested `<sortState>`. `has_children` is false for a self-closing element.
+fn read_auto_filter<R: std::io::BufRead>(
+    reader: &mut Reader<R>,
+    e: &quick_xml::events::BytesStart,
+    has_children: bool,
+) -> Result<Option<AutoFilter>> {
+    let mut filter = AutoFilter::default();
+    let mut range = None;
+    for attr in e.attributes().flatten() {
+        if attr.key.as_ref() == b"ref" {
+            let reference = attr.unescape_value()?;
+            range = parse_cell_range(&reference)
+                .or_else(|| parse_cell_ref(&reference).map(|(row, col)| (row, col, row, col)));
+        }
+    }
+    if has_children {
+        let mut buf = Vec::new();
+        let mut column = None;
+        let mut criteria = None;
+        loop {
+            let (e, has_children) = match reader.read_event_into(&mut buf)? {
+                Event::Start(e) => (e.into_owned(), true),
+                Event::Empty(e) => (e.into_owned(), false),
+                Event::End(e) => {
+                    match e.local_name().as_ref() {
+                        b"filterColumn" => {
+                            if let (Some(column), Some(criteria)) = (column.take(), criteria.take())
+                            {
+                                filter.columns.push(FilterColumn { column, criteria });
+                            }
+                        }
+                        b"autoFilter" => break,
+                        _ => {}
+                    }
+                    buf.clear();
+                    continue;
+                }
+                Event::Eof => break,
+                _ => {
+                    buf.clear();
+                    continue;
+                }
+            };
+            let attr = |key: &[u8]| attr_value(&e, key);
+            let flag = |key: &[u8], default: bool| -> Result<bool> {
+                Ok(attr(key)?.map_or(default, |value| value == "1" || value == "true"))
+            };
+            match e.local_name().as_ref() {
+                b"filterColumn" => {
+                    // colId counts from the range's first column
+                    let offset = attr(b"colId")?.and_then(|id| id.parse::<u32>().ok());
+                    column = offset.map(|offset| range.map_or(0, |(_, c1, _, _)| c1) + offset);
+                }
+                b"filters" => {
+                    criteria = Some(FilterCriteria::Values {
+                        values: Vec::new(),
+                        blank: flag(b"blank", false)?,
+                    });
+                }
+                b"filter" | b"dateGroupItem" => {
+                    let value = match e.local_name().as_ref() {
+                        b"filter" => attr(b"val")?,
+                        // Dates are picked by year, month, day...
+                        _ => {
+                            let mut parts = Vec::new();
+                            for key in [&b"year"[..], b"month", b"day"] {
+                                match attr(key)? {
+                                    Some(part) => parts.push(part),
+                                    None => break,
+                                }
+                            }
+                            (!parts.is_empty()).then(|| parts.join("-"))
+                        }
+                    };
+                    if let Some(FilterCriteria::Values { values, .. }) = criteria.as_mut() {
+                        values.extend(value);
+                    }
+                }
+                b"customFilters" => {
+                    criteria = Some(FilterCriteria::Custom {
+                        conditions: Vec::new(),
+                        and: flag(b"and", false)?,
+                    });
+                }
+                b"customFilter" => {
+                    let operator = attr(b"operator")?.map_or(Some(FilterOperator::Equal), |name| {
+                        FilterOperator::from_xlsx(&name)
+                    });
+                    if let (Some(operator), Some(FilterCriteria::Custom { conditions, .. })) =
+                        (operator, criteria.as_mut())
+                    {
+                        conditions.push(FilterCondition {
+                            operator,
+                            value: attr(b"val")?.unwrap_or_default(),
+                        });
+                    }
+                }
+                b"top10" => {
+                    criteria = Some(FilterCriteria::Top {
+                        count: attr(b"val")?
+                            .and_then(|val| val.parse().ok())
+                            .unwrap_or(10.0),
+                        percent: flag(b"percent", false)?,
+                        bottom: !flag(b"top", true)?,
+                    });
+                }
+                b"dynamicFilter" => {
+                    criteria = Some(FilterCriteria::Dynamic(attr(b"type")?.unwrap_or_default()));
+                }
+                b"colorFilter" => criteria = Some(FilterCriteria::Color),
+                b"iconFilter" => criteria = Some(FilterCriteria::Icon),
+                b"sortState" if has_children => filter.sort = read_sort_state(reader)?,
+                _ => {}
+            }
+            buf.clear();
+        }
+    }
+    Ok(range.map(|range| AutoFilter { range, ..filter }))
+}
+
+/// Read the `<sortCondition>`s of a `<sortState>` up to its end
+fn read_sort_state<R: std::io::BufRead>(reader: &mut Reader<R>) -> Result<Vec<SortKey>> {
+    let mut keys = Vec::new();
     let mut buf = Vec::new();
-    let mut text = String::new();
     loop {
         match reader.read_event_into(&mut buf)? {
-            Event::Text(e) => text.push_str(e.unescape()?.as_ref()),
-            Event::CData(e) => text.push_str(&String::from_utf8_lossy(e.as_ref())),
-            Event::End(_) => break,
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"sortCondition" => {
+                let mut column = None;
+                let mut descending = false;
+                for attr in e.attributes().flatten() {
+                    match attr.key.as_ref() {
+                        b"ref" => {
+                            let reference = attr.unescape_value()?;
+                            column = parse_cell_range(&reference)
+                                .map(|(_, col, _, _)| col)
+                                .or_else(|| parse_cell_ref(&reference).map(|(_, col)| col));
+                        }
+                        b"descending" => {
+                            descending = matches!(attr.value.as_ref(), b"1" | b"true");
+                        }
+                        _ => {}
+                    }
+                }
+                keys.extend(column.map(|column| SortKey { column, descending }));
+            }
+            Event::End(e) if e.local_name().as_ref() == b"sortState" => break,
             Event::Eof => break,
             _ => {}
         }
         buf.clear();
     }
-    Ok(text)
+    Ok(keys)
 }
-/// Count conditional formatting rules in a worksheet
-pub fn count_conditional_formatting(
-    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
-    sheet_name: &str,
-) -> Result<usize> {
-    let sheet_path = match get_xlsx_sheet_path(archive, sheet_name) {
-        Ok(path) => path,
-        Err(_) => return Ok(0),
-    };
 
-    let sheet_xml = match archive.by_name(&sheet_path) {
+/// Name of the sheet shown when the workbook opens, from the `activeTab` of
+/// the first `<workbookView>` (the first sheet when missing)
+pub fn extract_active_sheet_from_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<Option<String>> {
+    let workbook_xml = match archive.by_name("xl/workbook.xml") {
         Ok(file) => file,
-        Err(_) => return Ok(0), // No sheet file, return 0
+        Err(_) => return Ok(None),
     };
-
-    let buf_reader = BufReader::new(sheet_xml);
-    let mut reader = Reader::from_reader(buf_reader);
+    let mut reader = Reader::from_reader(BufReader::new(workbook_xml));
     reader.config_mut().trim_text(true);
 
     let mut buf = Vec::new();
-    let mut cf_count = 0;
-
+    let mut active_tab = None;
+    let mut names = Vec::new();
     loop {
-        match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
-                if e.name().as_ref() == b"cfRule" {
-                    cf_count += 1;
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) | Event::Empty(e) => match e.name().as_ref() {
+                b"workbookView" if active_tab.is_none() => {
+                    active_tab = Some(
+                        attr_value(&e, b"activeTab")?
+                            .and_then(|tab| tab.parse::<usize>().ok())
+                            .unwrap_or(0),
+                    );
                 }
-            }
-            Ok(Event::Eof) => break,
-            Err(e) => {
-                return Err(anyhow::anyhow!("XML parsing error: {}", e));
-            }
+                b"sheet" => names.extend(attr_value(&e, b"name")?),
+                _ => {}
+            },
+            Event::Eof => break,
             _ => {}
         }
         buf.clear();
     }
+    Ok(names.into_iter().nth(active_tab.unwrap_or(0)))
+}
 
-    Ok(cf_count)
+/// Read `<sheetProtection>`; the sheet is only protected when `sheet` is set
+fn read_sheet_protection(e: &quick_xml::events::BytesStart) -> Result<Option<SheetProtection>> {
+    if !attr_value(e, b"sheet")?.is_some_and(|v| v == "1" || v == "true") {
+        return Ok(None);
+    }
+    Ok(Some(SheetProtection {
+        password: read_password_hash(e, "")?,
+    }))
 }
 
-/// Check if XLSX file contains VBA macros
-pub fn has_vba_project_xlsx(
-    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
-) -> Result<bool> {
-    Ok(archive.by_name("xl/vbaProject.bin").is_ok())
+/// Read the password attributes of a protection element: the legacy
+/// `{prefix}Password` hash, or `{prefix}AlgorithmName` with its hash, salt and
+/// spin count. Without a prefix the names start in lower case (`password`).
+fn read_password_hash(
+    e: &quick_xml::events::BytesStart,
+    prefix: &str,
+) -> Result<Option<PasswordHash>> {
+    let key = |name: &str| {
+        if prefix.is_empty() {
+            let mut chars = name.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        } else {
+            format!("{}{}", prefix, name)
+        }
+    };
+
+    if let Some(algorithm) = attr_value(e, key("AlgorithmName").as_bytes())?
+        && let Some(value) = attr_value(e, key("HashValue").as_bytes())?
+    {
+        return Ok(Some(PasswordHash {
+            algorithm,
+            value,
+            salted: attr_value(e, key("SaltValue").as_bytes())?.is_some_and(|v| !v.is_empty()),
+            spin_count: attr_value(e, key("SpinCount").as_bytes())?
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+        }));
+    }
+    Ok(attr_value(e, key("Password").as_bytes())?
+        .filter(|v| !v.is_empty() && v != "0000")
+        .map(|v| PasswordHash::legacy(&v)))
 }
 
-/// Extract hidden sheets from XLSX file
-pub fn extract_hidden_sheets_from_xlsx(
+/// Read `<workbookProtection>` from workbook.xml
+pub fn extract_workbook_protection_from_xlsx(
     archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
-) -> Result<Vec<String>> {
-    let mut hidden_sheets = Vec::new();
-
-    // Try to read workbook.xml
+) -> Result<Option<WorkbookProtection>> {
     let workbook_xml = match archive.by_name("xl/workbook.xml") {
         Ok(file) => file,
-        Err(_) => return Ok(hidden_sheets), // No workbook.xml, return empty
+        Err(_) => return Ok(None),
     };
 
-    let buf_reader = BufReader::new(workbook_xml);
-    let mut reader = Reader::from_reader(buf_reader);
+    let mut reader = Reader::from_reader(BufReader::new(workbook_xml));
     reader.config_mut().trim_text(true);
 
     let mut buf = Vec::new();
-    let mut in_sheets = false;
-
     loop {
-        match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
-                match e.name().as_ref() {
-                    b"sheets" => in_sheets = true,
-                    b"sheet" if in_sheets => {
-                        let mut name = String::new();
-                        let mut state = String::new();
-
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"name" => {
-                                    name = attr.unescape_value()?.to_string();
-                                }
-                                b"state" => {
-                                    state = attr.unescape_value()?.to_string();
-                                }
-                                _ => {}
-                            }
-                        }
-
-                        // If state is "hidden" or "veryHidden", add to list
-                        if !name.is_empty() && (state == "hidden" || state == "veryHidden") {
-                            hidden_sheets.push(name);
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            Ok(Event::End(e)) => {
-                if e.name().as_ref() == b"sheets" {
-                    in_sheets = false;
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) | Event::Empty(e) => match e.name().as_ref() {
+                b"workbookProtection" => {
+                    let flag = |name: &[u8]| -> Result<bool> {
+                        Ok(attr_value(&e, name)?.is_some_and(|v| v == "1" || v == "true"))
+                    };
+                    let protection = WorkbookProtection {
+                        structure: flag(b"lockStructure")?,
+                        windows: flag(b"lockWindows")?,
+                        password: read_password_hash(&e, "workbook")?,
+                    };
+                    return Ok(protection.is_active().then_some(protection));
                 }
-            }
-            Ok(Event::Eof) => break,
-            Err(e) => return Err(anyhow::anyhow!("XML parsing error: {}", e)),
+                // workbookProtection precedes the sheet list
+                b"sheets" => break,
+                _ => {}
+            },
+            Event::Eof => break,
             _ => {}
         }
         buf.clear();
     }
 
-    Ok(hidden_sheets)
+    Ok(None)
 }
 
-/// Extract hidden columns and rows from a worksheet
-pub fn extract_hidden_columns_rows_from_xlsx(
+/// Read document properties from `docProps/core.xml`, `app.xml` and `custom.xml`
+pub fn extract_properties_from_xlsx(
     archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
-    sheet_name: &str,
-) -> Result<(Vec<u32>, Vec<u32>)> {
-    let mut hidden_columns = Vec::new();
-    let mut hidden_rows = Vec::new();
+) -> Result<DocumentProperties> {
+    let mut properties = DocumentProperties::default();
+
+    for (name, value) in read_property_part(archive, "docProps/core.xml")? {
+        match name.as_str() {
+            "title" => set_text(&mut properties.title, &value),
+            "subject" => set_text(&mut properties.subject, &value),
+            "description" => set_text(&mut properties.description, &value),
+            "keywords" => set_text(&mut properties.keywords, &value),
+            "creator" => set_text(&mut properties.author, &value),
+            "lastModifiedBy" => set_text(&mut properties.last_modified_by, &value),
+            "created" => properties.created = parse_timestamp(&value),
+            "modified" => properties.modified = parse_timestamp(&value),
+            _ => {}
+        }
+    }
+    for (name, value) in read_property_part(archive, "docProps/app.xml")? {
+        match name.as_str() {
+            "Company" => set_text(&mut properties.company, &value),
+            "Manager" => set_text(&mut properties.manager, &value),
+            "Template" => set_text(&mut properties.template, &value),
+            "Application" => set_text(&mut properties.application, &value),
+            _ => {}
+        }
+    }
+    properties
+        .custom
+        .extend(read_property_part(archive, "docProps/custom.xml")?);
 
-    let sheet_path = match get_xlsx_sheet_path(archive, sheet_name) {
-        Ok(path) => path,
-        Err(_) => return Ok((hidden_columns, hidden_rows)),
-    };
+    Ok(properties)
+}
 
-    let sheet_xml = match archive.by_name(&sheet_path) {
+/// Text content of a property part's elements, keyed by local name. Custom
+/// `<property name="...">` entries are keyed by their name instead.
+fn read_property_part(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+    path: &str,
+) -> Result<Vec<(String, String)>> {
+    let mut values = Vec::new();
+    let part = match archive.by_name(path) {
         Ok(file) => file,
-        Err(_) => return Ok((hidden_columns, hidden_rows)),
+        Err(_) => return Ok(values),
     };
 
-    let buf_reader = BufReader::new(sheet_xml);
-    let mut reader = Reader::from_reader(buf_reader);
+    let mut reader = Reader::from_reader(BufReader::new(part));
     reader.config_mut().trim_text(true);
 
+    let mut custom_name: Option<String> = None;
+    let mut element = String::new();
     let mut buf = Vec::new();
-
     loop {
-        match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
-                match e.name().as_ref() {
-                    b"col" => {
-                        // Column definition with hidden attribute
-                        let mut min_col = 0u32;
-                        let mut max_col = 0u32;
-                        let mut hidden = false;
-
-                        for attr in e.attributes() {
-                            if let Ok(attr) = attr {
-                                match attr.key.as_ref() {
-                                    b"min" => {
-                                        if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
-                                            min_col = val.saturating_sub(1); // Convert to 0-based
-                                        }
-                                    }
-                                    b"max" => {
-                                        if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
-                                            max_col = val.saturating_sub(1); // Convert to 0-based
-                                        }
-                                    }
-                                    b"hidden" => {
-                                        hidden = attr.unescape_value()? == "1"
-                                            || attr.unescape_value()?.to_lowercase() == "true";
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        }
-
-                        if hidden {
-                            for col in min_col..=max_col {
-                                hidden_columns.push(col);
-                            }
-                        }
-                    }
-                    b"row" => {
-                        // Row definition with hidden attribute
-                        let mut row_num = 0u32;
-                        let mut hidden = false;
-
-                        for attr in e.attributes() {
-                            if let Ok(attr) = attr {
-                                match attr.key.as_ref() {
-                                    b"r" => {
-                                        if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
-                                            row_num = val.saturating_sub(1); // Convert to 0-based
-                                        }
-                                    }
-                                    b"hidden" => {
-                                        hidden = attr.unescape_value()? == "1"
-                                            || attr.unescape_value()?.to_lowercase() == "true";
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        }
-
-                        if hidden {
-                            hidden_rows.push(row_num);
-                        }
-                    }
-                    _ => {}
-                }
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) if e.local_name().as_ref() == b"property" => {
+                custom_name = attr_value(&e, b"name")?;
             }
-            Ok(Event::Eof) => break,
-            Err(e) => return Err(anyhow::anyhow!("XML parsing error: {}", e)),
+            Event::Start(e) => {
+                element = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+            }
+            Event::Text(e) => {
+                let key = custom_name.clone().unwrap_or_else(|| element.clone());
+                values.push((key, e.unescape()?.to_string()));
+            }
+            Event::End(e) if e.local_name().as_ref() == b"property" => custom_name = None,
+            Event::End(_) => element.clear(),
+            Event::Eof => break,
             _ => {}
         }
         buf.clear();
     }
 
-    Ok((hidden_columns, hidden_rows))
+    Ok(values)
 }
 
-/// Extract merged cell ranges from a worksheet
-pub fn extract_merged_cells_from_xlsx(
-    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
-    sheet_name: &str,
-) -> Result<Vec<(u32, u32, u32, u32)>> {
-    let mut merged_cells = Vec::new();
+/// Read a `<hyperlink>` element, one link per cell of its `ref`. External targets
+/// come from the relationship named by `r:id`; `location` is a place in the
+/// workbook, or an anchor within the external target.
+fn read_hyperlink(
+    e: &quick_xml::events::BytesStart,
+    relationships: &[Relationship],
+) -> Result<Vec<Hyperlink>> {
+    let mut range = None;
+    let mut rel_id = None;
+    let mut location = None;
+    let mut display = None;
+    for attr in e.attributes().flatten() {
+        let value = attr.unescape_value()?.to_string();
+        match attr.key.as_ref() {
+            b"ref" => {
+                range = parse_cell_range(&value)
+                    .or_else(|| parse_cell_ref(&value).map(|(r, c)| (r, c, r, c)))
+            }
+            b"location" => location = Some(value),
+            b"display" => display = Some(value),
+            _ if attr.key.local_name().as_ref() == b"id" => rel_id = Some(value),
+            _ => {}
+        }
+    }
 
-    let sheet_path = match get_xlsx_sheet_path(archive, sheet_name) {
-        Ok(path) => path,
-        Err(_) => return Ok(merged_cells),
+    let target = match rel_id.and_then(|id| relationships.iter().find(|r| r.id == id)) {
+        Some(rel) => match location {
+            Some(location) => format!("{}#{}", rel.target, location),
+            None => rel.target.clone(),
+        },
+        None => match location {
+            Some(location) => format!("#{}", location),
+            None => return Ok(Vec::new()),
+        },
     };
-
-    let sheet_xml = match archive.by_name(&sheet_path) {
-        Ok(file) => file,
-        Err(_) => return Ok(merged_cells),
+    let Some((start_row, start_col, end_row, end_col)) = range else {
+        return Ok(Vec::new());
     };
 
-    let buf_reader = BufReader::new(sheet_xml);
-    let mut reader = Reader::from_reader(buf_reader);
-    reader.config_mut().trim_text(true);
+    let mut links = Vec::new();
+    for row in start_row..=end_row {
+        for col in start_col..=end_col {
+            links.push(Hyperlink::new(row, col, &target, display.clone()));
+        }
+    }
+    Ok(links)
+}
 
-    let mut buf = Vec::new();
+/// Read a `<dataValidation>` element; `has_body` when it was a start tag whose
+/// `formula1`/`formula2` (and, for the x14 form, `sqref`) children follow
+fn read_data_validation<R: std::io::BufRead>(
+    reader: &mut Reader<R>,
+    start: &quick_xml::events::BytesStart,
+    has_body: bool,
+) -> Result<DataValidation> {
+    let mut validation = DataValidation::default();
+    for attr in start.attributes().flatten() {
+        let value = attr.unescape_value()?;
+        match attr.key.as_ref() {
+            b"type" => validation.kind = ValidationKind::from_xlsx(&value),
+            b"operator" => validation.operator = ValidationOperator::from_xlsx(&value),
+            b"allowBlank" => validation.allow_blank = value == "1" || value == "true",
+            b"showErrorMessage" => validation.show_error = value == "1" || value == "true",
+            b"errorStyle" => validation.error_style = ValidationErrorStyle::parse(&value),
+            b"sqref" => validation.ranges = parse_sqref(&value),
+            _ => {}
+        }
+    }
+
+    if has_body {
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(e) => match e.local_name().as_ref() {
+                    b"formula1" => validation.formula1 = Some(read_text_node(reader)?),
+                    b"formula2" => validation.formula2 = Some(read_text_node(reader)?),
+                    b"sqref" => validation.ranges = parse_sqref(&read_text_node(reader)?),
+                    _ => {}
+                },
+                Event::End(e) if e.local_name().as_ref() == b"dataValidation" => break,
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+    }
+
+    // Operators and second bounds only mean something for comparisons
+    if matches!(
+        validation.kind,
+        ValidationKind::Any | ValidationKind::List | ValidationKind::Custom
+    ) {
+        validation.operator = ValidationOperator::default();
+        validation.formula2 = None;
+    } else if !matches!(
+        validation.operator,
+        ValidationOperator::Between | ValidationOperator::NotBetween
+    ) {
+        validation.formula2 = None;
+    }
+    Ok(validation)
+}
+
+/// Read a `<conditionalFormatting>` block (main or x14) into one entry per rule,
+/// with the id linking a main rule to its x14 copy (the `x14:id` child of the
+/// main rule, the `id` attribute of the x14 rule)
+fn read_conditional_formatting<R: std::io::BufRead>(
+    reader: &mut Reader<R>,
+    start: &quick_xml::events::BytesStart,
+) -> Result<Vec<(Option<String>, ConditionalFormat)>> {
+    let mut ranges = Vec::new();
+    for attr in start.attributes().flatten() {
+        if attr.key.as_ref() == b"sqref" {
+            ranges = parse_sqref(&attr.unescape_value()?);
+        }
+    }
 
+    let mut rules = Vec::new();
+    let mut buf = Vec::new();
     loop {
-        match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
-                if e.name().as_ref() == b"mergeCell" {
-                    for attr in e.attributes() {
-                        if let Ok(attr) = attr
-                            && attr.key.as_ref() == b"ref"
-                        {
-                            let ref_str = attr.unescape_value()?;
-                            if let Some((start_row, start_col, end_row, end_col)) =
-                                parse_cell_range(&ref_str)
-                            {
-                                merged_cells.push((start_row, start_col, end_row, end_col));
+        let event = reader.read_event_into(&mut buf)?;
+        match &event {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                b"cfRule" => {
+                    let mut rule = ConditionalFormat::default();
+                    let mut id = None;
+                    for attr in e.attributes().flatten() {
+                        let value = attr.unescape_value()?;
+                        match attr.key.as_ref() {
+                            b"type" => rule.kind = ConditionalFormatKind::from_xlsx(&value),
+                            b"operator" => {
+                                rule.operator = Some(ValidationOperator::from_xlsx(&value));
                             }
+                            b"priority" => rule.priority = value.parse().unwrap_or(0),
+                            b"stopIfTrue" => rule.stop_if_true = value == "1" || value == "true",
+                            b"id" => id = Some(value.into_owned()),
+                            _ => {}
                         }
                     }
+                    rules.push((id, rule));
                 }
-            }
-            Ok(Event::Eof) => break,
-            Err(e) => return Err(anyhow::anyhow!("XML parsing error: {}", e)),
+                b"formula" | b"f" if matches!(event, Event::Start(_)) => {
+                    let formula = read_text_node(reader)?;
+                    if let Some((_, rule)) = rules.last_mut() {
+                        rule.formulas.push(formula);
+                    }
+                }
+                b"id" if matches!(event, Event::Start(_)) => {
+                    let id = read_text_node(reader)?;
+                    if let Some((link, _)) = rules.last_mut() {
+                        *link = Some(id);
+                    }
+                }
+                b"cfvo" => {
+                    for attr in e.attributes().flatten() {
+                        if attr.key.as_ref() == b"val"
+                            && let Some((_, rule)) = rules.last_mut()
+                        {
+                            rule.formulas.push(attr.unescape_value()?.into_owned());
+                        }
+                    }
+                }
+                b"sqref" if matches!(event, Event::Start(_)) => {
+                    ranges = parse_sqref(&read_text_node(reader)?);
+                }
+                _ => {}
+            },
+            Event::End(e) if e.local_name().as_ref() == b"conditionalFormatting" => break,
+            Event::Eof => break,
             _ => {}
         }
         buf.clear();
     }
 
-    Ok(merged_cells)
-}
-
-/// Parse a cell range like "A1:B2" into (start_row, start_col, end_row, end_col)
-fn parse_cell_range(range: &str) -> Option<(u32, u32, u32, u32)> {
-    let parts: Vec<&str> = range.split(':').collect();
-    if parts.len() != 2 {
-        return None;
+    for (_, rule) in &mut rules {
+        rule.ranges = ranges.clone();
+        if rule.kind != ConditionalFormatKind::CellIs {
+            rule.operator = None;
+        }
     }
-
-    let (start_row, start_col) = parse_cell_ref(parts[0])?;
-    let (end_row, end_col) = parse_cell_ref(parts[1])?;
-
-    Some((start_row, start_col, end_row, end_col))
+    Ok(rules)
 }
 
-/// Parse a cell reference like "A1" into (row, col) as 0-based indices
-fn parse_cell_ref(cell_ref: &str) -> Option<(u32, u32)> {
-    let mut col = 0u32;
-    let mut row_str = String::new();
-
-    for ch in cell_ref.chars() {
-        if ch.is_ascii_alphabetic() {
-            col = col * 26 + (ch.to_ascii_uppercase() as u32 - 'A' as u32 + 1);
-        } else if ch.is_ascii_digit() {
-            row_str.push(ch);
+/// Remove the prefixes Excel writes before functions newer than the file
+/// format (`_xlfn.XLOOKUP`, `_xlfn._xlws.SORT`) and LAMBDA parameters
+/// (`_xlpm.x`), leaving the formula as Excel displays it
+fn strip_future_function_prefixes(formula: &str) -> String {
+    let mut result = String::with_capacity(formula.len());
+    let mut in_string = false;
+    let mut rest = formula;
+    while let Some(c) = rest.chars().next() {
+        if c == '"' {
+            in_string = !in_string;
+        } else if !in_string
+            && let Some(prefix) = ["_xlfn.", "_xlws.", "_xlpm."]
+                .into_iter()
+                .find(|prefix| rest.starts_with(prefix))
+        {
+            rest = &rest[prefix.len()..];
+            continue;
         }
+        result.push(c);
+        rest = &rest[c.len_utf8()..];
     }
+    result
+}
 
-    if row_str.is_empty() {
-        return None;
-    }
-
-    let row = row_str.parse::<u32>().ok()?;
-
-    // Convert to 0-based
-    Some((row.saturating_sub(1), col.saturating_sub(1)))
+/// Attributes of a cell's `<f>` element
+#[derive(Debug, Default)]
+struct FormulaAttributes {
+    /// Shared formula group of a `t="shared"` formula
+    shared_index: Option<u32>,
+    /// `ref`: the cells of a shared formula group, or the cells an array formula fills
+    range: Option<String>,
+    /// Whether this is an array formula (`t="array"`)
+    array: bool,
 }
 
-/// Extract cell formats from XLSX file
-/// Returns a list of format strings indexed by style index (xf index)
-pub fn extract_formats_from_xlsx(
-    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
-) -> Result<Vec<String>> {
-    let formats = Vec::new();
-    let mut num_fmts = HashMap::new();
+fn parse_cell_contents<R: std::io::BufRead>(
+    reader: &mut Reader<R>,
+    t_attr: &str,
+    shared_strings: &[(String, Option<Box<RichText>>)],
+    theme_colors: &[String],
+    num_fmt: Option<&str>,
+) -> Result<(CellValue, Option<String>, FormulaAttributes)> {
+    let mut value = CellValue::Empty;
+    let mut formula = None;
+    let mut attributes = FormulaAttributes::default();
+    let mut potential_error = None; // Store potential error value from t="e"
+    let mut buf = Vec::new();
 
-    // Built-in formats (simplified subset)
-    // See https://github.com/dtjohnson/xlsx-populate/blob/master/lib/NumFmt.js
-    num_fmts.insert(0, "General".to_string());
-    num_fmts.insert(1, "0".to_string());
-    num_fmts.insert(2, "0.00".to_string());
-    num_fmts.insert(3, "#,##0".to_string());
-    num_fmts.insert(4, "#,##0.00".to_string());
-    num_fmts.insert(9, "0%".to_string());
-    num_fmts.insert(10, "0.00%".to_string());
-    num_fmts.insert(11, "0.00E+00".to_string());
-    num_fmts.insert(12, "# ?/?".to_string());
-    num_fmts.insert(13, "# ??/??".to_string());
-    num_fmts.insert(14, "mm-dd-yy".to_string());
-    num_fmts.insert(15, "d-mmm-yy".to_string());
-    num_fmts.insert(16, "d-mmm".to_string());
-    num_fmts.insert(17, "mmm-yy".to_string());
-    num_fmts.insert(18, "h:mm AM/PM".to_string());
-    num_fmts.insert(19, "h:mm:ss AM/PM".to_string());
-    num_fmts.insert(20, "h:mm".to_string());
-    num_fmts.insert(21, "h:mm:ss".to_string());
-    num_fmts.insert(22, "m/d/yy h:mm".to_string());
-    num_fmts.insert(37, "#,##0 ;(#,##0)".to_string());
-    num_fmts.insert(38, "#,##0 ;[Red](#,##0)".to_string());
-    num_fmts.insert(39, "#,##0.00;(#,##0.00)".to_string());
-    num_fmts.insert(40, "#,##0.00;[Red](#,##0.00)".to_string());
-    num_fmts.insert(45, "mm:ss".to_string());
-    num_fmts.insert(46, "[h]:mm:ss".to_string());
-    num_fmts.insert(47, "mmss.0".to_string());
-    num_fmts.insert(48, "##0.0E+0".to_string());
-    num_fmts.insert(49, "@".to_string());
+    loop {
+        let event = reader.read_event_into(&mut buf)?;
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => match e.name().as_ref() {
+                b"v" => {
+                    let v_text = if let Event::Start(_) = event {
+                        read_text_node(reader)?
+                    } else {
+                        String::new()
+                    };
+                    value = match t_attr {
+                        "s" => {
+                            let idx = v_text.parse::<usize>().unwrap_or(0);
+                            let (text, formatting) =
+                                shared_strings.get(idx).cloned().unwrap_or_default();
+                            CellValue::Text(text, formatting)
+                        }
+                        "b" => CellValue::Boolean(v_text == "1"),
+                        "e" => {
+                            // Store the error value but don't create error cell yet
+                            // We need to check if there's a formula first
+                            potential_error = Some(v_text);
+                            CellValue::Empty // Temporary, will be set later if needed
+                        }
+                        _ => {
+                            // Check if this is a text-formatted number
+                            // In XLSX, text format is indicated by num_fmt == "@"
+                            if num_fmt == Some("@") {
+                                // Store as text even if it looks like a number
+                                CellValue::Text(v_text, None)
+                            } else if let Ok(n) = v_text.parse::<f64>() {
+                                CellValue::Number(n)
+                            } else {
+                                CellValue::Text(v_text, None)
+                            }
+                        }
+                    };
+                }
+                b"f" => {
+                    let mut si = None;
+                    let mut is_shared = false;
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"si" => {
+                                si = attr.unescape_value()?.parse::<u32>().ok();
+                            }
+                            b"t" => match attr.value.as_ref() {
+                                b"shared" => is_shared = true,
+                                b"array" => attributes.array = true,
+                                _ => {}
+                            },
+                            b"ref" => {
+                                attributes.range = Some(attr.unescape_value()?.to_string());
+                            }
+                            _ => {}
+                        }
+                    }
 
-    // Parse styles.xml
-    let styles_xml = match archive.by_name("xl/styles.xml") {
+                    if let Event::Start(_) = event {
+                        let f_text = read_text_node(reader)?;
+                        if !f_text.is_empty() {
+                            formula = Some(f_text);
+                        }
+                    }
+
+                    if is_shared {
+                        attributes.shared_index = si;
+                    }
+                }
+                b"is" => {
+                    // Inline strings are written like shared strings, runs included
+                    if let Event::Start(_) = event {
+                        reader.config_mut().trim_text(false);
+                        let item = read_string_item(reader, theme_colors);
+                        reader.config_mut().trim_text(true);
+                        let (text, formatting) = item?;
+                        value = CellValue::Text(text, formatting);
+                    }
+                }
+                _ => {}
+            },
+            Event::End(e) if e.name().as_ref() == b"c" => break,
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    // Post-processing: handle potential_error
+    // Excel uses t="e" for TWO different purposes:
+    // 1. Real error cells (formula evaluated to error) - e.g., =car() -> #NAME?
+    // 2. Array formulas (returns array, not error) - e.g., =Sheet!A1:B10 or =A1:A10
+    //
+    // The key distinction: array formulas contain range references (with ':')
+    // Real errors are formulas without ranges that failed evaluation
+    //
+    // Note: In valid spreadsheet files, t="e" ALWAYS has a formula. Errors without
+    // formulas don't exist in Excel/ODS files.
+    if let Some(err) = potential_error
+        && let Some(ref f) = formula
+    {
+        // If t="e" is present with a formula, it's a formula that evaluated to an error
+        value = CellValue::formula_with_error(f.clone(), err);
+    }
+
+    Ok((value, formula, attributes))
+}
+
+pub fn extract_shared_strings(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<Vec<(String, Option<Box<RichText>>)>> {
+    let theme = read_theme_colors(archive)?;
+    let mut strings = Vec::new();
+    let ss_xml = match archive.by_name("xl/sharedStrings.xml") {
         Ok(file) => file,
-        Err(_) => return Ok(formats), // No styles, return empty
+        Err(_) => return Ok(strings),
     };
 
-    let buf_reader = BufReader::new(styles_xml);
-    let mut reader = Reader::from_reader(buf_reader);
-    reader.config_mut().trim_text(true);
-
+    let mut reader = Reader::from_reader(BufReader::new(ss_xml));
+    // Spaces at the edges of a run are part of the text
+    reader.config_mut().trim_text(false);
     let mut buf = Vec::new();
 
     loop {
-        match reader.read_event_into(&mut buf) {
-            Ok(Event::Empty(e)) | Ok(Event::Start(e)) => {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) if e.name().as_ref() == b"si" => {
+                strings.push(read_string_item(&mut reader, &theme)?);
+            }
+            Event::Empty(e) if e.name().as_ref() == b"si" => {
+                strings.push((String::new(), None));
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(strings)
+}
+
+/// Read the rest of a shared (`<si>`) or inline (`<is>`) string: its plain
+/// text, and its runs and phonetic guides when it has any formatting
+fn read_string_item<R: std::io::BufRead>(
+    reader: &mut Reader<R>,
+    theme: &[String],
+) -> Result<(String, Option<Box<RichText>>)> {
+    let mut rich = RichText::default();
+    // Font of the `<r>` being read: `None` outside runs, `Some(None)` in a run
+    // without `<rPr>`
+    let mut run_font: Option<Option<Font>> = None;
+    let mut phonetic: Option<PhoneticRun> = None;
+    let mut buf = Vec::new();
+    loop {
+        let event = reader.read_event_into(&mut buf)?;
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let is_start = matches!(event, Event::Start(_));
                 match e.name().as_ref() {
-                    b"numFmt" => {
-                        let mut id = 0u32;
-                        let mut code = String::new();
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"numFmtId" => {
-                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
-                                        id = val;
-                                    }
+                    b"r" => run_font = Some(None),
+                    b"rPh" => {
+                        let offset = |key| -> Result<u32> {
+                            Ok(attr_value(e, key)?
+                                .and_then(|v| v.parse().ok())
+                                .unwrap_or(0))
+                        };
+                        phonetic = Some(PhoneticRun {
+                            text: String::new(),
+                            start: offset(b"sb")?,
+                            end: offset(b"eb")?,
+                        });
+                    }
+                    b"t" if is_start => {
+                        let text = read_text_node(reader)?;
+                        match (&mut phonetic, &run_font) {
+                            (Some(guide), _) => guide.text.push_str(&text),
+                            (None, Some(font)) => rich.push(&text, font.as_ref()),
+                            (None, None) => rich.push(&text, None),
+                        }
+                    }
+                    b"rPr" => run_font = Some(Some(Font::default())),
+                    name => {
+                        if let Some(Some(font)) = run_font.as_mut() {
+                            match name {
+                                b"b" => font.bold = flag_attr(e)?,
+                                b"i" => font.italic = flag_attr(e)?,
+                                b"strike" => font.strikethrough = flag_attr(e)?,
+                                b"u" => {
+                                    font.underline =
+                                        attr_value(e, b"val")?.is_none_or(|v| v != "none")
                                 }
-                                b"formatCode" => {
-                                    code =
-                                        attr.unescape_value().unwrap_or_default().replace('\\', "");
+                                b"sz" => {
+                                    font.size = attr_value(e, b"val")?.and_then(|v| v.parse().ok())
                                 }
+                                b"rFont" => font.name = attr_value(e, b"val")?,
+                                b"color" => font.color = read_xlsx_color(e, theme)?,
                                 _ => {}
                             }
                         }
-                        if !code.is_empty() {
-                            num_fmts.insert(id, code);
-                        }
-                    }
-                    b"xf" => {
-                        // We need to know if we are in cellXfs.
-                        // However, quick-xml is stream. styleSheet usually has <cellStyleXfs> then <cellXfs>.
-                        // We only care about <cellXfs>.
-                        // Determining if we are in cellXfs is tricky without state.
-                        // But usually cellXfs come after numFmts.
-                        // And cellStyleXfs also use `xf` tag.
-                        // We might need to track depth or parent.
-                        // Or simplistic: gather all xfs and hope cellXfs are processed in order?
-                        // No, `cellStyleXfs` are different.
-                        // I'll track `in_cell_xfs`.
                     }
-                    _ => {}
                 }
             }
-            Ok(Event::Eof) => break,
+            Event::End(ref e) => match e.name().as_ref() {
+                b"r" => run_font = None,
+                b"rPh" => rich.phonetic.extend(phonetic.take()),
+                b"si" | b"is" => break,
+                _ => {}
+            },
+            Event::Eof => break,
             _ => {}
         }
         buf.clear();
     }
+    Ok((rich.text(), rich.into_formatting()))
+}
 
-    // Reset reader to parse xfs properly
-    // ... Actually easier to do it in one pass if we track state.
-    // Let's rewrite the loop logic above.
-    Ok(Vec::new()) // Placeholder
+/// A relationship from an OPC part's `.rels` file
+#[derive(Debug, Clone)]
+pub struct Relationship {
+    pub id: String,
+    pub rel_type: String,
+    pub target: String,
+    /// `TargetMode="External"`: the target is a URL or path outside the package
+    pub external: bool,
 }
 
-/// Actual implementation of parsing styles
-pub fn parse_styles(
+/// Read the relationships of a package part such as `xl/worksheets/sheet1.xml`
+pub fn read_part_relationships(
     archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
-) -> Result<Vec<String>> {
-    let mut num_fmts = HashMap::new();
-
-    // Built-in formats (same as above)
-    num_fmts.insert(0, "General".to_string());
-    num_fmts.insert(1, "0".to_string());
-    num_fmts.insert(2, "0.00".to_string());
-    num_fmts.insert(3, "#,##0".to_string());
-    num_fmts.insert(4, "#,##0.00".to_string());
-    num_fmts.insert(9, "0%".to_string());
-    num_fmts.insert(10, "0.00%".to_string());
-    num_fmts.insert(11, "0.00E+00".to_string());
-    num_fmts.insert(12, "# ?/?".to_string());
-    num_fmts.insert(13, "# ??/??".to_string());
-    num_fmts.insert(14, "mm-dd-yy".to_string());
-    num_fmts.insert(15, "d-mmm-yy".to_string());
-    num_fmts.insert(16, "d-mmm".to_string());
-    num_fmts.insert(17, "mmm-yy".to_string());
-    num_fmts.insert(18, "h:mm AM/PM".to_string());
-    num_fmts.insert(19, "h:mm:ss AM/PM".to_string());
-    num_fmts.insert(20, "h:mm".to_string());
-    num_fmts.insert(21, "h:mm:ss".to_string());
-    num_fmts.insert(22, "m/d/yy h:mm".to_string());
-    num_fmts.insert(37, "#,##0 ;(#,##0)".to_string());
-    num_fmts.insert(38, "#,##0 ;[Red](#,##0)".to_string());
-    num_fmts.insert(39, "#,##0.00;(#,##0.00)".to_string());
-    num_fmts.insert(40, "#,##0.00;[Red](#,##0.00)".to_string());
-    num_fmts.insert(45, "mm:ss".to_string());
-    num_fmts.insert(46, "[h]:mm:ss".to_string());
-    num_fmts.insert(47, "mmss.0".to_string());
-    num_fmts.insert(48, "##0.0E+0".to_string());
-    num_fmts.insert(49, "@".to_string());
+    part_path: &str,
+) -> Result<Vec<Relationship>> {
+    let (dir, file) = part_path.rsplit_once('/').unwrap_or(("", part_path));
+    let rels_path = if dir.is_empty() {
+        format!("_rels/{}.rels", file)
+    } else {
+        format!("{}/_rels/{}.rels", dir, file)
+    };
 
-    let styles_xml = match archive.by_name("xl/styles.xml") {
+    let mut relationships = Vec::new();
+    let rels_xml = match archive.by_name(&rels_path) {
         Ok(file) => file,
-        Err(_) => return Ok(Vec::new()),
+        Err(_) => return Ok(relationships),
     };
-
-    let buf_reader = BufReader::new(styles_xml);
-    let mut reader = Reader::from_reader(buf_reader);
+    let mut reader = Reader::from_reader(BufReader::new(rels_xml));
     reader.config_mut().trim_text(true);
 
     let mut buf = Vec::new();
-    let mut xfs = Vec::new();
-    let mut in_cell_xfs = false;
-
     loop {
-        match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) => {
-                match e.name().as_ref() {
-                    b"numFmt" => {
-                        let mut id = 0u32;
-                        let mut code = String::new();
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"numFmtId" => {
-                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
-                                        id = val;
-                                    }
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"Relationship" => {
+                let mut rel = Relationship {
+                    id: String::new(),
+                    rel_type: String::new(),
+                    target: String::new(),
+                    external: false,
+                };
+                for attr in e.attributes().flatten() {
+                    match attr.key.as_ref() {
+                        b"Id" => rel.id = attr.unescape_value()?.to_string(),
+                        b"Type" => rel.rel_type = attr.unescape_value()?.to_string(),
+                        b"Target" => rel.target = attr.unescape_value()?.to_string(),
+                        b"TargetMode" => rel.external = attr.unescape_value()? == "External",
+                        _ => {}
+                    }
+                }
+                relationships.push(rel);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(relationships)
+}
+
+/// Resolve an internal relationship target against the part that owns it,
+/// e.g. `../comments1.xml` from `xl/worksheets/sheet1.xml` is `xl/comments1.xml`
+pub fn resolve_part_target(part_path: &str, target: &str) -> String {
+    if let Some(absolute) = target.strip_prefix('/') {
+        return absolute.to_string();
+    }
+    let mut segments: Vec<&str> = part_path.split('/').collect();
+    segments.pop();
+    for segment in target.split('/') {
+        match segment {
+            ".." => {
+                segments.pop();
+            }
+            "." | "" => {}
+            _ => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+/// Read the charts and pictures of a worksheet or chartsheet from its drawing parts
+pub fn extract_drawings_from_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+    sheet_path: &str,
+) -> Result<(Vec<Chart>, Vec<Image>)> {
+    let mut charts = Vec::new();
+    let mut images = Vec::new();
+
+    let drawings: Vec<String> = read_part_relationships(archive, sheet_path)?
+        .iter()
+        .filter(|r| !r.external && r.rel_type.ends_with("/drawing"))
+        .map(|r| resolve_part_target(sheet_path, &r.target))
+        .collect();
+    for drawing in drawings {
+        // Anchor and relationship id of each chart frame; anchor, relationship
+        // id and name of each picture
+        let mut chart_refs = Vec::new();
+        let mut picture_refs = Vec::new();
+        {
+            let drawing_xml = match archive.by_name(&drawing) {
+                Ok(file) => file,
+                Err(_) => continue,
+            };
+            let mut reader = Reader::from_reader(BufReader::new(drawing_xml));
+            reader.config_mut().trim_text(true);
+
+            let mut buf = Vec::new();
+            let mut anchor = (0u32, 0u32);
+            let mut in_from = false;
+            let mut name = String::new();
+            loop {
+                match reader.read_event_into(&mut buf) {
+                    Ok(Event::Start(e)) | Ok(Event::Empty(e)) => match e.local_name().as_ref() {
+                        // Absolute anchors (chart sheets) have no cell
+                        b"twoCellAnchor" | b"oneCellAnchor" | b"absoluteAnchor" => anchor = (0, 0),
+                        b"from" => in_from = true,
+                        b"row" if in_from => {
+                            anchor.0 = read_text_node(&mut reader)?.trim().parse().unwrap_or(0);
+                        }
+                        b"col" if in_from => {
+                            anchor.1 = read_text_node(&mut reader)?.trim().parse().unwrap_or(0);
+                        }
+                        b"cNvPr" => {
+                            for attr in e.attributes().flatten() {
+                                if attr.key.as_ref() == b"name" {
+                                    name = attr.unescape_value()?.to_string();
                                 }
-                                b"formatCode" => {
-                                    code =
-                                        attr.unescape_value().unwrap_or_default().replace('\\', "");
+                            }
+                        }
+                        b"chart" => {
+                            for attr in e.attributes().flatten() {
+                                if attr.key.as_ref() == b"r:id" {
+                                    chart_refs.push((anchor, attr.unescape_value()?.to_string()));
                                 }
-                                _ => {}
                             }
                         }
-                        if !code.is_empty() {
-                            num_fmts.insert(id, code);
+                        b"blip" => {
+                            for attr in e.attributes().flatten() {
+                                if attr.key.as_ref() == b"r:embed" {
+                                    picture_refs.push((
+                                        anchor,
+                                        attr.unescape_value()?.to_string(),
+                                        name.clone(),
+                                    ));
+                                }
+                            }
+                        }
+                        _ => {}
+                    },
+                    Ok(Event::End(e)) if e.local_name().as_ref() == b"from" => in_from = false,
+                    Ok(Event::Eof) => break,
+                    Err(_) => break,
+                    _ => {}
+                }
+                buf.clear();
+            }
+        }
+
+        let relationships = read_part_relationships(archive, &drawing)?;
+        let target = |id: &str| {
+            relationships
+                .iter()
+                .find(|rel| rel.id == id && !rel.external)
+                .map(|rel| resolve_part_target(&drawing, &rel.target))
+        };
+        for (anchor, id) in chart_refs {
+            if let Some(path) = target(&id) {
+                charts.push(Chart {
+                    anchor,
+                    ..parse_chart_xlsx(archive, &path)?
+                });
+            }
+        }
+        // Linked (external) pictures have no file in the package
+        for (anchor, id, name) in picture_refs {
+            if let Some(path) = target(&id) {
+                let size = archive
+                    .by_name(&path)
+                    .map(|file| file.compressed_size())
+                    .unwrap_or(0);
+                images.push(Image {
+                    name,
+                    anchor,
+                    path,
+                    size,
+                });
+            }
+        }
+    }
+
+    Ok((charts, images))
+}
+
+/// Read the plot type, title and series references of a chart part
+fn parse_chart_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+    path: &str,
+) -> Result<Chart> {
+    let mut chart = Chart::default();
+    let chart_xml = match archive.by_name(path) {
+        Ok(file) => file,
+        Err(_) => return Ok(chart),
+    };
+    let mut reader = Reader::from_reader(BufReader::new(chart_xml));
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut in_plot_area = false;
+    let mut title: Option<String> = None;
+    let mut series: Option<ChartSeries> = None;
+    // Element of the current series a `c:f` reference belongs to
+    let mut part: Option<Vec<u8>> = None;
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => match e.local_name().as_ref() {
+                b"plotArea" => in_plot_area = true,
+                // The chart title comes before the plot area; axis titles are inside it
+                b"title" if !in_plot_area && chart.title.is_none() => title = Some(String::new()),
+                b"t" if title.is_some() => {
+                    let text = read_text_node(&mut reader)?;
+                    title.get_or_insert_default().push_str(&text);
+                }
+                b"ser" if in_plot_area => series = Some(ChartSeries::default()),
+                name @ (b"tx" | b"cat" | b"xVal" | b"val" | b"yVal") if series.is_some() => {
+                    part = Some(name.to_vec());
+                }
+                b"f" => {
+                    if let (Some(series), Some(part)) = (series.as_mut(), part.as_deref()) {
+                        let reference = Some(read_text_node(&mut reader)?.trim().to_string());
+                        match part {
+                            b"tx" => series.name = reference,
+                            b"cat" | b"xVal" => series.categories = reference,
+                            _ => series.values = reference,
                         }
                     }
-                    b"cellXfs" => {
-                        in_cell_xfs = true;
+                }
+                name if in_plot_area && chart.kind.is_empty() => {
+                    if let Some(kind) = name.strip_suffix(b"Chart") {
+                        chart.kind = String::from_utf8_lossy(kind).to_string();
                     }
-                    b"xf" if in_cell_xfs => {
-                        // Extract numFmtId
-                        let mut num_fmt_id = 0u32;
-                        for attr in e.attributes() {
-                            if let Ok(attr) = attr
-                                && attr.key.as_ref() == b"numFmtId"
-                                && let Ok(val) = attr.unescape_value()?.parse::<u32>()
-                            {
-                                num_fmt_id = val;
+                }
+                _ => {}
+            },
+            Ok(Event::End(e)) => match e.local_name().as_ref() {
+                b"title" if title.is_some() => {
+                    chart.title = title.take().filter(|text| !text.is_empty());
+                }
+                b"plotArea" => in_plot_area = false,
+                b"ser" => chart.series.extend(series.take()),
+                b"tx" | b"cat" | b"xVal" | b"val" | b"yVal" => part = None,
+                _ => {}
+            },
+            Ok(Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(chart)
+}
+
+/// Read the notes and threaded comments of a worksheet, keyed by cell position.
+/// Threaded comments replace the placeholder notes Excel writes alongside them.
+pub fn extract_comments_from_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+    sheet_path: &str,
+) -> Result<BTreeMap<(u32, u32), Comment>> {
+    let relationships = read_part_relationships(archive, sheet_path)?;
+    let mut comments = BTreeMap::new();
+
+    for rel in relationships
+        .iter()
+        .filter(|r| !r.external && r.rel_type.ends_with("/comments"))
+    {
+        let path = resolve_part_target(sheet_path, &rel.target);
+        comments.extend(parse_legacy_comments(archive, &path)?);
+    }
+
+    let threaded: Vec<String> = relationships
+        .iter()
+        .filter(|r| !r.external && r.rel_type.ends_with("/threadedComment"))
+        .map(|r| resolve_part_target(sheet_path, &r.target))
+        .collect();
+    if !threaded.is_empty() {
+        let persons = extract_persons_from_xlsx(archive)?;
+        for path in threaded {
+            comments.extend(parse_threaded_comments(archive, &path, &persons)?);
+        }
+    }
+
+    Ok(comments)
+}
+
+/// Parse a legacy `comments*.xml` part
+fn parse_legacy_comments(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+    path: &str,
+) -> Result<Vec<((u32, u32), Comment)>> {
+    let mut comments = Vec::new();
+    let comments_xml = match archive.by_name(path) {
+        Ok(file) => file,
+        Err(_) => return Ok(comments),
+    };
+    // Whitespace inside text runs is significant, so text is not trimmed
+    let mut reader = Reader::from_reader(BufReader::new(comments_xml));
+
+    let mut buf = Vec::new();
+    let mut authors = Vec::new();
+    let mut current: Option<((u32, u32), Option<String>)> = None;
+    let mut text = String::new();
+    let mut in_phonetic = false;
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => match e.name().as_ref() {
+                b"author" => authors.push(read_text_node(&mut reader)?),
+                b"comment" => {
+                    let mut position = None;
+                    let mut author = None;
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"ref" => position = parse_cell_ref(&attr.unescape_value()?),
+                            b"authorId" => {
+                                author = attr
+                                    .unescape_value()?
+                                    .parse::<usize>()
+                                    .ok()
+                                    .and_then(|id| authors.get(id).cloned());
                             }
+                            _ => {}
                         }
-                        // Look up format code
-                        let format_code = num_fmts
-                            .get(&num_fmt_id)
-                            .cloned()
-                            .unwrap_or_else(|| "General".to_string());
-                        xfs.push(format_code);
                     }
-                    _ => {}
+                    current = position.map(|pos| (pos, author));
+                    text.clear();
                 }
-            }
-            Ok(Event::Empty(e)) => {
-                match e.name().as_ref() {
-                    b"numFmt" => {
-                        // Same as above, handle empty tag if it occurs (unlikely for numFmt with attrs)
-                        let mut id = 0u32;
-                        let mut code = String::new();
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"numFmtId" => {
-                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
-                                        id = val;
-                                    }
-                                }
-                                b"formatCode" => {
-                                    code =
-                                        attr.unescape_value().unwrap_or_default().replace('\\', "");
-                                }
-                                _ => {}
-                            }
+                // Phonetic runs repeat the text as readings
+                b"rPh" => in_phonetic = true,
+                b"t" if current.is_some() && !in_phonetic => {
+                    text.push_str(&read_text_node(&mut reader)?);
+                }
+                _ => {}
+            },
+            Event::End(e) => match e.name().as_ref() {
+                b"rPh" => in_phonetic = false,
+                b"comment" => {
+                    if let Some((position, author)) = current.take() {
+                        // Excel starts the note with a bold "Author:" line
+                        let mut body = text.as_str();
+                        if let Some(name) = &author
+                            && let Some(rest) = body
+                                .strip_prefix(name.as_str())
+                                .and_then(|r| r.strip_prefix(':'))
+                        {
+                            body = rest;
                         }
-                        if !code.is_empty() {
-                            num_fmts.insert(id, code);
+                        comments.push((
+                            position,
+                            Comment {
+                                author,
+                                text: body.trim().to_string(),
+                                ..Default::default()
+                            },
+                        ));
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(comments)
+}
+
+/// Map person ids to display names from `xl/persons/*.xml`
+fn extract_persons_from_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<HashMap<String, String>> {
+    let parts: Vec<String> = archive
+        .file_names()
+        .filter(|name| name.starts_with("xl/persons/") && name.ends_with(".xml"))
+        .map(str::to_string)
+        .collect();
+
+    let mut persons = HashMap::new();
+    for part in parts {
+        let person_xml = archive.by_name(&part)?;
+        let mut reader = Reader::from_reader(BufReader::new(person_xml));
+        reader.config_mut().trim_text(true);
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf)? {
+                Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"person" => {
+                    let mut id = String::new();
+                    let mut name = String::new();
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"id" => id = attr.unescape_value()?.to_string(),
+                            b"displayName" => name = attr.unescape_value()?.to_string(),
+                            _ => {}
                         }
                     }
-                    b"xf" if in_cell_xfs => {
-                        let mut num_fmt_id = 0u32;
-                        for attr in e.attributes() {
-                            if let Ok(attr) = attr
-                                && attr.key.as_ref() == b"numFmtId"
-                                && let Ok(val) = attr.unescape_value()?.parse::<u32>()
-                            {
-                                num_fmt_id = val;
-                            }
+                    persons.insert(id, name);
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+    }
+    Ok(persons)
+}
+
+/// Parse a `threadedComments*.xml` part, nesting replies under their thread
+fn parse_threaded_comments(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+    path: &str,
+    persons: &HashMap<String, String>,
+) -> Result<Vec<((u32, u32), Comment)>> {
+    let threads_xml = match archive.by_name(path) {
+        Ok(file) => file,
+        Err(_) => return Ok(Vec::new()),
+    };
+    let mut reader = Reader::from_reader(BufReader::new(threads_xml));
+
+    // (position, id, parent id, comment) in document order
+    type Entry = ((u32, u32), String, Option<String>, Comment);
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut current: Option<Entry> = None;
+
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) if e.name().as_ref() == b"threadedComment" => {
+                let mut position = None;
+                let mut id = String::new();
+                let mut parent = None;
+                let mut comment = Comment {
+                    threaded: true,
+                    ..Default::default()
+                };
+                for attr in e.attributes().flatten() {
+                    match attr.key.as_ref() {
+                        b"ref" => position = parse_cell_ref(&attr.unescape_value()?),
+                        b"id" => id = attr.unescape_value()?.to_string(),
+                        b"parentId" => parent = Some(attr.unescape_value()?.to_string()),
+                        b"dT" => comment.date = Some(attr.unescape_value()?.to_string()),
+                        b"personId" => {
+                            comment.author = persons.get(attr.unescape_value()?.as_ref()).cloned();
                         }
-                        let format_code = num_fmts
-                            .get(&num_fmt_id)
-                            .cloned()
-                            .unwrap_or_else(|| "General".to_string());
-                        xfs.push(format_code);
+                        _ => {}
                     }
-                    _ => {}
                 }
+                current = position.map(|pos| (pos, id, parent, comment));
             }
-            Ok(Event::End(e)) => {
-                if e.name().as_ref() == b"cellXfs" {
-                    in_cell_xfs = false;
+            Event::Start(e) if e.name().as_ref() == b"text" => {
+                let text = read_text_node(&mut reader)?;
+                if let Some((_, _, _, comment)) = current.as_mut() {
+                    comment.text = text.trim().to_string();
                 }
             }
-            Ok(Event::Eof) => break,
+            Event::End(e) if e.name().as_ref() == b"threadedComment" => {
+                entries.extend(current.take());
+            }
+            Event::Eof => break,
             _ => {}
         }
         buf.clear();
     }
 
-    Ok(xfs)
+    let mut threads: Vec<((u32, u32), String, Comment)> = Vec::new();
+    for (position, id, parent, comment) in entries {
+        match parent.and_then(|p| threads.iter_mut().find(|(_, id, _)| *id == p)) {
+            Some((_, _, root)) => root.replies.push(comment),
+            None => threads.push((position, id, comment)),
+        }
+    }
+    Ok(threads
+        .into_iter()
+        .map(|(position, _, comment)| (position, comment))
+        .collect())
 }
 
-/// Extract cell style indices from a worksheet
-pub fn extract_cell_style_indices_from_xlsx(
+fn read_text_node<R: std::io::BufRead>(reader: &mut Reader<R>) -> Result<String> {
+    let mut buf = Vec::new();
+    let mut text = String::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Text(e) => text.push_str(e.unescape()?.as_ref()),
+            Event::CData(e) => text.push_str(&String::from_utf8_lossy(e.as_ref())),
+            Event::End(_) => break,
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(text)
+}
+/// Count conditional formatting rules in a worksheet
+pub fn count_conditional_formatting(
     archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
     sheet_name: &str,
-) -> Result<HashMap<(u32, u32), usize>> {
-    let mut cell_styles = HashMap::new();
+) -> Result<usize> {
+    let sheet_path = match get_xlsx_sheet_path(archive, sheet_name) {
+        Ok(path) => path,
+        Err(_) => return Ok(0),
+    };
+
+    let sheet_xml = match archive.by_name(&sheet_path) {
+        Ok(file) => file,
+        Err(_) => return Ok(0), // No sheet file, return 0
+    };
+
+    let buf_reader = BufReader::new(sheet_xml);
+    let mut reader = Reader::from_reader(buf_reader);
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut cf_count = 0;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                if e.name().as_ref() == b"cfRule" {
+                    cf_count += 1;
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(anyhow::anyhow!("XML parsing error: {}", e));
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(cf_count)
+}
+
+/// Check if XLSX file contains VBA macros
+pub fn has_vba_project_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<bool> {
+    Ok(archive.by_name("xl/vbaProject.bin").is_ok())
+}
+
+/// Read the modules of `xl/vbaProject.bin`
+pub fn extract_macros_from_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<Vec<MacroModule>> {
+    let mut data = Vec::new();
+    match archive.by_name("xl/vbaProject.bin") {
+        Ok(mut file) => std::io::Read::read_to_end(&mut file, &mut data)?,
+        Err(_) => return Ok(Vec::new()),
+    };
+    // A damaged project still counts for `has_macros`; its source just isn't
+    // available
+    Ok(super::vba::read_vba_project(&data).unwrap_or_default())
+}
+
+/// Read the date system from the `date1904` attribute of `<workbookPr>` in workbook.xml
+pub fn extract_date_system_from_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<DateSystem> {
+    let workbook_xml = match archive.by_name("xl/workbook.xml") {
+        Ok(file) => file,
+        Err(_) => return Ok(DateSystem::default()),
+    };
+
+    let mut reader = Reader::from_reader(BufReader::new(workbook_xml));
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => match e.name().as_ref() {
+                b"workbookPr" => {
+                    for attr in e.attributes().flatten() {
+                        if attr.key.as_ref() == b"date1904" {
+                            let value = attr.unescape_value()?;
+                            if value == "1" || value == "true" {
+                                return Ok(DateSystem::Excel1904);
+                            }
+                        }
+                    }
+                    break;
+                }
+                // workbookPr precedes the sheet list
+                b"sheets" => break,
+                _ => {}
+            },
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(anyhow::anyhow!("XML parsing error: {}", e)),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(DateSystem::Excel1900)
+}
+
+/// Extract hidden sheets from XLSX file
+pub fn extract_hidden_sheets_from_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<Vec<String>> {
+    let mut hidden_sheets = Vec::new();
+
+    // Try to read workbook.xml
+    let workbook_xml = match archive.by_name("xl/workbook.xml") {
+        Ok(file) => file,
+        Err(_) => return Ok(hidden_sheets), // No workbook.xml, return empty
+    };
+
+    let buf_reader = BufReader::new(workbook_xml);
+    let mut reader = Reader::from_reader(buf_reader);
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut in_sheets = false;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                match e.name().as_ref() {
+                    b"sheets" => in_sheets = true,
+                    b"sheet" if in_sheets => {
+                        let mut name = String::new();
+                        let mut state = String::new();
+
+                        for attr in e.attributes().flatten() {
+                            match attr.key.as_ref() {
+                                b"name" => {
+                                    name = attr.unescape_value()?.to_string();
+                                }
+                                b"state" => {
+                                    state = attr.unescape_value()?.to_string();
+                                }
+                                _ => {}
+                            }
+                        }
+
+                        // If state is "hidden" or "veryHidden", add to list
+                        if !name.is_empty() && (state == "hidden" || state == "veryHidden") {
+                            hidden_sheets.push(name);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::End(e)) => {
+                if e.name().as_ref() == b"sheets" {
+                    in_sheets = false;
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(anyhow::anyhow!("XML parsing error: {}", e)),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(hidden_sheets)
+}
+
+/// Extract hidden columns and rows from a worksheet
+pub fn extract_hidden_columns_rows_from_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+    sheet_name: &str,
+) -> Result<(Vec<u32>, Vec<u32>)> {
+    let mut hidden_columns = Vec::new();
+    let mut hidden_rows = Vec::new();
+
+    let sheet_path = match get_xlsx_sheet_path(archive, sheet_name) {
+        Ok(path) => path,
+        Err(_) => return Ok((hidden_columns, hidden_rows)),
+    };
+
+    let sheet_xml = match archive.by_name(&sheet_path) {
+        Ok(file) => file,
+        Err(_) => return Ok((hidden_columns, hidden_rows)),
+    };
+
+    let buf_reader = BufReader::new(sheet_xml);
+    let mut reader = Reader::from_reader(buf_reader);
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                match e.name().as_ref() {
+                    b"col" => {
+                        // Column definition with hidden attribute
+                        let mut min_col = 0u32;
+                        let mut max_col = 0u32;
+                        let mut hidden = false;
+
+                        for attr in e.attributes() {
+                            if let Ok(attr) = attr {
+                                match attr.key.as_ref() {
+                                    b"min" => {
+                                        if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
+                                            min_col = val.saturating_sub(1); // Convert to 0-based
+                                        }
+                                    }
+                                    b"max" => {
+                                        if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
+                                            max_col = val.saturating_sub(1); // Convert to 0-based
+                                        }
+                                    }
+                                    b"hidden" => {
+                                        hidden = attr.unescape_value()? == "1"
+                                            || attr.unescape_value()?.to_lowercase() == "true";
+                                    }
+                                    _ => {}
+                                }
+                            }
+                        }
+
+                        if hidden {
+                            for col in min_col..=max_col {
+                                hidden_columns.push(col);
+                            }
+                        }
+                    }
+                    b"row" => {
+                        // Row definition with hidden attribute
+                        let mut row_num = 0u32;
+                        let mut hidden = false;
+
+                        for attr in e.attributes() {
+                            if let Ok(attr) = attr {
+                                match attr.key.as_ref() {
+                                    b"r" => {
+                                        if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
+                                            row_num = val.saturating_sub(1); // Convert to 0-based
+                                        }
+                                    }
+                                    b"hidden" => {
+                                        hidden = attr.unescape_value()? == "1"
+                                            || attr.unescape_value()?.to_lowercase() == "true";
+                                    }
+                                    _ => {}
+                                }
+                            }
+                        }
+
+                        if hidden {
+                            hidden_rows.push(row_num);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(anyhow::anyhow!("XML parsing error: {}", e)),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok((hidden_columns, hidden_rows))
+}
+
+/// Extract merged cell ranges from a worksheet
+pub fn extract_merged_cells_from_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+    sheet_name: &str,
+) -> Result<Vec<(u32, u32, u32, u32)>> {
+    let mut merged_cells = Vec::new();
+
+    let sheet_path = match get_xlsx_sheet_path(archive, sheet_name) {
+        Ok(path) => path,
+        Err(_) => return Ok(merged_cells),
+    };
+
+    let sheet_xml = match archive.by_name(&sheet_path) {
+        Ok(file) => file,
+        Err(_) => return Ok(merged_cells),
+    };
+
+    let buf_reader = BufReader::new(sheet_xml);
+    let mut reader = Reader::from_reader(buf_reader);
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                if e.name().as_ref() == b"mergeCell" {
+                    for attr in e.attributes() {
+                        if let Ok(attr) = attr
+                            && attr.key.as_ref() == b"ref"
+                        {
+                            let ref_str = attr.unescape_value()?;
+                            if let Some((start_row, start_col, end_row, end_col)) =
+                                parse_cell_range(&ref_str)
+                            {
+                                merged_cells.push((start_row, start_col, end_row, end_col));
+                            }
+                        }
+                    }
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(anyhow::anyhow!("XML parsing error: {}", e)),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(merged_cells)
+}
+
+/// Parse a cell range like "A1:B2" into (start_row, start_col, end_row, end_col)
+fn parse_cell_range(range: &str) -> Option<(u32, u32, u32, u32)> {
+    let parts: Vec<&str> = range.split(':').collect();
+    if parts.len() != 2 {
+        return None;
+    }
+
+    let (start_row, start_col) = parse_cell_ref(parts[0])?;
+    let (end_row, end_col) = parse_cell_ref(parts[1])?;
+
+    Some((start_row, start_col, end_row, end_col))
+}
+
+/// Parse a space-separated list of cells and ranges like "A1:A10 C3"
+fn parse_sqref(sqref: &str) -> Vec<(u32, u32, u32, u32)> {
+    sqref
+        .split_whitespace()
+        .filter_map(|part| {
+            parse_cell_range(part).or_else(|| parse_cell_ref(part).map(|(r, c)| (r, c, r, c)))
+        })
+        .collect()
+}
+
+/// Parse a cell reference like "A1" into (row, col) as 0-based indices
+fn parse_cell_ref(cell_ref: &str) -> Option<(u32, u32)> {
+    let mut col = 0u32;
+    let mut row_str = String::new();
+
+    for ch in cell_ref.chars() {
+        if ch.is_ascii_alphabetic() {
+            col = col * 26 + (ch.to_ascii_uppercase() as u32 - 'A' as u32 + 1);
+        } else if ch.is_ascii_digit() {
+            row_str.push(ch);
+        }
+    }
+
+    if row_str.is_empty() {
+        return None;
+    }
+
+    let row = row_str.parse::<u32>().ok()?;
+
+    // Convert to 0-based
+    Some((row.saturating_sub(1), col.saturating_sub(1)))
+}
+
+/// Extract cell formats from XLSX file
+/// Returns a list of format strings indexed by style index (xf index)
+pub fn extract_formats_from_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<Vec<String>> {
+    let formats = Vec::new();
+    let mut num_fmts = HashMap::new();
+
+    // Built-in formats (simplified subset)
+    // See https://github.com/dtjohnson/xlsx-populate/blob/master/lib/NumFmt.js
+    num_fmts.insert(0, "General".to_string());
+    num_fmts.insert(1, "0".to_string());
+    num_fmts.insert(2, "0.00".to_string());
+    num_fmts.insert(3, "#,##0".to_string());
+    num_fmts.insert(4, "#,##0.00".to_string());
+    num_fmts.insert(9, "0%".to_string());
+    num_fmts.insert(10, "0.00%".to_string());
+    num_fmts.insert(11, "0.00E+00".to_string());
+    num_fmts.insert(12, "# ?/?".to_string());
+    num_fmts.insert(13, "# ??/??".to_string());
+    num_fmts.insert(14, "mm-dd-yy".to_string());
+    num_fmts.insert(15, "d-mmm-yy".to_string());
+    num_fmts.insert(16, "d-mmm".to_string());
+    num_fmts.insert(17, "mmm-yy".to_string());
+    num_fmts.insert(18, "h:mm AM/PM".to_string());
+    num_fmts.insert(19, "h:mm:ss AM/PM".to_string());
+    num_fmts.insert(20, "h:mm".to_string());
+    num_fmts.insert(21, "h:mm:ss".to_string());
+    num_fmts.insert(22, "m/d/yy h:mm".to_string());
+    num_fmts.insert(37, "#,##0 ;(#,##0)".to_string());
+    num_fmts.insert(38, "#,##0 ;[Red](#,##0)".to_string());
+    num_fmts.insert(39, "#,##0.00;(#,##0.00)".to_string());
+    num_fmts.insert(40, "#,##0.00;[Red](#,##0.00)".to_string());
+    num_fmts.insert(45, "mm:ss".to_string());
+    num_fmts.insert(46, "[h]:mm:ss".to_string());
+    num_fmts.insert(47, "mmss.0".to_string());
+    num_fmts.insert(48, "##0.0E+0".to_string());
+    num_fmts.insert(49, "@".to_string());
+
+    // Parse styles.xml
+    let styles_xml = match archive.by_name("xl/styles.xml") {
+        Ok(file) => file,
+        Err(_) => return Ok(formats), // No styles, return empty
+    };
+
+    let buf_reader = BufReader::new(styles_xml);
+    let mut reader = Reader::from_reader(buf_reader);
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Empty(e)) | Ok(Event::Start(e)) => {
+                match e.name().as_ref() {
+                    b"numFmt" => {
+                        let mut id = 0u32;
+                        let mut code = String::new();
+                        for attr in e.attributes().flatten() {
+                            match attr.key.as_ref() {
+                                b"numFmtId" => {
+                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
+                                        id = val;
+                                    }
+                                }
+                                b"formatCode" => {
+                                    code =
+                                        attr.unescape_value().unwrap_or_default().replace('\\', "");
+                                }
+                                _ => {}
+                            }
+                        }
+                        if !code.is_empty() {
+                            num_fmts.insert(id, code);
+                        }
+                    }
+                    b"xf" => {
+                        // We need to know if we are in cellXfs.
+                        // However, quick-xml is stream. styleSheet usually has <cellStyleXfs> then <cellXfs>.
+                        // We only care about <cellXfs>.
+                        // Determining if we are in cellXfs is tricky without state.
+                        // But usually cellXfs come after numFmts.
+                        // And cellStyleXfs also use `xf` tag.
+                        // We might need to track depth or parent.
+                        // Or simplistic: gather all xfs and hope cellXfs are processed in order?
+                        // No, `cellStyleXfs` are different.
+                        // I'll track `in_cell_xfs`.
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    // Reset reader to parse xfs properly
+    // ... Actually easier to do it in one pass if we track state.
+    // Let's rewrite the loop logic above.
+    Ok(Vec::new()) // Placeholder
+}
+
+/// Actual implementation of parsing styles
+pub fn parse_styles(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<Vec<String>> {
+    let mut num_fmts = HashMap::new();
+
+    // Built-in formats (same as above)
+    num_fmts.insert(0, "General".to_string());
+    num_fmts.insert(1, "0".to_string());
+    num_fmts.insert(2, "0.00".to_string());
+    num_fmts.insert(3, "#,##0".to_string());
+    num_fmts.insert(4, "#,##0.00".to_string());
+    num_fmts.insert(9, "0%".to_string());
+    num_fmts.insert(10, "0.00%".to_string());
+    num_fmts.insert(11, "0.00E+00".to_string());
+    num_fmts.insert(12, "# ?/?".to_string());
+    num_fmts.insert(13, "# ??/??".to_string());
+    num_fmts.insert(14, "mm-dd-yy".to_string());
+    num_fmts.insert(15, "d-mmm-yy".to_string());
+    num_fmts.insert(16, "d-mmm".to_string());
+    num_fmts.insert(17, "mmm-yy".to_string());
+    num_fmts.insert(18, "h:mm AM/PM".to_string());
+    num_fmts.insert(19, "h:mm:ss AM/PM".to_string());
+    num_fmts.insert(20, "h:mm".to_string());
+    num_fmts.insert(21, "h:mm:ss".to_string());
+    num_fmts.insert(22, "m/d/yy h:mm".to_string());
+    num_fmts.insert(37, "#,##0 ;(#,##0)".to_string());
+    num_fmts.insert(38, "#,##0 ;[Red](#,##0)".to_string());
+    num_fmts.insert(39, "#,##0.00;(#,##0.00)".to_string());
+    num_fmts.insert(40, "#,##0.00;[Red](#,##0.00)".to_string());
+    num_fmts.insert(45, "mm:ss".to_string());
+    num_fmts.insert(46, "[h]:mm:ss".to_string());
+    num_fmts.insert(47, "mmss.0".to_string());
+    num_fmts.insert(48, "##0.0E+0".to_string());
+    num_fmts.insert(49, "@".to_string());
+
+    let styles_xml = match archive.by_name("xl/styles.xml") {
+        Ok(file) => file,
+        Err(_) => return Ok(Vec::new()),
+    };
+
+    let buf_reader = BufReader::new(styles_xml);
+    let mut reader = Reader::from_reader(buf_reader);
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut xfs = Vec::new();
+    let mut in_cell_xfs = false;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                match e.name().as_ref() {
+                    b"numFmt" => {
+                        let mut id = 0u32;
+                        let mut code = String::new();
+                        for attr in e.attributes().flatten() {
+                            match attr.key.as_ref() {
+                                b"numFmtId" => {
+                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
+                                        id = val;
+                                    }
+                                }
+                                b"formatCode" => {
+                                    code =
+                                        attr.unescape_value().unwrap_or_default().replace('\\', "");
+                                }
+                                _ => {}
+                            }
+                        }
+                        if !code.is_empty() {
+                            num_fmts.insert(id, code);
+                        }
+                    }
+                    b"cellXfs" => {
+                        in_cell_xfs = true;
+                    }
+                    b"xf" if in_cell_xfs => {
+                        // Extract numFmtId
+                        let mut num_fmt_id = 0u32;
+                        for attr in e.attributes() {
+                            if let Ok(attr) = attr
+                                && attr.key.as_ref() == b"numFmtId"
+                                && let Ok(val) = attr.unescape_value()?.parse::<u32>()
+                            {
+                                num_fmt_id = val;
+                            }
+                        }
+                        // Look up format code
+                        let format_code = num_fmts
+                            .get(&num_fmt_id)
+                            .cloned()
+                            .unwrap_or_else(|| "General".to_string());
+                        xfs.push(format_code);
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Empty(e)) => {
+                match e.name().as_ref() {
+                    b"numFmt" => {
+                        // Same as above, handle empty tag if it occurs (unlikely for numFmt with attrs)
+                        let mut id = 0u32;
+                        let mut code = String::new();
+                        for attr in e.attributes().flatten() {
+                            match attr.key.as_ref() {
+                                b"numFmtId" => {
+                                    if let Ok(val) = attr.unescape_value()?.parse::<u32>() {
+                                        id = val;
+                                    }
+                                }
+                                b"formatCode" => {
+                                    code =
+                                        attr.unescape_value().unwrap_or_default().replace('\\', "");
+                                }
+                                _ => {}
+                            }
+                        }
+                        if !code.is_empty() {
+                            num_fmts.insert(id, code);
+                        }
+                    }
+                    b"xf" if in_cell_xfs => {
+                        let mut num_fmt_id = 0u32;
+                        for attr in e.attributes() {
+                            if let Ok(attr) = attr
+                                && attr.key.as_ref() == b"numFmtId"
+                                && let Ok(val) = attr.unescape_value()?.parse::<u32>()
+                            {
+                                num_fmt_id = val;
+                            }
+                        }
+                        let format_code = num_fmts
+                            .get(&num_fmt_id)
+                            .cloned()
+                            .unwrap_or_else(|| "General".to_string());
+                        xfs.push(format_code);
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::End(e)) => {
+                if e.name().as_ref() == b"cellXfs" {
+                    in_cell_xfs = false;
+                }
+            }
+            Ok(Event::Eof) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(xfs)
+}
+
+/// Parse the cell style table: one [`CellStyle`] per `cellXfs` record, with its
+/// font, fill and border resolved and theme/indexed colors turned into RGB
+pub fn parse_cell_styles(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<Vec<CellStyle>> {
+    /// Section of styles.xml whose records are being read
+    #[derive(PartialEq)]
+    enum Section {
+        Other,
+        Fonts,
+        Fills,
+        Borders,
+        CellXfs,
+    }
+
+    let theme = read_theme_colors(archive)?;
+    let styles_xml = match archive.by_name("xl/styles.xml") {
+        Ok(file) => file,
+        Err(_) => return Ok(Vec::new()),
+    };
+    let mut reader = Reader::from_reader(BufReader::new(styles_xml));
+    reader.config_mut().trim_text(true);
+
+    let mut fonts = Vec::new();
+    let mut fills = Vec::new();
+    let mut borders = Vec::new();
+    // (fontId, fillId, borderId, alignment, protection) per cellXfs record
+    let mut xfs = Vec::new();
+
+    let mut section = Section::Other;
+    let mut font = Font::default();
+    let mut fill_pattern = None;
+    let mut fill_color = None;
+    let mut border = Border::default();
+    let mut edge: Option<(Vec<u8>, BorderLine)> = None;
+    let mut xf = (
+        0usize,
+        0usize,
+        0usize,
+        Alignment::default(),
+        Protection::default(),
+    );
+
+    let mut buf = Vec::new();
+    loop {
+        let event = reader.read_event_into(&mut buf)?;
+        let is_empty = matches!(event, Event::Empty(_));
+        match event {
+            Event::Start(e) | Event::Empty(e) => {
+                let name = e.name();
+                match (&section, name.as_ref()) {
+                    (_, b"fonts") => section = Section::Fonts,
+                    (_, b"fills") => section = Section::Fills,
+                    (_, b"borders") => section = Section::Borders,
+                    (_, b"cellXfs") => section = Section::CellXfs,
+                    (_, b"cellStyleXfs" | b"dxfs" | b"cellStyles" | b"colors") => {
+                        section = Section::Other
+                    }
+
+                    (Section::Fonts, b"font") => {
+                        font = Font::default();
+                        if is_empty {
+                            fonts.push(std::mem::take(&mut font));
+                        }
+                    }
+                    (Section::Fonts, b"b") => font.bold = flag_attr(&e)?,
+                    (Section::Fonts, b"i") => font.italic = flag_attr(&e)?,
+                    (Section::Fonts, b"strike") => font.strikethrough = flag_attr(&e)?,
+                    (Section::Fonts, b"u") => {
+                        font.underline = attr_value(&e, b"val")?.is_none_or(|v| v != "none")
+                    }
+                    (Section::Fonts, b"sz") => {
+                        font.size = attr_value(&e, b"val")?.and_then(|v| v.parse().ok())
+                    }
+                    (Section::Fonts, b"name") => font.name = attr_value(&e, b"val")?,
+                    (Section::Fonts, b"color") => font.color = read_xlsx_color(&e, &theme)?,
+
+                    (Section::Fills, b"fill") => {
+                        fill_pattern = None;
+                        fill_color = None;
+                    }
+                    (Section::Fills, b"patternFill") => {
+                        fill_pattern = attr_value(&e, b"patternType")?;
+                    }
+                    (Section::Fills, b"fgColor") => fill_color = read_xlsx_color(&e, &theme)?,
+                    // Gradient fills are approximated by their first stop
+                    (Section::Fills, b"gradientFill") => fill_pattern = Some("gradient".into()),
+                    (Section::Fills, b"color") if fill_color.is_none() => {
+                        fill_color = read_xlsx_color(&e, &theme)?
+                    }
+
+                    (Section::Borders, b"border") => {
+                        border = Border::default();
+                        if is_empty {
+                            borders.push(std::mem::take(&mut border));
+                        }
+                    }
+                    (
+                        Section::Borders,
+                        b"left" | b"right" | b"top" | b"bottom" | b"start" | b"end",
+                    ) => {
+                        if let Some(style) = attr_value(&e, b"style")?.filter(|s| s != "none") {
+                            let line = BorderLine { style, color: None };
+                            if is_empty {
+                                set_border_edge(&mut border, name.as_ref(), line);
+                            } else {
+                                edge = Some((name.as_ref().to_vec(), line));
+                            }
+                        }
+                    }
+                    (Section::Borders, b"color") => {
+                        if let Some((_, line)) = edge.as_mut() {
+                            line.color = read_xlsx_color(&e, &theme)?;
+                        }
+                    }
+
+                    (Section::CellXfs, b"xf") => {
+                        xf = (0, 0, 0, Alignment::default(), Protection::default());
+                        for attr in e.attributes().flatten() {
+                            let value = attr.unescape_value()?.parse::<usize>().unwrap_or(0);
+                            match attr.key.as_ref() {
+                                b"fontId" => xf.0 = value,
+                                b"fillId" => xf.1 = value,
+                                b"borderId" => xf.2 = value,
+                                _ => {}
+                            }
+                        }
+                        if is_empty {
+                            xfs.push(xf.clone());
+                        }
+                    }
+                    (Section::CellXfs, b"alignment") => {
+                        for attr in e.attributes().flatten() {
+                            let value = attr.unescape_value()?;
+                            match attr.key.as_ref() {
+                                b"horizontal" if value != "general" => {
+                                    xf.3.horizontal = Some(value.to_string())
+                                }
+                                b"vertical" if value != "bottom" => {
+                                    xf.3.vertical = Some(value.to_string())
+                                }
+                                b"wrapText" => xf.3.wrap_text = value == "1" || value == "true",
+                                b"shrinkToFit" => {
+                                    xf.3.shrink_to_fit = value == "1" || value == "true"
+                                }
+                                b"indent" => xf.3.indent = value.parse().unwrap_or(0),
+                                b"textRotation" => {
+                                    // 91-180 count clockwise from 90; 255 is stacked text
+                                    xf.3.rotation = match value.parse::<i32>().unwrap_or(0) {
+                                        r @ 0..=90 => r,
+                                        r @ 91..=180 => 90 - r,
+                                        _ => 0,
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                    (Section::CellXfs, b"protection") => {
+                        for attr in e.attributes().flatten() {
+                            let value = attr.unescape_value()?;
+                            match attr.key.as_ref() {
+                                b"locked" => xf.4.locked = value == "1" || value == "true",
+                                b"hidden" => xf.4.hidden = value == "1" || value == "true",
+                                _ => {}
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(e) => match (&section, e.name().as_ref()) {
+                (_, b"fonts" | b"fills" | b"borders" | b"cellXfs") => section = Section::Other,
+                (Section::Fonts, b"font") => fonts.push(std::mem::take(&mut font)),
+                (Section::Fills, b"fill") => fills.push(Fill {
+                    color: match fill_pattern.as_deref() {
+                        None | Some("none") => None,
+                        _ => fill_color.take(),
+                    },
+                }),
+                (Section::Borders, b"border") => borders.push(std::mem::take(&mut border)),
+                (Section::Borders, _) => {
+                    if let Some((edge_name, line)) = edge.take() {
+                        set_border_edge(&mut border, &edge_name, line);
+                    }
+                }
+                (Section::CellXfs, b"xf") => xfs.push(xf.clone()),
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(xfs
+        .into_iter()
+        .map(
+            |(font_id, fill_id, border_id, alignment, protection)| CellStyle {
+                font: fonts.get(font_id).cloned().unwrap_or_default(),
+                fill: fills.get(fill_id).cloned().unwrap_or_default(),
+                border: borders.get(border_id).cloned().unwrap_or_default(),
+                alignment,
+                protection,
+            },
+        )
+        .collect())
+}
+
+/// Boolean element such as `<b/>` or `<b val="0"/>`
+fn flag_attr(e: &quick_xml::events::BytesStart) -> Result<bool> {
+    Ok(attr_value(e, b"val")?.is_none_or(|v| v != "0" && v != "false"))
+}
+
+fn attr_value(e: &quick_xml::events::BytesStart, key: &[u8]) -> Result<Option<String>> {
+    for attr in e.attributes().flatten() {
+        if attr.key.as_ref() == key {
+            return Ok(Some(attr.unescape_value()?.to_string()));
+        }
+    }
+    Ok(None)
+}
+
+fn set_border_edge(border: &mut Border, edge: &[u8], line: BorderLine) {
+    match edge {
+        b"left" | b"start" => border.left = Some(line),
+        b"right" | b"end" => border.right = Some(line),
+        b"top" => border.top = Some(line),
+        b"bottom" => border.bottom = Some(line),
+        _ => {}
+    }
+}
+
+/// Resolve a `<color>`-like element (`rgb`, `theme` with `tint`, `indexed`, `auto`)
+fn read_xlsx_color(e: &quick_xml::events::BytesStart, theme: &[String]) -> Result<Option<String>> {
+    let mut color = None;
+    let mut tint = 0.0;
+    for attr in e.attributes().flatten() {
+        let value = attr.unescape_value()?;
+        match attr.key.as_ref() {
+            b"rgb" => color = parse_hex_color(&value),
+            b"theme" => {
+                color = value
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| theme.get(i).cloned())
+            }
+            b"indexed" => color = value.parse::<usize>().ok().and_then(indexed_color),
+            b"tint" => tint = value.parse().unwrap_or(0.0),
+            _ => {}
+        }
+    }
+    Ok(color.map(|rgb| {
+        if tint == 0.0 {
+            rgb
+        } else {
+            apply_tint(&rgb, tint)
+        }
+    }))
+}
+
+/// Colors of the legacy indexed palette; 64 and 65 are the system foreground
+/// and background
+fn indexed_color(index: usize) -> Option<String> {
+    const PALETTE: [u32; 56] = [
+        0x000000, 0xFFFFFF, 0xFF0000, 0x00FF00, 0x0000FF, 0xFFFF00, 0xFF00FF, 0x00FFFF, 0x800000,
+        0x008000, 0x000080, 0x808000, 0x800080, 0x008080, 0xC0C0C0, 0x808080, 0x9999FF, 0x993366,
+        0xFFFFCC, 0xCCFFFF, 0x660066, 0xFF8080, 0x0066CC, 0xCCCCFF, 0x000080, 0xFF00FF, 0xFFFF00,
+        0x00FFFF, 0x800080, 0x800000, 0x008080, 0x0000FF, 0x00CCFF, 0xCCFFFF, 0xCCFFCC, 0xFFFF99,
+        0x99CCFF, 0xFF99CC, 0xCC99FF, 0xFFCC99, 0x3366FF, 0x33CCCC, 0x99CC00, 0xFFCC00, 0xFF9900,
+        0xFF6600, 0x666699, 0x969696, 0x003366, 0x339966, 0x003300, 0x333300, 0x993300, 0x993366,
+        0x333399, 0x333333,
+    ];
+    let rgb = match index {
+        // Indices 0-7 repeat the first entries of the palette at 8-15
+        0..=7 => PALETTE[index],
+        8..=63 => PALETTE[index - 8],
+        64 => 0x000000,
+        65 => 0xFFFFFF,
+        _ => return None,
+    };
+    Some(format!("{:06X}", rgb))
+}
+
+/// Lighten (positive) or darken (negative) an `RRGGBB` color by a theme tint,
+/// applied to its HSL luminance
+fn apply_tint(rgb: &str, tint: f64) -> String {
+    let Ok(value) = u32::from_str_radix(rgb, 16) else {
+        return rgb.to_string();
+    };
+    let channel = |shift: u32| ((value >> shift) & 0xFF) as f64 / 255.0;
+    let (r, g, b) = (channel(16), channel(8), channel(0));
+
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let mut l = (max + min) / 2.0;
+    let (h, s) = if max == min {
+        (0.0, 0.0)
+    } else {
+        let d = max - min;
+        let s = if l > 0.5 {
+            d / (2.0 - max - min)
+        } else {
+            d / (max + min)
+        };
+        let h = if max == r {
+            (g - b) / d + if g < b { 6.0 } else { 0.0 }
+        } else if max == g {
+            (b - r) / d + 2.0
+        } else {
+            (r - g) / d + 4.0
+        };
+        (h / 6.0, s)
+    };
+
+    l = if tint < 0.0 {
+        l * (1.0 + tint)
+    } else {
+        l * (1.0 - tint) + tint
+    };
+
+    let hue_to_rgb = |p: f64, q: f64, mut t: f64| {
+        if t < 0.0 {
+            t += 1.0;
+        }
+        if t > 1.0 {
+            t -= 1.0;
+        }
+        if t < 1.0 / 6.0 {
+            p + (q - p) * 6.0 * t
+        } else if t < 0.5 {
+            q
+        } else if t < 2.0 / 3.0 {
+            p + (q - p) * (2.0 / 3.0 - t) * 6.0
+        } else {
+            p
+        }
+    };
+    let (r, g, b) = if s == 0.0 {
+        (l, l, l)
+    } else {
+        let q = if l < 0.5 {
+            l * (1.0 + s)
+        } else {
+            l + s - l * s
+        };
+        let p = 2.0 * l - q;
+        (
+            hue_to_rgb(p, q, h + 1.0 / 3.0),
+            hue_to_rgb(p, q, h),
+            hue_to_rgb(p, q, h - 1.0 / 3.0),
+        )
+    };
+    let byte = |v: f64| (v * 255.0).round().clamp(0.0, 255.0) as u32;
+    format!("{:02X}{:02X}{:02X}", byte(r), byte(g), byte(b))
+}
+
+/// Theme colors in the order cell styles index them: light 1, dark 1, light 2,
+/// dark 2, accents 1-6, hyperlink, followed hyperlink
+fn read_theme_colors(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+) -> Result<Vec<String>> {
+    const SLOTS: [&[u8]; 12] = [
+        b"lt1",
+        b"dk1",
+        b"lt2",
+        b"dk2",
+        b"accent1",
+        b"accent2",
+        b"accent3",
+        b"accent4",
+        b"accent5",
+        b"accent6",
+        b"hlink",
+        b"folHlink",
+    ];
+
+    let theme_xml = match archive.by_name("xl/theme/theme1.xml") {
+        Ok(file) => file,
+        Err(_) => return Ok(Vec::new()),
+    };
+    let mut reader = Reader::from_reader(BufReader::new(theme_xml));
+    reader.config_mut().trim_text(true);
+
+    let mut colors: HashMap<Vec<u8>, String> = HashMap::new();
+    let mut slot: Option<Vec<u8>> = None;
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) if SLOTS.contains(&e.local_name().as_ref()) => {
+                slot = Some(e.local_name().as_ref().to_vec());
+            }
+            Event::Start(e) | Event::Empty(e) if slot.is_some() => {
+                let key: &[u8] = match e.local_name().as_ref() {
+                    b"srgbClr" => b"val",
+                    b"sysClr" => b"lastClr",
+                    _ => continue,
+                };
+                if let Some(color) = attr_value(&e, key)?.and_then(|v| parse_hex_color(&v))
+                    && let Some(slot) = slot.take()
+                {
+                    colors.insert(slot, color);
+                }
+            }
+            Event::End(e) if e.local_name().as_ref() == b"clrScheme" => break,
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(SLOTS
+        .iter()
+        .map(|slot| colors.get(*slot).cloned().unwrap_or_default())
+        .collect())
+}
+
+/// Extract cell style indices from a worksheet
+pub fn extract_cell_style_indices_from_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+    sheet_name: &str,
+) -> Result<HashMap<(u32, u32), usize>> {
+    let mut cell_styles = HashMap::new();
+
+    let sheet_path = match get_xlsx_sheet_path(archive, sheet_name) {
+        Ok(path) => path,
+        Err(_) => return Ok(cell_styles),
+    };
+    let sheet_xml = match archive.by_name(&sheet_path) {
+        Ok(file) => file,
+        Err(_) => return Ok(cell_styles),
+    };
+
+    let buf_reader = BufReader::new(sheet_xml);
+    let mut reader = Reader::from_reader(buf_reader);
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                if e.name().as_ref() == b"c" {
+                    // Cell element `c`
+                    let mut row = 0u32;
+                    let mut col = 0u32;
+                    let mut style_index = 0usize;
+                    let mut has_style = false;
+
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"r" => {
+                                let r_str = attr.unescape_value()?;
+                                if let Some((r, c)) = parse_cell_ref(&r_str) {
+                                    row = r;
+                                    col = c;
+                                }
+                            }
+                            b"s" => {
+                                if let Ok(val) = attr.unescape_value()?.parse::<usize>() {
+                                    style_index = val;
+                                    has_style = true;
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    if has_style {
+                        cell_styles.insert((row, col), style_index);
+                    }
+                }
+            }
+            Ok(Event::Eof) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(cell_styles)
+}
+
+/// Extract formulas from an XLSX worksheet manually
+pub fn extract_formulas_from_xlsx(
+    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
+    sheet_name: &str,
+) -> Result<HashMap<(u32, u32), String>> {
+    let mut formulas = HashMap::new();
+    let mut shared_formulas = HashMap::new();
+
+    let sheet_path = match get_xlsx_sheet_path(archive, sheet_name) {
+        Ok(path) => path,
+        Err(_) => return Ok(formulas),
+    };
+
+    let sheet_xml = archive.by_name(&sheet_path)?;
+    let mut reader = Reader::from_reader(BufReader::new(sheet_xml));
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut current_cell_ref = String::new();
+    let mut in_formula = false;
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) | Event::Empty(e) => match e.name().as_ref() {
+                b"c" => {
+                    for attr in e.attributes().flatten() {
+                        if attr.key.as_ref() == b"r" {
+                            current_cell_ref = attr.unescape_value()?.to_string();
+                        }
+                    }
+                }
+                b"f" => {
+                    in_formula = true;
+                    let mut si = None;
+                    let mut t = None;
+
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"si" => {
+                                si = attr.unescape_value()?.parse::<u32>().ok();
+                            }
+                            b"t" => {
+                                t = Some(attr.unescape_value()?.to_string());
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    if let Some(_s_idx) = si
+                        && t.as_deref() == Some("shared")
+                    {
+                        // This might be the base or a consumer
+                        // We wait for the text content to see if it's the base
+                    }
+
+                    // Store si for the current cell to association after getting Text
+                    if let Some(s_idx) = si
+                        && let Some((r, c)) = parse_cell_ref(&current_cell_ref)
+                    {
+                        // Temporarily store si to handle it in Text
+                        // We use a prefix to distinguish from actual formulas
+                        formulas.insert((r, c), format!("__SHARED__{}", s_idx));
+                    }
+                }
+                _ => {}
+            },
+            Event::Text(e) if in_formula => {
+                let formula_text = e.unescape()?.to_string();
+                if let Some((r, c)) = parse_cell_ref(&current_cell_ref) {
+                    // Check if this cell was marked as shared
+                    if let Some(marker) = formulas.get(&(r, c))
+                        && marker.starts_with("__SHARED__")
+                    {
+                        let si: u32 = marker["__SHARED__".len()..].parse().unwrap();
+                        shared_formulas.insert(si, formula_text.clone());
+                    }
+                    formulas.insert((r, c), formula_text);
+                }
+            }
+            Event::End(e) => {
+                if e.name().as_ref() == b"f" {
+                    in_formula = false;
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    // Second pass to resolve shared formulas
+    for ((_r, _c), formula) in formulas.iter_mut() {
+        if formula.starts_with("__SHARED__") {
+            let si: u32 = formula["__SHARED__".len()..].parse().unwrap();
+            if let Some(base_formula) = shared_formulas.get(&si) {
+                *formula = base_formula.clone();
+            } else {
+                *formula = String::new(); // Fallback if si not found
+            }
+        }
+    }
+
+    Ok(formulas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_extract_tables_from_xlsx() {
+        use std::io::Cursor;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            let mut add = |name: &str, xml: &str| {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(xml.as_bytes()).unwrap();
+            };
+
+            add(
+                "xl/workbook.xml",
+                r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Q1 Sales" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+            );
+            add(
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#,
+            );
+            add(
+                "xl/worksheets/_rels/sheet1.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/table" Target="../tables/table1.xml"/></Relationships>"#,
+            );
+
+            // Add a table file
+            add(
+                "xl/tables/table1.xml",
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<table xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" id="1" name="Table1" displayName="MyTable" ref="A1:C4" tableType="xml" headerRowCount="1" totalsRowCount="1">
+    <tableColumns count="3">
+        <tableColumn id="1" name="Col1"/>
+        <tableColumn id="2" name="Col2"/>
+        <tableColumn id="3" name="Col3" totalsRowFunction="sum"/>
+    </tableColumns>
+    <tableStyleInfo name="TableStyleMedium2" showRowStripes="1"/>
+</table>"#,
+            );
+
+            // Add another table file (without displayName, and no sheet linking to it)
+            add(
+                "xl/tables/table2.xml",
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<table xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" id="2" name="OtherTable" ref="D4:E5" tableType="xml" headerRowCount="0">
+</table>"#,
+            );
+
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let tables = extract_tables_from_xlsx(&mut archive).unwrap();
+
+        assert_eq!(
+            tables,
+            vec![
+                Table {
+                    name: "MyTable".to_string(),
+                    sheet: "Q1 Sales".to_string(),
+                    range: (0, 0, 3, 2),
+                    columns: vec!["Col1".to_string(), "Col2".to_string(), "Col3".to_string()],
+                    header_rows: 1,
+                    totals_rows: 1,
+                    style: Some("TableStyleMedium2".to_string()),
+                },
+                Table {
+                    name: "OtherTable".to_string(),
+                    range: (3, 3, 4, 4),
+                    ..Default::default()
+                },
+            ]
+        );
+        assert_eq!(tables[0].a1_reference((1, 2, 2, 2)), "'Q1 Sales'!C2:C3");
+
+        // Table names stay usable as defined names
+        let names = XlsxReader::new(&mut archive)
+            .unwrap()
+            .read_defined_names()
+            .unwrap();
+        assert_eq!(
+            names,
+            vec![
+                DefinedName::new("MyTable", "A1:C4"),
+                DefinedName::new("OtherTable", "D4:E5"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_defined_names_from_xlsx() {
+        use std::io::Cursor;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            zip.start_file("xl/workbook.xml", options).unwrap();
+            zip.write_all(br#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+<sheets><sheet name="Inputs" sheetId="1" r:id="rId1"/><sheet name="Q2 Sales" sheetId="2" r:id="rId2"/></sheets>
+<definedNames>
+    <definedName name="_xlnm.Print_Area" localSheetId="0">Inputs!$A$1:$C$9</definedName>
+    <definedName name="Rate" comment="VAT rate">Inputs!$B$1</definedName>
+    <definedName name="Rate" localSheetId="1">'Q2 Sales'!$B$1</definedName>
+    <definedName name="Solver_opt" localSheetId="1" hidden="1">'Q2 Sales'!$D$4</definedName>
+</definedNames>
+</workbook>"#).unwrap();
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let names = extract_defined_names_from_xlsx(&mut archive).unwrap();
+
+        assert_eq!(
+            names,
+            vec![
+                DefinedName {
+                    comment: Some("VAT rate".to_string()),
+                    ..DefinedName::new("Rate", "Inputs!$B$1")
+                },
+                DefinedName {
+                    scope: Some("Q2 Sales".to_string()),
+                    ..DefinedName::new("Rate", "'Q2 Sales'!$B$1")
+                },
+                DefinedName {
+                    scope: Some("Q2 Sales".to_string()),
+                    hidden: true,
+                    ..DefinedName::new("Solver_opt", "'Q2 Sales'!$D$4")
+                },
+            ]
+        );
+        assert_eq!(names[1].qualified_name(), "'Q2 Sales'!Rate");
+    }
+
+    #[test]
+    fn test_extract_date_system_from_xlsx() {
+        use std::io::Cursor;
+        use zip::write::FileOptions;
+
+        let archive_with = |workbook_pr: &str| {
+            let mut buf = Vec::new();
+            {
+                let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+                let options =
+                    FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+                zip.start_file("xl/workbook.xml", options).unwrap();
+                write!(
+                    zip,
+                    r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">{}<sheets><sheet name="Sheet1" sheetId="1"/></sheets></workbook>"#,
+                    workbook_pr
+                )
+                .unwrap();
+                zip.finish().unwrap();
+            }
+            ZipArchive::new(Cursor::new(buf)).unwrap()
+        };
+
+        let mut archive = archive_with(r#"<workbookPr date1904="1"/>"#);
+        assert_eq!(
+            extract_date_system_from_xlsx(&mut archive).unwrap(),
+            DateSystem::Excel1904
+        );
+        let mut archive = archive_with(r#"<workbookPr defaultThemeVersion="124226"/>"#);
+        assert_eq!(
+            extract_date_system_from_xlsx(&mut archive).unwrap(),
+            DateSystem::Excel1900
+        );
+    }
+
+    #[test]
+    fn test_resolve_part_target() {
+        assert_eq!(
+            resolve_part_target("xl/worksheets/sheet1.xml", "../comments1.xml"),
+            "xl/comments1.xml"
+        );
+        assert_eq!(
+            resolve_part_target("xl/worksheets/sheet1.xml", "/xl/threadedComments/a.xml"),
+            "xl/threadedComments/a.xml"
+        );
+        assert_eq!(
+            resolve_part_target("xl/workbook.xml", "worksheets/sheet2.xml"),
+            "xl/worksheets/sheet2.xml"
+        );
+    }
+
+    #[test]
+    fn test_comments_and_threaded_comments_xlsx() {
+        use std::io::Cursor;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            let mut add = |name: &str, xml: &str| {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(xml.as_bytes()).unwrap();
+            };
+
+            add(
+                "xl/workbook.xml",
+                r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+            );
+            add(
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#,
+            );
+            add(
+                "xl/worksheets/sheet1.xml",
+                r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData><row r="1"><c r="A1"><v>1</v></c></row><row r="3"><c r="B3"><v>2</v></c></row></sheetData></worksheet>"#,
+            );
+            add(
+                "xl/worksheets/_rels/sheet1.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments" Target="../comments1.xml"/><Relationship Id="rId2" Type="http://schemas.microsoft.com/office/2017/10/relationships/threadedComment" Target="../threadedComments/threadedComment1.xml"/></Relationships>"#,
+            );
+            add(
+                "xl/comments1.xml",
+                r#"<comments xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><authors><author>Ann</author><author>tc={1}</author></authors><commentList><comment ref="A1" authorId="0"><text><r><rPr><b/></rPr><t>Ann:</t></r><r><t xml:space="preserve">
+Check this total</t></r><rPh sb="0" eb="1"><t>ignored</t></rPh></text></comment><comment ref="C2" authorId="1"><text><t>[Threaded comment] placeholder</t></text></comment></commentList></comments>"#,
+            );
+            add(
+                "xl/threadedComments/threadedComment1.xml",
+                r#"<ThreadedComments xmlns="http://schemas.microsoft.com/office/spreadsheetml/2018/threadedcomments"><threadedComment ref="C2" dT="2024-05-01T10:00:00.00" personId="{P1}" id="{1}"><text>Is this right?</text></threadedComment><threadedComment ref="C2" dT="2024-05-02T09:30:00.00" personId="{P2}" id="{2}" parentId="{1}"><text>Yes</text></threadedComment></ThreadedComments>"#,
+            );
+            add(
+                "xl/persons/person.xml",
+                r#"<personList xmlns="http://schemas.microsoft.com/office/spreadsheetml/2018/threadedcomments"><person displayName="Bo" id="{P1}"/><person displayName="Cy" id="{P2}"/></personList>"#,
+            );
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let mut reader = XlsxReader::new(&mut archive).unwrap();
+        let sheet = reader.read_sheet("Sheet1").unwrap();
+
+        let note = sheet.cells[&(0, 0)].comment.as_ref().unwrap();
+        assert_eq!(note.author.as_deref(), Some("Ann"));
+        assert_eq!(note.text, "Check this total");
+        assert!(!note.threaded);
+        assert_eq!(sheet.cells[&(0, 0)].value, CellValue::Number(1.0));
+
+        // Threaded comments land on blank cells and replace the placeholder note
+        let thread = sheet.cells[&(1, 2)].comment.as_ref().unwrap();
+        assert_eq!(sheet.cells[&(1, 2)].value, CellValue::Empty);
+        assert!(thread.threaded);
+        assert_eq!(thread.text, "Is this right?");
+        assert_eq!(thread.date.as_deref(), Some("2024-05-01T10:00:00.00"));
+        assert_eq!(thread.replies.len(), 1);
+        assert_eq!(thread.replies[0].text, "Yes");
+        assert_eq!(thread.authors().collect::<Vec<_>>(), vec!["Bo", "Cy"]);
+        assert_eq!(sheet.comments().count(), 2);
+
+        // Streaming delivers the same comments, including the comment-only row
+        let mut streamed = Vec::new();
+        reader
+            .for_each_row("Sheet1", &mut |row, cells| {
+                for cell in cells {
+                    streamed.push((row, cell.col, cell.comment.as_ref().map(|c| c.text.clone())));
+                }
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(
+            streamed,
+            vec![
+                (0, 0, Some("Check this total".to_string())),
+                (1, 2, Some("Is this right?".to_string())),
+                (2, 1, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hyperlinks_xlsx() {
+        use std::io::Cursor;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            let mut add = |name: &str, xml: &str| {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(xml.as_bytes()).unwrap();
+            };
+
+            add(
+                "xl/workbook.xml",
+                r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+            );
+            add(
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#,
+            );
+            add(
+                "xl/worksheets/sheet1.xml",
+                r##"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheetData><row r="1"><c r="A1" t="inlineStr"><is><t>Docs</t></is></c><c r="B1" t="str"><f>HYPERLINK("\\server\share\q3.xlsx","Q3")</f><v>Q3</v></c><c r="C1" t="str"><f>HYPERLINK("#Totals!A1")</f><v>#Totals!A1</v></c></row></sheetData><hyperlinks><hyperlink ref="A1" r:id="rId1" display="Docs"/><hyperlink ref="A2:A3" location="Totals!B2"/><hyperlink ref="C1" r:id="rId2" location="Sheet1!A1"/></hyperlinks></worksheet>"##,
+            );
+            add(
+                "xl/worksheets/_rels/sheet1.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="https://example.com/docs" TargetMode="External"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="file:///C:/data/other.xlsx" TargetMode="External"/></Relationships>"#,
+            );
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let mut reader = XlsxReader::new(&mut archive).unwrap();
+        let sheet = reader.read_sheet("Sheet1").unwrap();
+
+        let links: Vec<_> = sheet
+            .hyperlinks
+            .iter()
+            .map(|l| (l.row, l.col, l.target.as_str(), l.internal))
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                (0, 0, "https://example.com/docs", false),
+                (0, 1, "\\\\server\\share\\q3.xlsx", false),
+                (0, 2, "file:///C:/data/other.xlsx#Sheet1!A1", false),
+                (1, 0, "Totals!B2", true),
+                (2, 0, "Totals!B2", true),
+            ]
+        );
+        assert_eq!(sheet.hyperlinks[0].display.as_deref(), Some("Docs"));
+        assert_eq!(sheet.hyperlinks[1].display.as_deref(), Some("Q3"));
+        assert!(sheet.hyperlinks[1].is_file());
+
+        // Streaming still records the sheet's hyperlinks
+        let streamed = reader.for_each_row("Sheet1", &mut |_, _| Ok(())).unwrap();
+        assert_eq!(streamed.hyperlinks, sheet.hyperlinks);
+    }
+
+    #[test]
+    fn test_cell_styles_xlsx() {
+        use std::io::Cursor;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            let mut add = |name: &str, xml: &str| {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(xml.as_bytes()).unwrap();
+            };
+
+            add(
+                "xl/workbook.xml",
+                r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+            );
+            add(
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#,
+            );
+            add(
+                "xl/theme/theme1.xml",
+                r#"<a:theme xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><a:themeElements><a:clrScheme name="Office"><a:dk1><a:sysClr val="windowText" lastClr="000000"/></a:dk1><a:lt1><a:sysClr val="window" lastClr="FFFFFF"/></a:lt1><a:dk2><a:srgbClr val="44546A"/></a:dk2><a:lt2><a:srgbClr val="E7E6E6"/></a:lt2><a:accent1><a:srgbClr val="4472C4"/></a:accent1></a:clrScheme></a:themeElements></a:theme>"#,
+            );
+            add(
+                "xl/styles.xml",
+                r#"<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><fonts count="2"><font><sz val="11"/><name val="Calibri"/></font><font><b/><i val="0"/><u/><sz val="14"/><color theme="4"/><name val="Arial"/></font></fonts><fills count="3"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill><fill><patternFill patternType="solid"><fgColor theme="0" tint="-0.5"/><bgColor indexed="64"/></patternFill></fill></fills><borders count="2"><border><left/><right/><top/><bottom/></border><border><left style="thin"><color indexed="10"/></left><right/><top/><bottom style="double"><color rgb="FF00B050"/></bottom></border></borders><cellStyleXfs count="1"><xf numFmtId="0" fontId="1" fillId="2" borderId="1"/></cellStyleXfs><cellXfs count="2"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/><xf numFmtId="0" fontId="1" fillId="2" borderId="1" xfId="0" applyAlignment="1" applyProtection="1"><alignment horizontal="center" vertical="top" wrapText="1" indent="2" textRotation="135"/><protection locked="0" hidden="1"/></xf></cellXfs></styleSheet>"#,
+            );
+            add(
+                "xl/worksheets/sheet1.xml",
+                r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData><row r="1"><c r="A1"><v>1</v></c><c r="B1" s="1"><v>2</v></c></row></sheetData></worksheet>"#,
+            );
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let mut reader = XlsxReader::new(&mut archive).unwrap();
+        let styles = reader.read_styles().unwrap();
+        assert_eq!(styles.len(), 2);
+        assert_eq!(styles[0].font.name.as_deref(), Some("Calibri"));
+        assert_eq!(styles[0].fill.color, None);
+        assert!(styles[0].protection.locked);
+
+        let style = &styles[1];
+        assert_eq!(style.font.name.as_deref(), Some("Arial"));
+        assert_eq!(style.font.size, Some(14.0));
+        assert!(style.font.bold && style.font.underline && !style.font.italic);
+        assert_eq!(style.font.color.as_deref(), Some("4472C4"));
+        assert_eq!(style.fill.color.as_deref(), Some("808080"));
+        assert_eq!(
+            style.border.left,
+            Some(BorderLine {
+                style: "thin".to_string(),
+                color: Some("FF0000".to_string()),
+            })
+        );
+        assert_eq!(style.border.bottom.as_ref().unwrap().style, "double");
+        assert_eq!(
+            style.border.bottom.as_ref().unwrap().color.as_deref(),
+            Some("00B050")
+        );
+        assert!(style.border.right.is_none());
+        assert_eq!(style.alignment.horizontal.as_deref(), Some("center"));
+        assert_eq!(style.alignment.vertical.as_deref(), Some("top"));
+        assert!(style.alignment.wrap_text);
+        assert_eq!(style.alignment.indent, 2);
+        assert_eq!(style.alignment.rotation, -45);
+        assert!(!style.protection.locked && style.protection.hidden);
+
+        let sheet = reader.read_sheet("Sheet1").unwrap();
+        assert_eq!(sheet.cells[&(0, 0)].style, None);
+        assert_eq!(sheet.cells[&(0, 1)].style, Some(1));
+    }
+
+    #[test]
+    fn test_protection_xlsx() {
+        use std::io::Cursor;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            let mut add = |name: &str, xml: &str| {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(xml.as_bytes()).unwrap();
+            };
+
+            add(
+                "xl/workbook.xml",
+                r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><workbookPr/><workbookProtection workbookAlgorithmName="SHA-512" workbookHashValue="aGFzaA==" workbookSaltValue="c2FsdA==" workbookSpinCount="100000" lockStructure="1"/><sheets><sheet name="Legacy" sheetId="1" r:id="rId1"/><sheet name="Open" sheetId="2" r:id="rId2"/></sheets></workbook>"#,
+            );
+            add(
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet2.xml"/></Relationships>"#,
+            );
+            add(
+                "xl/worksheets/sheet1.xml",
+                r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData><row r="1"><c r="A1"><v>1</v></c></row></sheetData><sheetProtection password="CC1A" sheet="1" objects="1" scenarios="1"/></worksheet>"#,
+            );
+            // Protection options saved without turning protection on
+            add(
+                "xl/worksheets/sheet2.xml",
+                r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData/><sheetProtection formatCells="0"/></worksheet>"#,
+            );
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let mut reader = XlsxReader::new(&mut archive).unwrap();
+
+        let workbook = reader.read_workbook_protection().unwrap().unwrap();
+        assert!(workbook.structure && !workbook.windows);
+        let password = workbook.password.unwrap();
+        assert_eq!(password.algorithm, "SHA-512");
+        assert!(password.salted);
+        assert_eq!(password.spin_count, 100000);
+        assert_eq!(password.weakness(), None);
+
+        let legacy = reader.read_sheet("Legacy").unwrap();
+        let password = legacy.protection.unwrap().password.unwrap();
+        assert_eq!(password, PasswordHash::legacy("CC1A"));
+        assert!(password.weakness().is_some());
+        assert_eq!(reader.read_sheet("Open").unwrap().protection, None);
+
+        // Streaming keeps the protection with the sheet metadata
+        let streamed = reader.for_each_row("Legacy", &mut |_, _| Ok(())).unwrap();
+        assert!(streamed.protection.is_some());
+    }
+
+    #[test]
+    fn test_document_properties_xlsx() {
+        use crate::reader::DateTime;
+        use std::io::Cursor;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            let mut add = |name: &str, xml: &str| {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(xml.as_bytes()).unwrap();
+            };
+
+            add(
+                "docProps/core.xml",
+                r#"<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><dc:title>Q3 Budget</dc:title><dc:subject></dc:subject><dc:creator>Jane Doe</dc:creator><cp:keywords>budget, q3</cp:keywords><cp:lastModifiedBy>John Roe</cp:lastModifiedBy><dcterms:created xsi:type="dcterms:W3CDTF">2024-01-15T09:30:00Z</dcterms:created><dcterms:modified xsi:type="dcterms:W3CDTF">2024-02-01T17:05:12Z</dcterms:modified></cp:coreProperties>"#,
+            );
+            add(
+                "docProps/app.xml",
+                r#"<Properties xmlns="http://schemas.openxmlformats.org/officeDocument/2006/extended-properties" xmlns:vt="http://schemas.openxmlformats.org/officeDocument/2006/docPropsVTypes"><Application>Microsoft Excel</Application><TitlesOfParts><vt:vector size="1" baseType="lpstr"><vt:lpstr>Sheet1</vt:lpstr></vt:vector></TitlesOfParts><Company>Acme &amp; Co</Company><Manager>Max Mustermann</Manager><Template>C:\Users\jdoe\Templates\Budget.xltx</Template></Properties>"#,
+            );
+            add(
+                "docProps/custom.xml",
+                r#"<Properties xmlns="http://schemas.openxmlformats.org/officeDocument/2006/custom-properties" xmlns:vt="http://schemas.openxmlformats.org/officeDocument/2006/docPropsVTypes"><property fmtid="{D5CDD505-2E9C-101B-9397-08002B2CF9AE}" pid="2" name="Department"><vt:lpwstr>Finance</vt:lpwstr></property><property fmtid="{D5CDD505-2E9C-101B-9397-08002B2CF9AE}" pid="3" name="Reviewed"><vt:bool>true</vt:bool></property></Properties>"#,
+            );
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let properties = extract_properties_from_xlsx(&mut archive).unwrap();
+        assert_eq!(properties.title.as_deref(), Some("Q3 Budget"));
+        assert_eq!(properties.subject, None);
+        assert_eq!(properties.keywords.as_deref(), Some("budget, q3"));
+        assert_eq!(properties.author.as_deref(), Some("Jane Doe"));
+        assert_eq!(properties.last_modified_by.as_deref(), Some("John Roe"));
+        assert_eq!(properties.company.as_deref(), Some("Acme & Co"));
+        assert_eq!(properties.manager.as_deref(), Some("Max Mustermann"));
+        assert_eq!(
+            properties.template.as_deref(),
+            Some("C:\\Users\\jdoe\\Templates\\Budget.xltx")
+        );
+        assert_eq!(properties.application.as_deref(), Some("Microsoft Excel"));
+        assert_eq!(
+            properties.created,
+            Some(DateTime::from_ymd(2024, 1, 15).with_time(9, 30, 0))
+        );
+        assert_eq!(
+            properties.modified.map(|dt| dt.to_string()).as_deref(),
+            Some("2024-02-01T17:05:12")
+        );
+        assert_eq!(properties.custom.len(), 2);
+        assert_eq!(properties.custom["Department"], "Finance");
+        assert_eq!(properties.custom["Reviewed"], "true");
+
+        // The fixture leaves the personal fields blank
+        const TEST_XLSX: &[u8] = include_bytes!("../../../tests/minimal_test.xlsx");
+        let mut archive = ZipArchive::new(Cursor::new(TEST_XLSX)).unwrap();
+        let properties = extract_properties_from_xlsx(&mut archive).unwrap();
+        assert!(properties.personal().is_empty());
+        assert_eq!(
+            properties.created,
+            Some(DateTime::from_ymd(2025, 12, 22).with_time(10, 22, 16))
+        );
+        assert!(properties.application.unwrap().starts_with("LibreOffice"));
+    }
+
+    #[test]
+    fn test_data_validations_xlsx() {
+        use crate::reader::validation::{ValidationErrorStyle, ValidationKind, ValidationOperator};
+        use std::io::Cursor;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            let mut add = |name: &str, xml: &str| {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(xml.as_bytes()).unwrap();
+            };
+
+            add(
+                "xl/workbook.xml",
+                r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+            );
+            add(
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#,
+            );
+            add(
+                "xl/worksheets/sheet1.xml",
+                r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:x14="http://schemas.microsoft.com/office/spreadsheetml/2009/9/main" xmlns:xm="http://schemas.microsoft.com/office/excel/2006/main"><sheetData><row r="1"><c r="A1"><v>2.5</v></c></row></sheetData>
+<dataValidations count="2"><dataValidation type="decimal" operator="between" errorStyle="warning" allowBlank="1" showErrorMessage="1" sqref="A1:A10 C3"><formula1>0</formula1><formula2>1</formula2></dataValidation><dataValidation type="textLength" operator="lessThanOrEqual" sqref="D1"><formula1>5</formula1><formula2>0</formula2></dataValidation></dataValidations>
+<extLst><ext uri="{CCE6A557-97BC-4b89-ADB6-D9C93CAAB3DF}"><x14:dataValidations count="1"><x14:dataValidation type="list" allowBlank="1" showErrorMessage="1"><x14:formula1><xm:f>Lists!$A$1:$A$3</xm:f></x14:formula1><xm:sqref>B2:B20</xm:sqref></x14:dataValidation></x14:dataValidations></ext></extLst></worksheet>"#,
+            );
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let sheet = XlsxReader::new(&mut archive)
+            .unwrap()
+            .read_sheet("Sheet1")
+            .unwrap();
+        assert_eq!(sheet.data_validations.len(), 3);
+
+        let decimal = &sheet.data_validations[0];
+        assert_eq!(decimal.kind, ValidationKind::Decimal);
+        assert_eq!(decimal.ranges, vec![(0, 0, 9, 0), (2, 2, 2, 2)]);
+        assert_eq!(decimal.error_style, ValidationErrorStyle::Warning);
+        assert_eq!(
+            (decimal.formula1.as_deref(), decimal.formula2.as_deref()),
+            (Some("0"), Some("1"))
+        );
+        assert_eq!(decimal.check(&sheet.cells[&(0, 0)].value), Some(false));
+
+        let length = &sheet.data_validations[1];
+        assert_eq!(length.operator, ValidationOperator::LessThanOrEqual);
+        assert_eq!(length.formula2, None);
+        assert!(!length.allow_blank && !length.show_error);
+
+        let list = sheet.validation_at(5, 1).unwrap();
+        assert_eq!(list.kind, ValidationKind::List);
+        assert_eq!(list.list_source(), Some("Lists!$A$1:$A$3"));
+        assert_eq!(list.ranges, vec![(1, 1, 19, 1)]);
+    }
+
+    #[test]
+    fn test_conditional_formats_xlsx() {
+        use crate::reader::conditional_format::ConditionalFormatKind;
+        use crate::reader::validation::ValidationOperator;
+        use std::io::Cursor;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            let mut add = |name: &str, xml: &str| {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(xml.as_bytes()).unwrap();
+            };
+
+            add(
+                "xl/workbook.xml",
+                r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+            );
+            add(
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#,
+            );
+            add(
+                "xl/worksheets/sheet1.xml",
+                r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:x14="http://schemas.microsoft.com/office/spreadsheetml/2009/9/main" xmlns:xm="http://schemas.microsoft.com/office/excel/2006/main"><sheetData/>
+<conditionalFormatting sqref="A1:A10 C3"><cfRule type="cellIs" dxfId="0" priority="3" operator="between"><formula>1</formula><formula>10</formula></cfRule><cfRule type="expression" dxfId="1" priority="1" stopIfTrue="1"><formula>$B1&lt;TODAY()</formula></cfRule></conditionalFormatting>
+<conditionalFormatting sqref="D1:D20"><cfRule type="dataBar" priority="2"><dataBar><cfvo type="min"/><cfvo type="num" val="100"/><color rgb="FF638EC6"/></dataBar><extLst><ext uri="{B025F937-C7B1-47D3-B67F-A62EFF666E3E}"><x14:id>{AAA}</x14:id></ext></extLst></cfRule></conditionalFormatting>
+<extLst><ext uri="{78C0D931-6437-407d-A8EE-F0AAD7539E65}"><x14:conditionalFormattings><x14:conditionalFormatting xmlns:xm="http://schemas.microsoft.com/office/excel/2006/main"><x14:cfRule type="dataBar" id="{AAA}"><x14:dataBar><x14:cfvo type="autoMin"/><x14:cfvo type="num"><xm:f>100</xm:f></x14:cfvo></x14:dataBar></x14:cfRule><xm:sqref>D1:D20</xm:sqref></x14:conditionalFormatting><x14:conditionalFormatting xmlns:xm="http://schemas.microsoft.com/office/excel/2006/main"><x14:cfRule type="expression" priority="4" id="{BBB}"><xm:f>Lists!$A$1&gt;0</xm:f></x14:cfRule><xm:sqref>E1:E5</xm:sqref></x14:conditionalFormatting></x14:conditionalFormattings></ext></extLst></worksheet>"#,
+            );
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let sheet = XlsxReader::new(&mut archive)
+            .unwrap()
+            .read_sheet("Sheet1")
+            .unwrap();
+        let rules = &sheet.conditional_formats;
+        // Sorted by priority; the x14 copy of the data bar is dropped
+        assert_eq!(
+            rules.iter().map(|rule| rule.kind).collect::<Vec<_>>(),
+            vec![
+                ConditionalFormatKind::Expression,
+                ConditionalFormatKind::DataBar,
+                ConditionalFormatKind::CellIs,
+                ConditionalFormatKind::Expression,
+            ]
+        );
+
+        assert_eq!(rules[0].formulas, vec!["$B1<TODAY()"]);
+        assert!(rules[0].stop_if_true);
+        assert_eq!(rules[0].operator, None);
+        assert_eq!(rules[0].ranges, vec![(0, 0, 9, 0), (2, 2, 2, 2)]);
+
+        assert_eq!(rules[1].formulas, vec!["100"]);
+        assert_eq!(rules[1].sqref(), "D1:D20");
+
+        assert_eq!(rules[2].priority, 3);
+        assert_eq!(rules[2].operator, Some(ValidationOperator::Between));
+        assert_eq!(rules[2].formulas, vec!["1", "10"]);
+
+        assert_eq!(rules[3].formulas, vec!["Lists!$A$1>0"]);
+        assert_eq!(rules[3].ranges, vec![(0, 4, 4, 4)]);
+    }
+
+    #[test]
+    fn test_pivot_tables_xlsx() {
+        use crate::reader::pivot::PivotSource;
+        use std::io::Cursor;
+        use zip::write::FileOptions;
+
+        let records = "<r><n v=\"1\"/></r>".repeat(200);
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            let mut add = |name: &str, xml: &str| {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(xml.as_bytes()).unwrap();
+            };
+
+            add(
+                "xl/workbook.xml",
+                r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Data" sheetId="1" r:id="rId1"/><sheet name="Report" sheetId="2" r:id="rId2"/></sheets><pivotCaches><pivotCache cacheId="4" r:id="rId3"/><pivotCache cacheId="5" r:id="rId4"/></pivotCaches></workbook>"#,
+            );
+            add(
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet2.xml"/><Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/pivotCacheDefinition" Target="pivotCache/pivotCacheDefinition1.xml"/><Relationship Id="rId4" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/pivotCacheDefinition" Target="pivotCache/pivotCacheDefinition2.xml"/></Relationships>"#,
+            );
+            add(
+                "xl/worksheets/_rels/sheet2.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/pivotTable" Target="../pivotTables/pivotTable1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/pivotTable" Target="../pivotTables/pivotTable2.xml"/></Relationships>"#,
+            );
+            add(
+                "xl/pivotTables/pivotTable1.xml",
+                r#"<pivotTableDefinition xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" name="SalesByRegion" cacheId="4" dataCaption="Values"><location ref="A3:C8" firstHeaderRow="1" firstDataRow="1" firstDataCol="1"/><pivotFields count="1"><pivotField showAll="0"/></pivotFields></pivotTableDefinition>"#,
+            );
+            add(
+                "xl/pivotTables/_rels/pivotTable1.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/pivotCacheDefinition" Target="../pivotCache/pivotCacheDefinition1.xml"/></Relationships>"#,
+            );
+            add(
+                "xl/pivotTables/pivotTable2.xml",
+                r#"<pivotTableDefinition xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" name="Budget" cacheId="5"><location ref="F3:G10" firstHeaderRow="1" firstDataRow="1" firstDataCol="1"/></pivotTableDefinition>"#,
+            );
+            add(
+                "xl/pivotTables/_rels/pivotTable2.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/pivotCacheDefinition" Target="../pivotCache/pivotCacheDefinition2.xml"/></Relationships>"#,
+            );
+            add(
+                "xl/pivotCache/pivotCacheDefinition1.xml",
+                r#"<pivotCacheDefinition xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" r:id="rId1" refreshOnLoad="1" recordCount="200"><cacheSource type="worksheet"><worksheetSource ref="A1:C201" sheet="Data"/></cacheSource><cacheFields count="1"><cacheField name="Region" numFmtId="0"/></cacheFields></pivotCacheDefinition>"#,
+            );
+            add(
+                "xl/pivotCache/_rels/pivotCacheDefinition1.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/pivotCacheRecords" Target="pivotCacheRecords1.xml"/></Relationships>"#,
+            );
+            add(
+                "xl/pivotCache/pivotCacheRecords1.xml",
+                &format!(
+                    r#"<pivotCacheRecords xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" count="200">{}</pivotCacheRecords>"#,
+                    records
+                ),
+            );
+            add(
+                "xl/pivotCache/pivotCacheDefinition2.xml",
+                r#"<pivotCacheDefinition xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" saveData="0"><cacheSource type="worksheet"><worksheetSource ref="A1:B50" sheet="Plan 2024" r:id="rId1"/></cacheSource></pivotCacheDefinition>"#,
+            );
+            add(
+                "xl/pivotCache/_rels/pivotCacheDefinition2.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/externalLinkPath" Target="file:///C:/finance/budget.xlsx" TargetMode="External"/></Relationships>"#,
+            );
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let pivots = extract_pivot_tables_from_xlsx(&mut archive).unwrap();
+        assert_eq!(pivots.len(), 2);
+
+        assert_eq!(pivots[0].name, "SalesByRegion");
+        assert_eq!(pivots[0].sheet, "Report");
+        assert_eq!(pivots[0].location, (2, 0, 7, 2));
+        assert_eq!(
+            pivots[0].source,
+            PivotSource::Range {
+                sheet: "Data".to_string(),
+                reference: "A1:C201".to_string(),
+            }
+        );
+        let cache = pivots[0].cache.as_ref().unwrap();
+        assert_eq!(cache.id, 4);
+        assert_eq!(cache.records, Some(200));
+        assert!(cache.refresh_on_load);
+        // Definition and records parts, stored uncompressed
+        assert!(cache.size > records.len() as u64);
+
+        assert_eq!(pivots[1].name, "Budget");
+        assert_eq!(
+            pivots[1].source,
+            PivotSource::External {
+                workbook: "file:///C:/finance/budget.xlsx".to_string(),
+                reference: "'Plan 2024'!A1:B50".to_string(),
+            }
+        );
+        let cache = pivots[1].cache.as_ref().unwrap();
+        assert_eq!(cache.id, 5);
+        assert_eq!(cache.records, None);
+        assert!(cache.size < records.len() as u64);
+    }
+
+    #[test]
+    fn test_drawings_xlsx() {
+        use std::io::Cursor;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            let mut add = |name: &str, data: &[u8]| {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(data).unwrap();
+            };
+
+            add(
+                "xl/worksheets/_rels/sheet1.xml.rels",
+                br#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/drawing" Target="../drawings/drawing1.xml"/></Relationships>"#,
+            );
+            add(
+                "xl/drawings/drawing1.xml",
+                br#"<xdr:wsDr xmlns:xdr="http://schemas.openxmlformats.org/drawingml/2006/spreadsheetDrawing" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart">
+<xdr:twoCellAnchor><xdr:from><xdr:col>4</xdr:col><xdr:colOff>0</xdr:colOff><xdr:row>1</xdr:row><xdr:rowOff>0</xdr:rowOff></xdr:from><xdr:to><xdr:col>10</xdr:col><xdr:row>15</xdr:row></xdr:to>
+<xdr:graphicFrame><xdr:nvGraphicFramePr><xdr:cNvPr id="2" name="Chart 1"/></xdr:nvGraphicFramePr><a:graphic><a:graphicData><c:chart r:id="rId1"/></a:graphicData></a:graphic></xdr:graphicFrame></xdr:twoCellAnchor>
+<xdr:oneCellAnchor><xdr:from><xdr:col>0</xdr:col><xdr:row>20</xdr:row></xdr:from>
+<xdr:pic><xdr:nvPicPr><xdr:cNvPr id="3" name="Logo"/></xdr:nvPicPr><xdr:blipFill><a:blip r:embed="rId2"/></xdr:blipFill></xdr:pic></xdr:oneCellAnchor>
+<xdr:oneCellAnchor><xdr:from><xdr:col>2</xdr:col><xdr:row>20</xdr:row></xdr:from>
+<xdr:pic><xdr:nvPicPr><xdr:cNvPr id="4" name="Linked"/></xdr:nvPicPr><xdr:blipFill><a:blip r:link="rId3"/></xdr:blipFill></xdr:pic></xdr:oneCellAnchor>
+</xdr:wsDr>"#,
+            );
+            add(
+                "xl/drawings/_rels/drawing1.xml.rels",
+                br#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/chart" Target="../charts/chart1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/image1.png"/><Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="https://example.com/logo.png" TargetMode="External"/></Relationships>"#,
+            );
+            add(
+                "xl/charts/chart1.xml",
+                br#"<c:chartSpace xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><c:chart>
+<c:title><c:tx><c:rich><a:p><a:r><a:t>Monthly Sales</a:t></a:r></a:p></c:rich></c:tx></c:title>
+<c:plotArea><c:layout/><c:barChart><c:barDir val="col"/>
+<c:ser><c:idx val="0"/><c:tx><c:strRef><c:f>Data!$B$1</c:f></c:strRef></c:tx><c:cat><c:strRef><c:f>Data!$A$2:$A$13</c:f></c:strRef></c:cat><c:val><c:numRef><c:f>Data!$B$2:$B$13</c:f></c:numRef></c:val></c:ser>
+<c:ser><c:idx val="1"/><c:val><c:numRef><c:f>#REF!</c:f></c:numRef></c:val></c:ser>
+</c:barChart><c:lineChart><c:ser><c:val><c:numRef><c:f>Data!$D$2:$D$13</c:f></c:numRef></c:val></c:ser></c:lineChart>
+<c:valAx><c:title><c:tx><c:rich><a:p><a:r><a:t>Units</a:t></a:r></a:p></c:rich></c:tx></c:title></c:valAx></c:plotArea></c:chart></c:chartSpace>"#,
+            );
+            add("xl/media/image1.png", &[0u8; 300]);
+
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let (charts, images) =
+            extract_drawings_from_xlsx(&mut archive, "xl/worksheets/sheet1.xml").unwrap();
+
+        assert_eq!(charts.len(), 1);
+        let chart = &charts[0];
+        assert_eq!(chart.kind, "bar");
+        assert_eq!(chart.title.as_deref(), Some("Monthly Sales"));
+        assert_eq!(chart.anchor, (1, 4));
+        assert_eq!(chart.series.len(), 3);
+        assert_eq!(
+            chart.series[0],
+            ChartSeries {
+                name: Some("Data!$B$1".to_string()),
+                categories: Some("Data!$A$2:$A$13".to_string()),
+                values: Some("Data!$B$2:$B$13".to_string()),
+            }
+        );
+        assert_eq!(chart.series[1].values.as_deref(), Some("#REF!"));
+        assert_eq!(chart.series[2].values.as_deref(), Some("Data!$D$2:$D$13"));
+
+        // The linked picture has no file in the package
+        assert_eq!(
+            images,
+            vec![Image {
+                name: "Logo".to_string(),
+                anchor: (20, 0),
+                path: "xl/media/image1.png".to_string(),
+                size: 300,
+            }]
+        );
+    }
+    #[test]
+    fn test_active_content_xlsx() {
+        use std::io::Cursor;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            let mut add = |name: &str, data: &[u8]| {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(data).unwrap();
+            };
+
+            add(
+                "xl/workbook.xml",
+                br#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+            );
+            add(
+                "xl/_rels/workbook.xml.rels",
+                br#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#,
+            );
+            add(
+                "xl/worksheets/sheet1.xml",
+                br#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:mc="http://schemas.openxmlformats.org/markup-compatibility/2006"><sheetData/>
+<mc:AlternateContent><mc:Choice Requires="x14"><controls><control shapeId="1025" r:id="rId3" name="CommandButton1"/></controls></mc:Choice><mc:Fallback><controls><control shapeId="1025" r:id="rId3" name="CommandButton1"/></controls></mc:Fallback></mc:AlternateContent>
+<oleObjects><mc:AlternateContent><mc:Choice Requires="x14"><oleObject progId="Package" shapeId="1026" r:id="rId2"><objectPr defaultSize="0"/></oleObject></mc:Choice><mc:Fallback><oleObject progId="Package" shapeId="1026" r:id="rId2"/></mc:Fallback></mc:AlternateContent>
+<oleObject progId="Word.Document.12" shapeId="1027" link="[1]!''" oleUpdate="OLEUPDATE_ALWAYS"/></oleObjects></worksheet>"#,
+            );
+            add(
+                "xl/worksheets/_rels/sheet1.xml.rels",
+                br#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/package" Target="../embeddings/Microsoft_Word_Document.docx"/><Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/control" Target="../activeX/activeX1.xml"/></Relationships>"#,
+            );
+            add(
+                "xl/activeX/activeX1.xml",
+                br#"<ax:ocx xmlns:ax="http://schemas.microsoft.com/office/2006/activeX" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" ax:classid="{D7053240-CE69-11CD-A777-00DD01143C57}" ax:persistence="persistStreamInit" r:id="rId1"/>"#,
+            );
+            add(
+                "xl/connections.xml",
+                br#"<connections xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+<connection id="1" name="Query - Sales" type="5" refreshedVersion="8" background="1" refreshOnLoad="1"><dbPr connection="Provider=Microsoft.Mashup.OleDb.1;Data Source=$Workbook$;Location=Sales;Extended Properties=&quot;&quot;" command="SELECT * FROM [Sales]"/></connection>
+<connection id="2" name="Rates" type="4" refreshedVersion="8"><webPr url="https://example.com/rates" htmlTables="1"/></connection>
+<connection id="3" name="Orders" type="1"><dbPr connection="DSN=Orders;UID=sa;PWD=secret" command="SELECT * FROM orders"/></connection>
+<connection id="4" name="export" type="6"><textPr sourceFile="C:\data\export.csv"/></connection>
+</connections>"#,
+            );
+
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let content = extract_active_content_from_xlsx(&mut archive).unwrap();
+        let sheet = Some("Sheet1".to_string());
+        assert_eq!(
+            content,
+            vec![
+                ActiveContent {
+                    kind: ActiveContentKind::ActiveXControl,
+                    sheet: sheet.clone(),
+                    name: "CommandButton1".to_string(),
+                    target: Some("{D7053240-CE69-11CD-A777-00DD01143C57}".to_string()),
+                    runs_on_open: false,
+                },
+                ActiveContent {
+                    kind: ActiveContentKind::OleObject,
+                    sheet: sheet.clone(),
+                    name: "Package".to_string(),
+                    target: Some("xl/embeddings/Microsoft_Word_Document.docx".to_string()),
+                    runs_on_open: false,
+                },
+                ActiveContent {
+                    kind: ActiveContentKind::OleObject,
+                    sheet: sheet.clone(),
+                    name: "Word.Document.12".to_string(),
+                    target: Some("[1]!''".to_string()),
+                    runs_on_open: true,
+                },
+                ActiveContent {
+                    kind: ActiveContentKind::PowerQuery,
+                    sheet: None,
+                    name: "Query - Sales".to_string(),
+                    target: Some("Sales".to_string()),
+                    runs_on_open: true,
+                },
+                ActiveContent {
+                    kind: ActiveContentKind::WebQuery,
+                    sheet: None,
+                    name: "Rates".to_string(),
+                    target: Some("https://example.com/rates".to_string()),
+                    runs_on_open: false,
+                },
+                ActiveContent {
+                    kind: ActiveContentKind::DataConnection,
+                    sheet: None,
+                    name: "Orders".to_string(),
+                    target: Some("DSN=Orders;UID=sa;PWD=secret".to_string()),
+                    runs_on_open: false,
+                },
+                ActiveContent {
+                    kind: ActiveContentKind::DataConnection,
+                    sheet: None,
+                    name: "export".to_string(),
+                    target: Some("C:\\data\\export.csv".to_string()),
+                    runs_on_open: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_data_mashup_without_connection_xlsx() {
+        use std::io::Cursor;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            // customXml items are written as UTF-16 with a byte order mark
+            let xml = r#"<?xml version="1.0" encoding="utf-16"?><DataMashup xmlns="http://schemas.microsoft.com/DataMashup">AAAAAA==</DataMashup>"#;
+            let mut data = vec![0xFF, 0xFE];
+            data.extend(xml.encode_utf16().flat_map(u16::to_le_bytes));
+            zip.start_file("customXml/item1.xml", options).unwrap();
+            zip.write_all(&data).unwrap();
+            zip.finish().unwrap();
+        }
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let content = extract_active_content_from_xlsx(&mut archive).unwrap();
+        assert_eq!(content.len(), 1);
+        assert_eq!(content[0].kind, ActiveContentKind::PowerQuery);
+        assert_eq!(content[0].name, "DataMashup");
+    }
+
+    #[test]
+    fn test_macros_xlsx() {
+        use crate::reader::vba::tests::build_vba_project;
+        use std::io::Cursor;
+        use zip::write::FileOptions;
 
-    let sheet_path = match get_xlsx_sheet_path(archive, sheet_name) {
-        Ok(path) => path,
-        Err(_) => return Ok(cell_styles),
-    };
-    let sheet_xml = match archive.by_name(&sheet_path) {
-        Ok(file) => file,
-        Err(_) => return Ok(cell_styles),
-    };
+        let project = build_vba_project(
+            &[("Module1", true, "Sub Auto_Open()\r\nEnd Sub\r\n")],
+            "Module=Module1\r\n",
+        );
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            zip.start_file("xl/vbaProject.bin", options).unwrap();
+            zip.write_all(&project).unwrap();
+            zip.start_file("xl/vbaProjectSignature.bin", options)
+                .unwrap();
+            zip.write_all(b"not a project").unwrap();
+            zip.finish().unwrap();
+        }
 
-    let buf_reader = BufReader::new(sheet_xml);
-    let mut reader = Reader::from_reader(buf_reader);
-    reader.config_mut().trim_text(true);
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let modules = extract_macros_from_xlsx(&mut archive).unwrap();
+        assert_eq!(modules.len(), 1);
+        assert_eq!(modules[0].name, "Module1");
+        assert_eq!(modules[0].source, "Sub Auto_Open()\r\nEnd Sub\r\n");
+    }
 
-    let mut buf = Vec::new();
+    #[test]
+    fn test_layout_xlsx() {
+        use crate::reader::{SheetView, Span};
+        use std::io::Cursor;
+        use zip::write::FileOptions;
 
-    loop {
-        match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
-                if e.name().as_ref() == b"c" {
-                    // Cell element `c`
-                    let mut row = 0u32;
-                    let mut col = 0u32;
-                    let mut style_index = 0usize;
-                    let mut has_style = false;
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            let mut add = |name: &str, xml: &str| {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(xml.as_bytes()).unwrap();
+            };
 
-                    for attr in e.attributes().flatten() {
-                        match attr.key.as_ref() {
-                            b"r" => {
-                                let r_str = attr.unescape_value()?;
-                                if let Some((r, c)) = parse_cell_ref(&r_str) {
-                                    row = r;
-                                    col = c;
-                                }
-                            }
-                            b"s" => {
-                                if let Ok(val) = attr.unescape_value()?.parse::<usize>() {
-                                    style_index = val;
-                                    has_style = true;
-                                }
-                            }
-                            _ => {}
-                        }
-                    }
+            add(
+                "xl/workbook.xml",
+                r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><bookViews><workbookView activeTab="1"/></bookViews><sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/><sheet name="Sheet2" sheetId="2" r:id="rId2"/></sheets></workbook>"#,
+            );
+            add(
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet2.xml"/></Relationships>"#,
+            );
+            add(
+                "xl/worksheets/sheet1.xml",
+                r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetPr><tabColor rgb="FFFF0000"/></sheetPr><sheetViews><sheetView showGridLines="0" zoomScale="85" workbookViewId="0"><pane xSplit="1" ySplit="2" topLeftCell="B3" activePane="bottomRight" state="frozen"/></sheetView></sheetViews><sheetFormatPr defaultRowHeight="15" outlineLevelRow="2"/><cols><col min="1" max="1" width="30.7109375" customWidth="1"/><col min="2" max="4" width="9.140625" outlineLevel="1"/></cols><sheetData>
+<row r="1" ht="30" customHeight="1"><c r="A1" t="inlineStr"><is><t>Name</t></is></c></row>
+<row r="2" ht="16.5"><c r="A2"><v>1</v></c></row>
+<row r="3" outlineLevel="1"/>
+<row r="4" outlineLevel="2" hidden="1"/>
+</sheetData></worksheet>"#,
+            );
+            add(
+                "xl/worksheets/sheet2.xml",
+                r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetViews><sheetView tabSelected="1" workbookViewId="0"/></sheetViews><sheetData/></worksheet>"#,
+            );
+            zip.finish().unwrap();
+        }
 
-                    if has_style {
-                        cell_styles.insert((row, col), style_index);
-                    }
-                }
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let mut reader = XlsxReader::new(&mut archive).unwrap();
+        let sheet = reader.read_sheet("Sheet1").unwrap();
+        assert_eq!(
+            sheet.columns,
+            vec![
+                Span {
+                    first: 0,
+                    last: 0,
+                    size: Some(30.7109375),
+                    outline_level: 0,
+                },
+                Span {
+                    first: 1,
+                    last: 3,
+                    size: Some(9.140625),
+                    outline_level: 1,
+                },
+            ]
+        );
+        // Row 2's height was fitted to its content, not set
+        assert_eq!(sheet.row_height(0), Some(30.0));
+        assert_eq!(sheet.row_height(1), Some(15.0));
+        assert_eq!(sheet.row_outline_level(2), 1);
+        assert_eq!(sheet.row_outline_level(3), 2);
+        assert_eq!(sheet.hidden_rows, vec![3]);
+        assert_eq!(sheet.column_width(10), None);
+        assert_eq!(
+            sheet.view,
+            SheetView {
+                frozen_rows: 2,
+                frozen_columns: 1,
+                zoom: 85,
+                show_gridlines: false,
+                tab_color: Some("FF0000".to_string()),
+                selected: false,
+                active: false,
             }
-            Ok(Event::Eof) => break,
-            _ => {}
-        }
-        buf.clear();
+        );
+
+        let other = reader.read_sheet("Sheet2").unwrap();
+        assert!(other.view.selected && other.view.active);
     }
 
-    Ok(cell_styles)
-}
+    #[test]
+    fn test_page_setup_xlsx() {
+        use crate::reader::page_setup::{HeaderFooterKind, HeaderFooterPages, Orientation};
+        use std::io::Cursor;
+        use zip::write::FileOptions;
 
-/// Extract formulas from an XLSX worksheet manually
-pub fn extract_formulas_from_xlsx(
-    archive: &mut ZipArchive<impl std::io::Read + std::io::Seek>,
-    sheet_name: &str,
-) -> Result<HashMap<(u32, u32), String>> {
-    let mut formulas = HashMap::new();
-    let mut shared_formulas = HashMap::new();
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            let mut add = |name: &str, xml: &str| {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(xml.as_bytes()).unwrap();
+            };
 
-    let sheet_path = match get_xlsx_sheet_path(archive, sheet_name) {
-        Ok(path) => path,
-        Err(_) => return Ok(formulas),
-    };
+            add(
+                "xl/workbook.xml",
+                r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Report" sheetId="1" r:id="rId1"/><sheet name="Data" sheetId="2" r:id="rId2"/></sheets><definedNames><definedName name="_xlnm.Print_Area" localSheetId="0">Report!$A$1:$D$20,Report!$F$1:$G$5</definedName><definedName name="_xlnm.Print_Titles" localSheetId="0">Report!$A:$A,Report!$1:$2</definedName><definedName name="_xlnm.Print_Area" localSheetId="1">Data!#REF!</definedName></definedNames></workbook>"#,
+            );
+            add(
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet2.xml"/></Relationships>"#,
+            );
+            add(
+                "xl/worksheets/sheet1.xml",
+                r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetPr><pageSetUpPr fitToPage="1"/></sheetPr><sheetData/><pageMargins left="0.25" right="0.25" top="0.75" bottom="0.75" header="0.3" footer="0.3"/><pageSetup paperSize="9" scale="80" fitToHeight="0" orientation="landscape"/><headerFooter differentFirst="1"><oddHeader>&amp;L&amp;A&amp;RPage &amp;P</oddHeader><oddFooter>&amp;L&amp;Z&amp;F</oddFooter><firstHeader>&amp;CConfidential</firstHeader></headerFooter></worksheet>"#,
+            );
+            add(
+                "xl/worksheets/sheet2.xml",
+                r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData/><pageSetup scale="90" orientation="portrait"/></worksheet>"#,
+            );
+            zip.finish().unwrap();
+        }
 
-    let sheet_xml = archive.by_name(&sheet_path)?;
-    let mut reader = Reader::from_reader(BufReader::new(sheet_xml));
-    reader.config_mut().trim_text(true);
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        // Print names stay out of the defined names
+        assert!(
+            extract_defined_names_from_xlsx(&mut archive)
+                .unwrap()
+                .is_empty()
+        );
 
-    let mut buf = Vec::new();
-    let mut current_cell_ref = String::new();
-    let mut in_formula = false;
+        let mut reader = XlsxReader::new(&mut archive).unwrap();
+        let report = reader.read_sheet("Report").unwrap();
+        let setup = &report.page_setup;
+        assert_eq!(setup.print_area, vec![(0, 0, 19, 3), (0, 5, 4, 6)]);
+        assert_eq!(setup.title_rows, Some((0, 1)));
+        assert_eq!(setup.title_columns, Some((0, 0)));
+        assert_eq!(setup.orientation, Some(Orientation::Landscape));
+        // The scale is ignored when fitting to pages
+        assert_eq!(setup.scale, None);
+        assert_eq!(setup.fit_to_pages, Some((1, 0)));
+        assert_eq!(setup.margins.map(|m| (m.left, m.header)), Some((0.25, 0.3)));
+        let headers: Vec<_> = setup
+            .headers_footers
+            .iter()
+            .map(|h| (h.kind, h.pages, h.text.as_str()))
+            .collect();
+        assert_eq!(
+            headers,
+            vec![
+                (
+                    HeaderFooterKind::Header,
+                    HeaderFooterPages::All,
+                    "&L&A&RPage &P"
+                ),
+                (HeaderFooterKind::Footer, HeaderFooterPages::All, "&L&Z&F"),
+                (
+                    HeaderFooterKind::Header,
+                    HeaderFooterPages::First,
+                    "&CConfidential"
+                ),
+            ]
+        );
 
-    loop {
-        match reader.read_event_into(&mut buf)? {
-            Event::Start(e) | Event::Empty(e) => match e.name().as_ref() {
-                b"c" => {
-                    for attr in e.attributes().flatten() {
-                        if attr.key.as_ref() == b"r" {
-                            current_cell_ref = attr.unescape_value()?.to_string();
-                        }
-                    }
-                }
-                b"f" => {
-                    in_formula = true;
-                    let mut si = None;
-                    let mut t = None;
+        let data = reader.read_sheet("Data").unwrap();
+        assert!(data.page_setup.print_area.is_empty());
+        assert_eq!(
+            data.page_setup.broken_print_area.as_deref(),
+            Some("Data!#REF!")
+        );
+        assert_eq!(data.page_setup.scale, Some(90));
+        assert_eq!(data.page_setup.orientation, Some(Orientation::Portrait));
+    }
 
-                    for attr in e.attributes().flatten() {
-                        match attr.key.as_ref() {
-                            b"si" => {
-                                si = attr.unescape_value()?.parse::<u32>().ok();
-                            }
-                            b"t" => {
-                                t = Some(attr.unescape_value()?.to_string());
-                            }
-                            _ => {}
-                        }
-                    }
+    #[test]
+    fn test_auto_filter_xlsx() {
+        use crate::reader::filter::{
+            FilterColumn, FilterCondition, FilterCriteria, FilterOperator, SortKey,
+        };
+        use std::io::Cursor;
+        use zip::write::FileOptions;
 
-                    if let Some(_s_idx) = si
-                        && t.as_deref() == Some("shared")
-                    {
-                        // This might be the base or a consumer
-                        // We wait for the text content to see if it's the base
-                    }
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            let mut add = |name: &str, xml: &str| {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(xml.as_bytes()).unwrap();
+            };
 
-                    // Store si for the current cell to association after getting Text
-                    if let Some(s_idx) = si
-                        && let Some((r, c)) = parse_cell_ref(&current_cell_ref)
-                    {
-                        // Temporarily store si to handle it in Text
-                        // We use a prefix to distinguish from actual formulas
-                        formulas.insert((r, c), format!("__SHARED__{}", s_idx));
-                    }
-                }
-                _ => {}
-            },
-            Event::Text(e) if in_formula => {
-                let formula_text = e.unescape()?.to_string();
-                if let Some((r, c)) = parse_cell_ref(&current_cell_ref) {
-                    // Check if this cell was marked as shared
-                    if let Some(marker) = formulas.get(&(r, c))
-                        && marker.starts_with("__SHARED__")
-                    {
-                        let si: u32 = marker["__SHARED__".len()..].parse().unwrap();
-                        shared_formulas.insert(si, formula_text.clone());
-                    }
-                    formulas.insert((r, c), formula_text);
-                }
-            }
-            Event::End(e) => {
-                if e.name().as_ref() == b"f" {
-                    in_formula = false;
-                }
-            }
-            Event::Eof => break,
-            _ => {}
+            add(
+                "xl/workbook.xml",
+                r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Sales" sheetId="1" r:id="rId1"/><sheet name="Plain" sheetId="2" r:id="rId2"/></sheets></workbook>"#,
+            );
+            add(
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet2.xml"/></Relationships>"#,
+            );
+            add(
+                "xl/worksheets/sheet1.xml",
+                r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData><row r="1" hidden="1"/><row r="2"/><row r="4" hidden="1"/><row r="5" hidden="1"/><row r="12" hidden="1"/></sheetData><autoFilter ref="B2:E10"><filterColumn colId="0"><filters blank="1"><filter val="North"/><filter val="South"/><dateGroupItem year="2024" month="3" dateTimeGrouping="month"/></filters></filterColumn><filterColumn colId="2"><customFilters and="1"><customFilter operator="greaterThanOrEqual" val="100"/><customFilter val="*kg"/></customFilters></filterColumn><filterColumn colId="3"><top10 top="0" percent="1" val="5"/></filterColumn><sortState ref="B3:E10"><sortCondition descending="1" ref="D3:D10"/><sortCondition ref="B3:B10"/></sortState></autoFilter></worksheet>"#,
+            );
+            // Filter buttons without criteria hide nothing
+            add(
+                "xl/worksheets/sheet2.xml",
+                r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData><row r="3" hidden="1"/></sheetData><autoFilter ref="A1:C5"/><sortState ref="A2:C5"><sortCondition ref="C2:C5"/></sortState></worksheet>"#,
+            );
+            zip.finish().unwrap();
         }
-        buf.clear();
+
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let mut reader = XlsxReader::new(&mut archive).unwrap();
+        let sales = reader.read_sheet("Sales").unwrap();
+        let filter = sales.auto_filter.as_ref().unwrap();
+        assert_eq!(filter.range, (1, 1, 9, 4));
+        assert_eq!(
+            filter.columns,
+            vec![
+                FilterColumn {
+                    column: 1,
+                    criteria: FilterCriteria::Values {
+                        values: vec!["North".into(), "South".into(), "2024-3".into()],
+                        blank: true,
+                    },
+                },
+                FilterColumn {
+                    column: 3,
+                    criteria: FilterCriteria::Custom {
+                        conditions: vec![
+                            FilterCondition {
+                                operator: FilterOperator::GreaterThanOrEqual,
+                                value: "100".into(),
+                            },
+                            FilterCondition {
+                                operator: FilterOperator::Equal,
+                                value: "*kg".into(),
+                            },
+                        ],
+                        and: true,
+                    },
+                },
+                FilterColumn {
+                    column: 4,
+                    criteria: FilterCriteria::Top {
+                        count: 5.0,
+                        percent: true,
+                        bottom: true,
+                    },
+                },
+            ]
+        );
+        assert_eq!(
+            filter.sort,
+            vec![
+                SortKey {
+                    column: 3,
+                    descending: true,
+                },
+                SortKey {
+                    column: 1,
+                    descending: false,
+                },
+            ]
+        );
+        // Hidden rows below the header row are the filter's; the others were
+        // hidden by hand
+        assert_eq!(sales.filtered_rows, vec![3, 4]);
+        assert_eq!(sales.hidden_rows, vec![0, 11]);
+
+        let plain = reader.read_sheet("Plain").unwrap();
+        let filter = plain.auto_filter.as_ref().unwrap();
+        assert!(!filter.is_filtering());
+        assert_eq!(
+            filter.sort,
+            vec![SortKey {
+                column: 2,
+                descending: false,
+            }]
+        );
+        assert!(plain.filtered_rows.is_empty());
+        assert_eq!(plain.hidden_rows, vec![2]);
     }
 
-    // Second pass to resolve shared formulas
-    for ((_r, _c), formula) in formulas.iter_mut() {
-        if formula.starts_with("__SHARED__") {
-            let si: u32 = formula["__SHARED__".len()..].parse().unwrap();
-            if let Some(base_formula) = shared_formulas.get(&si) {
-                *formula = base_formula.clone();
-            } else {
-                *formula = String::new(); // Fallback if si not found
-            }
+    #[test]
+    fn test_rich_text_xlsx() {
+        use crate::reader::rich_text::TextRun;
+        use std::io::Cursor;
+        use zip::write::FileOptions;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            let mut add = |name: &str, xml: &str| {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(xml.as_bytes()).unwrap();
+            };
+
+            add(
+                "xl/workbook.xml",
+                r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Notes" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+            );
+            add(
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#,
+            );
+            add(
+                "xl/sharedStrings.xml",
+                r#"<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" count="3" uniqueCount="3">
+<si><t>Plain</t></si>
+<si><r><t xml:space="preserve">Total </t></r><r><rPr><b/><sz val="12"/><color rgb="FFFF0000"/><rFont val="Arial"/></rPr><t xml:space="preserve">due
+now</t></r></si>
+<si><t>東京</t><rPh sb="0" eb="2"><t>トウキョウ</t></rPh><phoneticPr fontId="1"/></si>
+</sst>"#,
+            );
+            add(
+                "xl/worksheets/sheet1.xml",
+                r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData><row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" t="s"><v>1</v></c><c r="C1" t="s"><v>2</v></c><c r="D1" t="inlineStr"><is><r><rPr><i/></rPr><t>Note:</t></r><r><t xml:space="preserve"> see B1</t></r></is></c></row></sheetData></worksheet>"#,
+            );
+            zip.finish().unwrap();
         }
-    }
 
-    Ok(formulas)
-}
+        let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
+        let mut reader = XlsxReader::new(&mut archive).unwrap();
+        let sheet = reader.read_sheet("Notes").unwrap();
+        let value = |col| sheet.get_cell(0, col).unwrap().value.clone();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::io::Write;
+        assert_eq!(value(0), CellValue::Text("Plain".into(), None));
+
+        let CellValue::Text(text, Some(rich)) = value(1) else {
+            panic!("B1 should keep its runs");
+        };
+        assert_eq!(text, "Total due\nnow");
+        assert_eq!(
+            rich.runs,
+            vec![
+                TextRun {
+                    text: "Total ".into(),
+                    font: None,
+                },
+                TextRun {
+                    text: "due\nnow".into(),
+                    font: Some(Font {
+                        name: Some("Arial".into()),
+                        size: Some(12.0),
+                        bold: true,
+                        color: Some("FF0000".into()),
+                        ..Default::default()
+                    }),
+                },
+            ]
+        );
+
+        // The reading guide is kept apart from the text
+        let CellValue::Text(text, Some(rich)) = value(2) else {
+            panic!("C1 should keep its phonetic guide");
+        };
+        assert_eq!(text, "東京");
+        assert_eq!(
+            rich.phonetic,
+            vec![PhoneticRun {
+                text: "トウキョウ".into(),
+                start: 0,
+                end: 2,
+            }]
+        );
+
+        let CellValue::Text(text, Some(rich)) = value(3) else {
+            panic!("D1 should keep its runs");
+        };
+        assert_eq!(text, "Note: see B1");
+        assert!(rich.runs[0].font.as_ref().unwrap().italic);
+    }
 
     #[test]
-    fn test_extract_tables_from_xlsx() {
+    fn test_array_formulas_xlsx() {
+        use crate::reader::ArrayFormula;
         use std::io::Cursor;
         use zip::write::FileOptions;
 
         let mut buf = Vec::new();
         {
             let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
-
             let options =
                 FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+            let mut add = |name: &str, xml: &str| {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(xml.as_bytes()).unwrap();
+            };
 
-            // Add a table file
-            zip.start_file("xl/tables/table1.xml", options).unwrap();
-            zip.write_all(br#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-<table xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" id="1" name="Table1" displayName="MyTable" ref="A1:C3" tableType="xml" headerRowCount="1">
-    <tableColumns count="3">
-        <tableColumn id="1" name="Col1"/>
-        <tableColumn id="2" name="Col2"/>
-        <tableColumn id="3" name="Col3"/>
-    </tableColumns>
-</table>"#).unwrap();
-
-            // Add another table file (without displayName)
-            zip.start_file("xl/tables/table2.xml", options).unwrap();
-            zip.write_all(br#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-<table xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" id="2" name="OtherTable" ref="D4:E5" tableType="xml" headerRowCount="1">
-</table>"#).unwrap();
-
+            add(
+                "xl/workbook.xml",
+                r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+            );
+            add(
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#,
+            );
+            add(
+                "xl/worksheets/sheet1.xml",
+                r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>
+<row r="1"><c r="A1" cm="1"><f t="array" ref="A1:A3">_xlfn.SEQUENCE(3)</f><v>1</v></c><c r="B1"><f t="array" ref="C2:B1">C5:D6*2</f><v>2</v></c><c r="D1"><f t="array" ref="D1">SUM(A1:A3*2)</f><v>12</v></c></row>
+<row r="2"><c r="A2"><v>2</v></c><c r="B2"><v>2</v></c></row>
+<row r="3"><c r="A3"><v>3</v></c><c r="E3" t="str"><f>_xlfn.XLOOKUP("_xlfn.",A1:A3,A1:A3)</f><v>1</v></c></row>
+</sheetData></worksheet>"#,
+            );
             zip.finish().unwrap();
         }
 
         let mut archive = ZipArchive::new(Cursor::new(buf)).unwrap();
-        let tables = extract_tables_from_xlsx(&mut archive).unwrap();
-
-        assert_eq!(tables.len(), 2);
-        assert_eq!(tables.get("MyTable"), Some(&"A1:C3".to_string()));
-        assert_eq!(tables.get("OtherTable"), Some(&"D4:E5".to_string()));
+        let sheet = XlsxReader::new(&mut archive)
+            .unwrap()
+            .read_sheet("Sheet1")
+            .unwrap();
+        assert_eq!(
+            sheet.array_formulas,
+            vec![
+                ArrayFormula {
+                    row: 0,
+                    col: 0,
+                    range: (0, 0, 2, 0),
+                    dynamic: true,
+                },
+                // Written reversed, as C2:B1
+                ArrayFormula {
+                    row: 0,
+                    col: 1,
+                    range: (0, 1, 1, 2),
+                    dynamic: false,
+                },
+                ArrayFormula {
+                    row: 0,
+                    col: 3,
+                    range: (0, 3, 0, 3),
+                    dynamic: false,
+                },
+            ]
+        );
+        assert_eq!(
+            sheet.get_cell(0, 0).unwrap().value.as_formula(),
+            Some("SEQUENCE(3)")
+        );
+        // Prefixes inside string literals are kept
+        assert_eq!(
+            sheet.get_cell(2, 4).unwrap().value.as_formula(),
+            Some(r#"XLOOKUP("_xlfn.",A1:A3,A1:A3)"#)
+        );
+        assert!(!sheet.is_spilled(0, 0));
+        assert!(sheet.is_spilled(1, 0));
+        assert!(sheet.is_spilled(1, 2));
+        assert!(!sheet.is_spilled(0, 3));
+        assert!(!sheet.is_spilled(0, 4));
     }
 }
 
@@ -1,7 +1,7 @@
 //! ERR001: Error cell value detection
 
-use super::{LinterRule, RuleCategory};
-use crate::reader::Workbook;
+use super::{LinterRule, RuleAccess, RuleCategory};
+use crate::reader::{CellError, Workbook};
 use crate::violation::{CellReference, Severity, Violation, ViolationScope};
 use anyhow::Result;
 
@@ -20,6 +20,10 @@ impl LinterRule for ErrorCellsRule {
         RuleCategory::UnresolvedErrors
     }
 
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Sheet
+    }
+
     fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
 
@@ -27,22 +31,13 @@ impl LinterRule for ErrorCellsRule {
             for cell in sheet.all_cells() {
                 let mut error_found = None;
 
-                if cell.value.is_error() {
-                    error_found =
-                        Some(cell.value.as_error().unwrap_or("Unknown error").to_string());
+                if let Some(error) = cell.value.as_error() {
+                    error_found = Some(error.clone());
                 } else if let Some(formula) = cell.value.as_formula() {
                     // Check for standard error literals in the formula string
-                    let error_literals = [
-                        "#NULL!", "#DIV/0!", "#VALUE!", "#REF!", "#NAME?", "#NUM!", "#N/A",
-                        "#SPILL!", "#CALC!",
-                    ];
-
-                    for check_err in error_literals {
-                        if formula.contains(check_err) {
-                            error_found = Some(check_err.to_string());
-                            break;
-                        }
-                    }
+                    error_found = CellError::STANDARD
+                        .into_iter()
+                        .find(|error| formula.contains(error.as_str()));
                 }
 
                 if let Some(error_value) = error_found {
@@ -66,29 +61,29 @@ impl LinterRule for ErrorCellsRule {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::CellStore;
     use crate::reader::workbook::{Cell, CellValue, Sheet};
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     #[test]
     fn test_error_cells_detection() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula_with_error("", "#DIV/0!".to_string()),
+                ..Default::default()
             },
         );
         cells.insert(
             (1, 0),
             Cell {
-                num_fmt: None,
                 row: 1,
                 col: 0,
                 value: CellValue::Number(42.0),
+                ..Default::default()
             },
         );
 
@@ -96,14 +91,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((2, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -122,24 +111,24 @@ mod tests {
 
     #[test]
     fn test_error_in_formula_detection() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=SUM(A1, [#REF!])".to_string()),
+                ..Default::default()
             },
         );
         // Standard ODS-like relative ref error or text error
         cells.insert(
             (1, 0),
             Cell {
-                num_fmt: None,
                 row: 1,
                 col: 0,
                 value: CellValue::formula("=#N/A".to_string()),
+                ..Default::default()
             },
         );
 
@@ -147,14 +136,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((2, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -1,6 +1,6 @@
 //! ERR002: Broken named ranges detection
 
-use super::{LinterRule, RuleCategory};
+use super::{LinterRule, RuleAccess, RuleCategory};
 use crate::reader::Workbook;
 use crate::violation::{Severity, Violation, ViolationScope};
 use anyhow::Result;
@@ -20,16 +20,28 @@ impl LinterRule for BrokenNamedRangesRule {
         RuleCategory::UnresolvedErrors
     }
 
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
     fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
 
         // Check each defined name to see if it references a valid range
-        for (name, reference) in &workbook.defined_names {
-            if is_broken_reference(workbook, reference) {
+        for defined in &workbook.defined_names {
+            if is_broken_reference(workbook, &defined.reference) {
+                // Sheet-local names are reported on their sheet
+                let scope = match &defined.scope {
+                    Some(sheet) => ViolationScope::Sheet(sheet.clone()),
+                    None => ViolationScope::Book,
+                };
                 violations.push(Violation::new(
                     self.id(),
-                    ViolationScope::Book,
-                    format!("Named range '{}' has broken reference: {}", name, reference),
+                    scope,
+                    format!(
+                        "Named range '{}' has broken reference: {}",
+                        defined.name, defined.reference
+                    ),
                     Severity::Error,
                 ));
             }
@@ -50,22 +62,28 @@ fn is_broken_reference(_workbook: &Workbook, reference: &str) -> bool {
 mod tests {
     use super::*;
     use crate::reader::workbook::Sheet;
-    use std::collections::HashMap;
+    use crate::reader::{CellStore, DefinedName};
     use std::path::PathBuf;
 
     #[test]
     fn test_broken_named_ranges() {
         let sheet = Sheet {
             name: "Sheet1".to_string(),
-            cells: HashMap::new(),
+            cells: CellStore::new(),
             used_range: None,
             ..Default::default()
         };
 
-        let mut defined_names = HashMap::new();
-        defined_names.insert("ValidRange".to_string(), "Sheet1!A1:B2".to_string());
-        // This SHOULD be reported
-        defined_names.insert("BrokenRange".to_string(), "Sheet1!#REF!".to_string());
+        let defined_names = vec![
+            DefinedName::new("ValidRange", "Sheet1!A1:B2"),
+            // This SHOULD be reported
+            DefinedName::new("BrokenRange", "Sheet1!#REF!"),
+            // Reported on the sheet it is local to
+            DefinedName {
+                scope: Some("Sheet1".to_string()),
+                ..DefinedName::new("BrokenRange", "#REF!$A$1")
+            },
+        ];
 
         let workbook = Workbook {
             path: PathBuf::from("test.xlsx"),
@@ -77,8 +95,13 @@ mod tests {
         let rule = BrokenNamedRangesRule;
         let violations = rule.check(&workbook).unwrap();
 
-        assert_eq!(violations.len(), 1);
+        assert_eq!(violations.len(), 2);
         assert_eq!(violations[0].rule_id, "ERR002");
         assert!(violations[0].message.contains("BrokenRange"));
+        assert_eq!(violations[0].scope, ViolationScope::Book);
+        assert_eq!(
+            violations[1].scope,
+            ViolationScope::Sheet("Sheet1".to_string())
+        );
     }
 }
@@ -64,9 +64,10 @@ impl LinterRule for CircularReferenceRule {
                 .unwrap_or(false);
 
             for cell in sheet.all_cells() {
-                if let Some(formula) = cell.value.as_formula() {
+                // Structured references (Table1[Amount]) are followed as A1 ranges
+                if let Some(formula) = workbook.resolved_formula(&sheet.name, cell) {
                     let refs = extract_cell_references(
-                        formula,
+                        &formula,
                         &self.cell_ref_pattern,
                         &sheet.name,
                         expand_ranges,
@@ -74,6 +75,24 @@ impl LinterRule for CircularReferenceRule {
                     dependencies.insert((sheet.name.clone(), cell.row, cell.col), refs);
                 }
             }
+
+            // Every cell of an array result comes from the array's formula
+            for array in &sheet.array_formulas {
+                let (r1, c1, r2, c2) = array.range;
+                if (r2 - r1 + 1) as u64 * (c2 - c1 + 1) as u64 > 100_000 {
+                    continue;
+                }
+                for row in r1..=r2 {
+                    for col in c1..=c2 {
+                        if (row, col) != (array.row, array.col) {
+                            dependencies
+                                .entry((sheet.name.clone(), row, col))
+                                .or_default()
+                                .push((sheet.name.clone(), array.row, array.col));
+                        }
+                    }
+                }
+            }
         }
 
         // 2. Detect circular references using DFS on the global graph
@@ -290,10 +309,10 @@ fn find_cycles(dependencies: &HashMap<Node, Vec<Node>>) -> Vec<Vec<Node>> {
 mod tests {
     use super::*;
     use crate::reader::workbook::{Cell, CellValue, Sheet};
-    use std::collections::HashMap;
+    use crate::reader::{ArrayFormula, CellStore};
     use std::path::PathBuf;
 
-    fn create_test_workbook(sheet_name: &str, cells: HashMap<(u32, u32), Cell>) -> Workbook {
+    fn create_test_workbook(sheet_name: &str, cells: CellStore) -> Workbook {
         let sheet = Sheet {
             name: sheet_name.to_string(),
             cells,
@@ -310,15 +329,15 @@ mod tests {
 
     #[test]
     fn test_circular_reference_direct() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         // A1 = A1+1
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=A1+1".to_string()),
+                ..Default::default()
             },
         );
 
@@ -332,24 +351,24 @@ mod tests {
 
     #[test]
     fn test_circular_reference_indirect() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         // A1 = B1, B1 = A1
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=B1".to_string()),
+                ..Default::default()
             },
         );
         cells.insert(
             (0, 1),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 1,
                 value: CellValue::formula("=A1".to_string()),
+                ..Default::default()
             },
         );
 
@@ -362,26 +381,26 @@ mod tests {
 
     #[test]
     fn test_circular_reference_range() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         // A1 = SUM(B1:B3)
         // B2 = A1
         // Cycle: A1 -> B2 -> A1
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=SUM(B1:B3)".to_string()),
+                ..Default::default()
             },
         );
         cells.insert(
             (1, 1),
             Cell {
-                num_fmt: None,
                 row: 1,
                 col: 1,
                 value: CellValue::formula("=A1".to_string()),
+                ..Default::default()
             },
         );
 
@@ -404,7 +423,7 @@ mod tests {
 
     #[test]
     fn test_circular_reference_range_no_expand() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         // A1 = SUM(B1:B3)
         // B2 = A1
         // Cycle: A1 -> B2 -> A1 (ONLY if A1 is expanded to depend on B2)
@@ -412,19 +431,19 @@ mod tests {
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=SUM(B1:B3)".to_string()),
+                ..Default::default()
             },
         );
         cells.insert(
             (1, 1),
             Cell {
-                num_fmt: None,
                 row: 1,
                 col: 1,
                 value: CellValue::formula("=A1".to_string()),
+                ..Default::default()
             },
         );
 
@@ -439,25 +458,25 @@ mod tests {
 
     #[test]
     fn test_circular_reference_range_sheet_override() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         // A1 = SUM(B1:B3)
         // B2 = A1
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=SUM(B1:B3)".to_string()),
+                ..Default::default()
             },
         );
         cells.insert(
             (1, 1),
             Cell {
-                num_fmt: None,
                 row: 1,
                 col: 1,
                 value: CellValue::formula("=A1".to_string()),
+                ..Default::default()
             },
         );
 
@@ -485,25 +504,25 @@ mod tests {
 
     #[test]
     fn test_huge_range_limit() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         // A1 = SUM(A2:A10000) - this range is okay
         // But if A1:XFD1048576 was present it would be skipped
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=SUM(A2:A3)".to_string()),
+                ..Default::default()
             },
         );
         cells.insert(
             (2, 0),
             Cell {
-                num_fmt: None,
                 row: 2,
                 col: 0,
                 value: CellValue::formula("=A1".to_string()),
+                ..Default::default()
             },
         );
 
@@ -514,4 +533,104 @@ mod tests {
         // Cycle: A1 -> A3 -> A1
         assert_eq!(violations.len(), 1);
     }
+
+    #[test]
+    fn test_circular_reference_through_structured_references() {
+        use crate::reader::Table;
+
+        // Sales table on A1:B3 (header row 1): Qty in column A, Amount in column B
+        let mut cells = CellStore::new();
+        // A2 = Sales[@Amount] and B2 = Sales[@Qty]*2 loop through the table
+        cells.insert(
+            (1, 0),
+            Cell {
+                row: 1,
+                col: 0,
+                value: CellValue::formula("=Sales[@Amount]"),
+                ..Default::default()
+            },
+        );
+        cells.insert(
+            (1, 1),
+            Cell {
+                row: 1,
+                col: 1,
+                value: CellValue::formula("=Sales[@Qty]*2"),
+                ..Default::default()
+            },
+        );
+        // A3 reads the column it sits in
+        cells.insert(
+            (2, 0),
+            Cell {
+                row: 2,
+                col: 0,
+                value: CellValue::formula("=SUM(Sales[Qty])"),
+                ..Default::default()
+            },
+        );
+
+        let mut workbook = create_test_workbook("Data", cells);
+        workbook.tables = vec![Table {
+            name: "Sales".to_string(),
+            sheet: "Data".to_string(),
+            range: (0, 0, 2, 1),
+            columns: vec!["Qty".to_string(), "Amount".to_string()],
+            header_rows: 1,
+            ..Default::default()
+        }];
+        let rule = CircularReferenceRule::new(&crate::config::LinterConfig::default());
+        let violations = rule.check(&workbook).unwrap();
+
+        let messages: Vec<_> = violations.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(violations.len(), 2, "{:?}", messages);
+        assert!(messages.contains(&"Circular reference detected: Data!A2 -> Data!B2 -> Data!A2"));
+        assert!(messages.contains(&"Circular reference detected: Data!A3 -> Data!A3"));
+    }
+
+    #[test]
+    fn test_circular_reference_through_spilled_cell() {
+        let mut cells = CellStore::new();
+        // A1 spills into A1:A3, B1 reads the spilled A3
+        cells.insert(
+            (0, 0),
+            Cell {
+                row: 0,
+                col: 0,
+                value: CellValue::formula("=SEQUENCE(3,1,B1)"),
+                ..Default::default()
+            },
+        );
+        cells.insert(
+            (2, 0),
+            Cell {
+                row: 2,
+                col: 0,
+                value: CellValue::Number(3.0),
+                ..Default::default()
+            },
+        );
+        cells.insert(
+            (0, 1),
+            Cell {
+                row: 0,
+                col: 1,
+                value: CellValue::formula("=A3+1"),
+                ..Default::default()
+            },
+        );
+
+        let mut workbook = create_test_workbook("Sheet1", cells);
+        workbook.sheets[0].array_formulas = vec![ArrayFormula {
+            row: 0,
+            col: 0,
+            range: (0, 0, 2, 0),
+            dynamic: true,
+        }];
+        let rule = CircularReferenceRule::new(&crate::config::LinterConfig::default());
+        let violations = rule.check(&workbook).unwrap();
+
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].message.contains("Sheet1!A3"));
+    }
 }
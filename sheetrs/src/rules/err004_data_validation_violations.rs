@@ -0,0 +1,254 @@
+//! ERR004: Cells violating their own data validation
+
+use super::{LinterRule, RuleCategory};
+use crate::reader::Workbook;
+use crate::reader::parser_utils::{parse_cell_range, parse_cell_ref};
+use crate::reader::workbook::{CellValue, Sheet};
+use crate::violation::{CellReference, Severity, Violation, ViolationScope};
+use anyhow::Result;
+
+pub struct DataValidationViolationsRule;
+
+impl LinterRule for DataValidationViolationsRule {
+    fn id(&self) -> &str {
+        "ERR004"
+    }
+
+    fn name(&self) -> &str {
+        "Data validation violations"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::UnresolvedErrors
+    }
+
+    // List sources may live on other sheets, so the default workbook access is kept
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        for sheet in &workbook.sheets {
+            for validation in &sheet.data_validations {
+                // Entries of a list taken from a range, when every entry is known
+                let source_items = validation
+                    .list_source()
+                    .and_then(|source| resolve_list(workbook, sheet, source));
+
+                for &(start_row, start_col, end_row, end_col) in &validation.ranges {
+                    for cell in sheet.cells_in_range((start_row, start_col), (end_row, end_col)) {
+                        let valid = match &source_items {
+                            Some(items) => Some(validation.check_in_list(&cell.value, items)),
+                            None => validation.check(&cell.value),
+                        };
+                        if valid == Some(false) {
+                            violations.push(Violation::new(
+                                self.id(),
+                                ViolationScope::Cell(
+                                    sheet.name.clone(),
+                                    CellReference::new(cell.row, cell.col),
+                                ),
+                                format!(
+                                    "Value '{}' does not satisfy the cell's data validation ({})",
+                                    cell.display_text(workbook.date_system),
+                                    validation.describe()
+                                ),
+                                Severity::Warning,
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(violations)
+    }
+}
+
+/// Entries of the range a list validation points at (directly or through a
+/// defined name), or `None` if the range cannot be found or holds formulas
+fn resolve_list(workbook: &Workbook, sheet: &Sheet, source: &str) -> Option<Vec<String>> {
+    let reference = workbook
+        .defined_name(source, Some(&sheet.name))
+        .map(|defined| defined.reference.as_str())
+        .unwrap_or(source);
+
+    // ODS names keep `Sheet.A1:Sheet.A5`; XLSX names and sources use `Sheet!A1:A5`
+    let (sheet_name, range) = match reference.rsplit_once('!') {
+        Some((sheet_name, range)) => (Some(sheet_name), range.to_string()),
+        None => match reference.split_once('.') {
+            Some((sheet_name, _)) => (
+                Some(sheet_name),
+                reference
+                    .split(':')
+                    .map(|part| part.rsplit('.').next().unwrap_or(part))
+                    .collect::<Vec<_>>()
+                    .join(":"),
+            ),
+            None => (None, reference.to_string()),
+        },
+    };
+    let target = match sheet_name {
+        Some(name) => {
+            let name = name.trim_start_matches('$');
+            let name = name
+                .strip_prefix('\'')
+                .and_then(|n| n.strip_suffix('\''))
+                .map(|n| n.replace("''", "'"))
+                .unwrap_or_else(|| name.to_string());
+            workbook.get_sheet(&name)?
+        }
+        None => sheet,
+    };
+
+    let (start_row, start_col, end_row, end_col) = parse_cell_range(&range)
+        .or_else(|| parse_cell_ref(&range).map(|(row, col)| (row, col, row, col)))?;
+
+    let mut items = Vec::new();
+    for cell in target.cells_in_range((start_row, start_col), (end_row, end_col)) {
+        match &cell.value {
+            CellValue::Empty => {}
+            CellValue::Text(text, _) => items.push(text.clone()),
+            CellValue::Number(n) => items.push(n.to_string()),
+            CellValue::Boolean(b) => items.push(if *b { "TRUE" } else { "FALSE" }.to_string()),
+            // The list depends on values that are not stored
+            CellValue::Formula { .. } => return None,
+        }
+    }
+    Some(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::validation::{ValidationKind, ValidationOperator};
+    use crate::reader::workbook::Cell;
+    use crate::reader::{CellStore, DataValidation, DefinedName};
+    use std::path::PathBuf;
+
+    fn sheet_with(name: &str, values: &[((u32, u32), CellValue)]) -> Sheet {
+        let mut cells = CellStore::new();
+        for ((row, col), value) in values {
+            cells.insert(
+                (*row, *col),
+                Cell {
+                    row: *row,
+                    col: *col,
+                    value: value.clone(),
+                    ..Default::default()
+                },
+            );
+        }
+        Sheet {
+            name: name.to_string(),
+            cells,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_literal_bounds() {
+        let mut sheet = sheet_with(
+            "Data",
+            &[
+                ((0, 0), CellValue::Number(5.0)),
+                ((1, 0), CellValue::Number(15.0)),
+                ((2, 0), CellValue::Text("n/a".to_string(), None)),
+                ((3, 0), CellValue::formula("A1*2")),
+                ((4, 1), CellValue::Number(99.0)),
+            ],
+        );
+        sheet.data_validations.push(DataValidation {
+            ranges: vec![(0, 0, 9, 0)],
+            kind: ValidationKind::WholeNumber,
+            operator: ValidationOperator::Between,
+            formula1: Some("1".to_string()),
+            formula2: Some("10".to_string()),
+            ..Default::default()
+        });
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![sheet],
+            ..Default::default()
+        };
+
+        let violations = DataValidationViolationsRule.check(&workbook).unwrap();
+        let cells: Vec<_> = violations
+            .iter()
+            .map(|v| match &v.scope {
+                ViolationScope::Cell(_, cell) => cell.to_string(),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(cells, vec!["A2", "A3"]);
+        assert!(violations[0].message.contains("'15'"));
+        assert!(
+            violations[0]
+                .message
+                .contains("whole number between 1 and 10")
+        );
+    }
+
+    #[test]
+    fn test_list_from_range_and_name() {
+        let lists = sheet_with(
+            "Lists",
+            &[
+                ((0, 0), CellValue::Text("Red".to_string(), None)),
+                ((1, 0), CellValue::Text("Green".to_string(), None)),
+            ],
+        );
+        let mut data = sheet_with(
+            "Data",
+            &[
+                ((0, 0), CellValue::Text("green".to_string(), None)),
+                ((1, 0), CellValue::Text("Blue".to_string(), None)),
+                ((0, 1), CellValue::Text("Blue".to_string(), None)),
+            ],
+        );
+        let by_range = DataValidation {
+            ranges: vec![(0, 0, 9, 0)],
+            kind: ValidationKind::List,
+            formula1: Some("Lists!$A$1:$A$5".to_string()),
+            ..Default::default()
+        };
+        let by_name = DataValidation {
+            ranges: vec![(0, 1, 0, 1)],
+            kind: ValidationKind::List,
+            formula1: Some("Colors".to_string()),
+            ..Default::default()
+        };
+        data.data_validations = vec![by_range, by_name];
+
+        let workbook = Workbook {
+            path: PathBuf::from("test.ods"),
+            sheets: vec![lists, data],
+            defined_names: vec![DefinedName::new("Colors", "Lists.A1:Lists.A2")],
+            ..Default::default()
+        };
+        let violations = DataValidationViolationsRule.check(&workbook).unwrap();
+        assert_eq!(violations.len(), 2);
+        assert!(violations.iter().all(|v| v.message.contains("'Blue'")));
+    }
+
+    #[test]
+    fn test_unresolvable_list_is_skipped() {
+        let mut data = sheet_with("Data", &[((0, 0), CellValue::Text("x".to_string(), None))]);
+        data.data_validations.push(DataValidation {
+            ranges: vec![(0, 0, 0, 0)],
+            kind: ValidationKind::List,
+            formula1: Some("Missing!$A$1:$A$3".to_string()),
+            ..Default::default()
+        });
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![data],
+            ..Default::default()
+        };
+        assert!(
+            DataValidationViolationsRule
+                .check(&workbook)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
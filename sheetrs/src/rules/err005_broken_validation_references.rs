@@ -0,0 +1,208 @@
+//! ERR005: Data validations pointing at #REF!, missing sheets or missing names
+
+use super::{LinterRule, RuleAccess, RuleCategory};
+use crate::reader::{DataValidation, Workbook};
+use crate::violation::{CellReference, Severity, Violation, ViolationScope};
+use anyhow::Result;
+use regex::Regex;
+use std::sync::OnceLock;
+
+pub struct BrokenValidationReferencesRule;
+
+impl LinterRule for BrokenValidationReferencesRule {
+    fn id(&self) -> &str {
+        "ERR005"
+    }
+
+    fn name(&self) -> &str {
+        "Broken data validation references"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::UnresolvedErrors
+    }
+
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        for sheet in &workbook.sheets {
+            for validation in &sheet.data_validations {
+                if let Some(problem) = find_broken_reference(workbook, &sheet.name, validation) {
+                    violations.push(Violation::new(
+                        self.id(),
+                        ViolationScope::Sheet(sheet.name.clone()),
+                        format!(
+                            "Data validation on {} {}",
+                            format_ranges(&validation.ranges),
+                            problem
+                        ),
+                        Severity::Error,
+                    ));
+                }
+            }
+        }
+
+        Ok(violations)
+    }
+}
+
+/// Describe the first broken reference in the validation's formulas
+fn find_broken_reference(
+    workbook: &Workbook,
+    sheet: &str,
+    validation: &DataValidation,
+) -> Option<String> {
+    // Literal lists are plain text
+    if validation.list_items().is_some() {
+        return None;
+    }
+
+    [&validation.formula1, &validation.formula2]
+        .into_iter()
+        .flatten()
+        .find_map(|formula| find_broken_formula_reference(workbook, sheet, formula))
+}
+
+/// Describe a reference to #REF!, a missing sheet or an undefined name in a
+/// formula evaluated on `sheet`
+pub(super) fn find_broken_formula_reference(
+    workbook: &Workbook,
+    sheet: &str,
+    formula: &str,
+) -> Option<String> {
+    static SHEET_REF: OnceLock<Regex> = OnceLock::new();
+    static NAME: OnceLock<Regex> = OnceLock::new();
+    static CELL: OnceLock<Regex> = OnceLock::new();
+    // Group 1: external workbook index, group 2: quoted sheet, group 3: plain sheet
+    let sheet_ref = SHEET_REF.get_or_init(|| {
+        Regex::new(r"(\[[^\]]*\])?(?:'((?:[^']|'')+)'|([A-Za-z0-9_.]+))!").unwrap()
+    });
+    let name = NAME.get_or_init(|| Regex::new(r"^[A-Za-z_\\][A-Za-z0-9_.]*$").unwrap());
+    let cell = CELL.get_or_init(|| Regex::new(r"^\$?[A-Za-z]{1,3}\$?[0-9]+$").unwrap());
+
+    let formula = formula.trim().trim_start_matches('=');
+    if formula.contains("#REF!") {
+        return Some(format!("references #REF! ({})", formula));
+    }
+
+    for caps in sheet_ref.captures_iter(formula) {
+        if caps.get(1).is_some() {
+            continue;
+        }
+        let sheet_name = match (caps.get(2), caps.get(3)) {
+            (Some(quoted), _) => quoted.as_str().replace("''", "'"),
+            (_, Some(plain)) => plain.as_str().to_string(),
+            _ => continue,
+        };
+        if !workbook
+            .sheets
+            .iter()
+            .any(|s| s.name.eq_ignore_ascii_case(&sheet_name))
+        {
+            return Some(format!(
+                "references missing sheet '{}' ({})",
+                sheet_name, formula
+            ));
+        }
+    }
+
+    // A bare identifier that is not a cell, a boolean or a known name
+    if name.is_match(formula)
+        && !cell.is_match(formula)
+        && !formula.eq_ignore_ascii_case("TRUE")
+        && !formula.eq_ignore_ascii_case("FALSE")
+        && workbook.defined_name(formula, Some(sheet)).is_none()
+    {
+        return Some(format!("references undefined name '{}'", formula));
+    }
+
+    None
+}
+
+/// Format ranges as `A1:A10, C3`
+pub(super) fn format_ranges(ranges: &[(u32, u32, u32, u32)]) -> String {
+    ranges
+        .iter()
+        .map(|&(start_row, start_col, end_row, end_col)| {
+            let start = CellReference::new(start_row, start_col);
+            if (start_row, start_col) == (end_row, end_col) {
+                start.to_string()
+            } else {
+                format!("{}:{}", start, CellReference::new(end_row, end_col))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::DefinedName;
+    use crate::reader::validation::ValidationKind;
+    use crate::reader::workbook::Sheet;
+    use std::path::PathBuf;
+
+    fn list(source: &str) -> DataValidation {
+        DataValidation {
+            ranges: vec![(0, 0, 9, 0), (2, 2, 2, 2)],
+            kind: ValidationKind::List,
+            formula1: Some(source.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_broken_validation_references() {
+        let data = Sheet {
+            name: "Data".to_string(),
+            data_validations: vec![
+                list("Lists!$A$1:$A$5"),
+                list("#REF!"),
+                list("'Old Lists'!$A$1:$A$5"),
+                list("Colors"),
+                list("Sizes"),
+                list("\"Missing!,Other\""),
+                list("[1]Remote!$A$1:$A$5"),
+            ],
+            ..Default::default()
+        };
+        let lists = Sheet {
+            name: "Lists".to_string(),
+            ..Default::default()
+        };
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![data, lists],
+            defined_names: vec![
+                DefinedName::new("Colors", "Lists!$B$1:$B$3"),
+                // Local to another sheet, so not visible from Data
+                DefinedName {
+                    scope: Some("Lists".to_string()),
+                    ..DefinedName::new("Sizes", "Lists!$C$1:$C$3")
+                },
+            ],
+            ..Default::default()
+        };
+
+        let violations = BrokenValidationReferencesRule.check(&workbook).unwrap();
+        let messages: Vec<_> = violations.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Data validation on A1:A10, C3 references #REF! (#REF!)",
+                "Data validation on A1:A10, C3 references missing sheet 'Old Lists' ('Old Lists'!$A$1:$A$5)",
+                "Data validation on A1:A10, C3 references undefined name 'Sizes'",
+            ]
+        );
+        assert!(
+            violations
+                .iter()
+                .all(|v| v.scope == ViolationScope::Sheet("Data".to_string()))
+        );
+    }
+}
@@ -0,0 +1,108 @@
+//! ERR006: Conditional formats pointing at #REF!, missing sheets or missing names
+
+use super::err005_broken_validation_references::{find_broken_formula_reference, format_ranges};
+use super::{LinterRule, RuleAccess, RuleCategory};
+use crate::reader::Workbook;
+use crate::violation::{Severity, Violation, ViolationScope};
+use anyhow::Result;
+
+pub struct BrokenConditionalFormatsRule;
+
+impl LinterRule for BrokenConditionalFormatsRule {
+    fn id(&self) -> &str {
+        "ERR006"
+    }
+
+    fn name(&self) -> &str {
+        "Broken conditional formatting references"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::UnresolvedErrors
+    }
+
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        for sheet in &workbook.sheets {
+            for rule in &sheet.conditional_formats {
+                if let Some(problem) = rule.formulas.iter().find_map(|formula| {
+                    find_broken_formula_reference(workbook, &sheet.name, formula)
+                }) {
+                    violations.push(Violation::new(
+                        self.id(),
+                        ViolationScope::Sheet(sheet.name.clone()),
+                        format!(
+                            "Conditional format ({}) on {} {}",
+                            rule.kind.as_str(),
+                            format_ranges(&rule.ranges),
+                            problem
+                        ),
+                        Severity::Error,
+                    ));
+                }
+            }
+        }
+
+        Ok(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::conditional_format::ConditionalFormatKind;
+    use crate::reader::workbook::Sheet;
+    use crate::reader::{ConditionalFormat, DefinedName};
+    use std::path::PathBuf;
+
+    fn expression(formula: &str) -> ConditionalFormat {
+        ConditionalFormat {
+            ranges: vec![(1, 0, 9, 3)],
+            kind: ConditionalFormatKind::Expression,
+            formulas: vec![formula.to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_broken_conditional_formats() {
+        let data = Sheet {
+            name: "Data".to_string(),
+            conditional_formats: vec![
+                expression("$B2>Limits!$A$1"),
+                expression("#REF!>0"),
+                expression("$B2>'Old Limits'!$A$1"),
+                expression("Threshold"),
+                expression("Missing"),
+                expression("TRUE"),
+            ],
+            ..Default::default()
+        };
+        let limits = Sheet {
+            name: "Limits".to_string(),
+            ..Default::default()
+        };
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![data, limits],
+            defined_names: vec![DefinedName::new("Threshold", "Limits!$A$1")],
+            ..Default::default()
+        };
+
+        let violations = BrokenConditionalFormatsRule.check(&workbook).unwrap();
+        let messages: Vec<_> = violations.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Conditional format (expression) on A2:D10 references #REF! (#REF!>0)",
+                "Conditional format (expression) on A2:D10 references missing sheet 'Old Limits' ($B2>'Old Limits'!$A$1)",
+                "Conditional format (expression) on A2:D10 references undefined name 'Missing'",
+            ]
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! ERR007: Pivot tables reading #REF!, missing sheets or names, or only part of their data
+//!
+//! A pivot table's source range doesn't grow when rows or columns are added
+//! next to it, so the pivot silently leaves the new data out.
+
+use super::err005_broken_validation_references::find_broken_formula_reference;
+use super::{LinterRule, RuleAccess, RuleCategory};
+use crate::reader::{CellValue, PivotTable, Workbook};
+use crate::violation::{CellReference, Severity, Violation, ViolationScope};
+use anyhow::Result;
+
+pub struct BrokenPivotSourcesRule;
+
+impl LinterRule for BrokenPivotSourcesRule {
+    fn id(&self) -> &str {
+        "ERR007"
+    }
+
+    fn name(&self) -> &str {
+        "Broken or stale pivot table sources"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::UnresolvedErrors
+    }
+
+    fn access(&self) -> RuleAccess {
+        // Whether the data grew past the source range depends on the source
+        // sheet's cells
+        RuleAccess::Workbook
+    }
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        for pivot in &workbook.pivot_tables {
+            let Some(formula) = pivot.source.formula() else {
+                continue;
+            };
+            let (message, severity) = if let Some(problem) =
+                find_broken_formula_reference(workbook, &pivot.sheet, &formula)
+            {
+                (
+                    format!("Pivot table '{}' {}", pivot.name, problem),
+                    Severity::Error,
+                )
+            } else if let Some(cell) = find_data_outside_source(workbook, pivot) {
+                (
+                    format!(
+                        "Pivot table '{}' reads {} but the data continues at {}",
+                        pivot.name, formula, cell
+                    ),
+                    Severity::Warning,
+                )
+            } else {
+                continue;
+            };
+
+            let scope = if pivot.sheet.is_empty() {
+                ViolationScope::Book
+            } else {
+                ViolationScope::Cell(
+                    pivot.sheet.clone(),
+                    CellReference::new(pivot.location.0, pivot.location.1),
+                )
+            };
+            violations.push(Violation::new(self.id(), scope, message, severity));
+        }
+
+        Ok(violations)
+    }
+}
+
+/// First filled cell in the row just below a range source, or right of its
+/// header row
+fn find_data_outside_source(workbook: &Workbook, pivot: &PivotTable) -> Option<CellReference> {
+    let (sheet, (r1, c1, r2, c2)) = pivot.source.range()?;
+    let sheet = workbook.get_sheet(sheet)?;
+    let filled = |row: u32, col: u32| {
+        sheet
+            .get_cell(row, col)
+            .is_some_and(|cell| !matches!(cell.value, CellValue::Empty))
+    };
+
+    let below = r2.checked_add(1).and_then(|row| {
+        (c1..=c2)
+            .find(|&col| filled(row, col))
+            .map(|col| CellReference::new(row, col))
+    });
+    below.or_else(|| {
+        c2.checked_add(1)
+            .filter(|&col| filled(r1, col))
+            .map(|col| CellReference::new(r1, col))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::pivot::PivotSource;
+    use crate::reader::workbook::{Cell, Sheet};
+    use crate::reader::{CellStore, DefinedName};
+    use std::path::PathBuf;
+
+    fn pivot(name: &str, source: PivotSource) -> PivotTable {
+        PivotTable {
+            name: name.to_string(),
+            sheet: "Report".to_string(),
+            location: (2, 0, 7, 2),
+            source,
+            cache: None,
+        }
+    }
+
+    fn range(sheet: &str, reference: &str) -> PivotSource {
+        PivotSource::Range {
+            sheet: sheet.to_string(),
+            reference: reference.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_broken_pivot_sources() {
+        let mut cells = CellStore::new();
+        // A1:B4 holds the data, C1 a header added later and A5 a new row
+        for (row, col) in [(0, 0), (0, 1), (1, 0), (2, 1), (0, 2), (4, 0)] {
+            cells.insert(
+                (row, col),
+                Cell {
+                    row,
+                    col,
+                    value: CellValue::Number(1.0),
+                    ..Default::default()
+                },
+            );
+        }
+        let data = Sheet {
+            name: "Data".to_string(),
+            cells,
+            ..Default::default()
+        };
+        let report = Sheet {
+            name: "Report".to_string(),
+            ..Default::default()
+        };
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![data, report],
+            defined_names: vec![DefinedName::new("Sales", "Data!$A$1:$C$5")],
+            pivot_tables: vec![
+                pivot("Complete", range("Data", "A1:C5")),
+                pivot("Deleted", range("Data", "#REF!")),
+                pivot("Renamed", range("Old Data", "A1:C5")),
+                pivot("Named", PivotSource::Name("Sales".to_string())),
+                pivot("Unnamed", PivotSource::Name("Missing".to_string())),
+                pivot("Short", range("Data", "A1:C4")),
+                pivot("Narrow", range("Data", "A1:B5")),
+                pivot(
+                    "Elsewhere",
+                    PivotSource::External {
+                        workbook: "q1.xlsx".to_string(),
+                        reference: "#REF!".to_string(),
+                    },
+                ),
+            ],
+            ..Default::default()
+        };
+
+        let violations = BrokenPivotSourcesRule.check(&workbook).unwrap();
+        let messages: Vec<_> = violations.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Pivot table 'Deleted' references #REF! (Data!#REF!)",
+                "Pivot table 'Renamed' references missing sheet 'Old Data' ('Old Data'!A1:C5)",
+                "Pivot table 'Unnamed' references undefined name 'Missing'",
+                "Pivot table 'Short' reads Data!A1:C4 but the data continues at A5",
+                "Pivot table 'Narrow' reads Data!A1:B5 but the data continues at C1",
+            ]
+        );
+        assert_eq!(violations[0].severity, Severity::Error);
+        assert_eq!(violations[3].severity, Severity::Warning);
+        assert_eq!(
+            violations[0].scope,
+            ViolationScope::Cell("Report".to_string(), CellReference::new(2, 0))
+        );
+    }
+}
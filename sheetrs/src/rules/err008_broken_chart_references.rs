@@ -0,0 +1,142 @@
+//! ERR008: Charts plotting #REF!, missing sheets or missing names
+//!
+//! Deleting the rows or sheet a chart series reads leaves `#REF!` in the
+//! series, and the chart quietly shows less data or none at all.
+
+use super::err005_broken_validation_references::find_broken_formula_reference;
+use super::{LinterRule, RuleAccess, RuleCategory};
+use crate::reader::{Chart, Workbook};
+use crate::violation::{CellReference, Severity, Violation, ViolationScope};
+use anyhow::Result;
+
+pub struct BrokenChartReferencesRule;
+
+impl LinterRule for BrokenChartReferencesRule {
+    fn id(&self) -> &str {
+        "ERR008"
+    }
+
+    fn name(&self) -> &str {
+        "Broken chart references"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::UnresolvedErrors
+    }
+
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        for sheet in &workbook.sheets {
+            for chart in &sheet.charts {
+                // One violation per chart, for its first broken reference
+                let Some(problem) = chart
+                    .series
+                    .iter()
+                    .flat_map(|series| series.references())
+                    .find_map(|reference| {
+                        find_broken_formula_reference(workbook, &sheet.name, reference)
+                    })
+                else {
+                    continue;
+                };
+                violations.push(Violation::new(
+                    self.id(),
+                    ViolationScope::Cell(
+                        sheet.name.clone(),
+                        CellReference::new(chart.anchor.0, chart.anchor.1),
+                    ),
+                    format!("{} {}", describe_chart(chart), problem),
+                    Severity::Error,
+                ));
+            }
+        }
+
+        Ok(violations)
+    }
+}
+
+fn describe_chart(chart: &Chart) -> String {
+    match &chart.title {
+        Some(title) => format!("Chart '{}'", title),
+        None if chart.kind.is_empty() => "Chart".to_string(),
+        None => format!("{} chart", capitalize(&chart.kind)),
+    }
+}
+
+/// `text` with its first letter in upper case
+pub(super) fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::DefinedName;
+    use crate::reader::drawings::ChartSeries;
+    use crate::reader::workbook::Sheet;
+    use std::path::PathBuf;
+
+    fn chart(title: Option<&str>, values: &[&str]) -> Chart {
+        Chart {
+            kind: "line".to_string(),
+            title: title.map(str::to_string),
+            anchor: (4, 1),
+            series: values
+                .iter()
+                .map(|values| ChartSeries {
+                    name: Some("Data!$B$1".to_string()),
+                    categories: Some("Data!$A$2:$A$13".to_string()),
+                    values: Some(values.to_string()),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_broken_chart_references() {
+        let report = Sheet {
+            name: "Report".to_string(),
+            charts: vec![
+                chart(Some("Sales"), &["Data!$B$2:$B$13", "Sales"]),
+                chart(Some("Costs"), &["Data!$B$2:$B$13", "Data!#REF!"]),
+                chart(None, &["'Old Data'!$C$2:$C$13"]),
+                chart(None, &["Missing"]),
+            ],
+            ..Default::default()
+        };
+        let data = Sheet {
+            name: "Data".to_string(),
+            ..Default::default()
+        };
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![data, report],
+            defined_names: vec![DefinedName::new("Sales", "Data!$C$2:$C$13")],
+            ..Default::default()
+        };
+
+        let violations = BrokenChartReferencesRule.check(&workbook).unwrap();
+        let messages: Vec<_> = violations.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Chart 'Costs' references #REF! (Data!#REF!)",
+                "Line chart references missing sheet 'Old Data' ('Old Data'!$C$2:$C$13)",
+                "Line chart references undefined name 'Missing'",
+            ]
+        );
+        assert_eq!(
+            violations[0].scope,
+            ViolationScope::Cell("Report".to_string(), CellReference::new(4, 1))
+        );
+    }
+}
@@ -0,0 +1,279 @@
+//! ERR009: Links to workbooks, sheets or cells that are gone
+//!
+//! Formulas reading another workbook keep showing the values cached when the
+//! link was last updated, so a moved file or a deleted sheet goes unnoticed
+//! until someone updates the links and gets `#REF!`. This rule opens each
+//! linked workbook it can find (at its recorded location, else by name in the
+//! `external_search_paths` folders or next to the linting workbook) and checks
+//! that the sheets and cells the formulas read still exist.
+
+use super::{LinterRule, RuleAccess, RuleCategory};
+use crate::config::LinterConfig;
+use crate::reader::tables::format_range;
+use crate::reader::{ExternalWorkbook, Workbook, open_workbook};
+use crate::violation::{Severity, Violation, ViolationScope};
+use anyhow::Result;
+use std::path::PathBuf;
+
+/// Empty cells listed in one violation before the rest are only counted
+const MAX_LISTED_CELLS: usize = 5;
+
+#[derive(Default)]
+pub struct BrokenExternalLinksRule {
+    search_paths: Vec<PathBuf>,
+}
+
+impl BrokenExternalLinksRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        let search_paths = config
+            .get_param_array("external_search_paths", None)
+            .unwrap_or_default()
+            .into_iter()
+            .map(PathBuf::from)
+            .collect();
+        Self { search_paths }
+    }
+
+    /// Problems with one linked workbook, as (message, severity)
+    fn check_link(&self, workbook: &Workbook, link: &ExternalWorkbook) -> Vec<(String, Severity)> {
+        let Some(location) = link.locate(&workbook.path, &self.search_paths) else {
+            return vec![(
+                format!(
+                    "External workbook '{}' not found (linked as '{}')",
+                    link.path, link.target
+                ),
+                Severity::Warning,
+            )];
+        };
+        let mut linked = match open_workbook(&location) {
+            Ok(linked) => linked,
+            Err(e) => {
+                return vec![(
+                    format!(
+                        "External workbook '{}' could not be read: {}",
+                        location.display(),
+                        e
+                    ),
+                    Severity::Warning,
+                )];
+            }
+        };
+
+        let mut problems = Vec::new();
+        let mut empty_cells = Vec::new();
+        // Sheets without cached cells aren't read by any formula
+        for sheet in link.sheets.iter().filter(|sheet| !sheet.cells.is_empty()) {
+            let Some(name) = linked
+                .sheet_names()
+                .iter()
+                .find(|name| name.eq_ignore_ascii_case(&sheet.name))
+                .cloned()
+            else {
+                problems.push((
+                    format!(
+                        "Sheet '{}' of '{}' no longer exists",
+                        sheet.name,
+                        location.display()
+                    ),
+                    Severity::Error,
+                ));
+                continue;
+            };
+            let Ok(current) = linked.read_sheet(&name) else {
+                continue;
+            };
+            for &(row, col) in sheet.cells.keys() {
+                let is_empty = current
+                    .get_cell(row, col)
+                    .is_none_or(|cell| cell.value.is_empty());
+                if is_empty {
+                    empty_cells.push(format_range(&name, (row, col, row, col)));
+                }
+            }
+        }
+
+        if !empty_cells.is_empty() {
+            let mut listed = empty_cells[..empty_cells.len().min(MAX_LISTED_CELLS)].join(", ");
+            if empty_cells.len() > MAX_LISTED_CELLS {
+                listed.push_str(&format!(
+                    " and {} more",
+                    empty_cells.len() - MAX_LISTED_CELLS
+                ));
+            }
+            problems.push((
+                format!(
+                    "Cells of '{}' read by formulas are now empty: {}",
+                    location.display(),
+                    listed
+                ),
+                Severity::Error,
+            ));
+        }
+        problems
+    }
+}
+
+impl LinterRule for BrokenExternalLinksRule {
+    fn id(&self) -> &str {
+        "ERR009"
+    }
+
+    fn name(&self) -> &str {
+        "Broken external links"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::UnresolvedErrors
+    }
+
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        for link in &workbook.external_workbooks {
+            for (message, severity) in self.check_link(workbook, link) {
+                violations.push(Violation::new(
+                    self.id(),
+                    ViolationScope::Book,
+                    message,
+                    severity,
+                ));
+            }
+        }
+
+        Ok(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::{CellValue, ExternalSheet};
+    use std::io::Write;
+    use std::path::Path;
+    use zip::write::FileOptions;
+
+    /// Write a one-sheet XLSX with a number in B3
+    fn write_linked_workbook(path: &Path, sheet: &str) {
+        let file = std::fs::File::create(path).unwrap();
+        let mut zip = zip::ZipWriter::new(file);
+        let options =
+            FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+        let parts = [
+            (
+                "[Content_Types].xml",
+                r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/></Types>"#.to_string(),
+            ),
+            (
+                "xl/workbook.xml",
+                format!(
+                    r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="{}" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+                    sheet
+                ),
+            ),
+            (
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#.to_string(),
+            ),
+            (
+                "xl/worksheets/sheet1.xml",
+                r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData><row r="3"><c r="B3"><v>8</v></c></row></sheetData></worksheet>"#.to_string(),
+            ),
+        ];
+        for (name, content) in parts {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    fn linked_sheet(name: &str, cells: &[(u32, u32)]) -> ExternalSheet {
+        ExternalSheet {
+            name: name.to_string(),
+            cells: cells
+                .iter()
+                .map(|&cell| (cell, CellValue::Number(1.0)))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_broken_external_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let shared = dir.path().join("shared");
+        std::fs::create_dir(&shared).unwrap();
+        write_linked_workbook(&dir.path().join("Rates.xlsx"), "Rates");
+        write_linked_workbook(&shared.join("Prices.xlsx"), "Prices");
+
+        let workbook = Workbook {
+            path: dir.path().join("Report.xlsx"),
+            external_workbooks: vec![
+                ExternalWorkbook {
+                    index: 0,
+                    path: "Rates.xlsx".to_string(),
+                    target: "Rates.xlsx".to_string(),
+                    sheets: vec![
+                        linked_sheet("rates", &[(2, 1), (3, 2)]),
+                        linked_sheet("Old Rates", &[(0, 0)]),
+                        linked_sheet("Unused", &[]),
+                    ],
+                },
+                // Moved since the link was made, found on the search path
+                ExternalWorkbook {
+                    index: 1,
+                    path: "Prices.xlsx".to_string(),
+                    target: r"C:\Finance\Prices.xlsx".to_string(),
+                    sheets: vec![linked_sheet("Prices", &[(2, 1)])],
+                },
+                ExternalWorkbook {
+                    index: 2,
+                    path: "Missing.xlsx".to_string(),
+                    target: "../archive/Missing.xlsx".to_string(),
+                    sheets: vec![linked_sheet("Data", &[(0, 0)])],
+                },
+            ],
+            ..Default::default()
+        };
+
+        let rule = BrokenExternalLinksRule {
+            search_paths: vec![shared],
+        };
+        let violations = rule.check(&workbook).unwrap();
+        let rates = dir.path().join("Rates.xlsx");
+        let messages: Vec<_> = violations
+            .iter()
+            .map(|v| (v.message.clone(), v.severity))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    format!("Sheet 'Old Rates' of '{}' no longer exists", rates.display()),
+                    Severity::Error
+                ),
+                (
+                    format!(
+                        "Cells of '{}' read by formulas are now empty: Rates!C4",
+                        rates.display()
+                    ),
+                    Severity::Error
+                ),
+                (
+                    "External workbook 'Missing.xlsx' not found (linked as '../archive/Missing.xlsx')"
+                        .to_string(),
+                    Severity::Warning
+                ),
+            ]
+        );
+
+        // Without the search path the moved workbook isn't found
+        let violations = BrokenExternalLinksRule::default().check(&workbook).unwrap();
+        assert!(violations.iter().any(|v| {
+            v.message
+                .starts_with("External workbook 'Prices.xlsx' not found")
+        }));
+    }
+}
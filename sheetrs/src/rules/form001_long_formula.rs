@@ -1,6 +1,6 @@
 //! SM003: Long formula
 
-use super::{LinterRule, RuleCategory};
+use super::{LinterRule, RuleAccess, RuleCategory};
 use crate::config::LinterConfig;
 use crate::reader::Workbook;
 use crate::violation::{Severity, Violation, ViolationScope};
@@ -33,6 +33,10 @@ impl LinterRule for LongFormulaRule {
         RuleCategory::Formula
     }
 
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Sheet
+    }
+
     fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
 
@@ -143,22 +147,22 @@ fn find_contiguous_ranges(cells: &[(u32, u32)]) -> Vec<Vec<(u32, u32)>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::CellStore;
     use crate::reader::workbook::{Cell, CellValue, Sheet};
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     #[test]
     fn test_long_formula() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         let long_formula = "=".to_string() + &"A1+".repeat(100); // >255 chars
 
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula(long_formula),
+                ..Default::default()
             },
         );
 
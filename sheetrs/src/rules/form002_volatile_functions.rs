@@ -1,6 +1,6 @@
 //! FORM002: Avoid volatile functions
 
-use super::{LinterRule, RuleCategory};
+use super::{LinterRule, RuleAccess, RuleCategory};
 use crate::config::LinterConfig;
 use crate::reader::Workbook;
 use crate::violation::{CellReference, Severity, Violation, ViolationScope};
@@ -32,6 +32,15 @@ impl VolatileFunctionsRule {
 
         Self { volatile_functions }
     }
+
+    /// First volatile function called in the formula (its name followed by `(`)
+    fn find_volatile_function(&self, formula: &str) -> Option<&str> {
+        let formula_upper = formula.to_uppercase();
+        self.volatile_functions
+            .iter()
+            .find(|func| formula_upper.contains(&format!("{}(", func)))
+            .map(String::as_str)
+    }
 }
 
 impl Default for VolatileFunctionsRule {
@@ -64,6 +73,10 @@ impl LinterRule for VolatileFunctionsRule {
         RuleCategory::Formula
     }
 
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Sheet
+    }
+
     fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
 
@@ -73,20 +86,13 @@ impl LinterRule for VolatileFunctionsRule {
                 std::collections::HashMap::new();
 
             for cell in sheet.all_cells() {
-                if let Some(formula) = cell.value.as_formula() {
-                    let formula_upper = formula.to_uppercase();
-
-                    for func in &self.volatile_functions {
-                        // Check if function appears in formula
-                        // Look for function name followed by opening parenthesis
-                        if formula_upper.contains(&format!("{}(", func)) {
-                            function_cells
-                                .entry(func.clone())
-                                .or_default()
-                                .push((cell.row, cell.col));
-                            break; // Only count each cell once
-                        }
-                    }
+                if let Some(formula) = cell.value.as_formula()
+                    && let Some(func) = self.find_volatile_function(formula)
+                {
+                    function_cells
+                        .entry(func.to_string())
+                        .or_default()
+                        .push((cell.row, cell.col));
                 }
             }
 
@@ -107,6 +113,26 @@ impl LinterRule for VolatileFunctionsRule {
                     ));
                 }
             }
+
+            // Conditional formats are re-evaluated whenever the sheet is redrawn
+            for rule in &sheet.conditional_formats {
+                if let Some(func) = rule
+                    .formulas
+                    .iter()
+                    .find_map(|formula| self.find_volatile_function(formula))
+                {
+                    violations.push(Violation::new(
+                        self.id(),
+                        ViolationScope::Sheet(sheet.name.clone()),
+                        format!(
+                            "Volatile function {}() found in conditional formatting on {}. Consider alternatives for better performance.",
+                            func,
+                            rule.sqref()
+                        ),
+                        Severity::Info,
+                    ));
+                }
+            }
         }
 
         Ok(violations)
@@ -179,20 +205,20 @@ fn find_contiguous_ranges(cells: &[(u32, u32)]) -> Vec<Vec<(u32, u32)>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::CellStore;
     use crate::reader::workbook::{Cell, CellValue, Sheet};
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     #[test]
     fn test_volatile_function_now() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=NOW()".to_string()),
+                ..Default::default()
             },
         );
 
@@ -200,14 +226,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((1, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -226,23 +246,23 @@ mod tests {
 
     #[test]
     fn test_multiple_volatile_functions() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=RAND()".to_string()),
+                ..Default::default()
             },
         );
         cells.insert(
             (1, 0),
             Cell {
-                num_fmt: None,
                 row: 1,
                 col: 0,
                 value: CellValue::formula("=TODAY()".to_string()),
+                ..Default::default()
             },
         );
 
@@ -250,14 +270,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((2, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -274,14 +288,14 @@ mod tests {
 
     #[test]
     fn test_case_insensitive() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=now()".to_string()),
+                ..Default::default()
             },
         );
 
@@ -289,14 +303,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((1, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -310,4 +318,42 @@ mod tests {
 
         assert_eq!(violations.len(), 1);
     }
+
+    #[test]
+    fn test_volatile_function_in_conditional_format() {
+        use crate::reader::ConditionalFormat;
+        use crate::reader::conditional_format::ConditionalFormatKind;
+
+        let sheet = Sheet {
+            name: "Sheet1".to_string(),
+            conditional_formats: vec![
+                ConditionalFormat {
+                    ranges: vec![(1, 0, 99, 0)],
+                    kind: ConditionalFormatKind::Expression,
+                    formulas: vec!["$A2<TODAY()".to_string()],
+                    ..Default::default()
+                },
+                ConditionalFormat {
+                    ranges: vec![(1, 1, 99, 1)],
+                    kind: ConditionalFormatKind::Expression,
+                    formulas: vec!["$B2>0".to_string()],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![sheet],
+            ..Default::default()
+        };
+
+        let violations = VolatileFunctionsRule::default().check(&workbook).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert!(
+            violations[0].message.starts_with(
+                "Volatile function TODAY() found in conditional formatting on A2:A100."
+            )
+        );
+    }
 }
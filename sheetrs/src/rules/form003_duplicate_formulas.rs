@@ -1,6 +1,6 @@
-//! PERF005: Avoid duplicate formulas
+//! FORM003: Avoid duplicate formulas
 
-use super::{LinterRule, RuleCategory};
+use super::{LinterRule, RuleAccess, RuleCategory};
 use crate::reader::Workbook;
 use crate::violation::{CellReference, Severity, Violation, ViolationScope};
 use anyhow::Result;
@@ -21,14 +21,23 @@ impl LinterRule for DuplicateFormulasRule {
         RuleCategory::Formula
     }
 
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Sheet
+    }
+
     fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
 
         for sheet in &workbook.sheets {
             // Group cells by formula content
             let mut formula_cells: HashMap<String, Vec<(u32, u32)>> = HashMap::new();
+            let arrays = sheet.array_formula_index();
 
             for cell in sheet.all_cells() {
+                // Some writers repeat an array formula in every cell of its range
+                if arrays.is_spilled(cell.row, cell.col) {
+                    continue;
+                }
                 if let Some(formula) = cell.value.as_formula() {
                     // Normalize formula for comparison (trim whitespace)
                     let normalized = formula.trim().to_string();
@@ -141,37 +150,37 @@ fn find_contiguous_ranges(cells: &[(u32, u32)]) -> Vec<Vec<(u32, u32)>> {
 mod tests {
     use super::*;
     use crate::reader::workbook::{Cell, CellValue, Sheet};
-    use std::collections::HashMap;
+    use crate::reader::{ArrayFormula, CellStore};
     use std::path::PathBuf;
 
     #[test]
     fn test_duplicate_formulas() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=A1+B1".to_string()),
+                ..Default::default()
             },
         );
         cells.insert(
             (1, 0),
             Cell {
-                num_fmt: None,
                 row: 1,
                 col: 0,
                 value: CellValue::formula("=A1+B1".to_string()),
+                ..Default::default()
             },
         );
         cells.insert(
             (2, 0),
             Cell {
-                num_fmt: None,
                 row: 2,
                 col: 0,
                 value: CellValue::formula("=A1+B1".to_string()),
+                ..Default::default()
             },
         );
 
@@ -179,14 +188,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((3, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -205,23 +208,23 @@ mod tests {
 
     #[test]
     fn test_unique_formulas() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=A1+B1".to_string()),
+                ..Default::default()
             },
         );
         cells.insert(
             (1, 0),
             Cell {
-                num_fmt: None,
                 row: 1,
                 col: 0,
                 value: CellValue::formula("=A2+B2".to_string()),
+                ..Default::default()
             },
         );
 
@@ -229,14 +232,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((2, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -250,4 +247,40 @@ mod tests {
 
         assert_eq!(violations.len(), 0);
     }
+
+    #[test]
+    fn test_array_formula_copies_are_not_duplicates() {
+        let mut cells = CellStore::new();
+        // Legacy array formula written into each cell of A1:A3
+        for row in 0..3 {
+            cells.insert(
+                (row, 0),
+                Cell {
+                    row,
+                    col: 0,
+                    value: CellValue::formula("=B1:B3*2".to_string()),
+                    ..Default::default()
+                },
+            );
+        }
+
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![Sheet {
+                name: "Sheet1".to_string(),
+                cells,
+                array_formulas: vec![ArrayFormula {
+                    row: 0,
+                    col: 0,
+                    range: (0, 0, 2, 0),
+                    dynamic: false,
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let violations = DuplicateFormulasRule.check(&workbook).unwrap();
+        assert!(violations.is_empty());
+    }
 }
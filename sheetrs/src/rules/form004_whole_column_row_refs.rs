@@ -1,6 +1,6 @@
 //! FORM001: Avoid whole-column or whole-row references
 
-use super::{LinterRule, RuleCategory};
+use super::{LinterRule, RuleAccess, RuleCategory};
 use crate::reader::Workbook;
 use crate::violation::{CellReference, Severity, Violation, ViolationScope};
 use anyhow::Result;
@@ -49,6 +49,10 @@ impl LinterRule for WholeColumnRowRefsRule {
         RuleCategory::Formula
     }
 
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Sheet
+    }
+
     fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
 
@@ -178,20 +182,20 @@ fn find_contiguous_ranges(cells: &[(u32, u32)]) -> Vec<Vec<(u32, u32)>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::CellStore;
     use crate::reader::workbook::{Cell, CellValue, Sheet};
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     #[test]
     fn test_whole_column_reference() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=SUM(A:A)".to_string()),
+                ..Default::default()
             },
         );
 
@@ -199,14 +203,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((1, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -225,14 +223,14 @@ mod tests {
 
     #[test]
     fn test_whole_row_reference() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=SUM(1:1)".to_string()),
+                ..Default::default()
             },
         );
 
@@ -240,14 +238,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((1, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -266,14 +258,14 @@ mod tests {
 
     #[test]
     fn test_bounded_reference() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=SUM(A1:A10)".to_string()),
+                ..Default::default()
             },
         );
 
@@ -281,14 +273,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((1, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -1,6 +1,6 @@
 //! FORM002: Empty string test → ISBLANK
 
-use super::{LinterRule, RuleCategory};
+use super::{LinterRule, RuleAccess, RuleCategory};
 use crate::reader::Workbook;
 use crate::violation::{CellReference, Severity, Violation, ViolationScope};
 use anyhow::Result;
@@ -47,6 +47,10 @@ impl LinterRule for EmptyStringTestRule {
         RuleCategory::Formula
     }
 
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Sheet
+    }
+
     fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
 
@@ -158,20 +162,20 @@ fn find_contiguous_ranges(cells: &[(u32, u32)]) -> Vec<Vec<(u32, u32)>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::CellStore;
     use crate::reader::workbook::{Cell, CellValue, Sheet};
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     #[test]
     fn test_empty_string_equals() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula(r#"=IF(A1="","Empty","Not Empty")"#.to_string()),
+                ..Default::default()
             },
         );
 
@@ -179,14 +183,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((1, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -205,14 +203,14 @@ mod tests {
 
     #[test]
     fn test_empty_string_not_equals() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula(r#"=IF(A1<>"","Not Empty","Empty")"#.to_string()),
+                ..Default::default()
             },
         );
 
@@ -220,14 +218,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((1, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -245,14 +237,14 @@ mod tests {
 
     #[test]
     fn test_len_equals_zero() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=IF(LEN(A1)=0,\"Empty\",\"Not Empty\")".to_string()),
+                ..Default::default()
             },
         );
 
@@ -260,14 +252,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((1, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -285,14 +271,14 @@ mod tests {
 
     #[test]
     fn test_isblank_usage() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=IF(ISBLANK(A1),\"Empty\",\"Not Empty\")".to_string()),
+                ..Default::default()
             },
         );
 
@@ -300,14 +286,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((1, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -1,6 +1,6 @@
 use crate::config::LinterConfig;
 use crate::reader::{CellValue, Workbook};
-use crate::rules::{LinterRule, RuleCategory};
+use crate::rules::{LinterRule, RuleAccess, RuleCategory};
 use crate::violation::{Severity, Violation, ViolationScope};
 use regex::Regex;
 
@@ -90,6 +90,10 @@ impl LinterRule for HardcodedValuesInFormulasRule {
         RuleCategory::Formula
     }
 
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Sheet
+    }
+
     fn check(&self, workbook: &Workbook) -> anyhow::Result<Vec<Violation>> {
         let mut violations = Vec::new();
 
@@ -114,7 +118,11 @@ impl LinterRule for HardcodedValuesInFormulasRule {
             // To support per-sheet overrides fully, we'd need to update `new` or passing logic,
             // or store `LinterConfig` and look up here.
 
+            let arrays = sheet.array_formula_index();
             for ((row, col), cell) in &sheet.cells {
+                if arrays.is_spilled(row, col) {
+                    continue;
+                }
                 if let CellValue::Formula { formula, .. } = &cell.value {
                     // Remove strings first
                     let formula_no_strings = string_regex.replace_all(formula, "");
@@ -146,10 +154,7 @@ impl LinterRule for HardcodedValuesInFormulasRule {
                                         self.id(),
                                         ViolationScope::Cell(
                                             sheet.name.clone(),
-                                            crate::violation::CellReference {
-                                                row: *row,
-                                                col: *col,
-                                            },
+                                            crate::violation::CellReference { row, col },
                                         ),
                                         format!("Hardcoded value found in formula: {}", val),
                                         Severity::Warning,
@@ -169,49 +174,49 @@ impl LinterRule for HardcodedValuesInFormulasRule {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::CellStore;
     use crate::reader::{Cell, Sheet};
-    use std::collections::HashMap;
     use std::path::PathBuf;
     use toml::Value;
 
     #[test]
     fn test_hardcoded_values() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=123+A1".to_string()),
+                ..Default::default()
             },
         ); // 123 (int)
         cells.insert(
             (0, 1),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 1,
                 value: CellValue::formula("=0+1.5".to_string()),
+                ..Default::default()
             },
         ); // 0 (int), 1.5 (float)
         cells.insert(
             (0, 2),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 2,
                 value: CellValue::formula(r#"=IF(A1>10, "Value: 5", 100)"#.to_string()),
+                ..Default::default()
             },
         ); // 10 (int, pow10), 5 (string), 100 (int, pow10)
 
         cells.insert(
             (0, 3),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 3,
                 value: CellValue::formula("=0.1+0.01".to_string()),
+                ..Default::default()
             },
         ); // 0.1 (pow10), 0.01 (pow10)
 
@@ -219,14 +224,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((1, 4)),
-            hidden_columns: vec![],
-            hidden_rows: vec![],
-            merged_cells: vec![],
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
-            sheet_path: None,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -354,26 +353,26 @@ mod tests {
 
     #[test]
     fn test_external_link_indices_not_flagged() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
 
         // External link formulas - indices should NOT be flagged
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=[1]Sheet1!A1".to_string()),
+                ..Default::default()
             },
         );
 
         cells.insert(
             (0, 1),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 1,
                 value: CellValue::formula("=[2]Data!B5".to_string()),
+                ..Default::default()
             },
         );
 
@@ -381,10 +380,10 @@ mod tests {
         cells.insert(
             (0, 2),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 2,
                 value: CellValue::formula("=[1]Sheet1!A1+5".to_string()),
+                ..Default::default()
             },
         );
 
@@ -392,14 +391,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((1, 3)),
-            hidden_columns: vec![],
-            hidden_rows: vec![],
-            merged_cells: vec![],
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
-            sheet_path: None,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -28,6 +28,10 @@ impl LinterRule for VLookupHLookupUsageRule {
         crate::rules::RuleCategory::Formula
     }
 
+    fn access(&self) -> crate::rules::RuleAccess {
+        crate::rules::RuleAccess::Sheet
+    }
+
     fn check(&self, workbook: &Workbook) -> anyhow::Result<Vec<Violation>> {
         let mut violations = Vec::new();
 
@@ -40,10 +44,7 @@ impl LinterRule for VLookupHLookupUsageRule {
                             self.id(),
                             ViolationScope::Cell(
                                 sheet.name.clone(),
-                                crate::violation::CellReference {
-                                    row: *row,
-                                    col: *col,
-                                },
+                                crate::violation::CellReference { row, col },
                             ),
                             "Avoid using VLOOKUP/HLOOKUP. Use XLOOKUP or INDEX/MATCH instead."
                                 .to_string(),
@@ -61,38 +62,38 @@ impl LinterRule for VLookupHLookupUsageRule {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::CellStore;
     use crate::reader::{Cell, Sheet};
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     #[test]
     fn test_vlookup_detection() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=VLOOKUP(A1, B:C, 2, FALSE)".to_string()),
+                ..Default::default()
             },
         );
         cells.insert(
             (0, 1),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 1,
                 value: CellValue::formula("=HLOOKUP(A1, B:C, 2, FALSE)".to_string()),
+                ..Default::default()
             },
         );
         cells.insert(
             (0, 2),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 2,
                 value: CellValue::formula("=SUM(A1:A10)".to_string()),
+                ..Default::default()
             },
         );
 
@@ -100,14 +101,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((1, 3)),
-            hidden_columns: vec![],
-            hidden_rows: vec![],
-            merged_cells: vec![],
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
-            sheet_path: None,
+            ..Default::default()
         };
 
         let workbook = Workbook {
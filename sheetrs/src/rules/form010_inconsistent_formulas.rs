@@ -0,0 +1,148 @@
+//! FORM010: Inconsistent formulas
+//!
+//! A formula whose neighbours on both sides, above and below or left and
+//! right, are copies of one formula (they share its R1C1 form) but which is
+//! not a copy itself was usually typed over or copied from the wrong cell.
+
+use super::{LinterRule, RuleAccess, RuleCategory};
+use crate::reader::Workbook;
+use crate::violation::{CellReference, Severity, Violation, ViolationScope};
+use anyhow::Result;
+use std::collections::HashMap;
+
+pub struct InconsistentFormulasRule;
+
+impl LinterRule for InconsistentFormulasRule {
+    fn id(&self) -> &str {
+        "FORM010"
+    }
+
+    fn name(&self) -> &str {
+        "Inconsistent formulas"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Formula
+    }
+
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Sheet
+    }
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        for sheet in &workbook.sheets {
+            // R1C1 form and text of each formula cell
+            let mut formulas: HashMap<(u32, u32), (String, &str)> = HashMap::new();
+            let arrays = sheet.array_formula_index();
+            for cell in sheet.all_cells() {
+                if arrays.is_spilled(cell.row, cell.col) {
+                    continue;
+                }
+                if let (Some(formula), Some(pattern)) =
+                    (cell.value.as_formula(), cell.formula_r1c1())
+                {
+                    formulas.insert((cell.row, cell.col), (pattern, formula.trim()));
+                }
+            }
+
+            let mut cells: Vec<&(u32, u32)> = formulas.keys().collect();
+            cells.sort();
+            for &(row, col) in cells {
+                let pattern = &formulas[&(row, col)].0;
+                let sides = [
+                    (row.checked_sub(1).map(|r| (r, col)), (row + 1, col)),
+                    (col.checked_sub(1).map(|c| (row, c)), (row, col + 1)),
+                ];
+                for (before, after) in sides {
+                    let (Some(before), Some(after)) =
+                        (before.and_then(|b| formulas.get(&b)), formulas.get(&after))
+                    else {
+                        continue;
+                    };
+                    if before.0 == after.0 && before.0 != *pattern {
+                        violations.push(Violation::new(
+                            self.id(),
+                            ViolationScope::Cell(sheet.name.clone(), CellReference::new(row, col)),
+                            format!(
+                                "Formula differs from the formulas around it, which are copies of '{}'",
+                                before.1
+                            ),
+                            Severity::Warning,
+                        ));
+                        break;
+                    }
+                }
+            }
+        }
+
+        Ok(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::CellStore;
+    use crate::reader::workbook::{Cell, CellValue, Sheet};
+    use std::path::PathBuf;
+
+    fn sheet_with_formulas(formulas: &[(u32, u32, &str)]) -> Workbook {
+        let mut cells = CellStore::new();
+        for &(row, col, formula) in formulas {
+            cells.insert(
+                (row, col),
+                Cell {
+                    row,
+                    col,
+                    value: CellValue::formula(formula),
+                    ..Default::default()
+                },
+            );
+        }
+
+        Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![Sheet {
+                name: "Sheet1".to_string(),
+                cells,
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_inconsistent_formula() {
+        // C2 breaks the `=A1+B1` column copied down C1:C3
+        let workbook = sheet_with_formulas(&[(0, 2, "=A1+B1"), (1, 2, "=A2*B2"), (2, 2, "=A3+B3")]);
+
+        let violations = InconsistentFormulasRule.check(&workbook).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].scope,
+            ViolationScope::Cell("Sheet1".to_string(), CellReference::new(1, 2))
+        );
+        assert_eq!(
+            violations[0].message,
+            "Formula differs from the formulas around it, which are copies of '=A1+B1'"
+        );
+    }
+
+    #[test]
+    fn test_copied_formulas() {
+        // Filled down and across, plus a differing formula at the end of a run
+        let workbook = sheet_with_formulas(&[
+            (6, 7, r#"=IF(I7="", "Y","N")"#),
+            (7, 7, r#"=IF(I8="", "Y","N")"#),
+            (8, 7, "=COUNTA(H7:H8)"),
+            (0, 0, "=$D$1*B1"),
+            (0, 1, "=$D$1*C1"),
+            (0, 2, "=$D$1*D1"),
+        ]);
+
+        let violations = InconsistentFormulasRule.check(&workbook).unwrap();
+        assert!(violations.is_empty());
+    }
+}
@@ -6,6 +6,12 @@ pub mod registry;
 pub mod err001_error_cells;
 pub mod err002_broken_named_ranges;
 pub mod err003_circular_references;
+pub mod err004_data_validation_violations;
+pub mod err005_broken_validation_references;
+pub mod err006_broken_conditional_formats;
+pub mod err007_broken_pivot_sources;
+pub mod err008_broken_chart_references;
+pub mod err009_broken_external_links;
 pub mod form001_long_formula;
 pub mod form002_volatile_functions;
 pub mod form003_duplicate_formulas;
@@ -15,16 +21,27 @@ pub mod form006_deep_formula_nesting;
 pub mod form007_deep_if_nesting;
 pub mod form008_hardcoded_values_in_formulas;
 pub mod form009_vlookup_hlookup_usage;
+pub mod form010_inconsistent_formulas;
 pub mod perf001_unused_named_ranges;
 pub mod perf002_unused_sheets;
 pub mod perf003_large_used_range;
 pub mod perf004_excessive_conditional_formatting;
 pub mod perf005_empty_sheets;
+pub mod perf006_fragmented_conditional_formatting;
+pub mod perf007_large_pivot_caches;
 pub mod sec001_external_workbooks;
 pub mod sec002_hidden_sheets;
 pub mod sec003_hidden_columns_rows;
 pub mod sec004_has_macros;
 pub mod sec005_web_urls;
+pub mod sec006_weak_protection;
+pub mod sec007_personal_metadata;
+pub mod sec008_external_pivot_sources;
+pub mod sec009_embedded_objects;
+pub mod sec010_data_connections;
+pub mod sec011_auto_run_macros;
+pub mod sec012_suspicious_macro_code;
+pub mod sec013_personal_headers_footers;
 
 pub mod sm001_excessive_sheet_counts;
 pub mod sm002_duplicate_sheet_names;
@@ -34,6 +51,11 @@ pub mod sm005_non_descriptive_sheet_name;
 pub mod ux001_inconsistent_number_format;
 pub mod ux002_inconsistent_date_format;
 pub mod ux003_blank_rows_columns;
+pub mod ux004_unvalidated_inputs;
+pub mod ux005_unfrozen_header_row;
+pub mod ux006_narrow_columns;
+pub mod ux007_deep_outline_groups;
+pub mod ux008_print_area_outside_data;
 
 use crate::reader::Workbook;
 use crate::violation::Violation;
@@ -50,10 +72,27 @@ pub trait LinterRule: Send + Sync {
     /// Rule category
     fn category(&self) -> RuleCategory;
 
+    /// How much of the workbook the rule needs loaded at once
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Workbook
+    }
+
     /// Check the workbook for violations
     fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>>;
 }
 
+/// Data a rule needs in memory, used to pick rules that can run in streaming mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleAccess {
+    /// Workbook metadata and sheet properties only (names, visibility, hidden
+    /// rows/columns, merges, conditional formats); never reads cells
+    Metadata,
+    /// Cells of one sheet at a time; never looks at other sheets' cells
+    Sheet,
+    /// Cells of every sheet together (cross-sheet references, workbook totals)
+    Workbook,
+}
+
 /// Rule categories
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RuleCategory {
@@ -1,7 +1,7 @@
 //! PERF001: Unused named ranges detection
 
 use super::{LinterRule, RuleCategory};
-use crate::reader::Workbook;
+use crate::reader::{DefinedName, Workbook};
 use crate::violation::{Severity, Violation, ViolationScope};
 use anyhow::Result;
 use std::collections::HashSet;
@@ -25,21 +25,40 @@ impl LinterRule for UnusedNamedRangesRule {
         let mut violations = Vec::new();
 
         // Collect all named ranges
-        let named_ranges: HashSet<&str> = workbook
+        let named_ranges: Vec<&DefinedName> = workbook
             .defined_names
-            .keys()
-            .filter(|name| !name.starts_with("_xlnm."))
-            .map(|s| s.as_str())
+            .iter()
+            .filter(|defined| !defined.name.starts_with("_xlnm."))
+            .collect();
+        // Local names can also be used from other sheets as `Sheet1!Name`
+        let qualified: Vec<Option<String>> = named_ranges
+            .iter()
+            .map(|defined| defined.scope.is_some().then(|| defined.qualified_name()))
             .collect();
 
         // Collect all named ranges used in formulas
         let mut used_names = HashSet::new();
         for sheet in &workbook.sheets {
+            // A bare name refers to the sheet's local name if there is one, else
+            // to the workbook-wide name
+            let visible: Vec<bool> = named_ranges
+                .iter()
+                .map(|defined| {
+                    workbook
+                        .defined_name(&defined.name, Some(&sheet.name))
+                        .is_some_and(|seen| std::ptr::eq(seen, *defined))
+                })
+                .collect();
+
             for cell in sheet.all_cells() {
                 if let Some(formula) = cell.value.as_formula() {
-                    for name in &named_ranges {
-                        if formula.contains(name) {
-                            used_names.insert(*name);
+                    for (index, defined) in named_ranges.iter().enumerate() {
+                        let bare_use = visible[index] && formula.contains(defined.name.as_str());
+                        let qualified_use = qualified[index]
+                            .as_ref()
+                            .is_some_and(|name| formula.contains(name.as_str()));
+                        if bare_use || qualified_use {
+                            used_names.insert(index);
                         }
                     }
                 }
@@ -47,12 +66,16 @@ impl LinterRule for UnusedNamedRangesRule {
         }
 
         // Report unused named ranges
-        for name in named_ranges {
-            if !used_names.contains(name) {
+        for (index, defined) in named_ranges.iter().enumerate() {
+            if !used_names.contains(&index) {
+                let scope = match &defined.scope {
+                    Some(sheet) => ViolationScope::Sheet(sheet.clone()),
+                    None => ViolationScope::Book,
+                };
                 violations.push(Violation::new(
                     self.id(),
-                    ViolationScope::Book,
-                    format!("Named range '{}' is defined but never used", name),
+                    scope,
+                    format!("Named range '{}' is defined but never used", defined.name),
                     Severity::Warning,
                 ));
             }
@@ -65,20 +88,20 @@ impl LinterRule for UnusedNamedRangesRule {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::CellStore;
     use crate::reader::workbook::{Cell, CellValue, Sheet};
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     #[test]
     fn test_unused_named_ranges() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=UsedRange".to_string()),
+                ..Default::default()
             },
         );
 
@@ -89,9 +112,10 @@ mod tests {
             ..Default::default()
         };
 
-        let mut defined_names = HashMap::new();
-        defined_names.insert("UsedRange".to_string(), "Sheet1!A1:B2".to_string());
-        defined_names.insert("UnusedRange".to_string(), "Sheet1!C1:D2".to_string());
+        let defined_names = vec![
+            DefinedName::new("UsedRange", "Sheet1!A1:B2"),
+            DefinedName::new("UnusedRange", "Sheet1!C1:D2"),
+        ];
 
         let workbook = Workbook {
             path: PathBuf::from("test.xlsx"),
@@ -107,4 +131,66 @@ mod tests {
         assert_eq!(violations[0].rule_id, "PERF001");
         assert!(violations[0].message.contains("UnusedRange"));
     }
+
+    #[test]
+    fn test_unused_named_ranges_scopes() {
+        let formula_sheet = |name: &str, formula: &str| {
+            let mut cells = CellStore::new();
+            cells.insert(
+                (0, 0),
+                Cell {
+                    row: 0,
+                    col: 0,
+                    value: CellValue::formula(formula),
+                    ..Default::default()
+                },
+            );
+            Sheet {
+                name: name.to_string(),
+                cells,
+                ..Default::default()
+            }
+        };
+        let local = |name: &str, sheet: &str| DefinedName {
+            scope: Some(sheet.to_string()),
+            ..DefinedName::new(name, format!("'{}'!$A$1", sheet))
+        };
+
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![
+                // Sheet1's own Rate hides the workbook-wide one
+                formula_sheet("Sheet1", "=Rate*2"),
+                formula_sheet("Q2 Sales", "=Sheet1!Tax+1"),
+                formula_sheet("Other", "=Discount"),
+            ],
+            defined_names: vec![
+                DefinedName::new("Rate", "Rates!$A$1"),
+                local("Rate", "Sheet1"),
+                local("Tax", "Sheet1"),
+                // Only visible on its own sheet
+                local("Discount", "Q2 Sales"),
+            ],
+            ..Default::default()
+        };
+
+        let violations = UnusedNamedRangesRule.check(&workbook).unwrap();
+        let reported: Vec<_> = violations
+            .iter()
+            .map(|v| (v.scope.clone(), v.message.as_str()))
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                (
+                    ViolationScope::Book,
+                    "Named range 'Rate' is defined but never used"
+                ),
+                (
+                    ViolationScope::Sheet("Q2 Sales".to_string()),
+                    "Named range 'Discount' is defined but never used"
+                ),
+            ]
+        );
+    }
 }
@@ -33,7 +33,7 @@ impl LinterRule for UnusedSheetsRule {
         // Check formulas for sheet references
         for sheet in &workbook.sheets {
             for cell in sheet.all_cells() {
-                if let Some(formula) = cell.value.as_formula() {
+                if let Some(formula) = workbook.resolved_formula(&sheet.name, cell) {
                     for other_sheet in &all_sheets {
                         let simple_ref = format!("{}!", other_sheet);
                         let quoted_ref = format!("'{}'!", other_sheet);
@@ -66,7 +66,8 @@ impl LinterRule for UnusedSheetsRule {
         }
 
         // Check named ranges for sheet references
-        for (name, reference) in &workbook.defined_names {
+        for defined in &workbook.defined_names {
+            let (name, reference) = (&defined.name, &defined.reference);
             // Ignore built-in names (e.g. Print_Area) which shouldn't count as "usage"
             if name.contains("Print_Area")
                 || name.contains("Filter_Database")
@@ -84,24 +85,32 @@ impl LinterRule for UnusedSheetsRule {
             }
         }
 
+        // Check chart series for sheet references
+        referenced_sheets.extend(workbook.sheets_read_by_charts());
+
         // Report sheets that are not referenced by any other sheet
         // A sheet is considered "used" if:
         // - It's the only sheet, OR
         // - It's referenced by another sheet, OR
-        // - It contains formulas (it's doing work), OR
+        // - It contains formulas or charts (it's doing work), OR
         // - Formula parsing failed for it (safe default)
         for sheet in &workbook.sheets {
             let is_only_sheet = workbook.sheets.len() == 1;
             let is_referenced = referenced_sheets.contains(sheet.name.as_str());
             let has_formulas = sheet.cells.values().any(|c| c.value.is_formula());
             let has_content = sheet.cells.values().any(|c| !c.value.is_empty());
+            let has_drawings = !sheet.charts.is_empty() || !sheet.images.is_empty();
 
             let is_hidden = workbook.hidden_sheets.contains(&sheet.name);
 
             // A sheet is unused if it's not referenced and has content.
             // Hidden sheets with content but no incoming references are considered unused,
             // even if they contain formulas, as they are effectively dead code.
-            if !is_only_sheet && !is_referenced && has_content && (!has_formulas || is_hidden) {
+            if !is_only_sheet
+                && !is_referenced
+                && has_content
+                && (!(has_formulas || has_drawings) || is_hidden)
+            {
                 violations.push(Violation::new(
                     self.id(),
                     ViolationScope::Book,
@@ -110,6 +119,8 @@ impl LinterRule for UnusedSheetsRule {
                         sheet.name,
                         if has_formulas {
                             " (hidden sheet with formulas)"
+                        } else if has_drawings {
+                            " (hidden sheet with charts or images)"
                         } else {
                             " and contains no formulas"
                         }
@@ -126,20 +137,20 @@ impl LinterRule for UnusedSheetsRule {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::CellStore;
     use crate::reader::workbook::{Cell, CellValue, Sheet};
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     #[test]
     fn test_unused_sheets() {
-        let mut cells1 = HashMap::new();
+        let mut cells1 = CellStore::new();
         cells1.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=Sheet2!A1".to_string()),
+                ..Default::default()
             },
         );
 
@@ -147,24 +158,18 @@ mod tests {
             name: "Sheet1".to_string(),
             cells: cells1,
             used_range: Some((1, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
-        let mut cells2 = HashMap::new();
+        let mut cells2 = CellStore::new();
         cells2.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::Number(42.0),
+                ..Default::default()
             },
         );
 
@@ -172,24 +177,18 @@ mod tests {
             name: "Sheet2".to_string(),
             cells: cells2,
             used_range: Some((1, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
-        let mut cells3 = HashMap::new();
+        let mut cells3 = CellStore::new();
         cells3.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::Number(100.0),
+                ..Default::default()
             },
         );
 
@@ -197,14 +196,8 @@ mod tests {
             name: "Sheet3".to_string(),
             cells: cells3,
             used_range: Some((1, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -221,4 +214,57 @@ mod tests {
         assert_eq!(violations[0].rule_id, "PERF002");
         assert!(violations[0].message.contains("Sheet3"));
     }
+
+    #[test]
+    fn test_sheets_used_by_charts() {
+        use crate::reader::drawings::{Chart, ChartSeries, Image};
+
+        let filled = |name: &str| {
+            let mut cells = CellStore::new();
+            cells.insert(
+                (0, 0),
+                Cell {
+                    row: 0,
+                    col: 0,
+                    value: CellValue::Number(1.0),
+                    ..Default::default()
+                },
+            );
+            Sheet {
+                name: name.to_string(),
+                cells,
+                ..Default::default()
+            }
+        };
+
+        // Data is only read by the chart on Dashboard; Notes holds a picture
+        let dashboard = Sheet {
+            name: "Dashboard".to_string(),
+            charts: vec![Chart {
+                kind: "bar".to_string(),
+                series: vec![ChartSeries {
+                    values: Some("Data!$A$1:$A$10".to_string()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let mut notes = filled("Notes");
+        notes.images.push(Image {
+            name: "Logo".to_string(),
+            path: "xl/media/image1.png".to_string(),
+            ..Default::default()
+        });
+
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![dashboard, filled("Data"), notes, filled("Old")],
+            ..Default::default()
+        };
+
+        let violations = UnusedSheetsRule.check(&workbook).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].message.contains("'Old'"));
+    }
 }
@@ -1,6 +1,6 @@
 //! PERF003: Large used range detection
 
-use super::{LinterRule, RuleCategory};
+use super::{LinterRule, RuleAccess, RuleCategory};
 use crate::config::LinterConfig;
 use crate::reader::Workbook;
 use crate::violation::{Severity, Violation, ViolationScope};
@@ -32,6 +32,10 @@ impl LinterRule for LargeUsedRangeRule {
         RuleCategory::Performance
     }
 
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Sheet
+    }
+
     fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
 
@@ -55,7 +59,7 @@ impl LinterRule for LargeUsedRangeRule {
                     .values()
                     .filter(|c| match &c.value {
                         crate::reader::workbook::CellValue::Empty => false,
-                        crate::reader::workbook::CellValue::Text(s) => !s.trim().is_empty(),
+                        crate::reader::workbook::CellValue::Text(s, _) => !s.trim().is_empty(),
                         _ => true,
                     })
                     .fold(None, |acc: Option<(u32, u32)>, c| match acc {
@@ -94,30 +98,30 @@ impl LinterRule for LargeUsedRangeRule {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::CellStore;
     use crate::reader::workbook::{Cell, CellValue, Sheet};
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     #[test]
     fn test_large_used_range() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         // Data only in first few cells
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::Number(1.0),
+                ..Default::default()
             },
         );
         cells.insert(
             (1, 0),
             Cell {
-                num_fmt: None,
                 row: 1,
                 col: 0,
                 value: CellValue::Number(2.0),
+                ..Default::default()
             },
         );
 
@@ -1,6 +1,6 @@
 //! PERF004: Excessive conditional formatting detection
 
-use super::{LinterRule, RuleCategory};
+use super::{LinterRule, RuleAccess, RuleCategory};
 use crate::config::LinterConfig;
 use crate::reader::Workbook;
 use crate::violation::{Severity, Violation, ViolationScope};
@@ -32,6 +32,10 @@ impl LinterRule for ExcessiveConditionalFormattingRule {
         RuleCategory::Performance
     }
 
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
     fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
 
@@ -41,11 +45,15 @@ impl LinterRule for ExcessiveConditionalFormattingRule {
                 .get_param_int("max_conditional_formatting", Some(&sheet.name))
                 .unwrap_or(5) as u32;
 
-            let cf_count = sheet.conditional_formatting_count;
+            let cf_count = sheet.conditional_formats.len();
 
             if cf_count > threshold as usize {
-                let ranges_str = if !sheet.conditional_formatting_ranges.is_empty() {
-                    let mut ranges = sheet.conditional_formatting_ranges.clone();
+                let ranges_str = if !sheet.conditional_formats.is_empty() {
+                    let mut ranges: Vec<String> = sheet
+                        .conditional_formats
+                        .iter()
+                        .map(|cf| cf.sqref())
+                        .collect();
                     ranges.sort();
                     ranges.dedup();
                     format!(" Ranges: {}", ranges.join(", "))
@@ -72,6 +80,7 @@ impl LinterRule for ExcessiveConditionalFormattingRule {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::ConditionalFormat;
     use crate::reader::workbook::Sheet;
     use std::path::PathBuf;
 
@@ -99,12 +108,15 @@ mod tests {
             path: PathBuf::from("test.xlsx"),
             sheets: vec![Sheet {
                 name: "Sheet1".to_string(),
-                conditional_formatting_count: 10,
-                conditional_formatting_ranges: vec![
-                    "A1:A10".to_string(),
-                    "B1:B10".to_string(),
-                    "A1:A10".to_string(), // Duplicate to test dedup
-                ],
+                conditional_formats: [(0, 0, 9, 0), (0, 1, 9, 1), (0, 0, 9, 0)]
+                    .into_iter()
+                    .cycle()
+                    .take(10)
+                    .map(|range| ConditionalFormat {
+                        ranges: vec![range],
+                        ..Default::default()
+                    })
+                    .collect(),
                 ..Default::default()
             }],
             ..Default::default()
@@ -67,7 +67,8 @@ impl LinterRule for EmptySheetsRule {
         }
 
         // Check named ranges for sheet references
-        for (name, reference) in &workbook.defined_names {
+        for defined in &workbook.defined_names {
+            let (name, reference) = (&defined.name, &defined.reference);
             // Ignore built-in names (e.g. Print_Area) which shouldn't count as "usage"
             if name.contains("Print_Area")
                 || name.contains("Filter_Database")
@@ -85,6 +86,9 @@ impl LinterRule for EmptySheetsRule {
             }
         }
 
+        // Check chart series for sheet references
+        referenced_sheets.extend(workbook.sheets_read_by_charts());
+
         for sheet in &workbook.sheets {
             let is_only_sheet = workbook.sheets.len() == 1;
             let is_referenced = referenced_sheets.contains(sheet.name.as_str());
@@ -97,7 +101,8 @@ impl LinterRule for EmptySheetsRule {
             // (Wait, empty string cells or nulls?)
             // Usually cells.is_empty() implies no content.
             // But let's check properly:
-            let has_content = !sheet.cells.is_empty();
+            let has_content =
+                !sheet.cells.is_empty() || !sheet.charts.is_empty() || !sheet.images.is_empty();
 
             let formula_error = &sheet.formula_parsing_error;
 
@@ -124,19 +129,19 @@ impl LinterRule for EmptySheetsRule {
 mod tests {
     use super::*;
     use crate::reader::workbook::{Cell, CellValue, Sheet};
-    use std::collections::HashMap;
+    use crate::reader::{CellStore, DefinedName};
     use std::path::PathBuf;
 
     #[test]
     fn test_empty_unused_sheets() {
-        let mut cells1 = HashMap::new();
+        let mut cells1 = CellStore::new();
         cells1.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
-                value: CellValue::Text("Data".to_string()),
+                value: CellValue::Text("Data".to_string(), None),
+                ..Default::default()
             },
         );
 
@@ -144,46 +149,33 @@ mod tests {
             name: "Main".to_string(),
             cells: cells1,
             used_range: Some((1, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         // Filled but unused sheet (Should be PERF002, NOT PERF005)
-        let mut cells2 = HashMap::new();
+        let mut cells2 = CellStore::new();
         cells2.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::Number(42.0),
+                ..Default::default()
             },
         );
         let sheet2 = Sheet {
             name: "UnusedData".to_string(),
             cells: cells2,
             used_range: Some((1, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         // Empty unused sheet (Should be PERF005)
         let sheet3 = Sheet {
             name: "Empty".to_string(),
-            cells: HashMap::new(),
-            used_range: None,
+            cells: CellStore::new(),
             ..Default::default()
         };
 
@@ -204,14 +196,14 @@ mod tests {
     #[test]
     fn test_empty_unused_sheets_hidden_with_print_area() {
         // Test that a hidden, empty sheet referenced ONLY by Print_Area is detected as unused
-        let mut cells1 = HashMap::new();
+        let mut cells1 = CellStore::new();
         cells1.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::Number(1.0),
+                ..Default::default()
             },
         );
 
@@ -219,40 +211,22 @@ mod tests {
             name: "Main".to_string(),
             cells: cells1,
             used_range: Some((1, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let sheet2 = Sheet {
             name: "HiddenEmpty".to_string(),
-            cells: HashMap::new(), // Empty
-            used_range: None,
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
+            cells: CellStore::new(), // Empty
             visible: true,
+            ..Default::default()
         };
 
-        let mut defined_names = HashMap::new();
         // This simulates a Print_Area on the hidden sheet. logic should ignore it.
-        defined_names.insert(
-            "Print_Area".to_string(),
-            "HiddenEmpty!$A$1:$B$2".to_string(),
-        );
-        defined_names.insert(
-            "_xlnm.Print_Area".to_string(),
-            "HiddenEmpty!$A$1:$B$2".to_string(),
-        );
+        let defined_names = vec![
+            DefinedName::new("Print_Area", "HiddenEmpty!$A$1:$B$2"),
+            DefinedName::new("_xlnm.Print_Area", "HiddenEmpty!$A$1:$B$2"),
+        ];
 
         let workbook = Workbook {
             path: PathBuf::from("test.xlsx"),
@@ -261,6 +235,7 @@ mod tests {
             hidden_sheets: vec!["HiddenEmpty".to_string()],
             has_macros: false,
             external_workbooks: Vec::new(),
+            ..Default::default()
         };
 
         let rule = EmptySheetsRule;
@@ -270,4 +245,65 @@ mod tests {
         assert_eq!(violations[0].rule_id, "PERF005");
         assert!(violations[0].message.contains("HiddenEmpty"));
     }
+
+    #[test]
+    fn test_chart_sheet_is_not_empty() {
+        use crate::reader::drawings::Chart;
+
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![
+                Sheet {
+                    name: "Data".to_string(),
+                    ..Default::default()
+                },
+                Sheet {
+                    name: "Chart1".to_string(),
+                    charts: vec![Chart::default()],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let violations = EmptySheetsRule.check(&workbook).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].message.contains("'Data'"));
+    }
+
+    #[test]
+    fn test_chart_reads_only_named_sheet() {
+        use crate::reader::drawings::{Chart, ChartSeries};
+
+        // The chart reads MySheet1, which doesn't make Sheet1 used
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![
+                Sheet {
+                    name: "Sheet1".to_string(),
+                    ..Default::default()
+                },
+                Sheet {
+                    name: "MySheet1".to_string(),
+                    ..Default::default()
+                },
+                Sheet {
+                    name: "Chart1".to_string(),
+                    charts: vec![Chart {
+                        series: vec![ChartSeries {
+                            values: Some("MySheet1!$A$1:$A$5".to_string()),
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let violations = EmptySheetsRule.check(&workbook).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].message.contains("'Sheet1'"));
+    }
 }
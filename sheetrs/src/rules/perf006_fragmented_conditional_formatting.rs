@@ -0,0 +1,178 @@
+//! PERF006: Overlapping or fragmented conditional formatting
+//!
+//! Copying and inserting formatted cells makes Excel split a rule into many
+//! copies, each applied to a few cells, or apply it twice to the same cells.
+//! Every copy is evaluated separately, which slows down recalculation and
+//! scrolling.
+
+use super::err005_broken_validation_references::format_ranges;
+use super::{LinterRule, RuleAccess, RuleCategory};
+use crate::config::LinterConfig;
+use crate::reader::Workbook;
+use crate::violation::{Severity, Violation, ViolationScope};
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// Ranges listed in a message before the rest are summarized
+const MAX_LISTED_RANGES: usize = 5;
+
+/// (start_row, start_col, end_row, end_col)
+type Range = (u32, u32, u32, u32);
+
+#[derive(Default)]
+pub struct FragmentedConditionalFormattingRule {
+    config: LinterConfig,
+}
+
+impl FragmentedConditionalFormattingRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            config: config.clone(),
+        }
+    }
+}
+
+impl LinterRule for FragmentedConditionalFormattingRule {
+    fn id(&self) -> &str {
+        "PERF006"
+    }
+
+    fn name(&self) -> &str {
+        "Overlapping or fragmented conditional formatting"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Performance
+    }
+
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        for sheet in &workbook.sheets {
+            let threshold = self
+                .config
+                .get_param_int("max_conditional_formatting_fragments", Some(&sheet.name))
+                .unwrap_or(4) as usize;
+
+            // Ranges of the rules sharing a definition, in order of first appearance
+            let mut groups: Vec<(&str, Vec<Range>)> = Vec::new();
+            let mut group_index = HashMap::new();
+            for rule in &sheet.conditional_formats {
+                let key = (rule.kind, rule.operator, rule.stop_if_true, &rule.formulas);
+                let index = *group_index.entry(key).or_insert_with(|| {
+                    groups.push((rule.kind.as_str(), Vec::new()));
+                    groups.len() - 1
+                });
+                groups[index].1.extend_from_slice(&rule.ranges);
+            }
+
+            for (kind, ranges) in groups {
+                let message = if let Some((first, second)) = find_overlap(&ranges) {
+                    format!(
+                        "Conditional format ({}) is applied more than once to the same cells ({} and {})",
+                        kind,
+                        format_ranges(&[first]),
+                        format_ranges(&[second])
+                    )
+                } else if ranges.len() > threshold {
+                    let listed = format_ranges(&ranges[..ranges.len().min(MAX_LISTED_RANGES)]);
+                    let more = ranges.len().saturating_sub(MAX_LISTED_RANGES);
+                    format!(
+                        "Conditional format ({}) is split into {} ranges (threshold: {}): {}{}",
+                        kind,
+                        ranges.len(),
+                        threshold,
+                        listed,
+                        if more > 0 {
+                            format!(" and {} more", more)
+                        } else {
+                            String::new()
+                        }
+                    )
+                } else {
+                    continue;
+                };
+
+                violations.push(Violation::new(
+                    self.id(),
+                    ViolationScope::Sheet(sheet.name.clone()),
+                    message,
+                    Severity::Warning,
+                ));
+            }
+        }
+
+        Ok(violations)
+    }
+}
+
+/// First pair of ranges that share a cell
+fn find_overlap(ranges: &[Range]) -> Option<(Range, Range)> {
+    ranges.iter().enumerate().find_map(|(i, &a)| {
+        ranges[i + 1..]
+            .iter()
+            .find(|b| a.0 <= b.2 && b.0 <= a.2 && a.1 <= b.3 && b.1 <= a.3)
+            .map(|&b| (a, b))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::ConditionalFormat;
+    use crate::reader::conditional_format::ConditionalFormatKind;
+    use crate::reader::validation::ValidationOperator;
+    use crate::reader::workbook::Sheet;
+    use std::path::PathBuf;
+
+    fn greater_than(value: &str, ranges: Vec<(u32, u32, u32, u32)>) -> ConditionalFormat {
+        ConditionalFormat {
+            ranges,
+            kind: ConditionalFormatKind::CellIs,
+            operator: Some(ValidationOperator::GreaterThan),
+            formulas: vec![value.to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_fragmented_conditional_formatting() {
+        let sheet = Sheet {
+            name: "Sheet1".to_string(),
+            conditional_formats: vec![
+                // Pasted cell by cell down column A
+                greater_than("5", vec![(0, 0, 0, 0), (1, 0, 1, 0)]),
+                greater_than("5", vec![(2, 0, 2, 0)]),
+                greater_than("5", vec![(3, 0, 3, 0), (4, 0, 4, 0), (5, 0, 5, 0)]),
+                greater_than("5", vec![(6, 0, 6, 0)]),
+                // Applied twice to C5:C10
+                greater_than("10", vec![(0, 2, 9, 2)]),
+                greater_than("10", vec![(4, 2, 14, 2)]),
+                // Different rules may share cells
+                greater_than("20", vec![(0, 2, 9, 2)]),
+            ],
+            ..Default::default()
+        };
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![sheet],
+            ..Default::default()
+        };
+
+        let violations = FragmentedConditionalFormattingRule::default()
+            .check(&workbook)
+            .unwrap();
+        let messages: Vec<_> = violations.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Conditional format (cellIs) is split into 7 ranges (threshold: 4): A1, A2, A3, A4, A5 and 2 more",
+                "Conditional format (cellIs) is applied more than once to the same cells (C1:C10 and C5:C15)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! PERF007: Large pivot caches
+//!
+//! XLSX pivot tables save a copy of every source row in their cache, which
+//! can make up most of the file. Caches that are rebuilt on open, or whose
+//! source lives in the workbook anyway, often don't need to be saved.
+
+use super::{LinterRule, RuleAccess, RuleCategory};
+use crate::config::LinterConfig;
+use crate::reader::{PivotTable, Workbook};
+use crate::violation::{Severity, Violation, ViolationScope};
+use anyhow::Result;
+
+#[derive(Default)]
+pub struct LargePivotCachesRule {
+    config: LinterConfig,
+}
+
+impl LargePivotCachesRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            config: config.clone(),
+        }
+    }
+}
+
+impl LinterRule for LargePivotCachesRule {
+    fn id(&self) -> &str {
+        "PERF007"
+    }
+
+    fn name(&self) -> &str {
+        "Large pivot caches"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Performance
+    }
+
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        // Pivot tables sharing a cache, in order of first appearance
+        let mut caches: Vec<Vec<&PivotTable>> = Vec::new();
+        for pivot in &workbook.pivot_tables {
+            let Some(cache) = &pivot.cache else {
+                continue;
+            };
+            match caches
+                .iter_mut()
+                .find(|group| group[0].cache.as_ref().is_some_and(|c| c.id == cache.id))
+            {
+                Some(group) => group.push(pivot),
+                None => caches.push(vec![pivot]),
+            }
+        }
+
+        for pivots in caches {
+            let first = pivots[0];
+            let Some(cache) = &first.cache else {
+                continue;
+            };
+            let threshold_kb = self
+                .config
+                .get_param_int("max_pivot_cache_kb", Some(&first.sheet))
+                .unwrap_or(1024) as u64;
+            let size_kb = cache.size / 1024;
+            if size_kb <= threshold_kb {
+                continue;
+            }
+
+            let names = pivots
+                .iter()
+                .map(|pivot| format!("'{}'", pivot.name))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let records = cache
+                .records
+                .map(|records| format!(", {} records", records))
+                .unwrap_or_default();
+            let scope = if first.sheet.is_empty() {
+                ViolationScope::Book
+            } else {
+                ViolationScope::Sheet(first.sheet.clone())
+            };
+            violations.push(Violation::new(
+                self.id(),
+                scope,
+                format!(
+                    "Pivot cache of {} takes {} KB in the file{} (threshold: {} KB){}",
+                    names,
+                    size_kb,
+                    records,
+                    threshold_kb,
+                    if cache.refresh_on_load {
+                        "; it is rebuilt on open, so it doesn't need to be saved"
+                    } else {
+                        ""
+                    }
+                ),
+                Severity::Warning,
+            ));
+        }
+
+        Ok(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::pivot::PivotCache;
+    use std::path::PathBuf;
+
+    fn pivot(name: &str, id: u32, size: u64, refresh_on_load: bool) -> PivotTable {
+        PivotTable {
+            name: name.to_string(),
+            sheet: "Report".to_string(),
+            cache: Some(PivotCache {
+                id,
+                records: Some(50_000),
+                size,
+                refresh_on_load,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_large_pivot_caches() {
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            pivot_tables: vec![
+                pivot("ByRegion", 1, 3 * 1024 * 1024, false),
+                pivot("Small", 2, 200 * 1024, false),
+                pivot("ByMonth", 1, 3 * 1024 * 1024, false),
+                pivot("Refreshed", 3, 2 * 1024 * 1024, true),
+                // ODS data pilots keep no cache
+                PivotTable {
+                    name: "DataPilot1".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let violations = LargePivotCachesRule::default().check(&workbook).unwrap();
+        let messages: Vec<_> = violations.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Pivot cache of 'ByRegion', 'ByMonth' takes 3072 KB in the file, 50000 records (threshold: 1024 KB)",
+                "Pivot cache of 'Refreshed' takes 2048 KB in the file, 50000 records (threshold: 1024 KB); it is rebuilt on open, so it doesn't need to be saved",
+            ]
+        );
+        assert_eq!(
+            violations[0].scope,
+            ViolationScope::Sheet("Report".to_string())
+        );
+    }
+}
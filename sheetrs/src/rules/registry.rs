@@ -64,6 +64,14 @@ fn create_all_rules(config: &LinterConfig) -> Vec<Box<dyn LinterRule>> {
         Box::new(sec003_hidden_columns_rows::HiddenColumnsRowsRule),
         Box::new(sec004_has_macros::HasMacrosRule),
         Box::new(sec005_web_urls::WebUrlsRule::new(config)),
+        Box::new(sec006_weak_protection::WeakProtectionRule),
+        Box::new(sec007_personal_metadata::PersonalMetadataRule),
+        Box::new(sec008_external_pivot_sources::ExternalPivotSourcesRule),
+        Box::new(sec009_embedded_objects::EmbeddedObjectsRule),
+        Box::new(sec010_data_connections::DataConnectionsRule),
+        Box::new(sec011_auto_run_macros::AutoRunMacrosRule),
+        Box::new(sec012_suspicious_macro_code::SuspiciousMacroCodeRule),
+        Box::new(sec013_personal_headers_footers::PersonalHeadersFootersRule),
         Box::new(ux001_inconsistent_number_format::NumericTextRule),
         Box::new(ux003_blank_rows_columns::BlankRowsColumnsRule::new(config)),
         Box::new(perf001_unused_named_ranges::UnusedNamedRangesRule),
@@ -79,6 +87,14 @@ fn create_all_rules(config: &LinterConfig) -> Vec<Box<dyn LinterRule>> {
                 config,
             ),
         ),
+        Box::new(
+            perf006_fragmented_conditional_formatting::FragmentedConditionalFormattingRule::new(
+                config,
+            ),
+        ),
+        Box::new(perf007_large_pivot_caches::LargePivotCachesRule::new(
+            config,
+        )),
         Box::new(form004_whole_column_row_refs::WholeColumnRowRefsRule::new()),
         Box::new(sm001_excessive_sheet_counts::ExcessiveSheetCountsRule::new(
             config,
@@ -97,10 +113,28 @@ fn create_all_rules(config: &LinterConfig) -> Vec<Box<dyn LinterRule>> {
         Box::new(form009_vlookup_hlookup_usage::VLookupHLookupUsageRule::new(
             config,
         )),
+        Box::new(form010_inconsistent_formulas::InconsistentFormulasRule),
         Box::new(ux002_inconsistent_date_format::InconsistentDateFormatRule::new(config)),
         Box::new(err003_circular_references::CircularReferenceRule::new(
             config,
         )),
+        Box::new(err004_data_validation_violations::DataValidationViolationsRule),
+        Box::new(err005_broken_validation_references::BrokenValidationReferencesRule),
+        Box::new(err006_broken_conditional_formats::BrokenConditionalFormatsRule),
+        Box::new(err007_broken_pivot_sources::BrokenPivotSourcesRule),
+        Box::new(err008_broken_chart_references::BrokenChartReferencesRule),
+        Box::new(err009_broken_external_links::BrokenExternalLinksRule::new(
+            config,
+        )),
+        Box::new(ux004_unvalidated_inputs::UnvalidatedInputsRule::new(config)),
+        Box::new(ux005_unfrozen_header_row::UnfrozenHeaderRowRule::new(
+            config,
+        )),
+        Box::new(ux006_narrow_columns::NarrowColumnsRule),
+        Box::new(ux007_deep_outline_groups::DeepOutlineGroupsRule::new(
+            config,
+        )),
+        Box::new(ux008_print_area_outside_data::PrintAreaOutsideDataRule),
     ]
 }
 
@@ -1,8 +1,8 @@
 //! SEC001: External workbook references
 
-use super::{LinterRule, RuleCategory};
+use super::{LinterRule, RuleAccess, RuleCategory};
 use crate::config::LinterConfig;
-use crate::reader::Workbook;
+use crate::reader::{Hyperlink, Workbook};
 use crate::violation::{Severity, Violation, ViolationScope};
 use anyhow::Result;
 
@@ -57,6 +57,14 @@ impl LinterRule for ExternalWorkbooksRule {
         RuleCategory::SecurityAndPrivacy
     }
 
+    fn access(&self) -> RuleAccess {
+        match self.scope {
+            // Book scope only reads the external link metadata
+            LinkScope::Book => RuleAccess::Metadata,
+            LinkScope::Sheet => RuleAccess::Sheet,
+        }
+    }
+
     fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
 
@@ -70,6 +78,21 @@ impl LinterRule for ExternalWorkbooksRule {
                     Severity::Warning,
                 ));
             }
+
+            let linked: std::collections::BTreeSet<&str> = workbook
+                .sheets
+                .iter()
+                .flat_map(|sheet| &sheet.hyperlinks)
+                .filter_map(linked_workbook)
+                .collect();
+            for path in linked {
+                violations.push(Violation::new(
+                    self.id(),
+                    ViolationScope::Book,
+                    format!("External workbook '{}' linked by hyperlink.", path),
+                    Severity::Warning,
+                ));
+            }
         }
 
         // SHEET scope: from formulas
@@ -108,6 +131,28 @@ impl LinterRule for ExternalWorkbooksRule {
                         ));
                     }
                 }
+
+                let mut linked: std::collections::BTreeMap<&str, Vec<(u32, u32)>> =
+                    std::collections::BTreeMap::new();
+                for link in &sheet.hyperlinks {
+                    if let Some(path) = linked_workbook(link) {
+                        linked.entry(path).or_default().push((link.row, link.col));
+                    }
+                }
+                for (path, cells) in linked {
+                    for range in find_contiguous_ranges(&cells) {
+                        violations.push(Violation::new(
+                            self.id(),
+                            ViolationScope::Sheet(sheet.name.clone()),
+                            format!(
+                                "External workbook link {} found in range: {}",
+                                path,
+                                format_single_range(&range)
+                            ),
+                            Severity::Warning,
+                        ));
+                    }
+                }
             }
         }
 
@@ -115,6 +160,24 @@ impl LinterRule for ExternalWorkbooksRule {
     }
 }
 
+/// Path of the spreadsheet file an external hyperlink opens, without any
+/// `#location` anchor
+pub(super) fn linked_workbook(link: &Hyperlink) -> Option<&str> {
+    const EXTENSIONS: &[&str] = &[
+        ".xlsx", ".xlsm", ".xlsb", ".xls", ".xltx", ".xltm", ".ods", ".ots", ".fods",
+    ];
+
+    if link.internal {
+        return None;
+    }
+    let path = link.target.split('#').next().unwrap_or_default();
+    let lower = path.to_ascii_lowercase();
+    EXTENSIONS
+        .iter()
+        .any(|ext| lower.ends_with(ext))
+        .then_some(path)
+}
+
 /// Extract [N] indices from formula
 fn extract_external_workbook_indices(formula: &str) -> Vec<usize> {
     use regex::Regex;
@@ -127,9 +190,10 @@ fn extract_external_workbook_indices(formula: &str) -> Vec<usize> {
     for cap in re.captures_iter(formula) {
         if let Some(num_str) = cap.get(1)
             && let Ok(num) = num_str.as_str().parse::<usize>()
-                && num > 0 {
-                    indices.push(num - 1); // Convert 1-based to 0-based
-                }
+            && num > 0
+        {
+            indices.push(num - 1); // Convert 1-based to 0-based
+        }
     }
     indices
 }
@@ -229,20 +293,20 @@ fn find_contiguous_ranges(cells: &[(u32, u32)]) -> Vec<Vec<(u32, u32)>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::CellStore;
     use crate::reader::workbook::{Cell, CellValue, Sheet};
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     #[test]
     fn test_external_workbook_in_formula() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::formula("=[1]Sheet1!A1".to_string()),
+                ..Default::default()
             },
         );
 
@@ -259,6 +323,7 @@ mod tests {
             external_workbooks: vec![crate::reader::ExternalWorkbook {
                 index: 0,
                 path: "Book1.xlsx".to_string(),
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -279,7 +344,7 @@ mod tests {
     fn test_external_workbook_in_metadata() {
         let sheet = Sheet {
             name: "Sheet1".to_string(),
-            cells: HashMap::new(),
+            cells: CellStore::new(),
             used_range: Some((0, 0)),
             ..Default::default()
         };
@@ -290,6 +355,7 @@ mod tests {
             external_workbooks: vec![crate::reader::ExternalWorkbook {
                 index: 0,
                 path: "external_workbook.xlsx".to_string(),
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -303,4 +369,49 @@ mod tests {
         assert!(violations[0].message.contains("external_workbook.xlsx"));
         assert!(violations[0].message.contains("metadata"));
     }
+
+    #[test]
+    fn test_external_workbook_hyperlinks() {
+        use crate::reader::Hyperlink;
+
+        let sheet = Sheet {
+            name: "Sheet1".to_string(),
+            hyperlinks: vec![
+                Hyperlink::new(0, 0, "file:///C:/data/Budget.xlsx#Sheet1!A1", None),
+                Hyperlink::new(1, 0, "file:///C:/data/Budget.xlsx", None),
+                Hyperlink::new(3, 1, "\\\\server\\share\\plan.ods", None),
+                Hyperlink::new(4, 1, "https://example.com", None),
+                Hyperlink::new(5, 1, "#Other.xlsx", None),
+            ],
+            ..Default::default()
+        };
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![sheet],
+            ..Default::default()
+        };
+
+        let book = ExternalWorkbooksRule::default().check(&workbook).unwrap();
+        let messages: Vec<_> = book.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "External workbook '\\\\server\\share\\plan.ods' linked by hyperlink.",
+                "External workbook 'file:///C:/data/Budget.xlsx' linked by hyperlink.",
+            ]
+        );
+
+        let rule = ExternalWorkbooksRule {
+            scope: LinkScope::Sheet,
+        };
+        let sheet_level = rule.check(&workbook).unwrap();
+        let messages: Vec<_> = sheet_level.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "External workbook link \\\\server\\share\\plan.ods found in range: B4",
+                "External workbook link file:///C:/data/Budget.xlsx found in range: A1:A2",
+            ]
+        );
+    }
 }
@@ -1,6 +1,6 @@
 //! SEC002: Hidden sheets detection
 
-use super::{LinterRule, RuleCategory};
+use super::{LinterRule, RuleAccess, RuleCategory};
 use crate::reader::Workbook;
 use crate::violation::{Severity, Violation, ViolationScope};
 use anyhow::Result;
@@ -20,6 +20,10 @@ impl LinterRule for HiddenSheetsRule {
         RuleCategory::SecurityAndPrivacy
     }
 
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
     fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
 
@@ -1,6 +1,9 @@
 //! SEC003: Hidden columns and rows detection
+//!
+//! Only rows hidden by hand are reported; rows an autofilter hides are in
+//! plain sight once the filter is cleared.
 
-use super::{LinterRule, RuleCategory};
+use super::{LinterRule, RuleAccess, RuleCategory};
 use crate::reader::Workbook;
 use crate::violation::{Severity, Violation, ViolationScope};
 use anyhow::Result;
@@ -20,6 +23,10 @@ impl LinterRule for HiddenColumnsRowsRule {
         RuleCategory::SecurityAndPrivacy
     }
 
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
     fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
 
@@ -131,8 +138,8 @@ fn column_index_to_letter(mut index: u32) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::CellStore;
     use crate::reader::workbook::Sheet;
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     #[test]
@@ -141,16 +148,10 @@ mod tests {
             path: PathBuf::from("test.xlsx"),
             sheets: vec![Sheet {
                 name: "Sheet1".to_string(),
-                cells: HashMap::new(),
-                used_range: None,
+                cells: CellStore::new(),
                 hidden_columns: vec![0, 1, 2, 5], // A, B, C, F
-                hidden_rows: Vec::new(),
-                merged_cells: Vec::new(),
-                sheet_path: None,
-                formula_parsing_error: None,
-                conditional_formatting_count: 0,
-                conditional_formatting_ranges: Vec::new(),
                 visible: true,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -170,16 +171,11 @@ mod tests {
             path: PathBuf::from("test.xlsx"),
             sheets: vec![Sheet {
                 name: "Sheet1".to_string(),
-                cells: HashMap::new(),
-                used_range: None,
-                hidden_columns: Vec::new(),
+                cells: CellStore::new(),
                 hidden_rows: vec![0, 1, 2, 10, 11], // 1, 2, 3, 11, 12
-                merged_cells: Vec::new(),
-                sheet_path: None,
-                formula_parsing_error: None,
-                conditional_formatting_count: 0,
-                conditional_formatting_ranges: Vec::new(),
+                filtered_rows: vec![20, 21],        // hidden by the autofilter
                 visible: true,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -187,7 +183,7 @@ mod tests {
         let rule = HiddenColumnsRowsRule;
         let violations = rule.check(&workbook).unwrap();
 
-        assert_eq!(violations.len(), 2); // Two ranges: 1:3 and 11:12
+        assert_eq!(violations.len(), 2); // Two ranges: 1:3 and 11:12, not 21:22
         assert_eq!(violations[0].rule_id, "SEC003");
         assert!(violations[0].message.contains("1:3"));
         assert!(violations[1].message.contains("11:12"));
@@ -1,7 +1,11 @@
 //! SEC004: Macros and scripts detection
+//!
+//! Besides the macro project itself, lists the document, sheet and control
+//! events that run a script.
 
-use super::{LinterRule, RuleCategory};
+use super::{LinterRule, RuleAccess, RuleCategory};
 use crate::reader::Workbook;
+use crate::reader::active_content::ActiveContentKind;
 use crate::violation::{Severity, Violation, ViolationScope};
 use anyhow::Result;
 
@@ -20,6 +24,10 @@ impl LinterRule for HasMacrosRule {
         RuleCategory::SecurityAndPrivacy
     }
 
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
     fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
 
@@ -32,6 +40,31 @@ impl LinterRule for HasMacrosRule {
             ));
         }
 
+        for item in &workbook.active_content {
+            if item.kind != ActiveContentKind::EventScript {
+                continue;
+            }
+            let scope = match &item.sheet {
+                Some(sheet) => ViolationScope::Sheet(sheet.clone()),
+                None => ViolationScope::Book,
+            };
+            violations.push(Violation::new(
+                self.id(),
+                scope,
+                format!(
+                    "Event '{}' runs {}{}",
+                    item.name,
+                    item.target.as_deref().unwrap_or("a script"),
+                    if item.runs_on_open {
+                        " when the document is opened"
+                    } else {
+                        ""
+                    }
+                ),
+                Severity::Warning,
+            ));
+        }
+
         Ok(violations)
     }
 }
@@ -70,4 +103,32 @@ mod tests {
 
         assert_eq!(violations.len(), 0);
     }
+
+    #[test]
+    fn test_event_scripts() {
+        use crate::reader::ActiveContent;
+
+        let workbook = Workbook {
+            path: PathBuf::from("test.ods"),
+            has_macros: true,
+            active_content: vec![ActiveContent {
+                kind: ActiveContentKind::EventScript,
+                name: "dom:load".to_string(),
+                target: Some(
+                    "vnd.sun.star.script:Standard.Module1.Main?language=Basic&location=document"
+                        .to_string(),
+                ),
+                runs_on_open: true,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let violations = HasMacrosRule.check(&workbook).unwrap();
+        assert_eq!(violations.len(), 2);
+        assert_eq!(
+            violations[1].message,
+            "Event 'dom:load' runs vnd.sun.star.script:Standard.Module1.Main?language=Basic&location=document when the document is opened"
+        );
+    }
 }
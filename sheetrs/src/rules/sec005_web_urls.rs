@@ -1,8 +1,9 @@
 //! SEC005: Web URL links in cell values
 
+use super::sec001_external_workbooks::linked_workbook;
 use super::{LinterRule, RuleCategory};
 use crate::config::LinterConfig;
-use crate::reader::Workbook;
+use crate::reader::{Hyperlink, Workbook};
 use crate::violation::{Severity, Violation, ViolationScope};
 use anyhow::Result;
 
@@ -100,7 +101,7 @@ impl LinterRule for WebUrlsRule {
             let mut url_cells: Vec<(u32, u32, String)> = Vec::new();
 
             for cell in sheet.all_cells() {
-                if let crate::reader::workbook::CellValue::Text(text) = &cell.value {
+                if let crate::reader::workbook::CellValue::Text(text, _) = &cell.value {
                     let urls = extract_urls(text);
                     for url in urls {
                         seen_urls.insert(url.clone());
@@ -109,6 +110,16 @@ impl LinterRule for WebUrlsRule {
                 }
             }
 
+            // Hyperlink targets, which the displayed text may hide
+            // Links to spreadsheet files are SEC001's
+            for link in sheet.hyperlinks.iter().filter(|link| is_link_target(link)) {
+                let entry = (link.row, link.col, link.target.clone());
+                if !url_cells.contains(&entry) {
+                    seen_urls.insert(link.target.clone());
+                    url_cells.push(entry);
+                }
+            }
+
             // SHEET scope: create range-based violations per sheet
             if matches!(self.scope, LinkScope::Sheet) && !url_cells.is_empty() {
                 let grouped = group_cells_by_value(url_cells);
@@ -188,6 +199,20 @@ fn extract_urls(text: &str) -> Vec<String> {
     re.find_iter(text).map(|m| m.as_str().to_string()).collect()
 }
 
+/// Whether a hyperlink leaves the workbook for a web or FTP address, or for a
+/// `file://` or UNC path other than a spreadsheet
+fn is_link_target(link: &Hyperlink) -> bool {
+    if link.internal {
+        return false;
+    }
+    let target = link.target.trim_start().to_ascii_lowercase();
+    let is_web = ["http://", "https://", "ftp://"]
+        .iter()
+        .any(|scheme| target.starts_with(scheme));
+    let is_file = target.starts_with("file://") || target.starts_with("\\\\");
+    is_web || (is_file && linked_workbook(link).is_none())
+}
+
 /// Group cells by their URL value
 fn group_cells_by_value(cells: Vec<(u32, u32, String)>) -> Vec<(String, Vec<(u32, u32)>)> {
     use std::collections::HashMap;
@@ -297,20 +322,20 @@ fn check_url_status(_url: &str, _timeout_secs: u64) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::CellStore;
     use crate::reader::workbook::{Cell, CellValue, Sheet};
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     #[test]
     fn test_url_in_text_cell() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
-                value: CellValue::Text("https://example.com".to_string()),
+                value: CellValue::Text("https://example.com".to_string(), None),
+                ..Default::default()
             },
         );
 
@@ -343,14 +368,14 @@ mod tests {
 
     #[test]
     fn test_url_book_scope() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
-                value: CellValue::Text("Visit https://example.com for more".to_string()),
+                value: CellValue::Text("Visit https://example.com for more".to_string(), None),
+                ..Default::default()
             },
         );
 
@@ -378,16 +403,17 @@ mod tests {
 
     #[test]
     fn test_multiple_urls_in_cell() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
                 value: CellValue::Text(
                     "Check https://example.com and https://test.org".to_string(),
+                    None,
                 ),
+                ..Default::default()
             },
         );
 
@@ -414,4 +440,72 @@ mod tests {
         // Should detect both URLs
         assert_eq!(violations.len(), 2);
     }
+
+    #[test]
+    fn test_hyperlink_targets() {
+        use crate::reader::Hyperlink;
+
+        let mut cells = CellStore::new();
+        cells.insert(
+            (0, 0),
+            Cell {
+                value: CellValue::Text("https://example.com".to_string(), None),
+                ..Default::default()
+            },
+        );
+        cells.insert(
+            (1, 0),
+            Cell {
+                value: CellValue::Text("Quarterly report".to_string(), None),
+                ..Default::default()
+            },
+        );
+
+        let sheet = Sheet {
+            name: "Sheet1".to_string(),
+            cells,
+            hyperlinks: vec![
+                Hyperlink::new(0, 0, "https://example.com", None),
+                Hyperlink::new(1, 0, "https://example.com/q3", None),
+                Hyperlink::new(2, 0, "#Sheet2!A1", None),
+                Hyperlink::new(3, 0, "\\\\server\\share\\report.pdf", None),
+                Hyperlink::new(4, 0, "mailto:finance@example.com", None),
+                Hyperlink::new(5, 0, "Rates.xlsx", None),
+                Hyperlink::new(6, 0, "file:///C:/docs/notes.docx", None),
+                Hyperlink::new(7, 0, "\\\\server\\share\\Rates.xlsx", None),
+            ],
+            ..Default::default()
+        };
+
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![sheet],
+            ..Default::default()
+        };
+
+        let rule = WebUrlsRule {
+            scope: LinkScope::Sheet,
+            status: LinkStatus::All,
+            timeout_secs: 5,
+        };
+        let mut messages: Vec<_> = rule
+            .check(&workbook)
+            .unwrap()
+            .into_iter()
+            .map(|v| v.message)
+            .collect();
+        messages.sort();
+
+        // The text URL and its hyperlink are reported once; internal and mail
+        // links are not URLs, and links to spreadsheets are left to SEC001
+        assert_eq!(
+            messages,
+            vec![
+                "External URL '\\\\server\\share\\report.pdf' found in range: A4",
+                "External URL 'file:///C:/docs/notes.docx' found in range: A7",
+                "External URL 'https://example.com' found in range: A1",
+                "External URL 'https://example.com/q3' found in range: A2",
+            ]
+        );
+    }
 }
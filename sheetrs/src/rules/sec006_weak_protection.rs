@@ -0,0 +1,120 @@
+//! SEC006: Sheet or workbook protection with an easily reversed password hash
+
+use super::{LinterRule, RuleAccess, RuleCategory};
+use crate::reader::Workbook;
+use crate::violation::{Severity, Violation, ViolationScope};
+use anyhow::Result;
+
+pub struct WeakProtectionRule;
+
+impl LinterRule for WeakProtectionRule {
+    fn id(&self) -> &str {
+        "SEC006"
+    }
+
+    fn name(&self) -> &str {
+        "Weak protection password hash"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::SecurityAndPrivacy
+    }
+
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        if let Some(weakness) = workbook
+            .protection
+            .as_ref()
+            .and_then(|protection| protection.password.as_ref())
+            .and_then(|password| password.weakness())
+        {
+            violations.push(Violation::new(
+                self.id(),
+                ViolationScope::Book,
+                format!("Workbook protection password uses {}", weakness),
+                Severity::Warning,
+            ));
+        }
+
+        for sheet in &workbook.sheets {
+            if let Some(weakness) = sheet
+                .protection
+                .as_ref()
+                .and_then(|protection| protection.password.as_ref())
+                .and_then(|password| password.weakness())
+            {
+                violations.push(Violation::new(
+                    self.id(),
+                    ViolationScope::Sheet(sheet.name.clone()),
+                    format!("Sheet protection password uses {}", weakness),
+                    Severity::Warning,
+                ));
+            }
+        }
+
+        Ok(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::protection::PasswordHash;
+    use crate::reader::workbook::Sheet;
+    use crate::reader::{SheetProtection, WorkbookProtection};
+    use std::path::PathBuf;
+
+    fn protected_sheet(name: &str, password: Option<PasswordHash>) -> Sheet {
+        Sheet {
+            name: name.to_string(),
+            protection: Some(SheetProtection { password }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_weak_protection() {
+        let strong = PasswordHash {
+            algorithm: "SHA-512".to_string(),
+            value: "c2VjcmV0".to_string(),
+            salted: true,
+            spin_count: 100000,
+        };
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![
+                protected_sheet("Legacy", Some(PasswordHash::legacy("CC1A"))),
+                protected_sheet("Strong", Some(strong)),
+                protected_sheet("NoPassword", None),
+                Sheet::new("Unprotected".to_string()),
+            ],
+            protection: Some(WorkbookProtection {
+                structure: true,
+                windows: false,
+                password: Some(PasswordHash::from_ods("c2VjcmV0", None)),
+            }),
+            ..Default::default()
+        };
+
+        let violations = WeakProtectionRule.check(&workbook).unwrap();
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].scope, ViolationScope::Book);
+        assert_eq!(
+            violations[0].message,
+            "Workbook protection password uses the broken SHA-1 algorithm"
+        );
+        assert_eq!(
+            violations[1].scope,
+            ViolationScope::Sheet("Legacy".to_string())
+        );
+        assert_eq!(
+            violations[1].message,
+            "Sheet protection password uses the legacy 16-bit hash, which is trivially reversible"
+        );
+    }
+}
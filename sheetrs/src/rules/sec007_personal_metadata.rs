@@ -0,0 +1,82 @@
+//! SEC007: Personal information in document properties
+
+use super::{LinterRule, RuleAccess, RuleCategory};
+use crate::reader::Workbook;
+use crate::violation::{Severity, Violation, ViolationScope};
+use anyhow::Result;
+
+/// Reports author, editor, manager, company and template paths that would be
+/// shared with the file
+pub struct PersonalMetadataRule;
+
+impl LinterRule for PersonalMetadataRule {
+    fn id(&self) -> &str {
+        "SEC007"
+    }
+
+    fn name(&self) -> &str {
+        "Personal metadata"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::SecurityAndPrivacy
+    }
+
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        Ok(workbook
+            .properties
+            .personal()
+            .into_iter()
+            .map(|(label, value)| {
+                Violation::new(
+                    self.id(),
+                    ViolationScope::Book,
+                    format!("Document property {}: '{}'", label, value),
+                    Severity::Warning,
+                )
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::DocumentProperties;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_personal_metadata() {
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            properties: DocumentProperties {
+                title: Some("Q3 Budget".to_string()),
+                author: Some("Jane Doe".to_string()),
+                company: Some("Acme".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let violations = PersonalMetadataRule.check(&workbook).unwrap();
+        let messages: Vec<_> = violations.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Document property Author: 'Jane Doe'",
+                "Document property Company: 'Acme'",
+            ]
+        );
+        assert!(violations.iter().all(|v| v.scope == ViolationScope::Book));
+
+        let anonymous = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            ..Default::default()
+        };
+        assert!(PersonalMetadataRule.check(&anonymous).unwrap().is_empty());
+    }
+}
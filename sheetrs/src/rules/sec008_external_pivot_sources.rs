@@ -0,0 +1,111 @@
+//! SEC008: Pivot tables sourced from external workbooks
+//!
+//! The source of a pivot table lives in its cache definition rather than in
+//! the workbook's external links, so SEC001 doesn't see it. Refreshing the
+//! pivot reads the other file, and the cache keeps a copy of its data.
+
+use super::{LinterRule, RuleAccess, RuleCategory};
+use crate::reader::Workbook;
+use crate::reader::pivot::PivotSource;
+use crate::violation::{CellReference, Severity, Violation, ViolationScope};
+use anyhow::Result;
+
+pub struct ExternalPivotSourcesRule;
+
+impl LinterRule for ExternalPivotSourcesRule {
+    fn id(&self) -> &str {
+        "SEC008"
+    }
+
+    fn name(&self) -> &str {
+        "Pivot tables sourced from external workbooks"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::SecurityAndPrivacy
+    }
+
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        for pivot in &workbook.pivot_tables {
+            let PivotSource::External {
+                workbook: path,
+                reference,
+            } = &pivot.source
+            else {
+                continue;
+            };
+            let scope = if pivot.sheet.is_empty() {
+                ViolationScope::Book
+            } else {
+                ViolationScope::Cell(
+                    pivot.sheet.clone(),
+                    CellReference::new(pivot.location.0, pivot.location.1),
+                )
+            };
+            violations.push(Violation::new(
+                self.id(),
+                scope,
+                format!(
+                    "Pivot table '{}' reads {} in external workbook '{}'",
+                    pivot.name, reference, path
+                ),
+                Severity::Warning,
+            ));
+        }
+
+        Ok(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::PivotTable;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_external_pivot_sources() {
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            pivot_tables: vec![
+                PivotTable {
+                    name: "Budget".to_string(),
+                    sheet: "Report".to_string(),
+                    location: (2, 5, 9, 6),
+                    source: PivotSource::External {
+                        workbook: "file:///C:/finance/budget.xlsx".to_string(),
+                        reference: "'Plan 2024'!A1:B50".to_string(),
+                    },
+                    cache: None,
+                },
+                PivotTable {
+                    name: "Local".to_string(),
+                    sheet: "Report".to_string(),
+                    source: PivotSource::Range {
+                        sheet: "Data".to_string(),
+                        reference: "A1:C20".to_string(),
+                    },
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let violations = ExternalPivotSourcesRule.check(&workbook).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].message,
+            "Pivot table 'Budget' reads 'Plan 2024'!A1:B50 in external workbook 'file:///C:/finance/budget.xlsx'"
+        );
+        assert_eq!(
+            violations[0].scope,
+            ViolationScope::Cell("Report".to_string(), CellReference::new(2, 5))
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! SEC009: Embedded OLE objects and ActiveX controls
+//!
+//! Embedded objects can carry documents, executables or packages that run
+//! when double-clicked, linked objects read other files, and ActiveX controls
+//! run code from installed components. None of them need a VBA project, so
+//! SEC004 doesn't see them.
+
+use super::{LinterRule, RuleAccess, RuleCategory};
+use crate::reader::Workbook;
+use crate::reader::active_content::ActiveContentKind;
+use crate::violation::{Severity, Violation, ViolationScope};
+use anyhow::Result;
+
+pub struct EmbeddedObjectsRule;
+
+impl LinterRule for EmbeddedObjectsRule {
+    fn id(&self) -> &str {
+        "SEC009"
+    }
+
+    fn name(&self) -> &str {
+        "Embedded objects and ActiveX controls"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::SecurityAndPrivacy
+    }
+
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        for item in &workbook.active_content {
+            if !matches!(
+                item.kind,
+                ActiveContentKind::OleObject | ActiveContentKind::ActiveXControl
+            ) {
+                continue;
+            }
+            let name = if item.name.is_empty() {
+                String::new()
+            } else {
+                format!(" '{}'", item.name)
+            };
+            let target = match (item.kind, &item.target) {
+                (ActiveContentKind::ActiveXControl, Some(class_id)) => {
+                    format!(" (class {})", class_id)
+                }
+                (_, Some(target)) => format!(" ({})", target),
+                (_, None) => String::new(),
+            };
+            let update = if item.runs_on_open {
+                "; the link is updated on open"
+            } else {
+                ""
+            };
+            let scope = match &item.sheet {
+                Some(sheet) => ViolationScope::Sheet(sheet.clone()),
+                None => ViolationScope::Book,
+            };
+            violations.push(Violation::new(
+                self.id(),
+                scope,
+                format!("Contains {}{}{}{}", item.kind.label(), name, target, update),
+                Severity::Warning,
+            ));
+        }
+
+        Ok(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::ActiveContent;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_embedded_objects() {
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            active_content: vec![
+                ActiveContent {
+                    kind: ActiveContentKind::OleObject,
+                    sheet: Some("Sheet1".to_string()),
+                    name: "Package".to_string(),
+                    target: Some("xl/embeddings/oleObject1.bin".to_string()),
+                    runs_on_open: false,
+                },
+                ActiveContent {
+                    kind: ActiveContentKind::ActiveXControl,
+                    sheet: Some("Sheet1".to_string()),
+                    name: "CommandButton1".to_string(),
+                    target: Some("{D7053240-CE69-11CD-A777-00DD01143C57}".to_string()),
+                    runs_on_open: false,
+                },
+                ActiveContent {
+                    kind: ActiveContentKind::OleObject,
+                    sheet: Some("Sheet2".to_string()),
+                    name: "Word.Document.12".to_string(),
+                    target: Some("file:///C:/docs/report.docx".to_string()),
+                    runs_on_open: true,
+                },
+                ActiveContent {
+                    kind: ActiveContentKind::DataConnection,
+                    name: "Orders".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let violations = EmbeddedObjectsRule.check(&workbook).unwrap();
+        let messages: Vec<_> = violations.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Contains OLE object 'Package' (xl/embeddings/oleObject1.bin)",
+                "Contains ActiveX control 'CommandButton1' (class {D7053240-CE69-11CD-A777-00DD01143C57})",
+                "Contains OLE object 'Word.Document.12' (file:///C:/docs/report.docx); the link is updated on open",
+            ]
+        );
+        assert_eq!(
+            violations[0].scope,
+            ViolationScope::Sheet("Sheet1".to_string())
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! SEC010: External data connections, web queries and Power Query queries
+//!
+//! Refreshing a connection runs its query against a database, file or web
+//! page, and connection strings often keep the user name and password in
+//! plain text inside the file.
+
+use super::err008_broken_chart_references::capitalize;
+use super::{LinterRule, RuleAccess, RuleCategory};
+use crate::reader::Workbook;
+use crate::reader::active_content::ActiveContentKind;
+use crate::violation::{Severity, Violation, ViolationScope};
+use anyhow::Result;
+
+pub struct DataConnectionsRule;
+
+impl LinterRule for DataConnectionsRule {
+    fn id(&self) -> &str {
+        "SEC010"
+    }
+
+    fn name(&self) -> &str {
+        "External data connections"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::SecurityAndPrivacy
+    }
+
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        for item in &workbook.active_content {
+            if !matches!(
+                item.kind,
+                ActiveContentKind::DataConnection
+                    | ActiveContentKind::WebQuery
+                    | ActiveContentKind::PowerQuery
+            ) {
+                continue;
+            }
+            let mut message = format!("{} '{}'", capitalize(item.kind.label()), item.name);
+            if let Some(target) = item.redacted_target() {
+                message.push_str(&format!(" reads {}", target));
+            }
+            if item.runs_on_open {
+                message.push_str("; it is refreshed on open");
+            }
+            let severity = if item.has_password() {
+                message.push_str("; the connection string stores a password");
+                Severity::Error
+            } else {
+                Severity::Warning
+            };
+            let scope = match &item.sheet {
+                Some(sheet) => ViolationScope::Sheet(sheet.clone()),
+                None => ViolationScope::Book,
+            };
+            violations.push(Violation::new(self.id(), scope, message, severity));
+        }
+
+        Ok(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::ActiveContent;
+    use std::path::PathBuf;
+
+    fn connection(kind: ActiveContentKind, name: &str, target: &str) -> ActiveContent {
+        ActiveContent {
+            kind,
+            name: name.to_string(),
+            target: Some(target.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_data_connections() {
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            active_content: vec![
+                connection(
+                    ActiveContentKind::DataConnection,
+                    "Orders",
+                    "DSN=Orders;UID=sa;PWD=secret",
+                ),
+                ActiveContent {
+                    runs_on_open: true,
+                    ..connection(
+                        ActiveContentKind::WebQuery,
+                        "Rates",
+                        "https://example.com/rates",
+                    )
+                },
+                connection(ActiveContentKind::PowerQuery, "Query - Sales", "Sales"),
+                ActiveContent {
+                    kind: ActiveContentKind::OleObject,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let violations = DataConnectionsRule.check(&workbook).unwrap();
+        let messages: Vec<_> = violations.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Data connection 'Orders' reads DSN=Orders;UID=sa;PWD=***; the connection string stores a password",
+                "Web query 'Rates' reads https://example.com/rates; it is refreshed on open",
+                "Power Query query 'Query - Sales' reads Sales",
+            ]
+        );
+        assert_eq!(violations[0].severity, Severity::Error);
+        assert_eq!(violations[1].severity, Severity::Warning);
+        assert_eq!(violations[0].scope, ViolationScope::Book);
+    }
+}
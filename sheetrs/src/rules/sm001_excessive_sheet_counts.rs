@@ -1,6 +1,6 @@
 //! SM001: Excessive sheet counts
 
-use super::{LinterRule, RuleCategory};
+use super::{LinterRule, RuleAccess, RuleCategory};
 use crate::config::LinterConfig;
 use crate::reader::Workbook;
 use crate::violation::{Severity, Violation, ViolationScope};
@@ -39,6 +39,10 @@ impl LinterRule for ExcessiveSheetCountsRule {
         RuleCategory::StructuralAndMaintainability
     }
 
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
     fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
         let sheet_count = workbook.sheets.len() as u32;
@@ -62,8 +66,8 @@ impl LinterRule for ExcessiveSheetCountsRule {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::CellStore;
     use crate::reader::workbook::Sheet;
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     #[test]
@@ -72,16 +76,9 @@ mod tests {
         for i in 0..60 {
             sheets.push(Sheet {
                 name: format!("Sheet{}", i),
-                cells: HashMap::new(),
-                used_range: None,
-                hidden_columns: Vec::new(),
-                hidden_rows: Vec::new(),
-                merged_cells: Vec::new(),
-                sheet_path: None,
-                formula_parsing_error: None,
-                conditional_formatting_count: 0,
-                conditional_formatting_ranges: Vec::new(),
+                cells: CellStore::new(),
                 visible: true,
+                ..Default::default()
             });
         }
 
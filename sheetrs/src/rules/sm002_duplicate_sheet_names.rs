@@ -1,6 +1,6 @@
 //! SM002: Duplicate sheet names
 
-use super::{LinterRule, RuleCategory};
+use super::{LinterRule, RuleAccess, RuleCategory};
 use crate::reader::Workbook;
 use crate::violation::{Severity, Violation, ViolationScope};
 use anyhow::Result;
@@ -29,6 +29,10 @@ impl LinterRule for DuplicateSheetNamesRule {
         RuleCategory::StructuralAndMaintainability
     }
 
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
     fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
         let mut name_map: HashMap<String, Vec<String>> = HashMap::new();
@@ -61,8 +65,8 @@ impl LinterRule for DuplicateSheetNamesRule {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::CellStore;
     use crate::reader::workbook::Sheet;
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     #[test]
@@ -70,19 +74,19 @@ mod tests {
         let sheets = vec![
             Sheet {
                 name: "Data".to_string(),
-                cells: HashMap::new(),
+                cells: CellStore::new(),
                 used_range: None,
                 ..Default::default()
             },
             Sheet {
                 name: "data".to_string(),
-                cells: HashMap::new(),
+                cells: CellStore::new(),
                 used_range: None,
                 ..Default::default()
             },
             Sheet {
                 name: "Summary".to_string(),
-                cells: HashMap::new(),
+                cells: CellStore::new(),
                 used_range: None,
                 ..Default::default()
             },
@@ -108,31 +112,31 @@ mod tests {
         let sheets = vec![
             Sheet {
                 name: "Sheet1".to_string(),
-                cells: HashMap::new(),
+                cells: CellStore::new(),
                 used_range: None,
                 ..Default::default()
             },
             Sheet {
                 name: "sheet 1".to_string(),
-                cells: HashMap::new(),
+                cells: CellStore::new(),
                 used_range: None,
                 ..Default::default()
             },
             Sheet {
                 name: "Sheet-1".to_string(),
-                cells: HashMap::new(),
+                cells: CellStore::new(),
                 used_range: None,
                 ..Default::default()
             },
             Sheet {
                 name: "Data_2024".to_string(),
-                cells: HashMap::new(),
+                cells: CellStore::new(),
                 used_range: None,
                 ..Default::default()
             },
             Sheet {
                 name: "data2024".to_string(),
-                cells: HashMap::new(),
+                cells: CellStore::new(),
                 used_range: None,
                 ..Default::default()
             },
@@ -1,6 +1,6 @@
 //! SM003: Long text cell
 
-use super::{LinterRule, RuleCategory};
+use super::{LinterRule, RuleAccess, RuleCategory};
 use crate::config::LinterConfig;
 use crate::reader::Workbook;
 use crate::violation::{Severity, Violation, ViolationScope};
@@ -33,6 +33,10 @@ impl LinterRule for LongTextCellRule {
         RuleCategory::StructuralAndMaintainability
     }
 
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Sheet
+    }
+
     fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
 
@@ -45,8 +49,9 @@ impl LinterRule for LongTextCellRule {
             let mut long_text_cells: Vec<(u32, u32)> = Vec::new();
 
             for cell in sheet.all_cells() {
-                if let crate::reader::workbook::CellValue::Text(text) = &cell.value
-                    && text.len() > threshold
+                // Characters, not bytes; phonetic guides aren't part of the text
+                if let crate::reader::workbook::CellValue::Text(text, _) = &cell.value
+                    && text.chars().count() > threshold
                 {
                     long_text_cells.push((cell.row, cell.col));
                 }
@@ -143,22 +148,32 @@ fn find_contiguous_ranges(cells: &[(u32, u32)]) -> Vec<Vec<(u32, u32)>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::CellStore;
     use crate::reader::workbook::{Cell, CellValue, Sheet};
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     #[test]
     fn test_long_text_cell() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         let long_text = "A".repeat(300); // >255 chars
 
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
-                value: CellValue::Text(long_text),
+                value: CellValue::Text(long_text, None),
+                ..Default::default()
+            },
+        );
+        // 400 bytes of UTF-8, but only 200 characters
+        cells.insert(
+            (5, 5),
+            Cell {
+                row: 5,
+                col: 5,
+                value: CellValue::Text("é".repeat(200), None),
+                ..Default::default()
             },
         );
 
@@ -166,14 +181,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((1, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -1,6 +1,6 @@
 //! SM005: Non-descriptive sheet names
 
-use super::{LinterRule, RuleCategory};
+use super::{LinterRule, RuleAccess, RuleCategory};
 use crate::config::LinterConfig;
 use crate::reader::Workbook;
 use crate::violation::{Severity, Violation, ViolationScope};
@@ -32,6 +32,10 @@ impl LinterRule for NonDescriptiveSheetNameRule {
         RuleCategory::StructuralAndMaintainability
     }
 
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
     fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
 
@@ -65,8 +69,8 @@ impl LinterRule for NonDescriptiveSheetNameRule {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::CellStore;
     use crate::reader::workbook::Sheet;
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     #[test]
@@ -74,19 +78,19 @@ mod tests {
         let sheets = vec![
             Sheet {
                 name: "Sheet1".to_string(),
-                cells: HashMap::new(),
+                cells: CellStore::new(),
                 used_range: None,
                 ..Default::default()
             },
             Sheet {
                 name: "Copy of Data".to_string(),
-                cells: HashMap::new(),
+                cells: CellStore::new(),
                 used_range: None,
                 ..Default::default()
             },
             Sheet {
                 name: "Analysis".to_string(),
-                cells: HashMap::new(),
+                cells: CellStore::new(),
                 used_range: None,
                 ..Default::default()
             },
@@ -1,7 +1,7 @@
 //! UX001: Inconsistent number formatting detection
 //! Detects numeric data stored as text instead of as number type
 
-use super::{LinterRule, RuleCategory};
+use super::{LinterRule, RuleAccess, RuleCategory};
 use crate::reader::Workbook;
 use crate::violation::{Severity, Violation, ViolationScope};
 use anyhow::Result;
@@ -21,17 +21,24 @@ impl LinterRule for NumericTextRule {
         RuleCategory::FormattingAndUsability
     }
 
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Sheet
+    }
+
     fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
 
         for sheet in &workbook.sheets {
             // Collect all cells with numeric text
             let mut numeric_text_cells: Vec<(u32, u32)> = Vec::new();
+            let arrays = sheet.array_formula_index();
 
             for cell in sheet.all_cells() {
-                // Check if cell contains text that looks like a number
-                if let crate::reader::workbook::CellValue::Text(text) = &cell.value
+                // Check if cell contains text that looks like a number; array
+                // results are computed, not typed in
+                if let crate::reader::workbook::CellValue::Text(text, _) = &cell.value
                     && is_numeric_text(text)
+                    && !arrays.is_spilled(cell.row, cell.col)
                 {
                     numeric_text_cells.push((cell.row, cell.col));
                 }
@@ -142,32 +149,32 @@ fn is_numeric_text(text: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::CellStore;
     use crate::reader::workbook::{Cell, CellValue, Sheet};
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     #[test]
     fn test_numeric_text_detection() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
 
         // Numeric text - should be flagged
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
-                value: CellValue::Text("42".to_string()),
+                value: CellValue::Text("42".to_string(), None),
+                ..Default::default()
             },
         );
 
         cells.insert(
             (1, 0),
             Cell {
-                num_fmt: None,
                 row: 1,
                 col: 0,
-                value: CellValue::Text("3.14".to_string()),
+                value: CellValue::Text("3.14".to_string(), None),
+                ..Default::default()
             },
         );
 
@@ -175,10 +182,10 @@ mod tests {
         cells.insert(
             (2, 0),
             Cell {
-                num_fmt: None,
                 row: 2,
                 col: 0,
                 value: CellValue::Number(100.0),
+                ..Default::default()
             },
         );
 
@@ -186,10 +193,10 @@ mod tests {
         cells.insert(
             (3, 0),
             Cell {
-                num_fmt: None,
                 row: 3,
                 col: 0,
-                value: CellValue::Text("Hello".to_string()),
+                value: CellValue::Text("Hello".to_string(), None),
+                ..Default::default()
             },
         );
 
@@ -197,14 +204,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((4, 1)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
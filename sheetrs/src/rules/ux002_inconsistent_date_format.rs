@@ -1,5 +1,5 @@
 use crate::config::LinterConfig;
-use crate::reader::{CellValue, Workbook};
+use crate::reader::{CellValue, NumberFormat, Workbook};
 use crate::rules::LinterRule;
 use crate::violation::{Severity, Violation, ViolationScope};
 
@@ -35,27 +35,9 @@ impl InconsistentDateFormatRule {
         }
     }
 
-    /// Check if a format string represents a date
+    /// Check if a format string shows numbers as calendar dates
     fn is_date_format(fmt: &str) -> bool {
-        // Simple heuristic: contains date characters
-        // Exclude simple number formats
-        let lower = fmt.to_lowercase();
-
-        // Exclude "Red", "Blue" etc colors
-        let lower_no_color = lower.replace("[red]", "").replace("[blue]", "");
-
-        // Check for d, m, y. Note 'm' can be minutes, but usually in context of time.
-        // Should ANY date/time that doesn't match the specific format be flagged?
-        // Or only Dates?
-        // User request: "Detect if date not formatted as a specific format setup"
-        // So if it IS a date, it must match.
-        // Excel date formats: d, m, y.
-        (lower_no_color.contains('d')
-            || lower_no_color.contains('y')
-            || (lower_no_color.contains('m')
-                && !lower_no_color.contains('0')
-                && !lower_no_color.contains('#')))
-            && !lower_no_color.contains("general") // General is not a date
+        NumberFormat::parse(fmt).is_ok_and(|format| format.is_date())
     }
 }
 
@@ -72,6 +54,10 @@ impl LinterRule for InconsistentDateFormatRule {
         crate::rules::RuleCategory::FormattingAndUsability
     }
 
+    fn access(&self) -> crate::rules::RuleAccess {
+        crate::rules::RuleAccess::Sheet
+    }
+
     fn check(&self, workbook: &Workbook) -> anyhow::Result<Vec<Violation>> {
         let mut violations = Vec::new();
 
@@ -90,7 +76,7 @@ impl LinterRule for InconsistentDateFormatRule {
                 // Formulas can also result in dates.
                 let is_candidate = match cell.value {
                     CellValue::Number(_) => true,
-                    CellValue::Text(_) => true,
+                    CellValue::Text(..) => true,
                     CellValue::Formula { .. } => true,
                     _ => false,
                 };
@@ -99,18 +85,15 @@ impl LinterRule for InconsistentDateFormatRule {
                     // Normalize format: remove escape backslashes common in XLSX (e.g. "mm\-dd\-yyyy" -> "mm-dd-yyyy")
                     let normalized_fmt = fmt.replace('\\', "");
 
-                    // Check if it's a date format (using original check, but on normalized or raw? usually safe to check raw)
-                    if Self::is_date_format(&normalized_fmt)
+                    // Parse the raw code: escaped characters are literals, not date tokens
+                    if Self::is_date_format(fmt)
                         && normalized_fmt != required_format.replace('\\', "")
                     {
                         violations.push(Violation::new(
                             self.id(),
                             ViolationScope::Cell(
                                 sheet.name.clone(),
-                                crate::violation::CellReference {
-                                    row: *row,
-                                    col: *col,
-                                },
+                                crate::violation::CellReference { row, col },
                             ),
                             format!(
                                 "Date format '{}' does not match required format '{}'",
@@ -130,13 +113,13 @@ impl LinterRule for InconsistentDateFormatRule {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::CellStore;
     use crate::reader::{Cell, Sheet};
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     #[test]
     fn test_date_format_check() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         // Correct format
         cells.insert(
             (0, 0),
@@ -145,6 +128,7 @@ mod tests {
                 row: 0,
                 col: 0,
                 value: CellValue::Number(44000.0),
+                ..Default::default()
             },
         );
         // Incorrect format (d-m-y)
@@ -155,6 +139,7 @@ mod tests {
                 row: 0,
                 col: 1,
                 value: CellValue::Number(44000.0),
+                ..Default::default()
             },
         );
         // Not a date (General)
@@ -165,6 +150,7 @@ mod tests {
                 row: 0,
                 col: 2,
                 value: CellValue::Number(123.0),
+                ..Default::default()
             },
         );
 
@@ -172,14 +158,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((1, 3)),
-            hidden_columns: vec![],
-            hidden_rows: vec![],
-            merged_cells: vec![],
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
-            sheet_path: None,
+            ..Default::default()
         };
 
         let workbook = Workbook {
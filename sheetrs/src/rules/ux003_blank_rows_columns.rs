@@ -1,9 +1,10 @@
 //! UX003: Blank rows/columns in used ranges
 
-use super::{LinterRule, RuleCategory};
+use super::{LinterRule, RuleAccess, RuleCategory};
 use crate::reader::Workbook;
 use crate::violation::{Severity, Violation, ViolationScope};
 use anyhow::Result;
+use std::collections::HashSet;
 
 pub struct BlankRowsColumnsRule {
     max_blank_row: u32,
@@ -35,6 +36,10 @@ impl LinterRule for BlankRowsColumnsRule {
         RuleCategory::FormattingAndUsability
     }
 
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Sheet
+    }
+
     fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
         let mut violations = Vec::new();
 
@@ -55,16 +60,8 @@ impl LinterRule for BlankRowsColumnsRule {
                     let max_col = used_cols.saturating_sub(1);
 
                     // Find min from actual cells
-                    let (cell_min_row, cell_min_col) = if sheet.cells.is_empty() {
-                        (0, 0)
-                    } else {
-                        sheet
-                            .cells
-                            .keys()
-                            .fold((u32::MAX, u32::MAX), |(min_r, min_c), (r, c)| {
-                                (min_r.min(*r), min_c.min(*c))
-                            })
-                    };
+                    let cell_min_row = sheet.cells.row_bounds().map_or(0, |(min, _)| min);
+                    let cell_min_col = sheet.cells.column_bounds().map_or(0, |(min, _)| min);
 
                     (cell_min_row, max_row, cell_min_col, max_col)
                 } else {
@@ -201,17 +198,8 @@ fn group_contiguous_indices(indices: &[u32]) -> Vec<Vec<u32>> {
 
 /// Find the used range (min/max row and column with data)
 fn find_used_range(sheet: &crate::reader::workbook::Sheet) -> (u32, u32, u32, u32) {
-    let mut min_row = u32::MAX;
-    let mut max_row = 0u32;
-    let mut min_col = u32::MAX;
-    let mut max_col = 0u32;
-
-    for cell in sheet.all_cells() {
-        min_row = min_row.min(cell.row);
-        max_row = max_row.max(cell.row);
-        min_col = min_col.min(cell.col);
-        max_col = max_col.max(cell.col);
-    }
+    let (min_row, max_row) = sheet.cells.row_bounds().unwrap_or((u32::MAX, 0));
+    let (min_col, max_col) = sheet.cells.column_bounds().unwrap_or((u32::MAX, 0));
 
     (min_row, max_row, min_col, max_col)
 }
@@ -225,15 +213,18 @@ fn find_blank_rows(
     max_col: u32,
 ) -> Vec<u32> {
     let mut blank_rows = Vec::new();
+    // Rows the autofilter hides aren't seen as gaps
+    let filtered_rows: HashSet<u32> = sheet.filtered_rows.iter().copied().collect();
 
     for row in min_row..=max_row {
+        if filtered_rows.contains(&row) {
+            continue;
+        }
+
         // Check if row has any non-empty data
-        let has_data = (min_col..=max_col).any(|col| {
-            sheet
-                .cells
-                .get(&(row, col))
-                .is_some_and(|c| !c.value.is_empty())
-        });
+        let has_data = sheet
+            .cells_in_range((row, min_col), (row, max_col))
+            .any(|c| !c.value.is_empty());
 
         // Check if row is part of a merged cell
         let in_merged_cell = sheet
@@ -259,14 +250,16 @@ fn find_blank_columns(
 ) -> Vec<u32> {
     let mut blank_cols = Vec::new();
 
+    // One pass over the range instead of probing every (row, col) pair
+    let data_columns: HashSet<u32> = sheet
+        .cells_in_range((min_row, min_col), (max_row, max_col))
+        .filter(|c| !c.value.is_empty())
+        .map(|c| c.col)
+        .collect();
+
     for col in min_col..=max_col {
         // Check if column has any non-empty data
-        let has_data = (min_row..=max_row).any(|row| {
-            sheet
-                .cells
-                .get(&(row, col))
-                .is_some_and(|c| !c.value.is_empty())
-        });
+        let has_data = data_columns.contains(&col);
 
         // Check if column is part of a merged cell
         let in_merged_cell = sheet
@@ -346,39 +339,39 @@ fn column_index_to_letter(col: u32) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reader::CellStore;
     use crate::reader::workbook::{Cell, CellValue, Sheet};
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     #[test]
     fn test_blank_rows() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         // Row 0: A1, B1
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
-                value: CellValue::Text("A1".to_string()),
+                value: CellValue::Text("A1".to_string(), None),
+                ..Default::default()
             },
         );
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
-                value: CellValue::Text("A1".to_string()),
+                value: CellValue::Text("A1".to_string(), None),
+                ..Default::default()
             },
         );
         cells.insert(
             (0, 1),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 1,
-                value: CellValue::Text("B1".to_string()),
+                value: CellValue::Text("B1".to_string(), None),
+                ..Default::default()
             },
         );
         // Row 1: blank -> 1 contiguous
@@ -386,19 +379,19 @@ mod tests {
         cells.insert(
             (2, 0),
             Cell {
-                num_fmt: None,
                 row: 2,
                 col: 0,
-                value: CellValue::Text("A3".to_string()),
+                value: CellValue::Text("A3".to_string(), None),
+                ..Default::default()
             },
         );
         cells.insert(
             (2, 1),
             Cell {
-                num_fmt: None,
                 row: 2,
                 col: 1,
-                value: CellValue::Text("B3".to_string()),
+                value: CellValue::Text("B3".to_string(), None),
+                ..Default::default()
             },
         );
 
@@ -406,14 +399,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((3, 2)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -441,28 +428,33 @@ mod tests {
         };
         let violations_relaxed = rule_relaxed.check(&workbook).unwrap();
         assert_eq!(violations_relaxed.len(), 0);
+
+        // A row hidden by the autofilter isn't a gap
+        let mut filtered = workbook.clone();
+        filtered.sheets[0].filtered_rows = vec![1];
+        assert!(rule.check(&filtered).unwrap().is_empty());
     }
 
     #[test]
     fn test_blank_columns() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         // Column A: A1, A2
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
-                value: CellValue::Text("A1".to_string()),
+                value: CellValue::Text("A1".to_string(), None),
+                ..Default::default()
             },
         );
         cells.insert(
             (1, 0),
             Cell {
-                num_fmt: None,
                 row: 1,
                 col: 0,
-                value: CellValue::Text("A2".to_string()),
+                value: CellValue::Text("A2".to_string(), None),
+                ..Default::default()
             },
         );
         // Column B: blank -> 1 contiguous
@@ -470,19 +462,19 @@ mod tests {
         cells.insert(
             (0, 2),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 2,
-                value: CellValue::Text("C1".to_string()),
+                value: CellValue::Text("C1".to_string(), None),
+                ..Default::default()
             },
         );
         cells.insert(
             (1, 2),
             Cell {
-                num_fmt: None,
                 row: 1,
                 col: 2,
-                value: CellValue::Text("C2".to_string()),
+                value: CellValue::Text("C2".to_string(), None),
+                ..Default::default()
             },
         );
 
@@ -490,14 +482,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((2, 3)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -529,41 +515,41 @@ mod tests {
 
     #[test]
     fn test_no_blanks() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
-                value: CellValue::Text("A1".to_string()),
+                value: CellValue::Text("A1".to_string(), None),
+                ..Default::default()
             },
         );
         cells.insert(
             (0, 1),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 1,
-                value: CellValue::Text("B1".to_string()),
+                value: CellValue::Text("B1".to_string(), None),
+                ..Default::default()
             },
         );
         cells.insert(
             (1, 0),
             Cell {
-                num_fmt: None,
                 row: 1,
                 col: 0,
-                value: CellValue::Text("A2".to_string()),
+                value: CellValue::Text("A2".to_string(), None),
+                ..Default::default()
             },
         );
         cells.insert(
             (1, 1),
             Cell {
-                num_fmt: None,
                 row: 1,
                 col: 1,
-                value: CellValue::Text("B2".to_string()),
+                value: CellValue::Text("B2".to_string(), None),
+                ..Default::default()
             },
         );
 
@@ -571,14 +557,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((2, 2)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -598,24 +578,24 @@ mod tests {
 
     #[test]
     fn test_merged_cells_not_blank() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         // Row 0: A1, B1
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
-                value: CellValue::Text("A1".to_string()),
+                value: CellValue::Text("A1".to_string(), None),
+                ..Default::default()
             },
         );
         cells.insert(
             (0, 1),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 1,
-                value: CellValue::Text("B1".to_string()),
+                value: CellValue::Text("B1".to_string(), None),
+                ..Default::default()
             },
         );
         // Row 1: blank but part of merged cell F2:F5
@@ -623,29 +603,29 @@ mod tests {
         cells.insert(
             (2, 0),
             Cell {
-                num_fmt: None,
                 row: 2,
                 col: 0,
-                value: CellValue::Text("A3".to_string()),
+                value: CellValue::Text("A3".to_string(), None),
+                ..Default::default()
             },
         );
         cells.insert(
             (2, 1),
             Cell {
-                num_fmt: None,
                 row: 2,
                 col: 1,
-                value: CellValue::Text("B3".to_string()),
+                value: CellValue::Text("B3".to_string(), None),
+                ..Default::default()
             },
         );
         // Merged cell F2:F5 (row 1-4, col 5) - only first cell has data
         cells.insert(
             (1, 5),
             Cell {
-                num_fmt: None,
                 row: 1,
                 col: 5,
-                value: CellValue::Text("Merged".to_string()),
+                value: CellValue::Text("Merged".to_string(), None),
+                ..Default::default()
             },
         );
 
@@ -653,14 +633,9 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((5, 6)),
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
             merged_cells: vec![(1, 5, 4, 5)], // F2:F5 (rows 1-4, col 5)
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -692,16 +667,10 @@ mod tests {
     fn test_empty_sheet_no_violations() {
         let sheet = Sheet {
             name: "Empty".to_string(),
-            cells: HashMap::new(),
+            cells: CellStore::new(),
             used_range: Some((1, 1)), // A1 reported by parser
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -722,15 +691,15 @@ mod tests {
 
     #[test]
     fn test_styled_but_empty_row_col() {
-        let mut cells = HashMap::new();
+        let mut cells = CellStore::new();
         // Row 0: data
         cells.insert(
             (0, 0),
             Cell {
-                num_fmt: None,
                 row: 0,
                 col: 0,
-                value: CellValue::Text("A1".to_string()),
+                value: CellValue::Text("A1".to_string(), None),
+                ..Default::default()
             },
         );
         // Row 1: Styled but Empty. Should be reported as blank row!
@@ -741,6 +710,7 @@ mod tests {
                 row: 1,
                 col: 0,
                 value: CellValue::Empty,
+                ..Default::default()
             },
         );
 
@@ -748,14 +718,8 @@ mod tests {
             name: "Sheet1".to_string(),
             cells,
             used_range: Some((2, 1)), // 2 rows, 1 col
-            hidden_columns: Vec::new(),
-            hidden_rows: Vec::new(),
-            merged_cells: Vec::new(),
-            sheet_path: None,
-            formula_parsing_error: None,
-            conditional_formatting_count: 0,
-            conditional_formatting_ranges: Vec::new(),
             visible: true,
+            ..Default::default()
         };
 
         let workbook = Workbook {
@@ -0,0 +1,327 @@
+//! UX004: Input areas without data validation
+
+use super::{LinterRule, RuleCategory};
+use crate::config::LinterConfig;
+use crate::reader::Workbook;
+use crate::reader::workbook::CellValue;
+use crate::violation::{Severity, Violation, ViolationScope};
+use anyhow::Result;
+use regex::Regex;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+/// Reports groups of constant cells that formulas read as single-cell inputs
+/// but that carry no data validation
+pub struct UnvalidatedInputsRule {
+    cell_ref_pattern: Regex,
+    config: LinterConfig,
+}
+
+impl UnvalidatedInputsRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        // Group 2/3: quoted/unquoted sheet name, group 4/5: column/row,
+        // group 6: range end (ranges are data, not inputs)
+        let cell_ref_pattern = Regex::new(
+            r"(?:('([^']+)'|([A-Za-z0-9_\.]+))!)?\$?([A-Za-z]{1,3})\$?([0-9]+)(:\$?[A-Za-z]*\$?[0-9]*)?",
+        )
+        .unwrap();
+
+        Self {
+            cell_ref_pattern,
+            config: config.clone(),
+        }
+    }
+}
+
+impl Default for UnvalidatedInputsRule {
+    fn default() -> Self {
+        Self::new(&LinterConfig::default())
+    }
+}
+
+impl LinterRule for UnvalidatedInputsRule {
+    fn id(&self) -> &str {
+        "UX004"
+    }
+
+    fn name(&self) -> &str {
+        "Unvalidated input cells"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::FormattingAndUsability
+    }
+
+    // Inputs are found through formulas on any sheet, so workbook access is needed
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        // Sheet name -> single cells read by formulas
+        let mut referenced: BTreeMap<String, HashSet<(u32, u32)>> = BTreeMap::new();
+        for sheet in &workbook.sheets {
+            for cell in sheet.all_cells() {
+                let Some(formula) = cell.value.as_formula() else {
+                    continue;
+                };
+                for cap in self.cell_ref_pattern.captures_iter(formula) {
+                    let whole = cap.get(0).unwrap();
+                    // Function names such as LOG10( look like references, and
+                    // text literals may hold anything
+                    let is_function = formula[whole.end()..].starts_with('(');
+                    let in_text = formula[..whole.start()].matches('"').count() % 2 == 1;
+                    if cap.get(6).is_some() || is_function || in_text {
+                        continue;
+                    }
+                    let sheet_name = cap
+                        .get(2)
+                        .or_else(|| cap.get(3))
+                        .map(|m| m.as_str())
+                        .unwrap_or(&sheet.name);
+                    if let Some(coords) = parse_components(&cap[5], &cap[4]) {
+                        referenced
+                            .entry(sheet_name.to_string())
+                            .or_default()
+                            .insert(coords);
+                    }
+                }
+            }
+        }
+
+        for sheet in &workbook.sheets {
+            let Some(refs) = referenced.get(&sheet.name) else {
+                continue;
+            };
+            let threshold = self
+                .config
+                .get_param_int("min_input_area_size", Some(&sheet.name))
+                .unwrap_or(3) as usize;
+            let arrays = sheet.array_formula_index();
+            let validations = sheet.validation_index();
+
+            let mut inputs: Vec<(u32, u32)> = refs
+                .iter()
+                .copied()
+                .filter(|&(row, col)| {
+                    sheet.get_cell(row, col).is_some_and(|cell| {
+                        !matches!(cell.value, CellValue::Empty | CellValue::Formula { .. })
+                    }) && !arrays.is_spilled(row, col)
+                        && validations.at(row, col).is_none()
+                })
+                .collect();
+            inputs.sort();
+
+            for range in find_contiguous_ranges(&inputs) {
+                if range.len() >= threshold {
+                    violations.push(Violation::new(
+                        self.id(),
+                        ViolationScope::Sheet(sheet.name.clone()),
+                        format!(
+                            "Input area without data validation: {}",
+                            format_single_range(&range)
+                        ),
+                        Severity::Info,
+                    ));
+                }
+            }
+        }
+
+        Ok(violations)
+    }
+}
+
+fn parse_components(row_str: &str, col_str: &str) -> Option<(u32, u32)> {
+    let row = row_str.parse::<u32>().ok()?;
+    let mut col = 0u32;
+    for ch in col_str.chars() {
+        col = col * 26 + (ch.to_ascii_uppercase() as u32 - 'A' as u32 + 1);
+    }
+    if row == 0 || col == 0 {
+        return None;
+    }
+
+    Some((row - 1, col - 1))
+}
+
+/// Format a single contiguous range
+fn format_single_range(cells: &[(u32, u32)]) -> String {
+    use crate::violation::CellReference;
+
+    if cells.is_empty() {
+        return String::new();
+    }
+
+    if cells.len() == 1 {
+        return CellReference::new(cells[0].0, cells[0].1).to_string();
+    }
+
+    let min_row = cells.iter().map(|(r, _)| r).min().unwrap();
+    let max_row = cells.iter().map(|(r, _)| r).max().unwrap();
+    let min_col = cells.iter().map(|(_, c)| c).min().unwrap();
+    let max_col = cells.iter().map(|(_, c)| c).max().unwrap();
+
+    let start = CellReference::new(*min_row, *min_col);
+    let end = CellReference::new(*max_row, *max_col);
+
+    format!("{}:{}", start, end)
+}
+
+/// Find contiguous ranges from a list of cells
+fn find_contiguous_ranges(cells: &[(u32, u32)]) -> Vec<Vec<(u32, u32)>> {
+    let cell_set: HashSet<(u32, u32)> = cells.iter().copied().collect();
+    let mut visited: HashSet<(u32, u32)> = HashSet::new();
+    let mut ranges: Vec<Vec<(u32, u32)>> = Vec::new();
+
+    for &cell in cells {
+        if visited.contains(&cell) {
+            continue;
+        }
+
+        // BFS to find all connected cells
+        let mut range = Vec::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(cell);
+        visited.insert(cell);
+
+        while let Some((row, col)) = queue.pop_front() {
+            range.push((row, col));
+
+            // Check all 4 adjacent cells (up, down, left, right)
+            let neighbors = [
+                (row.wrapping_sub(1), col),
+                (row + 1, col),
+                (row, col.wrapping_sub(1)),
+                (row, col + 1),
+            ];
+
+            for neighbor in neighbors {
+                if cell_set.contains(&neighbor) && !visited.contains(&neighbor) {
+                    visited.insert(neighbor);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        ranges.push(range);
+    }
+
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::validation::ValidationKind;
+    use crate::reader::workbook::{Cell, Sheet};
+    use crate::reader::{CellStore, DataValidation};
+    use std::path::PathBuf;
+
+    fn cell(row: u32, col: u32, value: CellValue) -> ((u32, u32), Cell) {
+        (
+            (row, col),
+            Cell {
+                row,
+                col,
+                value,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_unvalidated_inputs() {
+        let mut inputs = CellStore::new();
+        for row in 0..4 {
+            let (key, c) = cell(row, 1, CellValue::Number(row as f64));
+            inputs.insert(key, c);
+        }
+        let (key, c) = cell(0, 3, CellValue::Number(1.0));
+        inputs.insert(key, c);
+        let mut input_sheet = Sheet {
+            name: "Inputs".to_string(),
+            cells: inputs,
+            ..Default::default()
+        };
+
+        let mut calc = CellStore::new();
+        let (key, c) = cell(0, 0, CellValue::formula("Inputs!B1+Inputs!B2*Inputs!$B$3"));
+        calc.insert(key, c);
+        let (key, c) = cell(
+            1,
+            0,
+            CellValue::formula("SUM(Inputs!B1:B4)+Inputs!B4+Inputs!D1"),
+        );
+        calc.insert(key, c);
+        let calc_sheet = Sheet {
+            name: "Calc".to_string(),
+            cells: calc,
+            ..Default::default()
+        };
+
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![input_sheet.clone(), calc_sheet.clone()],
+            ..Default::default()
+        };
+        let rule = UnvalidatedInputsRule::default();
+        let violations = rule.check(&workbook).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].message,
+            "Input area without data validation: B1:B4"
+        );
+        assert_eq!(
+            violations[0].scope,
+            ViolationScope::Sheet("Inputs".to_string())
+        );
+
+        // Validating part of the area leaves too few unvalidated inputs
+        input_sheet.data_validations.push(DataValidation {
+            ranges: vec![(0, 1, 1, 1)],
+            kind: ValidationKind::Decimal,
+            formula1: Some("0".to_string()),
+            ..Default::default()
+        });
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![input_sheet, calc_sheet],
+            ..Default::default()
+        };
+        assert!(rule.check(&workbook).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_functions_and_text_are_not_inputs() {
+        let mut cells = CellStore::new();
+        // Constants in B2:B4, C2:C4 and LOG10:LOG12
+        for row in 1..4 {
+            for col in [1, 2] {
+                let (key, c) = cell(row, col, CellValue::Number(1.0));
+                cells.insert(key, c);
+            }
+        }
+        let log = 12 * 26 * 26 + 15 * 26 + 7 - 1;
+        for row in 9..12 {
+            let (key, c) = cell(row, log, CellValue::Number(2.0));
+            cells.insert(key, c);
+        }
+        let (key, c) = cell(
+            0,
+            0,
+            CellValue::formula(r#"LOG10(2)+LOG11(2)+LOG12(2)+C2+C3+C4&"see B2, B3 and ""B4""""#),
+        );
+        cells.insert(key, c);
+
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![Sheet {
+                name: "Sheet1".to_string(),
+                cells,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let violations = UnvalidatedInputsRule::default().check(&workbook).unwrap();
+        let messages: Vec<_> = violations.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(messages, vec!["Input area without data validation: C2:C4"]);
+    }
+}
@@ -0,0 +1,179 @@
+//! UX005: Large table without frozen header row
+//!
+//! When a sheet holds a long list under a row of headers, scrolling down hides
+//! what each column means unless the header row is frozen. Excel tables are
+//! skipped: Excel shows their headers in place of the column letters.
+
+use super::{LinterRule, RuleAccess, RuleCategory};
+use crate::config::LinterConfig;
+use crate::reader::{CellValue, Workbook};
+use crate::violation::{Severity, Violation, ViolationScope};
+use anyhow::Result;
+use std::collections::BTreeSet;
+
+pub struct UnfrozenHeaderRowRule {
+    min_rows: u32,
+}
+
+impl UnfrozenHeaderRowRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        let min_rows = config
+            .get_param_int("min_rows_for_frozen_header", None)
+            .unwrap_or(50) as u32;
+
+        Self { min_rows }
+    }
+}
+
+impl Default for UnfrozenHeaderRowRule {
+    fn default() -> Self {
+        Self { min_rows: 50 }
+    }
+}
+
+impl LinterRule for UnfrozenHeaderRowRule {
+    fn id(&self) -> &str {
+        "UX005"
+    }
+
+    fn name(&self) -> &str {
+        "Large table without frozen header row"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::FormattingAndUsability
+    }
+
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Sheet
+    }
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        for sheet in &workbook.sheets {
+            // The header is the first row with content: two or more text cells
+            let Some(header_row) = sheet
+                .all_cells()
+                .filter(|cell| cell.value != CellValue::Empty)
+                .map(|cell| cell.row)
+                .min()
+            else {
+                continue;
+            };
+            let header: Vec<_> = sheet
+                .all_cells()
+                .filter(|cell| cell.row == header_row && cell.value != CellValue::Empty)
+                .collect();
+            if header.len() < 2
+                || !header
+                    .iter()
+                    .all(|cell| matches!(cell.value, CellValue::Text(..)))
+            {
+                continue;
+            }
+            if sheet.view.frozen_rows > header_row {
+                continue;
+            }
+            let in_table = workbook.tables.iter().any(|table| {
+                table.sheet == sheet.name && table.header_rows > 0 && table.range.0 == header_row
+            });
+            if in_table {
+                continue;
+            }
+
+            let data_rows: BTreeSet<u32> = sheet
+                .all_cells()
+                .filter(|cell| cell.row > header_row && cell.value != CellValue::Empty)
+                .map(|cell| cell.row)
+                .collect();
+            if (data_rows.len() as u32) < self.min_rows {
+                continue;
+            }
+
+            violations.push(Violation::new(
+                self.id(),
+                ViolationScope::Sheet(sheet.name.clone()),
+                format!(
+                    "{} rows of data under the header in row {}, but the header row isn't frozen",
+                    data_rows.len(),
+                    header_row + 1
+                ),
+                Severity::Info,
+            ));
+        }
+
+        Ok(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::{Cell, CellStore, Sheet, SheetView, Table};
+    use std::path::PathBuf;
+
+    fn list_sheet(name: &str, frozen_rows: u32) -> Sheet {
+        let mut cells = CellStore::new();
+        let mut add = |row, col, value| {
+            cells.insert(
+                (row, col),
+                Cell {
+                    row,
+                    col,
+                    value,
+                    ..Default::default()
+                },
+            );
+        };
+        add(1, 0, CellValue::Text("Date".to_string(), None));
+        add(1, 1, CellValue::Text("Amount".to_string(), None));
+        for row in 2..62 {
+            add(row, 0, CellValue::Number(45000.0 + row as f64));
+            add(row, 1, CellValue::Number(row as f64));
+        }
+        let mut sheet = Sheet::new(name.to_string());
+        sheet.cells = cells;
+        sheet.view = SheetView {
+            frozen_rows,
+            ..Default::default()
+        };
+        sheet
+    }
+
+    #[test]
+    fn test_unfrozen_header_row() {
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![
+                list_sheet("Loose", 0),
+                // Freezing only the blank row above the header doesn't help
+                list_sheet("Above", 1),
+                list_sheet("Frozen", 2),
+                list_sheet("Table", 0),
+            ],
+            tables: vec![Table {
+                name: "Payments".to_string(),
+                sheet: "Table".to_string(),
+                range: (1, 0, 61, 1),
+                header_rows: 1,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let violations = UnfrozenHeaderRowRule::default().check(&workbook).unwrap();
+        let scopes: Vec<_> = violations.iter().map(|v| &v.scope).collect();
+        assert_eq!(
+            scopes,
+            vec![
+                &ViolationScope::Sheet("Loose".to_string()),
+                &ViolationScope::Sheet("Above".to_string()),
+            ]
+        );
+        assert_eq!(
+            violations[0].message,
+            "60 rows of data under the header in row 2, but the header row isn't frozen"
+        );
+    }
+}
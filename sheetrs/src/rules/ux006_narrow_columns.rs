@@ -0,0 +1,155 @@
+//! UX006: Columns too narrow for their content
+//!
+//! A formatted number or date that doesn't fit its column is shown as `####`.
+//! Text spills into empty neighbours and `General` numbers are rounded to fit,
+//! so only numbers with an explicit format are checked. All digits have the
+//! same width, which makes the displayed text's length a good measure.
+
+use super::{LinterRule, RuleAccess, RuleCategory};
+use crate::reader::{CellValue, Workbook};
+use crate::violation::{CellReference, Severity, Violation, ViolationScope};
+use anyhow::Result;
+use std::collections::BTreeMap;
+
+/// Excel's default width: 8.43 characters plus the cell padding
+const DEFAULT_COLUMN_WIDTH: f64 = 9.140625;
+/// Cell padding (5 pixels) in characters of 7 pixels
+const PADDING: f64 = 5.0 / 7.0;
+
+pub struct NarrowColumnsRule;
+
+impl LinterRule for NarrowColumnsRule {
+    fn id(&self) -> &str {
+        "UX006"
+    }
+
+    fn name(&self) -> &str {
+        "Columns too narrow for their content"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::FormattingAndUsability
+    }
+
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Sheet
+    }
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        for sheet in &workbook.sheets {
+            // Column -> (cells showing ####, widest text and its position)
+            let mut narrow: BTreeMap<u32, (usize, String, u32)> = BTreeMap::new();
+            for cell in sheet.all_cells() {
+                if !matches!(cell.value, CellValue::Number(_))
+                    || cell
+                        .num_fmt
+                        .as_deref()
+                        .is_none_or(|code| code.eq_ignore_ascii_case("general"))
+                    || sheet.hidden_columns.contains(&cell.col)
+                {
+                    continue;
+                }
+                // Merged cells have the width of all their columns
+                let merged = sheet.merged_cells.iter().any(|&(r1, c1, r2, c2)| {
+                    (r1..=r2).contains(&cell.row) && (c1..=c2).contains(&cell.col)
+                });
+                if merged {
+                    continue;
+                }
+                let width = sheet.column_width(cell.col).unwrap_or(DEFAULT_COLUMN_WIDTH);
+                let text = cell.display_text(workbook.date_system);
+                if text.chars().count() as f64 <= width - PADDING {
+                    continue;
+                }
+                let entry = narrow
+                    .entry(cell.col)
+                    .or_insert_with(|| (0, String::new(), cell.row));
+                entry.0 += 1;
+                if text.chars().count() > entry.1.chars().count() {
+                    entry.1 = text;
+                    entry.2 = cell.row;
+                }
+            }
+
+            for (col, (count, widest, row)) in narrow {
+                let width = sheet.column_width(col).unwrap_or(DEFAULT_COLUMN_WIDTH);
+                let cell = CellReference::new(row, col).to_excel_ref();
+                violations.push(Violation::new(
+                    self.id(),
+                    ViolationScope::Sheet(sheet.name.clone()),
+                    format!(
+                        "Column {} is too narrow for {} value{}, shown as #### (widest: '{}' in {}, {} characters; the column fits {:.1})",
+                        CellReference::col_to_letter(col),
+                        count,
+                        if count == 1 { "" } else { "s" },
+                        widest,
+                        cell,
+                        widest.chars().count(),
+                        (width - PADDING).max(0.0)
+                    ),
+                    Severity::Warning,
+                ));
+            }
+        }
+
+        Ok(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::{Cell, CellStore, Sheet, Span};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_narrow_columns() {
+        let mut cells = CellStore::new();
+        let mut add = |row, col, value: f64, num_fmt: Option<&str>| {
+            cells.insert(
+                (row, col),
+                Cell {
+                    row,
+                    col,
+                    value: CellValue::Number(value),
+                    num_fmt: num_fmt.map(str::to_string),
+                    ..Default::default()
+                },
+            );
+        };
+        // Column A is 6 characters wide
+        add(0, 0, 1234567.891, Some("#,##0.00"));
+        add(1, 0, 12.5, Some("#,##0.00"));
+        add(2, 0, 45658.0, Some("yyyy-mm-dd"));
+        add(3, 0, 1234567.891, None);
+        // Column B has the default width
+        add(0, 1, 1234567.891, Some("#,##0.00"));
+        add(1, 1, 123.0, Some("0.00"));
+
+        let mut sheet = Sheet::new("Sheet1".to_string());
+        sheet.cells = cells;
+        sheet.columns = vec![Span {
+            first: 0,
+            last: 0,
+            size: Some(6.0),
+            outline_level: 0,
+        }];
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![sheet],
+            ..Default::default()
+        };
+
+        let violations = NarrowColumnsRule.check(&workbook).unwrap();
+        let messages: Vec<_> = violations.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Column A is too narrow for 2 values, shown as #### (widest: '1,234,567.89' in A1, 12 characters; the column fits 5.3)",
+                "Column B is too narrow for 1 value, shown as #### (widest: '1,234,567.89' in B1, 12 characters; the column fits 8.4)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! UX007: Deeply nested outline groups
+//!
+//! Row and column groups nested several levels deep are hard to expand and
+//! collapse correctly, and usually stand in for a structure that would be
+//! clearer as separate sheets or a pivot table.
+
+use super::{LinterRule, RuleAccess, RuleCategory};
+use crate::config::LinterConfig;
+use crate::reader::{Span, Workbook};
+use crate::violation::{CellReference, Severity, Violation, ViolationScope};
+use anyhow::Result;
+
+pub struct DeepOutlineGroupsRule {
+    max_level: u8,
+}
+
+impl DeepOutlineGroupsRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        let max_level = config
+            .get_param_int("max_outline_level", None)
+            .unwrap_or(3)
+            .clamp(0, u8::MAX as i64) as u8;
+
+        Self { max_level }
+    }
+}
+
+impl Default for DeepOutlineGroupsRule {
+    fn default() -> Self {
+        Self { max_level: 3 }
+    }
+}
+
+impl DeepOutlineGroupsRule {
+    /// Runs nested deeper than allowed, merged where adjacent, with the
+    /// deepest level reached
+    fn deep_runs(&self, spans: &[Span]) -> (Vec<(u32, u32)>, u8) {
+        let mut runs: Vec<(u32, u32)> = Vec::new();
+        let mut deepest = 0;
+        for span in spans
+            .iter()
+            .filter(|span| span.outline_level > self.max_level)
+        {
+            deepest = deepest.max(span.outline_level);
+            match runs.last_mut() {
+                Some(last) if last.1 + 1 == span.first => last.1 = span.last,
+                _ => runs.push((span.first, span.last)),
+            }
+        }
+        (runs, deepest)
+    }
+}
+
+impl LinterRule for DeepOutlineGroupsRule {
+    fn id(&self) -> &str {
+        "UX007"
+    }
+
+    fn name(&self) -> &str {
+        "Deeply nested outline groups"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::FormattingAndUsability
+    }
+
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Metadata
+    }
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        for sheet in &workbook.sheets {
+            let (rows, row_depth) = self.deep_runs(&sheet.rows);
+            let (columns, column_depth) = self.deep_runs(&sheet.columns);
+            let format_run = |(first, last): (u32, u32), label: &dyn Fn(u32) -> String| {
+                if first == last {
+                    label(first)
+                } else {
+                    format!("{}-{}", label(first), label(last))
+                }
+            };
+
+            if !rows.is_empty() {
+                let ranges: Vec<_> = rows
+                    .into_iter()
+                    .map(|run| format_run(run, &|row| (row + 1).to_string()))
+                    .collect();
+                violations.push(Violation::new(
+                    self.id(),
+                    ViolationScope::Sheet(sheet.name.clone()),
+                    format!(
+                        "Rows {} are grouped {} levels deep (more than {})",
+                        ranges.join(", "),
+                        row_depth,
+                        self.max_level
+                    ),
+                    Severity::Info,
+                ));
+            }
+            if !columns.is_empty() {
+                let ranges: Vec<_> = columns
+                    .into_iter()
+                    .map(|run| format_run(run, &CellReference::col_to_letter))
+                    .collect();
+                violations.push(Violation::new(
+                    self.id(),
+                    ViolationScope::Sheet(sheet.name.clone()),
+                    format!(
+                        "Columns {} are grouped {} levels deep (more than {})",
+                        ranges.join(", "),
+                        column_depth,
+                        self.max_level
+                    ),
+                    Severity::Info,
+                ));
+            }
+        }
+
+        Ok(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::Sheet;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_deep_outline_groups() {
+        let span = |first, last, outline_level| Span {
+            first,
+            last,
+            size: None,
+            outline_level,
+        };
+        let mut sheet = Sheet::new("Report".to_string());
+        sheet.rows = vec![
+            span(1, 1, 1),
+            span(2, 10, 3),
+            span(11, 12, 4),
+            span(13, 14, 5),
+            span(15, 20, 1),
+            span(30, 30, 4),
+        ];
+        sheet.columns = vec![span(1, 3, 2)];
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![sheet],
+            ..Default::default()
+        };
+
+        let violations = DeepOutlineGroupsRule::default().check(&workbook).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].message,
+            "Rows 12-15, 31 are grouped 5 levels deep (more than 3)"
+        );
+    }
+}
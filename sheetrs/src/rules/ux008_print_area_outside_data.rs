@@ -0,0 +1,157 @@
+//! UX008: Print area outside the data
+//!
+//! A print area is set once and easily forgotten: after the data moves or
+//! grows, printing gives blank pages, and after its rows are deleted the area
+//! reads `#REF!` and Excel prints nothing at all.
+
+use super::{LinterRule, RuleAccess, RuleCategory};
+use crate::reader::Workbook;
+use crate::reader::tables::format_range;
+use crate::violation::{CellReference, Severity, Violation, ViolationScope};
+use anyhow::Result;
+
+pub struct PrintAreaOutsideDataRule;
+
+impl LinterRule for PrintAreaOutsideDataRule {
+    fn id(&self) -> &str {
+        "UX008"
+    }
+
+    fn name(&self) -> &str {
+        "Print area outside the data"
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::FormattingAndUsability
+    }
+
+    fn access(&self) -> RuleAccess {
+        RuleAccess::Sheet
+    }
+
+    fn check(&self, workbook: &Workbook) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        for sheet in &workbook.sheets {
+            let setup = &sheet.page_setup;
+            if let Some(reference) = &setup.broken_print_area {
+                violations.push(Violation::new(
+                    self.id(),
+                    ViolationScope::Sheet(sheet.name.clone()),
+                    format!("Print area refers to deleted cells: {}", reference),
+                    Severity::Warning,
+                ));
+                continue;
+            }
+
+            let Some((last_row, last_col)) = sheet.last_data_cell() else {
+                continue;
+            };
+            for &(r1, c1, r2, c2) in &setup.print_area {
+                let has_data = sheet
+                    .cells_in_range((r1, c1), (r2, c2))
+                    .any(|cell| !cell.value.is_empty());
+                if has_data {
+                    continue;
+                }
+                violations.push(Violation::new(
+                    self.id(),
+                    ViolationScope::Sheet(sheet.name.clone()),
+                    format!(
+                        "Print area {} holds no data (the data ends at {})",
+                        describe_area((r1, c1, r2, c2)),
+                        format_range("", (last_row, last_col, last_row, last_col))
+                    ),
+                    Severity::Warning,
+                ));
+            }
+        }
+
+        Ok(violations)
+    }
+}
+
+/// A print range as Excel shows it: `A1:C9`, or `D:E` and `1:2` for whole
+/// columns and rows
+fn describe_area((r1, c1, r2, c2): (u32, u32, u32, u32)) -> String {
+    if r2 == u32::MAX {
+        format!(
+            "{}:{}",
+            CellReference::col_to_letter(c1),
+            CellReference::col_to_letter(c2)
+        )
+    } else if c2 == u32::MAX {
+        format!("{}:{}", r1 + 1, r2 + 1)
+    } else {
+        format_range("", (r1, c1, r2, c2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::{Cell, CellStore, CellValue, PageSetup, Sheet};
+    use std::path::PathBuf;
+
+    fn sheet(name: &str, print_area: Vec<(u32, u32, u32, u32)>) -> Sheet {
+        let mut cells = CellStore::new();
+        for row in 0..10 {
+            cells.insert(
+                (row, 0),
+                Cell {
+                    row,
+                    col: 0,
+                    value: CellValue::Number(row as f64),
+                    ..Default::default()
+                },
+            );
+        }
+        let mut sheet = Sheet::new(name.to_string());
+        sheet.cells = cells;
+        sheet.page_setup = PageSetup {
+            print_area,
+            ..Default::default()
+        };
+        sheet
+    }
+
+    #[test]
+    fn test_print_area_outside_data() {
+        let mut broken = sheet("Broken", Vec::new());
+        broken.page_setup.broken_print_area = Some("Broken!#REF!".to_string());
+        let workbook = Workbook {
+            path: PathBuf::from("test.xlsx"),
+            sheets: vec![
+                sheet("Covered", vec![(0, 0, 9, 3)]),
+                sheet("Moved", vec![(0, 0, 9, 0), (20, 0, 40, 5)]),
+                sheet("Columns", vec![(0, 3, u32::MAX, 4)]),
+                sheet("Unset", Vec::new()),
+                broken,
+            ],
+            ..Default::default()
+        };
+
+        let violations = PrintAreaOutsideDataRule.check(&workbook).unwrap();
+        let messages: Vec<_> = violations
+            .iter()
+            .map(|v| (&v.scope, v.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    &ViolationScope::Sheet("Moved".to_string()),
+                    "Print area A21:F41 holds no data (the data ends at A10)"
+                ),
+                (
+                    &ViolationScope::Sheet("Columns".to_string()),
+                    "Print area D:E holds no data (the data ends at A10)"
+                ),
+                (
+                    &ViolationScope::Sheet("Broken".to_string()),
+                    "Print area refers to deleted cells: Broken!#REF!"
+                ),
+            ]
+        );
+    }
+}
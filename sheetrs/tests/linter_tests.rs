@@ -0,0 +1,59 @@
+use sheetrs::Linter;
+use sheetrs::config::{GlobalConfig, LinterConfig};
+use std::path::PathBuf;
+
+fn test_asset(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests")
+        .join(name)
+}
+
+#[test]
+fn test_streaming_matches_full_lint_for_streamable_rules() {
+    let linter = Linter::with_config(LinterConfig {
+        global: GlobalConfig {
+            enabled_rules: ["ALL".to_string()].into_iter().collect(),
+            // SEC005 may probe URLs over the network
+            disabled_rules: ["SEC005".to_string()].into_iter().collect(),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    let path = test_asset("minimal_test.xlsx");
+
+    let skipped = linter.streaming_skipped_rules();
+    assert!(skipped.contains(&"ERR003"));
+    assert!(!skipped.contains(&"ERR001"));
+
+    let full: Vec<_> = linter
+        .lint_file(&path)
+        .unwrap()
+        .into_iter()
+        .filter(|v| !skipped.contains(&v.rule_id.as_str()))
+        .collect();
+    let streamed = linter.lint_file_streaming(&path).unwrap();
+
+    assert!(!streamed.is_empty());
+    assert_eq!(streamed.len(), full.len());
+    for violation in &full {
+        assert!(streamed.contains(violation), "missing {:?}", violation);
+    }
+}
+
+#[test]
+fn test_encrypted_file_reports_typed_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("encrypted.xlsx");
+    // CFB signature followed by a directory entry naming the encrypted package
+    let mut data = vec![0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+    data.extend_from_slice(&[0; 504]);
+    data.extend("EncryptedPackage".encode_utf16().flat_map(u16::to_le_bytes));
+    std::fs::write(&path, data).unwrap();
+
+    let error = Linter::new().lint_file(&path).unwrap_err();
+    let encrypted = error
+        .downcast_ref::<sheetrs::reader::EncryptedFileError>()
+        .expect("typed encryption error");
+    assert_eq!(encrypted.path, path);
+    assert!(error.to_string().contains("encrypted"));
+}
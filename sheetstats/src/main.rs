@@ -2,6 +2,8 @@ use anyhow::{Context, Result};
 use clap::{Parser, ValueEnum};
 use serde::Serialize;
 use sheetrs::reader;
+use sheetrs::reader::pivot::PivotSource;
+use std::collections::{BTreeMap, HashSet};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -34,6 +36,60 @@ struct FileStats {
     total_file_size: u64,
     formula_stats: Vec<FormulaStats>,
     cell_stats: Vec<CellStats>,
+    properties: DocumentProperties,
+    pivot_tables: Vec<PivotTableStats>,
+    drawing_stats: Vec<DrawingStats>,
+}
+
+#[derive(Serialize)]
+struct PivotTableStats {
+    name: String,
+    /// Sheet-qualified cells the pivot table fills
+    location: String,
+    /// Source range or name, prefixed with the workbook for external sources
+    source: String,
+    /// Compressed size of the pivot cache; 0 for ODS, which keeps none
+    cache_size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache_records: Option<u64>,
+}
+
+/// Charts and pictures of a sheet
+#[derive(Serialize)]
+struct DrawingStats {
+    sheet_name: String,
+    charts: usize,
+    images: usize,
+    /// Compressed size of the image files the sheet shows, each file counted once
+    media_size: u64,
+    /// Share of the file taken by `media_size`
+    percentage: f64,
+}
+
+/// Document metadata; unset fields are omitted
+#[derive(Serialize)]
+struct DocumentProperties {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    subject: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_modified_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    company: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    manager: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    template: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    application: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    modified: Option<String>,
+    custom: BTreeMap<String, String>,
 }
 
 #[derive(Serialize)]
@@ -87,6 +143,42 @@ fn main() -> Result<()> {
     // Calculate cell statistics
     let cell_stats = calculate_cell_stats(&workbook);
 
+    let properties = &workbook.properties;
+    let properties = DocumentProperties {
+        title: properties.title.clone(),
+        subject: properties.subject.clone(),
+        author: properties.author.clone(),
+        last_modified_by: properties.last_modified_by.clone(),
+        company: properties.company.clone(),
+        manager: properties.manager.clone(),
+        template: properties.template.clone(),
+        application: properties.application.clone(),
+        created: properties.created.map(|dt| dt.to_string()),
+        modified: properties.modified.map(|dt| dt.to_string()),
+        custom: properties.custom.clone(),
+    };
+
+    let pivot_tables = workbook
+        .pivot_tables
+        .iter()
+        .map(|pivot| PivotTableStats {
+            name: pivot.name.clone(),
+            location: reader::tables::format_range(&pivot.sheet, pivot.location),
+            source: match &pivot.source {
+                PivotSource::External {
+                    workbook,
+                    reference,
+                } => format!("[{}]{}", workbook, reference),
+                PivotSource::Connection(connection) => format!("connection {}", connection),
+                source => source.formula().unwrap_or_else(|| "other".to_string()),
+            },
+            cache_size: pivot.cache.as_ref().map_or(0, |cache| cache.size),
+            cache_records: pivot.cache.as_ref().and_then(|cache| cache.records),
+        })
+        .collect();
+
+    let drawing_stats = calculate_drawing_stats(&workbook, total_file_size);
+
     let stats = FileStats {
         total_sheets,
         total_named_ranges,
@@ -94,6 +186,9 @@ fn main() -> Result<()> {
         total_file_size,
         formula_stats,
         cell_stats,
+        properties,
+        pivot_tables,
+        drawing_stats,
     };
 
     // Output results
@@ -160,6 +255,34 @@ fn calculate_cell_stats(workbook: &sheetrs::reader::Workbook) -> Vec<CellStats>
         .collect()
 }
 
+fn calculate_drawing_stats(workbook: &reader::Workbook, total_size: u64) -> Vec<DrawingStats> {
+    workbook
+        .sheets
+        .iter()
+        .filter(|sheet| !sheet.charts.is_empty() || !sheet.images.is_empty())
+        .map(|sheet| {
+            let mut paths = HashSet::new();
+            let media_size = sheet
+                .images
+                .iter()
+                .filter(|image| paths.insert(image.path.as_str()))
+                .map(|image| image.size)
+                .sum();
+            DrawingStats {
+                sheet_name: sheet.name.clone(),
+                charts: sheet.charts.len(),
+                images: sheet.images.len(),
+                media_size,
+                percentage: if total_size > 0 {
+                    (media_size as f64 / total_size as f64) * 100.0
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect()
+}
+
 fn calculate_sheet_sizes(
     file_path: &PathBuf,
     total_size: u64,
@@ -235,6 +358,48 @@ fn print_human(stats: &FileStats) {
         humanize_size(stats.total_file_size)
     );
 
+    let properties = &stats.properties;
+    let fields = [
+        ("Title", &properties.title),
+        ("Subject", &properties.subject),
+        ("Author", &properties.author),
+        ("Last Modified By", &properties.last_modified_by),
+        ("Company", &properties.company),
+        ("Manager", &properties.manager),
+        ("Template", &properties.template),
+        ("Application", &properties.application),
+        ("Created", &properties.created),
+        ("Modified", &properties.modified),
+    ];
+    if fields.iter().any(|(_, value)| value.is_some()) || !properties.custom.is_empty() {
+        println!("\nDocument Properties:");
+        for (label, value) in fields {
+            if let Some(value) = value {
+                println!("  {}: {}", label, value);
+            }
+        }
+        for (name, value) in &properties.custom {
+            println!("  {} (custom): {}", name, value);
+        }
+    }
+
+    if !stats.pivot_tables.is_empty() {
+        println!("\nPivot Tables:");
+        for pivot in &stats.pivot_tables {
+            print!(
+                "  {} at {} from {}",
+                pivot.name, pivot.location, pivot.source
+            );
+            if pivot.cache_size > 0 {
+                print!(", cache {}", humanize_size(pivot.cache_size));
+            }
+            if let Some(records) = pivot.cache_records {
+                print!(" ({} records)", records);
+            }
+            println!();
+        }
+    }
+
     if !stats.sheet_sizes.is_empty() {
         println!("\nSheet Sizes (compressed):");
         for sheet in &stats.sheet_sizes {
@@ -247,6 +412,24 @@ fn print_human(stats: &FileStats) {
         }
     }
 
+    if !stats.drawing_stats.is_empty() {
+        println!("\nCharts and Images by Sheet:");
+        for stat in &stats.drawing_stats {
+            print!(
+                "  {}: {} charts, {} images",
+                stat.sheet_name, stat.charts, stat.images
+            );
+            if stat.media_size > 0 {
+                print!(
+                    ", {} of media ({:.2}%)",
+                    humanize_size(stat.media_size),
+                    stat.percentage
+                );
+            }
+            println!();
+        }
+    }
+
     if !stats.formula_stats.is_empty() {
         println!("\nFormulas by Sheet:");
         for stat in &stats.formula_stats {